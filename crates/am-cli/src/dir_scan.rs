@@ -0,0 +1,182 @@
+//! Directory walking for `am ingest --dir`.
+//!
+//! Walks the directory recursively and keeps `.txt`, `.md`, and `.html`
+//! files, skipping two kinds of noise:
+//!   - Built-in rules: vendored and build output directories
+//!     (`node_modules`, `target`, `dist`, ...), lockfiles, and minified
+//!     assets. `--include <name>` lifts a rule for one directory or file name.
+//!   - Git ignore rules: when the directory is inside a git work tree,
+//!     candidates are filtered through `git check-ignore --stdin`, so
+//!     `.gitignore`, `.git/info/exclude`, and global excludes all apply.
+//!     Outside a repo (or without git) this step is a no-op.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// Extensions ingested from a directory scan.
+const INGEST_EXTENSIONS: &[&str] = &["txt", "md", "html"];
+
+/// Directory names never descended into unless named by `--include`.
+const SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "vendor",
+    "target",
+    "dist",
+    "build",
+    "out",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".next",
+    "coverage",
+];
+
+/// Lockfile names skipped unless named by `--include`.
+const SKIP_FILES: &[&str] = &[
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Cargo.lock",
+    "Gemfile.lock",
+    "poetry.lock",
+    "composer.lock",
+];
+
+/// Why a path was left out of a directory scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SkipReason {
+    /// Matched a built-in noise rule (vendored dir, lockfile, minified asset).
+    Builtin,
+    /// Ignored by git.
+    GitIgnored,
+}
+
+/// Files to ingest plus everything skipped along the way.
+#[derive(Debug, Default)]
+pub(crate) struct DirScan {
+    pub files: Vec<PathBuf>,
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
+impl DirScan {
+    pub(crate) fn skipped_count(&self, reason: SkipReason) -> usize {
+        self.skipped.iter().filter(|(_, r)| *r == reason).count()
+    }
+}
+
+/// Recursively collect ingestible files under `dir`.
+///
+/// `include` lists directory or file names exempt from the built-in rules.
+/// Results are sorted so ingestion order is stable across runs.
+pub(crate) fn scan_dir(dir: &Path, include: &[String]) -> Result<DirScan> {
+    let mut scan = DirScan::default();
+    walk(dir, include, &mut scan)?;
+
+    let ignored = git_ignored(dir, &scan.files);
+    if !ignored.is_empty() {
+        scan.files.retain(|p| {
+            if ignored.contains(p) {
+                scan.skipped.push((p.clone(), SkipReason::GitIgnored));
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    scan.files.sort();
+    scan.skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(scan)
+}
+
+fn walk(dir: &Path, include: &[String], scan: &mut DirScan) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("failed to read dir {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let included = include.contains(&name);
+
+        if file_type.is_dir() {
+            // Git metadata is never content and not worth reporting
+            if name == ".git" {
+                continue;
+            }
+            if !included && SKIP_DIRS.contains(&name.as_str()) {
+                scan.skipped.push((path, SkipReason::Builtin));
+            } else {
+                walk(&path, include, scan)?;
+            }
+        } else if file_type.is_file() {
+            if !included && is_noise_file(&name) {
+                scan.skipped.push((path, SkipReason::Builtin));
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| INGEST_EXTENSIONS.contains(&ext))
+            {
+                scan.files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Lockfiles and minified assets (`app.min.js`, `docs.min.html`).
+fn is_noise_file(name: &str) -> bool {
+    SKIP_FILES.contains(&name) || name.contains(".min.")
+}
+
+/// Paths among `candidates` that git ignores, or none when `dir` is not in
+/// a git work tree or git is unavailable.
+fn git_ignored(dir: &Path, candidates: &[PathBuf]) -> HashSet<PathBuf> {
+    if candidates.is_empty() {
+        return HashSet::new();
+    }
+    let child = Command::new("git")
+        .args(["check-ignore", "--stdin", "-z"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        tracing::debug!("git unavailable, not applying ignore rules");
+        return HashSet::new();
+    };
+
+    // Paths go to git relative to `dir`, its working directory
+    let mut input = Vec::new();
+    for path in candidates {
+        let rel = path.strip_prefix(dir).unwrap_or(path);
+        input.extend_from_slice(rel.to_string_lossy().as_bytes());
+        input.push(0);
+    }
+    if let Some(mut stdin) = child.stdin.take() {
+        // A write error means git exited early (not a repo); the exit
+        // status below reports it.
+        let _ = stdin.write_all(&input);
+    }
+
+    let Ok(output) = child.wait_with_output() else {
+        return HashSet::new();
+    };
+    // Exit 0: some paths ignored. 1: none ignored. 128: not a repo.
+    if output.status.code() != Some(0) {
+        return HashSet::new();
+    }
+    output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| dir.join(String::from_utf8_lossy(s).as_ref()))
+        .collect()
+}
@@ -0,0 +1,136 @@
+//! What scripts can rely on: exit statuses, and which stream a line goes
+//! to.
+//!
+//! stdout carries the data a command produces. Progress and status lines
+//! go to stderr through [`note!`]; headers, rules, and hints around the
+//! data go through [`decor!`]. `--quiet` drops both, leaving errors and
+//! warnings.
+
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use am_store::error::StoreError;
+
+/// Success, with results where the command recalls.
+pub(crate) const SUCCESS: u8 = 0;
+/// Any failure not classified below, e.g. a file that cannot be read.
+pub(crate) const FAILURE: u8 = 1;
+/// Success, but recall found nothing (`am query`, `am knows`).
+pub(crate) const EMPTY: u8 = 2;
+/// Invalid arguments, configuration, or input data.
+pub(crate) const INVALID: u8 = 3;
+/// The store failed: SQLite, its files, a busy lock, or problems found by
+/// `am doctor`.
+pub(crate) const STORE: u8 = 4;
+/// Some inputs of a multi-file ingest or `am sync --all` failed; the rest
+/// were stored.
+pub(crate) const PARTIAL: u8 = 5;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub(crate) fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// A progress or status line: stderr, unless `--quiet`.
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if !$crate::exit::quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use note;
+
+/// A header, rule, blank line, or hint around stdout data, unless
+/// `--quiet`.
+macro_rules! decor {
+    ($($arg:tt)*) => {
+        if !$crate::exit::quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use decor;
+
+/// End the process with `code` for an outcome that is not an error.
+/// Flushes stdout first; destructors do not run.
+pub(crate) fn now(code: u8) -> ! {
+    let _ = std::io::stdout().flush();
+    std::process::exit(i32::from(code));
+}
+
+/// A failure whose exit status its error types would not give it.
+#[derive(Debug)]
+pub(crate) struct Coded {
+    code: u8,
+    message: String,
+}
+
+impl fmt::Display for Coded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Coded {}
+
+/// An error that exits with [`STORE`].
+pub(crate) fn store_failure(message: impl Into<String>) -> anyhow::Error {
+    Coded {
+        code: STORE,
+        message: message.into(),
+    }
+    .into()
+}
+
+/// Exit status for a failed command: the first cause in its chain with a
+/// known type decides. Plain messages are the CLI's own checks of its
+/// arguments, so an error made only of messages is [`INVALID`].
+pub(crate) fn code_for(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(coded) = cause.downcast_ref::<Coded>() {
+            return coded.code;
+        }
+        if let Some(store) = cause.downcast_ref::<StoreError>() {
+            return match store {
+                StoreError::InvalidData(_) => INVALID,
+                StoreError::Sqlite(_) | StoreError::Io(_) => STORE,
+            };
+        }
+        if cause.is::<std::io::Error>() || cause.is::<reqwest::Error>() {
+            return FAILURE;
+        }
+    }
+    INVALID
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn code_for_classifies_by_first_typed_cause() {
+        let disk = StoreError::Io(std::io::Error::other("disk full"));
+        assert_eq!(
+            code_for(&anyhow::Error::from(disk).context("failed to save")),
+            STORE
+        );
+        let invalid = StoreError::InvalidData("db_size_mb must be > 0".into());
+        assert_eq!(code_for(&anyhow::Error::from(invalid)), INVALID);
+        let missing: anyhow::Result<()> =
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound)).context("failed to read x");
+        assert_eq!(code_for(&missing.unwrap_err()), FAILURE);
+        assert_eq!(code_for(&anyhow::anyhow!("usage: am bias set")), INVALID);
+        assert_eq!(
+            code_for(&store_failure("found 2 problems").context("doctor")),
+            STORE
+        );
+    }
+}
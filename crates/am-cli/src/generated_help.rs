@@ -6,18 +6,32 @@ pub const CLI_ABOUT: &str = "Geometric memory for AI agents - persistent recall
 #[rustfmt::skip]
 pub const CLI_LONG_ABOUT: &str = "am - Geometric memory for AI agents\n\nModels memory as points on a 3-sphere (S³ manifold) using quaternion positions,\ngolden-angle phasors, IDF-weighted drift, and Kuramoto phase coupling. Memories\naren't stored in flat text - they're positioned in geometric space where related\nconcepts naturally cluster through physics-inspired dynamics.\n\nHow it works:\n  - Words are placed on S³ as quaternion positions within neighborhoods\n  - Querying activates matching words and drifts them closer via SLERP\n  - Phase coupling synchronizes related concepts across sessions\n  - Conscious memories (marked salient) persist globally across projects\n\nAs an MCP server (primary mode):\n  Claude Code runs `am serve` automatically. The AI calls these tools:\n    am_query              Recall context at session start\n    am_activate_response  Strengthen connections after responses\n    am_salient            Mark insights as conscious memory\n    am_buffer             Buffer exchanges, auto-create episodes\n    am_ingest             Ingest documents as memory episodes\n    am_stats              Memory system diagnostics\n    am_export / am_import Portable state backup and restore\n\nAs a CLI (for humans):\n  Query, ingest, inspect, and manage memories directly.";
 #[rustfmt::skip]
-pub const CLI_AFTER_HELP: &str = "Setup with Claude Code:\n  claude mcp add am -- npx -y attention-matters serve\n\nQuick start:\n  am ingest README.md              # Feed a document into memory\n  am query \"authentication flow\"   # Recall relevant context\n  am inspect                       # See what's in memory\n  am inspect conscious             # Browse conscious memories\n  am stats                         # System diagnostics\n\nData location:  ~/.attention-matters/brain.db\n  Single unified brain - one product, one memory.\n\nConfiguration:  ~/.attention-matters/.am.config.toml\n  Environment variables override file values:\n    AM_DATA_DIR     Base directory for brain.db and config\n    AM_GC_ENABLED   Enable automatic GC on startup (default: false)\n    AM_DB_SIZE_MB   DB size limit in MB for GC threshold (default: 50)\n\nhttps://github.com/srobinson/attention-matters";
+pub const CLI_AFTER_HELP: &str = "Setup with Claude Code:\n  claude mcp add am -- npx -y attention-matters serve\n\nQuick start:\n  am ingest README.md              # Feed a document into memory\n  am query \"authentication flow\"   # Recall relevant context\n  am inspect                       # See what's in memory\n  am inspect conscious             # Browse conscious memories\n  am stats                         # System diagnostics\n\nData location:  ~/.attention-matters/brain.db\n  Single unified brain - one product, one memory.\n\nConfiguration:  ~/.attention-matters/.am.config.toml\n  Environment variables override file values:\n    AM_DATA_DIR     Base directory for brain.db and config\n    AM_GC_ENABLED   Enable automatic GC on startup (default: false)\n    AM_DB_SIZE_MB   DB size limit in MB for GC threshold (default: 50)\n    AM_ACTOR        Model or agent recorded on new memories\n    AM_BRAIN        Named brain inside brain.db (same as --brain)\n\nOutput:  data goes to stdout; progress and status lines to stderr.\n  --quiet leaves only data on stdout and errors or warnings on stderr.\n\nExit codes:\n  0  success\n  1  other failure, e.g. an unreadable file\n  2  nothing found (query, knows)\n  3  invalid arguments, configuration, or input\n  4  store failure (SQLite, busy lock, problems found by doctor)\n  5  partial: some inputs of an ingest or sync --all failed\n\nhttps://github.com/srobinson/attention-matters";
 
 #[rustfmt::skip]
 pub const QUERY_ABOUT: &str = "Query geometric memory for relevant context.";
 #[rustfmt::skip]
-pub const QUERY_LONG_ABOUT: &str = "Query the geometric memory system.\n\nActivates matching words on the S³ manifold, drifts related\nconcepts closer via IDF-weighted SLERP, computes phasor\ninterference, and returns composed context split into:\n* Conscious recall (previously marked salient)\n* Subconscious recall (from ingested documents/conversations)\n* Novel connections (lateral associations via interference)";
+pub const QUERY_LONG_ABOUT: &str = "Query the geometric memory system.\n\nActivates matching words on the S³ manifold, drifts related\nconcepts closer via IDF-weighted SLERP, computes phasor\ninterference, and returns composed context split into:\n* Conscious recall (previously marked salient)\n* Subconscious recall (from ingested documents/conversations)\n* Novel connections (lateral associations via interference)\n\nA query word that matches nothing in memory is corrected to the\nclosest stored word (one typo, two for words of 8+ letters) and\ncounts at a discount. --verbose reports each correction.";
 #[rustfmt::skip]
-pub const QUERY_AFTER_HELP: &str = "Examples:\n  am query \"authentication middleware\"\n  am query \"database schema migration\" --verbose";
+pub const QUERY_AFTER_HELP: &str = "Examples:\n  am query \"authentication middleware\"\n  am query \"database schema migration\" --verbose\n  am query \"old deploy scripts\" --include-archived\n  am query \"2023 release notes\" --include-epochs\n  am query \"release checklist\" --actor codex";
 #[rustfmt::skip]
 pub const QUERY_TEXT_HELP: &str = "Query text";
 #[rustfmt::skip]
 pub const QUERY_MAX_TOKENS_HELP: &str = "Maximum token budget for composed context";
+#[rustfmt::skip]
+pub const QUERY_DEBUG_HELP: &str = "Include per-token word weights and feedback scores";
+#[rustfmt::skip]
+pub const QUERY_INCLUDE_ARCHIVED_HELP: &str = "Also search archived episodes";
+#[rustfmt::skip]
+pub const QUERY_INCLUDE_EPOCHS_HELP: &str = "Also search episodes moved into epoch files";
+#[rustfmt::skip]
+pub const QUERY_MIN_ACTIVATED_WORDS_HELP: &str = "Minimum distinct query words a memory must match";
+#[rustfmt::skip]
+pub const QUERY_MIN_SCORE_HELP: &str = "Minimum candidate score";
+#[rustfmt::skip]
+pub const QUERY_ACTOR_HELP: &str = "Only recall memories created by this actor";
+#[rustfmt::skip]
+pub const QUERY_PROJECT_HELP: &str = "Favour memories tagged for this project";
 
 #[rustfmt::skip]
 pub const QUERY_INDEX_ABOUT: &str = "Get a compact index of matching memories (two-phase retrieval step 1).";
@@ -29,10 +43,21 @@ pub const RETRIEVE_ABOUT: &str = "Retrieve full content for specific neighborhoo
 #[rustfmt::skip]
 pub const RETRIEVE_IDS_HELP: &str = "Neighborhood UUIDs to retrieve";
 
+#[rustfmt::skip]
+pub const KNOWS_ABOUT: &str = "Check whether memory knows anything about a topic.";
+#[rustfmt::skip]
+pub const KNOWS_LONG_ABOUT: &str = "Check whether memory holds anything about a topic.\n\nLooks up the text's words and ranks candidate neighborhoods the way\na query would, without activating, drifting, or composing anything.\nPrints the activated word count, candidates per category, and the\ntop candidate score.\n\nExits 0 when memory has candidates and 2 when it has none, so\nscripts can branch on the exit status.";
+#[rustfmt::skip]
+pub const KNOWS_AFTER_HELP: &str = "Examples:\n  am knows \"kubernetes ingress\"\n  am knows \"release checklist\" --json\n  am knows \"flaky test\" || echo \"nothing recorded yet\"";
+#[rustfmt::skip]
+pub const KNOWS_TEXT_HELP: &str = "Text to check for";
+
 #[rustfmt::skip]
 pub const ACTIVATE_RESPONSE_ABOUT: &str = "Strengthen memory connections from response text.";
 #[rustfmt::skip]
 pub const ACTIVATE_RESPONSE_TEXT_HELP: &str = "Response text";
+#[rustfmt::skip]
+pub const ACTIVATE_RESPONSE_DRIFT_SCALE_HELP: &str = "Drift scale from 0.0 to 1.0";
 
 #[rustfmt::skip]
 pub const SALIENT_ABOUT: &str = "Mark an insight as conscious (cross-session) memory.";
@@ -40,9 +65,15 @@ pub const SALIENT_ABOUT: &str = "Mark an insight as conscious (cross-session) me
 pub const SALIENT_TEXT_HELP: &str = "Text to mark as conscious memory";
 #[rustfmt::skip]
 pub const SALIENT_SUPERSEDES_HELP: &str = "Neighborhood UUIDs this memory supersedes";
+#[rustfmt::skip]
+pub const SALIENT_FORCE_HELP: &str = "Store even when the text repeats an existing memory";
 
 #[rustfmt::skip]
-pub const BUFFER_ABOUT: &str = "Buffer a conversation exchange pair.";
+pub const BUFFER_ABOUT: &str = "List, flush, or clear buffered conversation exchanges.";
+#[rustfmt::skip]
+pub const BUFFER_LONG_ABOUT: &str = "Manage exchanges buffered by am_buffer that have not yet become\nan episode. Pending exchanges are normally flushed by the next\nam_query; this makes them visible and lets you act on them now.\n\nActions:\n• list:  show pending exchanges with timestamps (default)\n• flush: create the conversation episode now, exactly as the\n         server would\n• clear: discard pending exchanges (asks for confirmation)";
+#[rustfmt::skip]
+pub const BUFFER_AFTER_HELP: &str = "Examples:\n  am buffer                  # List pending exchanges\n  am buffer list --json      # Machine-readable listing\n  am buffer flush            # Turn them into an episode now\n  am buffer clear --force    # Discard without prompting";
 #[rustfmt::skip]
 pub const BUFFER_USER_HELP: &str = "User's message text";
 #[rustfmt::skip]
@@ -51,36 +82,46 @@ pub const BUFFER_ASSISTANT_HELP: &str = "Assistant's response text";
 #[rustfmt::skip]
 pub const INGEST_ABOUT: &str = "Ingest a document as a memory episode.";
 #[rustfmt::skip]
-pub const INGEST_LONG_ABOUT: &str = "Ingest document files as memory episodes.\n\nText is split into 3-sentence chunks, each becoming a\nneighborhood of word occurrences placed on the S³ manifold\nwith golden-angle phasor spacing. Supports .txt, .md, .html.";
+pub const INGEST_LONG_ABOUT: &str = "Ingest document files as memory episodes.\n\nText is split into 3-sentence chunks, each becoming a\nneighborhood of word occurrences placed on the S³ manifold\nwith golden-angle phasor spacing. Supports .txt, .md, .html.\n\n--dir walks the directory recursively. Inside a git repo,\nfiles ignored by git are skipped. Vendored and build output\ndirectories (node_modules, vendor, target, dist, build, ...),\nlockfiles, and minified assets are skipped too; name one with\n--include to ingest it anyway. A summary of skipped paths is\nprinted.\n\n--jsonl ingests a file that is already chunked: one JSON object\nper line, {\"text\": ..., \"name\": ..., \"tags\": [...]} with name and\ntags optional. Each line becomes exactly one neighborhood, all in\none episode named after the file.\n\nFiles are ingested on --jobs threads (default: one per core) and\nsaved in one transaction, in the order given. Each file is placed\nwith its own RNG seeded from --seed and its path, so the same\n--seed and paths give the same placement at any --jobs.\n\n--dry-run chunks and tokenizes everything but saves nothing. Each\nfile reports its would-be neighborhood and occurrence counts, the\nestimated database growth (at the store's current bytes per\noccurrence), and a preview of its first chunks.";
 #[rustfmt::skip]
-pub const INGEST_AFTER_HELP: &str = "Examples:\n  am ingest README.md ARCHITECTURE.md\n  am ingest --dir ./docs\n  am ingest --dir ./docs notes.txt";
+pub const INGEST_AFTER_HELP: &str = "Examples:\n  am ingest README.md ARCHITECTURE.md\n  am ingest --dry-run --dir ./docs     # Preview without saving\n  am ingest --jsonl chunks.jsonl\n  am ingest --dir ./docs\n  am ingest --dir ./docs notes.txt\n  am ingest --dir . --include vendor   # Also walk vendor/\n  am ingest --dir ./docs --seed 7      # Reproducible placement";
 #[rustfmt::skip]
 pub const INGEST_TEXT_HELP: &str = "Document text to ingest";
 #[rustfmt::skip]
+pub const INGEST_CHUNKS_HELP: &str = "JSONL file of pre-chunked text, one {text, name?, tags?} object per line";
+#[rustfmt::skip]
 pub const INGEST_NAME_HELP: &str = "Episode name";
+#[rustfmt::skip]
+pub const INGEST_DRY_RUN_HELP: &str = "Report what would be ingested without saving";
 
 #[rustfmt::skip]
 pub const STATS_ABOUT: &str = "Get memory system statistics.";
 #[rustfmt::skip]
-pub const STATS_LONG_ABOUT: &str = "Display memory statistics.\n\nShows total occurrences (N), episode count, conscious memory\ncount, database size, and activation distribution.";
+pub const STATS_LONG_ABOUT: &str = "Display memory statistics.\n\nShows total occurrences (N), episode count, conscious memory\ncount, database size, and activation distribution. The text\nline shows how many bytes chunk text takes on disk against its\nplain size, and how many chunks are stored compressed.\n\nIf loading found stored geometry out of range (non-unit\nquaternions, phase angles outside [0, 2π)), the repaired\nrecord count is shown too. Set AM_STRICT_LOAD=1 to fail the\nload on such records instead of repairing them.\n\nWith --include-epochs, epoch files written by `am gc --epochs` are\nlisted with their episode counts and sizes.";
 #[rustfmt::skip]
-pub const STATS_AFTER_HELP: &str = "Example:\n  am stats";
+pub const STATS_AFTER_HELP: &str = "Examples:\n  am stats\n  am stats --include-epochs";
 
 #[rustfmt::skip]
 pub const EXPORT_ABOUT: &str = "Export the full DAE system state as JSON.";
 #[rustfmt::skip]
-pub const EXPORT_LONG_ABOUT: &str = "Export the full memory state as v0.7.2-compatible JSON.\n\nThe exported file contains all episodes, neighborhoods,\noccurrences, and conscious memories. Can be imported on\nanother machine or into a different project.";
+pub const EXPORT_LONG_ABOUT: &str = "Export the full memory state as v0.7.2-compatible JSON.\n\nThe exported file contains all episodes, neighborhoods,\noccurrences, and conscious memories. Can be imported on\nanother machine or into a different project. Episodes moved into\nepoch files are left out unless --include-epochs is given.\n\nThe export leaves out state that lives only in the store: the\nconversation buffer not yet flushed into an episode, metadata\noverrides, frozen words, and word biases. --full writes an\narchive that nests the export beside that state, for moving a\nbrain between machines; import it with `am import --full`.";
 #[rustfmt::skip]
-pub const EXPORT_AFTER_HELP: &str = "Example:\n  am export backup.json";
+pub const EXPORT_AFTER_HELP: &str = "Examples:\n  am export backup.json\n  am export full.json --include-epochs\n  am export --full archive.json        # Keep buffer, metadata, biases";
+#[rustfmt::skip]
+pub const EXPORT_PATH_HELP: &str = "Output file path";
 
 #[rustfmt::skip]
 pub const IMPORT_ABOUT: &str = "Import a full DAE system state from JSON.";
 #[rustfmt::skip]
-pub const IMPORT_LONG_ABOUT: &str = "Import a previously exported memory state.\n\nReplaces the current memory with the imported state.\nAll memories are stored in the unified brain database.";
+pub const IMPORT_LONG_ABOUT: &str = "Import a previously exported memory state.\n\nReplaces the current memory with the imported state, or\nmerges into it with --merge. The file is streamed episode by\nepisode, so large exports do not need to fit in memory. A\nfailed import rolls back and leaves the brain untouched.\nAll memories are stored in the unified brain database.\n\nEpisode, neighborhood, and occurrence IDs are preserved.\nMissing or malformed IDs (e.g. files from the original\nJavaScript DAE) are regenerated with a warning; pass\n--strict-ids to reject such files instead.\n\nThe file is validated as it streams. Errors name the episode,\nneighborhood, and field they were found in, and a truncated\nfile reports the episode it ends in. Unparseable fields,\nzero-length quaternions, and duplicate IDs refuse the import\nby default; pass --lenient to skip the neighborhoods and\nepisodes they belong to and import the rest. Non-unit\nquaternions are normalized with a warning.\n\nWith --full, the file is an archive written by `am export\n--full`. Its buffer, metadata, frozen words, and word biases\nreplace this brain's, or with --merge are added where absent,\nin the same transaction as the export.";
 #[rustfmt::skip]
-pub const IMPORT_AFTER_HELP: &str = "Example:\n  am import backup.json";
+pub const IMPORT_AFTER_HELP: &str = "Examples:\n  am import backup.json\n  am import --full archive.json\n  am import --merge laptop-export.json\n  am import --strict-ids backup.json  # Fail on any missing ID\n  am import --lenient edited.json     # Skip what fails validation";
 #[rustfmt::skip]
 pub const IMPORT_STATE_HELP: &str = "Full state JSON to import";
+#[rustfmt::skip]
+pub const IMPORT_PATH_HELP: &str = "Input file path";
+#[rustfmt::skip]
+pub const IMPORT_LENIENT_HELP: &str = "Skip neighborhoods and episodes that fail validation instead of refusing the file";
 
 #[rustfmt::skip]
 pub const FEEDBACK_ABOUT: &str = "Provide relevance feedback on recalled memories.";
@@ -94,46 +135,167 @@ pub const FEEDBACK_SIGNAL_HELP: &str = "Feedback signal: boost or demote";
 #[rustfmt::skip]
 pub const BATCH_QUERY_ABOUT: &str = "Process multiple queries in a single batch pass.";
 #[rustfmt::skip]
-pub const BATCH_QUERY_QUERIES_HELP: &str = "List of queries (JSON array of {query, max_tokens?} objects)";
+pub const BATCH_QUERY_QUERIES_HELP: &str = "List of queries (JSON array of {query, max_tokens?, project?} objects)";
+
+#[rustfmt::skip]
+pub const DECISIONS_ABOUT: &str = "List recorded decisions, newest first.";
+#[rustfmt::skip]
+pub const DECISIONS_LONG_ABOUT: &str = "List the decision registry: conscious memories of type decision,\nnewest first, with their creation time. Superseded decisions are\nlisted too, marked with the memory that replaced them. Decisions\nrecorded before creation times were kept show no date.";
+#[rustfmt::skip]
+pub const DECISIONS_AFTER_HELP: &str = "Examples:\n  am decisions                     # Every recorded decision\n  am decisions --keyword sqlite    # Only decisions mentioning sqlite\n  am decisions --json";
+#[rustfmt::skip]
+pub const DECISIONS_KEYWORD_HELP: &str = "Only list decisions containing this keyword";
 
 #[rustfmt::skip]
 pub const SERVE_ABOUT: &str = "Start MCP server on stdio transport";
 #[rustfmt::skip]
-pub const SERVE_LONG_ABOUT: &str = "Start the MCP (Model Context Protocol) server on stdio transport.\n\nThis is the primary mode - Claude Code launches this automatically\nwhen configured as an MCP server. The server exposes 12 tools that\nthe AI agent calls to build and query geometric memory.";
+pub const SERVE_LONG_ABOUT: &str = "Start the MCP (Model Context Protocol) server on stdio transport.\n\nThis is the primary mode - Claude Code launches this automatically\nwhen configured as an MCP server. The server exposes 14 tools that\nthe AI agent calls to build and query geometric memory.";
 #[rustfmt::skip]
-pub const SERVE_AFTER_HELP: &str = "Setup:\n  claude mcp add am -- npx -y attention-matters serve\n\nThe server exposes:\n  am_query, am_query_index, am_retrieve, am_activate_response,\n  am_salient, am_buffer, am_ingest, am_stats, am_export,\n  am_import, am_feedback, am_batch_query";
+pub const SERVE_AFTER_HELP: &str = "Setup:\n  claude mcp add am -- npx -y attention-matters serve\n\nThe server exposes:\n  am_query, am_query_index, am_retrieve, am_knows,\n  am_activate_response, am_salient, am_buffer, am_ingest, am_stats,\n  am_export, am_import, am_feedback, am_batch_query, am_decisions\n\nRead-only REST API (for dashboards):\n  am serve --http 3001             # Also answers GET /rest/stats, /rest/conscious,\n                                   # /rest/episodes, /rest/query?q=...&budget=N\n  am serve --rest 127.0.0.1:9090   # The REST API alone on its own address\n  Set [http] token (or AM_HTTP_TOKEN) to require a bearer token.\n\nMetrics (for Prometheus):\n  am serve --metrics-addr 127.0.0.1:9187   # GET /metrics: tool calls and\n                                           # latencies, save durations, GC\n                                           # runs, memory and DB size\n  AM_METRICS_ADDR sets the address when the flag is absent.\n\nNamed brains:\n  am --brain team-a serve          # Serve one brain from the shared brain.db\n  With --http, each request may pick its brain with an x-am-brain\n  header or a \"brain\" field in the JSON body.";
 
 #[rustfmt::skip]
 pub const INSPECT_ABOUT: &str = "Browse memories, episodes, and neighborhoods";
 #[rustfmt::skip]
-pub const INSPECT_LONG_ABOUT: &str = "Inspect the contents of geometric memory.\n\nFive modes let you see exactly what's stored:\n• overview (default) - summary with top words and recent episodes\n• conscious - list all conscious (salient) memories\n• episodes - list subconscious episodes with stats\n• neighborhoods - all neighborhoods ranked by activation\n• --query - run a query and show the full recall breakdown\n\nTrust requires transparency. This command shows you\nwhat the AI remembers and why.";
+pub const INSPECT_LONG_ABOUT: &str = "Inspect the contents of geometric memory.\n\nSix modes let you see exactly what's stored:\n• overview (default) - summary with top words and recent episodes\n• conscious - list all conscious (salient) memories with their type\n  and creation time (--search to filter by text or word,\n  --sort recency|activation|type to reorder)\n• episodes - list subconscious episodes with stats, newest first\n  (--sort name|size|activation to reorder, --archived for episodes\n  archived by `am gc --archive`)\n• episode <id> - one episode in full: metadata, every neighborhood's\n  text, each word with its activation count, and totals. Takes an ID,\n  an ID prefix (like a git short hash), a name prefix, or `conscious`;\n  a prefix matching several episodes lists them\n• neighborhoods - all neighborhoods ranked by activation, with the\n  mean/max angle (radians) their words have drifted since ingest\n  (--sort recent|size to reorder)\n• --query - run a query and show the full recall breakdown\n\nListings are paged with --limit and --offset; --json output wraps\nthe page as {\"total\", \"offset\", \"items\"}.\n\nTrust requires transparency. This command shows you\nwhat the AI remembers and why.";
+#[rustfmt::skip]
+pub const INSPECT_AFTER_HELP: &str = "Examples:\n  am inspect                        # Overview\n  am inspect conscious              # List conscious memories\n  am inspect conscious --search retry --sort recency\n  am inspect episodes --limit 50    # More episodes\n  am inspect episodes --offset 20   # Next page of episodes\n  am inspect episodes --archived    # Episodes archived by gc --archive\n  am inspect episodes --sort size   # Largest episodes first\n  am inspect episode 3f2a9c1e       # One episode by ID prefix\n  am inspect episode conscious      # Every conscious memory's words\n  am inspect neighborhoods --json   # Machine-readable\n  am inspect neighborhoods --sort recent --offset 20\n  am inspect --query \"auth flow\"    # Query with full breakdown";
+
+#[rustfmt::skip]
+pub const WEIGHT_ABOUT: &str = "Show the IDF weight of one or more words";
+#[rustfmt::skip]
+pub const WEIGHT_LONG_ABOUT: &str = "Show how strongly each word counts during recall.\n\nFor every word, prints the number of neighborhoods containing it,\nits total occurrence count, and its IDF weight (1 / neighborhoods,\nclamped by the word weight floor and ceiling). Words not in memory\nget the maximum weight. Counts come straight from the database, so\nthis is cheap even for a large brain.";
+#[rustfmt::skip]
+pub const WEIGHT_AFTER_HELP: &str = "Examples:\n  am weight cache                # Weight of a single word\n  am weight cache ttl eviction   # Compare several words\n  am weight cache --json         # Machine-readable output";
+
+#[rustfmt::skip]
+pub const WORD_ABOUT: &str = "Freeze common words out of drift, or let them drift again";
+#[rustfmt::skip]
+pub const WORD_LONG_ABOUT: &str = "Freeze words that are too common to steer the manifold (\"error\",\n\"file\", \"line\") without stopwording them.\n\nA frozen word still activates, scores, and matches in recall; its\noccurrences just never drift or take part in phase coupling, so it\nstops pulling unrelated memories together. Frozen words are kept per\nbrain and apply from the next load (restart a running am serve).\n\nActions:\n• freeze:   stop the given words from drifting\n• unfreeze: let them drift again\nPass --list to show the frozen words. am doctor --vocab suggests\ncandidates.";
 #[rustfmt::skip]
-pub const INSPECT_AFTER_HELP: &str = "Examples:\n  am inspect                        # Overview\n  am inspect conscious              # List conscious memories\n  am inspect episodes --limit 50    # More episodes\n  am inspect neighborhoods --json   # Machine-readable\n  am inspect --query \"auth flow\"    # Query with full breakdown";
+pub const WORD_AFTER_HELP: &str = "Examples:\n  am word freeze error file line   # Keep them searchable, but still\n  am word unfreeze line            # Let one drift again\n  am word --list                   # Show frozen words";
+
+#[rustfmt::skip]
+pub const BIAS_ABOUT: &str = "Boost or mute specific words in recall scoring";
+#[rustfmt::skip]
+pub const BIAS_LONG_ABOUT: &str = "Correct IDF where it gets a word wrong. A term that appears everywhere\ngets a tiny weight, even when it is exactly the word you query by; a\nbias multiplies that weight back up, or down to nothing.\n\nThe multiplier applies after IDF and its clamps, from 0 to 10. At 0\nthe word no longer counts toward any score, but its occurrences stay\nstored, activate, and drift as before; drift and phase coupling use\nthe unbiased weight. Biases are kept per brain and apply from the\nnext load (restart a running am serve). am weight shows each word's\nbiased weight, and am inspect query lists the active biases.\n\nActions:\n• list:  show biased words (the default)\n• set:   multiply a word's weight by the given factor\n• clear: go back to the plain IDF weight";
+#[rustfmt::skip]
+pub const BIAS_AFTER_HELP: &str = "Examples:\n  am bias set nancy 4      # A ubiquitous term you still query by\n  am bias set todo 0       # Mute a word without deleting it\n  am bias clear nancy      # Back to plain IDF\n  am bias list --json";
+
+#[rustfmt::skip]
+pub const EVAL_ABOUT: &str = "Score recall quality against a labeled YAML suite";
+#[rustfmt::skip]
+pub const EVAL_LONG_ABOUT: &str = "Measure recall quality on a labeled fixture corpus.\n\nThe suite file lists a small corpus and a set of queries, each with\nthe documents (expect_docs) or text snippets (expect_snippets) it\nshould recall. Every query runs against a freshly built corpus with\na fixed RNG seed and a frozen clock, so results are reproducible.\n\nReports precision@k and mean reciprocal rank (MRR) per query, plus\nrelevant/retrieved counts per recall category. Your own memory\ndatabase is never opened or modified.";
+#[rustfmt::skip]
+pub const EVAL_AFTER_HELP: &str = "Examples:\n  am eval crates/am-core/tests/fixtures/recall_suite.yaml\n  am eval suite.yaml --json     # Machine-readable report";
 
 #[rustfmt::skip]
 pub const SYNC_ABOUT: &str = "Ingest Claude Code session transcripts into memory";
 #[rustfmt::skip]
-pub const SYNC_LONG_ABOUT: &str = "Sync Claude Code session transcripts into geometric memory.\n\nTwo modes:\n1. Stdin (hook-triggered): reads transcript_path + session_id from\n   JSON on stdin and ingests that single session. Used by Claude Code\n   PreCompact/Stop hooks.\n2. Discovery (--all): walks the filesystem to discover and re-ingest\n   all session transcripts. For manual bulk re-sync.\n\nReplace semantics: if an episode with the same name already exists,\nit is replaced (not duplicated).";
+pub const SYNC_LONG_ABOUT: &str = "Sync Claude Code session transcripts into geometric memory.\n\nTwo modes:\n1. Stdin (hook-triggered): reads transcript_path + session_id from\n   JSON on stdin and ingests that single session. Used by Claude Code\n   PreCompact/Stop hooks.\n2. Discovery (--all): walks the filesystem to discover and re-ingest\n   all session transcripts. For manual bulk re-sync.\n\nReplace semantics: if an episode with the same name already exists,\nit is replaced (not duplicated).\n\nExtraction filters drop pasted files and echoed diffs: messages over\nmax_message_chars are truncated with a marker, messages that are mostly\ncode fences or diff hunks are skipped, and --role / --skip-role keep or\ndrop whole roles. Defaults come from the [sync] section of\n.am.config.toml; flags override them. Each session reports how much\nwas filtered.";
 #[rustfmt::skip]
-pub const SYNC_AFTER_HELP: &str = "Examples:\n  echo '{...}' | am sync     # Ingest single session from hook stdin\n  am sync --all              # Discover and re-ingest all transcripts\n  am sync --all --dry-run    # Show what would be ingested\n  am sync --all --dir ~/.claude  # Custom Claude config directory";
+pub const SYNC_AFTER_HELP: &str = "Examples:\n  echo '{...}' | am sync     # Ingest single session from hook stdin\n  am sync --all              # Discover and re-ingest all transcripts\n  am sync --all --dry-run    # Show what would be ingested\n  am sync --all --dir ~/.claude  # Custom Claude config directory\n  am sync --all --max-message-chars 2000 --skip-role assistant";
 
 #[rustfmt::skip]
 pub const GC_ABOUT: &str = "Garbage collect: prune cold occurrences and compact storage";
 #[rustfmt::skip]
-pub const GC_LONG_ABOUT: &str = "Run garbage collection on the memory database.\n\nRemoves low-activation occurrences (below the activation floor),\ncleans up empty neighborhoods and episodes, then VACUUMs the\nSQLite database to reclaim disk space.\n\nConscious memories are never auto-evicted.";
+pub const GC_LONG_ABOUT: &str = "Run garbage collection on the memory database.\n\nRemoves low-activation occurrences (below the activation floor),\ncleans up empty neighborhoods and episodes, then VACUUMs the\nSQLite database to reclaim disk space.\n\nWith --archive, nothing is deleted: episodes whose occurrences are\nall at or below the floor (and outside the retention window) are\nflagged as archived instead. Archived episodes are skipped by normal\nrecall, reachable with `am query --include-archived`, listed by\n`am inspect episodes --archived`, and brought back with\n`am restore-episode <id>`.\n\nWith --epochs, cold and archived episodes move out of brain.db into\none file per year of their timestamp (brain-2024.db, ...) next to\nit. Normal recall reads brain.db only; epoch files are searched by\n`am query --include-epochs`, or when nothing current matches.\n\nConscious memories are never auto-evicted and never leave brain.db.\n\nWhat the --target-mb pass evicts goes on the undo stack first, so\n`am undo` can put it back; the copy counts toward brain.db's size\nuntil it ages off the stack.\n\nEvery run except --dry-run also prunes the audit log (`am log`) to\nits newest --audit-keep rows, 10000 unless the config's audit_keep\nsays otherwise, and purges what `am forget` put in quarantine more\nthan quarantine_days ago.";
+#[rustfmt::skip]
+pub const GC_AFTER_HELP: &str = "Examples:\n  am gc                     # Default: floor=1 (remove zero-activation)\n  am gc --floor 2           # Remove occurrences activated ≤2 times\n  am gc --dry-run           # Preview what would be removed\n  am gc --target-mb 10      # Shrink DB to ~10 MB\n  am gc --archive           # Archive cold episodes instead of deleting\n  am gc --epochs            # Move cold episodes into per-year epoch files\n  am gc --audit-keep 500    # Keep only the newest 500 audit log rows";
+
+#[rustfmt::skip]
+pub const FORGET_ABOUT: &str = "Selectively forget memories by term, episode, conscious ID, or query";
 #[rustfmt::skip]
-pub const GC_AFTER_HELP: &str = "Examples:\n  am gc                     # Default: floor=1 (remove zero-activation)\n  am gc --floor 2           # Remove occurrences activated ≤2 times\n  am gc --dry-run           # Preview what would be removed\n  am gc --target-mb 10      # Shrink DB to ~10 MB";
+pub const FORGET_LONG_ABOUT: &str = "Remove specific memories from the database.\n\nFour modes:\n• By term: removes all occurrences of a word across all episodes\n• By episode: removes an entire subconscious episode by UUID\n• By conscious ID: removes a specific conscious memory by UUID\n• By query: removes the --top N neighborhoods a query ranks best\n\n--query ranks memory as recall would, without changing it, and shows\nthe plan (score, category, episode, ID, preview) before asking to\nforget it; --yes skips the question. Conscious matches are kept unless\n--include-conscious is given. With --json the plan is printed for\nreview and nothing is forgotten unless --yes is also given. Episodes\nleft empty go with their neighborhoods.\n\nWhat a forget deletes is kept in quarantine for quarantine_days (30\nby default): out of recall, but `am restore-forgotten` can bring it\nback until `am gc` purges it. --hard deletes outright.\n\nForgetting a conscious memory also records it as forgotten in the\nconscious journal, so `am recover-conscious` does not bring it back;\n--keep-journal leaves the journal alone for the rare case you want\nrecovery to keep it. Use `am inspect` to find IDs before forgetting.\n`am undo` puts back what the last forget removed.";
+#[rustfmt::skip]
+pub const FORGET_AFTER_HELP: &str = "Examples:\n  am forget password            # Remove all occurrences of \"password\"\n  am forget --episode abc123    # Remove episode by ID\n  am forget --conscious def456  # Remove conscious memory by ID\n  am forget --conscious def456 --keep-journal  # ...but let recovery restore it\n  am forget --query \"old staging hosts\" --top 5  # Review, then forget\n  am forget --query \"old staging hosts\" --json   # Plan only, for scripts\n  am forget password --hard     # Skip quarantine";
+
+#[rustfmt::skip]
+pub const UNDO_ABOUT: &str = "Restore what the last forget or aggressive GC removed";
+#[rustfmt::skip]
+pub const UNDO_LONG_ABOUT: &str = "Put back the rows the most recent forget (by term, episode,\nconscious ID, or query) or size-targeted GC (`am gc --target-mb`) deleted.\nEach of those copies what it deletes onto an undo stack in brain.db\nfirst; `am undo` restores the newest entry and removes it, so running\nit again steps further back.\n\nRows are restored under their original IDs. A row whose ID is in use\nagain, or whose episode or neighborhood has since been deleted, is\nskipped. The restore is recorded in the audit log as `undo`.\n\nThe stack keeps the newest 10 operations and at most 16 MB of copied\nrows, configurable in the [undo] section of .am.config.toml\n(keep = 0 turns it off). A forget that has aged off the stack can\nstill be brought back by `am restore-forgotten` while it is in\nquarantine. A running `am serve` picks up restored memories on its\nnext start.";
+#[rustfmt::skip]
+pub const UNDO_AFTER_HELP: &str = "Examples:\n  am undo --list               # Undo points, newest first\n  am undo                      # Restore the newest one";
+
+#[rustfmt::skip]
+pub const LOG_ABOUT: &str = "Show the audit log of changes to memory, newest first";
+#[rustfmt::skip]
+pub const LOG_LONG_ABOUT: &str = "Show what changed memory and when. Every salient mark, ingest,\nbuffer flush, feedback, forget, GC pass, and import appends one row\nin the same transaction as the change, so a change that failed to\nsave never shows up. Each row has its time, kind, a count (memories\nstored, occurrences removed, episodes imported, ...), the project\nand actor current at the time, and a short detail.\n\nKinds: salient, ingest, buffer, feedback, forget, gc, import, undo.\n\n--since takes an ISO-8601 time or date (2026-03-01,\n2026-03-01T12:00:00Z) or an age like 30m, 24h, or 7d. `am gc` keeps\nthe newest 10000 rows by default (see `am gc --audit-keep`).";
+#[rustfmt::skip]
+pub const LOG_AFTER_HELP: &str = "Examples:\n  am log                       # Newest 50 changes\n  am log --since 7d            # Everything from the last week\n  am log --kind forget         # Only deletions\n  am log --limit 0 --json      # Every row, as JSON";
 
 #[rustfmt::skip]
-pub const FORGET_ABOUT: &str = "Selectively forget memories by term, episode, or conscious ID";
+pub const RENAME_EPISODE_ABOUT: &str = "Rename a subconscious episode";
 #[rustfmt::skip]
-pub const FORGET_LONG_ABOUT: &str = "Remove specific memories from the database.\n\nThree modes:\n• By term: removes all occurrences of a word across all episodes\n• By episode: removes an entire subconscious episode by UUID\n• By conscious ID: removes a specific conscious memory by UUID\n\nUse `am inspect` to find IDs before forgetting.";
+pub const RENAME_EPISODE_LONG_ABOUT: &str = "Rename a subconscious episode. The name appears as the recall source\nlabel, so a descriptive name makes recalled context easier to place.\n\nBuffered conversations and synced sessions are named automatically\nfrom their most distinctive words; use this to override that label.";
 #[rustfmt::skip]
-pub const FORGET_AFTER_HELP: &str = "Examples:\n  am forget password            # Remove all occurrences of \"password\"\n  am forget --episode abc123    # Remove episode by ID\n  am forget --conscious def456  # Remove conscious memory by ID";
+pub const RENAME_EPISODE_AFTER_HELP: &str = "Examples:\n  am inspect episodes                          # Find the episode ID\n  am rename-episode abc123... \"webhook retries\"  # Set a new name";
+
+#[rustfmt::skip]
+pub const RESTORE_EPISODE_ABOUT: &str = "Return an archived episode to normal recall";
+#[rustfmt::skip]
+pub const RESTORE_EPISODE_LONG_ABOUT: &str = "Un-archive an episode that `am gc --archive` moved out of normal\nrecall. The episode is loaded and queried like any other from the\nnext session on.";
+#[rustfmt::skip]
+pub const RESTORE_EPISODE_AFTER_HELP: &str = "Examples:\n  am inspect episodes --archived   # Find the episode ID\n  am restore-episode abc123...     # Bring it back";
+
+#[rustfmt::skip]
+pub const RESTORE_FORGOTTEN_ABOUT: &str = "Bring back memories `am forget` put in quarantine";
+#[rustfmt::skip]
+pub const RESTORE_FORGOTTEN_LONG_ABOUT: &str = "Restore forgets held in quarantine. `am forget` keeps every row it\ndeletes there for quarantine_days (30 by default), out of recall,\nuntil `am gc` purges it for good; `am forget --hard` skips it.\n\nGive the ID of one entry, or --all for every entry, optionally only\nthose forgotten --since a time or age. Entries are restored newest\nfirst; rows whose ID is in use again, or whose parent is gone, are\nskipped. A restored forget can no longer be undone with `am undo`,\nand an undone one leaves quarantine.";
+#[rustfmt::skip]
+pub const RESTORE_FORGOTTEN_AFTER_HELP: &str = "Examples:\n  am restore-forgotten --list           # What is in quarantine\n  am restore-forgotten 12               # Restore one forget\n  am restore-forgotten --all --since 1d # Everything forgotten today";
+
+#[rustfmt::skip]
+pub const RECOVER_CONSCIOUS_ABOUT: &str = "Restore salient memories from the conscious journal";
+#[rustfmt::skip]
+pub const RECOVER_CONSCIOUS_LONG_ABOUT: &str = "Replay a conscious journal into the brain store. Every salient memory\nis also appended to conscious.journal next to brain.db (unless\nconscious_journal = false in .am.config.toml), so if the database is\nlost or corrupted the most valuable memories can be rebuilt.\nEntries whose ID or text is already in the store are skipped, so it\nis safe to run against an existing store or to run more than once.\nMemories forgotten with `am forget --conscious` are left out too.";
+#[rustfmt::skip]
+pub const RECOVER_CONSCIOUS_AFTER_HELP: &str = "Examples:\n  am recover-conscious                          # Replay the live journal\n  am recover-conscious --from backup.journal    # Replay a saved copy";
+
+#[rustfmt::skip]
+pub const DOCTOR_ABOUT: &str = "Check the brain store for damage";
+#[rustfmt::skip]
+pub const DOCTOR_LONG_ABOUT: &str = "Check brain.db for damage: SQLite integrity (PRAGMA quick_check) and\nrows whose parent episode or neighborhood is missing, and whether a\nrunning am serve failed to save changes it already holds in memory.\nWith --deep,\nalso load the whole system and check the invariants every query relies\non: unit-length positions and seeds, phasors in [0, 2pi), occurrences\nthat point back at their neighborhood, unique IDs, epochs below the\ncounter, and supersession links that resolve.\n\nWith --vocab, list the words found in the most neighborhoods. Words\nin more than --max-share of them (default 0.3) are flagged; when one\nepisode supplies most of a flagged word's neighborhoods (an ingested\nlog directory, say), that episode is reported with the commands to\nforget or archive it.\nExits non-zero if anything is wrong.";
+#[rustfmt::skip]
+pub const DOCTOR_AFTER_HELP: &str = "Examples:\n  am doctor           # SQLite integrity and orphaned rows\n  am doctor --deep    # Also load the system and check its invariants\n  am doctor --vocab   # Find episodes flooding the vocabulary";
+
+#[rustfmt::skip]
+pub const SNAPSHOT_ABOUT: &str = "Fingerprint memory state, or diff it against an earlier fingerprint";
+#[rustfmt::skip]
+pub const SNAPSHOT_LONG_ABOUT: &str = "Record a lightweight fingerprint of memory, or compare the current\nstate with one recorded earlier.\n\nA fingerprint holds each episode's occurrence count, a hash of every\nconscious memory's text, and the IDF weight of the 200 most\nwidespread words. It is read straight from the database, so taking\none is cheap even for a large brain, and it is saved as a small JSON\nfile under snapshots/ in the data directory.\n\nWith --diff, prints the episodes added, removed, resized, or\narchived since the earlier snapshot, the conscious memories added,\nremoved, or edited, and the word weights that moved by more than\n--threshold percent. Nothing is saved or changed.";
+#[rustfmt::skip]
+pub const SNAPSHOT_AFTER_HELP: &str = "Examples:\n  am snapshot before-import          # Record a fingerprint\n  am import team.json\n  am snapshot --diff before-import   # What the import changed\n  am snapshot --diff before-import --threshold 25 --json";
+
+#[rustfmt::skip]
+pub const EXPORT_POSITIONS_ABOUT: &str = "Write every occurrence's position, projected to 3D, for plotting";
+#[rustfmt::skip]
+pub const EXPORT_POSITIONS_LONG_ABOUT: &str = "Write one row per occurrence, with its word, episode, activation\ncount, drift, and position on S³ projected to R³, to a CSV file\n(.csv), a JSON array (.json), or JSON Lines (.jsonl). Rows are\nstreamed from the database in occurrence ID order, so two exports\ntaken at different times line up and can be diffed to see what\ndrifted. If the export fails, the partial file is removed.\n\n--projection stereographic (the default) projects from the pole\nw = -1, taking a point in the w < 0 half as its antipode, so every\npoint lands in the unit ball and none near the pole blows up; q and\n-q, the same rotation, land together. --projection drop-w keeps x, y,\nz and drops w.";
+#[rustfmt::skip]
+pub const EXPORT_POSITIONS_AFTER_HELP: &str = "Examples:\n  am export-positions before.csv\n  am export-positions --projection drop-w positions.jsonl";
+
+#[rustfmt::skip]
+pub const SCHEMA_ABOUT: &str = "Print the JSON Schema of a command's --json output";
+#[rustfmt::skip]
+pub const SCHEMA_LONG_ABOUT: &str = "Print the JSON Schema (draft 2020-12) describing what a command prints\nwith --json.\n\nEvery --json payload is an object with a schema_version field. Adding\na field keeps the version; removing or renaming one, or changing what\nit means, bumps it. Scripts can check schema_version, or validate\nagainst the published schema, instead of breaking silently.";
+#[rustfmt::skip]
+pub const SCHEMA_AFTER_HELP: &str = "Examples:\n  am schema knows                    # Schema of am knows --json\n  am schema inspect-episodes         # Schema of am inspect episodes --json\n  am schema snapshot > snapshot.schema.json";
+
+#[rustfmt::skip]
+pub const MIGRATE_ABOUT: &str = "Run a one-time cleanup over stored memories";
+#[rustfmt::skip]
+pub const MIGRATE_LONG_ABOUT: &str = "Run a one-time cleanup over memories stored by older versions.\n\n  clean-text  Strip ANSI escape sequences and control characters\n              from stored source text. Ingest does this now; this\n              cleans what was stored before it did. Where the words\n              change (\"32mpass\" becomes \"pass\"), the neighborhood's\n              occurrences are rebuilt; surviving words keep their\n              position and activation.\n  compress-text\n              Deflate the text of long subconscious chunks saved\n              before compression was on (compress_text_min_bytes),\n              then VACUUM so the database shrinks. New chunks are\n              compressed as they are saved.\n\nArchived episodes are left as they are; restore them first to\ninclude them. Pass --dry-run to see what would change, or --apply\nto write it.";
+#[rustfmt::skip]
+pub const MIGRATE_AFTER_HELP: &str = "Examples:\n  am migrate clean-text --dry-run    # Count affected neighborhoods\n  am migrate clean-text --apply      # Scrub them\n  am migrate compress-text --apply   # Compress stored chunk text";
 
 #[rustfmt::skip]
 pub const INIT_ABOUT: &str = "Generate a default .am.config.toml";
 #[rustfmt::skip]
-pub const INIT_LONG_ABOUT: &str = "Generate a fully commented .am.config.toml with all fields\nand their compiled defaults. Writes to the current directory\nby default, or to ~/.attention-matters/ with --global.\nIf a config file already exists, prompts before overwriting.";
+pub const INIT_LONG_ABOUT: &str = "Generate a fully commented .am.config.toml with all fields\nand their compiled defaults. Writes to the current directory\nby default, or with --global to the data directory ($AM_DATA_DIR,\nelse ~/.attention-matters/).\nIf a config file already exists, prompts before overwriting.";
 #[rustfmt::skip]
 pub const INIT_AFTER_HELP: &str = "Examples:\n  am init                 # Write config to current directory\n  am init --global        # Write config to ~/.attention-matters/\n  am init --force         # Overwrite without prompting";
+
+#[rustfmt::skip]
+pub const CONFIG_ABOUT: &str = "Show the effective configuration or change one setting";
+#[rustfmt::skip]
+pub const CONFIG_LONG_ABOUT: &str = "Every command and am serve read one .am.config.toml: the first of\n./.am.config.toml, $AM_DATA_DIR/.am.config.toml, and\n~/.attention-matters/.am.config.toml. Environment variables (AM_DATA_DIR,\nAM_PROJECT, AM_DB_SIZE_MB, ...) override its values, and command flags\noverride both.\n\nActions:\n• show: every setting with its value and where it came from: default,\n        detected (the project name from git), the config file, or\n        env VAR. Reports a file that failed to parse, with the line\n        and key, and keys it does not know. A key prefix narrows the\n        list (the default action)\n• set:  write one setting to the config file in effect, or with\n        --global to the data directory's. The value is read as TOML\n        (true, 5, 0.3, [\"a\", \"b\"]); anything else is a string.\n        Comments in the file are kept. Unknown keys and values the\n        config would reject are refused before anything is written\n\nA running am serve reads the config at start; restart it to apply a\nchange.";
+#[rustfmt::skip]
+pub const CONFIG_AFTER_HELP: &str = "Examples:\n  am config                            # Everything, with sources\n  am config show compose               # Budgeted recall settings\n  am config set buffer.threshold 5\n  am config set retention.retention_days 30 --global\n  am config set project.name web-app";
@@ -1,12 +1,12 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use anyhow::{Context, Result};
 use axum::extract::{MatchedPath, Path, State};
-use axum::http::Request;
-use axum::http::StatusCode;
-use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE, HeaderName};
+use axum::http::{HeaderMap, Request, StatusCode};
 use axum::response::IntoResponse;
 use axum::{Json, Router, routing::get, routing::post};
 use serde::Deserialize;
@@ -17,16 +17,106 @@ use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::field::Empty;
 
+use am_store::config::Config;
 use am_store::project::BrainStore;
+use am_store::store::validate_brain_name;
 
-use crate::server::AmServer;
+use am_server::{AmServer, ErrorKind, ToolError};
 
 // --- Shared state ---
 
+/// Header selecting the brain a request reads and writes. POST bodies may
+/// carry a `brain` field instead; the body wins when both are present.
+pub(crate) const BRAIN_HEADER: &str = "x-am-brain";
+
 /// Shared application state passed to all HTTP handlers.
 #[derive(Clone)]
 pub(crate) struct AppState {
-    pub server: Arc<AmServer<BrainStore>>,
+    pub brains: Arc<BrainServers>,
+}
+
+/// One server per brain in the shared `brain.db`. The brain `am serve` was
+/// started with is always loaded; others are opened on first request.
+pub(crate) struct BrainServers {
+    default_brain: String,
+    default_server: Arc<AmServer<BrainStore>>,
+    config: Config,
+    opened: Mutex<HashMap<String, Arc<AmServer<BrainStore>>>>,
+}
+
+impl BrainServers {
+    /// Bearer token the REST API requires, from `http.token`.
+    pub(crate) fn rest_token(&self) -> Option<&str> {
+        self.config.http.token.as_deref()
+    }
+
+    pub(crate) fn new(
+        default_server: Arc<AmServer<BrainStore>>,
+        default_brain: String,
+        config: Config,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            default_brain,
+            default_server,
+            config,
+            opened: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The server for `brain`, or for the default brain when `None`.
+    /// Loading a brain reads the store, so callers run this off the async
+    /// workers (see [`AppState::server_for`]).
+    fn get(&self, brain: Option<&str>) -> Result<Arc<AmServer<BrainStore>>, ApiError> {
+        let brain = match brain {
+            None => return Ok(Arc::clone(&self.default_server)),
+            Some(b) if b == self.default_brain => return Ok(Arc::clone(&self.default_server)),
+            Some(b) => b,
+        };
+        validate_brain_name(brain).map_err(|e| bad_request(e.to_string()))?;
+        let mut opened = self.opened.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(server) = opened.get(brain) {
+            return Ok(Arc::clone(server));
+        }
+        tracing::info!(brain, "opening brain for HTTP requests");
+        let store = BrainStore::open_named(&self.config, brain)
+            .map_err(|e| internal_error(format!("failed to open brain {brain:?}: {e}")))?;
+        let server = AmServer::builder(store)
+            .plans(&self.config.plans)
+            .buffer(&self.config.buffer)
+            .allowed_paths(self.config.mcp_allowed_paths())
+            .max_ingest_bytes(self.config.mcp.max_ingest_bytes)
+            .build()
+            .map_err(|e| internal_error(format!("failed to load brain {brain:?}: {e}")))?;
+        let server = Arc::new(server);
+        opened.insert(brain.to_string(), Arc::clone(&server));
+        Ok(server)
+    }
+}
+
+impl AppState {
+    /// Resolve the server for a request: the body's `brain` field, else
+    /// the [`BRAIN_HEADER`], else the default brain.
+    pub(crate) async fn server_for(
+        &self,
+        headers: &HeaderMap,
+        body_brain: Option<&str>,
+    ) -> Result<Arc<AmServer<BrainStore>>, ApiError> {
+        let brain = match body_brain {
+            Some(b) => Some(b.to_string()),
+            None => headers
+                .get(BRAIN_HEADER)
+                .map(|v| {
+                    v.to_str()
+                        .map(str::to_string)
+                        .map_err(|_| bad_request(format!("{BRAIN_HEADER} is not valid text")))
+                })
+                .transpose()?,
+        };
+        let brains = Arc::clone(&self.brains);
+        tokio::task::spawn_blocking(move || brains.get(brain.as_deref()))
+            .await
+            .map_err(|e| internal_error(format!("brain lookup failed: {e}")))?
+    }
 }
 
 /// Unwrap a tool_result_text Value into the inner JSON.
@@ -44,11 +134,38 @@ fn unwrap_tool_result(result: &Value) -> Value {
         .unwrap_or_else(|| result.clone())
 }
 
-/// Dispatch a tool call and unwrap the MCP envelope.
-fn dispatch(server: &AmServer<BrainStore>, tool: &str, args: &Value) -> Result<Value, String> {
-    server
-        .dispatch_tool(tool, args)
+/// Run a tool call on the blocking pool. Tool handlers are synchronous:
+/// they lock the brain, compute, and wait for their reads and writes on
+/// the store thread, so none may run on a runtime worker, where a slow save
+/// would stall every other request and timer. Calls awaited one after
+/// another keep their order, so one call's writes land before the next
+/// call reads.
+pub(crate) async fn call_tool(
+    server: &Arc<AmServer<BrainStore>>,
+    tool: &'static str,
+    args: Value,
+) -> Result<Value, ToolError> {
+    let server = Arc::clone(server);
+    tokio::task::spawn_blocking(move || server.dispatch_tool(tool, &args))
+        .await
+        .unwrap_or_else(|e| {
+            Err(ToolError::new(
+                ErrorKind::Internal,
+                format!("{tool} task failed: {e}"),
+            ))
+        })
+}
+
+/// [`call_tool`] with the MCP envelope unwrapped, for HTTP handlers.
+pub(crate) async fn dispatch(
+    server: &Arc<AmServer<BrainStore>>,
+    tool: &'static str,
+    args: Value,
+) -> Result<Value, ApiError> {
+    call_tool(server, tool, args)
+        .await
         .map(|v| unwrap_tool_result(&v))
+        .map_err(ApiError::from)
 }
 
 // --- Bind / Serve ---
@@ -63,10 +180,10 @@ pub(crate) async fn bind_http(port: u16) -> Result<TcpListener> {
 
 pub(crate) async fn serve_http(
     listener: TcpListener,
-    server: Arc<AmServer<BrainStore>>,
+    brains: Arc<BrainServers>,
     cancel: CancellationToken,
 ) -> Result<()> {
-    let app_state = AppState { server };
+    let app_state = AppState { brains };
     tracing::info!("building HTTP router");
 
     let app = Router::new()
@@ -95,6 +212,7 @@ pub(crate) async fn serve_http(
             get(handle_episode_neighborhoods),
         )
         .route("/api/chat", post(crate::llm_proxy::handle_chat))
+        .nest("/rest", crate::rest_api::router(app_state.clone()))
         .fallback(handle_not_found)
         .with_state(app_state)
         .layer(
@@ -103,7 +221,11 @@ pub(crate) async fn serve_http(
                     is_local_origin(origin.as_bytes())
                 }))
                 .allow_methods(Any)
-                .allow_headers([CONTENT_TYPE, AUTHORIZATION]),
+                .allow_headers([
+                    CONTENT_TYPE,
+                    AUTHORIZATION,
+                    HeaderName::from_static(BRAIN_HEADER),
+                ]),
         )
         .layer(
             TraceLayer::new_for_http()
@@ -192,7 +314,7 @@ fn is_local_origin(origin: &[u8]) -> bool {
 
 // --- Error response helper ---
 
-struct ApiError {
+pub(crate) struct ApiError {
     code: String,
     message: String,
     status: StatusCode,
@@ -217,6 +339,24 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// Tool failures keep their kind: bad arguments are 400, missing records
+/// 404, and a busy or still-loading brain 503 so clients know to retry.
+impl From<ToolError> for ApiError {
+    fn from(e: ToolError) -> Self {
+        let status = match e.kind() {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            _ if e.retriable() => StatusCode::SERVICE_UNAVAILABLE,
+            _ if e.code() == am_server::error::INVALID_PARAMS => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            code: e.kind().as_str().to_uppercase(),
+            message: e.message().to_owned(),
+            status,
+        }
+    }
+}
+
 fn bad_request(message: impl Into<String>) -> ApiError {
     ApiError {
         code: "INVALID_REQUEST".to_string(),
@@ -225,6 +365,14 @@ fn bad_request(message: impl Into<String>) -> ApiError {
     }
 }
 
+pub(crate) fn unauthorized(message: impl Into<String>) -> ApiError {
+    ApiError {
+        code: "UNAUTHORIZED".to_string(),
+        message: message.into(),
+        status: StatusCode::UNAUTHORIZED,
+    }
+}
+
 fn internal_error(message: impl Into<String>) -> ApiError {
     ApiError {
         code: "INTERNAL_ERROR".to_string(),
@@ -245,39 +393,57 @@ async fn handle_not_found() -> impl IntoResponse {
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct QueryRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     text: String,
     max_tokens: Option<usize>,
+    project: Option<String>,
+    #[serde(default)]
+    include_candidates: bool,
+    candidate_limit: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct QueryIndexRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     text: String,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct RetrieveByIdsRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     ids: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct BufferRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     user: String,
     assistant: String,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct IngestRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     text: String,
     name: Option<String>,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct ActivateResponseRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     text: String,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct SalientRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     text: String,
     #[serde(default)]
     supersedes: Vec<String>,
@@ -285,6 +451,8 @@ struct SalientRequest {
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct FeedbackRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     query: String,
     neighborhood_ids: Vec<String>,
     signal: String,
@@ -294,15 +462,20 @@ struct FeedbackRequest {
 struct BatchQueryItem {
     query: String,
     max_tokens: Option<usize>,
+    project: Option<String>,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct McpBatchQueryRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     queries: Vec<BatchQueryItem>,
 }
 
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct ImportRequest {
+    #[serde(default, skip_serializing)]
+    brain: Option<String>,
     state: serde_json::Value,
 }
 
@@ -311,9 +484,12 @@ struct ImportRequest {
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     let started = Instant::now();
     // dispatch a lightweight stats call to verify the brain is accessible
-    let _ = state
-        .server
-        .dispatch_tool("am_stats", &serde_json::json!({}));
+    let _ = call_tool(
+        &state.brains.default_server,
+        "am_stats",
+        serde_json::json!({}),
+    )
+    .await;
     tracing::info!(
         elapsed_ms = started.elapsed().as_millis(),
         "health check ok"
@@ -323,11 +499,13 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
 
 async fn handle_query(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<QueryRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let started = Instant::now();
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_query", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_query", args).await?;
     tracing::info!(
         elapsed_ms = started.elapsed().as_millis(),
         "query completed"
@@ -337,85 +515,107 @@ async fn handle_query(
 
 async fn handle_query_index(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<QueryIndexRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_query_index", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_query_index", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_retrieve(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<RetrieveByIdsRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_retrieve", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_retrieve", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_buffer(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<BufferRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_buffer", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_buffer", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_ingest(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<IngestRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_ingest", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_ingest", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_activate(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ActivateResponseRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_activate_response", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_activate_response", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_salient(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<SalientRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_salient", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_salient", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_feedback(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<FeedbackRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_feedback", &args).map_err(bad_request)?;
+    let result = dispatch(&server, "am_feedback", args).await?;
     Ok(Json(result))
 }
 
 async fn handle_batch_query(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<McpBatchQueryRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_batch_query", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_batch_query", args).await?;
     Ok(Json(result))
 }
 
-async fn handle_stats(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
-    let result =
-        dispatch(&state.server, "am_stats", &serde_json::json!({})).map_err(internal_error)?;
+async fn handle_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    let result = dispatch(&server, "am_stats", serde_json::json!({})).await?;
     Ok(Json(result))
 }
 
-async fn handle_export(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
-    let result =
-        dispatch(&state.server, "am_export", &serde_json::json!({})).map_err(internal_error)?;
+async fn handle_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    let result = dispatch(&server, "am_export", serde_json::json!({})).await?;
     let json_str = serde_json::to_string(&result).map_err(|e| internal_error(e.to_string()))?;
     Ok((
         StatusCode::OK,
@@ -426,30 +626,32 @@ async fn handle_export(State(state): State<AppState>) -> Result<impl IntoRespons
 
 async fn handle_import(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ImportRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, req.brain.as_deref()).await?;
     let args = serde_json::to_value(&req).map_err(|e| internal_error(e.to_string()))?;
-    let result = dispatch(&state.server, "am_import", &args).map_err(internal_error)?;
+    let result = dispatch(&server, "am_import", args).await?;
     Ok(Json(result))
 }
 
-async fn handle_episodes(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
-    let result =
-        dispatch(&state.server, "am_episodes", &serde_json::json!({})).map_err(internal_error)?;
+async fn handle_episodes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    let result = dispatch(&server, "am_episodes", serde_json::json!({})).await?;
     Ok(Json(result))
 }
 
 async fn handle_episode_neighborhoods(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(episode_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
     let args = serde_json::json!({"episode_id": episode_id});
-    let result =
-        dispatch(&state.server, "am_episode_neighborhoods", &args).map_err(|msg| ApiError {
-            code: "NOT_FOUND".to_string(),
-            message: msg,
-            status: StatusCode::NOT_FOUND,
-        })?;
+    let result = dispatch(&server, "am_episode_neighborhoods", args).await?;
     Ok(Json(result))
 }
 
@@ -514,6 +716,59 @@ mod tests {
         assert_eq!(unwrapped, serde_json::json!({"key": "value"}));
     }
 
+    /// A slow save stalls neither the runtime nor stats on the same
+    /// brain: on a single-threaded runtime, while a large ingest's save runs
+    /// on the store thread, `am_stats` answers with the last store figures,
+    /// flagged `store_busy`, and the ingest's writes are visible to the
+    /// next call once it returns.
+    #[tokio::test]
+    async fn test_slow_tool_call_does_not_block_runtime() {
+        let server = Arc::new(AmServer::new(BrainStore::open_in_memory().unwrap()).unwrap());
+        // Store figures for stats to fall back on during the save
+        call_tool(&server, "am_stats", serde_json::json!({}))
+            .await
+            .unwrap();
+        let text: String = (0..3000)
+            .map(|i| format!("Sentence {i} covers topic{} and detail{}. ", i % 97, i % 89))
+            .collect();
+
+        let ingest = tokio::spawn({
+            let server = Arc::clone(&server);
+            async move {
+                call_tool(
+                    &server,
+                    "am_ingest",
+                    serde_json::json!({ "text": text, "name": "bulk" }),
+                )
+                .await
+            }
+        });
+        let mut answered_during_save = false;
+        while !ingest.is_finished() {
+            let stats = call_tool(&server, "am_stats", serde_json::json!({}))
+                .await
+                .unwrap();
+            let stats = unwrap_tool_result(&stats);
+            if stats["store_busy"] == true {
+                assert!(stats["db_size_bytes"].is_u64(), "{stats}");
+                answered_during_save = true;
+                break;
+            }
+        }
+        ingest.await.unwrap().unwrap();
+        assert!(
+            answered_during_save,
+            "am_stats never answered while the ingest was saving"
+        );
+
+        let stats = call_tool(&server, "am_stats", serde_json::json!({}))
+            .await
+            .unwrap();
+        let stats = unwrap_tool_result(&stats);
+        assert_eq!(stats["episodes"], 1);
+        assert!(stats.get("store_busy").is_none());
+    }
+
     #[test]
     fn test_unwrap_tool_result_passthrough() {
         let raw = serde_json::json!({"key": "value"});
@@ -20,8 +20,8 @@ use uuid::Uuid;
 
 use am_store::project::BrainStore;
 
-use crate::http_server::AppState;
-use crate::server::AmServer;
+use crate::http_server::{AppState, call_tool};
+use am_server::AmServer;
 
 const DEFAULT_MODEL: &str = "anthropic/claude-sonnet-4-20250514";
 const DEFAULT_MAX_TOKENS: u32 = 4096;
@@ -283,6 +283,11 @@ pub(crate) async fn handle_chat(
         }
     };
 
+    let server = match state.server_for(&headers, None).await {
+        Ok(server) => server,
+        Err(e) => return e.into_response(),
+    };
+
     let model = req.model.as_deref().unwrap_or(DEFAULT_MODEL).to_string();
     let max_tokens = req.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
     let mode = req.mode.clone();
@@ -297,11 +302,11 @@ pub(crate) async fn handle_chat(
         "chat request accepted"
     );
 
-    // Step 1: Query DAE for memory context via dispatch_tool
+    // Step 1: Query DAE for memory context, on the blocking pool
     let context_started = std::time::Instant::now();
     let query_args = serde_json::json!({"text": user_message});
     let (dae_context_str, context_event) = {
-        let result = match state.server.dispatch_tool("am_query", &query_args) {
+        let result = match call_tool(&server, "am_query", query_args).await {
             Ok(v) => unwrap_tool_result(&v),
             Err(e) => {
                 tracing::warn!(request_id, error = %e, "memory query failed");
@@ -413,7 +418,7 @@ pub(crate) async fn handle_chat(
         "OpenRouter stream established"
     );
 
-    let server_clone = Arc::clone(&state.server);
+    let server_clone = Arc::clone(&server);
     let user_msg_clone = user_message.clone();
     let context_json = serde_json::to_string(&context_event).unwrap_or_default();
     let (tx, mut rx) = mpsc::channel::<String>(32);
@@ -488,7 +493,12 @@ pub(crate) async fn handle_chat(
             response_chars = full_response.chars().count(),
             "stream completed"
         );
-        post_response_ops(&server_clone, &user_msg_clone, &full_response, &worker_request_id);
+        // Off the runtime: the three tool calls run in order on one
+        // blocking thread
+        let _ = tokio::task::spawn_blocking(move || {
+            post_response_ops(&server_clone, &user_msg_clone, &full_response, &worker_request_id);
+        })
+        .await;
     }.instrument(info_span!("chat_stream", request_id = %request_id, mode = %mode, model = %model)));
 
     let stream = async_stream::stream! {
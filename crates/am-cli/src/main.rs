@@ -1,28 +1,58 @@
 mod colors;
+mod dir_scan;
+mod exit;
 #[path = "generated_help.rs"]
 mod generated_help;
 mod http_server;
-mod jsonrpc;
 mod llm_proxy;
-mod server;
+mod metrics_server;
+mod output;
+mod pidfile;
+mod rest_api;
+mod snapshot;
 mod sync;
 mod sync_dispatch;
 
 use sync_dispatch::{safe_prefix, truncate_text};
 
 use std::path::PathBuf;
+use std::time::Instant;
 
 use std::io::Write;
 
 use am_core::{
-    compose::compose_context, query::QueryEngine, serde_compat::export_json, store_trait::AmStore,
-    surface::compute_surface, tokenizer::ingest_text,
+    compose::{
+        CutoffReason, RankedFragment, RecallCategory, compose_context, probe, ranked_matches,
+    },
+    constants::MAX_WORD_BIAS,
+    eval::{EvalSuite, run_suite},
+    query::{QueryEngine, QueryOptions},
+    serde_compat::{
+        IdPolicy, ImportIssue, ImportOptions, ValidationPolicy, count_export_episodes, export_json,
+    },
+    store_trait::{AmStore, AuditEvent, AuditKind, StoreErrorKind},
+    surface::compute_surface,
+    tokenizer::{
+        IngestChunk, IngestDoc, IngestPreview, document_seed, ingest_chunks, ingest_parallel,
+    },
+};
+use am_server::{AmServer, jsonrpc};
+use am_store::{
+    archive::count_archive_episodes,
+    config::Config,
+    error::StoreError,
+    journal,
+    json_bridge::ImportMode,
+    project::BrainStore,
+    store::{
+        AuditFilter, BusyRetry, ConsciousSort, DEFAULT_BRAIN, EpisodeNeighborhood, EpisodeSort,
+        NeighborhoodInfo, NeighborhoodSort, Page, PageRequest, VocabStats, WordSource, WordStat,
+    },
 };
-use am_store::{config::Config, project::BrainStore};
 use anyhow::{Context, Result};
 use clap::{ColorChoice, Parser, Subcommand, ValueEnum};
-use rand::SeedableRng;
 use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 
 #[derive(Parser)]
 #[command(
@@ -38,6 +68,14 @@ pub(crate) struct Cli {
     #[arg(long, global = true)]
     verbose: bool,
 
+    /// Print only data on stdout, and only errors and warnings on stderr
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Brain to use inside the shared brain.db (default: $AM_BRAIN, or "default")
+    #[arg(long, global = true)]
+    brain: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -53,6 +91,16 @@ enum Commands {
         /// Start an HTTP/SSE server on this port (e.g. 3001)
         #[arg(long)]
         http: Option<u16>,
+
+        /// Serve only the read-only REST API on this address
+        /// (e.g. 127.0.0.1:9090)
+        #[arg(long, value_name = "ADDR")]
+        rest: Option<std::net::SocketAddr>,
+
+        /// Serve Prometheus metrics at GET /metrics on this address
+        /// (e.g. 127.0.0.1:9187; default: $AM_METRICS_ADDR)
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<std::net::SocketAddr>,
     },
 
     #[command(
@@ -63,6 +111,32 @@ enum Commands {
     Query {
         #[arg(help = generated_help::QUERY_TEXT_HELP)]
         text: String,
+
+        #[arg(long, help = generated_help::QUERY_INCLUDE_ARCHIVED_HELP)]
+        include_archived: bool,
+
+        #[arg(long, help = generated_help::QUERY_INCLUDE_EPOCHS_HELP)]
+        include_epochs: bool,
+
+        #[arg(long, help = generated_help::QUERY_ACTOR_HELP)]
+        actor: Option<String>,
+
+        #[arg(long, help = generated_help::QUERY_PROJECT_HELP)]
+        project: Option<String>,
+    },
+
+    #[command(
+        about = generated_help::KNOWS_ABOUT,
+        long_about = generated_help::KNOWS_LONG_ABOUT,
+        after_help = generated_help::KNOWS_AFTER_HELP,
+    )]
+    Knows {
+        #[arg(help = generated_help::KNOWS_TEXT_HELP)]
+        text: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     #[command(
@@ -72,12 +146,30 @@ enum Commands {
     )]
     Ingest {
         /// File path(s) to ingest
-        #[arg(required_unless_present = "dir")]
+        #[arg(required_unless_present_any = ["dir", "jsonl"])]
         files: Vec<PathBuf>,
 
-        /// Ingest .txt/.md/.html files from this directory
+        #[arg(long, help = generated_help::INGEST_CHUNKS_HELP)]
+        jsonl: Vec<PathBuf>,
+
+        /// Ingest .txt/.md/.html files from this directory (recursive)
         #[arg(long)]
         dir: Option<PathBuf>,
+
+        /// Directory or file name to ingest despite the built-in skip rules
+        #[arg(long, requires = "dir")]
+        include: Vec<String>,
+
+        /// Seed for word placement (default: random)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Files to ingest in parallel (default: one per core)
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: Option<u16>,
+
+        #[arg(long, help = generated_help::INGEST_DRY_RUN_HELP)]
+        dry_run: bool,
     },
 
     #[command(
@@ -85,7 +177,25 @@ enum Commands {
         long_about = generated_help::STATS_LONG_ABOUT,
         after_help = generated_help::STATS_AFTER_HELP,
     )]
-    Stats,
+    Stats {
+        /// List epoch files with their episode counts and sizes
+        #[arg(long)]
+        include_epochs: bool,
+    },
+
+    #[command(
+        about = generated_help::DECISIONS_ABOUT,
+        long_about = generated_help::DECISIONS_LONG_ABOUT,
+        after_help = generated_help::DECISIONS_AFTER_HELP,
+    )]
+    Decisions {
+        #[arg(long, help = generated_help::DECISIONS_KEYWORD_HELP)]
+        keyword: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     #[command(
         about = generated_help::EXPORT_ABOUT,
@@ -93,8 +203,17 @@ enum Commands {
         after_help = generated_help::EXPORT_AFTER_HELP,
     )]
     Export {
-        /// Output file path
+        #[arg(help = generated_help::EXPORT_PATH_HELP)]
         path: PathBuf,
+
+        /// Also export episodes moved into epoch files
+        #[arg(long)]
+        include_epochs: bool,
+
+        /// Write a full archive: the export plus the buffer, metadata,
+        /// frozen words, and word biases
+        #[arg(long)]
+        full: bool,
     },
 
     #[command(
@@ -103,8 +222,23 @@ enum Commands {
         after_help = generated_help::IMPORT_AFTER_HELP,
     )]
     Import {
-        /// Input file path
+        #[arg(help = generated_help::IMPORT_PATH_HELP)]
         path: PathBuf,
+
+        /// Merge into existing memory instead of replacing it
+        #[arg(long)]
+        merge: bool,
+
+        /// Fail on missing or malformed IDs instead of regenerating them
+        #[arg(long)]
+        strict_ids: bool,
+
+        #[arg(long, help = generated_help::IMPORT_LENIENT_HELP)]
+        lenient: bool,
+
+        /// Read a full archive written by `am export --full`
+        #[arg(long)]
+        full: bool,
     },
 
     #[command(
@@ -117,6 +251,10 @@ enum Commands {
         #[arg(value_enum, default_value_t = InspectMode::Overview)]
         mode: InspectMode,
 
+        /// Episode ID, ID prefix, or name prefix; `conscious` for the
+        /// conscious episode (episode)
+        target: Option<String>,
+
         /// Run a query and show full recall breakdown
         #[arg(long, short)]
         query: Option<String>,
@@ -125,6 +263,96 @@ enum Commands {
         #[arg(long, default_value_t = 20)]
         limit: usize,
 
+        /// Skip this many items before listing (conscious, episodes, neighborhoods)
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// List archived episodes instead of live ones (episodes)
+        #[arg(long)]
+        archived: bool,
+
+        /// Listing order (episodes: default recent; neighborhoods: default
+        /// activation; conscious: default stored order)
+        #[arg(long, value_enum)]
+        sort: Option<SortKey>,
+
+        /// Only conscious memories whose text contains this, or that hold
+        /// it as a word (conscious)
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::WEIGHT_ABOUT,
+        long_about = generated_help::WEIGHT_LONG_ABOUT,
+        after_help = generated_help::WEIGHT_AFTER_HELP,
+    )]
+    Weight {
+        /// Word(s) to look up
+        #[arg(required = true)]
+        words: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::WORD_ABOUT,
+        long_about = generated_help::WORD_LONG_ABOUT,
+        after_help = generated_help::WORD_AFTER_HELP,
+    )]
+    Word {
+        /// What to do with the words
+        #[arg(value_enum, required_unless_present = "list")]
+        action: Option<WordAction>,
+
+        /// Words to freeze or unfreeze
+        words: Vec<String>,
+
+        /// List frozen words
+        #[arg(long, conflicts_with = "action")]
+        list: bool,
+
+        /// Output as JSON (--list)
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::BIAS_ABOUT,
+        long_about = generated_help::BIAS_LONG_ABOUT,
+        after_help = generated_help::BIAS_AFTER_HELP,
+    )]
+    Bias {
+        /// What to do with word biases
+        #[arg(value_enum, default_value_t = BiasAction::List)]
+        action: BiasAction,
+
+        /// Word to bias or clear
+        word: Option<String>,
+
+        /// Multiplier on the word's IDF weight, 0 to 10 (set)
+        multiplier: Option<f64>,
+
+        /// Output as JSON (list)
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::EVAL_ABOUT,
+        long_about = generated_help::EVAL_LONG_ABOUT,
+        after_help = generated_help::EVAL_AFTER_HELP,
+    )]
+    Eval {
+        /// YAML suite file (corpus + labeled queries)
+        suite: PathBuf,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -147,6 +375,22 @@ enum Commands {
         /// Override Claude config directory (default: ~/.claude or CLAUDE_CONFIG_DIR)
         #[arg(long)]
         dir: Option<PathBuf>,
+
+        /// Truncate messages longer than this many characters (0 = off)
+        #[arg(long)]
+        max_message_chars: Option<usize>,
+
+        /// Skip messages where more than this share is code fences or diffs (1.0 = off)
+        #[arg(long)]
+        max_code_ratio: Option<f64>,
+
+        /// Only keep messages with this role (user, assistant); repeatable
+        #[arg(long = "role")]
+        roles: Vec<String>,
+
+        /// Drop messages with this role (user, assistant); repeatable
+        #[arg(long = "skip-role")]
+        skip_roles: Vec<String>,
     },
 
     #[command(
@@ -166,6 +410,18 @@ enum Commands {
         /// Show what would be cleaned without doing it
         #[arg(long)]
         dry_run: bool,
+
+        /// Archive cold episodes instead of deleting occurrences
+        #[arg(long, conflicts_with = "target_mb")]
+        archive: bool,
+
+        /// Move cold and archived episodes into per-year epoch files
+        #[arg(long, conflicts_with_all = ["target_mb", "archive"])]
+        epochs: bool,
+
+        /// Keep this many of the newest audit log rows (default: config audit_keep)
+        #[arg(long)]
+        audit_keep: Option<usize>,
     },
 
     #[command(
@@ -184,6 +440,237 @@ enum Commands {
         /// Conscious memory (neighborhood) UUID to remove
         #[arg(long, conflicts_with = "term", conflicts_with = "episode")]
         conscious: Option<String>,
+
+        /// With --conscious: leave the conscious journal alone, so
+        /// recover-conscious can restore the memory
+        #[arg(long, requires = "conscious")]
+        keep_journal: bool,
+
+        /// Forget the neighborhoods this query ranks best, after showing
+        /// them
+        #[arg(long, conflicts_with_all = ["term", "episode", "conscious"])]
+        query: Option<String>,
+
+        /// With --query: how many of the best matches to forget
+        #[arg(long, default_value_t = 10, requires = "query")]
+        top: usize,
+
+        /// With --query: forget matching conscious memories too
+        #[arg(long, requires = "query")]
+        include_conscious: bool,
+
+        /// With --query: forget without asking
+        #[arg(long, short = 'y', requires = "query")]
+        yes: bool,
+
+        /// With --query: print the plan as JSON; forgets only with --yes
+        #[arg(long, requires = "query")]
+        json: bool,
+
+        /// Delete outright instead of keeping what is forgotten in
+        /// quarantine
+        #[arg(long)]
+        hard: bool,
+    },
+
+    #[command(
+        about = generated_help::UNDO_ABOUT,
+        long_about = generated_help::UNDO_LONG_ABOUT,
+        after_help = generated_help::UNDO_AFTER_HELP,
+    )]
+    Undo {
+        /// List the undo points instead of restoring the newest
+        #[arg(long)]
+        list: bool,
+    },
+
+    #[command(
+        about = generated_help::LOG_ABOUT,
+        long_about = generated_help::LOG_LONG_ABOUT,
+        after_help = generated_help::LOG_AFTER_HELP,
+    )]
+    Log {
+        /// Only changes at or after this time (ISO-8601, or an age like 24h, 7d)
+        #[arg(long, value_parser = parse_since)]
+        since: Option<String>,
+
+        /// Only changes of this kind (salient, ingest, buffer, feedback, forget, gc, import, undo)
+        #[arg(long, value_parser = parse_audit_kind)]
+        kind: Option<AuditKind>,
+
+        /// Newest rows to show; 0 shows all
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::BUFFER_ABOUT,
+        long_about = generated_help::BUFFER_LONG_ABOUT,
+        after_help = generated_help::BUFFER_AFTER_HELP,
+    )]
+    Buffer {
+        /// What to do with pending exchanges
+        #[arg(value_enum, default_value_t = BufferAction::List)]
+        action: BufferAction,
+
+        /// Discard without prompting (clear)
+        #[arg(long)]
+        force: bool,
+
+        /// Output as JSON (list)
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::RENAME_EPISODE_ABOUT,
+        long_about = generated_help::RENAME_EPISODE_LONG_ABOUT,
+        after_help = generated_help::RENAME_EPISODE_AFTER_HELP,
+    )]
+    RenameEpisode {
+        /// Episode UUID (see `am inspect episodes`)
+        id: String,
+
+        /// New episode name
+        name: String,
+    },
+
+    #[command(
+        about = generated_help::RESTORE_EPISODE_ABOUT,
+        long_about = generated_help::RESTORE_EPISODE_LONG_ABOUT,
+        after_help = generated_help::RESTORE_EPISODE_AFTER_HELP,
+    )]
+    RestoreEpisode {
+        /// Archived episode UUID (see `am inspect episodes --archived`)
+        id: String,
+    },
+
+    #[command(
+        about = generated_help::RESTORE_FORGOTTEN_ABOUT,
+        long_about = generated_help::RESTORE_FORGOTTEN_LONG_ABOUT,
+        after_help = generated_help::RESTORE_FORGOTTEN_AFTER_HELP,
+    )]
+    RestoreForgotten {
+        /// Quarantine entry ID (see --list)
+        #[arg(
+            required_unless_present_any = ["all", "list"],
+            conflicts_with_all = ["all", "list"]
+        )]
+        id: Option<i64>,
+
+        /// Restore every entry in quarantine
+        #[arg(long, conflicts_with = "list")]
+        all: bool,
+
+        /// With --all: only what was forgotten since this ISO-8601 time or
+        /// age (e.g. 24h, 7d)
+        #[arg(long, requires = "all", value_parser = parse_since)]
+        since: Option<String>,
+
+        /// List the forgets held in quarantine
+        #[arg(long)]
+        list: bool,
+    },
+
+    #[command(
+        about = generated_help::RECOVER_CONSCIOUS_ABOUT,
+        long_about = generated_help::RECOVER_CONSCIOUS_LONG_ABOUT,
+        after_help = generated_help::RECOVER_CONSCIOUS_AFTER_HELP,
+    )]
+    RecoverConscious {
+        /// Journal to replay (default: conscious.journal in the data dir)
+        #[arg(long)]
+        from: Option<PathBuf>,
+    },
+
+    #[command(
+        about = generated_help::DOCTOR_ABOUT,
+        long_about = generated_help::DOCTOR_LONG_ABOUT,
+        after_help = generated_help::DOCTOR_AFTER_HELP,
+    )]
+    Doctor {
+        /// Also load the system and check its geometric invariants
+        #[arg(long)]
+        deep: bool,
+
+        /// Also report the most widespread words and the episodes flooding them
+        #[arg(long)]
+        vocab: bool,
+
+        /// Flag words found in more than this fraction of neighborhoods
+        #[arg(long, default_value_t = 0.3, requires = "vocab")]
+        max_share: f64,
+    },
+
+    #[command(
+        about = generated_help::SNAPSHOT_ABOUT,
+        long_about = generated_help::SNAPSHOT_LONG_ABOUT,
+        after_help = generated_help::SNAPSHOT_AFTER_HELP,
+    )]
+    Snapshot {
+        /// Snapshot name (default: the current UTC time)
+        #[arg(conflicts_with = "diff")]
+        name: Option<String>,
+
+        /// Compare the current state with an earlier snapshot (name or path)
+        #[arg(long, value_name = "EARLIER")]
+        diff: Option<String>,
+
+        /// Report word weights that moved by more than this percentage
+        #[arg(long, default_value_t = 10.0, requires = "diff")]
+        threshold: f64,
+
+        /// Output the diff as JSON
+        #[arg(long, requires = "diff")]
+        json: bool,
+    },
+
+    #[command(
+        about = generated_help::EXPORT_POSITIONS_ABOUT,
+        long_about = generated_help::EXPORT_POSITIONS_LONG_ABOUT,
+        after_help = generated_help::EXPORT_POSITIONS_AFTER_HELP,
+    )]
+    ExportPositions {
+        /// Output file: .csv, .json (one array), or .jsonl (JSON Lines)
+        path: PathBuf,
+
+        /// How to map S³ to R³
+        #[arg(long, value_enum, default_value_t = Projection::Stereographic)]
+        projection: Projection,
+    },
+
+    #[command(
+        about = generated_help::SCHEMA_ABOUT,
+        long_about = generated_help::SCHEMA_LONG_ABOUT,
+        after_help = generated_help::SCHEMA_AFTER_HELP,
+    )]
+    Schema {
+        /// Output to describe
+        #[arg(value_enum)]
+        output: output::JsonOutput,
+    },
+
+    #[command(
+        about = generated_help::MIGRATE_ABOUT,
+        long_about = generated_help::MIGRATE_LONG_ABOUT,
+        after_help = generated_help::MIGRATE_AFTER_HELP,
+    )]
+    Migrate {
+        /// Cleanup to run
+        #[arg(value_enum)]
+        migration: Migration,
+
+        /// Report what would change without writing
+        #[arg(long, required_unless_present = "apply", conflicts_with = "apply")]
+        dry_run: bool,
+
+        /// Write the changes
+        #[arg(long)]
+        apply: bool,
     },
 
     #[command(
@@ -192,7 +679,8 @@ enum Commands {
         after_help = generated_help::INIT_AFTER_HELP,
     )]
     Init {
-        /// Write to ~/.attention-matters/ instead of the current directory
+        /// Write to the data directory ($AM_DATA_DIR or ~/.attention-matters/)
+        /// instead of the current directory
         #[arg(long)]
         global: bool,
 
@@ -200,6 +688,27 @@ enum Commands {
         #[arg(long)]
         force: bool,
     },
+
+    #[command(
+        about = generated_help::CONFIG_ABOUT,
+        long_about = generated_help::CONFIG_LONG_ABOUT,
+        after_help = generated_help::CONFIG_AFTER_HELP,
+    )]
+    Config {
+        /// What to do with the configuration
+        #[arg(value_enum, default_value_t = ConfigAction::Show)]
+        action: ConfigAction,
+
+        /// Dotted key, e.g. buffer.threshold (set; show filters by prefix)
+        key: Option<String>,
+
+        /// New value, read as TOML; bare words are strings (set)
+        value: Option<String>,
+
+        /// Write to the data directory's config file (set)
+        #[arg(long)]
+        global: bool,
+    },
 }
 
 #[derive(Clone, ValueEnum)]
@@ -210,21 +719,155 @@ enum InspectMode {
     Conscious,
     /// List subconscious episodes with stats
     Episodes,
+    /// One episode in full: its neighborhoods, words, and activation
+    Episode,
     /// All neighborhoods ranked by activation
     Neighborhoods,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum SortKey {
+    /// Newest first
+    #[value(alias = "recency")]
+    Recent,
+    /// Alphabetical by name (episodes)
+    Name,
+    /// Most occurrences first (episodes, neighborhoods)
+    Size,
+    /// Highest total activation first
+    Activation,
+    /// Grouped by neighborhood type, newest first within each (conscious)
+    Type,
+}
+
+impl SortKey {
+    fn episode_sort(key: Option<Self>) -> Result<EpisodeSort> {
+        Ok(match key.unwrap_or(Self::Recent) {
+            Self::Recent => EpisodeSort::Recent,
+            Self::Name => EpisodeSort::Name,
+            Self::Size => EpisodeSort::Size,
+            Self::Activation => EpisodeSort::Activation,
+            Self::Type => anyhow::bail!("--sort type applies to conscious memories only"),
+        })
+    }
+
+    fn neighborhood_sort(key: Option<Self>) -> Result<NeighborhoodSort> {
+        Ok(match key.unwrap_or(Self::Activation) {
+            Self::Activation => NeighborhoodSort::Activation,
+            Self::Recent => NeighborhoodSort::Recency,
+            Self::Size => NeighborhoodSort::OccurrenceCount,
+            Self::Name | Self::Type => {
+                anyhow::bail!("neighborhoods sort by activation, recent, or size")
+            }
+        })
+    }
+
+    fn conscious_sort(key: Option<Self>) -> Result<ConsciousSort> {
+        Ok(match key {
+            None => ConsciousSort::Stored,
+            Some(Self::Recent) => ConsciousSort::Recency,
+            Some(Self::Activation) => ConsciousSort::Activation,
+            Some(Self::Type) => ConsciousSort::Type,
+            Some(Self::Name | Self::Size) => {
+                anyhow::bail!("conscious memories sort by recency, activation, or type")
+            }
+        })
+    }
+}
+
+/// How `am export-positions` maps a point on S³ to R³.
+#[derive(Clone, Copy, ValueEnum)]
+enum Projection {
+    /// Stereographic, each point from the pole farther from it
+    Stereographic,
+    /// Keep x, y, z and drop w
+    DropW,
+}
+
+#[derive(Clone, ValueEnum)]
+enum BufferAction {
+    /// Show pending exchanges without consuming them
+    List,
+    /// Turn pending exchanges into a conversation episode now
+    Flush,
+    /// Discard pending exchanges
+    Clear,
+}
+
+#[derive(Clone, ValueEnum)]
+enum WordAction {
+    /// Stop the words from drifting
+    Freeze,
+    /// Let the words drift again
+    Unfreeze,
+}
+
+#[derive(Clone, ValueEnum)]
+enum BiasAction {
+    /// Show biased words
+    List,
+    /// Multiply a word's weight
+    Set,
+    /// Remove a word's bias
+    Clear,
+}
+
+#[derive(Clone, ValueEnum)]
+enum ConfigAction {
+    /// Show each setting, its value, and where the value came from
+    Show,
+    /// Write one setting to a config file
+    Set,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Migration {
+    /// Strip ANSI escapes and control characters from stored text
+    CleanText,
+    /// Compress long subconscious chunk text stored before compression
+    CompressText,
+}
+
 pub(crate) fn load_config() -> Result<Config> {
-    am_store::config::load().context("invalid configuration")
+    let mut config = am_store::config::load().context("invalid configuration")?;
+    config.retry.on_retry = Some(busy_notice);
+    Ok(config)
 }
 
-pub(crate) fn open_store(_cli: &Cli) -> Result<BrainStore> {
+/// Environment fallback for `--brain`.
+const BRAIN_ENV: &str = "AM_BRAIN";
+
+/// The brain selected by `--brain` or `AM_BRAIN`.
+pub(crate) fn brain_name(cli: &Cli) -> String {
+    cli.brain
+        .clone()
+        .or_else(|| std::env::var(BRAIN_ENV).ok().filter(|b| !b.is_empty()))
+        .unwrap_or_else(|| DEFAULT_BRAIN.to_string())
+}
+
+pub(crate) fn open_store(cli: &Cli) -> Result<BrainStore> {
+    let config = load_config()?;
+    open_store_with(cli, &config)
+}
+
+/// Open the selected brain without write access, for commands that only
+/// read and must not contend with a running `am serve`.
+fn open_store_readonly(cli: &Cli) -> Result<BrainStore> {
     let config = load_config()?;
-    BrainStore::open(&config).context("failed to open brain store")
+    let brain = brain_name(cli);
+    BrainStore::open_readonly(&config, &brain)
+        .with_context(|| format!("failed to open brain store read-only (brain {brain:?})"))
+}
+
+fn open_store_with(cli: &Cli, config: &Config) -> Result<BrainStore> {
+    let brain = brain_name(cli);
+    BrainStore::open_named(config, &brain)
+        .with_context(|| format!("failed to open brain store (brain {brain:?})"))
 }
 
 fn init_tracing(verbose: bool) {
     use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
 
     let filter = if verbose {
         EnvFilter::new("debug")
@@ -232,140 +875,435 @@ fn init_tracing(verbose: bool) {
         EnvFilter::from_default_env().add_directive(tracing::Level::WARN.into())
     };
 
+    // Verbose runs log each pipeline span as it closes, with its sizes
+    // and busy time
+    let span_events = if verbose {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+
     tracing_subscriber::fmt()
         .with_env_filter(filter)
+        .with_span_events(span_events)
         .with_writer(std::io::stderr)
         .with_ansi(false)
         .init();
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    init_tracing(cli.verbose);
-
-    match &cli.command {
-        Commands::Serve { http } => cmd_serve(&cli, *http),
-        Commands::Query { text } => cmd_query(&cli, text),
-        Commands::Ingest { files, dir } => cmd_ingest(&cli, files, dir.as_deref()),
-        Commands::Stats => cmd_stats(&cli),
-        Commands::Export { path } => cmd_export(&cli, path),
-        Commands::Import { path } => cmd_import(&cli, path),
-        Commands::Inspect {
-            mode,
-            query,
-            limit,
-            json,
-        } => cmd_inspect(&cli, mode, query.as_deref(), *limit, *json),
-        Commands::Sync { all, dry_run, dir } => {
-            sync_dispatch::cmd_sync(&cli, *all, *dry_run, dir.as_deref())
+fn main() -> std::process::ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            let code = if e.use_stderr() {
+                exit::INVALID
+            } else {
+                exit::SUCCESS
+            };
+            return code.into();
         }
-        Commands::Gc {
-            floor,
-            target_mb,
+    };
+    init_tracing(cli.verbose);
+    exit::set_quiet(cli.quiet);
+
+    let result = match &cli.command {
+        Commands::Serve {
+            http,
+            rest,
+            metrics_addr,
+        } => cmd_serve(&cli, *http, *rest, *metrics_addr),
+        Commands::Query {
+            text,
+            include_archived,
+            include_epochs,
+            actor,
+            project,
+        } => cmd_query(
+            &cli,
+            text,
+            *include_archived,
+            *include_epochs,
+            actor.as_deref(),
+            project.as_deref(),
+        ),
+        Commands::Knows { text, json } => cmd_knows(&cli, text, *json),
+        Commands::Ingest {
+            files,
+            jsonl,
+            dir,
+            include,
+            seed,
+            jobs,
+            dry_run,
+        } => cmd_ingest(
+            &cli,
+            files,
+            jsonl,
+            dir.as_deref(),
+            include,
+            &IngestOptions {
+                seed: *seed,
+                jobs: *jobs,
+                dry_run: *dry_run,
+            },
+        ),
+        Commands::Stats { include_epochs } => cmd_stats(&cli, *include_epochs),
+        Commands::Decisions { keyword, json } => cmd_decisions(&cli, keyword.as_deref(), *json),
+        Commands::Export {
+            path,
+            include_epochs,
+            full,
+        } => cmd_export(&cli, path, *include_epochs, *full),
+        Commands::Import {
+            path,
+            merge,
+            strict_ids,
+            lenient,
+            full,
+        } => {
+            let options = ImportOptions {
+                ids: if *strict_ids {
+                    IdPolicy::Strict
+                } else {
+                    IdPolicy::Lenient
+                },
+                validation: if *lenient {
+                    ValidationPolicy::Lenient
+                } else {
+                    ValidationPolicy::Strict
+                },
+            };
+            cmd_import(&cli, path, *merge, *full, options)
+        }
+        Commands::Inspect {
+            mode,
+            target,
+            query,
+            limit,
+            offset,
+            archived,
+            sort,
+            search,
+            json,
+        } => cmd_inspect(
+            &cli,
+            mode,
+            query.as_deref(),
+            PageRequest::new(*limit, *offset),
+            &InspectFilter {
+                archived: *archived,
+                sort: *sort,
+                search: search.as_deref(),
+                target: target.as_deref(),
+            },
+            *json,
+        ),
+        Commands::Weight { words, json } => cmd_weight(&cli, words, *json),
+        Commands::Word {
+            action,
+            words,
+            list: _,
+            json,
+        } => cmd_word(&cli, action.as_ref(), words, *json),
+        Commands::Bias {
+            action,
+            word,
+            multiplier,
+            json,
+        } => cmd_bias(&cli, action, word.as_deref(), *multiplier, *json),
+        Commands::Eval { suite, json } => cmd_eval(suite, *json),
+        Commands::Sync {
+            all,
+            dry_run,
+            dir,
+            max_message_chars,
+            max_code_ratio,
+            roles,
+            skip_roles,
+        } => {
+            let filters = sync_dispatch::FilterArgs {
+                max_message_chars: *max_message_chars,
+                max_code_ratio: *max_code_ratio,
+                roles: roles.clone(),
+                skip_roles: skip_roles.clone(),
+            };
+            sync_dispatch::cmd_sync(&cli, *all, *dry_run, dir.as_deref(), &filters)
+        }
+        Commands::Gc {
+            floor,
+            target_mb,
             dry_run,
-        } => cmd_gc(&cli, *floor, *target_mb, *dry_run),
+            archive,
+            epochs,
+            audit_keep,
+        } => cmd_gc(
+            &cli,
+            *floor,
+            *target_mb,
+            *dry_run,
+            *archive,
+            *epochs,
+            *audit_keep,
+        ),
         Commands::Forget {
             term,
             episode,
             conscious,
-        } => cmd_forget(
+            keep_journal,
+            query,
+            top,
+            include_conscious,
+            yes,
+            json,
+            hard,
+        } => match query {
+            Some(query) => cmd_forget_query(
+                &cli,
+                query,
+                &ForgetQuery {
+                    top: *top,
+                    include_conscious: *include_conscious,
+                    yes: *yes,
+                    json: *json,
+                    hard: *hard,
+                },
+            ),
+            None => cmd_forget(
+                &cli,
+                term.as_deref(),
+                episode.as_deref(),
+                conscious.as_deref(),
+                *keep_journal,
+                *hard,
+            ),
+        },
+        Commands::Undo { list } => cmd_undo(&cli, *list),
+        Commands::Log {
+            since,
+            kind,
+            limit,
+            json,
+        } => cmd_log(
             &cli,
-            term.as_deref(),
-            episode.as_deref(),
-            conscious.as_deref(),
+            &AuditFilter {
+                since: since.clone(),
+                kind: *kind,
+                limit: (*limit > 0).then_some(*limit),
+            },
+            *json,
         ),
+        Commands::Buffer {
+            action,
+            force,
+            json,
+        } => cmd_buffer(&cli, action, *force, *json),
+        Commands::RenameEpisode { id, name } => cmd_rename_episode(&cli, id, name),
+        Commands::RestoreEpisode { id } => cmd_restore_episode(&cli, id),
+        Commands::RestoreForgotten {
+            id,
+            all,
+            since,
+            list,
+        } => cmd_restore_forgotten(&cli, *id, *all && !*list, since.as_deref()),
+        Commands::RecoverConscious { from } => cmd_recover_conscious(&cli, from.as_deref()),
+        Commands::Doctor {
+            deep,
+            vocab,
+            max_share,
+        } => cmd_doctor(&cli, *deep, vocab.then_some(*max_share)),
+        Commands::Snapshot {
+            name,
+            diff,
+            threshold,
+            json,
+        } => match diff {
+            Some(earlier) => cmd_snapshot_diff(&cli, earlier, *threshold, *json),
+            None => cmd_snapshot(&cli, name.as_deref()),
+        },
+        Commands::ExportPositions { path, projection } => {
+            cmd_export_positions(&cli, path, *projection)
+        }
+        Commands::Schema { output } => cmd_schema(*output),
+        Commands::Migrate {
+            migration,
+            dry_run: _,
+            apply,
+        } => cmd_migrate(&cli, migration, *apply),
         Commands::Init { global, force } => cmd_init(*global, *force),
+        Commands::Config {
+            action,
+            key,
+            value,
+            global,
+        } => cmd_config(action, key.as_deref(), value.as_deref(), *global),
+    };
+    match result.map_err(explain_busy) {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            exit::code_for(&err).into()
+        }
     }
 }
 
-// ---------------------------------------------------------------------------
-// Advisory pidfile for observability
-// ---------------------------------------------------------------------------
-
-fn pidfile_path() -> Option<PathBuf> {
-    let base = std::env::var("AM_DATA_DIR")
-        .ok()
-        .map(PathBuf::from)
-        .or_else(|| am_store::project::default_base_dir().ok())?;
-    Some(base.join("am-serve.pid"))
+/// Printed before each retry when another process holds the database.
+fn busy_notice(retry: &BusyRetry) {
+    eprintln!(
+        "memory is busy (another am process is writing); retrying in {:.1}s ({}/{})",
+        retry.delay.as_secs_f64(),
+        retry.attempt + 1,
+        retry.max_attempts
+    );
 }
 
-/// Check for an existing pidfile and log accordingly, then write our own.
-fn acquire_pidfile() -> Option<PathBuf> {
-    let path = pidfile_path()?;
-    if let Ok(content) = std::fs::read_to_string(&path)
-        && let Ok(pid) = content.trim().parse::<u32>()
-    {
-        if is_process_alive(pid) {
-            tracing::warn!(
-                "another am serve (PID {pid}) is running - coexisting with busy_timeout"
-            );
-        } else {
-            tracing::info!("cleaned up stale pidfile (PID {pid} is dead)");
-            let _ = std::fs::remove_file(&path);
-        }
-    }
-
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
-    match std::fs::File::create(&path) {
-        Ok(mut f) => {
-            let _ = write!(f, "{}", std::process::id());
-            tracing::info!("wrote pidfile: {}", path.display());
-            Some(path)
-        }
-        Err(e) => {
-            tracing::warn!("failed to write pidfile: {e}");
-            None
-        }
+/// Lead with what a busy database means when retries ran out.
+fn explain_busy(err: anyhow::Error) -> anyhow::Error {
+    let busy = err.chain().any(|e| {
+        e.downcast_ref::<StoreError>()
+            .is_some_and(|e| e.kind() == StoreErrorKind::Busy)
+    });
+    if busy {
+        err.context("memory is busy (another am process is writing); try again once it finishes")
+    } else {
+        err
     }
 }
 
-fn release_pidfile(path: &std::path::Path) {
-    let _ = std::fs::remove_file(path);
-    tracing::info!("removed pidfile: {}", path.display());
+// ---------------------------------------------------------------------------
+// Advisory pidfile for observability
+// ---------------------------------------------------------------------------
+
+/// The data directory the store opens: `AM_DATA_DIR`, then the config
+/// file's `data_dir`, then `~/.attention-matters`.
+fn serve_base_dir() -> Option<PathBuf> {
+    am_store::config::load()
+        .map(|config| config.data_dir)
+        .or_else(|_| am_store::project::default_base_dir())
+        .ok()
 }
 
-#[cfg(unix)]
-fn is_process_alive(pid: u32) -> bool {
-    // kill(pid, 0) checks existence without sending a signal
-    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+fn pidfile_path() -> Option<PathBuf> {
+    Some(serve_base_dir()?.join("am-serve.pid"))
 }
 
-#[cfg(not(unix))]
-fn is_process_alive(_pid: u32) -> bool {
-    false // conservative: assume dead on non-unix
+/// Written by `am serve` when a store write fails, read by `am doctor`.
+fn unsaved_marker_path() -> Option<PathBuf> {
+    Some(serve_base_dir()?.join("am-serve.unsaved"))
 }
 
-fn cmd_serve(cli: &Cli, http_port: Option<u16>) -> Result<()> {
-    let store = open_store(cli)?;
+fn cmd_serve(
+    cli: &Cli,
+    http_port: Option<u16>,
+    rest_addr: Option<std::net::SocketAddr>,
+    metrics_addr: Option<std::net::SocketAddr>,
+) -> Result<()> {
+    let config = load_config()?;
+    let metrics_addr = match metrics_addr {
+        Some(addr) => Some(addr),
+        None => metrics_addr_from_env()?,
+    };
+    if let Some(addr) = rest_addr
+        && !addr.ip().is_loopback()
+        && config.http.token.is_none()
+    {
+        anyhow::bail!(
+            "--rest {addr} is reachable from other machines; set http.token (or AM_HTTP_TOKEN) \
+             so the REST API requires a bearer token"
+        );
+    }
+    let store = open_store_with(cli, &config)?;
     tracing::info!("starting MCP server");
 
-    let pidfile = acquire_pidfile();
+    let pidfile = pidfile_path().filter(|path| pidfile::acquire(path));
 
-    let server =
-        std::sync::Arc::new(server::AmServer::new(store).map_err(|e| anyhow::anyhow!("{e}"))?);
+    let mut builder = AmServer::builder(store)
+        .plans(&config.plans)
+        .buffer(&config.buffer)
+        .budget_config(config.compose)
+        .allowed_paths(config.mcp_allowed_paths())
+        .max_ingest_bytes(config.mcp.max_ingest_bytes);
+    if let Some(marker) = unsaved_marker_path() {
+        builder = builder.unsaved_marker(marker);
+    }
+    let server = std::sync::Arc::new(builder.build()?);
 
     // Install signal handlers that close stdin to unblock the stdio loop.
     install_signal_handlers();
+    #[cfg(windows)]
+    {
+        let server = std::sync::Arc::clone(&server);
+        let pidfile = pidfile.clone();
+        install_console_handler(move || {
+            server.checkpoint_wal();
+            if let Some(path) = &pidfile {
+                pidfile::release(path);
+            }
+        });
+    }
 
-    // If --http is requested, spin up a tokio runtime for the HTTP server
-    // alongside the sync JSON-RPC stdio loop.
-    let _http_guard = if let Some(port) = http_port {
-        let server_clone = std::sync::Arc::clone(&server);
+    // If --http, --rest, or --metrics-addr is requested, spin up a tokio
+    // runtime for the HTTP servers alongside the sync JSON-RPC stdio loop.
+    let _http_guard = if http_port.is_some() || rest_addr.is_some() || metrics_addr.is_some() {
+        let brains = http_server::BrainServers::new(
+            std::sync::Arc::clone(&server),
+            brain_name(cli),
+            config.clone(),
+        );
         let cancel = tokio_util::sync::CancellationToken::new();
         let cancel_clone = cancel.clone();
 
         let rt = tokio::runtime::Runtime::new().context("failed to create tokio runtime")?;
-        let listener = rt.block_on(http_server::bind_http(port))?;
+        let http_listener = http_port
+            .map(|port| rt.block_on(http_server::bind_http(port)))
+            .transpose()?;
+        let rest_listener = rest_addr
+            .map(|addr| rt.block_on(rest_api::bind_rest(addr)))
+            .transpose()?;
+        let metrics_listener = metrics_addr
+            .map(|addr| rt.block_on(metrics_server::bind_metrics(addr)))
+            .transpose()?;
+        let metrics_source = std::sync::Arc::clone(&server);
 
         let handle = std::thread::spawn(move || {
             rt.block_on(async {
-                if let Err(e) = http_server::serve_http(listener, server_clone, cancel_clone).await
-                {
-                    tracing::error!("HTTP server error: {e}");
-                }
+                let http = async {
+                    if let Some(listener) = http_listener
+                        && let Err(e) = http_server::serve_http(
+                            listener,
+                            std::sync::Arc::clone(&brains),
+                            cancel_clone.clone(),
+                        )
+                        .await
+                    {
+                        tracing::error!("HTTP server error: {e}");
+                    }
+                };
+                let rest = async {
+                    if let Some(listener) = rest_listener
+                        && let Err(e) = rest_api::serve_rest(
+                            listener,
+                            std::sync::Arc::clone(&brains),
+                            cancel_clone.clone(),
+                        )
+                        .await
+                    {
+                        tracing::error!("REST API error: {e}");
+                    }
+                };
+                let metrics = async {
+                    if let Some(listener) = metrics_listener
+                        && let Err(e) = metrics_server::serve_metrics(
+                            listener,
+                            metrics_source,
+                            cancel_clone.clone(),
+                        )
+                        .await
+                    {
+                        tracing::error!("metrics server error: {e}");
+                    }
+                };
+                tokio::join!(http, rest, metrics);
             });
         });
 
@@ -375,7 +1313,11 @@ fn cmd_serve(cli: &Cli, http_port: Option<u16>) -> Result<()> {
     };
 
     // Run the JSON-RPC stdio loop. Blocks until stdin closes or I/O error.
-    let result = jsonrpc::run_stdio_loop(|name, args| server.dispatch_tool(name, args));
+    let result = jsonrpc::run_stdio_loop(
+        &server.tool_list(),
+        |params| server.observe_client_info(params),
+        |name, args| server.dispatch_tool(name, args),
+    );
 
     // Signal HTTP server to shut down
     if let Some((_handle, cancel)) = _http_guard {
@@ -385,17 +1327,32 @@ fn cmd_serve(cli: &Cli, http_port: Option<u16>) -> Result<()> {
 
     // Clean shutdown: WAL checkpoint + pidfile cleanup
     server.checkpoint_wal();
-    if let Some(path) = pidfile {
-        release_pidfile(&path);
+    if let Some(path) = &pidfile {
+        pidfile::release(path);
     }
 
     result
 }
 
+const METRICS_ADDR_ENV: &str = "AM_METRICS_ADDR";
+
+/// The metrics address in `AM_METRICS_ADDR`, if set and not empty.
+fn metrics_addr_from_env() -> Result<Option<std::net::SocketAddr>> {
+    match std::env::var(METRICS_ADDR_ENV) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map(Some)
+            .with_context(|| format!("{METRICS_ADDR_ENV}={value:?} is not a socket address")),
+        _ => Ok(None),
+    }
+}
+
 /// Install signal handlers that close stdin to unblock the blocking stdio loop.
 ///
 /// On Unix, SIGTERM/SIGHUP/SIGINT close `/dev/stdin` via dup2, causing
-/// `BufRead::lines()` to return `None` and the loop to exit cleanly.
+/// `BufRead::lines()` to return `None` and the loop to exit cleanly. On
+/// Windows see [`install_console_handler`].
 fn install_signal_handlers() {
     #[cfg(unix)]
     {
@@ -422,55 +1379,239 @@ fn install_signal_handlers() {
     }
 }
 
-fn cmd_query(cli: &Cli, text: &str) -> Result<()> {
+/// Run `shutdown` on Ctrl+C, Ctrl+Break, or console close, then exit.
+///
+/// Windows has no way to close stdin under a blocked read the way the Unix
+/// handler does, so the handler thread checkpoints the WAL and removes the
+/// pidfile itself. A second event while that runs exits at once.
+#[cfg(windows)]
+fn install_console_handler(shutdown: impl Fn() + Send + Sync + 'static) {
+    use std::sync::OnceLock;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use windows_sys::Win32::Foundation::{BOOL, TRUE};
+    use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+
+    static SHUTDOWN: OnceLock<Box<dyn Fn() + Send + Sync>> = OnceLock::new();
+    static SIGNALED: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "system" fn handler(_event: u32) -> BOOL {
+        if SIGNALED.swap(true, Ordering::SeqCst) {
+            // Second event: force exit
+            std::process::exit(1);
+        }
+        if let Some(shutdown) = SHUTDOWN.get() {
+            shutdown();
+        }
+        std::process::exit(0);
+    }
+
+    if SHUTDOWN.set(Box::new(shutdown)).is_ok() {
+        // SAFETY: `handler` is a valid `extern "system"` routine for the
+        // life of the process.
+        if unsafe { SetConsoleCtrlHandler(Some(handler), TRUE) } == 0 {
+            tracing::warn!(
+                "failed to install console handler: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+fn cmd_query(
+    cli: &Cli,
+    text: &str,
+    include_archived: bool,
+    include_epochs: bool,
+    actor: Option<&str>,
+    project: Option<&str>,
+) -> Result<()> {
+    let config = load_config()?;
+    let load_started = Instant::now();
     let store = open_store(cli)?;
     let mut system = store.load_system().context("failed to load system")?;
+    let load_time = load_started.elapsed();
+    if let Some(verbs) = config.plans.verbs {
+        system.set_plan_verbs(verbs);
+    }
+    // Nothing is saved after a CLI query, so archived and epoch episodes
+    // only live in this process's copy of the system.
+    if include_archived {
+        let archived = store
+            .store()
+            .load_archived_episodes()
+            .context("failed to load archived episodes")?;
+        system.episodes.extend(archived);
+        system.mark_dirty();
+    }
+    if include_epochs {
+        let epochs = store
+            .store()
+            .load_epoch_episodes()
+            .context("failed to load epoch files")?;
+        system.episodes.extend(epochs);
+        system.mark_dirty();
+    }
+    system.set_actor_filter(actor.map(str::to_owned));
+    if let Some(project) = project {
+        system.set_project(Some(project.to_owned()));
+    }
 
-    let query_result = QueryEngine::process_query(&mut system, text);
+    let options = QueryOptions {
+        spelling_tolerance: true,
+    };
+    let query_result = QueryEngine::process_query_with(&mut system, text, options);
+    let compose_started = Instant::now();
     let surface = compute_surface(&system, &query_result);
     let composed = compose_context(&mut system, &surface, &query_result, None);
+    let compose_time = compose_started.elapsed();
 
     if composed.context.is_empty() {
-        println!("(no memories found)");
+        exit::note!("(no memories found)");
     } else {
         println!("{}", composed.context);
     }
 
     if cli.verbose {
+        for correction in &query_result.corrections {
+            eprintln!("--- corrected: {correction} ---");
+        }
+        for capped in &query_result.capped_words {
+            eprintln!(
+                "--- capped: \"{}\" activated {} of {} occurrences ---",
+                capped.word,
+                capped.activated,
+                capped.activated + capped.skipped
+            );
+        }
+        eprintln!(
+            "--- metrics: conscious={}, subconscious={}, novel={}, confidence={} ---",
+            composed.metrics.conscious,
+            composed.metrics.subconscious,
+            composed.metrics.novel,
+            composed.confidence.as_str()
+        );
+        let counts = &composed.candidates;
         eprintln!(
-            "--- metrics: conscious={}, subconscious={}, novel={} ---",
-            composed.metrics.conscious, composed.metrics.subconscious, composed.metrics.novel
+            "--- candidates: conscious={}, subconscious={}, novel={}, activated_words={} ---",
+            counts.conscious, counts.subconscious, counts.novel, counts.activated_words
         );
+        if let Some(suggestion) = counts.suggestion() {
+            eprintln!("--- suggestion: {suggestion} ---");
+        }
         eprintln!(
             "--- stats: N={}, episodes={}, conscious={} ---",
             system.n(),
             system.episodes.len(),
             system.conscious_episode.neighborhoods.len()
         );
+        // Nothing is saved, so there is no save stage
+        let timings = &query_result.timings;
+        eprintln!(
+            "--- timings: load={:.2}ms, activation={:.2}ms, drift={:.2}ms, compose={:.2}ms ---",
+            load_time.as_secs_f64() * 1e3,
+            timings.activation.as_secs_f64() * 1e3,
+            timings.drift.as_secs_f64() * 1e3,
+            compose_time.as_secs_f64() * 1e3
+        );
     }
 
+    if composed.context.is_empty() {
+        exit::now(exit::EMPTY);
+    }
     Ok(())
 }
 
-fn cmd_ingest(cli: &Cli, files: &[PathBuf], dir: Option<&std::path::Path>) -> Result<()> {
+fn cmd_knows(cli: &Cli, text: &str, json: bool) -> Result<()> {
+    let config = load_config()?;
     let store = open_store(cli)?;
     let mut system = store.load_system().context("failed to load system")?;
-    let mut rng = SmallRng::from_os_rng();
+    if let Some(verbs) = config.plans.verbs {
+        system.set_plan_verbs(verbs);
+    }
+    let result = probe(&mut system, text);
+    let counts = &result.candidates;
+
+    if json {
+        output::print_json(output::Knows {
+            known: result.is_known(),
+            candidates: output::Candidates {
+                conscious: counts.conscious,
+                subconscious: counts.subconscious,
+                novel: counts.novel,
+                activated_words: counts.activated_words,
+                filtered: counts.filtered,
+            },
+            top_score: result.top_score,
+        })?;
+    } else {
+        println!("activated words: {}", counts.activated_words);
+        println!(
+            "candidates:      conscious={}, subconscious={}, novel={}",
+            counts.conscious, counts.subconscious, counts.novel
+        );
+        match result.top_score {
+            Some(score) => println!("top score:       {score:.2}"),
+            None => println!("top score:       -"),
+        }
+    }
+
+    if !result.is_known() {
+        exit::now(exit::EMPTY);
+    }
+    Ok(())
+}
+
+/// Placement and saving options for `am ingest`.
+struct IngestOptions {
+    seed: Option<u64>,
+    jobs: Option<u16>,
+    dry_run: bool,
+}
+
+fn cmd_ingest(
+    cli: &Cli,
+    files: &[PathBuf],
+    jsonl: &[PathBuf],
+    dir: Option<&std::path::Path>,
+    include: &[String],
+    options: &IngestOptions,
+) -> Result<()> {
+    let store = open_store(cli)?;
+    // A dry run measures the store instead of loading it.
+    let measured = if options.dry_run {
+        let stored = store
+            .occurrence_count()
+            .context("failed to count occurrences")?;
+        Some((store.db_size(), stored))
+    } else {
+        None
+    };
+    let seed = options
+        .seed
+        .unwrap_or_else(|| SmallRng::from_os_rng().random());
+    let jobs = options.jobs.map_or_else(
+        || std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+        usize::from,
+    );
 
     let mut paths: Vec<PathBuf> = files.to_vec();
 
     if let Some(dir) = dir {
-        let entries = std::fs::read_dir(dir)
-            .with_context(|| format!("failed to read dir {}", dir.display()))?;
-        for entry in entries.flatten() {
-            let p = entry.path();
-            if p.is_file()
-                && let Some(ext) = p.extension().and_then(|e| e.to_str())
-                && matches!(ext, "txt" | "md" | "html")
-            {
-                paths.push(p);
+        let scan = dir_scan::scan_dir(dir, include)?;
+        if !scan.skipped.is_empty() {
+            let colors::Colors { dim, reset, .. } = colors::Colors::stdout();
+            for (path, reason) in &scan.skipped {
+                tracing::debug!("skipped {} ({reason:?})", path.display());
             }
+            exit::note!(
+                "{dim}skipped {} path(s): {} gitignored, {} by built-in rules \
+                 (--include <name> to override; --verbose to list){reset}",
+                scan.skipped.len(),
+                scan.skipped_count(dir_scan::SkipReason::GitIgnored),
+                scan.skipped_count(dir_scan::SkipReason::Builtin),
+            );
         }
+        paths.extend(scan.files);
     }
 
     // Deduplicate by canonical path so files listed both as positional args
@@ -481,41 +1622,186 @@ fn cmd_ingest(cli: &Cli, files: &[PathBuf], dir: Option<&std::path::Path>) -> Re
         seen.insert(key)
     });
 
+    // An input that cannot be read or parsed is skipped; the others are
+    // still ingested and the command exits with PARTIAL.
+    let inputs = paths.len() + jsonl.len();
+    let mut failed = Vec::new();
+    let mut docs = Vec::with_capacity(paths.len());
+    let mut doc_paths = Vec::with_capacity(paths.len());
     for path in &paths {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("failed to read {}", path.display()))?;
+        // Logs and exports are not always valid UTF-8; keep what decodes.
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                failed.push(
+                    anyhow::Error::from(e).context(format!("failed to read {}", path.display())),
+                );
+                continue;
+            }
+        };
         let name = path
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("unnamed");
-        let episode = ingest_text(&content, Some(name), &mut rng);
-        let nbhd_count = episode.neighborhoods.len();
-        let occ_count: usize = episode
-            .neighborhoods
+        docs.push(IngestDoc {
+            key: path.to_string_lossy().into_owned(),
+            name: name.to_string(),
+            text: String::from_utf8_lossy(&bytes).into_owned(),
+        });
+        doc_paths.push(path);
+    }
+
+    // Workers finish out of order; each line is formatted whole before it
+    // is printed so lines never interleave. A preview is the dry run's
+    // output; otherwise the lines are progress.
+    let mut episodes = ingest_parallel(&docs, seed, jobs, |i, episode| {
+        let report = ingest_report(doc_paths[i], episode, measured);
+        if measured.is_some() {
+            println!("{report}");
+        } else {
+            exit::note!("{report}");
+        }
+    });
+
+    for path in jsonl {
+        match ingest_jsonl(path, seed) {
+            Ok(episode) => {
+                let report = ingest_report(path, &episode, measured);
+                if measured.is_some() {
+                    println!("{report}");
+                } else {
+                    exit::note!("{report}");
+                }
+                episodes.push(episode);
+            }
+            Err(e) => failed.push(e),
+        }
+    }
+
+    if episodes.is_empty() && !failed.is_empty() {
+        let first = failed.remove(0);
+        for e in &failed {
+            eprintln!("failed: {e:#}");
+        }
+        return Err(first);
+    }
+    for e in &failed {
+        eprintln!("failed: {e:#}");
+    }
+
+    if let Some((db_size, stored)) = measured {
+        let total: u64 = episodes
             .iter()
-            .map(|n| n.occurrences.len())
+            .map(|e| IngestPreview::new(e, db_size, stored).estimated_bytes)
             .sum();
-        system.add_episode(episode);
         println!(
-            "ingested {} → {} neighborhoods, {} occurrences",
-            path.display(),
-            nbhd_count,
-            occ_count
+            "dry run: {} episode(s), ~{} estimated growth; nothing saved",
+            episodes.len(),
+            format_bytes(total)
         );
+        exit_if_partial(failed.len(), inputs);
+        return Ok(());
+    }
+
+    let mut system = store.load_system().context("failed to load system")?;
+    let first_new = system.episodes.len();
+    for episode in episodes {
+        system.add_episode(episode);
     }
 
     // Intentional save_system: CLI batch ingest processes multiple files
     // into a fresh system. A full write is acceptable for this offline path.
+    let added = &system.episodes[first_new..];
+    let event = AuditEvent::new(
+        AuditKind::Ingest,
+        added.iter().map(|e| e.neighborhoods.len() as u64).sum(),
+    )
+    .with_context(&system)
+    .with_detail(format!("{} files", added.len()));
     store
-        .save_system(&system)
+        .audited(&event, || store.save_system(&system))
         .context("failed to save system")?;
 
-    println!("done. N={}, episodes={}", system.n(), system.episodes.len());
+    let reused: usize = system.episodes[first_new..]
+        .iter()
+        .map(|e| e.reused_neighborhoods.len())
+        .sum();
+    if reused > 0 {
+        exit::note!("reused {reused} stored neighborhood(s) instead of duplicating them");
+    }
+    exit::note!("done. N={}, episodes={}", system.n(), system.episodes.len());
+    exit_if_partial(failed.len(), inputs);
     Ok(())
 }
 
-fn cmd_stats(cli: &Cli) -> Result<()> {
-    let store = open_store(cli)?;
+/// Parse a JSONL file of chunks into one episode.
+fn ingest_jsonl(path: &std::path::Path, seed: u64) -> Result<am_core::episode::Episode> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut chunks = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let chunk: IngestChunk = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: invalid chunk", path.display(), i + 1))?;
+        chunks.push(chunk);
+    }
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unnamed");
+    let mut rng = SmallRng::seed_from_u64(document_seed(seed, &path.to_string_lossy()));
+    ingest_chunks(&chunks, Some(name), &mut rng)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+}
+
+/// Exit with PARTIAL when some of `inputs` failed and the rest were
+/// handled.
+fn exit_if_partial(failed: usize, inputs: usize) {
+    if failed > 0 {
+        eprintln!("{failed} of {inputs} inputs failed; the rest were ingested");
+        exit::now(exit::PARTIAL);
+    }
+}
+
+/// The line `am ingest` prints for one document, or its preview when
+/// `measured` holds the store's size and occurrence count (`--dry-run`).
+fn ingest_report(
+    path: &std::path::Path,
+    episode: &am_core::episode::Episode,
+    measured: Option<(u64, u64)>,
+) -> String {
+    let Some((db_size, stored)) = measured else {
+        let occ_count: usize = episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.occurrences.len())
+            .sum();
+        return format!(
+            "ingested {} → {} neighborhoods, {} occurrences",
+            path.display(),
+            episode.neighborhoods.len(),
+            occ_count
+        );
+    };
+    let preview = IngestPreview::new(episode, db_size, stored);
+    let mut report = format!(
+        "would ingest {} → {} neighborhoods, {} occurrences, ~{}",
+        path.display(),
+        preview.neighborhoods,
+        preview.occurrences,
+        format_bytes(preview.estimated_bytes)
+    );
+    for chunk in &preview.chunks {
+        report.push_str("\n  | ");
+        report.push_str(&chunk.replace('\n', " "));
+    }
+    report
+}
+
+fn cmd_stats(cli: &Cli, include_epochs: bool) -> Result<()> {
+    let store = open_store_readonly(cli)?;
     let system = store.load_system().context("failed to load system")?;
 
     let db_size = store.db_size();
@@ -537,35 +1823,154 @@ fn cmd_stats(cli: &Cli) -> Result<()> {
         activation.zero_activation,
         activation.total,
     );
+    let text = store
+        .store()
+        .text_storage()
+        .context("failed to measure stored text")?;
+    println!(
+        "text:       {} on disk for {} ({}/{} chunks compressed)",
+        format_bytes(text.stored_bytes),
+        format_bytes(text.text_bytes),
+        text.compressed,
+        text.neighborhoods,
+    );
+    let repaired = store.store().repaired_records();
+    if repaired > 0 {
+        println!("repaired:   {repaired} records with invalid geometry on load");
+    }
+    if include_epochs {
+        let epochs = store
+            .store()
+            .epoch_info()
+            .context("failed to read epoch files")?;
+        println!("epochs:     {}", epochs.len());
+        for epoch in &epochs {
+            println!(
+                "  {}: {} episodes, {}",
+                epoch.path.display(),
+                epoch.episodes,
+                format_bytes(epoch.size),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn cmd_decisions(cli: &Cli, keyword: Option<&str>, json: bool) -> Result<()> {
+    let store = open_store(cli)?;
+    let system = store.load_system().context("failed to load system")?;
+    let decisions = system.decisions(keyword);
+
+    if json {
+        let decisions = decisions
+            .iter()
+            .map(|n| output::Decision {
+                id: n.id.to_string(),
+                text: n.source_text.clone(),
+                created_at: n.created_at.clone(),
+                superseded: n.superseded_by.is_some(),
+                superseded_by: n.superseded_by.map(|id| id.to_string()),
+                actor: n.actor.clone(),
+            })
+            .collect();
+        return output::print_json(output::Decisions { decisions });
+    }
+
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        yellow,
+        ..
+    } = colors::Colors::stdout();
+
+    exit::decor!("{bold}DECISIONS{reset} {dim}({}){reset}", decisions.len());
+    exit::decor!("{dim}───────────────────────────────{reset}");
+
+    if decisions.is_empty() {
+        exit::decor!("  (no decisions)");
+        exit::decor!();
+        exit::decor!("  {dim}Use am_salient with a DECISION: prefix to record one.{reset}");
+        return Ok(());
+    }
+
+    for (i, nbhd) in decisions.iter().enumerate() {
+        println!("  {bold}{}. {reset}{}", i + 1, nbhd.source_text);
+        println!(
+            "     {dim}id={} · {}{}{reset}",
+            safe_prefix(&nbhd.id.to_string(), 8),
+            nbhd.created_at.as_deref().unwrap_or("undated"),
+            actor_suffix(nbhd.actor.as_deref()),
+        );
+        if let Some(by) = nbhd.superseded_by {
+            println!(
+                "     {yellow}superseded by {}{reset}",
+                safe_prefix(&by.to_string(), 8)
+            );
+        }
+    }
+
     Ok(())
 }
 
+/// Mode-specific listing options for `am inspect`.
+struct InspectFilter<'a> {
+    archived: bool,
+    sort: Option<SortKey>,
+    search: Option<&'a str>,
+    target: Option<&'a str>,
+}
+
 fn cmd_inspect(
     cli: &Cli,
     mode: &InspectMode,
     query: Option<&str>,
-    limit: usize,
+    page: PageRequest,
+    filter: &InspectFilter<'_>,
     json: bool,
 ) -> Result<()> {
     // --query flag overrides mode
     if let Some(text) = query {
         return cmd_inspect_query(cli, text);
     }
+    if filter.search.is_some() && !matches!(mode, InspectMode::Conscious) {
+        anyhow::bail!("--search applies to `am inspect conscious` only");
+    }
+    if filter.target.is_some() && !matches!(mode, InspectMode::Episode) {
+        anyhow::bail!("an episode ID applies to `am inspect episode` only");
+    }
 
-    let store = open_store(cli)?;
+    let store = open_store_readonly(cli)?;
 
     match mode {
-        InspectMode::Overview => inspect_overview(&store, limit, json),
-        InspectMode::Conscious => inspect_conscious(&store, limit, json),
-        InspectMode::Episodes => inspect_episodes(&store, limit, json),
-        InspectMode::Neighborhoods => inspect_neighborhoods(&store, limit, json),
+        InspectMode::Overview => inspect_overview(&store, page.limit, json),
+        InspectMode::Conscious => {
+            let sort = SortKey::conscious_sort(filter.sort)?;
+            inspect_conscious(&store, page, filter.search, sort, json)
+        }
+        InspectMode::Episodes => {
+            let sort = SortKey::episode_sort(filter.sort)?;
+            inspect_episodes(&store, page, filter.archived, sort, json)
+        }
+        InspectMode::Episode => {
+            let Some(key) = filter.target else {
+                anyhow::bail!(
+                    "`am inspect episode` needs an episode ID, ID prefix, or name (or `conscious`)"
+                );
+            };
+            inspect_episode(&store, key, page, json)
+        }
+        InspectMode::Neighborhoods => {
+            let sort = SortKey::neighborhood_sort(filter.sort)?;
+            inspect_neighborhoods(&store, page, sort, json)
+        }
     }
 }
 
 fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()> {
-    let episodes = store
+    let sub_episodes = store
         .store()
-        .list_episodes()
+        .list_subconscious_episodes_page(PageRequest::new(5, 0), EpisodeSort::Recent)
         .context("failed to list episodes")?;
     let activation = store
         .store()
@@ -582,46 +1987,41 @@ fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()>
         .context("failed to get top words")?;
     let conscious = store
         .store()
-        .list_conscious_neighborhoods()
+        .list_conscious_neighborhoods_page(PageRequest::new(limit, 0), None, ConsciousSort::Stored)
         .context("failed to list conscious")?;
 
-    let sub_episodes: Vec<_> = episodes.iter().filter(|e| !e.is_conscious).collect();
-
     if json {
-        let top_words_json: Vec<serde_json::Value> = top_words
-            .iter()
-            .map(|(word, act, count)| {
-                serde_json::json!({"word": word, "activation": act, "occurrences": count})
-            })
-            .collect();
-        let conscious_json: Vec<serde_json::Value> = conscious
-            .iter()
-            .map(|n| {
-                serde_json::json!({
-                    "id": n.id,
-                    "text": truncate_text(&n.source_text, 200),
-                    "occurrences": n.occurrence_count,
-                    "activation": n.total_activation,
-                })
-            })
-            .collect();
-
-        let out = serde_json::json!({
-            "total_occurrences": activation.total,
-            "unique_words": unique_words,
-            "episodes": sub_episodes.len(),
-            "conscious_memories": conscious.len(),
-            "db_size_bytes": db_size,
-            "activation": {
-                "mean": activation.mean_activation,
-                "max": activation.max_activation,
-                "zero_count": activation.zero_activation,
+        return output::print_json(output::InspectOverview {
+            total_occurrences: activation.total,
+            unique_words,
+            episodes: sub_episodes.total,
+            conscious_memories: conscious.total,
+            db_size_bytes: db_size,
+            activation: output::ActivationSummary {
+                mean: activation.mean_activation,
+                max: activation.max_activation,
+                zero_count: activation.zero_activation,
             },
-            "top_words": top_words_json,
-            "conscious": conscious_json,
+            top_words: top_words
+                .iter()
+                .map(|(word, activation, occurrences)| output::TopWord {
+                    word: word.clone(),
+                    activation: *activation,
+                    occurrences: *occurrences,
+                })
+                .collect(),
+            conscious: conscious
+                .items
+                .iter()
+                .map(|n| output::ConsciousSummary {
+                    id: n.id.clone(),
+                    text: truncate_text(&n.source_text, 200),
+                    occurrences: n.occurrence_count,
+                    activation: n.total_activation,
+                    actor: n.actor.clone(),
+                })
+                .collect(),
         });
-        println!("{}", serde_json::to_string_pretty(&out).unwrap());
-        return Ok(());
     }
 
     let colors::Colors {
@@ -632,14 +2032,14 @@ fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()>
         ..
     } = colors::Colors::stdout();
 
-    println!("{bold}MEMORY OVERVIEW{reset}");
-    println!("{dim}───────────────────────────────{reset}");
+    exit::decor!("{bold}MEMORY OVERVIEW{reset}");
+    exit::decor!("{dim}───────────────────────────────{reset}");
     println!(
         "  occurrences:  {bold}{}{reset} {dim}({} unique words){reset}",
         activation.total, unique_words
     );
-    println!("  episodes:     {bold}{}{reset}", sub_episodes.len());
-    println!("  conscious:    {bold}{}{reset}", conscious.len());
+    println!("  episodes:     {bold}{}{reset}", sub_episodes.total);
+    println!("  conscious:    {bold}{}{reset}", conscious.total);
     println!(
         "  db size:      {bold}{:.1}MB{reset}",
         db_size as f64 / (1024.0 * 1024.0)
@@ -652,42 +2052,42 @@ fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()>
         activation.total
     );
 
-    if !conscious.is_empty() {
-        println!();
-        println!(
+    if !conscious.items.is_empty() {
+        exit::decor!();
+        exit::decor!(
             "{bold}CONSCIOUS MEMORIES{reset} {dim}({}){reset}",
-            conscious.len()
+            conscious.total
         );
-        println!("{dim}───────────────────────────────{reset}");
-        for (i, nbhd) in conscious.iter().take(5).enumerate() {
+        exit::decor!("{dim}───────────────────────────────{reset}");
+        for (i, nbhd) in conscious.items.iter().take(5).enumerate() {
             let text = truncate_text(&nbhd.source_text, 80);
             println!("  {cyan}{}. {reset}{text}", i + 1);
         }
-        if conscious.len() > 5 {
-            println!(
+        if conscious.total > 5 {
+            exit::decor!(
                 "  {dim}... and {} more (use `am inspect conscious`){reset}",
-                conscious.len() - 5
+                conscious.total - 5
             );
         }
     }
 
     if !top_words.is_empty() {
-        println!();
-        println!("{bold}TOP WORDS{reset} {dim}(by activation){reset}");
-        println!("{dim}───────────────────────────────{reset}");
+        exit::decor!();
+        exit::decor!("{bold}TOP WORDS{reset} {dim}(by activation){reset}");
+        exit::decor!("{dim}───────────────────────────────{reset}");
         for (word, act, count) in top_words.iter().take(10) {
             println!("  {cyan}{:<20}{reset} act={:<5} ×{}", word, act, count);
         }
     }
 
-    if !sub_episodes.is_empty() {
-        println!();
-        println!(
+    if !sub_episodes.items.is_empty() {
+        exit::decor!();
+        exit::decor!(
             "{bold}RECENT EPISODES{reset} {dim}({}){reset}",
-            sub_episodes.len()
+            sub_episodes.total
         );
-        println!("{dim}───────────────────────────────{reset}");
-        for (i, ep) in sub_episodes.iter().take(5).enumerate() {
+        exit::decor!("{dim}───────────────────────────────{reset}");
+        for (i, ep) in sub_episodes.items.iter().enumerate() {
             let name = if ep.name.is_empty() {
                 "(unnamed)"
             } else {
@@ -700,10 +2100,10 @@ fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()>
                 ep.occurrence_count
             );
         }
-        if sub_episodes.len() > 5 {
-            println!(
+        if sub_episodes.total > 5 {
+            exit::decor!(
                 "  {dim}... and {} more (use `am inspect episodes`){reset}",
-                sub_episodes.len() - 5
+                sub_episodes.total - 5
             );
         }
     }
@@ -711,96 +2111,148 @@ fn inspect_overview(store: &BrainStore, limit: usize, json: bool) -> Result<()>
     Ok(())
 }
 
-fn inspect_conscious(store: &BrainStore, limit: usize, json: bool) -> Result<()> {
+fn inspect_conscious(
+    store: &BrainStore,
+    page: PageRequest,
+    search: Option<&str>,
+    sort: ConsciousSort,
+    json: bool,
+) -> Result<()> {
     let conscious = store
         .store()
-        .list_conscious_neighborhoods()
+        .list_conscious_neighborhoods_page(page, search, sort)
         .context("failed to list conscious memories")?;
 
     if json {
-        let items: Vec<serde_json::Value> = conscious
-            .iter()
-            .take(limit)
-            .map(|n| {
-                serde_json::json!({
-                    "id": n.id,
-                    "text": n.source_text,
-                    "occurrences": n.occurrence_count,
-                    "activation": n.total_activation,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&items).unwrap());
-        return Ok(());
+        return print_page_json(&conscious, page, |n| output::ConsciousItem {
+            id: n.id.clone(),
+            text: n.source_text.clone(),
+            kind: n.neighborhood_type.as_str().to_string(),
+            created_at: n.created_at.clone(),
+            occurrences: n.occurrence_count,
+            activation: n.total_activation,
+            tokens: n.llm_tokens,
+            actor: n.actor.clone(),
+            project: n.project.clone(),
+            group_id: n.group_id.clone(),
+        });
     }
 
     let colors::Colors {
         bold, dim, reset, ..
     } = colors::Colors::stdout();
 
-    println!(
+    exit::decor!(
         "{bold}CONSCIOUS MEMORIES{reset} {dim}({}){reset}",
-        conscious.len()
+        conscious.total
     );
-    println!("{dim}───────────────────────────────{reset}");
+    exit::decor!("{dim}───────────────────────────────{reset}");
 
-    if conscious.is_empty() {
-        println!("  (no conscious memories)");
-        println!();
-        println!("  {dim}Use am_salient to mark important insights.{reset}");
+    if conscious.total == 0 {
+        if let Some(term) = search {
+            exit::decor!("  (no conscious memories match {term:?})");
+            return Ok(());
+        }
+        exit::decor!("  (no conscious memories)");
+        exit::decor!();
+        exit::decor!("  {dim}Use am_salient to mark important insights.{reset}");
         return Ok(());
     }
 
-    for (i, nbhd) in conscious.iter().take(limit).enumerate() {
+    for (i, (nbhd, part)) in group_rows(&conscious.items).into_iter().enumerate() {
         let text = if nbhd.source_text.is_empty() {
             "(no source text)".to_string()
         } else {
             nbhd.source_text.clone()
         };
-        println!("  {bold}{}. {reset}{text}", i + 1);
+        // Tagged memories show their project next to the type
+        let project = nbhd
+            .project
+            .as_deref()
+            .map(|p| format!(" · {p}"))
+            .unwrap_or_default();
+        // Chunks of one long salient text show which part they are
+        let part = if part > 0 {
+            format!(" · part {part}")
+        } else {
+            String::new()
+        };
+        println!(
+            "  {bold}{}. {reset}{dim}[{}{project}{part}]{reset} {text}",
+            page.offset + i + 1,
+            nbhd.neighborhood_type.as_str()
+        );
+        let created = nbhd
+            .created_at
+            .as_deref()
+            .map(|at| format!(" · {}", safe_prefix(at, 10)))
+            .unwrap_or_default();
         println!(
-            "     {dim}id={} · {} words · activation={}{reset}",
+            "     {dim}id={} · {} words · activation={}{created}{}{reset}",
             safe_prefix(&nbhd.id, 8),
             nbhd.occurrence_count,
-            nbhd.total_activation
+            nbhd.total_activation,
+            actor_suffix(nbhd.actor.as_deref()),
         );
     }
 
-    if conscious.len() > limit {
-        println!(
-            "\n  {dim}Showing {limit} of {} (use --limit to see more){reset}",
-            conscious.len()
-        );
-    }
+    print_page_footer(&conscious, page, dim, reset);
 
     Ok(())
 }
 
-fn inspect_episodes(store: &BrainStore, limit: usize, json: bool) -> Result<()> {
-    let episodes = store
-        .store()
-        .list_episodes()
-        .context("failed to list episodes")?;
+/// Conscious rows in display order: each chunk of a long salient text
+/// follows the first chunk of its group on the page, numbered by its part
+/// (1 for the first). Ungrouped rows keep their place with part 0.
+fn group_rows(items: &[NeighborhoodInfo]) -> Vec<(&NeighborhoodInfo, usize)> {
+    let mut placed = vec![false; items.len()];
+    let mut rows = Vec::with_capacity(items.len());
+    for (i, nbhd) in items.iter().enumerate() {
+        if placed[i] {
+            continue;
+        }
+        let Some(group) = &nbhd.group_id else {
+            rows.push((nbhd, 0));
+            continue;
+        };
+        rows.push((nbhd, 1));
+        let mut part = 1;
+        for (j, other) in items.iter().enumerate().skip(i + 1) {
+            if !placed[j] && other.group_id.as_ref() == Some(group) {
+                placed[j] = true;
+                part += 1;
+                rows.push((other, part));
+            }
+        }
+    }
+    rows
+}
 
-    let sub_episodes: Vec<_> = episodes.iter().filter(|e| !e.is_conscious).collect();
+fn inspect_episodes(
+    store: &BrainStore,
+    page: PageRequest,
+    archived: bool,
+    sort: EpisodeSort,
+    json: bool,
+) -> Result<()> {
+    let sub_episodes = if archived {
+        store.store().list_archived_episodes_page(page, sort)
+    } else {
+        store.store().list_subconscious_episodes_page(page, sort)
+    }
+    .context("failed to list episodes")?;
 
     if json {
-        let items: Vec<serde_json::Value> = sub_episodes
-            .iter()
-            .take(limit)
-            .map(|e| {
-                serde_json::json!({
-                    "id": e.id,
-                    "name": e.name,
-                    "timestamp": e.timestamp,
-                    "neighborhoods": e.neighborhood_count,
-                    "occurrences": e.occurrence_count,
-                    "activation": e.total_activation,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&items).unwrap());
-        return Ok(());
+        return print_page_json(&sub_episodes, page, |e| output::EpisodeItem {
+            id: e.id.clone(),
+            name: e.name.clone(),
+            timestamp: e.timestamp.clone(),
+            neighborhoods: e.neighborhood_count,
+            occurrences: e.occurrence_count,
+            activation: e.total_activation,
+            tokens: e.llm_tokens,
+            actor: e.actor.clone(),
+        });
     }
 
     let colors::Colors {
@@ -811,19 +2263,34 @@ fn inspect_episodes(store: &BrainStore, limit: usize, json: bool) -> Result<()>
         ..
     } = colors::Colors::stdout();
 
-    println!("{bold}EPISODES{reset} {dim}({}){reset}", sub_episodes.len());
-    println!("{dim}───────────────────────────────{reset}");
+    let heading = if archived {
+        "ARCHIVED EPISODES"
+    } else {
+        "EPISODES"
+    };
+    exit::decor!(
+        "{bold}{heading}{reset} {dim}({}){reset}",
+        sub_episodes.total
+    );
+    exit::decor!("{dim}───────────────────────────────{reset}");
+
+    if archived && sub_episodes.total == 0 {
+        exit::decor!("  (no archived episodes)");
+        exit::decor!();
+        exit::decor!("  {dim}Episodes are archived by `am gc --archive`.{reset}");
+        return Ok(());
+    }
 
-    if sub_episodes.is_empty() {
-        println!("  (no episodes)");
-        println!();
-        println!(
-            "  {dim}Episodes are created by am_buffer (after 3 exchanges) or am ingest.{reset}"
+    if sub_episodes.total == 0 {
+        exit::decor!("  (no episodes)");
+        exit::decor!();
+        exit::decor!(
+            "  {dim}Episodes are created by am_buffer (after buffer.threshold exchanges) or am ingest.{reset}"
         );
         return Ok(());
     }
 
-    for (i, ep) in sub_episodes.iter().take(limit).enumerate() {
+    for (i, ep) in sub_episodes.items.iter().enumerate() {
         let name = if ep.name.is_empty() {
             "(unnamed)"
         } else {
@@ -834,47 +2301,229 @@ fn inspect_episodes(store: &BrainStore, limit: usize, json: bool) -> Result<()>
         } else {
             &ep.timestamp
         };
-        println!("{cyan}  {}. {reset}{bold}{name}{reset}", i + 1);
         println!(
-            "     {dim}{} neighborhoods · {} occurrences · activation={} {ts}{reset}",
-            ep.neighborhood_count, ep.occurrence_count, ep.total_activation,
+            "{cyan}  {}. {reset}{bold}{name}{reset}",
+            page.offset + i + 1
         );
+        println!(
+            "     {dim}{} neighborhoods · {} occurrences · ~{} tokens · activation={}{} {ts}{reset}",
+            ep.neighborhood_count,
+            ep.occurrence_count,
+            ep.llm_tokens,
+            ep.total_activation,
+            actor_suffix(ep.actor.as_deref()),
+        );
+    }
+
+    print_page_footer(&sub_episodes, page, dim, reset);
+
+    Ok(())
+}
+
+fn inspect_episode(store: &BrainStore, key: &str, page: PageRequest, json: bool) -> Result<()> {
+    let mut matches = store
+        .store()
+        .find_episodes(key)
+        .context("failed to look up episode")?;
+    let episode = match matches.len() {
+        0 => anyhow::bail!("no episode matches `{key}` (see `am inspect episodes`)"),
+        1 => matches.remove(0),
+        _ => {
+            let listed: Vec<String> = matches
+                .iter()
+                .map(|e| format!("  {} {}", safe_prefix(&e.id, 8), e.name))
+                .collect();
+            anyhow::bail!(
+                "`{key}` matches {} episodes; use more of the ID:\n{}",
+                matches.len(),
+                listed.join("\n")
+            );
+        }
+    };
+    let neighborhoods = store
+        .store()
+        .episode_neighborhoods(&episode.id)
+        .context("failed to load episode neighborhoods")?;
+
+    let words = neighborhoods.iter().flat_map(|n| &n.words);
+    let unique_words = words
+        .clone()
+        .map(|(w, _)| w.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len() as u64;
+    let max_activation = words.map(|(_, a)| *a).max().unwrap_or(0);
+    let mean_activation = if episode.occurrence_count == 0 {
+        0.0
+    } else {
+        episode.total_activation as f64 / episode.occurrence_count as f64
+    };
+    let total = neighborhoods.len() as u64;
+    let shown: Vec<_> = neighborhoods
+        .into_iter()
+        .skip(page.offset)
+        .take(page.limit)
+        .collect();
+    let activation_of =
+        |n: &EpisodeNeighborhood| -> u64 { n.words.iter().map(|(_, a)| u64::from(*a)).sum() };
+
+    if json {
+        return output::print_json(output::EpisodeDetail {
+            id: episode.id.clone(),
+            name: episode.name.clone(),
+            is_conscious: episode.is_conscious,
+            archived: episode.archived,
+            timestamp: episode.timestamp.clone(),
+            actor: episode.actor.clone(),
+            stats: output::EpisodeStats {
+                neighborhoods: episode.neighborhood_count,
+                occurrences: episode.occurrence_count,
+                unique_words,
+                total_activation: episode.total_activation,
+                mean_activation,
+                max_activation,
+                tokens: episode.llm_tokens,
+            },
+            neighborhoods: output::Page {
+                total,
+                offset: page.offset,
+                items: shown
+                    .iter()
+                    .map(|n| output::EpisodeNeighborhood {
+                        id: n.id.clone(),
+                        source_text: n.source_text.clone(),
+                        kind: n.neighborhood_type.as_str().to_string(),
+                        created_at: n.created_at.clone(),
+                        total_activation: activation_of(n),
+                        tokens: n.llm_tokens,
+                        actor: n.actor.clone(),
+                        project: n.project.clone(),
+                        feedback_score: n.feedback_score,
+                        words: n
+                            .words
+                            .iter()
+                            .map(|(word, activation)| output::WordActivation {
+                                word: word.clone(),
+                                activation: *activation,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            },
+        });
+    }
+
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        yellow,
+    } = colors::Colors::stdout();
+
+    let name = if episode.is_conscious {
+        "conscious"
+    } else if episode.name.is_empty() {
+        "(unnamed)"
+    } else {
+        &episode.name
+    };
+    let archived = if episode.archived {
+        format!(" {yellow}[archived]{reset}")
+    } else {
+        String::new()
+    };
+    exit::decor!("{bold}EPISODE{reset} {name}{archived}");
+    exit::decor!("{dim}───────────────────────────────{reset}");
+    println!("  ID:          {}", episode.id);
+    if !episode.timestamp.is_empty() {
+        println!("  Timestamp:   {}", episode.timestamp);
+    }
+    if let Some(actor) = &episode.actor {
+        println!("  Actor:       {actor}");
+    }
+    println!(
+        "  Size:        {} neighborhoods · {} occurrences · {} unique words · ~{} tokens",
+        episode.neighborhood_count, episode.occurrence_count, unique_words, episode.llm_tokens
+    );
+    println!(
+        "  Activation:  total={} mean={mean_activation:.2} max={max_activation}",
+        episode.total_activation
+    );
+    exit::decor!();
+
+    if total == 0 {
+        exit::decor!("  (no neighborhoods)");
+        return Ok(());
     }
 
-    if sub_episodes.len() > limit {
+    for (i, nbhd) in shown.iter().enumerate() {
+        let text = nbhd
+            .source_text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!(
+            "  {cyan}{}. {reset}{text} {dim}[{}]{reset}",
+            page.offset + i + 1,
+            nbhd.neighborhood_type.as_str()
+        );
+        let words: Vec<String> = nbhd
+            .words
+            .iter()
+            .map(|(word, activation)| format!("{word}({activation})"))
+            .collect();
+        println!("     {}", words.join(" "));
         println!(
-            "\n  {dim}Showing {limit} of {} (use --limit to see more){reset}",
-            sub_episodes.len()
+            "     {dim}{} · activation={}{}{}{reset}",
+            safe_prefix(&nbhd.id, 8),
+            activation_of(nbhd),
+            feedback_suffix(nbhd.feedback_score),
+            actor_suffix(nbhd.actor.as_deref()),
         );
     }
 
+    print_page_footer(
+        &Page {
+            items: shown,
+            total,
+        },
+        page,
+        dim,
+        reset,
+    );
+
     Ok(())
 }
 
-fn inspect_neighborhoods(store: &BrainStore, limit: usize, json: bool) -> Result<()> {
+fn inspect_neighborhoods(
+    store: &BrainStore,
+    page: PageRequest,
+    sort: NeighborhoodSort,
+    json: bool,
+) -> Result<()> {
     let neighborhoods = store
         .store()
-        .list_neighborhoods()
+        .list_neighborhoods_page(page, sort)
         .context("failed to list neighborhoods")?;
 
     if json {
-        let items: Vec<serde_json::Value> = neighborhoods
-            .iter()
-            .take(limit)
-            .map(|n| {
-                serde_json::json!({
-                    "id": n.id,
-                    "source_text": n.source_text,
-                    "episode": n.episode_name,
-                    "is_conscious": n.is_conscious,
-                    "occurrences": n.occurrence_count,
-                    "total_activation": n.total_activation,
-                    "max_activation": n.max_activation,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&items).unwrap());
-        return Ok(());
+        return print_page_json(&neighborhoods, page, |n| output::NeighborhoodItem {
+            id: n.id.clone(),
+            source_text: n.source_text.clone(),
+            episode: n.episode_name.clone(),
+            is_conscious: n.is_conscious,
+            occurrences: n.occurrence_count,
+            total_activation: n.total_activation,
+            max_activation: n.max_activation,
+            drift: output::Drift {
+                mean: n.mean_drift,
+                max: n.max_drift,
+            },
+            tokens: n.llm_tokens,
+            actor: n.actor.clone(),
+            feedback_score: n.feedback_score,
+            created_at: n.created_at.clone(),
+        });
     }
 
     let colors::Colors {
@@ -885,46 +2534,112 @@ fn inspect_neighborhoods(store: &BrainStore, limit: usize, json: bool) -> Result
         yellow,
     } = colors::Colors::stdout();
 
-    println!(
-        "{bold}NEIGHBORHOODS{reset} {dim}({} total, by activation){reset}",
-        neighborhoods.len()
+    let order = match sort {
+        NeighborhoodSort::Activation => "by activation",
+        NeighborhoodSort::Recency => "newest first",
+        NeighborhoodSort::OccurrenceCount => "by size",
+    };
+    exit::decor!(
+        "{bold}NEIGHBORHOODS{reset} {dim}({} total, {order}){reset}",
+        neighborhoods.total
     );
-    println!("{dim}───────────────────────────────{reset}");
+    exit::decor!("{dim}───────────────────────────────{reset}");
 
-    if neighborhoods.is_empty() {
-        println!("  (no neighborhoods)");
+    if neighborhoods.total == 0 {
+        exit::decor!("  (no neighborhoods)");
         return Ok(());
     }
 
-    for (i, nbhd) in neighborhoods.iter().take(limit).enumerate() {
+    for (i, nbhd) in neighborhoods.items.iter().enumerate() {
         let tag = if nbhd.is_conscious {
             format!("{yellow}[conscious]{reset}")
         } else {
             format!("{dim}[{}]{reset}", nbhd.episode_name)
         };
         let text = truncate_text(&nbhd.source_text, 70);
-        println!("  {cyan}{}. {reset}{text} {tag}", i + 1);
+        println!("  {cyan}{}. {reset}{text} {tag}", page.offset + i + 1);
         println!(
-            "     {dim}{} words · activation: total={} max={}{reset}",
-            nbhd.occurrence_count, nbhd.total_activation, nbhd.max_activation,
+            "     {dim}{} words · activation: total={} max={} · drift: mean={:.3} max={:.3}{}{}{reset}",
+            nbhd.occurrence_count,
+            nbhd.total_activation,
+            nbhd.max_activation,
+            nbhd.mean_drift,
+            nbhd.max_drift,
+            feedback_suffix(nbhd.feedback_score),
+            actor_suffix(nbhd.actor.as_deref()),
         );
     }
 
-    if neighborhoods.len() > limit {
-        println!(
-            "\n  {dim}Showing {limit} of {} (use --limit to see more){reset}",
-            neighborhoods.len()
-        );
-    }
+    print_page_footer(&neighborhoods, page, dim, reset);
 
     Ok(())
 }
 
+/// Byte count in MB, or KB below one MB.
+fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= MB {
+        format!("{:.1}MB", bytes / MB)
+    } else {
+        format!("{:.1}KB", bytes / 1024.0)
+    }
+}
+
+/// ` · feedback: <score>` for inspect listings, empty when no feedback has
+/// been given.
+fn feedback_suffix(score: f64) -> String {
+    if score == 0.0 {
+        String::new()
+    } else {
+        format!(" · feedback: {score:+.2}")
+    }
+}
+
+/// ` · by <actor>` for inspect listings, empty when the creator is unknown.
+fn actor_suffix(actor: Option<&str>) -> String {
+    actor.map_or_else(String::new, |a| format!(" · by {a}"))
+}
+
+/// Print a paged inspect listing as JSON, each row mapped by `item`.
+fn print_page_json<T, I: serde::Serialize>(
+    page: &Page<T>,
+    request: PageRequest,
+    item: impl Fn(&T) -> I,
+) -> Result<()> {
+    output::print_json(output::Page {
+        total: page.total,
+        offset: request.offset,
+        items: page.items.iter().map(item).collect(),
+    })
+}
+
+/// "Showing a-b of N" footer, printed only when the page is not the whole listing.
+fn print_page_footer<T>(page: &Page<T>, request: PageRequest, dim: &str, reset: &str) {
+    if page.items.is_empty() && page.total > 0 {
+        exit::decor!(
+            "\n  {dim}Offset {} is past the end ({} total){reset}",
+            request.offset,
+            page.total
+        );
+    } else if (page.items.len() as u64) < page.total {
+        let first = request.offset + 1;
+        let last = request.offset + page.items.len();
+        exit::decor!(
+            "\n  {dim}Showing {first}-{last} of {} (use --limit/--offset to see more){reset}",
+            page.total
+        );
+    }
+}
+
 fn cmd_inspect_query(cli: &Cli, text: &str) -> Result<()> {
-    let store = open_store(cli)?;
+    let store = open_store_readonly(cli)?;
     let mut system = store.load_system().context("failed to load system")?;
 
-    let query_result = QueryEngine::process_query(&mut system, text);
+    let options = QueryOptions {
+        spelling_tolerance: true,
+    };
+    let query_result = QueryEngine::process_query_with(&mut system, text, options);
     let surface = compute_surface(&system, &query_result);
     let composed = compose_context(&mut system, &surface, &query_result, None);
 
@@ -932,18 +2647,21 @@ fn cmd_inspect_query(cli: &Cli, text: &str) -> Result<()> {
         bold, dim, reset, ..
     } = colors::Colors::stdout();
 
-    println!("{bold}RECALL{reset} for {dim}\"{text}\"{reset}");
-    println!("{dim}───────────────────────────────{reset}");
+    exit::decor!("{bold}RECALL{reset} for {dim}\"{text}\"{reset}");
+    for correction in &query_result.corrections {
+        println!("{dim}corrected: {correction}{reset}");
+    }
+    exit::decor!("{dim}───────────────────────────────{reset}");
 
     if composed.context.is_empty() {
-        println!("  (no memories match this query)");
+        exit::decor!("  (no memories match this query)");
     } else {
         for line in composed.context.lines() {
             println!("  {line}");
         }
     }
 
-    println!();
+    exit::decor!();
     println!(
         "{dim}metrics: conscious={}, subconscious={}, novel={}{reset}",
         composed.metrics.conscious, composed.metrics.subconscious, composed.metrics.novel
@@ -954,126 +2672,1590 @@ fn cmd_inspect_query(cli: &Cli, text: &str) -> Result<()> {
         system.episodes.len(),
         system.conscious_episode.neighborhoods.len()
     );
+    let policy = system.word_weight_policy();
+    println!(
+        "{dim}weights: floor={}, ceiling={}, hapax drift={}, response drift scale={}{reset}",
+        policy.floor,
+        policy.ceiling,
+        if policy.skip_hapax_drift { "off" } else { "on" },
+        policy.response_drift_scale
+    );
+    if !system.word_biases().is_empty() {
+        let mut biases: Vec<_> = system.word_biases().iter().collect();
+        biases.sort_by(|a, b| a.0.cmp(b.0));
+        let biases: Vec<String> = biases.iter().map(|(w, m)| format!("{w}×{m}")).collect();
+        println!("{dim}biases:  {}{reset}", biases.join(", "));
+    }
+    let cutoffs = system.compose_config();
+    println!(
+        "{dim}cutoffs: min_activated_words={}, min_score={}, filtered={}{reset}",
+        cutoffs.min_activated_words,
+        cutoffs.min_score,
+        composed.filtered.len()
+    );
+    for f in &composed.filtered {
+        let reason = match f.reason {
+            CutoffReason::TooFewWords => "too few words",
+            CutoffReason::LowScore => "low score",
+            CutoffReason::InvalidScore => "invalid score",
+        };
+        let text = system
+            .get_neighborhood_ref(f.neighborhood_id)
+            .map(|r| truncate_text(&system.get_neighborhood(r).source_text, 60))
+            .unwrap_or_default();
+        println!(
+            "{dim}  - {reason}: {} word(s), score={:.3} \"{text}\"{reset}",
+            f.activated_words, f.score
+        );
+    }
 
     Ok(())
 }
 
-fn cmd_gc(cli: &Cli, floor: u32, target_mb: Option<u64>, dry_run: bool) -> Result<()> {
+fn cmd_weight(cli: &Cli, words: &[String], json: bool) -> Result<()> {
     let store = open_store(cli)?;
-    let db = store.store();
+    let weights = store
+        .store()
+        .word_weights(words)
+        .context("failed to look up word weights")?;
+
+    if json {
+        let words = weights
+            .iter()
+            .map(|w| output::WordWeight {
+                word: w.word.clone(),
+                neighborhoods: w.neighborhood_count,
+                occurrences: w.occurrence_count,
+                weight: w.weight,
+                bias: w.bias,
+            })
+            .collect();
+        return output::print_json(output::Weights { words });
+    }
+
     let colors::Colors {
-        bold, dim, reset, ..
+        bold,
+        dim,
+        reset,
+        cyan,
+        ..
     } = colors::Colors::stdout();
 
-    let stats = db
-        .activation_distribution()
-        .context("failed to read stats")?;
-    let db_size = db.db_size();
+    println!(
+        "{bold}{:<20} {:>8} {:>8} {:>8}{reset}",
+        "WORD", "NBHDS", "OCCS", "WEIGHT"
+    );
+    for w in &weights {
+        let note = if w.neighborhood_count == 0 {
+            format!(" {dim}(not in memory){reset}")
+        } else if w.bias != 1.0 {
+            format!(" {dim}(bias ×{}){reset}", w.bias)
+        } else {
+            String::new()
+        };
+        println!(
+            "{cyan}{:<20}{reset} {:>8} {:>8} {:>8.4}{note}",
+            w.word, w.neighborhood_count, w.occurrence_count, w.weight
+        );
+    }
+    Ok(())
+}
 
-    if dry_run {
-        // Show what would happen
-        let eligible: u64 = db
-            .gc_eligible_count(floor)
-            .context("failed to query eligible occurrences")?;
+/// Freeze or unfreeze words, or list the frozen ones when `action` is
+/// `None` (`--list`).
+fn cmd_word(cli: &Cli, action: Option<&WordAction>, words: &[String], json: bool) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        ..
+    } = colors::Colors::stdout();
 
-        println!("{bold}GC dry run{reset}\n");
-        println!("  total occurrences:   {}", stats.total);
-        println!("  activation floor:    ≤{floor}");
-        println!("  eligible for eviction: {eligible}");
-        println!("  database size:       {:.1} KB", db_size as f64 / 1024.0);
-        if let Some(mb) = target_mb {
-            println!("  target size:         {mb} MB");
+    let Some(action) = action else {
+        let frozen = store
+            .store()
+            .frozen_words()
+            .context("failed to read frozen words")?;
+        if json {
+            output::print_json(output::FrozenWords { words: frozen })?;
+        } else if frozen.is_empty() {
+            exit::decor!("{dim}No frozen words.{reset}");
+        } else {
+            exit::decor!("{bold}{} frozen{reset}", frozen.len());
+            for word in &frozen {
+                println!("  {cyan}{word}{reset}");
+            }
         }
-        println!("\n{dim}No changes made. Remove --dry-run to execute.{reset}");
         return Ok(());
-    }
-
-    // Run activation-floor GC pass
-    let config = load_config()?;
-    let result = db.gc_pass(floor, &config.retention).context("GC failed")?;
-
-    println!("{bold}GC complete{reset}\n");
-    println!("  evicted occurrences:    {}", result.evicted_occurrences);
-    println!("  removed neighborhoods:  {}", result.removed_neighborhoods);
-    println!("  removed episodes:       {}", result.removed_episodes);
+    };
 
-    // If target_mb specified and still over budget, run aggressive pass
-    if let Some(mb) = target_mb {
-        let target_bytes = mb * 1024 * 1024;
-        let current_size = db.db_size();
-        if current_size > target_bytes {
-            let aggressive = db
-                .gc_to_target_size(target_bytes, &config.retention)
-                .context("aggressive GC failed")?;
-            println!(
-                "\n  {bold}aggressive pass:{reset} evicted {} more occurrences",
-                aggressive.evicted_occurrences
-            );
-        }
+    if words.is_empty() {
+        anyhow::bail!("name at least one word");
+    }
+    let (verb, already, changed) = match action {
+        WordAction::Freeze => (
+            "froze",
+            "already frozen",
+            store
+                .store()
+                .freeze_words(words)
+                .context("failed to freeze words")?,
+        ),
+        WordAction::Unfreeze => (
+            "unfroze",
+            "not frozen",
+            store
+                .store()
+                .unfreeze_words(words)
+                .context("failed to unfreeze words")?,
+        ),
+    };
+    let skipped = words.len() - changed;
+    if skipped > 0 {
+        println!("{bold}{verb}{reset} {changed} words {dim}({skipped} {already}){reset}");
+    } else {
+        println!("{bold}{verb}{reset} {changed} words");
     }
-
-    let after_size = db.db_size();
-    println!(
-        "\n  size: {:.1} KB → {:.1} KB",
-        result.before_size as f64 / 1024.0,
-        after_size as f64 / 1024.0,
-    );
-
     Ok(())
 }
 
-fn cmd_forget(
+/// List, set, or clear per-word scoring multipliers.
+fn cmd_bias(
     cli: &Cli,
-    term: Option<&str>,
-    episode_id: Option<&str>,
-    conscious_id: Option<&str>,
+    action: &BiasAction,
+    word: Option<&str>,
+    multiplier: Option<f64>,
+    json: bool,
 ) -> Result<()> {
     let store = open_store(cli)?;
-    let colors::Colors { bold, reset, .. } = colors::Colors::stdout();
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        ..
+    } = colors::Colors::stdout();
 
-    if let Some(id) = episode_id {
-        let removed = store
-            .forget_episode(id)
-            .context("failed to forget episode")?;
-        if removed == 0 {
-            println!("Episode not found: {id}");
-        } else {
-            println!("{bold}Forgot{reset} episode {id} ({removed} occurrences removed)");
+    match action {
+        BiasAction::List => {
+            let biases = store
+                .store()
+                .word_biases()
+                .context("failed to read word biases")?;
+            if json {
+                let biases = biases
+                    .into_iter()
+                    .map(|(word, multiplier)| output::WordBias { word, multiplier })
+                    .collect();
+                return output::print_json(output::WordBiases { biases });
+            }
+            if biases.is_empty() {
+                exit::decor!("{dim}No word biases.{reset}");
+                return Ok(());
+            }
+            exit::decor!("{bold}{} biased{reset}", biases.len());
+            for (word, multiplier) in &biases {
+                let note = if *multiplier == 0.0 {
+                    format!(" {dim}(muted){reset}")
+                } else {
+                    String::new()
+                };
+                println!("  {cyan}{word:<20}{reset} ×{multiplier}{note}");
+            }
         }
-    } else if let Some(id) = conscious_id {
-        let removed = store
-            .forget_conscious(id)
-            .context("failed to forget conscious memory")?;
+        BiasAction::Set => {
+            let (Some(word), Some(multiplier)) = (word, multiplier) else {
+                anyhow::bail!("usage: am bias set <word> <multiplier>");
+            };
+            if !multiplier.is_finite() {
+                anyhow::bail!("multiplier must be a number from 0 to {MAX_WORD_BIAS}");
+            }
+            let stored = store
+                .store()
+                .set_word_bias(word, multiplier)
+                .context("failed to save word bias")?;
+            let word = word.to_lowercase();
+            if stored == multiplier {
+                println!("{bold}biased{reset} {word} ×{stored}");
+            } else {
+                println!(
+                    "{bold}biased{reset} {word} ×{stored} {dim}(clamped from {multiplier}){reset}"
+                );
+            }
+        }
+        BiasAction::Clear => {
+            let Some(word) = word else {
+                anyhow::bail!("usage: am bias clear <word>");
+            };
+            let cleared = store
+                .store()
+                .clear_word_biases(&[word.to_owned()])
+                .context("failed to clear word bias")?;
+            let word = word.to_lowercase();
+            if cleared > 0 {
+                println!("{bold}cleared{reset} bias on {word}");
+            } else {
+                println!("{dim}{word} has no bias{reset}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cmd_eval(path: &std::path::Path, json: bool) -> Result<()> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let suite: EvalSuite = serde_norway::from_str(&raw)
+        .with_context(|| format!("invalid eval suite {}", path.display()))?;
+
+    let report = run_suite(&suite);
+
+    if json {
+        return output::print_json(report);
+    }
+
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        yellow,
+        ..
+    } = colors::Colors::stdout();
+
+    let name = if report.suite.is_empty() {
+        path.display().to_string()
+    } else {
+        report.suite.clone()
+    };
+    exit::decor!(
+        "{bold}EVAL{reset} {name} {dim}({} queries, k={}){reset}",
+        report.queries.len(),
+        report.k
+    );
+    exit::decor!("{dim}───────────────────────────────{reset}");
+
+    for q in &report.queries {
+        let marker = if q.reciprocal_rank > 0.0 { " " } else { "!" };
+        println!(
+            "{yellow}{marker}{reset} p@{}={:.2}  rr={:.2}  {}",
+            report.k, q.precision_at_k, q.reciprocal_rank, q.query
+        );
+        for hit in &q.hits {
+            println!(
+                "    {dim}{}. [{}] {} ({:.3}){reset}{}",
+                hit.rank,
+                hit.category,
+                hit.document,
+                hit.score,
+                if hit.relevant { " ✓" } else { "" }
+            );
+        }
+    }
+
+    exit::decor!();
+    println!(
+        "  precision@{}: {:.3}",
+        report.k, report.mean_precision_at_k
+    );
+    println!("  MRR:          {:.3}", report.mrr);
+    for (category, stats) in &report.categories {
+        println!(
+            "  {dim}{category}: {}/{} relevant{reset}",
+            stats.relevant, stats.retrieved
+        );
+    }
+
+    Ok(())
+}
+
+fn cmd_gc(
+    cli: &Cli,
+    floor: u32,
+    target_mb: Option<u64>,
+    dry_run: bool,
+    archive: bool,
+    epochs: bool,
+    audit_keep: Option<usize>,
+) -> Result<()> {
+    let store = open_store(cli)?;
+    let db = store.store();
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    let stats = db
+        .activation_distribution()
+        .context("failed to read stats")?;
+    let db_size = db.db_size();
+    let config = load_config()?;
+    let audit_keep = audit_keep.unwrap_or(config.audit_keep);
+
+    if epochs {
+        let report = db
+            .epoch_pass(floor, &config.retention, !dry_run)
+            .context("epoch pass failed")?;
+        if dry_run {
+            exit::decor!("{bold}GC epoch dry run{reset}\n");
+        } else {
+            exit::decor!("{bold}GC complete{reset}\n");
+        }
+        println!("  activation floor:      ≤{floor}");
+        for moved in &report.moved {
+            println!(
+                "  {}: {} episodes, {} neighborhoods",
+                moved.file, moved.episodes, moved.neighborhoods
+            );
+        }
+        println!("  episodes moved:        {}", report.episodes());
+        if dry_run {
+            exit::decor!("\n{dim}No changes made. Remove --dry-run to execute.{reset}");
+        } else {
+            prune_audit_log(db, audit_keep)?;
+            purge_quarantine(db)?;
+            println!(
+                "\n  size: {:.1} KB → {:.1} KB",
+                report.before_size as f64 / 1024.0,
+                report.after_size as f64 / 1024.0,
+            );
+            exit::decor!(
+                "\n{dim}Epoch files are searched by `am query --include-epochs`, or when nothing current matches.{reset}"
+            );
+        }
+        return Ok(());
+    }
+
+    if archive {
+        if dry_run {
+            let eligible = db
+                .gc_archive_eligible_count(floor, &config.retention)
+                .context("failed to query cold episodes")?;
+            exit::decor!("{bold}GC archive dry run{reset}\n");
+            println!("  activation floor:      ≤{floor}");
+            println!("  episodes to archive:   {eligible}");
+            exit::decor!("\n{dim}No changes made. Remove --dry-run to execute.{reset}");
+            return Ok(());
+        }
+        let result = db
+            .gc_archive_pass(floor, &config.retention)
+            .context("GC archive failed")?;
+        exit::decor!("{bold}GC complete{reset}\n");
+        println!("  archived episodes:      {}", result.archived_episodes);
+        prune_audit_log(db, audit_keep)?;
+        purge_quarantine(db)?;
+        exit::decor!(
+            "\n{dim}Archived episodes are skipped by recall; see `am inspect episodes --archived`.{reset}"
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        // Show what would happen
+        let eligible: u64 = db
+            .gc_eligible_count(floor)
+            .context("failed to query eligible occurrences")?;
+
+        exit::decor!("{bold}GC dry run{reset}\n");
+        println!("  total occurrences:   {}", stats.total);
+        println!("  activation floor:    ≤{floor}");
+        println!("  eligible for eviction: {eligible}");
+        println!("  database size:       {:.1} KB", db_size as f64 / 1024.0);
+        if let Some(mb) = target_mb {
+            println!("  target size:         {mb} MB");
+        }
+        exit::decor!("\n{dim}No changes made. Remove --dry-run to execute.{reset}");
+        return Ok(());
+    }
+
+    // Run activation-floor GC pass
+    let result = db.gc_pass(floor, &config.retention).context("GC failed")?;
+
+    exit::decor!("{bold}GC complete{reset}\n");
+    println!("  evicted occurrences:    {}", result.evicted_occurrences);
+    println!("  removed neighborhoods:  {}", result.removed_neighborhoods);
+    println!("  removed episodes:       {}", result.removed_episodes);
+
+    // If target_mb specified and still over budget, run aggressive pass
+    if let Some(mb) = target_mb {
+        let target_bytes = mb * 1024 * 1024;
+        let current_size = db.db_size();
+        if current_size > target_bytes {
+            let aggressive = db
+                .gc_to_target_size(target_bytes, &config.retention)
+                .context("aggressive GC failed")?;
+            println!(
+                "\n  {bold}aggressive pass:{reset} evicted {} more occurrences",
+                aggressive.evicted_occurrences
+            );
+        }
+    }
+
+    prune_audit_log(db, audit_keep)?;
+    purge_quarantine(db)?;
+
+    let after_size = db.db_size();
+    println!(
+        "\n  size: {:.1} KB → {:.1} KB",
+        result.before_size as f64 / 1024.0,
+        after_size as f64 / 1024.0,
+    );
+
+    Ok(())
+}
+
+/// Drop all but the newest `keep` audit log rows, as part of `am gc`.
+fn prune_audit_log(db: &am_store::store::Store, keep: usize) -> Result<()> {
+    let pruned = db.prune_audit(keep).context("failed to prune audit log")?;
+    if pruned > 0 {
+        println!("  pruned audit rows:      {pruned}");
+    }
+    Ok(())
+}
+
+/// Drop quarantine entries past their retention window, as part of
+/// `am gc`.
+fn purge_quarantine(db: &am_store::store::Store) -> Result<()> {
+    let purged = db
+        .purge_quarantine()
+        .context("failed to purge quarantine")?;
+    if purged > 0 {
+        println!("  purged forgotten:       {purged}");
+    }
+    Ok(())
+}
+
+/// `--since` for `am log`: an ISO-8601 time or date, or an age such as
+/// `30m`, `24h`, or `7d`, resolved to an ISO-8601 UTC time.
+fn parse_since(value: &str) -> std::result::Result<String, String> {
+    if let Some(secs) = am_core::time::iso8601_to_unix(value) {
+        return Ok(am_core::time::unix_to_iso8601(secs));
+    }
+    let unit = match value.chars().last() {
+        Some('m') => 60,
+        Some('h') => 3600,
+        Some('d') => 86_400,
+        _ => 0,
+    };
+    value[..value.len() - 1]
+        .parse::<u64>()
+        .ok()
+        .filter(|_| unit > 0)
+        .map(|n| {
+            let now = am_core::time::now_unix_secs();
+            am_core::time::unix_to_iso8601(now.saturating_sub(n.saturating_mul(unit)))
+        })
+        .ok_or_else(|| format!("expected an ISO-8601 time or an age like 24h or 7d, got {value:?}"))
+}
+
+fn parse_audit_kind(value: &str) -> std::result::Result<AuditKind, String> {
+    AuditKind::parse(value).ok_or_else(|| {
+        let kinds: Vec<_> = AuditKind::ALL.iter().map(|k| k.as_str()).collect();
+        format!("expected one of {}, got {value:?}", kinds.join(", "))
+    })
+}
+
+fn cmd_log(cli: &Cli, filter: &AuditFilter, json: bool) -> Result<()> {
+    let store = open_store_readonly(cli)?;
+    let entries = store
+        .store()
+        .audit_log(filter)
+        .context("failed to read audit log")?;
+
+    if json {
+        let entries = entries
+            .into_iter()
+            .map(|e| output::AuditItem {
+                id: e.id,
+                at: e.at,
+                kind: e.kind.as_str().to_string(),
+                count: e.count,
+                project: e.project,
+                actor: e.actor,
+                detail: e.detail,
+            })
+            .collect();
+        return output::print_json(output::AuditLog { entries });
+    }
+
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    exit::decor!("{bold}AUDIT LOG{reset} {dim}({}){reset}", entries.len());
+    exit::decor!("{dim}───────────────────────────────{reset}");
+
+    if entries.is_empty() {
+        exit::decor!("  (no changes recorded)");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        let project = entry
+            .project
+            .as_deref()
+            .map_or_else(String::new, |p| format!(" · {p}"));
+        println!(
+            "  {dim}{}{reset}  {bold}{:<8}{reset} {:>6}  {}{dim}{project}{}{reset}",
+            entry.at,
+            entry.kind.as_str(),
+            entry.count,
+            entry.detail,
+            actor_suffix(entry.actor.as_deref()),
+        );
+    }
+
+    Ok(())
+}
+
+fn cmd_forget(
+    cli: &Cli,
+    term: Option<&str>,
+    episode_id: Option<&str>,
+    conscious_id: Option<&str>,
+    keep_journal: bool,
+    hard: bool,
+) -> Result<()> {
+    let store = open_forget_store(cli, hard)?;
+    let colors::Colors { bold, reset, .. } = colors::Colors::stdout();
+
+    let removed = if let Some(id) = episode_id {
+        let removed = store
+            .forget_episode(id)
+            .context("failed to forget episode")?;
+        if removed == 0 {
+            println!("Episode not found: {id}");
+        } else {
+            println!("{bold}Forgot{reset} episode {id} ({removed} occurrences removed)");
+        }
+        removed
+    } else if let Some(id) = conscious_id {
+        let removed = store
+            .forget_conscious_with(id, keep_journal)
+            .context("failed to forget conscious memory")?;
         if removed == 0 {
             println!("Conscious memory not found: {id}");
         } else {
             println!("{bold}Forgot{reset} conscious memory {id} ({removed} occurrences removed)");
         }
-    } else if let Some(word) = term {
-        let (removed_occs, removed_nbhds, removed_eps) =
-            store.forget_term(word).context("failed to forget term")?;
-        if removed_occs == 0 {
-            println!("No occurrences of \"{word}\" found.");
+        removed
+    } else if let Some(word) = term {
+        let (removed_occs, removed_nbhds, removed_eps) =
+            store.forget_term(word).context("failed to forget term")?;
+        if removed_occs == 0 {
+            println!("No occurrences of \"{word}\" found.");
+        } else {
+            println!(
+                "{bold}Forgot{reset} \"{word}\": {removed_occs} occurrences, \
+                 {removed_nbhds} neighborhoods, {removed_eps} episodes removed"
+            );
+        }
+        removed_occs
+    } else {
+        anyhow::bail!("specify a term, --episode <id>, or --conscious <id> to forget");
+    };
+
+    if removed > 0 {
+        quarantine_hint(&store);
+    }
+    Ok(())
+}
+
+/// Open the store for `am forget`; with `hard`, forgets skip quarantine.
+fn open_forget_store(cli: &Cli, hard: bool) -> Result<BrainStore> {
+    let mut config = load_config()?;
+    if hard {
+        config.quarantine_days = 0;
+    }
+    open_store_with(cli, &config)
+}
+
+/// Tell where a forget went, when it went to quarantine.
+fn quarantine_hint(store: &BrainStore) {
+    let days = store.store().quarantine_days();
+    if days > 0 {
+        let colors::Colors { dim, reset, .. } = colors::Colors::stdout();
+        exit::decor!(
+            "{dim}Kept in quarantine for {days} days; see `am restore-forgotten --list`.{reset}"
+        );
+    }
+}
+
+/// Options of `am forget --query`.
+struct ForgetQuery {
+    top: usize,
+    include_conscious: bool,
+    yes: bool,
+    json: bool,
+    hard: bool,
+}
+
+fn cmd_forget_query(cli: &Cli, query: &str, opts: &ForgetQuery) -> Result<()> {
+    let store = open_forget_store(cli, opts.hard)?;
+    let mut system = store.load_system().context("failed to load system")?;
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    let mut conscious_skipped = 0;
+    let plan: Vec<RankedFragment> = ranked_matches(&mut system, query)
+        .into_iter()
+        .filter(|m| {
+            let keep = opts.include_conscious || m.category != RecallCategory::Conscious;
+            if !keep {
+                conscious_skipped += 1;
+            }
+            keep
+        })
+        .take(opts.top)
+        .collect();
+    let ids: Vec<uuid::Uuid> = plan.iter().map(|m| m.neighborhood_id).collect();
+    let category = |c: RecallCategory| match c {
+        RecallCategory::Conscious => "conscious",
+        RecallCategory::Subconscious => "subconscious",
+        RecallCategory::Novel => "novel",
+    };
+    let detail = format!("query \"{query}\": {} neighborhoods", ids.len());
+
+    if opts.json {
+        let forgotten = if opts.yes && !ids.is_empty() {
+            let report = store
+                .forget_neighborhoods(&ids, &detail)
+                .context("failed to forget neighborhoods")?;
+            Some(output::ForgetCounts {
+                occurrences: report.occurrences,
+                neighborhoods: report.neighborhoods,
+                episodes: report.episodes,
+            })
+        } else {
+            None
+        };
+        output::print_json(output::ForgetPlan {
+            query: query.to_string(),
+            matches: plan
+                .iter()
+                .map(|m| output::ForgetMatch {
+                    id: m.neighborhood_id.to_string(),
+                    category: category(m.category).to_string(),
+                    episode: m.episode_name.clone(),
+                    score: m.score,
+                    preview: truncate_text(&m.text, 80),
+                })
+                .collect(),
+            conscious_skipped,
+            forgotten,
+        })?;
+    } else {
+        exit::decor!(
+            "{bold}FORGET PLAN{reset} {dim}(\"{query}\", {} of top {}){reset}",
+            plan.len(),
+            opts.top
+        );
+        exit::decor!("{dim}───────────────────────────────{reset}");
+        for m in &plan {
+            println!(
+                "{:>7.3}  {:<12}  {}  {dim}{}{reset}",
+                m.score,
+                category(m.category),
+                m.neighborhood_id,
+                m.episode_name
+            );
+            println!("         {}", truncate_text(&m.text, 80));
+        }
+        if conscious_skipped > 0 {
+            exit::note!(
+                "{conscious_skipped} conscious match(es) kept; add --include-conscious to forget them"
+            );
+        }
+        if !ids.is_empty() {
+            if !opts.yes {
+                eprint!("Forget these {} neighborhood(s)? [y/N] ", ids.len());
+                std::io::stderr().flush()?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !answer.trim().eq_ignore_ascii_case("y") {
+                    println!("aborted");
+                    return Ok(());
+                }
+            }
+            let report = store
+                .forget_neighborhoods(&ids, &detail)
+                .context("failed to forget neighborhoods")?;
+            println!(
+                "{bold}Forgot{reset} {} occurrences, {} neighborhoods, {} episodes",
+                report.occurrences, report.neighborhoods, report.episodes
+            );
+            quarantine_hint(&store);
+        }
+    }
+
+    if ids.is_empty() {
+        exit::now(exit::EMPTY);
+    }
+    Ok(())
+}
+
+fn cmd_restore_forgotten(cli: &Cli, id: Option<i64>, all: bool, since: Option<&str>) -> Result<()> {
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    if id.is_none() && !all {
+        let store = open_store_readonly(cli)?;
+        let days = store.store().quarantine_days();
+        let entries = store
+            .store()
+            .quarantine_entries()
+            .context("failed to read quarantine")?;
+
+        exit::decor!("{bold}QUARANTINE{reset} {dim}({}){reset}", entries.len());
+        exit::decor!("{dim}───────────────────────────────{reset}");
+        if entries.is_empty() {
+            println!("  (nothing forgotten)");
+            return Ok(());
+        }
+        for entry in &entries {
+            println!(
+                "  {bold}{:>4}{reset}  {dim}{}{reset}  {}",
+                entry.id, entry.forgotten_at, entry.detail,
+            );
+            let purge = am_core::time::iso8601_to_unix(&entry.forgotten_at)
+                .map(|at| am_core::time::unix_to_iso8601(at + u64::from(days) * 86_400))
+                .filter(|_| days > 0)
+                .map(|at| format!(", purged by `am gc` after {at}"))
+                .unwrap_or_default();
+            println!(
+                "        {dim}restores {} episodes, {} neighborhoods, {} occurrences{purge}{reset}",
+                entry.episodes, entry.neighborhoods, entry.occurrences,
+            );
+        }
+        return Ok(());
+    }
+
+    let store = open_store(cli)?;
+    let ids: Vec<i64> = match id {
+        Some(id) => vec![id],
+        None => store
+            .store()
+            .quarantine_entries()
+            .context("failed to read quarantine")?
+            .into_iter()
+            .filter(|e| since.is_none_or(|since| e.forgotten_at.as_str() >= since))
+            .map(|e| e.id)
+            .collect(),
+    };
+    let report = store
+        .restore_forgotten(&ids)
+        .context("failed to restore forgotten memories")?;
+    if report.entries.is_empty() {
+        if let Some(id) = id {
+            anyhow::bail!("no quarantine entry {id}; see `am restore-forgotten --list`");
+        }
+        println!("Nothing to restore.");
+        return Ok(());
+    }
+    println!(
+        "{bold}Restored{reset} {} forget(s): {} episodes, {} neighborhoods, {} occurrences",
+        report.entries.len(),
+        report.episodes,
+        report.neighborhoods,
+        report.occurrences,
+    );
+    for entry in &report.entries {
+        println!("  {dim}{:>4}  {}{reset}", entry.id, entry.detail);
+    }
+    if report.skipped > 0 {
+        println!(
+            "  {dim}skipped {} rows whose ID is in use again or whose parent is gone{reset}",
+            report.skipped
+        );
+    }
+    Ok(())
+}
+
+fn cmd_undo(cli: &Cli, list: bool) -> Result<()> {
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    if list {
+        let store = open_store_readonly(cli)?;
+        let entries = store
+            .store()
+            .undo_entries()
+            .context("failed to read undo stack")?;
+
+        exit::decor!("{bold}UNDO POINTS{reset} {dim}({}){reset}", entries.len());
+        exit::decor!("{dim}───────────────────────────────{reset}");
+        if entries.is_empty() {
+            println!("  (nothing to undo)");
+            return Ok(());
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let next = if i == 0 { "  <- next" } else { "" };
+            println!(
+                "  {dim}{}{reset}  {bold}{:<6}{reset} {}{dim}{next}{reset}",
+                entry.at,
+                entry.kind.as_str(),
+                entry.detail,
+            );
+            println!(
+                "    {dim}restores {} episodes, {} neighborhoods, {} occurrences{reset}",
+                entry.episodes, entry.neighborhoods, entry.occurrences,
+            );
+        }
+        return Ok(());
+    }
+
+    let store = open_store(cli)?;
+    let Some(report) = store.undo_last().context("failed to undo")? else {
+        println!("Nothing to undo.");
+        return Ok(());
+    };
+    println!(
+        "{bold}Undid{reset} {} {}: {} episodes, {} neighborhoods, {} occurrences restored",
+        report.entry.kind.as_str(),
+        report.entry.detail,
+        report.episodes,
+        report.neighborhoods,
+        report.occurrences,
+    );
+    if report.skipped > 0 {
+        println!(
+            "  {dim}skipped {} rows whose ID is in use again or whose parent is gone{reset}",
+            report.skipped
+        );
+    }
+    Ok(())
+}
+
+fn cmd_buffer(cli: &Cli, action: &BufferAction, force: bool, json: bool) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        ..
+    } = colors::Colors::stdout();
+
+    match action {
+        BufferAction::List => {
+            let entries = store
+                .store()
+                .list_buffer()
+                .context("failed to read conversation buffer")?;
+            if json {
+                let exchanges = entries
+                    .iter()
+                    .map(|e| output::BufferedExchange {
+                        id: e.id,
+                        created_at: e.created_at.clone(),
+                        user: e.user_text.clone(),
+                        assistant: e.assistant_text.clone(),
+                    })
+                    .collect();
+                return output::print_json(output::Buffer { exchanges });
+            }
+            if entries.is_empty() {
+                exit::decor!("Conversation buffer is empty");
+                return Ok(());
+            }
+            exit::decor!(
+                "{bold}BUFFERED EXCHANGES{reset} {dim}({}){reset}",
+                entries.len()
+            );
+            exit::decor!("{dim}───────────────────────────────{reset}");
+            for e in &entries {
+                println!("  {cyan}#{}{reset} {dim}{}{reset}", e.id, e.created_at);
+                println!("    user:      {}", truncate_text(&e.user_text, 80));
+                println!("    assistant: {}", truncate_text(&e.assistant_text, 80));
+            }
+        }
+        BufferAction::Flush => {
+            let config = load_config()?;
+            let mut system = store.load_system().context("failed to load system")?;
+            let mut rng = SmallRng::from_os_rng();
+            let pending = store.store().buffer_count()?;
+            let options = am_server::FlushOptions {
+                detect_plans: config.plans.detect,
+                auto_salient: config.buffer.auto_salient,
+            };
+            match am_server::flush_orphaned_buffer(&store, &mut system, &mut rng, options)
+                .context("failed to save flushed episode")?
+            {
+                Some(flushed) => {
+                    println!(
+                        "{bold}Flushed{reset} {pending} exchange(s) into episode \"{}\"",
+                        flushed.name
+                    );
+                    if !flushed.auto_salient.is_empty() {
+                        println!(
+                            "  {dim}Marked {} stated decision(s) or preference(s) salient{reset}",
+                            flushed.auto_salient.len()
+                        );
+                    }
+                }
+                None => exit::decor!("Conversation buffer is empty"),
+            }
+        }
+        BufferAction::Clear => {
+            let pending = store.store().buffer_count()?;
+            if pending == 0 {
+                exit::decor!("Conversation buffer is empty");
+                return Ok(());
+            }
+            if !force {
+                eprint!("Discard {pending} buffered exchange(s)? [y/N] ");
+                std::io::stderr().flush()?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !answer.trim().eq_ignore_ascii_case("y") {
+                    println!("aborted");
+                    return Ok(());
+                }
+            }
+            let cleared = store
+                .store()
+                .clear_buffer()
+                .context("failed to clear conversation buffer")?;
+            println!("{bold}Discarded{reset} {cleared} buffered exchange(s)");
+        }
+    }
+    Ok(())
+}
+
+fn cmd_rename_episode(cli: &Cli, id: &str, name: &str) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors { bold, reset, .. } = colors::Colors::stdout();
+
+    let renamed = store
+        .store()
+        .rename_episode(id, name)
+        .context("failed to rename episode")?;
+    if renamed {
+        println!("{bold}Renamed{reset} episode {id} to \"{name}\"");
+    } else {
+        println!("Episode not found: {id}");
+    }
+    Ok(())
+}
+
+fn cmd_restore_episode(cli: &Cli, id: &str) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors { bold, reset, .. } = colors::Colors::stdout();
+
+    let restored = store
+        .store()
+        .restore_episode(id)
+        .context("failed to restore episode")?;
+    if restored {
+        println!("{bold}Restored{reset} episode {id}");
+    } else {
+        println!("Archived episode not found: {id}");
+    }
+    Ok(())
+}
+
+fn cmd_recover_conscious(cli: &Cli, from: Option<&std::path::Path>) -> Result<()> {
+    let config = load_config()?;
+    let store = open_store_with(cli, &config)?;
+    let colors::Colors { bold, reset, .. } = colors::Colors::stdout();
+
+    let live = config
+        .data_dir
+        .join(journal::journal_file_name(store.store().brain()));
+    let from = from.unwrap_or(&live);
+    let mut rng = SmallRng::from_os_rng();
+    let report = store
+        .recover_conscious(from, &mut rng)
+        .with_context(|| format!("failed to recover from {}", from.display()))?;
+
+    println!(
+        "{bold}Recovered{reset} {} conscious memories from {} ({} already present)",
+        report.restored.len(),
+        from.display(),
+        report.skipped
+    );
+    if report.forgotten > 0 {
+        println!("  {} entries left out: forgotten since", report.forgotten);
+    }
+    Ok(())
+}
+
+fn cmd_doctor(cli: &Cli, deep: bool, vocab_max_share: Option<f64>) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        yellow,
+        ..
+    } = colors::Colors::stdout();
+
+    let version = store
+        .store()
+        .get_metadata("schema_version")
+        .context("failed to read schema version")?
+        .unwrap_or_else(|| "?".to_string());
+    println!("{bold}schema{reset}     v{version}");
+
+    let mut problems = store
+        .store()
+        .integrity_problems()
+        .context("integrity check failed")?;
+    println!("{bold}integrity{reset}  {} problems", problems.len());
+
+    if let Some(note) = unsaved_marker_path().and_then(|p| std::fs::read_to_string(p).ok()) {
+        problems.push(format!(
+            "am serve failed to save changes; memory and store diverged at {}",
+            note.trim()
+        ));
+    }
+
+    if deep {
+        let system = store.load_system().context("failed to load system")?;
+        let repaired = store.store().repaired_records();
+        let violations = am_core::invariants::check_system(&system);
+        println!(
+            "{bold}invariants{reset} {} violations {dim}({} occurrences, {} neighborhoods checked; {repaired} rows repaired on load){reset}",
+            violations.len(),
+            system.n(),
+            system.total_neighborhoods(),
+        );
+        if repaired > 0 {
+            problems.push(format!(
+                "{repaired} stored rows have invalid geometry (repaired in memory only)"
+            ));
+        }
+        problems.extend(violations.iter().map(ToString::to_string));
+    }
+
+    let mut fixes = Vec::new();
+    if let Some(max_share) = vocab_max_share {
+        let stats = store
+            .store()
+            .word_stats(VOCAB_REPORT_WORDS)
+            .context("failed to aggregate word stats")?;
+        let frozen = store
+            .store()
+            .frozen_words()
+            .context("failed to read frozen words")?;
+        let report = vocab_report(&stats, max_share, &frozen);
+        print_vocab_report(&stats, &report, max_share);
+        for (episode, words) in &report.suspects {
+            problems.push(format!(
+                "episode \"{}\" contributes most neighborhoods for {} flooding words: {}",
+                episode.episode_name,
+                words.len(),
+                words.join(", ")
+            ));
+            fixes.push(format!(
+                "am forget --episode {}   {dim}# drop \"{}\"{reset}",
+                episode.episode_id, episode.episode_name
+            ));
+        }
+        if !fixes.is_empty() {
+            fixes.push(format!(
+                "am gc --archive   {dim}# or archive cold episodes instead{reset}"
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        exit::decor!("{dim}No problems found.{reset}");
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("  {yellow}!{reset} {problem}");
+    }
+    if !fixes.is_empty() {
+        exit::decor!("\n{bold}Suggested fixes:{reset}");
+        for fix in &fixes {
+            println!("  {fix}");
+        }
+    }
+    Err(exit::store_failure(format!(
+        "found {} problems",
+        problems.len()
+    )))
+}
+
+/// Words listed by `am doctor --vocab`.
+const VOCAB_REPORT_WORDS: usize = 20;
+
+/// Words over the share threshold, and the episodes that supply most of
+/// their neighborhoods.
+struct VocabReport<'a> {
+    flagged: Vec<&'a WordStat>,
+    /// Each suspect episode with the flagged words it dominates, in the
+    /// order the words were flagged.
+    suspects: Vec<(&'a WordSource, Vec<&'a str>)>,
+    /// Flagged words no single episode dominates and not yet frozen:
+    /// candidates for `am word freeze`.
+    freeze: Vec<&'a str>,
+}
+
+/// Flag words found in more than `max_share` of live neighborhoods. A
+/// flagged word whose neighborhoods mostly come from one episode points
+/// at that episode as the noise source; words spread evenly across many
+/// episodes are ordinary vocabulary that IDF already discounts, though
+/// they still pull on drift, so unless `frozen` they are suggested for
+/// freezing.
+fn vocab_report<'a>(stats: &'a VocabStats, max_share: f64, frozen: &[String]) -> VocabReport<'a> {
+    let flagged: Vec<&WordStat> = stats
+        .words
+        .iter()
+        .filter(|w| w.share(stats.total_neighborhoods) > max_share)
+        .collect();
+    let mut suspects: Vec<(&WordSource, Vec<&str>)> = Vec::new();
+    let mut freeze: Vec<&str> = Vec::new();
+    for word in &flagged {
+        let Some(top) = word.sources.first() else {
+            continue;
+        };
+        if top.neighborhood_count * 2 <= word.neighborhood_count {
+            if !frozen.contains(&word.word) {
+                freeze.push(&word.word);
+            }
+            continue;
+        }
+        match suspects
+            .iter_mut()
+            .find(|(ep, _)| ep.episode_id == top.episode_id)
+        {
+            Some((_, words)) => words.push(&word.word),
+            None => suspects.push((top, vec![&word.word])),
+        }
+    }
+    VocabReport {
+        flagged,
+        suspects,
+        freeze,
+    }
+}
+
+fn print_vocab_report(stats: &VocabStats, report: &VocabReport<'_>, max_share: f64) {
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        yellow,
+        ..
+    } = colors::Colors::stdout();
+    println!(
+        "{bold}vocab{reset}      {} of the top {} words in over {:.0}% of {} neighborhoods",
+        report.flagged.len(),
+        stats.words.len(),
+        max_share * 100.0,
+        stats.total_neighborhoods
+    );
+    for word in &stats.words {
+        let share = word.share(stats.total_neighborhoods);
+        let marker = if report.flagged.iter().any(|w| w.word == word.word) {
+            format!("{yellow}!{reset}")
         } else {
+            " ".to_string()
+        };
+        let top = word
+            .sources
+            .first()
+            .map(|s| {
+                format!(
+                    "{} {:.0}%",
+                    s.episode_name,
+                    100.0 * s.neighborhood_count as f64 / word.neighborhood_count as f64
+                )
+            })
+            .unwrap_or_default();
+        println!(
+            "  {marker} {:<20} {:>4.0}% {dim}({} neighborhoods, {} episodes; top: {top}){reset}",
+            word.word,
+            share * 100.0,
+            word.neighborhood_count,
+            word.sources.len(),
+        );
+    }
+    if !report.freeze.is_empty() {
+        exit::decor!(
+            "  {dim}Widespread but not dominated by one episode; freeze them to keep them out of drift:{reset}"
+        );
+        println!("    am word freeze {}", report.freeze.join(" "));
+    }
+}
+
+fn snapshot_dir() -> Result<PathBuf> {
+    Ok(serve_base_dir()
+        .context("cannot determine the data directory")?
+        .join("snapshots"))
+}
+
+fn cmd_snapshot(cli: &Cli, name: Option<&str>) -> Result<()> {
+    let name = name.map_or_else(snapshot::default_name, str::to_string);
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        anyhow::bail!("invalid snapshot name {name:?}");
+    }
+    let path = snapshot_dir()?.join(format!("{name}.json"));
+    if path.exists() {
+        anyhow::bail!("snapshot {name:?} already exists ({})", path.display());
+    }
+
+    let store = open_store(cli)?;
+    let print = snapshot::Fingerprint::take(store.store())?;
+    print.save(&path)?;
+
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+    println!(
+        "{bold}snapshot{reset} {name}: {} episodes, {} conscious, {} word weights",
+        print.episodes.len(),
+        print.conscious.len(),
+        print.word_weights.len()
+    );
+    println!("{dim}{}{reset}", path.display());
+    exit::decor!("{dim}Compare later with: am snapshot --diff {name}{reset}");
+    Ok(())
+}
+
+/// File format of `am export-positions`, from the path's extension.
+#[derive(Clone, Copy, PartialEq)]
+enum PositionsFormat {
+    Csv,
+    /// One JSON array, streamed row by row.
+    Json,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+fn cmd_export_positions(cli: &Cli, path: &std::path::Path, projection: Projection) -> Result<()> {
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => PositionsFormat::Csv,
+        Some("json") => PositionsFormat::Json,
+        Some("jsonl") => PositionsFormat::Jsonl,
+        _ => anyhow::bail!(
+            "positions path must end in .csv, .json, or .jsonl (got {})",
+            path.display()
+        ),
+    };
+    let store = open_store_readonly(cli)?;
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    let written = write_positions(&store, std::io::BufWriter::new(file), format, projection)
+        .with_context(|| format!("failed to export positions to {}", path.display()));
+    // A failed walk would leave a truncated file that still parses as CSV
+    let rows = match written {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = std::fs::remove_file(path);
+            return Err(e);
+        }
+    };
+
+    exit::note!("exported {rows} positions to {}", path.display());
+    Ok(())
+}
+
+/// Stream every occurrence position of `store` into `out` as `format`.
+/// Returns how many rows were written.
+fn write_positions(
+    store: &BrainStore,
+    mut out: impl Write,
+    format: PositionsFormat,
+    projection: Projection,
+) -> Result<u64> {
+    match format {
+        PositionsFormat::Csv => writeln!(out, "id,word,episode,activation,drift,x,y,z")?,
+        PositionsFormat::Json => write!(out, "[")?,
+        PositionsFormat::Jsonl => {}
+    }
+    let mut first = true;
+    let rows = store.store().for_each_position(|row| {
+        let [x, y, z] = match projection {
+            Projection::Stereographic => row.position.stereographic_project(),
+            Projection::DropW => [row.position.x, row.position.y, row.position.z],
+        };
+        if format == PositionsFormat::Csv {
+            writeln!(
+                out,
+                "{},{},{},{},{},{x},{y},{z}",
+                row.id,
+                csv_field(&row.word),
+                csv_field(&row.episode),
+                row.activation_count,
+                row.drift,
+            )?;
+            return Ok(());
+        }
+        let record = serde_json::json!({
+            "id": row.id,
+            "word": row.word,
+            "episode": row.episode,
+            "activation": row.activation_count,
+            "drift": row.drift,
+            "x": x,
+            "y": y,
+            "z": z,
+        });
+        if format == PositionsFormat::Json {
+            write!(out, "{}\n  ", if first { "" } else { "," })?;
+        }
+        serde_json::to_writer(&mut out, &record).map_err(std::io::Error::from)?;
+        if format == PositionsFormat::Jsonl {
+            writeln!(out)?;
+        }
+        first = false;
+        Ok(())
+    })?;
+    if format == PositionsFormat::Json {
+        writeln!(out, "{}]", if first { "" } else { "\n" })?;
+    }
+    out.flush()?;
+    Ok(rows)
+}
+
+/// `value` as one CSV field, quoted when it holds a comma, quote, or line
+/// break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+fn cmd_schema(output: output::JsonOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&output.schema())?);
+    Ok(())
+}
+
+fn cmd_snapshot_diff(cli: &Cli, earlier: &str, threshold_pct: f64, json: bool) -> Result<()> {
+    if !threshold_pct.is_finite() || threshold_pct < 0.0 {
+        anyhow::bail!("--threshold must be a percentage >= 0, got {threshold_pct}");
+    }
+    let before = snapshot::Fingerprint::load(&snapshot::snapshot_path(&snapshot_dir()?, earlier))?;
+    let store = open_store(cli)?;
+    let now = snapshot::Fingerprint::take(store.store())?;
+    if before.brain != now.brain {
+        anyhow::bail!(
+            "snapshot {earlier:?} is of brain {:?}, not {:?}",
+            before.brain,
+            now.brain
+        );
+    }
+    let diff = before.diff(&now, threshold_pct / 100.0);
+
+    if json {
+        let episode = |(id, e): &(&str, &snapshot::EpisodePrint)| output::SnapshotEpisode {
+            id: id.to_string(),
+            name: e.name.clone(),
+            occurrences: e.occurrences,
+        };
+        let conscious = |(id, c): &(&str, &snapshot::ConsciousPrint)| output::SnapshotConscious {
+            id: id.to_string(),
+            preview: c.preview.clone(),
+        };
+        return output::print_json(output::SnapshotDiff {
+            since: before.taken_at.clone(),
+            episodes: output::EpisodeChanges {
+                added: diff.added_episodes.iter().map(episode).collect(),
+                removed: diff.removed_episodes.iter().map(episode).collect(),
+                changed: diff
+                    .changed_episodes
+                    .iter()
+                    .map(|c| output::EpisodeChange {
+                        id: c.id.to_string(),
+                        name: c.after.name.clone(),
+                        occurrences_before: c.before.occurrences,
+                        occurrences_after: c.after.occurrences,
+                        archived_before: c.before.archived,
+                        archived_after: c.after.archived,
+                    })
+                    .collect(),
+            },
+            conscious: output::ConsciousChanges {
+                added: diff.added_conscious.iter().map(conscious).collect(),
+                removed: diff.removed_conscious.iter().map(conscious).collect(),
+                edited: diff.edited_conscious.iter().map(conscious).collect(),
+            },
+            weights: diff
+                .weights
+                .iter()
+                .map(|w| output::WeightMove {
+                    word: w.word.to_string(),
+                    before: w.before,
+                    after: w.after,
+                })
+                .collect(),
+            words_entered: diff.words_entered,
+            words_left: diff.words_left,
+        });
+    }
+
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        yellow,
+    } = colors::Colors::stdout();
+    println!(
+        "{bold}since{reset} {} {dim}({earlier}){reset}",
+        before.taken_at
+    );
+    if diff.is_empty() {
+        exit::decor!("{dim}No changes.{reset}");
+        return Ok(());
+    }
+    for (id, e) in &diff.added_episodes {
+        println!(
+            "  {cyan}+ episode{reset} {} {dim}({} occurrences, {}){reset}",
+            e.name,
+            e.occurrences,
+            safe_prefix(id, 8)
+        );
+    }
+    for (id, e) in &diff.removed_episodes {
+        println!(
+            "  {yellow}- episode{reset} {} {dim}({} occurrences, {}){reset}",
+            e.name,
+            e.occurrences,
+            safe_prefix(id, 8)
+        );
+    }
+    for c in &diff.changed_episodes {
+        let archive = match (c.before.archived, c.after.archived) {
+            (false, true) => ", archived",
+            (true, false) => ", restored",
+            _ => "",
+        };
+        println!(
+            "  {yellow}~ episode{reset} {} {dim}({} -> {} occurrences{archive}){reset}",
+            c.after.name, c.before.occurrences, c.after.occurrences
+        );
+    }
+    for (id, c) in &diff.added_conscious {
+        println!(
+            "  {cyan}+ conscious{reset} {} {dim}{}{reset}",
+            c.preview,
+            safe_prefix(id, 8)
+        );
+    }
+    for (id, c) in &diff.removed_conscious {
+        println!(
+            "  {yellow}- conscious{reset} {} {dim}{}{reset}",
+            c.preview,
+            safe_prefix(id, 8)
+        );
+    }
+    for (id, c) in &diff.edited_conscious {
+        println!(
+            "  {yellow}~ conscious{reset} {} {dim}{}{reset}",
+            c.preview,
+            safe_prefix(id, 8)
+        );
+    }
+    if !diff.weights.is_empty() {
+        println!(
+            "{bold}weights{reset} {} moved more than {threshold_pct}%",
+            diff.weights.len()
+        );
+        for w in &diff.weights {
             println!(
-                "{bold}Forgot{reset} \"{word}\": {removed_occs} occurrences, \
-                 {removed_nbhds} neighborhoods, {removed_eps} episodes removed"
+                "  {:<20} {:.4} -> {:.4} {dim}({:+.0}%){reset}",
+                w.word,
+                w.before,
+                w.after,
+                w.relative() * 100.0
             );
         }
+    }
+    if diff.words_entered + diff.words_left > 0 {
+        println!(
+            "{dim}{} words entered and {} left the top {}{reset}",
+            diff.words_entered,
+            diff.words_left,
+            snapshot::FINGERPRINT_WORDS
+        );
+    }
+    Ok(())
+}
+
+fn cmd_migrate(cli: &Cli, migration: &Migration, apply: bool) -> Result<()> {
+    if let Migration::CompressText = migration {
+        return migrate_compress_text(cli, apply);
+    }
+    let store = open_store(cli)?;
+    let mut system = store.load_system().context("failed to load system")?;
+    let mut rng = SmallRng::from_os_rng();
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    let report = am_core::sanitize::scrub_system(&mut system, &mut rng);
+    let verb = if apply { "cleaned" } else { "would clean" };
+    println!(
+        "{bold}clean-text{reset} {verb} {} neighborhoods ({} retokenized)",
+        report.cleaned.len(),
+        report.retokenized.len()
+    );
+
+    if report.is_empty() {
+        exit::decor!("{dim}Nothing to clean.{reset}");
+    } else if apply {
+        store
+            .save_system(&system)
+            .context("failed to save system")?;
     } else {
-        anyhow::bail!("specify a term, --episode <id>, or --conscious <id> to forget");
+        exit::decor!("\n{dim}No changes made. Pass --apply to write them.{reset}");
     }
+    Ok(())
+}
+
+fn migrate_compress_text(cli: &Cli, apply: bool) -> Result<()> {
+    let store = open_store(cli)?;
+    let colors::Colors {
+        bold, dim, reset, ..
+    } = colors::Colors::stdout();
+
+    let before = store.db_size();
+    let compaction = store
+        .store()
+        .compress_text(apply)
+        .context("failed to compress text")?;
+    let verb = if apply {
+        "compressed"
+    } else {
+        "would compress"
+    };
+    println!(
+        "{bold}compress-text{reset} {verb} {} neighborhoods ({} -> {})",
+        compaction.neighborhoods,
+        format_bytes(compaction.bytes_before),
+        format_bytes(compaction.bytes_after),
+    );
 
+    if compaction.neighborhoods == 0 {
+        exit::decor!("{dim}Nothing to compress.{reset}");
+    } else if apply {
+        println!(
+            "db_size: {} -> {}",
+            format_bytes(before),
+            format_bytes(store.db_size())
+        );
+    } else {
+        exit::decor!("\n{dim}No changes made. Pass --apply to write them.{reset}");
+    }
     Ok(())
 }
 
 fn cmd_init(global: bool, force: bool) -> Result<()> {
     let dir = if global {
-        am_store::project::default_base_dir().context("cannot determine global config directory")?
+        am_store::config::global_config_dir().context("cannot determine global config directory")?
     } else {
         std::env::current_dir().context("failed to get current directory")?
     };
-    let config_path = dir.join(".am.config.toml");
+    let config_path = dir.join(am_store::config::CONFIG_FILE_NAME);
 
     // Ensure the target directory exists (relevant for --global)
     std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
@@ -1096,35 +4278,211 @@ fn cmd_init(global: bool, force: bool) -> Result<()> {
     std::fs::write(&config_path, &content)
         .with_context(|| format!("failed to write {}", config_path.display()))?;
 
-    println!("wrote {}", config_path.display());
+    exit::note!("wrote {}", config_path.display());
+    Ok(())
+}
+
+fn cmd_config(
+    action: &ConfigAction,
+    key: Option<&str>,
+    value: Option<&str>,
+    global: bool,
+) -> Result<()> {
+    let colors::Colors {
+        bold,
+        dim,
+        reset,
+        cyan,
+        yellow,
+    } = colors::Colors::stdout();
+
+    match action {
+        ConfigAction::Show => {
+            let report = am_store::config::explain().context("invalid configuration")?;
+            match &report.path {
+                Some(path) => println!("{bold}config file:{reset} {}", path.display()),
+                None => exit::decor!("{dim}no config file; defaults and environment only{reset}"),
+            }
+            for problem in &report.problems {
+                println!("{yellow}{problem}{reset}");
+            }
+            let entries: Vec<_> = report
+                .entries
+                .iter()
+                .filter(|e| key.is_none_or(|prefix| e.key.starts_with(prefix)))
+                .collect();
+            if entries.is_empty() {
+                anyhow::bail!("no config key starts with {:?}", key.unwrap_or_default());
+            }
+            let width = entries
+                .iter()
+                .map(|e| e.value.chars().count())
+                .max()
+                .unwrap_or(0)
+                .min(40);
+            exit::decor!();
+            for entry in entries {
+                println!(
+                    "  {cyan}{:<30}{reset} {:<width$} {dim}{}{reset}",
+                    entry.key, entry.value, entry.source
+                );
+            }
+        }
+        ConfigAction::Set => {
+            let (Some(key), Some(value)) = (key, value) else {
+                anyhow::bail!("usage: am config set <key> <value> [--global]");
+            };
+            let in_effect = am_store::config::config_file_path();
+            let path = match (&in_effect, global) {
+                (Some(path), false) => path.clone(),
+                _ => am_store::config::global_config_dir()
+                    .context("cannot determine global config directory")?
+                    .join(am_store::config::CONFIG_FILE_NAME),
+            };
+            am_store::config::set_value(&path, key, value)?;
+            println!("{bold}set{reset} {key} in {}", path.display());
+
+            if in_effect.as_ref().is_some_and(|p| *p != path) {
+                println!(
+                    "{dim}not in effect here: {} is read first{reset}",
+                    in_effect.unwrap_or_default().display()
+                );
+            } else if let Ok(report) = am_store::config::explain()
+                && let Some(entry) = report.entries.iter().find(|e| e.key == key)
+                && let am_store::config::ConfigSource::Env(var) = entry.source
+            {
+                println!("{dim}{var} is set and overrides it{reset}");
+            }
+        }
+    }
     Ok(())
 }
 
-fn cmd_export(cli: &Cli, path: &std::path::Path) -> Result<()> {
+fn cmd_export(cli: &Cli, path: &std::path::Path, include_epochs: bool, full: bool) -> Result<()> {
     if path.extension().is_none_or(|ext| ext != "json") {
         anyhow::bail!("export path must end in .json (got {})", path.display());
     }
     let store = open_store(cli)?;
-    let system = store.load_system().context("failed to load system")?;
+    let mut system = store.load_system().context("failed to load system")?;
+    if include_epochs {
+        let epochs = store
+            .store()
+            .load_epoch_episodes()
+            .context("failed to load epoch files")?;
+        system.episodes.extend(epochs);
+        system.mark_dirty();
+    }
 
-    let json = export_json(&system).context("failed to serialize state")?;
+    let json = if full {
+        store
+            .store()
+            .export_archive_string(&system)
+            .context("failed to serialize archive")?
+    } else {
+        export_json(&system).context("failed to serialize state")?
+    };
     std::fs::write(path, &json).with_context(|| format!("failed to write {}", path.display()))?;
 
-    println!("exported to {}", path.display());
+    exit::note!("exported to {}", path.display());
     Ok(())
 }
 
-fn cmd_import(cli: &Cli, path: &std::path::Path) -> Result<()> {
+fn cmd_import(
+    cli: &Cli,
+    path: &std::path::Path,
+    merge: bool,
+    full: bool,
+    options: ImportOptions,
+) -> Result<()> {
     let store = open_store(cli)?;
-    store
-        .import_json_file(path)
-        .context("failed to import JSON")?;
+    let mode = if merge {
+        ImportMode::Merge
+    } else {
+        ImportMode::Replace
+    };
+
+    let (summary, restored, total) = if full {
+        // The archive wrapper is parsed whole; its export is still
+        // imported episode by episode.
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let total = count_archive_episodes(&json)
+            .with_context(|| format!("invalid archive file {}", path.display()))?;
+        let step = (total / 20).max(1);
+        let (summary, state) = store
+            .store()
+            .import_archive_str(&json, mode, options, |done| {
+                if !exit::quiet() && (done % step == 0 || done == total) {
+                    eprint!("\rimported {done}/{total} episodes");
+                }
+            })
+            .context("failed to import archive")?;
+        (summary, Some(state), total)
+    } else {
+        // Cheap first pass to size the progress counter; episodes are skipped
+        // without being materialized.
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let total = count_export_episodes(std::io::BufReader::new(file))
+            .with_context(|| format!("invalid export file {}", path.display()))?;
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let step = (total / 20).max(1);
+        let summary = store
+            .store()
+            .import_json_reader_with(file, mode, options, |done| {
+                if !exit::quiet() && (done % step == 0 || done == total) {
+                    eprint!("\rimported {done}/{total} episodes");
+                }
+            })
+            .context("failed to import JSON")?;
+        (summary, None, total)
+    };
+    if total > 0 {
+        exit::note!();
+    }
 
     let system = store
         .load_system()
         .context("failed to load system after import")?;
 
-    println!(
+    if summary.skipped_episodes > 0 {
+        exit::note!(
+            "skipped {} episodes already in memory",
+            summary.skipped_episodes
+        );
+    }
+    let report = &summary.report;
+    if report.repaired_ids > 0 {
+        eprintln!(
+            "warning: regenerated {} missing or invalid IDs (use --strict-ids to reject such files)",
+            report.repaired_ids
+        );
+    }
+    if report.clamped_timestamps > 0 {
+        eprintln!(
+            "warning: {} episodes were dated in the future (the exporting machine's clock ran ahead); reset them to now",
+            report.clamped_timestamps
+        );
+    }
+    print_import_issues("warning", &report.warnings);
+    print_import_issues("skipped", &report.errors);
+    if report.dropped_episodes + report.dropped_neighborhoods > 0 {
+        eprintln!(
+            "skipped {} episodes and {} neighborhoods that failed validation",
+            report.dropped_episodes, report.dropped_neighborhoods
+        );
+    }
+    if let Some(state) = restored {
+        exit::note!(
+            "restored {} buffered exchanges, {} metadata keys, {} frozen words, {} word biases",
+            state.buffer,
+            state.metadata,
+            state.frozen_words,
+            state.word_biases
+        );
+    }
+    exit::note!(
         "imported from {}. N={}, episodes={}, conscious={}",
         path.display(),
         system.n(),
@@ -1133,3 +4491,18 @@ fn cmd_import(cli: &Cli, path: &std::path::Path) -> Result<()> {
     );
     Ok(())
 }
+
+/// Issues listed on stderr before the rest are counted.
+const MAX_PRINTED_ISSUES: usize = 20;
+
+fn print_import_issues(label: &str, issues: &[ImportIssue]) {
+    for issue in issues.iter().take(MAX_PRINTED_ISSUES) {
+        eprintln!("{label}: {issue}");
+    }
+    if issues.len() > MAX_PRINTED_ISSUES {
+        eprintln!(
+            "{label}: ... and {} more",
+            issues.len() - MAX_PRINTED_ISSUES
+        );
+    }
+}
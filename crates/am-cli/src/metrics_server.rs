@@ -0,0 +1,97 @@
+//! Prometheus scrape endpoint for `am serve --metrics-addr`.
+//!
+//! `GET /metrics` returns [`AmServer::metrics_text`] for the brain `am
+//! serve` opened: tool call counts and latencies, save durations, GC runs,
+//! and memory, database, and buffer gauges. The listener runs beside the
+//! stdio loop whether or not an MCP client ever connects, and stops when
+//! the server does. It serves counts only, never memory content, so it
+//! takes no token.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use am_server::AmServer;
+use am_store::project::BrainStore;
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::{Router, routing::get};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Content type of the Prometheus text exposition format.
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub(crate) async fn bind_metrics(addr: SocketAddr) -> Result<TcpListener> {
+    tracing::info!(%addr, "binding metrics listener");
+    TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind metrics to {addr} (port may be in use)"))
+}
+
+/// Serve `GET /metrics` on `listener` until `cancel` fires.
+pub(crate) async fn serve_metrics(
+    listener: TcpListener,
+    server: Arc<AmServer<BrainStore>>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(handle_metrics))
+        .with_state(server);
+
+    let addr = listener.local_addr()?;
+    tracing::info!(%addr, "metrics listening");
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+        .context("metrics server error")
+}
+
+async fn handle_metrics(State(server): State<Arc<AmServer<BrainStore>>>) -> impl IntoResponse {
+    // Rendering waits for a running tool call, so keep it off the workers
+    let text = tokio::task::spawn_blocking(move || server.metrics_text())
+        .await
+        .unwrap_or_default();
+    ([(CONTENT_TYPE, TEXT_FORMAT)], text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_text() {
+        let server = Arc::new(AmServer::new(BrainStore::open_in_memory().unwrap()).unwrap());
+        server
+            .dispatch_tool(
+                "am_ingest",
+                &serde_json::json!({"text": "Canary rollouts restore pods.", "name": "deploy"}),
+            )
+            .unwrap();
+
+        let listener = bind_metrics(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+        let cancel = CancellationToken::new();
+        let serving = tokio::spawn(serve_metrics(listener, server, cancel.clone()));
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(
+            response.headers()[CONTENT_TYPE.as_str()]
+                .to_str()
+                .unwrap()
+                .starts_with("text/plain; version=0.0.4")
+        );
+        let body = response.text().await.unwrap();
+        assert!(body.contains("am_tool_calls_total{tool=\"am_ingest\",outcome=\"ok\"} 1\n"));
+        assert!(body.contains("am_episodes 1\n"));
+
+        // Cancelling stops the listener
+        cancel.cancel();
+        serving.await.unwrap().unwrap();
+        assert!(reqwest::get(&url).await.is_err());
+    }
+}
@@ -0,0 +1,459 @@
+//! Typed `--json` payloads.
+//!
+//! Every command that prints JSON builds one of these structs and prints it
+//! through [`print_json`], which adds [`SCHEMA_VERSION`]. The structs derive
+//! `JsonSchema`, so `am schema <output>` publishes exactly the shape the
+//! command prints. Adding a field is compatible; removing or renaming one,
+//! or changing what it means, bumps [`SCHEMA_VERSION`].
+
+use anyhow::Result;
+use clap::ValueEnum;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::Serialize;
+
+/// Version of every `--json` payload shape, reported as `schema_version`.
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// A payload with its `schema_version`.
+#[derive(Serialize, JsonSchema)]
+struct Versioned<T> {
+    /// Bumped when a field is removed, renamed, or changes meaning.
+    schema_version: u32,
+    #[serde(flatten)]
+    payload: T,
+}
+
+/// Print `payload` as pretty JSON with its `schema_version`.
+pub(crate) fn print_json<T: Serialize>(payload: T) -> Result<()> {
+    let out = Versioned {
+        schema_version: SCHEMA_VERSION,
+        payload,
+    };
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+/// Outputs `am schema` can describe, named after the command that prints
+/// them.
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum JsonOutput {
+    /// am knows --json
+    Knows,
+    /// am decisions --json
+    Decisions,
+    /// am inspect --json
+    Inspect,
+    /// am inspect conscious --json
+    InspectConscious,
+    /// am inspect episodes --json
+    InspectEpisodes,
+    /// am inspect episode <id> --json
+    InspectEpisode,
+    /// am inspect neighborhoods --json
+    InspectNeighborhoods,
+    /// am weight --json
+    Weight,
+    /// am word --list --json
+    Word,
+    /// am bias list --json
+    Bias,
+    /// am eval --json
+    Eval,
+    /// am buffer list --json
+    Buffer,
+    /// am log --json
+    Log,
+    /// am snapshot --diff --json
+    Snapshot,
+    /// am forget --query --json
+    Forget,
+}
+
+impl JsonOutput {
+    /// JSON Schema of this output, `schema_version` included.
+    pub fn schema(self) -> Schema {
+        match self {
+            Self::Knows => schema_for!(Versioned<Knows>),
+            Self::Decisions => schema_for!(Versioned<Decisions>),
+            Self::Inspect => schema_for!(Versioned<InspectOverview>),
+            Self::InspectConscious => schema_for!(Versioned<Page<ConsciousItem>>),
+            Self::InspectEpisodes => schema_for!(Versioned<Page<EpisodeItem>>),
+            Self::InspectEpisode => schema_for!(Versioned<EpisodeDetail>),
+            Self::InspectNeighborhoods => schema_for!(Versioned<Page<NeighborhoodItem>>),
+            Self::Weight => schema_for!(Versioned<Weights>),
+            Self::Word => schema_for!(Versioned<FrozenWords>),
+            Self::Bias => schema_for!(Versioned<WordBiases>),
+            Self::Eval => schema_for!(Versioned<am_core::eval::EvalReport>),
+            Self::Buffer => schema_for!(Versioned<Buffer>),
+            Self::Log => schema_for!(Versioned<AuditLog>),
+            Self::Snapshot => schema_for!(Versioned<SnapshotDiff>),
+            Self::Forget => schema_for!(Versioned<ForgetPlan>),
+        }
+    }
+}
+
+// --- knows ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Knows {
+    /// Whether any neighborhood would be a recall candidate.
+    pub known: bool,
+    pub candidates: Candidates,
+    /// Best candidate score, `null` without candidates.
+    pub top_score: Option<f64>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Candidates {
+    pub conscious: usize,
+    pub subconscious: usize,
+    pub novel: usize,
+    /// Distinct query words found in memory.
+    pub activated_words: usize,
+    /// Candidates dropped by the recall cutoffs.
+    pub filtered: usize,
+}
+
+// --- decisions ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Decisions {
+    /// Newest first.
+    pub decisions: Vec<Decision>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Decision {
+    pub id: String,
+    pub text: String,
+    pub created_at: Option<String>,
+    pub superseded: bool,
+    pub superseded_by: Option<String>,
+    pub actor: Option<String>,
+}
+
+// --- inspect ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct InspectOverview {
+    pub total_occurrences: u64,
+    pub unique_words: u64,
+    /// Live subconscious episodes.
+    pub episodes: u64,
+    pub conscious_memories: u64,
+    pub db_size_bytes: u64,
+    pub activation: ActivationSummary,
+    pub top_words: Vec<TopWord>,
+    pub conscious: Vec<ConsciousSummary>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ActivationSummary {
+    pub mean: f64,
+    pub max: u32,
+    /// Occurrences never activated.
+    pub zero_count: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TopWord {
+    pub word: String,
+    pub activation: u32,
+    pub occurrences: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ConsciousSummary {
+    pub id: String,
+    /// Source text, truncated to 200 characters.
+    pub text: String,
+    pub occurrences: u64,
+    pub activation: u64,
+    pub actor: Option<String>,
+}
+
+/// One page of an inspect listing.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Page<T> {
+    /// Matching items across all pages.
+    pub total: u64,
+    pub offset: usize,
+    pub items: Vec<T>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ConsciousItem {
+    pub id: String,
+    pub text: String,
+    /// `memory`, `decision`, `preference`, `plan`, ...
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub created_at: Option<String>,
+    pub occurrences: u64,
+    pub activation: u64,
+    /// Estimated LLM tokens of the text.
+    pub tokens: u64,
+    pub actor: Option<String>,
+    /// Project the memory is tagged for; `null` applies to every project.
+    pub project: Option<String>,
+    /// Shared by the chunks of one long salient text; `null` for a memory
+    /// stored whole.
+    pub group_id: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeItem {
+    pub id: String,
+    pub name: String,
+    pub timestamp: String,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+    pub activation: u64,
+    pub tokens: u64,
+    pub actor: Option<String>,
+}
+
+/// One episode in full, with a page of its neighborhoods.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeDetail {
+    pub id: String,
+    pub name: String,
+    pub is_conscious: bool,
+    pub archived: bool,
+    pub timestamp: String,
+    pub actor: Option<String>,
+    /// Totals over the whole episode, not just the listed page.
+    pub stats: EpisodeStats,
+    /// Neighborhoods in insertion order.
+    pub neighborhoods: Page<EpisodeNeighborhood>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeStats {
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+    /// Distinct words across the episode.
+    pub unique_words: u64,
+    pub total_activation: u64,
+    /// Mean activation per occurrence, 0 for an empty episode.
+    pub mean_activation: f64,
+    pub max_activation: u32,
+    pub tokens: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeNeighborhood {
+    pub id: String,
+    pub source_text: String,
+    /// `memory`, `decision`, `preference`, `plan`, ...
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub created_at: Option<String>,
+    pub total_activation: u64,
+    pub tokens: u64,
+    pub actor: Option<String>,
+    /// Project the memory is tagged for; `null` applies to every project.
+    pub project: Option<String>,
+    /// Decayed sum of `am_feedback` signals; 0 is neutral.
+    pub feedback_score: f64,
+    /// One entry per occurrence, in stored order.
+    pub words: Vec<WordActivation>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WordActivation {
+    pub word: String,
+    pub activation: u32,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct NeighborhoodItem {
+    pub id: String,
+    pub source_text: String,
+    pub episode: String,
+    pub is_conscious: bool,
+    pub occurrences: u64,
+    pub total_activation: u64,
+    pub max_activation: u32,
+    pub drift: Drift,
+    pub tokens: u64,
+    pub actor: Option<String>,
+    /// Decayed sum of `am_feedback` signals (+1 boost, -1 demote); 0 is
+    /// neutral. Recall multiplies the neighborhood's score by 1.25^score.
+    pub feedback_score: f64,
+    pub created_at: Option<String>,
+}
+
+/// Angles in radians that occurrences moved since they were placed.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Drift {
+    pub mean: f64,
+    pub max: f64,
+}
+
+// --- weight / word ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Weights {
+    /// In the order given on the command line, lowercased.
+    pub words: Vec<WordWeight>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WordWeight {
+    pub word: String,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+    /// Clamped IDF weight times `bias`, as recall scores the word.
+    pub weight: f64,
+    /// Multiplier set with `am bias`, 1.0 when the word has none.
+    pub bias: f64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct FrozenWords {
+    /// Words frozen out of drift, alphabetically.
+    pub words: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WordBiases {
+    /// Biased words, alphabetically.
+    pub biases: Vec<WordBias>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WordBias {
+    pub word: String,
+    /// Factor on the word's IDF weight, 0 (muted) to 10.
+    pub multiplier: f64,
+}
+
+// --- buffer ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Buffer {
+    /// Pending exchanges, oldest first.
+    pub exchanges: Vec<BufferedExchange>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BufferedExchange {
+    pub id: i64,
+    pub created_at: String,
+    pub user: String,
+    pub assistant: String,
+}
+
+// --- log ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AuditLog {
+    /// Newest first.
+    pub entries: Vec<AuditItem>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AuditItem {
+    pub id: i64,
+    /// ISO-8601 UTC time the change committed.
+    pub at: String,
+    /// salient, ingest, buffer, feedback, forget, gc, or import.
+    pub kind: String,
+    /// What changed, counted per kind: memories stored, occurrences
+    /// removed, episodes imported, ...
+    pub count: u64,
+    pub project: Option<String>,
+    pub actor: Option<String>,
+    pub detail: String,
+}
+
+// --- snapshot ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SnapshotDiff {
+    /// When the earlier snapshot was taken.
+    pub since: String,
+    pub episodes: EpisodeChanges,
+    pub conscious: ConsciousChanges,
+    /// Weights that moved by more than the threshold, largest move first.
+    pub weights: Vec<WeightMove>,
+    /// Words that entered the recorded top words.
+    pub words_entered: usize,
+    /// Words that left the recorded top words.
+    pub words_left: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeChanges {
+    pub added: Vec<SnapshotEpisode>,
+    pub removed: Vec<SnapshotEpisode>,
+    pub changed: Vec<EpisodeChange>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SnapshotEpisode {
+    pub id: String,
+    pub name: String,
+    pub occurrences: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct EpisodeChange {
+    pub id: String,
+    pub name: String,
+    pub occurrences_before: u64,
+    pub occurrences_after: u64,
+    pub archived_before: bool,
+    pub archived_after: bool,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ConsciousChanges {
+    pub added: Vec<SnapshotConscious>,
+    pub removed: Vec<SnapshotConscious>,
+    pub edited: Vec<SnapshotConscious>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SnapshotConscious {
+    pub id: String,
+    pub preview: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WeightMove {
+    pub word: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+// --- forget ---
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ForgetPlan {
+    pub query: String,
+    /// Best first; what `--yes` forgets.
+    pub matches: Vec<ForgetMatch>,
+    /// Conscious matches left out for lack of `--include-conscious`.
+    pub conscious_skipped: usize,
+    /// What was removed, `null` unless `--yes` forgot the matches.
+    pub forgotten: Option<ForgetCounts>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ForgetMatch {
+    pub id: String,
+    /// `conscious`, `subconscious`, or `novel`.
+    pub category: String,
+    pub episode: String,
+    pub score: f64,
+    pub preview: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ForgetCounts {
+    pub occurrences: u64,
+    pub neighborhoods: u64,
+    pub episodes: u64,
+}
@@ -0,0 +1,190 @@
+//! Advisory pidfile for `am serve`.
+//!
+//! The pidfile only tells a second server (and `am doctor`) that another
+//! one is running; SQLite's busy timeout arbitrates the actual writes. A
+//! pidfile naming a dead process is stale and gets replaced.
+
+use std::path::Path;
+use std::time::Duration;
+
+/// Attempts at a rename or delete before giving up. Windows refuses both
+/// while another process (an antivirus scan, a second server reading the
+/// file) has the file open, which usually clears within milliseconds.
+const FILE_RETRIES: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(20);
+
+/// Check for an existing pidfile and log accordingly, then write our own.
+/// Returns whether the pidfile now names this process.
+pub fn acquire(path: &Path) -> bool {
+    let own_pid = std::process::id();
+    if let Some(pid) = read_pid(path)
+        && pid != own_pid
+    {
+        if is_process_alive(pid) {
+            tracing::warn!(
+                "another am serve (PID {pid}) is running - coexisting with busy_timeout"
+            );
+        } else {
+            tracing::info!("cleaned up stale pidfile (PID {pid} is dead)");
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match write_pid(path, own_pid) {
+        Ok(()) => {
+            tracing::info!("wrote pidfile: {}", path.display());
+            true
+        }
+        Err(e) => {
+            tracing::warn!("failed to write pidfile: {e}");
+            false
+        }
+    }
+}
+
+/// Remove the pidfile if it still names this process. A server started
+/// after us may have replaced it, and its pidfile is not ours to delete.
+pub fn release(path: &Path) {
+    match read_pid(path) {
+        Some(pid) if pid == std::process::id() => {}
+        Some(pid) => {
+            tracing::info!("leaving pidfile of another am serve (PID {pid})");
+            return;
+        }
+        None => return,
+    }
+    match retry(|| std::fs::remove_file(path)) {
+        Ok(()) => tracing::info!("removed pidfile: {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("failed to remove pidfile {}: {e}", path.display()),
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Write through a temporary file and rename it into place, so a reader
+/// never sees a half-written PID.
+fn write_pid(path: &Path, pid: u32) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("pid.{pid}.tmp"));
+    std::fs::write(&tmp, pid.to_string())?;
+    retry(|| std::fs::rename(&tmp, path)).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// Run `op` until it succeeds or fails for a reason other than the file
+/// being in use, up to [`FILE_RETRIES`] times.
+fn retry(mut op: impl FnMut() -> std::io::Result<()>) -> std::io::Result<()> {
+    for _ in 1..FILE_RETRIES {
+        match op() {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => std::thread::sleep(RETRY_DELAY),
+            result => return result,
+        }
+    }
+    op()
+}
+
+/// Whether a process with this PID exists.
+#[cfg(unix)]
+pub fn is_process_alive(pid: u32) -> bool {
+    // 0 and out-of-range values would address process groups
+    let Ok(pid @ 1..) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // kill(pid, 0) checks existence without sending a signal. EPERM means
+    // the process exists but belongs to another user.
+    let exists = unsafe { libc::kill(pid, 0) } == 0;
+    exists || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Whether a process with this PID exists and has not exited.
+#[cfg(windows)]
+pub fn is_process_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_ACCESS_DENIED, GetLastError, STILL_ACTIVE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: the handle is checked for null before use and closed once.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            // A process we may not query still exists
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut exit_code = 0u32;
+        let queried = GetExitCodeProcess(handle, &raw mut exit_code) != 0;
+        CloseHandle(handle);
+        // An exited process keeps its object while handles to it are open
+        queried && exit_code == STILL_ACTIVE as u32
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn is_process_alive(_pid: u32) -> bool {
+    false // conservative: assume dead where liveness cannot be checked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PID of a child that has already exited and been reaped.
+    fn exited_pid() -> u32 {
+        #[cfg(windows)]
+        let mut child = std::process::Command::new("cmd")
+            .args(["/C", "exit 0"])
+            .spawn()
+            .unwrap();
+        #[cfg(not(windows))]
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn test_current_process_is_alive() {
+        assert!(is_process_alive(std::process::id()));
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn test_exited_process_is_dead() {
+        assert!(!is_process_alive(exited_pid()));
+    }
+
+    #[test]
+    fn test_stale_pidfile_is_replaced_and_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("am-serve.pid");
+        std::fs::write(&path, exited_pid().to_string()).unwrap();
+
+        assert!(acquire(&path));
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        release(&path);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_release_keeps_another_servers_pidfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("am-serve.pid");
+        assert!(acquire(&path));
+
+        // A later server replaced our pidfile
+        let other = std::process::id() + 1;
+        std::fs::write(&path, other.to_string()).unwrap();
+        release(&path);
+        assert_eq!(read_pid(&path), Some(other));
+    }
+}
@@ -0,0 +1,255 @@
+//! Read-only REST API for dashboards.
+//!
+//! `GET /rest/stats`, `/rest/conscious`, `/rest/episodes`, and
+//! `/rest/query?q=...&budget=N` return the JSON of the matching tools
+//! (`am_stats`, `am_conscious`, `am_episodes`, `am_query`). Queries run with
+//! `learn: false`, so polling never activates, drifts, or saves anything.
+//! The routes are nested under `/rest` on the `am serve --http` listener,
+//! and `am serve --rest ADDR` serves them alone on their own address. When
+//! `http.token` is set, every route requires `Authorization: Bearer <token>`.
+//! The brain is picked by the `x-am-brain` header, as on the JSON API.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::extract::{Query, Request, State};
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router, routing::get};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tower_http::trace::TraceLayer;
+
+use crate::http_server::{ApiError, AppState, BrainServers, dispatch, unauthorized};
+
+/// The `/rest` routes, to nest under `/rest` with [`AppState`].
+pub(crate) fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/stats", get(handle_stats))
+        .route("/conscious", get(handle_conscious))
+        .route("/episodes", get(handle_episodes))
+        .route("/query", get(handle_query))
+        .route_layer(middleware::from_fn_with_state(state, require_token))
+}
+
+pub(crate) async fn bind_rest(addr: SocketAddr) -> Result<TcpListener> {
+    tracing::info!(%addr, "binding REST API listener");
+    TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind REST API to {addr} (port may be in use)"))
+}
+
+/// Serve only the REST API on `listener`, for `am serve --rest`.
+pub(crate) async fn serve_rest(
+    listener: TcpListener,
+    brains: Arc<BrainServers>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let state = AppState { brains };
+    let app = Router::new()
+        .nest("/rest", router(state.clone()))
+        .with_state(state)
+        .layer(TraceLayer::new_for_http());
+
+    let addr = listener.local_addr()?;
+    tracing::info!(%addr, "REST API listening");
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+        .context("REST API server error")
+}
+
+/// Reject requests without the configured bearer token.
+async fn require_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(token) = state.brains.rest_token() {
+        let presented = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if !presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes())) {
+            return Err(unauthorized("missing or wrong bearer token"));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// Compare without returning at the first differing byte, so response
+/// timing does not reveal how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryParams {
+    q: String,
+    /// Token budget, as `max_tokens` on `am_query`.
+    budget: Option<usize>,
+}
+
+async fn handle_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    Ok(Json(
+        dispatch(&server, "am_stats", serde_json::json!({})).await?,
+    ))
+}
+
+async fn handle_conscious(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    Ok(Json(
+        dispatch(&server, "am_conscious", serde_json::json!({})).await?,
+    ))
+}
+
+async fn handle_episodes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    Ok(Json(
+        dispatch(&server, "am_episodes", serde_json::json!({})).await?,
+    ))
+}
+
+async fn handle_query(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<QueryParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let server = state.server_for(&headers, None).await?;
+    let args = serde_json::json!({
+        "text": params.q,
+        "max_tokens": params.budget,
+        "learn": false,
+    });
+    Ok(Json(dispatch(&server, "am_query", args).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use am_server::AmServer;
+    use am_store::config::Config;
+    use am_store::project::BrainStore;
+    use serde_json::Value;
+
+    /// A REST-only server over an in-memory brain holding one document and
+    /// one conscious memory, on an ephemeral port.
+    async fn start(token: Option<&str>) -> (String, CancellationToken) {
+        let server = AmServer::new(BrainStore::open_in_memory().unwrap()).unwrap();
+        server
+            .dispatch_tool(
+                "am_ingest",
+                &serde_json::json!({
+                    "text": "The borrow checker enforces exclusive mutable access.",
+                    "name": "rust-safety",
+                }),
+            )
+            .unwrap();
+        server
+            .dispatch_tool(
+                "am_salient",
+                &serde_json::json!({ "text": "DECISION: keep the borrow checker happy" }),
+            )
+            .unwrap();
+        let mut config = Config::default();
+        config.http.token = token.map(str::to_string);
+        let brains = BrainServers::new(Arc::new(server), "default".to_string(), config);
+
+        let listener = bind_rest(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let base = format!("http://{}/rest", listener.local_addr().unwrap());
+        let cancel = CancellationToken::new();
+        tokio::spawn(serve_rest(listener, brains, cancel.clone()));
+        (base, cancel)
+    }
+
+    async fn get_json(url: &str, token: Option<&str>) -> (u16, Value) {
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_rest_endpoints_return_tool_json() {
+        let (base, cancel) = start(None).await;
+
+        let (status, stats) = get_json(&format!("{base}/stats"), None).await;
+        assert_eq!(status, 200);
+        assert_eq!(stats["episodes"], 1);
+        assert_eq!(stats["conscious"], 1);
+
+        let (status, conscious) = get_json(&format!("{base}/conscious"), None).await;
+        assert_eq!(status, 200);
+        assert_eq!(conscious[0]["type"], "decision");
+
+        let (status, episodes) = get_json(&format!("{base}/episodes"), None).await;
+        assert_eq!(status, 200);
+        assert!(
+            episodes
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|e| e["name"] == "rust-safety")
+        );
+
+        let url = format!("{base}/query?q=borrow%20checker&budget=200");
+        let (status, first) = get_json(&url, None).await;
+        assert_eq!(status, 200);
+        assert!(
+            first["context"]
+                .as_str()
+                .unwrap()
+                .contains("borrow checker")
+        );
+        assert_eq!(first["budget"]["tokens_budget"], 200);
+        // Polling learns nothing, so the same query recalls the same thing
+        let (_, second) = get_json(&url, None).await;
+        assert_eq!(second["context"], first["context"]);
+
+        let (status, _) = get_json(&format!("{base}/query"), None).await;
+        assert_eq!(status, 400, "q is required");
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_rest_requires_configured_token() {
+        let (base, cancel) = start(Some("s3cret")).await;
+
+        let (status, body) = get_json(&format!("{base}/stats"), None).await;
+        assert_eq!(status, 401);
+        assert_eq!(body["code"], "UNAUTHORIZED");
+        let (status, _) = get_json(&format!("{base}/stats"), Some("wrong")).await;
+        assert_eq!(status, 401);
+        let (status, _) = get_json(&format!("{base}/conscious"), Some("s3cret")).await;
+        assert_eq!(status, 200);
+        cancel.cancel();
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"s3cret", b"s3cret"));
+        assert!(!constant_time_eq(b"s3cret", b"s3creT"));
+        assert!(!constant_time_eq(b"s3cret", b"s3cre"));
+    }
+}
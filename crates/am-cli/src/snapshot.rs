@@ -0,0 +1,257 @@
+//! Lightweight state fingerprints for `am snapshot`.
+//!
+//! A fingerprint records, straight from SQL and without loading the
+//! system: each episode's occurrence count, a hash of every conscious
+//! neighborhood's text, and the IDF weight of the most widespread words.
+//! Two fingerprints diff into what changed between them (episodes added or
+//! removed, conscious memories added or edited, weights that moved), which
+//! answers "why did recall change after that import" without a full
+//! export. Fingerprints are small JSON files under `<data dir>/snapshots`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use am_store::store::Store;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Words whose weights a fingerprint records, most widespread first.
+pub(crate) const FINGERPRINT_WORDS: usize = 200;
+
+/// Characters of conscious text kept as a preview.
+const PREVIEW_CHARS: usize = 60;
+
+/// Snapshot format version, bumped when a field changes meaning.
+const FINGERPRINT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Fingerprint {
+    pub version: u32,
+    pub brain: String,
+    pub taken_at: String,
+    /// Subconscious episodes by ID, archived ones included.
+    pub episodes: BTreeMap<String, EpisodePrint>,
+    /// Conscious neighborhoods by ID.
+    pub conscious: BTreeMap<String, ConsciousPrint>,
+    /// IDF weight of the [`FINGERPRINT_WORDS`] most widespread words.
+    pub word_weights: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EpisodePrint {
+    pub name: String,
+    pub occurrences: u64,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ConsciousPrint {
+    /// FNV-1a hash of the source text, hex.
+    pub hash: String,
+    pub preview: String,
+}
+
+/// An episode present in both fingerprints whose size or archive state
+/// changed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EpisodeChange<'a> {
+    pub id: &'a str,
+    pub before: &'a EpisodePrint,
+    pub after: &'a EpisodePrint,
+}
+
+/// A word whose weight moved by more than the diff threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WeightChange<'a> {
+    pub word: &'a str,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl WeightChange<'_> {
+    /// Relative change, `(after - before) / before`.
+    pub fn relative(&self) -> f64 {
+        (self.after - self.before) / self.before
+    }
+}
+
+/// What changed from an earlier fingerprint to a later one.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct FingerprintDiff<'a> {
+    pub added_episodes: Vec<(&'a str, &'a EpisodePrint)>,
+    pub removed_episodes: Vec<(&'a str, &'a EpisodePrint)>,
+    pub changed_episodes: Vec<EpisodeChange<'a>>,
+    pub added_conscious: Vec<(&'a str, &'a ConsciousPrint)>,
+    pub removed_conscious: Vec<(&'a str, &'a ConsciousPrint)>,
+    /// Same ID, different text.
+    pub edited_conscious: Vec<(&'a str, &'a ConsciousPrint)>,
+    /// Largest relative move first.
+    pub weights: Vec<WeightChange<'a>>,
+    /// Words that entered or left the recorded top words.
+    pub words_entered: usize,
+    pub words_left: usize,
+}
+
+impl FingerprintDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        *self == FingerprintDiff::default()
+    }
+}
+
+impl Fingerprint {
+    /// Fingerprint the store's brain as it is now.
+    pub fn take(store: &Store) -> Result<Self> {
+        let episodes = store
+            .list_episodes()
+            .context("failed to list episodes")?
+            .into_iter()
+            .filter(|e| !e.is_conscious)
+            .map(|e| {
+                (
+                    e.id,
+                    EpisodePrint {
+                        name: e.name,
+                        occurrences: e.occurrence_count,
+                        archived: e.archived,
+                    },
+                )
+            })
+            .collect();
+
+        let conscious = store
+            .list_conscious_neighborhoods()
+            .context("failed to list conscious neighborhoods")?
+            .into_iter()
+            .map(|n| {
+                let hash = format!(
+                    "{:016x}",
+                    am_core::tokenizer::fnv1a(n.source_text.as_bytes())
+                );
+                let preview = crate::sync_dispatch::truncate_text(&n.source_text, PREVIEW_CHARS);
+                (n.id, ConsciousPrint { hash, preview })
+            })
+            .collect();
+
+        let policy = store
+            .word_weight_policy()
+            .context("failed to read word weight policy")?;
+        let word_weights = store
+            .word_stats(FINGERPRINT_WORDS)
+            .context("failed to aggregate word stats")?
+            .words
+            .into_iter()
+            .map(|w| {
+                let weight = policy.weight_for(w.neighborhood_count as usize);
+                (w.word, weight)
+            })
+            .collect();
+
+        Ok(Self {
+            version: FINGERPRINT_VERSION,
+            brain: store.brain().to_string(),
+            taken_at: am_core::time::now_iso8601(),
+            episodes,
+            conscious,
+            word_weights,
+        })
+    }
+
+    /// What changed from `self` to `later`. Weights are reported when they
+    /// moved by more than `threshold` (a fraction, 0.1 = 10%).
+    pub fn diff<'a>(&'a self, later: &'a Self, threshold: f64) -> FingerprintDiff<'a> {
+        let mut diff = FingerprintDiff::default();
+
+        for (id, before) in &self.episodes {
+            match later.episodes.get(id) {
+                None => diff.removed_episodes.push((id, before)),
+                Some(after) if after != before => {
+                    diff.changed_episodes
+                        .push(EpisodeChange { id, before, after })
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added_episodes = later
+            .episodes
+            .iter()
+            .filter(|(id, _)| !self.episodes.contains_key(*id))
+            .map(|(id, e)| (id.as_str(), e))
+            .collect();
+
+        for (id, before) in &self.conscious {
+            match later.conscious.get(id) {
+                None => diff.removed_conscious.push((id, before)),
+                Some(after) if after.hash != before.hash => {
+                    diff.edited_conscious.push((id, after));
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added_conscious = later
+            .conscious
+            .iter()
+            .filter(|(id, _)| !self.conscious.contains_key(*id))
+            .map(|(id, c)| (id.as_str(), c))
+            .collect();
+
+        for (word, &before) in &self.word_weights {
+            match later.word_weights.get(word) {
+                None => diff.words_left += 1,
+                Some(&after) => {
+                    let change = WeightChange {
+                        word,
+                        before,
+                        after,
+                    };
+                    if before > 0.0 && change.relative().abs() > threshold {
+                        diff.weights.push(change);
+                    }
+                }
+            }
+        }
+        diff.words_entered = later
+            .word_weights
+            .keys()
+            .filter(|w| !self.word_weights.contains_key(*w))
+            .count();
+        diff.weights.sort_by(|a, b| {
+            b.relative()
+                .abs()
+                .total_cmp(&a.relative().abs())
+                .then(a.word.cmp(b.word))
+        });
+
+        diff
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read snapshot {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("{} is not an am snapshot", path.display()))
+    }
+}
+
+/// Path of snapshot `name` under `dir`. A `name` that is already a path
+/// to an existing file is used as is.
+pub(crate) fn snapshot_path(dir: &Path, name: &str) -> PathBuf {
+    let as_path = Path::new(name);
+    if as_path.is_file() {
+        return as_path.to_path_buf();
+    }
+    dir.join(format!("{name}.json"))
+}
+
+/// Default snapshot name: the UTC time, safe as a file name.
+pub(crate) fn default_name() -> String {
+    am_core::time::now_iso8601().replace(':', "")
+}
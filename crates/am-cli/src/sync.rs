@@ -9,6 +9,10 @@
 //!   - Ignored: tool_use blocks, tool_result messages, system prompts,
 //!     file-history-snapshot entries, very short messages (< 20 chars)
 //!
+//! On top of those fixed rules, `ExtractFilters` drops roles, skips
+//! messages that are mostly code fences or diff hunks, and truncates
+//! oversized messages. What they remove is tallied in a `FilterReport`.
+//!
 //! Long sessions are chunked into multiple episodes (EXCHANGES_PER_EPISODE
 //! user turns per episode, matching the source DAE's episodic model).
 
@@ -67,6 +71,111 @@ pub struct ExtractedEpisode {
     pub text: String,
 }
 
+/// Message roles that `ExtractFilters` can keep or drop.
+pub const ROLES: &[&str] = &["user", "assistant"];
+
+/// Configurable filters applied to each message after the fixed rules.
+///
+/// The default filters nothing; `am sync` builds its filters from the
+/// `[sync]` config section and command-line overrides.
+#[derive(Debug, Clone)]
+pub struct ExtractFilters {
+    /// Truncate longer messages with a marker; 0 disables truncation.
+    pub max_message_chars: usize,
+    /// Skip a message (or assistant block) when more than this share of
+    /// its text is code fences or diff hunks; 1.0 disables the check.
+    pub max_code_ratio: f64,
+    /// Roles to keep; empty keeps every role.
+    pub roles: Vec<String>,
+    /// Roles to drop, applied after `roles`.
+    pub skip_roles: Vec<String>,
+}
+
+impl Default for ExtractFilters {
+    fn default() -> Self {
+        Self {
+            max_message_chars: 0,
+            max_code_ratio: 1.0,
+            roles: Vec::new(),
+            skip_roles: Vec::new(),
+        }
+    }
+}
+
+impl ExtractFilters {
+    /// Reject role names that no transcript message carries.
+    pub fn validate(&self) -> Result<()> {
+        for role in self.roles.iter().chain(&self.skip_roles) {
+            if !ROLES.contains(&role.as_str()) {
+                anyhow::bail!(
+                    "unknown message role {role:?} (expected one of: {})",
+                    ROLES.join(", ")
+                );
+            }
+        }
+        if !(0.0..=1.0).contains(&self.max_code_ratio) {
+            anyhow::bail!(
+                "max code ratio must be within [0, 1], got: {}",
+                self.max_code_ratio
+            );
+        }
+        Ok(())
+    }
+
+    fn allows_role(&self, role: &str) -> bool {
+        (self.roles.is_empty() || self.roles.iter().any(|r| r == role))
+            && !self.skip_roles.iter().any(|r| r == role)
+    }
+
+    fn is_mostly_code(&self, raw: &str) -> bool {
+        self.max_code_ratio < 1.0 && code_ratio(raw) > self.max_code_ratio
+    }
+
+    /// Cut `text` to `max_message_chars`, appending a marker that says
+    /// how much was dropped.
+    fn truncate(&self, text: String, report: &mut FilterReport) -> String {
+        if self.max_message_chars == 0 {
+            return text;
+        }
+        let total = text.trim_end().chars().count();
+        if total <= self.max_message_chars {
+            return text;
+        }
+        let removed = total - self.max_message_chars;
+        report.truncated += 1;
+        report.filtered_chars += removed;
+        let kept: String = text.chars().take(self.max_message_chars).collect();
+        format!("{}\n[... truncated {removed} chars]", kept.trim_end())
+    }
+}
+
+/// What `ExtractFilters` removed from one session.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FilterReport {
+    /// Messages cut down to `max_message_chars`.
+    pub truncated: usize,
+    /// Messages or assistant blocks skipped as mostly code or diff.
+    pub skipped_code: usize,
+    /// Messages skipped because of their role.
+    pub skipped_role: usize,
+    /// Characters removed by truncation and skipping.
+    pub filtered_chars: usize,
+}
+
+impl FilterReport {
+    pub fn is_empty(&self) -> bool {
+        self.truncated == 0 && self.skipped_code == 0 && self.skipped_role == 0
+    }
+
+    /// One-line summary, e.g. `filtered 5120 chars: 1 truncated, 2 code/diff, 0 by role`.
+    pub fn summary(&self) -> String {
+        format!(
+            "filtered {} chars: {} truncated, {} code/diff, {} by role",
+            self.filtered_chars, self.truncated, self.skipped_code, self.skipped_role
+        )
+    }
+}
+
 /// A single exchange: one user turn and everything the assistant produces
 /// before the next user turn.
 struct Exchange {
@@ -97,7 +206,13 @@ impl Exchange {
 ///   - Main chain content is grouped into exchanges (one per user turn)
 ///     and chunked into episodes of EXCHANGES_PER_EPISODE.
 ///   - Each subagent's work (identified by slug) becomes its own episode.
-pub fn extract_episodes(path: &Path, session_prefix: &str) -> Result<Vec<ExtractedEpisode>> {
+///
+/// Returns the episodes with a report of what `filters` removed.
+pub fn extract_episodes(
+    path: &Path,
+    session_prefix: &str,
+    filters: &ExtractFilters,
+) -> Result<(Vec<ExtractedEpisode>, FilterReport)> {
     let content =
         fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
 
@@ -106,6 +221,7 @@ pub fn extract_episodes(path: &Path, session_prefix: &str) -> Result<Vec<Extract
 
     // Sidechain content grouped by agent slug/id
     let mut sidechains: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut report = FilterReport::default();
 
     for line in content.lines() {
         if line.is_empty() {
@@ -126,10 +242,13 @@ pub fn extract_episodes(path: &Path, session_prefix: &str) -> Result<Vec<Extract
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let Some(part) = extract_message(&obj, msg_type, filters, &mut report) else {
+            continue;
+        };
         if is_sidechain {
-            extract_sidechain_entry(&obj, msg_type, &mut sidechains);
+            add_sidechain_part(&obj, part, &mut sidechains);
         } else {
-            extract_main_entry(&obj, msg_type, &mut main_exchanges, &mut current);
+            add_main_part(msg_type, part, &mut main_exchanges, &mut current);
         }
     }
 
@@ -138,48 +257,29 @@ pub fn extract_episodes(path: &Path, session_prefix: &str) -> Result<Vec<Extract
         main_exchanges.push(current);
     }
 
-    build_episodes(&main_exchanges, &sidechains, session_prefix)
+    let episodes = build_episodes(&main_exchanges, &sidechains, session_prefix)?;
+    Ok((episodes, report))
 }
 
-/// Route a main-chain JSONL entry into the exchange list.
-fn extract_main_entry(
-    obj: &serde_json::Value,
+/// Route an extracted main-chain part into the exchange list.
+fn add_main_part(
     msg_type: &str,
+    part: String,
     exchanges: &mut Vec<Exchange>,
     current: &mut Exchange,
 ) {
-    match msg_type {
-        "user" => {
-            if let Some(text) = extract_user_text(obj)
-                && text.len() >= MIN_TEXT_LEN
-                && !is_system_prompt(&text)
-            {
-                // New user turn: close the previous exchange
-                if !current.is_empty() {
-                    let finished = std::mem::replace(current, Exchange::new());
-                    exchanges.push(finished);
-                }
-                current.push(format!("[user]\n{}", strip_markdown(&text)));
-            }
-        }
-        "assistant" => {
-            let parts = extract_content_blocks(obj);
-            let parts: Vec<String> = parts
-                .into_iter()
-                .filter(|p| p.len() >= MIN_TEXT_LEN)
-                .collect();
-            if !parts.is_empty() {
-                current.push(format!("[assistant]\n{}", parts.join("\n")));
-            }
-        }
-        _ => {}
+    // New user turn: close the previous exchange
+    if msg_type == "user" && !current.is_empty() {
+        let finished = std::mem::replace(current, Exchange::new());
+        exchanges.push(finished);
     }
+    current.push(part);
 }
 
-/// Route a sidechain JSONL entry into the agent map.
-fn extract_sidechain_entry(
+/// Route an extracted sidechain part into the agent map.
+fn add_sidechain_part(
     obj: &serde_json::Value,
-    msg_type: &str,
+    part: String,
     sidechains: &mut BTreeMap<String, Vec<String>>,
 ) {
     let agent_key = obj
@@ -188,34 +288,68 @@ fn extract_sidechain_entry(
         .and_then(|v| v.as_str())
         .unwrap_or("agent")
         .to_string();
+    sidechains.entry(agent_key).or_default().push(part);
+}
 
-    match msg_type {
+/// Turn one user or assistant entry into a `[role]` part.
+///
+/// The fixed rules (tool traffic, system prompts, short fragments) run
+/// first, so `report` only counts substantive content the filters removed.
+fn extract_message(
+    obj: &serde_json::Value,
+    msg_type: &str,
+    filters: &ExtractFilters,
+    report: &mut FilterReport,
+) -> Option<String> {
+    let body = match msg_type {
         "user" => {
-            if let Some(text) = extract_user_text(obj)
-                && text.len() >= MIN_TEXT_LEN
-                && !is_system_prompt(&text)
-            {
-                sidechains
-                    .entry(agent_key)
-                    .or_default()
-                    .push(format!("[user]\n{}", strip_markdown(&text)));
+            let text = extract_user_text(obj)?;
+            if text.len() < MIN_TEXT_LEN || is_system_prompt(&text) {
+                return None;
             }
+            if !filters.allows_role(msg_type) {
+                report.skipped_role += 1;
+                report.filtered_chars += text.chars().count();
+                return None;
+            }
+            if filters.is_mostly_code(&text) {
+                report.skipped_code += 1;
+                report.filtered_chars += text.chars().count();
+                return None;
+            }
+            strip_markdown(&text)
         }
         "assistant" => {
-            let parts = extract_content_blocks(obj);
-            let parts: Vec<String> = parts
+            let blocks: Vec<(&str, String)> = extract_content_blocks(obj)
                 .into_iter()
-                .filter(|p| p.len() >= MIN_TEXT_LEN)
+                .filter(|(_, text)| text.len() >= MIN_TEXT_LEN)
                 .collect();
-            if !parts.is_empty() {
-                sidechains
-                    .entry(agent_key)
-                    .or_default()
-                    .push(format!("[assistant]\n{}", parts.join("\n")));
+            if blocks.is_empty() {
+                return None;
+            }
+            if !filters.allows_role(msg_type) {
+                report.skipped_role += 1;
+                report.filtered_chars +=
+                    blocks.iter().map(|(_, t)| t.chars().count()).sum::<usize>();
+                return None;
+            }
+            let mut parts = Vec::with_capacity(blocks.len());
+            for (raw, text) in blocks {
+                if filters.is_mostly_code(raw) {
+                    report.skipped_code += 1;
+                    report.filtered_chars += text.chars().count();
+                } else {
+                    parts.push(text);
+                }
+            }
+            if parts.is_empty() {
+                return None;
             }
+            parts.join("\n")
         }
-        _ => {}
-    }
+        _ => return None,
+    };
+    Some(format!("[{msg_type}]\n{}", filters.truncate(body, report)))
 }
 
 /// Assemble extracted content into named episodes.
@@ -350,8 +484,9 @@ fn extract_user_text(obj: &serde_json::Value) -> Option<String> {
 
 /// Extract text and thinking blocks from an assistant message.
 ///
-/// Returns all substantive content. Skips tool_use blocks entirely.
-fn extract_content_blocks(obj: &serde_json::Value) -> Vec<String> {
+/// Returns each non-empty block as its raw markdown and its stripped
+/// text. Skips tool_use blocks entirely.
+fn extract_content_blocks(obj: &serde_json::Value) -> Vec<(&str, String)> {
     let Some(content) = obj
         .get("message")
         .and_then(|m| m.get("content"))
@@ -374,7 +509,7 @@ fn extract_content_blocks(obj: &serde_json::Value) -> Vec<String> {
             if trimmed.is_empty() {
                 None
             } else {
-                Some(trimmed.to_string())
+                Some((raw, trimmed.to_string()))
             }
         })
         .collect()
@@ -391,6 +526,53 @@ fn is_system_prompt(text: &str) -> bool {
         || start.starts_with("<system")
 }
 
+/// Share of a message's non-blank text (by bytes) that sits inside code
+/// fences or reads as a unified diff.
+fn code_ratio(raw: &str) -> f64 {
+    let mut total = 0usize;
+    let mut code = 0usize;
+    let mut in_fence = false;
+    let mut in_hunk = false;
+
+    for line in raw.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        total += line.len();
+
+        let is_fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        if is_fence {
+            in_fence = !in_fence;
+            code += line.len();
+            continue;
+        }
+        if in_fence {
+            code += line.len();
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if in_hunk && !line.starts_with(['+', '-', ' ', '\\']) {
+            in_hunk = false;
+        }
+        let is_diff = in_hunk
+            || line.starts_with("diff --git")
+            || line.starts_with("+++ ")
+            || line.starts_with("--- ");
+        if is_diff {
+            code += line.len();
+        }
+    }
+
+    if total == 0 {
+        0.0
+    } else {
+        code as f64 / total as f64
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Legacy: flat text extraction (used by --all bulk re-ingest)
 // ---------------------------------------------------------------------------
@@ -399,11 +581,15 @@ fn is_system_prompt(text: &str) -> bool {
 ///
 /// Simpler extraction that concatenates all user/assistant text into a
 /// single string. Used by the --all discovery path for bulk re-ingest.
-pub fn extract_session_text(path: &Path) -> Result<String> {
+pub fn extract_session_text(
+    path: &Path,
+    filters: &ExtractFilters,
+) -> Result<(String, FilterReport)> {
     let content =
         fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
 
     let mut parts: Vec<String> = Vec::new();
+    let mut report = FilterReport::default();
 
     for line in content.lines() {
         if line.is_empty() {
@@ -415,30 +601,12 @@ pub fn extract_session_text(path: &Path) -> Result<String> {
         };
 
         let msg_type = obj.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-        match msg_type {
-            "user" => {
-                if let Some(text) = extract_user_text(&obj)
-                    && text.len() >= MIN_TEXT_LEN
-                    && !is_system_prompt(&text)
-                {
-                    parts.push(format!("[user]\n{}", strip_markdown(&text)));
-                }
-            }
-            "assistant" => {
-                let blocks: Vec<String> = extract_content_blocks(&obj)
-                    .into_iter()
-                    .filter(|p| p.len() >= MIN_TEXT_LEN)
-                    .collect();
-                if !blocks.is_empty() {
-                    parts.push(format!("[assistant]\n{}", blocks.join("\n")));
-                }
-            }
-            _ => continue,
+        if let Some(part) = extract_message(&obj, msg_type, filters, &mut report) {
+            parts.push(part);
         }
     }
 
-    Ok(parts.join("\n\n"))
+    Ok((parts.join("\n\n"), report))
 }
 
 // ---------------------------------------------------------------------------
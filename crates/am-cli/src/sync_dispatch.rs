@@ -1,10 +1,15 @@
 use std::fmt::Write as _;
 
-use am_core::tokenizer::ingest_text;
+use am_core::{
+    salient::tag_plans,
+    store_trait::{AmStore, AuditEvent, AuditKind},
+    tokenizer::ingest_text,
+};
 use anyhow::{Context, Result};
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
+use crate::exit::{self, decor, note};
 use crate::sync;
 use crate::{Cli, load_config, open_store};
 
@@ -34,11 +39,53 @@ pub(crate) fn truncate_text(text: &str, max_len: usize) -> String {
     }
 }
 
+/// Whether an existing episode name refers to the sync episode `base`.
+/// Synced episodes are labeled `"{base}: word, word, ..."` after ingest,
+/// so replace semantics match either the bare or the labeled name.
+pub(crate) fn is_same_episode(existing: &str, base: &str) -> bool {
+    existing
+        .strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(": "))
+}
+
+/// Extraction filter flags from the command line; unset values fall back
+/// to the `[sync]` config section.
+pub(crate) struct FilterArgs {
+    pub max_message_chars: Option<usize>,
+    pub max_code_ratio: Option<f64>,
+    pub roles: Vec<String>,
+    pub skip_roles: Vec<String>,
+}
+
+/// Merge command-line overrides onto the configured extraction filters.
+fn resolve_filters(
+    config: &am_store::config::SyncConfig,
+    args: &FilterArgs,
+) -> Result<sync::ExtractFilters> {
+    let filters = sync::ExtractFilters {
+        max_message_chars: args.max_message_chars.unwrap_or(config.max_message_chars),
+        max_code_ratio: args.max_code_ratio.unwrap_or(config.max_code_ratio),
+        roles: if args.roles.is_empty() {
+            config.roles.clone()
+        } else {
+            args.roles.clone()
+        },
+        skip_roles: if args.skip_roles.is_empty() {
+            config.skip_roles.clone()
+        } else {
+            args.skip_roles.clone()
+        },
+    };
+    filters.validate()?;
+    Ok(filters)
+}
+
 pub(crate) fn cmd_sync(
     cli: &Cli,
     all: bool,
     dry_run: bool,
     dir_override: Option<&std::path::Path>,
+    filter_args: &FilterArgs,
 ) -> Result<()> {
     let hook_input = sync::read_hook_input();
 
@@ -46,18 +93,18 @@ pub(crate) fn cmd_sync(
         && !all
     {
         // Stdin mode: hook-triggered single-session ingest
-        return cmd_sync_single(cli, hook, dry_run);
+        return cmd_sync_single(cli, hook, dry_run, filter_args);
     }
 
     if all {
         // Discovery mode: bulk re-ingest via filesystem walk
-        cmd_sync_discover(cli, dry_run, dir_override)
+        cmd_sync_discover(cli, dry_run, dir_override, filter_args)
     } else {
         // Interactive terminal, no --all flag - print usage hint
-        println!("Usage: pipe hook JSON on stdin, or use --all for bulk discovery.\n");
-        println!("  echo '{{\"session_id\":\"...\",\"transcript_path\":\"...\"}}' | am sync");
-        println!("  am sync --all");
-        println!("  am sync --all --dry-run");
+        note!("Usage: pipe hook JSON on stdin, or use --all for bulk discovery.\n");
+        note!("  echo '{{\"session_id\":\"...\",\"transcript_path\":\"...\"}}' | am sync");
+        note!("  am sync --all");
+        note!("  am sync --all --dry-run");
         Ok(())
     }
 }
@@ -68,7 +115,12 @@ pub(crate) fn cmd_sync(
 /// source of truth. Main-chain content is chunked into episodes of 5 exchanges.
 /// Each subagent's work becomes its own episode. Thinking blocks are captured
 /// alongside text. Tool interactions are excluded.
-fn cmd_sync_single(cli: &Cli, hook: sync::HookInput, dry_run: bool) -> Result<()> {
+fn cmd_sync_single(
+    cli: &Cli,
+    hook: sync::HookInput,
+    dry_run: bool,
+    filter_args: &FilterArgs,
+) -> Result<()> {
     let crate::colors::Colors {
         bold, dim, reset, ..
     } = crate::colors::Colors::stdout();
@@ -91,11 +143,17 @@ fn cmd_sync_single(cli: &Cli, hook: sync::HookInput, dry_run: bool) -> Result<()
         return Ok(());
     }
 
-    let extracted = sync::extract_episodes(&path, session_prefix)
+    let config = load_config()?;
+    let filters = resolve_filters(&config.sync, filter_args)?;
+    let (extracted, report) = sync::extract_episodes(&path, session_prefix, &filters)
         .with_context(|| format!("failed to parse {}", path.display()))?;
 
+    if !report.is_empty() {
+        note!("  {dim}{session_prefix} {}{reset}", report.summary());
+    }
+
     if extracted.is_empty() {
-        println!("  {dim}skip{reset} {session_prefix} (no substantive content)",);
+        note!("  {dim}skip{reset} {session_prefix} (no substantive content)",);
         return Ok(());
     }
 
@@ -108,7 +166,7 @@ fn cmd_sync_single(cli: &Cli, hook: sync::HookInput, dry_run: bool) -> Result<()
                 ep.text.len()
             );
         }
-        println!(
+        decor!(
             "\n{dim}Dry run: {} episode(s), no changes made.{reset}",
             extracted.len()
         );
@@ -128,25 +186,37 @@ fn cmd_sync_single(cli: &Cli, hook: sync::HookInput, dry_run: bool) -> Result<()
 
     for ep in &extracted {
         // Replace semantics: remove existing episode with same name
-        system.episodes.retain(|e| e.name != ep.name);
+        system
+            .episodes
+            .retain(|e| !is_same_episode(&e.name, &ep.name));
 
-        let episode = ingest_text(&ep.text, Some(&ep.name), &mut rng);
+        let mut episode = ingest_text(&ep.text, Some(&ep.name), &mut rng);
+        if config.plans.detect {
+            tag_plans(&mut episode);
+        }
         let nbhd_count = episode.neighborhoods.len();
         total_neighborhoods += nbhd_count;
         system.add_episode(episode);
+        system.label_episode(system.episodes.len() - 1, &ep.name);
 
         let preview = truncate_text(&ep.text, 60);
-        println!(
+        note!(
             "  {bold}episode{reset} {} -> {nbhd_count} neighborhoods {dim}{preview}{reset}",
             ep.name,
         );
     }
 
+    let event = AuditEvent::new(AuditKind::Ingest, total_neighborhoods as u64)
+        .with_context(&system)
+        .with_detail(format!(
+            "sync {session_prefix}: {} episodes",
+            extracted.len()
+        ));
     store
-        .save_system(&system)
+        .audited(&event, || store.save_system(&system))
         .context("failed to save system")?;
 
-    println!(
+    note!(
         "\n{bold}Done.{reset} {} episode(s), {total_neighborhoods} neighborhoods, N={}, total episodes={}",
         extracted.len(),
         system.n(),
@@ -154,9 +224,8 @@ fn cmd_sync_single(cli: &Cli, hook: sync::HookInput, dry_run: bool) -> Result<()
     );
 
     // Write debug log if sync_log_dir is configured
-    let config = load_config()?;
     if let Some(ref log_dir) = config.sync_log_dir
-        && let Err(e) = write_sync_log(log_dir, session_prefix, &extracted)
+        && let Err(e) = write_sync_log(log_dir, session_prefix, &extracted, &report)
     {
         eprintln!("sync log failed: {e}");
     }
@@ -169,6 +238,7 @@ fn write_sync_log(
     log_dir: &std::path::Path,
     session_prefix: &str,
     episodes: &[sync::ExtractedEpisode],
+    report: &sync::FilterReport,
 ) -> Result<()> {
     std::fs::create_dir_all(log_dir)
         .with_context(|| format!("failed to create {}", log_dir.display()))?;
@@ -184,6 +254,7 @@ fn write_sync_log(
     writeln!(out, "session: {session_prefix}")?;
     writeln!(out, "timestamp: {timestamp}")?;
     writeln!(out, "episodes: {}", episodes.len())?;
+    writeln!(out, "{}", report.summary())?;
     writeln!(out)?;
 
     for ep in episodes {
@@ -202,17 +273,18 @@ fn cmd_sync_discover(
     cli: &Cli,
     dry_run: bool,
     dir_override: Option<&std::path::Path>,
+    filter_args: &FilterArgs,
 ) -> Result<()> {
     let claude_dir = sync::resolve_claude_dir(dir_override);
     let project_dir = match sync::find_project_dir(&claude_dir) {
         Some(dir) => dir,
         None => {
-            println!(
+            note!(
                 "No Claude Code project directory found for current working directory.\n\
                  Searched: {}/projects/",
                 claude_dir.display()
             );
-            println!(
+            note!(
                 "\nTip: Run this from your project root, or use --dir to specify the Claude config directory."
             );
             return Ok(());
@@ -226,11 +298,11 @@ fn cmd_sync_discover(
     } = crate::colors::Colors::stdout();
 
     if sessions.is_empty() {
-        println!("No sessions found.");
+        note!("No sessions found.");
         return Ok(());
     }
 
-    println!("{bold}Found {}{reset} session(s) to sync\n", sessions.len());
+    note!("{bold}Found {}{reset} session(s) to sync\n", sessions.len());
 
     // Defer store/system loading until we know we need to write. In dry-run
     // mode this avoids creating brain.db as a side effect.
@@ -240,18 +312,34 @@ fn cmd_sync_discover(
         SmallRng,
     )> = None;
 
+    let config = load_config()?;
+    let filters = resolve_filters(&config.sync, filter_args)?;
+    let detect_plans = !dry_run && config.plans.detect;
     let mut total_episodes = 0u32;
+    let mut total_neighborhoods = 0usize;
     let mut total_text_len = 0usize;
+    let mut total_filtered = 0usize;
+    let mut failed = 0usize;
 
     for session in &sessions {
-        let text = match sync::extract_session_text(&session.path) {
+        let (text, report) = match sync::extract_session_text(&session.path, &filters) {
             Ok(t) => t,
             Err(e) => {
                 eprintln!("  warning: failed to parse {}: {e}", session.path.display());
+                failed += 1;
                 continue;
             }
         };
 
+        total_filtered += report.filtered_chars;
+        if !report.is_empty() {
+            note!(
+                "  {dim}{} {}{reset}",
+                safe_prefix(&session.session_id, 8),
+                report.summary()
+            );
+        }
+
         if text.is_empty() {
             if dry_run {
                 println!(
@@ -284,14 +372,21 @@ fn cmd_sync_discover(
             };
 
             // Replace semantics: remove existing episode with same name
-            system.episodes.retain(|e| e.name != episode_name);
+            system
+                .episodes
+                .retain(|e| !is_same_episode(&e.name, &episode_name));
 
-            let episode = ingest_text(&text, Some(&episode_name), rng);
+            let mut episode = ingest_text(&text, Some(&episode_name), rng);
+            if detect_plans {
+                tag_plans(&mut episode);
+            }
             let nbhd_count = episode.neighborhoods.len();
             system.add_episode(episode);
+            system.label_episode(system.episodes.len() - 1, &episode_name);
             total_episodes += 1;
+            total_neighborhoods += nbhd_count;
 
-            println!(
+            note!(
                 "  {bold}synced{reset} {} → {} neighborhoods {dim}{text_preview}{reset}",
                 safe_prefix(&session.session_id, 8),
                 nbhd_count,
@@ -300,22 +395,34 @@ fn cmd_sync_discover(
     }
 
     if dry_run {
-        println!(
-            "\n{dim}Dry run: would ingest ~{} chars from {} sessions.{reset}",
+        decor!(
+            "\n{dim}Dry run: would ingest ~{} chars from {} sessions ({total_filtered} chars filtered).{reset}",
             total_text_len,
             sessions.len()
         );
     } else if let Some((store, system, _)) = &store_state {
         if total_episodes > 0 {
-            store.save_system(system).context("failed to save system")?;
+            let event = AuditEvent::new(AuditKind::Ingest, total_neighborhoods as u64)
+                .with_context(system)
+                .with_detail(format!("sync: {total_episodes} episodes"));
+            store
+                .audited(&event, || store.save_system(system))
+                .context("failed to save system")?;
         }
 
-        println!(
-            "\n{bold}Done.{reset} Ingested {total_episodes} episode(s). N={}, episodes={}",
+        note!(
+            "\n{bold}Done.{reset} Ingested {total_episodes} episode(s), {total_filtered} chars filtered. N={}, episodes={}",
             system.n(),
             system.episodes.len()
         );
     }
 
+    if failed > 0 {
+        eprintln!(
+            "{failed} of {} sessions could not be parsed",
+            sessions.len()
+        );
+        exit::now(exit::PARTIAL);
+    }
     Ok(())
 }
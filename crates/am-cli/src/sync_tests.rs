@@ -70,7 +70,7 @@ fn test_short_session_single_episode() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "abc12345").unwrap();
+    let (episodes, _) = extract_episodes(&path, "abc12345", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert_eq!(episodes[0].name, "session-abc12345");
     assert!(episodes[0].text.contains("authentication"));
@@ -89,7 +89,7 @@ fn test_role_headers_in_episodes() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "role1234").unwrap();
+    let (episodes, _) = extract_episodes(&path, "role1234", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(episodes[0].text.contains("[user]\nHow does authentication"));
     assert!(
@@ -110,7 +110,7 @@ fn test_role_headers_in_session_text() {
         ],
     );
 
-    let text = extract_session_text(&path).unwrap();
+    let (text, _) = extract_session_text(&path, &ExtractFilters::default()).unwrap();
     assert!(text.contains("[user]\nExplain quaternion"));
     assert!(text.contains("[assistant]\nSLERP produces"));
 }
@@ -140,7 +140,7 @@ fn test_markdown_table_stripped() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "table123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "table123", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     let text = &episodes[0].text;
     // Table pipes and separator rows should be gone
@@ -164,7 +164,7 @@ fn test_markdown_formatting_stripped() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "fmt12345").unwrap();
+    let (episodes, _) = extract_episodes(&path, "fmt12345", &ExtractFilters::default()).unwrap();
     let text = &episodes[0].text;
     // Markdown syntax should be gone
     assert!(!text.contains("##"), "heading markers present: {text}");
@@ -195,7 +195,7 @@ fn test_long_session_chunked_into_episodes() {
     }
 
     let path = write_transcript(&dir, &lines);
-    let episodes = extract_episodes(&path, "longsess").unwrap();
+    let (episodes, _) = extract_episodes(&path, "longsess", &ExtractFilters::default()).unwrap();
 
     assert_eq!(episodes.len(), 3);
     assert_eq!(episodes[0].name, "session-longsess-1");
@@ -219,7 +219,7 @@ fn test_thinking_blocks_captured() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "think123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "think123", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(episodes[0].text.contains("quaternion manifold"));
     assert!(episodes[0].text.contains("3-sphere"));
@@ -239,7 +239,7 @@ fn test_tool_use_blocks_excluded() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "tools123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "tools123", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(!episodes[0].text.contains("tool_use"));
     assert!(episodes[0].text.contains("TOML format"));
@@ -265,7 +265,7 @@ fn test_sidechain_becomes_own_episode() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "side1234").unwrap();
+    let (episodes, _) = extract_episodes(&path, "side1234", &ExtractFilters::default()).unwrap();
     // One main episode + one sidechain episode
     assert_eq!(episodes.len(), 2);
     assert_eq!(episodes[0].name, "session-side1234");
@@ -289,7 +289,7 @@ fn test_sidechain_tool_result_excluded() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "sc123456").unwrap();
+    let (episodes, _) = extract_episodes(&path, "sc123456", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(!episodes[0].text.contains("output data"));
     assert!(episodes[0].text.contains("registry pattern"));
@@ -307,7 +307,7 @@ fn test_system_prompts_filtered() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "sys12345").unwrap();
+    let (episodes, _) = extract_episodes(&path, "sys12345", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(!episodes[0].text.contains("Orchestrator"));
     assert!(episodes[0].text.contains("bearer tokens"));
@@ -326,7 +326,7 @@ fn test_short_messages_filtered() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "short123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "short123", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(!episodes[0].text.contains("yes"));
     assert!(!episodes[0].text.contains("ok sure"));
@@ -344,7 +344,7 @@ fn test_empty_session_no_episodes() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "empty123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "empty123", &ExtractFilters::default()).unwrap();
     assert!(episodes.is_empty());
 }
 
@@ -368,7 +368,7 @@ fn test_multiple_sidechains_separate_episodes() {
         ],
     );
 
-    let episodes = extract_episodes(&path, "multi123").unwrap();
+    let (episodes, _) = extract_episodes(&path, "multi123", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 3); // main + 2 sidechains
 
     let names: Vec<&str> = episodes.iter().map(|e| e.name.as_str()).collect();
@@ -388,11 +388,197 @@ fn test_user_content_array_format() {
             ],
         );
 
-    let episodes = extract_episodes(&path, "arr12345").unwrap();
+    let (episodes, _) = extract_episodes(&path, "arr12345", &ExtractFilters::default()).unwrap();
     assert_eq!(episodes.len(), 1);
     assert!(episodes[0].text.contains("query engine"));
 }
 
+// --- Extraction filter tests ---
+
+/// Build a properly escaped main-chain user JSONL line.
+fn main_user_raw(content: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "isSidechain": false,
+        "message": {"role": "user", "content": content}
+    })
+    .to_string()
+}
+
+/// A user pasting a whole source file into the conversation.
+fn giant_pasted_file(fenced: bool) -> String {
+    let body: String = (0..400)
+        .map(|i| format!("    let value_{i} = compute_something({i});\n"))
+        .collect();
+    if fenced {
+        format!("Here is the file:\n```rust\nfn main() {{\n{body}}}\n```")
+    } else {
+        format!("Here is the file:\n{body}")
+    }
+}
+
+fn filters(max_message_chars: usize, max_code_ratio: f64) -> ExtractFilters {
+    ExtractFilters {
+        max_message_chars,
+        max_code_ratio,
+        ..ExtractFilters::default()
+    }
+}
+
+#[test]
+fn test_default_filters_remove_nothing() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(
+        &dir,
+        &[
+            main_user_raw(&giant_pasted_file(true)),
+            main_assistant_text("That file computes four hundred values in sequence."),
+        ],
+    );
+
+    let (episodes, report) =
+        extract_episodes(&path, "nofilter", &ExtractFilters::default()).unwrap();
+    assert!(report.is_empty());
+    assert_eq!(report.filtered_chars, 0);
+    assert!(episodes[0].text.contains("value_399"));
+}
+
+#[test]
+fn test_giant_pasted_file_truncated() {
+    let dir = TempDir::new().unwrap();
+    let pasted = giant_pasted_file(false);
+    let path = write_transcript(
+        &dir,
+        &[
+            main_user_raw(&pasted),
+            main_assistant_text("That file computes four hundred values in sequence."),
+        ],
+    );
+
+    let (episodes, report) = extract_episodes(&path, "truncate", &filters(500, 1.0)).unwrap();
+    assert_eq!(report.truncated, 1);
+    assert_eq!(report.skipped_code, 0);
+    assert!(report.filtered_chars > pasted.len() / 2);
+
+    let text = &episodes[0].text;
+    assert!(text.contains("Here is the file"));
+    assert!(!text.contains("value_399"));
+    assert!(text.contains(&format!("[... truncated {} chars]", report.filtered_chars)));
+    assert!(text.contains("[assistant]\nThat file computes"));
+}
+
+#[test]
+fn test_truncation_counts_chars_not_bytes() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(&dir, &[main_user_raw(&"é".repeat(40))]);
+
+    let (text, report) = extract_session_text(&path, &filters(30, 1.0)).unwrap();
+    assert_eq!(report.truncated, 1);
+    assert_eq!(report.filtered_chars, 10);
+    assert!(text.contains(&"é".repeat(30)));
+}
+
+#[test]
+fn test_fenced_pasted_file_skipped() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(
+        &dir,
+        &[
+            main_user("Can you review the parser module for error handling?"),
+            main_user_raw(&giant_pasted_file(true)),
+            main_assistant_text("The parser swallows errors in the tokenizer loop."),
+        ],
+    );
+
+    let (episodes, report) = extract_episodes(&path, "fenced12", &filters(0, 0.8)).unwrap();
+    assert_eq!(report.skipped_code, 1);
+    assert_eq!(report.truncated, 0);
+    let text = &episodes[0].text;
+    assert!(text.contains("review the parser"));
+    assert!(!text.contains("compute_something"));
+    assert!(text.contains("swallows errors"));
+}
+
+#[test]
+fn test_diff_heavy_assistant_block_skipped() {
+    let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,4 +1,4 @@\n fn parse() {\n-    let x = 1;\n+    let x = 2;\n     x\n }\n";
+    let assistant = serde_json::json!({
+        "type": "assistant",
+        "isSidechain": false,
+        "message": {
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "thinking": "The constant was off by one in the parser."},
+                {"type": "text", "text": diff}
+            ]
+        }
+    })
+    .to_string();
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(
+        &dir,
+        &[
+            main_user("Fix the off-by-one in the parser please."),
+            assistant,
+        ],
+    );
+
+    let (episodes, report) = extract_episodes(&path, "diff1234", &filters(0, 0.8)).unwrap();
+    assert_eq!(report.skipped_code, 1);
+    let text = &episodes[0].text;
+    assert!(text.contains("off by one in the parser"));
+    assert!(!text.contains("let x = 2"));
+}
+
+#[test]
+fn test_prose_with_lists_is_not_code() {
+    let prose = "Steps we agreed on:\n- move the cache into the store\n- drop the global lock\n+ keep the old API for one release\n\nThen ship it.";
+    assert!(code_ratio(prose) < 0.1);
+    assert!(code_ratio("```\nlet a = 1;\n```") > 0.99);
+}
+
+#[test]
+fn test_role_filters() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(
+        &dir,
+        &[
+            main_user("How does authentication work in this codebase?"),
+            main_assistant_text("The auth middleware uses JWT tokens stored in HTTP-only cookies."),
+        ],
+    );
+
+    let skip_assistant = ExtractFilters {
+        skip_roles: vec!["assistant".to_string()],
+        ..ExtractFilters::default()
+    };
+    let (text, report) = extract_session_text(&path, &skip_assistant).unwrap();
+    assert_eq!(report.skipped_role, 1);
+    assert!(text.contains("authentication"));
+    assert!(!text.contains("JWT"));
+
+    let only_assistant = ExtractFilters {
+        roles: vec!["assistant".to_string()],
+        ..ExtractFilters::default()
+    };
+    let (text, report) = extract_session_text(&path, &only_assistant).unwrap();
+    assert_eq!(report.skipped_role, 1);
+    assert!(!text.contains("authentication"));
+    assert!(text.contains("JWT"));
+}
+
+#[test]
+fn test_filters_reject_unknown_role() {
+    let bad = ExtractFilters {
+        skip_roles: vec!["tool".to_string()],
+        ..ExtractFilters::default()
+    };
+    let err = bad.validate().unwrap_err();
+    assert!(err.to_string().contains("unknown message role"));
+    assert!(filters(100, 0.5).validate().is_ok());
+    assert!(filters(100, 2.0).validate().is_err());
+}
+
 // --- Legacy extract_session_text tests ---
 
 #[test]
@@ -409,7 +595,7 @@ fn test_extract_session_text_includes_thinking() {
         ],
     );
 
-    let text = extract_session_text(&path).unwrap();
+    let (text, _) = extract_session_text(&path, &ExtractFilters::default()).unwrap();
     assert!(text.contains("shortest-arc"));
     assert!(text.contains("constant-speed"));
 }
@@ -426,7 +612,7 @@ fn test_extract_session_text_filters_system_prompts() {
         ],
     );
 
-    let text = extract_session_text(&path).unwrap();
+    let (text, _) = extract_session_text(&path, &ExtractFilters::default()).unwrap();
     assert!(!text.contains("Orchestrator"));
     assert!(text.contains("authentication middleware"));
 }
@@ -439,7 +625,7 @@ fn test_extract_session_text_empty_session() {
         &[r#"{"type":"file-history-snapshot","snapshot":{}}"#.to_string()],
     );
 
-    let text = extract_session_text(&path).unwrap();
+    let (text, _) = extract_session_text(&path, &ExtractFilters::default()).unwrap();
     assert!(text.is_empty());
 }
 
@@ -531,3 +717,16 @@ fn test_hook_input_missing_event_name() {
     assert_eq!(input.session_id, "abc");
     assert!(input.hook_event_name.is_none());
 }
+
+#[test]
+fn test_is_same_episode_matches_labeled_name() {
+    use crate::sync_dispatch::is_same_episode;
+
+    assert!(is_same_episode("session-abcd1234", "session-abcd1234"));
+    assert!(is_same_episode(
+        "session-abcd1234: retries, webhook",
+        "session-abcd1234"
+    ));
+    assert!(!is_same_episode("session-abcd12345", "session-abcd1234"));
+    assert!(!is_same_episode("session-abcd", "session-abcd1234"));
+}
@@ -44,8 +44,8 @@ fn ingest_file_then_stats() {
         .arg(&input)
         .assert()
         .success()
-        .stdout(predicate::str::contains("ingested"))
-        .stdout(predicate::str::contains("done. N="));
+        .stderr(predicate::str::contains("ingested"))
+        .stderr(predicate::str::contains("done. N="));
 
     // Stats should show data
     let output = am_cmd(&dir).args(["stats"]).output().unwrap();
@@ -56,6 +56,67 @@ fn ingest_file_then_stats() {
     assert_eq!(extract_stat_value(&stdout, "episodes:"), "1");
 }
 
+#[test]
+fn ingest_dry_run_previews_without_saving() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("doc.txt");
+    std::fs::write(
+        &input,
+        "Dry runs only preview. Nothing reaches the database. Counts still show.",
+    )
+    .unwrap();
+
+    am_cmd(&dir)
+        .args(["ingest", "--dry-run"])
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("would ingest"))
+        .stdout(predicate::str::contains("1 neighborhoods"))
+        .stdout(predicate::str::contains("| Dry runs only preview."))
+        .stdout(predicate::str::contains("nothing saved"))
+        .stderr(predicate::str::contains("done. N=").not());
+
+    let output = am_cmd(&dir).args(["stats"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(extract_stat_value(&stdout, "N:"), "0");
+    assert_eq!(extract_stat_value(&stdout, "episodes:"), "0");
+}
+
+#[test]
+fn ingest_jsonl_keeps_each_chunk_whole() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("tickets.jsonl");
+    std::fs::write(
+        &input,
+        "{\"text\": \"Login loops after refresh. Cookie expired. Token reissued. Retry storms.\"}\n\
+         \n\
+         {\"text\": \"Billing export times out\", \"name\": \"TICKET-7\", \"tags\": [\"billing\"]}\n",
+    )
+    .unwrap();
+
+    am_cmd(&dir)
+        .args(["ingest", "--jsonl"])
+        .arg(&input)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("2 neighborhoods"));
+
+    let output = am_cmd(&dir).args(["stats"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(extract_stat_value(&stdout, "episodes:"), "1");
+
+    // A malformed line is reported with its line number
+    let bad = dir.path().join("bad.jsonl");
+    std::fs::write(&bad, "{\"text\": \"fine\"}\nnot json\n").unwrap();
+    am_cmd(&dir)
+        .args(["ingest", "--jsonl"])
+        .arg(&bad)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bad.jsonl:2"));
+}
+
 #[test]
 fn query_after_ingest() {
     let dir = TempDir::new().unwrap();
@@ -80,6 +141,191 @@ fn query_after_ingest() {
         .stdout(predicate::str::is_empty().not());
 }
 
+#[test]
+fn query_corrects_typos_and_reports_them_when_verbose() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("ops.txt");
+    std::fs::write(&input, "The kubernetes cluster restarts pods on failure.").unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+
+    am_cmd(&dir)
+        .args(["--verbose", "query", "kubernets pods"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("kubernetes cluster"))
+        .stderr(predicate::str::contains(
+            "corrected: kubernets → kubernetes",
+        ));
+}
+
+#[test]
+fn knows_exits_2_only_when_memory_has_nothing() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("ops.txt");
+    std::fs::write(&input, "The kubernetes cluster restarts pods on failure.").unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+
+    am_cmd(&dir)
+        .args(["knows", "kubernetes pods"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("activated words: 2"))
+        .stdout(predicate::str::contains("subconscious=1"));
+
+    let output = am_cmd(&dir)
+        .args(["knows", "zeppelin", "--json"])
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["known"], false);
+    assert_eq!(json["candidates"]["activated_words"], 0);
+    assert_eq!(json["candidates"]["conscious"], 0);
+    assert_eq!(json["candidates"]["subconscious"], 0);
+    assert_eq!(json["candidates"]["novel"], 0);
+    assert!(json["top_score"].is_null());
+}
+
+#[test]
+fn exit_codes_separate_empty_invalid_store_and_partial() {
+    let dir = TempDir::new().unwrap();
+    let good = dir.path().join("good.txt");
+    std::fs::write(&good, "The relay signs webhook deliveries.").unwrap();
+
+    // One input missing: the other is still stored
+    am_cmd(&dir)
+        .args(["ingest"])
+        .arg(&good)
+        .arg(dir.path().join("missing.txt"))
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("1 of 2 inputs failed"));
+    assert_eq!(episode_names(&dir), ["good"]);
+
+    am_cmd(&dir).args(["query", "webhook"]).assert().code(0);
+    am_cmd(&dir)
+        .args(["query", "zeppelin"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::is_empty());
+
+    am_cmd(&dir).args(["bias", "set"]).assert().code(3);
+    am_cmd(&dir)
+        .args(["inspect", "neighborhoods", "--sort", "bogus"])
+        .assert()
+        .code(3);
+    am_cmd(&dir).args(["--help"]).assert().code(0);
+
+    let broken = TempDir::new().unwrap();
+    std::fs::write(broken.path().join("brain.db"), "not a database ".repeat(16)).unwrap();
+    am_cmd(&broken)
+        .args(["stats"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("not a database"));
+}
+
+#[test]
+fn quiet_leaves_only_data() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("relay.txt");
+    std::fs::write(&input, "The relay signs webhook deliveries.").unwrap();
+
+    am_cmd(&dir)
+        .args(["--quiet", "ingest"])
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    let output = am_cmd(&dir)
+        .args(["inspect", "episodes", "--quiet"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("relay"));
+    assert!(!stdout.contains("EPISODES"));
+    assert!(!stdout.contains("───"));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn actor_attribution_and_query_filter() {
+    let dir = TempDir::new().unwrap();
+    for (actor, text) in [
+        ("claude-code", "Deployment runs through the blue cluster."),
+        ("codex", "Deployment runs through the green cluster."),
+    ] {
+        let input = dir.path().join(format!("{actor}.txt"));
+        std::fs::write(&input, text).unwrap();
+        am_cmd(&dir)
+            .env("AM_ACTOR", actor)
+            .args(["ingest"])
+            .arg(&input)
+            .assert()
+            .success();
+    }
+
+    let out = am_cmd(&dir)
+        .args(["inspect", "episodes", "--json"])
+        .output()
+        .unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let actors: Vec<_> = listing["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["actor"].as_str().unwrap().to_string())
+        .collect();
+    // Newest first
+    assert_eq!(actors, vec!["codex", "claude-code"]);
+
+    am_cmd(&dir)
+        .args(["query", "deployment cluster", "--actor", "codex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("green cluster"))
+        .stdout(predicate::str::contains("blue cluster").not());
+}
+
+#[test]
+fn weight_reports_counts_and_idf() {
+    let dir = TempDir::new().unwrap();
+
+    let input = dir.path().join("cache.txt");
+    std::fs::write(
+        &input,
+        "The cache stores hot rows. Cache eviction runs hourly. Rows expire after a TTL.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+
+    let output = am_cmd(&dir)
+        .args(["weight", "Cache", "nonexistentword", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let rows = json["words"].as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["word"], "cache");
+    assert!(rows[0]["neighborhoods"].as_u64().unwrap() >= 1);
+    assert!(rows[0]["occurrences"].as_u64().unwrap() >= 2);
+    assert_eq!(rows[1]["neighborhoods"], 0);
+    assert!(rows[0]["weight"].as_f64().unwrap() <= rows[1]["weight"].as_f64().unwrap());
+
+    am_cmd(&dir)
+        .args(["weight", "cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache"));
+
+    am_cmd(&dir).args(["weight"]).assert().failure();
+}
+
 #[test]
 fn export_import_roundtrip() {
     let dir = TempDir::new().unwrap();
@@ -107,7 +353,7 @@ fn export_import_roundtrip() {
         .arg(&export_path)
         .assert()
         .success()
-        .stdout(predicate::str::contains("exported to"));
+        .stderr(predicate::str::contains("exported to"));
 
     assert!(export_path.exists(), "export file should exist");
 
@@ -117,7 +363,7 @@ fn export_import_roundtrip() {
         .arg(&export_path)
         .assert()
         .success()
-        .stdout(predicate::str::contains("imported from"));
+        .stderr(predicate::str::contains("imported from"));
 
     // Stats after import should match
     let stats_after = am_cmd(&dir).args(["stats"]).output().unwrap();
@@ -171,7 +417,7 @@ fn ingest_dir() {
         .arg(&docs_dir)
         .assert()
         .success()
-        .stdout(predicate::str::contains("ingested"));
+        .stderr(predicate::str::contains("ingested"));
 
     let output = am_cmd(&dir).args(["stats"]).output().unwrap();
     assert!(output.status.success());
@@ -212,7 +458,7 @@ fn ingest_dir_deduplicates_overlapping_files() {
         .arg(docs_dir.join("first.md"))
         .assert()
         .success()
-        .stdout(predicate::str::contains("ingested"));
+        .stderr(predicate::str::contains("ingested"));
 
     let output = am_cmd(&dir).args(["stats"]).output().unwrap();
     assert!(output.status.success());
@@ -226,6 +472,344 @@ fn ingest_dir_deduplicates_overlapping_files() {
     );
 }
 
+#[test]
+fn inspect_episodes_sorts_by_name_and_size() {
+    let dir = TempDir::new().unwrap();
+    for (stem, text) in [
+        (
+            "zeta",
+            "Zeta covers the deploy pipeline, the staging cluster, and the rollback drill.",
+        ),
+        ("alpha", "Alpha is short."),
+    ] {
+        let input = dir.path().join(format!("{stem}.txt"));
+        std::fs::write(&input, text).unwrap();
+        am_cmd(&dir).arg("ingest").arg(&input).assert().success();
+    }
+
+    let sorted = |sort: &str| -> Vec<String> {
+        let out = am_cmd(&dir)
+            .args(["inspect", "episodes", "--json", "--sort", sort])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        json["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(sorted("name"), ["alpha", "zeta"]);
+    assert_eq!(sorted("size"), ["zeta", "alpha"]);
+}
+
+#[test]
+fn inspect_neighborhoods_sorts_and_pages() {
+    let dir = TempDir::new().unwrap();
+    for (stem, text) in [
+        (
+            "long",
+            "The deploy pipeline runs canary checks before every release.",
+        ),
+        ("short", "Rollback drills."),
+    ] {
+        let input = dir.path().join(format!("{stem}.txt"));
+        std::fs::write(&input, text).unwrap();
+        am_cmd(&dir).arg("ingest").arg(&input).assert().success();
+    }
+
+    let listed = |args: &[&str]| -> serde_json::Value {
+        let out = am_cmd(&dir)
+            .args(["inspect", "neighborhoods", "--json"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{out:?}");
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    let by_size = listed(&["--sort", "size"]);
+    assert_eq!(by_size["total"], 2);
+    assert_eq!(by_size["items"][0]["episode"], "long");
+    let page = listed(&["--sort", "size", "--limit", "1", "--offset", "1"]);
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["offset"], 1);
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page["items"][0]["episode"], "short");
+
+    am_cmd(&dir)
+        .args(["inspect", "neighborhoods", "--sort", "name"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("activation, recent, or size"));
+}
+
+#[test]
+fn inspect_episode_by_prefix_and_ambiguity() {
+    let dir = TempDir::new().unwrap();
+    for (stem, text) in [
+        ("deploy-notes", "The deploy pipeline runs canary checks."),
+        ("deploy-plan", "Rollback drills happen weekly."),
+    ] {
+        let input = dir.path().join(format!("{stem}.txt"));
+        std::fs::write(&input, text).unwrap();
+        am_cmd(&dir).arg("ingest").arg(&input).assert().success();
+    }
+
+    am_cmd(&dir)
+        .args(["inspect", "episode", "deploy"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("matches 2 episodes"))
+        .stderr(predicate::str::contains("deploy-notes"))
+        .stderr(predicate::str::contains("deploy-plan"));
+    am_cmd(&dir)
+        .args(["inspect", "episode", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no episode matches"));
+
+    let out = am_cmd(&dir)
+        .args(["inspect", "episode", "deploy-n", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["name"], "deploy-notes");
+    assert_eq!(json["stats"]["neighborhoods"], 1);
+    let words: Vec<&str> = json["neighborhoods"]["items"][0]["words"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        words,
+        ["the", "deploy", "pipeline", "runs", "canary", "checks"]
+    );
+
+    // An ID prefix picks the same episode; the text view shows each word.
+    let id = json["id"].as_str().unwrap();
+    am_cmd(&dir)
+        .args(["inspect", "episode", &id[..8]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("EPISODE deploy-notes"))
+        .stdout(predicate::str::contains("canary(0)"));
+    am_cmd(&dir)
+        .args(["inspect", "episode", "conscious"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("EPISODE conscious"));
+}
+
+/// Episode names from `am inspect episodes --json`.
+fn episode_names(dir: &TempDir) -> Vec<String> {
+    let out = am_cmd(dir)
+        .args(["inspect", "episodes", "--json", "--limit", "100"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let mut names: Vec<String> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn named_brains_share_the_data_dir_without_sharing_memories() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("alpha.txt");
+    std::fs::write(&input, "Alice keeps her notes about the billing service.").unwrap();
+
+    am_cmd(&dir)
+        .args(["--brain", "alice", "ingest"])
+        .arg(&input)
+        .assert()
+        .success();
+
+    assert!(episode_names(&dir).is_empty());
+    let out = am_cmd(&dir)
+        .env("AM_BRAIN", "alice")
+        .args(["inspect", "episodes", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["items"][0]["name"], "alpha");
+    // The flag wins over the environment
+    let out = am_cmd(&dir)
+        .env("AM_BRAIN", "alice")
+        .args(["--brain", "bob", "inspect", "episodes", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["total"], 0);
+
+    am_cmd(&dir)
+        .args(["--brain", "../escape", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid brain name"));
+}
+
+#[test]
+fn one_export_imports_into_two_brains() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("notes.txt");
+    std::fs::write(
+        &input,
+        "The billing service retries failed invoices nightly.",
+    )
+    .unwrap();
+    let export = dir.path().join("a.json");
+
+    am_cmd(&dir).arg("ingest").arg(&input).assert().success();
+    am_cmd(&dir).arg("export").arg(&export).assert().success();
+    am_cmd(&dir)
+        .args(["--brain", "b", "import"])
+        .arg(&export)
+        .assert()
+        .success();
+
+    let out = am_cmd(&dir)
+        .args(["--brain", "b", "inspect", "episodes", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["items"][0]["name"], "notes");
+    assert_eq!(episode_names(&dir), vec!["notes".to_string()]);
+}
+
+/// Export with the random ids and wall-clock timestamps removed, leaving
+/// only what the seed determines.
+fn seeded_state(dir: &TempDir) -> serde_json::Value {
+    fn strip(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.retain(|k, _| !matches!(k.as_str(), "id" | "neighborhoodId" | "timestamp"));
+                map.values_mut().for_each(strip);
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    let export_path = dir.path().join("export.json");
+    am_cmd(dir)
+        .args(["export"])
+        .arg(&export_path)
+        .assert()
+        .success();
+    let mut state: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&export_path).unwrap()).unwrap();
+    strip(&mut state);
+    state
+}
+
+#[test]
+fn ingest_seed_is_deterministic_across_job_counts() {
+    let docs = TempDir::new().unwrap();
+    for i in 0..8 {
+        std::fs::write(
+            docs.path().join(format!("doc{i}.md")),
+            format!("Document {i} covers drift and decay. It mentions sqlite {i} times. Done."),
+        )
+        .unwrap();
+    }
+
+    let ingest = |jobs: &str| {
+        let dir = TempDir::new().unwrap();
+        am_cmd(&dir)
+            .args(["ingest", "--seed", "7", "--jobs", jobs, "--dir"])
+            .arg(docs.path())
+            .assert()
+            .success();
+        seeded_state(&dir)
+    };
+    let serial = ingest("1");
+    assert_eq!(serial["system"]["episodes"].as_array().unwrap().len(), 8);
+    assert_eq!(serial, ingest("4"));
+}
+
+#[test]
+fn ingest_dir_respects_gitignore_and_builtin_rules() {
+    let dir = TempDir::new().unwrap();
+    let repo = dir.path().join("repo");
+    let doc = |rel: &str, text: &str| {
+        let path = repo.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    };
+
+    doc(
+        "README.md",
+        "Tracked readme about the webhook relay. It retries failed deliveries. Done.",
+    );
+    doc(
+        "docs/design.md",
+        "Design notes for the relay. Deliveries are signed. Keys rotate monthly.",
+    );
+    doc(
+        "scratch/notes.md",
+        "Scratch notes that git ignores. Nobody should recall these. Really.",
+    );
+    doc(
+        "debug.txt",
+        "Debug log that git ignores. Lines of noise. More noise.",
+    );
+    doc(
+        "node_modules/left-pad/README.md",
+        "Vendored package readme. It pads strings. On the left.",
+    );
+    doc(
+        "vendor/lib/README.md",
+        "Vendored library docs. Useful after all. Include me.",
+    );
+    doc("site/app.min.html", "<p>Minified page. Tiny. Noise.</p>");
+    doc(".gitignore", "scratch/\n*.txt\n");
+
+    let git = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(&repo)
+        .status();
+    if !git.is_ok_and(|s| s.success()) {
+        eprintln!("git unavailable, skipping");
+        return;
+    }
+
+    am_cmd(&dir)
+        .args(["ingest", "--dir"])
+        .arg(&repo)
+        .args(["--include", "vendor"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "skipped 4 path(s): 2 gitignored, 2 by built-in rules",
+        ));
+
+    // Ignored and vendored files produce no episodes; tracked docs do
+    assert_eq!(episode_names(&dir), ["README", "README", "design"]);
+
+    // Without git, only the built-in rules apply
+    std::fs::remove_dir_all(repo.join(".git")).unwrap();
+    let fresh = TempDir::new().unwrap();
+    am_cmd(&fresh)
+        .args(["ingest", "--dir"])
+        .arg(&repo)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "skipped 3 path(s): 0 gitignored, 3 by built-in rules",
+        ));
+    assert_eq!(
+        episode_names(&fresh),
+        ["README", "debug", "design", "notes"]
+    );
+}
+
 #[test]
 fn missing_required_args() {
     let dir = TempDir::new().unwrap();
@@ -361,6 +945,24 @@ fn inspect_json_outputs() {
         .assert()
         .success()
         .stdout(predicate::str::contains("\"source_text\""));
+
+    // Paged listings report the full total alongside the requested window
+    let page = |offset: &str| -> serde_json::Value {
+        let out = am_cmd(&dir)
+            .args(["inspect", "neighborhoods", "--json", "--limit", "1"])
+            .args(["--offset", offset])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    let first = page("0");
+    assert_eq!(first["items"].as_array().unwrap().len(), 1);
+    assert!(first["total"].as_u64().unwrap() >= 1);
+    let past_end = page("1000");
+    assert!(past_end["items"].as_array().unwrap().is_empty());
+    assert_eq!(past_end["total"], first["total"]);
+    assert_eq!(past_end["offset"], 1000);
 }
 
 #[test]
@@ -389,7 +991,7 @@ fn sync_dry_run() {
         .arg(&claude_dir)
         .assert()
         .success()
-        .stdout(predicate::str::contains("Found 1"))
+        .stderr(predicate::str::contains("Found 1"))
         .stdout(predicate::str::contains("sync"))
         .stdout(predicate::str::contains("Dry run"));
 
@@ -427,9 +1029,9 @@ fn sync_ingests_sessions() {
         .arg(&claude_dir)
         .assert()
         .success()
-        .stdout(predicate::str::contains("Found 2"))
-        .stdout(predicate::str::contains("episode"))
-        .stdout(predicate::str::contains("Done."));
+        .stderr(predicate::str::contains("Found 2"))
+        .stderr(predicate::str::contains("episode"))
+        .stderr(predicate::str::contains("Done."));
 
     // Stats should show 2 episodes
     am_cmd(&dir)
@@ -444,8 +1046,8 @@ fn sync_ingests_sessions() {
         .arg(&claude_dir)
         .assert()
         .success()
-        .stdout(predicate::str::contains("Found 2"))
-        .stdout(predicate::str::contains("Done."));
+        .stderr(predicate::str::contains("Found 2"))
+        .stderr(predicate::str::contains("Done."));
 
     am_cmd(&dir)
         .args(["stats"])
@@ -467,7 +1069,7 @@ fn sync_no_project_dir() {
         .arg(&empty_claude)
         .assert()
         .success()
-        .stdout(predicate::str::contains("No Claude Code project directory"));
+        .stderr(predicate::str::contains("No Claude Code project directory"));
 }
 
 #[test]
@@ -494,8 +1096,8 @@ fn sync_stdin_hook() {
         .write_stdin(hook_json.as_bytes())
         .assert()
         .success()
-        .stdout(predicate::str::contains("episode"))
-        .stdout(predicate::str::contains("Done."));
+        .stderr(predicate::str::contains("episode"))
+        .stderr(predicate::str::contains("Done."));
 
     // Verify 1 episode created
     am_cmd(&dir)
@@ -510,8 +1112,8 @@ fn sync_stdin_hook() {
         .write_stdin(hook_json.as_bytes())
         .assert()
         .success()
-        .stdout(predicate::str::contains("episode"))
-        .stdout(predicate::str::contains("Done."));
+        .stderr(predicate::str::contains("episode"))
+        .stderr(predicate::str::contains("Done."));
 
     am_cmd(&dir)
         .args(["stats"])
@@ -661,46 +1263,840 @@ fn gc_target_mb_triggers_aggressive_eviction() {
 }
 
 #[test]
-fn forget_term() {
+fn gc_archive_then_query_and_restore() {
     let dir = TempDir::new().unwrap();
 
-    let input = dir.path().join("forget.txt");
     std::fs::write(
-        &input,
-        "Authentication uses JWT tokens for session management. \
-         Password hashing employs bcrypt with salt rounds. \
-         Token refresh handles expiration transparently.",
+        dir.path().join(".am.config.toml"),
+        "[retention]\nmin_neighborhoods = 0\ngrace_epochs = 0\nretention_days = 0\n",
     )
     .unwrap();
 
+    let input = dir.path().join("gc-archive.txt");
+    std::fs::write(
+        &input,
+        "Quantum entanglement connects particles across spacetime. \
+         Bell inequality violations confirm nonlocal correlations. \
+         Decoherence destroys quantum superposition in macroscopic systems.",
+    )
+    .unwrap();
     am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
 
     am_cmd(&dir)
-        .args(["forget", "password"])
+        .args(["gc", "--floor", "99", "--archive"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Forgot"))
-        .stdout(predicate::str::contains("password"));
-}
+        .stdout(predicate::str::contains("archived episodes:      1"));
 
-#[test]
-fn forget_term_not_found() {
-    let dir = TempDir::new().unwrap();
+    // Archived, not deleted: gone from recall and listings, kept on disk
+    assert!(episode_names(&dir).is_empty());
     am_cmd(&dir)
-        .args(["forget", "nonexistent"])
+        .args(["query", "quantum entanglement"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("entanglement").not());
+    am_cmd(&dir)
+        .args(["query", "quantum entanglement", "--include-archived"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("No occurrences"));
+        .stdout(predicate::str::contains("entanglement"));
+
+    let out = am_cmd(&dir)
+        .args(["inspect", "episodes", "--archived", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["total"], 1);
+    let id = json["items"][0]["id"].as_str().unwrap().to_string();
+
+    am_cmd(&dir)
+        .args(["restore-episode", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored"));
+    assert_eq!(episode_names(&dir), vec!["gc-archive".to_string()]);
+    am_cmd(&dir)
+        .args(["restore-episode", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived episode not found"));
 }
 
 #[test]
-fn forget_requires_argument() {
+fn gc_epochs_then_query_stats_and_export() {
     let dir = TempDir::new().unwrap();
-    // No term, no --episode, no --conscious
-    am_cmd(&dir).args(["forget"]).assert().failure();
-}
 
-// -- Config validation integration tests --
+    std::fs::write(
+        dir.path().join(".am.config.toml"),
+        "[retention]\nmin_neighborhoods = 0\ngrace_epochs = 0\nretention_days = 0\n",
+    )
+    .unwrap();
+
+    let input = dir.path().join("gc-epoch.txt");
+    std::fs::write(
+        &input,
+        "Quantum entanglement connects particles across spacetime. \
+         Bell inequality violations confirm nonlocal correlations.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    am_cmd(&dir)
+        .args(["gc", "--floor", "99", "--epochs", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("episodes moved:        1"));
+    assert_eq!(episode_names(&dir), vec!["gc-epoch".to_string()]);
+
+    am_cmd(&dir)
+        .args(["gc", "--floor", "99", "--epochs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("episodes moved:        1"));
+    assert!(episode_names(&dir).is_empty());
+
+    // Moved out of brain.db: gone from recall unless epochs are asked for
+    am_cmd(&dir)
+        .args(["query", "quantum entanglement"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("Bell inequality").not());
+    am_cmd(&dir)
+        .args(["query", "quantum entanglement", "--include-epochs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bell inequality"));
+
+    am_cmd(&dir)
+        .args(["stats", "--include-epochs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("epochs:     1"))
+        .stdout(predicate::str::contains("1 episodes"));
+
+    let plain = dir.path().join("plain.json");
+    let full = dir.path().join("full.json");
+    am_cmd(&dir).args(["export"]).arg(&plain).assert().success();
+    am_cmd(&dir)
+        .args(["export", "--include-epochs"])
+        .arg(&full)
+        .assert()
+        .success();
+    assert!(
+        !std::fs::read_to_string(&plain)
+            .unwrap()
+            .contains("Bell inequality")
+    );
+    assert!(
+        std::fs::read_to_string(&full)
+            .unwrap()
+            .contains("Bell inequality")
+    );
+}
+
+#[test]
+fn recover_conscious_from_journal_copy() {
+    let dir = TempDir::new().unwrap();
+    let backup = dir.path().join("backup.journal");
+    std::fs::write(
+        &backup,
+        concat!(
+            r#"{"timestamp":"2026-01-02T03:04:05Z","type":"decision","text":"DECISION: keep sqlite","id":"6f1c2d3e-0000-4000-8000-000000000001"}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-02T03:04:06Z","type":"preference","text":"PREFERENCE: short commits","id":"6f1c2d3e-0000-4000-8000-000000000002"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    am_cmd(&dir)
+        .args(["recover-conscious", "--from"])
+        .arg(&backup)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Recovered 2 conscious memories"));
+
+    let out = am_cmd(&dir)
+        .args(["inspect", "conscious", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let mut texts: Vec<&str> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["text"].as_str().unwrap())
+        .collect();
+    texts.sort_unstable();
+    assert_eq!(
+        texts,
+        vec!["DECISION: keep sqlite", "PREFERENCE: short commits"]
+    );
+
+    let out = am_cmd(&dir)
+        .args(["inspect", "conscious", "--json", "--search", "SQLite"])
+        .args(["--sort", "type"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["items"][0]["text"], "DECISION: keep sqlite");
+    assert_eq!(json["items"][0]["type"], "decision");
+
+    am_cmd(&dir)
+        .args(["inspect", "episodes", "--search", "sqlite"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--search applies to"));
+
+    // Restored memories were copied into the live journal
+    let live = std::fs::read_to_string(dir.path().join("conscious.journal")).unwrap();
+    assert_eq!(live.lines().count(), 2);
+
+    am_cmd(&dir)
+        .args(["recover-conscious", "--from"])
+        .arg(&backup)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(2 already present)"));
+}
+
+#[test]
+fn decisions_lists_recorded_decisions_newest_first() {
+    let dir = TempDir::new().unwrap();
+    let backup = dir.path().join("backup.journal");
+    std::fs::write(
+        &backup,
+        concat!(
+            r#"{"timestamp":"2026-01-02T03:04:05Z","type":"decision","text":"keep sqlite","id":"6f1c2d3e-0000-4000-8000-000000000001"}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-02T03:04:06Z","type":"insight","text":"sqlite wal is fast","id":"6f1c2d3e-0000-4000-8000-000000000002"}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-03T09:00:00Z","type":"decision","text":"ship the cli first","id":"6f1c2d3e-0000-4000-8000-000000000003"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+    am_cmd(&dir)
+        .args(["recover-conscious", "--from"])
+        .arg(&backup)
+        .assert()
+        .success();
+
+    let out = am_cmd(&dir).args(["decisions", "--json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let listed: Vec<(&str, &str)> = json["decisions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| {
+            (
+                d["text"].as_str().unwrap(),
+                d["created_at"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("ship the cli first", "2026-01-03T09:00:00Z"),
+            ("keep sqlite", "2026-01-02T03:04:05Z"),
+        ]
+    );
+
+    am_cmd(&dir)
+        .args(["decisions", "--keyword", "SQLite"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DECISIONS (1)"))
+        .stdout(predicate::str::contains("keep sqlite"))
+        .stdout(predicate::str::contains("sqlite wal").not());
+}
+
+/// Source text of every stored neighborhood, via `am inspect neighborhoods`.
+fn stored_texts(dir: &TempDir) -> Vec<String> {
+    let out = am_cmd(dir)
+        .args(["inspect", "neighborhoods", "--json", "--limit", "100"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["source_text"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn ingest_and_migrate_strip_ansi_escapes() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("build.log");
+    std::fs::write(
+        &input,
+        b"\x1b[1m\x1b[32mPASS\x1b[0m cache tests\r\nCompiling \xffcache v0.1.0.\r\n",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    let texts = stored_texts(&dir);
+    assert_eq!(texts.len(), 1);
+    assert!(!texts[0].contains('\x1b'), "{texts:?}");
+    assert!(texts[0].contains("PASS cache tests"), "{texts:?}");
+    assert!(texts[0].contains('\u{fffd}'), "invalid UTF-8 is replaced");
+
+    // Simulate a store written before ingest cleaned its input
+    let export_path = dir.path().join("export.json");
+    am_cmd(&dir)
+        .args(["export"])
+        .arg(&export_path)
+        .assert()
+        .success();
+    let mut export: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&export_path).unwrap()).unwrap();
+    export["system"]["episodes"][0]["neighborhoods"][0]["sourceText"] =
+        "\x1b[31mFAIL\x1b[0m cache tests".into();
+    std::fs::write(&export_path, export.to_string()).unwrap();
+    am_cmd(&dir)
+        .args(["import"])
+        .arg(&export_path)
+        .assert()
+        .success();
+
+    am_cmd(&dir)
+        .args(["migrate", "clean-text", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("would clean 1 neighborhoods"));
+    assert!(stored_texts(&dir)[0].contains('\x1b'));
+
+    am_cmd(&dir)
+        .args(["migrate", "clean-text", "--apply"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "cleaned 1 neighborhoods (1 retokenized)",
+        ));
+    assert_eq!(stored_texts(&dir), vec!["FAIL cache tests"]);
+
+    am_cmd(&dir)
+        .args(["migrate", "clean-text", "--apply"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to clean."));
+    am_cmd(&dir)
+        .args(["migrate", "clean-text"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn doctor_vocab_blames_the_episode_flooding_the_vocabulary() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(
+        &notes,
+        "Drift pulls related words together. Phasors stay on the unit circle. \
+         The cache is invalidated on every write. Retries back off exponentially. \
+         Decisions are recorded in the conscious manifold. Sessions share one brain.",
+    )
+    .unwrap();
+    let log = dir.path().join("build.log");
+    let lines: String = (0..30)
+        .map(|i| format!("ERROR worker {i} request timeout after retry.\n"))
+        .collect();
+    std::fs::write(&log, lines).unwrap();
+    am_cmd(&dir)
+        .args(["ingest"])
+        .arg(&notes)
+        .arg(&log)
+        .assert()
+        .success();
+
+    let output = am_cmd(&dir)
+        .args(["doctor", "--vocab"])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.contains("! error"), "{stdout}");
+    assert!(stdout.contains("! timeout"), "{stdout}");
+    assert!(
+        stdout.contains("episode \"build\" contributes most neighborhoods"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("episode \"notes\""), "{stdout}");
+    assert!(stdout.contains("am forget --episode "), "{stdout}");
+}
+
+#[test]
+fn word_freeze_unfreeze_and_doctor_suggestions() {
+    let dir = TempDir::new().unwrap();
+    let topics = [
+        ["cache", "queue", "index", "lease"],
+        ["shard", "token", "route", "batch"],
+    ];
+    for (name, words) in ["alpha.txt", "beta.txt"].iter().zip(topics) {
+        let text: String = words
+            .iter()
+            .map(|w| format!("The widget handled {w} cleanly. "))
+            .collect();
+        let path = dir.path().join(name);
+        std::fs::write(&path, text).unwrap();
+        am_cmd(&dir).args(["ingest"]).arg(&path).assert().success();
+    }
+    let suggestion = |dir: &TempDir| {
+        let output = am_cmd(dir)
+            .args(["doctor", "--vocab"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .find(|l| l.contains("am word freeze"))
+            .map(str::to_string)
+    };
+
+    let line = suggestion(&dir).expect("freeze suggestion");
+    assert!(line.contains("widget"), "{line}");
+
+    am_cmd(&dir)
+        .args(["word", "freeze", "Widget", "cleanly"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("froze 2 words"));
+    am_cmd(&dir)
+        .args(["word", "freeze", "widget"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(1 already frozen)"));
+
+    let output = am_cmd(&dir)
+        .args(["word", "--list", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["words"], serde_json::json!(["cleanly", "widget"]));
+
+    let line = suggestion(&dir).expect("freeze suggestion");
+    assert!(!line.contains("widget"), "{line}");
+
+    am_cmd(&dir)
+        .args(["word", "unfreeze", "cleanly"])
+        .assert()
+        .success();
+    am_cmd(&dir)
+        .args(["word", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 frozen"))
+        .stdout(predicate::str::contains("cleanly").not());
+    am_cmd(&dir).args(["word", "freeze"]).assert().failure();
+}
+
+#[test]
+fn bias_sets_lists_and_clears_word_multipliers() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, "Nancy routes workers. Nancy compiles prompts.").unwrap();
+    am_cmd(&dir)
+        .args(["ingest", notes.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let weight = |dir: &TempDir| {
+        let output = am_cmd(dir)
+            .args(["weight", "nancy", "--json"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        json["words"][0]["weight"].as_f64().unwrap()
+    };
+    let plain = weight(&dir);
+
+    am_cmd(&dir)
+        .args(["bias", "set", "Nancy", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("biased nancy ×4"));
+    am_cmd(&dir)
+        .args(["bias", "set", "prompts", "50"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("clamped from 50"));
+    assert!((weight(&dir) - 4.0 * plain).abs() < 1e-9);
+
+    let output = am_cmd(&dir)
+        .args(["bias", "list", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        json["biases"],
+        serde_json::json!([
+            { "word": "nancy", "multiplier": 4.0 },
+            { "word": "prompts", "multiplier": 10.0 },
+        ])
+    );
+    am_cmd(&dir)
+        .args(["inspect", "--query", "nancy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("biases:  nancy×4, prompts×10"));
+
+    am_cmd(&dir)
+        .args(["bias", "clear", "nancy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cleared bias on nancy"));
+    assert!((weight(&dir) - plain).abs() < 1e-9);
+    am_cmd(&dir)
+        .args(["bias"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 biased"));
+    am_cmd(&dir)
+        .args(["bias", "set", "nancy"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn snapshot_diff_shows_new_episode_and_moved_weights() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(
+        &notes,
+        "The cache evicts stale entries. The queue drains in order. \
+         Retries back off exponentially.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&notes).assert().success();
+
+    am_cmd(&dir)
+        .args(["snapshot", "before"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("snapshot before: 1 episodes"));
+    assert!(dir.path().join("snapshots/before.json").is_file());
+    am_cmd(&dir).args(["snapshot", "before"]).assert().failure();
+
+    am_cmd(&dir)
+        .args(["snapshot", "--diff", "before"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No changes."));
+
+    let extra = dir.path().join("extra.txt");
+    std::fs::write(&extra, "The cache warms up after deploys.").unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&extra).assert().success();
+
+    let output = am_cmd(&dir)
+        .args(["snapshot", "--diff", "before", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let diff: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let added = diff["episodes"]["added"].as_array().unwrap();
+    assert_eq!(added.len(), 1, "{diff}");
+    assert!(added[0]["name"].as_str().unwrap().starts_with("extra"));
+    assert!(diff["episodes"]["removed"].as_array().unwrap().is_empty());
+    assert!(diff["episodes"]["changed"].as_array().unwrap().is_empty());
+    assert!(diff["conscious"]["added"].as_array().unwrap().is_empty());
+
+    // "cache" and "the" now appear in one more neighborhood each; no
+    // other stored word does.
+    let mut moved: Vec<&str> = diff["weights"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["word"].as_str().unwrap())
+        .collect();
+    moved.sort_unstable();
+    assert_eq!(moved, ["cache", "the"], "{diff}");
+    assert!(diff["words_entered"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn doctor_reports_healthy_store() {
+    let dir = TempDir::new().unwrap();
+    am_cmd(&dir)
+        .args(["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 problems"));
+
+    let input = dir.path().join("doc.txt");
+    std::fs::write(
+        &input,
+        "Drift pulls related words together. Phasors stay on the unit circle.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    am_cmd(&dir)
+        .args(["query", "drift phasors words"])
+        .assert()
+        .success();
+
+    am_cmd(&dir)
+        .args(["doctor", "--deep"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 violations"))
+        .stdout(predicate::str::contains("No problems found."));
+}
+
+#[test]
+fn forget_term() {
+    let dir = TempDir::new().unwrap();
+
+    let input = dir.path().join("forget.txt");
+    std::fs::write(
+        &input,
+        "Authentication uses JWT tokens for session management. \
+         Password hashing employs bcrypt with salt rounds. \
+         Token refresh handles expiration transparently.",
+    )
+    .unwrap();
+
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+
+    am_cmd(&dir)
+        .args(["forget", "password"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Forgot"))
+        .stdout(predicate::str::contains("password"));
+}
+
+#[test]
+fn forget_term_not_found() {
+    let dir = TempDir::new().unwrap();
+    am_cmd(&dir)
+        .args(["forget", "nonexistent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No occurrences"));
+}
+
+#[test]
+fn forget_query_shows_a_plan_then_forgets_the_matches() {
+    let dir = TempDir::new().unwrap();
+    let garden = dir.path().join("garden.txt");
+    let kernel = dir.path().join("kernel.txt");
+    std::fs::write(
+        &garden,
+        "Tomatoes need staking before the summer heat. Prune tomato suckers weekly.",
+    )
+    .unwrap();
+    std::fs::write(
+        &kernel,
+        "The scheduler preempts threads on timer interrupts. Spinlocks guard runqueues.",
+    )
+    .unwrap();
+    am_cmd(&dir)
+        .args(["ingest"])
+        .arg(&garden)
+        .arg(&kernel)
+        .assert()
+        .success();
+    let occurrences = |dir: &TempDir| {
+        let out = am_cmd(dir).args(["stats"]).output().unwrap();
+        extract_stat_value(&String::from_utf8_lossy(&out.stdout), "N:")
+    };
+    let before = occurrences(&dir);
+
+    // --json alone is a plan: nothing is forgotten
+    let out = am_cmd(&dir)
+        .args([
+            "forget",
+            "--query",
+            "tomato staking",
+            "--top",
+            "5",
+            "--json",
+        ])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let matches = plan["matches"].as_array().unwrap();
+    assert!(!matches.is_empty());
+    assert!(
+        matches
+            .iter()
+            .all(|m| m["preview"].as_str().unwrap().contains("omato"))
+    );
+    assert!(plan["forgotten"].is_null());
+    assert_eq!(occurrences(&dir), before);
+
+    // Declining the prompt keeps everything
+    am_cmd(&dir)
+        .args(["forget", "--query", "tomato staking"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FORGET PLAN"))
+        .stdout(predicate::str::contains("aborted"));
+    assert_eq!(occurrences(&dir), before);
+
+    am_cmd(&dir)
+        .args(["forget", "--query", "tomato staking", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Forgot"));
+    assert_ne!(occurrences(&dir), before);
+
+    // The garden is gone, the kernel notes are not
+    am_cmd(&dir)
+        .args(["forget", "--query", "tomato staking", "--json"])
+        .assert()
+        .code(2);
+    am_cmd(&dir)
+        .args(["query", "scheduler spinlocks"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("runqueues"));
+}
+
+#[test]
+fn forget_keeps_memories_in_quarantine_until_restored() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("notes.txt");
+    std::fs::write(
+        &input,
+        "The staging cluster runs on three bare metal hosts in Frankfurt.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+
+    am_cmd(&dir)
+        .args(["forget", "frankfurt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kept in quarantine for 30 days"));
+    am_cmd(&dir)
+        .args(["restore-forgotten", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("term frankfurt"));
+
+    am_cmd(&dir)
+        .args(["restore-forgotten", "--all", "--since", "1h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored 1 forget(s)"));
+    am_cmd(&dir)
+        .args(["query", "frankfurt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Frankfurt"));
+
+    // --hard leaves nothing to restore
+    am_cmd(&dir)
+        .args(["forget", "frankfurt", "--hard"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("quarantine").not());
+    am_cmd(&dir)
+        .args(["restore-forgotten", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(nothing forgotten)"));
+    am_cmd(&dir)
+        .args(["restore-forgotten", "7"])
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn export_positions_streams_projected_rows() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("notes.txt");
+    std::fs::write(&input, "Quaternions place every word on the three sphere.").unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    let occurrences = {
+        let out = am_cmd(&dir).args(["stats"]).output().unwrap();
+        extract_stat_value(&String::from_utf8_lossy(&out.stdout), "N:")
+    };
+
+    let csv = dir.path().join("positions.csv");
+    am_cmd(&dir)
+        .args(["export-positions"])
+        .arg(&csv)
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(&csv).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("id,word,episode,activation,drift,x,y,z"));
+    let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows.len().to_string(), occurrences);
+    for row in &rows {
+        let r2: f64 = row[5..8]
+            .iter()
+            .map(|c| c.parse::<f64>().unwrap().powi(2))
+            .sum();
+        assert!(
+            r2 <= 1.0 + 1e-9,
+            "stereographic point outside the unit ball"
+        );
+    }
+
+    // .json is one array, .jsonl one object per line
+    let json = dir.path().join("positions.json");
+    am_cmd(&dir)
+        .args(["export-positions", "--projection", "drop-w"])
+        .arg(&json)
+        .assert()
+        .success();
+    let array: serde_json::Value = serde_json::from_slice(&std::fs::read(&json).unwrap()).unwrap();
+    let array = array.as_array().unwrap();
+    assert_eq!(array.len(), rows.len());
+    assert_eq!(array[0]["id"].as_str(), Some(rows[0][0]));
+    assert!(array[0]["x"].is_f64() && array[0]["activation"].is_u64());
+
+    let jsonl = dir.path().join("positions.jsonl");
+    am_cmd(&dir)
+        .args(["export-positions", "--projection", "drop-w"])
+        .arg(&jsonl)
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(&jsonl).unwrap();
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(&lines, array);
+
+    am_cmd(&dir)
+        .args(["export-positions"])
+        .arg(dir.path().join("positions.txt"))
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn forget_requires_argument() {
+    let dir = TempDir::new().unwrap();
+    // No term, no --episode, no --conscious
+    am_cmd(&dir).args(["forget"]).assert().failure();
+}
+
+// -- Config validation integration tests --
 
 #[test]
 fn config_validation_gc_with_zero_db_size() {
@@ -745,6 +2141,69 @@ fn config_validation_malformed_toml_still_works() {
         .stdout(predicate::str::contains("N:"));
 }
 
+#[test]
+fn config_malformed_toml_names_key_and_line() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join(".am.config.toml"),
+        "gc_enabled = false\n[retention]\nretention_days = \"soon\"\n",
+    )
+    .unwrap();
+    am_cmd(&dir)
+        .args(["config", "show", "retention"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".am.config.toml:3: `retention.retention_days`: invalid type",
+        ))
+        .stdout(predicate::str::contains("the file is ignored"));
+}
+
+#[test]
+fn config_set_then_show_reports_sources() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join(".am.config.toml");
+
+    am_cmd(&dir)
+        .args(["config", "set", "buffer.threshold", "5"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("set buffer.threshold"));
+    am_cmd(&dir)
+        .args(["config", "set", "buffer.threshhold", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown config key `buffer.threshhold`",
+        ));
+    am_cmd(&dir)
+        .args(["config", "set", "compose.novel_budget_fraction", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("compose.novel_budget_fraction"));
+    assert_eq!(
+        std::fs::read_to_string(&config).unwrap(),
+        "[buffer]\nthreshold = 5\n"
+    );
+
+    let show = am_cmd(&dir)
+        .env("AM_DB_SIZE_MB", "70")
+        .args(["config", "show"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&show.get_output().stdout).into_owned();
+    let line = |key: &str| {
+        stdout
+            .lines()
+            .find(|l| l.split_whitespace().next() == Some(key))
+            .unwrap_or_else(|| panic!("no {key} line in:\n{stdout}"))
+            .to_string()
+    };
+    assert!(line("buffer.threshold").contains(&config.display().to_string()));
+    assert!(line("db_size_mb").contains("env AM_DB_SIZE_MB"));
+    assert!(line("gc_enabled").ends_with("default"));
+}
+
 // -- Relative HOME rejection (ALP-1635) --
 
 #[test]
@@ -858,3 +2317,216 @@ fn no_home_init_global_fails() {
                 .or(predicate::str::contains("global config")),
         );
 }
+
+#[test]
+fn eval_builtin_suite_json() {
+    let dir = TempDir::new().unwrap();
+    let suite = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../am-core/tests/fixtures/recall_suite.yaml"
+    );
+
+    let output = am_cmd(&dir)
+        .args(["eval", suite, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["suite"], "builtin-recall");
+    assert!(report["mrr"].as_f64().unwrap() > 0.0);
+    assert!(report["categories"].is_object());
+}
+
+#[test]
+fn log_lists_changes_and_gc_prunes_it() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("deploy.txt");
+    std::fs::write(
+        &input,
+        "Canary rollouts restore pods after a failed deploy.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    am_cmd(&dir).args(["forget", "canary"]).assert().success();
+
+    am_cmd(&dir)
+        .args(["log"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("AUDIT LOG"))
+        .stdout(predicate::str::contains("1 files"))
+        .stdout(predicate::str::contains("term canary"));
+
+    let out = am_cmd(&dir)
+        .args(["log", "--kind", "forget", "--since", "1d", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let entries = json["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["kind"], "forget");
+    assert_eq!(entries[0]["count"], 1);
+
+    am_cmd(&dir)
+        .args(["log", "--kind", "nap"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected one of salient"));
+
+    // gc keeps only the newest rows
+    am_cmd(&dir)
+        .args(["gc", "--audit-keep", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pruned audit rows:      1"));
+    let out = am_cmd(&dir).args(["log", "--json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["entries"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn undo_restores_what_forget_removed() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("deploy.txt");
+    std::fs::write(
+        &input,
+        "Canary rollouts restore pods after a failed deploy.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    let export = |name: &str| {
+        let path = dir.path().join(name);
+        am_cmd(&dir).args(["export"]).arg(&path).assert().success();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        json["system"].clone()
+    };
+    let before = export("before.json");
+
+    am_cmd(&dir)
+        .args(["undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo."));
+    am_cmd(&dir).args(["forget", "canary"]).assert().success();
+    am_cmd(&dir)
+        .args(["undo", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UNDO POINTS (1)"))
+        .stdout(predicate::str::contains("term canary"))
+        .stdout(predicate::str::contains(
+            "restores 0 episodes, 0 neighborhoods, 1 occurrences",
+        ));
+    am_cmd(&dir)
+        .args(["undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Undid forget term canary"));
+
+    assert_eq!(export("after.json"), before);
+    am_cmd(&dir)
+        .args(["log", "--kind", "undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("forget term canary"));
+}
+
+#[test]
+fn json_outputs_match_their_published_schemas() {
+    let dir = TempDir::new().unwrap();
+    let backup = dir.path().join("backup.journal");
+    std::fs::write(
+        &backup,
+        r#"{"timestamp":"2026-01-02T03:04:05Z","type":"decision","text":"keep sqlite","id":"6f1c2d3e-0000-4000-8000-000000000001"}"#,
+    )
+    .unwrap();
+    am_cmd(&dir)
+        .args(["recover-conscious", "--from"])
+        .arg(&backup)
+        .assert()
+        .success();
+    let input = dir.path().join("cache.txt");
+    std::fs::write(
+        &input,
+        "The cache stores hot rows. Cache eviction runs hourly.",
+    )
+    .unwrap();
+    am_cmd(&dir).args(["ingest"]).arg(&input).assert().success();
+    am_cmd(&dir).args(["snapshot", "before"]).assert().success();
+    am_cmd(&dir)
+        .args(["word", "freeze", "cache"])
+        .assert()
+        .success();
+    let suite = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../am-core/tests/fixtures/recall_suite.yaml"
+    );
+
+    let cases: &[(&str, &[&str])] = &[
+        ("knows", &["knows", "cache eviction", "--json"]),
+        ("decisions", &["decisions", "--json"]),
+        ("inspect", &["inspect", "--json"]),
+        ("inspect-conscious", &["inspect", "conscious", "--json"]),
+        ("inspect-episodes", &["inspect", "episodes", "--json"]),
+        (
+            "inspect-episode",
+            &["inspect", "episode", "cache", "--json"],
+        ),
+        (
+            "inspect-neighborhoods",
+            &["inspect", "neighborhoods", "--json"],
+        ),
+        ("weight", &["weight", "cache", "--json"]),
+        ("word", &["word", "--list", "--json"]),
+        ("bias", &["bias", "list", "--json"]),
+        ("eval", &["eval", suite, "--json"]),
+        ("buffer", &["buffer", "list", "--json"]),
+        ("snapshot", &["snapshot", "--diff", "before", "--json"]),
+        ("log", &["log", "--json"]),
+    ];
+    for (output, args) in cases {
+        let schema = am_cmd(&dir)
+            .args(["schema", output])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let schema: serde_json::Value = serde_json::from_slice(&schema).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let out = am_cmd(&dir).args(*args).output().unwrap();
+        assert!(out.status.success(), "am {args:?} failed: {out:?}");
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(json["schema_version"], 1, "am {args:?}");
+        let errors: Vec<String> = validator
+            .iter_errors(&json)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(
+            errors.is_empty(),
+            "am {args:?} violates its schema: {errors:?}"
+        );
+    }
+
+    am_cmd(&dir)
+        .args(["schema", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value"));
+}
+
+#[test]
+fn serve_rest_off_loopback_requires_token() {
+    let dir = TempDir::new().unwrap();
+    am_cmd(&dir)
+        .env_remove("AM_HTTP_TOKEN")
+        .args(["serve", "--rest", "0.0.0.0:0"])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("set http.token"));
+}
@@ -192,7 +192,7 @@ fn initialize_response_has_required_fields() {
 }
 
 #[test]
-fn tools_list_returns_all_12_tools() {
+fn tools_list_returns_all_14_tools() {
     let dir = TempDir::new().unwrap();
     let mut child = spawn_serve(&dir);
     let stdin = child.stdin.as_mut().unwrap();
@@ -213,7 +213,7 @@ fn tools_list_returns_all_12_tools() {
 
     assert_eq!(resp["id"], 2);
     let tools = resp["result"]["tools"].as_array().expect("tools array");
-    assert_eq!(tools.len(), 12, "should have exactly 12 tools");
+    assert_eq!(tools.len(), 14, "should have exactly 14 tools");
 
     let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
 
@@ -221,6 +221,7 @@ fn tools_list_returns_all_12_tools() {
         "am_query",
         "am_query_index",
         "am_retrieve",
+        "am_knows",
         "am_activate_response",
         "am_salient",
         "am_buffer",
@@ -230,6 +231,7 @@ fn tools_list_returns_all_12_tools() {
         "am_import",
         "am_feedback",
         "am_batch_query",
+        "am_decisions",
     ];
     for name in &expected {
         assert!(names.contains(name), "missing tool: {name}");
@@ -314,7 +316,10 @@ fn tools_call_unknown_tool_returns_error() {
     let (_resp, reader) = handshake(stdin, reader);
 
     let (resp, _reader) = call_tool(stdin, reader, 6, "nonexistent_tool", serde_json::json!({}));
-    assert_eq!(resp["result"]["isError"], true);
+    assert!(resp["result"].is_null());
+    assert_eq!(resp["error"]["code"], -32602);
+    assert_eq!(resp["error"]["data"]["kind"], "unknown_tool");
+    assert_eq!(resp["error"]["data"]["retriable"], false);
 
     drop(child.stdin.take());
     child.wait().unwrap();
@@ -634,3 +639,106 @@ fn tools_call_am_salient() {
     drop(child.stdin.take());
     child.wait().unwrap();
 }
+
+/// Buffer exchanges through a server, then shut it down.
+fn buffer_via_server(dir: &TempDir, exchanges: &[(&str, &str)]) {
+    let mut child = spawn_serve(dir);
+    let stdin = child.stdin.as_mut().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (_resp, mut reader) = handshake(stdin, BufReader::new(stdout));
+
+    for (i, (user, assistant)) in exchanges.iter().enumerate() {
+        let (resp, r) = call_tool(
+            stdin,
+            reader,
+            80 + i as u64,
+            "am_buffer",
+            serde_json::json!({ "user": user, "assistant": assistant }),
+        );
+        assert!(extract_tool_json(&resp)["episode_created"].is_null());
+        reader = r;
+    }
+
+    drop(child.stdin.take());
+    child.wait().unwrap();
+}
+
+/// Run a CLI command against the same data dir and return stdout.
+fn run_cli(dir: &TempDir, args: &[&str]) -> String {
+    let out = Command::new(am_binary())
+        .args(args)
+        .env("AM_DATA_DIR", dir.path())
+        .output()
+        .expect("failed to run am");
+    assert!(out.status.success(), "am {args:?} failed: {out:?}");
+    String::from_utf8(out.stdout).unwrap()
+}
+
+fn buffered(dir: &TempDir) -> Vec<serde_json::Value> {
+    let json: serde_json::Value =
+        serde_json::from_str(&run_cli(dir, &["buffer", "list", "--json"])).unwrap();
+    json["exchanges"].as_array().unwrap().clone()
+}
+
+fn episode_total(dir: &TempDir) -> u64 {
+    let json: serde_json::Value =
+        serde_json::from_str(&run_cli(dir, &["inspect", "episodes", "--json"])).unwrap();
+    json["total"].as_u64().unwrap()
+}
+
+#[test]
+fn buffer_cli_lists_and_flushes_server_buffer() {
+    let dir = TempDir::new().unwrap();
+    buffer_via_server(
+        &dir,
+        &[
+            (
+                "How does the retry queue back off?",
+                "Exponential backoff with jitter, capped at five minutes.",
+            ),
+            (
+                "Where is the dead letter queue configured?",
+                "In the webhook worker settings, under retry policy.",
+            ),
+        ],
+    );
+
+    let pending = buffered(&dir);
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0]["user"], "How does the retry queue back off?");
+    assert!(
+        pending[0]["created_at"]
+            .as_str()
+            .is_some_and(|t| !t.is_empty())
+    );
+    // Listing does not consume the buffer
+    assert_eq!(buffered(&dir).len(), 2);
+    assert_eq!(episode_total(&dir), 0);
+
+    let out = run_cli(&dir, &["buffer", "flush"]);
+    assert!(out.contains("Flushed 2 exchange(s)"), "{out}");
+    assert!(buffered(&dir).is_empty());
+    assert_eq!(episode_total(&dir), 1);
+
+    let out = run_cli(&dir, &["buffer", "flush"]);
+    assert!(out.contains("empty"), "{out}");
+    assert_eq!(episode_total(&dir), 1);
+}
+
+#[test]
+fn buffer_cli_clear_discards_without_episode() {
+    let dir = TempDir::new().unwrap();
+    buffer_via_server(
+        &dir,
+        &[(
+            "Scratch question about lunch",
+            "Nothing worth remembering here.",
+        )],
+    );
+    assert_eq!(buffered(&dir).len(), 1);
+
+    let out = run_cli(&dir, &["buffer", "clear", "--force"]);
+    assert!(out.contains("Discarded 1"), "{out}");
+    assert!(buffered(&dir).is_empty());
+    assert_eq!(episode_total(&dir), 0);
+}
@@ -0,0 +1,97 @@
+//! Criterion benchmarks for `BatchQueryEngine` in `batch.rs`.
+//!
+//! Run with: `cargo bench -p am-core --bench batch`
+//!
+//! Benchmarks:
+//! - `batch_query` of 8 queries against a 50k-occurrence system, composing
+//!   on 1 thread (the sequential baseline) and on every available core.
+//!   The shared activation and drift pass is the same in both; the gap is
+//!   the per-query scoring and composition run in parallel.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use am_core::{
+    batch::{BatchQueryEngine, BatchQueryRequest},
+    episode::Episode,
+    neighborhood::Neighborhood,
+    system::DAESystem,
+};
+
+const OCCURRENCES: usize = 50_000;
+const WORDS_PER_NEIGHBORHOOD: usize = 10;
+const NEIGHBORHOODS_PER_EPISODE: usize = 10;
+const VOCABULARY: usize = 2_000;
+
+/// `OCCURRENCES` occurrences in 10-word neighborhoods drawn from a
+/// `VOCABULARY`-word vocabulary, plus a few conscious memories.
+fn build_system(rng: &mut SmallRng) -> DAESystem {
+    let mut system = DAESystem::new("bench");
+    let words: Vec<String> = (0..VOCABULARY).map(|i| format!("word{i}")).collect();
+    let neighborhoods = OCCURRENCES / WORDS_PER_NEIGHBORHOOD;
+
+    for ep_idx in 0..neighborhoods / NEIGHBORHOODS_PER_EPISODE {
+        let mut episode = Episode::new(&format!("bench{ep_idx}"));
+        for _ in 0..NEIGHBORHOODS_PER_EPISODE {
+            let tokens: Vec<String> = (0..WORDS_PER_NEIGHBORHOOD)
+                .map(|_| words[rng.random_range(0..VOCABULARY)].clone())
+                .collect();
+            let text = tokens.join(" ");
+            episode.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, rng));
+        }
+        system.add_episode(episode);
+    }
+    for i in 0..20 {
+        system.add_to_conscious(&format!("word{i} word{} key insight", i * 7), rng);
+    }
+    system
+}
+
+/// Eight four-word queries, each sharing a word with the next.
+fn build_requests() -> Vec<BatchQueryRequest> {
+    (0..8)
+        .map(|q| BatchQueryRequest {
+            query: format!(
+                "word{} word{} word{} word{}",
+                q * 3,
+                q * 3 + 3,
+                100 + q * 11,
+                500 + q * 37
+            ),
+            max_tokens: None,
+            project: None,
+        })
+        .collect()
+}
+
+fn bench_batch_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_query");
+    group.sample_size(10);
+    let requests = build_requests();
+    let cores = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+
+    let mut thread_counts = vec![1];
+    if cores > 1 {
+        thread_counts.push(cores);
+    }
+    for threads in thread_counts {
+        group.bench_with_input(
+            BenchmarkId::new("threads", threads),
+            &threads,
+            |b, &threads| {
+                let mut rng = SmallRng::seed_from_u64(42);
+                let mut system = build_system(&mut rng);
+                b.iter(|| {
+                    BatchQueryEngine::batch_query_with_threads(&mut system, &requests, threads)
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_query);
+criterion_main!(benches);
@@ -6,6 +6,7 @@
 //! - `pairwise_drift` at mobile sizes: 10, 50, 100, 199
 //! - `centroid_drift` at mobile sizes: 200, 500, 1000
 //! - `process_query` end-to-end pipeline
+//! - `hot_word` query on a word in 5000 neighborhoods, capped and uncapped
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use rand::Rng;
@@ -13,7 +14,8 @@ use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
 use am_core::{
-    episode::Episode, neighborhood::Neighborhood, query::QueryEngine, system::DAESystem,
+    constants::DEFAULT_MAX_WORD_ACTIVATIONS, episode::Episode, neighborhood::Neighborhood,
+    query::QueryEngine, system::DAESystem,
 };
 
 /// Build a system with `n` activated occurrences spread across episodes.
@@ -68,7 +70,7 @@ fn bench_drift_and_consolidate(c: &mut Criterion) {
             let mut rng = SmallRng::seed_from_u64(42);
             let (mut system, refs) = build_system(size, &mut rng);
             b.iter(|| {
-                QueryEngine::drift_and_consolidate(&mut system, &refs);
+                QueryEngine::drift_and_consolidate(&mut system, &refs, 1.0);
             });
         });
     }
@@ -79,7 +81,7 @@ fn bench_drift_and_consolidate(c: &mut Criterion) {
             let mut rng = SmallRng::seed_from_u64(42);
             let (mut system, refs) = build_system(size, &mut rng);
             b.iter(|| {
-                QueryEngine::drift_and_consolidate(&mut system, &refs);
+                QueryEngine::drift_and_consolidate(&mut system, &refs, 1.0);
             });
         });
     }
@@ -125,5 +127,43 @@ fn bench_process_query(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_drift_and_consolidate, bench_process_query);
+fn bench_hot_word(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hot_word");
+    group.sample_size(20);
+
+    // 0 turns the cap off
+    for cap in [0, DEFAULT_MAX_WORD_ACTIVATIONS] {
+        group.bench_with_input(BenchmarkId::new("cap", cap), &cap, |b, &cap| {
+            let mut rng = SmallRng::seed_from_u64(42);
+            let mut system = DAESystem::new("bench");
+            for i in 0..5000 {
+                let tokens: Vec<String> = vec![
+                    "error".to_string(),
+                    format!("code{}", i % 97),
+                    format!("module{}", i % 31),
+                ];
+                let text = tokens.join(" ");
+                let nbhd = Neighborhood::from_tokens(&tokens, None, &text, &mut rng);
+                let mut episode = Episode::new("bench");
+                episode.add_neighborhood(nbhd);
+                system.add_episode(episode);
+            }
+            system.add_to_conscious("error budgets matter", &mut rng);
+            system.set_max_word_activations(cap);
+
+            b.iter(|| {
+                QueryEngine::process_query(&mut system, "error code7");
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_drift_and_consolidate,
+    bench_process_query,
+    bench_hot_word
+);
 criterion_main!(benches);
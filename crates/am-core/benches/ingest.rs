@@ -0,0 +1,60 @@
+//! Criterion benchmarks for parallel document ingestion in `tokenizer.rs`.
+//!
+//! Run with: `cargo bench -p am-core --bench ingest`
+//!
+//! Benchmarks:
+//! - `ingest_parallel` of 400 markdown-sized documents on 1, 2, and 4
+//!   threads. Documents are independent, so throughput should scale close
+//!   to linearly up to the core count.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use am_core::tokenizer::{IngestDoc, ingest_parallel};
+
+const DOCS: usize = 400;
+
+/// `DOCS` documents of ~40 sentences each, varied enough that every one
+/// tokenizes differently.
+fn build_docs() -> Vec<IngestDoc> {
+    (0..DOCS)
+        .map(|i| {
+            let text: String = (0..40)
+                .map(|s| {
+                    format!(
+                        "Section {s} of document {i} explains drift, decay, and phase \
+                         coupling for word{} near word{}. ",
+                        (i * 7 + s) % 300,
+                        (i * 13 + s) % 300
+                    )
+                })
+                .collect();
+            IngestDoc {
+                key: format!("docs/{i}.md"),
+                name: format!("doc{i}"),
+                text,
+            }
+        })
+        .collect()
+}
+
+fn bench_ingest_parallel(c: &mut Criterion) {
+    let docs = build_docs();
+    let mut group = c.benchmark_group("ingest_parallel");
+    group.throughput(Throughput::Elements(DOCS as u64));
+    group.sample_size(10);
+
+    for threads in [1, 2, 4] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                b.iter(|| ingest_parallel(&docs, 42, threads, |_, _| {}));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingest_parallel);
+criterion_main!(benches);
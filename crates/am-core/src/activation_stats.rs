@@ -2,6 +2,7 @@
 ///
 /// Returned by store implementations to provide a snapshot of
 /// activation distribution across all persisted occurrences.
+#[derive(Debug, Clone)]
 pub struct ActivationStats {
     pub total: u64,
     pub zero_activation: u64,
@@ -8,13 +8,23 @@
 //!
 //! The IDF weights are a global property of the manifold - they don't change
 //! between queries in the same batch. This is where the amortization comes from.
+//!
+//! A batch learns once: the manifold drifts a single time for the union, and
+//! every query is then scored against that same post-drift state. Results do
+//! not depend on the order of queries in the batch.
+//!
+//! Only the shared activation and drift pass mutates the system. Per-query
+//! scoring and composition then read it through `&DAESystem` and run on
+//! worker threads; results come back in request order.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::compose::{BudgetConfig, BudgetedContextResult, compose_context_budgeted};
-use crate::query::{QueryEngine, QueryManifest, QueryResult};
+use crate::compose::{BudgetConfig, BudgetedContextResult, compose_budgeted_indexed};
+use crate::constants::QUERY_DRIFT_SCALE;
+use crate::query::{QueryEngine, QueryManifest, QueryResult, QueryTimings};
 use crate::surface::compute_surface;
-use crate::system::{DAESystem, OccurrenceRef};
+use crate::system::{DAESystem, OccurrenceRef, normalize_project};
 use crate::tokenizer::tokenize;
 
 /// A single query in a batch.
@@ -24,6 +34,10 @@ pub struct BatchQueryRequest {
     pub query: String,
     /// Optional token budget for this query's context. If None, uses default.
     pub max_tokens: Option<usize>,
+    /// Project this query's recall favours in place of the system's, so
+    /// one batch can serve several repositories. Normalized with
+    /// [`normalize_project`]; blank means no override.
+    pub project: Option<String>,
 }
 
 /// Result for a single query within a batch.
@@ -60,8 +74,8 @@ pub struct BatchQueryOutput {
 /// system.add_episode(ep);
 ///
 /// let requests = vec![
-///     BatchQueryRequest { query: "algebra".into(), max_tokens: None },
-///     BatchQueryRequest { query: "graphics".into(), max_tokens: Some(500) },
+///     BatchQueryRequest { query: "algebra".into(), max_tokens: None, project: None },
+///     BatchQueryRequest { query: "graphics".into(), max_tokens: Some(500), project: None },
 /// ];
 ///
 /// let output = BatchQueryEngine::batch_query(&mut system, &requests);
@@ -82,13 +96,32 @@ impl BatchQueryEngine {
     /// 3. Drift the union once - single O(n^2) or O(n) pass.
     /// 4. Compute interference once for the full activated set.
     /// 5. For each individual query, build a per-query activation subset,
-    ///    compute surface, and compose context with its own budget.
+    ///    compute surface, and compose context with its own budget. This
+    ///    step only reads the system, so queries compose in parallel.
     ///
     /// The IDF weights don't change between step 2 and step 5 because
-    /// activation doesn't modify the neighborhood index - it only bumps
-    /// occurrence counters. So `get_word_weight()` returns the same value
-    /// for all queries in the batch.
+    /// activation and drift add and remove no neighborhoods - they only
+    /// bump occurrence counters and move positions. So `get_word_weight()`
+    /// returns the same value for all queries in the batch; debug builds
+    /// check the word stats generation to be sure.
+    ///
+    /// Steps 1-4 walk tokens in sorted order and step 5 moves no occurrence,
+    /// so permuting `requests` permutes the results and nothing else.
+    ///
+    /// Step 5 uses one thread per available core; see
+    /// [`Self::batch_query_with_threads`].
     pub fn batch_query(system: &mut DAESystem, requests: &[BatchQueryRequest]) -> BatchQueryOutput {
+        let threads = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+        Self::batch_query_with_threads(system, requests, threads)
+    }
+
+    /// [`Self::batch_query`] composing the per-query contexts on up to
+    /// `threads` worker threads. The output does not depend on `threads`.
+    pub fn batch_query_with_threads(
+        system: &mut DAESystem,
+        requests: &[BatchQueryRequest],
+        threads: usize,
+    ) -> BatchQueryOutput {
         if requests.is_empty() {
             return BatchQueryOutput {
                 results: Vec::new(),
@@ -97,12 +130,14 @@ impl BatchQueryEngine {
         }
 
         // Step 1: Union of all query tokens and per-query token sets
-        let mut all_tokens: HashSet<String> = HashSet::new();
-        let per_query_tokens: Vec<HashSet<String>> = requests
+        // Sorted sets: drift accumulates deltas in activation order, so the
+        // walk over tokens must not depend on hashing or on request order.
+        let mut all_tokens: BTreeSet<String> = BTreeSet::new();
+        let per_query_tokens: Vec<BTreeSet<String>> = requests
             .iter()
             .map(|req| {
                 let tokens = tokenize(&req.query);
-                let unique: HashSet<String> =
+                let unique: BTreeSet<String> =
                     tokens.into_iter().map(|t| t.to_lowercase()).collect();
                 all_tokens.extend(unique.iter().cloned());
                 unique
@@ -111,7 +146,7 @@ impl BatchQueryEngine {
 
         // Count how many queries contain each token. A token shared by N
         // queries must be activated N times to match sequential semantics.
-        let mut token_query_count: HashMap<String, usize> = HashMap::new();
+        let mut token_query_count: BTreeMap<String, usize> = BTreeMap::new();
         for query_set in &per_query_tokens {
             for token in query_set {
                 *token_query_count.entry(token.clone()).or_insert(0) += 1;
@@ -124,10 +159,13 @@ impl BatchQueryEngine {
         let mut all_subconscious: Vec<OccurrenceRef> = Vec::new();
         let mut all_conscious: Vec<OccurrenceRef> = Vec::new();
         let mut activated_ids: Vec<uuid::Uuid> = Vec::new();
+        // Each ref once per query it was activated for, so a shared token
+        // spills as often as sequential queries would spill it
+        let mut spill_refs: Vec<OccurrenceRef> = Vec::new();
 
         // Build word->refs map for per-query partitioning
-        let mut word_to_sub_refs: HashMap<String, Vec<OccurrenceRef>> = HashMap::new();
-        let mut word_to_con_refs: HashMap<String, Vec<OccurrenceRef>> = HashMap::new();
+        let mut word_to_sub_refs: BTreeMap<String, Vec<OccurrenceRef>> = BTreeMap::new();
+        let mut word_to_con_refs: BTreeMap<String, Vec<OccurrenceRef>> = BTreeMap::new();
 
         for token in &all_tokens {
             // First call: activates once (activation_count += 1) and
@@ -143,6 +181,9 @@ impl BatchQueryEngine {
             // Each extra call must also be tracked in activated_ids so that
             // persist_manifest issues the matching number of SQL increments.
             let extra = token_query_count.get(token).copied().unwrap_or(1) - 1;
+            for r in activation.subconscious.iter().chain(&activation.conscious) {
+                spill_refs.extend(std::iter::repeat_n(*r, extra + 1));
+            }
             if extra > 0 {
                 for r in activation.subconscious.iter().chain(&activation.conscious) {
                     let occ = system.get_occurrence_mut(*r);
@@ -164,13 +205,20 @@ impl BatchQueryEngine {
             all_conscious.extend(activation.conscious);
         }
 
+        let spilled = system.spill_activation(&spill_refs);
+
+        // Weights read from here on must match the ones activation saw.
+        // Activation already rebuilt the indexes unless nothing matched.
+        system.rebuild_indexes();
+        let weights_generation = system.word_stats().generation();
+
         // Step 3: Drift the union once
         let all_refs: Vec<OccurrenceRef> = all_subconscious
             .iter()
             .chain(all_conscious.iter())
             .copied()
             .collect();
-        let mut drifted = QueryEngine::drift_and_consolidate(system, &all_refs);
+        let mut drifted = QueryEngine::drift_and_consolidate(system, &all_refs, QUERY_DRIFT_SCALE);
 
         // Step 4: Compute interference and Kuramoto coupling for the full set
         drifted.extend(QueryEngine::couple_phases(
@@ -184,64 +232,127 @@ impl BatchQueryEngine {
             drifted,
             activated: activated_ids,
             demoted_activations: Vec::new(),
+            spilled,
+            feedback_scores: Vec::new(),
         };
 
-        // Step 5: Per-query partitioning and context composition
-        let mut results = Vec::with_capacity(requests.len());
-
-        for (i, req) in requests.iter().enumerate() {
-            let query_tokens = &per_query_tokens[i];
+        // Step 5: Per-query partitioning and context composition, all against
+        // the post-drift state. Nothing here moves an occurrence, so with the
+        // indexes rebuilt up front the queries share the system read-only.
+        system.rebuild_indexes();
+        debug_assert_eq!(
+            system.word_stats().generation(),
+            weights_generation,
+            "word weights changed between activation and composition"
+        );
+        let system: &DAESystem = system;
+        let next = AtomicUsize::new(0);
+        let score_some = || {
+            let mut done = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(req) = requests.get(i) else {
+                    return done;
+                };
+                let result = Self::score_query(
+                    system,
+                    req,
+                    &per_query_tokens[i],
+                    &word_to_sub_refs,
+                    &word_to_con_refs,
+                );
+                done.push((i, result));
+            }
+        };
 
-            // Build per-query activation by filtering the union results
-            let sub_refs: Vec<OccurrenceRef> = query_tokens
-                .iter()
-                .flat_map(|t| word_to_sub_refs.get(t).cloned().unwrap_or_default())
-                .collect();
-            let con_refs: Vec<OccurrenceRef> = query_tokens
-                .iter()
-                .flat_map(|t| word_to_con_refs.get(t).cloned().unwrap_or_default())
+        let mut slots: Vec<Option<BatchQueryResult>> = requests.iter().map(|_| None).collect();
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.clamp(1, requests.len()))
+                .map(|_| scope.spawn(score_some))
                 .collect();
+            for worker in workers {
+                let done = worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                for (i, result) in done {
+                    slots[i] = Some(result);
+                }
+            }
+        });
+        let results = slots.into_iter().flatten().collect();
+
+        BatchQueryOutput { results, manifest }
+    }
 
-            let activated_count = sub_refs.len() + con_refs.len();
+    /// Compose one query's context from its slice of the union activation.
+    /// Indexes must be current; the system is only read.
+    fn score_query(
+        system: &DAESystem,
+        req: &BatchQueryRequest,
+        query_tokens: &BTreeSet<String>,
+        word_to_sub_refs: &BTreeMap<String, Vec<OccurrenceRef>>,
+        word_to_con_refs: &BTreeMap<String, Vec<OccurrenceRef>>,
+    ) -> BatchQueryResult {
+        // Build per-query activation by filtering the union results
+        let sub_refs: Vec<OccurrenceRef> = query_tokens
+            .iter()
+            .filter_map(|t| word_to_sub_refs.get(t))
+            .flatten()
+            .copied()
+            .collect();
+        let con_refs: Vec<OccurrenceRef> = query_tokens
+            .iter()
+            .filter_map(|t| word_to_con_refs.get(t))
+            .flatten()
+            .copied()
+            .collect();
 
-            // Build a per-query QueryResult for compose_context
-            let (interference, _) = QueryEngine::compute_interference(system, &sub_refs, &con_refs);
+        let activated_count = sub_refs.len() + con_refs.len();
 
-            let query_result = QueryResult {
-                activation: crate::system::ActivationResult {
-                    subconscious: sub_refs,
-                    conscious: con_refs,
-                },
-                interference,
-                query_token_count: query_tokens.len(),
-                manifest: QueryManifest::default(),
-            };
+        // Build a per-query QueryResult for compose_context
+        let (interference, _) = QueryEngine::compute_interference(system, &sub_refs, &con_refs);
 
-            let surface = compute_surface(system, &query_result);
+        let query_result = QueryResult {
+            query: req.query.clone(),
+            activation: crate::system::ActivationResult {
+                subconscious: sub_refs,
+                conscious: con_refs,
+                capped: 0,
+            },
+            interference,
+            query_token_count: query_tokens.len(),
+            plan_intent: system.is_plan_query(query_tokens),
+            manifest: QueryManifest::default(),
+            corrections: Vec::new(),
+            capped_words: Vec::new(),
+            timings: QueryTimings::default(),
+            project: req.project.as_deref().and_then(normalize_project),
+        };
 
-            let budget = BudgetConfig {
-                max_tokens: req.max_tokens.unwrap_or(4096),
-                min_conscious: 1,
-                min_subconscious: 1,
-                min_novel: 0,
-            };
+        let surface = compute_surface(system, &query_result);
+
+        let budget = BudgetConfig {
+            max_tokens: req.max_tokens.unwrap_or(4096),
+            min_conscious: 1,
+            min_subconscious: 1,
+            min_novel: 0,
+            ..BudgetConfig::default()
+        };
 
-            let context = compose_context_budgeted(system, &surface, &query_result, &budget, None);
+        let context = compose_budgeted_indexed(system, &surface, &query_result, &budget, None);
 
-            results.push(BatchQueryResult {
-                query: req.query.clone(),
-                context,
-                activated_count,
-            });
+        BatchQueryResult {
+            query: req.query.clone(),
+            context,
+            activated_count,
         }
-
-        BatchQueryOutput { results, manifest }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compose::compose_context_budgeted;
     use crate::episode::Episode;
     use crate::neighborhood::Neighborhood;
     use rand::SeedableRng;
@@ -311,10 +422,12 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum physics".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "rust compiler".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
         ];
 
@@ -337,10 +450,12 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum physics".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "neural network".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
         ];
 
@@ -365,10 +480,12 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum physics neural".to_string(),
                 max_tokens: Some(50), // Tight budget
+                project: None,
             },
             BatchQueryRequest {
                 query: "quantum physics neural".to_string(),
                 max_tokens: Some(100_000), // Huge budget
+                project: None,
             },
         ];
 
@@ -405,6 +522,7 @@ mod tests {
             &[BatchQueryRequest {
                 query: "quantum physics".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             }],
         );
 
@@ -416,6 +534,7 @@ mod tests {
             min_conscious: 1,
             min_subconscious: 1,
             min_novel: 0,
+            ..BudgetConfig::default()
         };
         let direct = compose_context_budgeted(&mut sys2, &surface, &query_result, &budget, None);
 
@@ -437,10 +556,12 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum physics".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "quantum computing".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
         ];
 
@@ -488,14 +609,17 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum alpha".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "quantum beta".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "quantum gamma".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
         ];
         let _results = BatchQueryEngine::batch_query(&mut sys_batch, &requests);
@@ -540,10 +664,12 @@ mod tests {
             BatchQueryRequest {
                 query: "quantum physics".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
             BatchQueryRequest {
                 query: "rust compiler".to_string(),
                 max_tokens: Some(4096),
+                project: None,
             },
         ];
         let _results = BatchQueryEngine::batch_query(&mut sys, &requests);
@@ -571,4 +697,160 @@ mod tests {
             );
         }
     }
+
+    /// Parallel composition returns results in request order and matches
+    /// composing every query on one thread.
+    #[test]
+    fn test_batch_threads_keep_order_and_results() {
+        let queries = [
+            "quantum physics",
+            "rust compiler",
+            "neural network",
+            "biology protein",
+            "kubernetes pod",
+            "quantum neural architecture",
+            "deep learning wave",
+            "borrow lifetime container",
+            "unknown words only",
+        ];
+        let requests: Vec<BatchQueryRequest> = queries
+            .iter()
+            .map(|q| BatchQueryRequest {
+                query: (*q).to_string(),
+                max_tokens: Some(4096),
+                project: None,
+            })
+            .collect();
+        let base = crate::serde_compat::export_json(&make_batch_system()).unwrap();
+
+        let run = |threads| {
+            let mut sys = crate::serde_compat::import_json(&base).unwrap();
+            BatchQueryEngine::batch_query_with_threads(&mut sys, &requests, threads)
+                .results
+                .into_iter()
+                .map(|r| (r.query, r.context.context, r.activated_count))
+                .collect::<Vec<_>>()
+        };
+
+        let sequential = run(1);
+        let order: Vec<&str> = sequential.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(order, queries);
+        for threads in [2, 4, 16] {
+            assert_eq!(run(threads), sequential, "{threads} threads");
+        }
+    }
+
+    /// Each query's `project` decides which tagged memories keep full
+    /// weight, whatever the system's own project is.
+    #[test]
+    fn test_batch_project_override_per_query() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test");
+        crate::salient::mark_salient_typed(
+            &mut sys,
+            "DECISION (repo-x): redis cache warms nightly",
+            &mut rng,
+        );
+        crate::salient::mark_salient_typed(
+            &mut sys,
+            "DECISION (repo-y): memcached cache evicts hourly",
+            &mut rng,
+        );
+        sys.set_project(Some("repo-x".to_string()));
+
+        let requests: Vec<BatchQueryRequest> = [Some(" Repo-Y "), Some("repo-x"), None]
+            .into_iter()
+            .map(|project| BatchQueryRequest {
+                query: "redis memcached".to_string(),
+                max_tokens: Some(4096),
+                project: project.map(str::to_owned),
+            })
+            .collect();
+        let output = BatchQueryEngine::batch_query(&mut sys, &requests);
+        // (redis score, memcached score) for result `i`
+        let scores = |i: usize| {
+            let score = |word: &str| {
+                output.results[i]
+                    .context
+                    .included
+                    .iter()
+                    .find(|f| f.text.contains(word))
+                    .map(|f| f.score)
+                    .unwrap()
+            };
+            (score("redis"), score("memcached"))
+        };
+        let (redis, memcached) = scores(0);
+        assert!(memcached > redis, "repo-y: {memcached} vs {redis}");
+        let (redis, memcached) = scores(1);
+        assert!(redis > memcached, "repo-x: {redis} vs {memcached}");
+        assert_eq!(
+            scores(2),
+            scores(1),
+            "no override keeps the system's project"
+        );
+        assert_eq!(sys.project(), Some("repo-x"));
+    }
+
+    /// Two workers submitting the same batch in different orders must get
+    /// the same per-query contexts and leave the manifold in the same state.
+    #[test]
+    fn test_batch_permuted_queries_yield_identical_contexts() {
+        let queries = [
+            "quantum physics wave",
+            "neural network learning",
+            "quantum neural architecture",
+            "rust compiler borrow",
+        ];
+        let permutations: [[usize; 4]; 4] =
+            [[0, 1, 2, 3], [3, 2, 1, 0], [2, 0, 3, 1], [1, 3, 0, 2]];
+        // Round-trip once so every run starts from identical IDs.
+        let base = crate::serde_compat::export_json(&make_batch_system()).unwrap();
+
+        let run = |order: &[usize; 4]| {
+            let mut sys = crate::serde_compat::import_json(&base).unwrap();
+            let requests: Vec<BatchQueryRequest> = order
+                .iter()
+                .map(|&i| BatchQueryRequest {
+                    query: queries[i].to_string(),
+                    max_tokens: Some(4096),
+                    project: None,
+                })
+                .collect();
+            let output = BatchQueryEngine::batch_query(&mut sys, &requests);
+            let mut by_query: Vec<(String, String, Vec<uuid::Uuid>, usize)> = output
+                .results
+                .into_iter()
+                .map(|r| {
+                    let ids = r
+                        .context
+                        .included
+                        .iter()
+                        .map(|f| f.neighborhood_id)
+                        .collect();
+                    (r.query, r.context.context, ids, r.activated_count)
+                })
+                .collect();
+            by_query.sort_by(|a, b| a.0.cmp(&b.0));
+            let positions: Vec<_> = sys
+                .episodes
+                .iter()
+                .chain(std::iter::once(&sys.conscious_episode))
+                .flat_map(|e| &e.neighborhoods)
+                .flat_map(|n| &n.occurrences)
+                .map(|o| (o.position, o.phasor.theta, o.activation_count))
+                .collect();
+            (by_query, positions)
+        };
+
+        let (expected, expected_positions) = run(&permutations[0]);
+        for order in &permutations[1..] {
+            let (actual, positions) = run(order);
+            assert_eq!(actual, expected, "contexts differ for order {order:?}");
+            assert_eq!(
+                positions, expected_positions,
+                "manifold state differs for order {order:?}"
+            );
+        }
+    }
 }
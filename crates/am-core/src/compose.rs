@@ -1,13 +1,23 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
 
 use uuid::Uuid;
 
+use crate::constants::{
+    CONFIDENCE_HIGH_STRENGTH, CONFIDENCE_MEDIUM_STRENGTH, DEFAULT_MIN_ACTIVATED_WORDS,
+    DEFAULT_MIN_CANDIDATE_SCORE, DEFAULT_NOVEL_BUDGET_FRACTION, DEFAULT_NOVEL_MAX_ACTIVATED_WORDS,
+    DEFAULT_RERANK_TOP_K, DEFAULT_RERANK_WEIGHT,
+};
 use crate::neighborhood::NeighborhoodType;
-use crate::query::QueryResult;
-use crate::scoring::{MIN_SCORE_THRESHOLD, RankedCandidate, get_episode_name, rank_candidates};
-use crate::surface::SurfaceResult;
-use crate::system::DAESystem;
-use crate::tokenizer::token_count;
+use crate::query::{QueryEngine, QueryResult};
+use crate::salient::format_plan_steps;
+use crate::scoring::{
+    MIN_SCORE_THRESHOLD, RankedCandidate, get_episode_name, rank_candidates, score_desc,
+};
+use crate::surface::{SurfaceResult, compute_surface};
+use crate::system::{DAESystem, OccurrenceRef};
+use crate::tokenizer::{token_count, tokenize, tokenize_with_spans};
 
 /// Category of recalled content.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,6 +27,28 @@ pub enum RecallCategory {
     Novel,
 }
 
+/// Why a novel connection surfaced, rendered on its header line so the
+/// reader does not have to guess (or invent) the link.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NovelBridge {
+    /// Query words the neighborhood shares, highest IDF weight first.
+    pub via: Vec<String>,
+    /// Conscious neighborhood the novel one's activated occurrences
+    /// interfere with most constructively, with the start of its text.
+    pub resonates_with: Option<(Uuid, String)>,
+}
+
+impl fmt::Display for NovelBridge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let via: Vec<String> = self.via.iter().map(|w| format!("'{w}'")).collect();
+        write!(f, "via: {}", via.join(", "))?;
+        if let Some((_, note)) = &self.resonates_with {
+            write!(f, ", resonates with conscious note on \"{note}\"")?;
+        }
+        Ok(())
+    }
+}
+
 /// Metrics about the composed context.
 pub struct ContextMetrics {
     pub conscious: u32,
@@ -34,7 +66,8 @@ pub struct TokenEstimate {
 }
 
 /// Estimate LLM tokens from text length (chars / 4, rounded up).
-fn estimate_llm_tokens(text: &str) -> usize {
+#[must_use]
+pub fn estimate_llm_tokens(text: &str) -> usize {
     text.len().div_ceil(4)
 }
 
@@ -45,6 +78,532 @@ pub struct CategorizedIds {
     pub novel: Vec<Uuid>,
 }
 
+/// Cutoffs applied while ranking, before any category selection.
+///
+/// A neighborhood that matches fewer than `min_activated_words` distinct
+/// query words, or scores below `min_score` (after interference, vividness,
+/// and plan boosts), never becomes a candidate. Raising
+/// `min_activated_words` to 2 stops a single shared common word from
+/// producing a misleading recall entry. `min_score` is checked again after
+/// session diminishing returns, so a memory recalled repeatedly in one
+/// session eventually stops being repeated.
+///
+/// `labels` frames each entry in the composed context. With `highlight`
+/// set, the query's words are wrapped in its markers wherever they appear
+/// in recalled text. `novel` decides which subconscious matches count as
+/// novel connections.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposeConfig {
+    pub min_activated_words: usize,
+    pub min_score: f64,
+    pub labels: LabelSet,
+    pub highlight: Option<Highlight>,
+    pub novel: NovelConfig,
+}
+
+impl Default for ComposeConfig {
+    fn default() -> Self {
+        Self {
+            min_activated_words: DEFAULT_MIN_ACTIVATED_WORDS,
+            min_score: DEFAULT_MIN_CANDIDATE_SCORE,
+            labels: LabelSet::default(),
+            highlight: None,
+            novel: NovelConfig::default(),
+        }
+    }
+}
+
+/// Which subconscious matches qualify as novel connections.
+///
+/// A neighborhood is eligible when its raw novelty (`max word weight *
+/// max plasticity / activated occurrences`) reaches `min_novelty_score`
+/// and, with `require_no_conscious_overlap`, it shares no activated word
+/// with conscious memory. It qualifies when it also matches at most
+/// `max_activated_words` distinct query words; qualifying neighborhoods
+/// are ranked by raw novelty.
+///
+/// When the query activates no conscious memory, or nothing qualifies
+/// (in a mature brain most matches hit several words), every eligible
+/// neighborhood is a novel candidate instead, ranked fewest activated
+/// occurrences first, then highest IDF weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NovelConfig {
+    pub max_activated_words: usize,
+    pub require_no_conscious_overlap: bool,
+    pub min_novelty_score: f64,
+}
+
+impl Default for NovelConfig {
+    fn default() -> Self {
+        Self {
+            max_activated_words: DEFAULT_NOVEL_MAX_ACTIVATED_WORDS,
+            require_no_conscious_overlap: true,
+            min_novelty_score: 0.0,
+        }
+    }
+}
+
+/// Markers wrapped around matched words in recalled text, e.g. `**` and
+/// `**` for Markdown bold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Highlight {
+    pub open: String,
+    pub close: String,
+}
+
+impl Highlight {
+    /// Wrap every word of `text` that tokenizes to one of `words`. Spans
+    /// come from [`tokenize_with_spans`], so matching is case-insensitive
+    /// and punctuation next to a word stays outside the markers.
+    #[must_use]
+    pub fn apply(&self, text: &str, words: &HashSet<String>) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for token in tokenize_with_spans(text) {
+            if words.contains(&token.text) {
+                out.push_str(&text[last..token.byte_start]);
+                out.push_str(&self.open);
+                out.push_str(&text[token.byte_start..token.byte_end]);
+                out.push_str(&self.close);
+                last = token.byte_end;
+            }
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// The system's highlight markers with the words a query activated, when
+/// its compose config asks for highlighting.
+fn highlighter(
+    system: &DAESystem,
+    query_result: &QueryResult,
+) -> Option<(Highlight, HashSet<String>)> {
+    let highlight = system.compose_config().highlight.clone()?;
+    let activation = &query_result.activation;
+    let words = activation
+        .subconscious
+        .iter()
+        .chain(&activation.conscious)
+        .map(|r| system.get_occurrence(*r).word.clone())
+        .collect();
+    Some((highlight, words))
+}
+
+/// `text` with `highlighter`'s words marked, or as is without one.
+fn highlighted<'a>(
+    highlighter: Option<&(Highlight, HashSet<String>)>,
+    text: &'a str,
+) -> std::borrow::Cow<'a, str> {
+    match highlighter {
+        Some((highlight, words)) => highlight.apply(text, words).into(),
+        None => text.into(),
+    }
+}
+
+/// The framing text around each recalled entry, so deployments can
+/// localize or restyle the context without forking the formatter.
+///
+/// Templates substitute `{index}` (subconscious entry number), `{source}`
+/// (episode name), and `{bridge}` (a novel connection's explanation).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelSet {
+    /// Conscious entry header: `CONSCIOUS RECALL:`
+    pub conscious: String,
+    /// Conscious entry source line: `[Source: Previously marked salient]`
+    pub conscious_source: String,
+    /// Subconscious entry header: `SUBCONSCIOUS RECALL {index}:`
+    pub subconscious: String,
+    /// Novel entry header: `NOVEL CONNECTION:`
+    pub novel: String,
+    /// Novel entry header with its bridge: `NOVEL CONNECTION ({bridge}):`
+    pub novel_bridge: String,
+    /// Subconscious and novel source line: `[Source: {source}]`
+    pub source: String,
+    /// Prefix on decisions: `[DECIDED]`
+    pub decided: String,
+    /// Prefix on preferences: `[PREFERENCE]`
+    pub preference: String,
+    /// Line above a plan's steps: `[PLAN]`
+    pub plan: String,
+}
+
+impl Default for LabelSet {
+    fn default() -> Self {
+        Self {
+            conscious: "CONSCIOUS RECALL:".to_string(),
+            conscious_source: "[Source: Previously marked salient]".to_string(),
+            subconscious: "SUBCONSCIOUS RECALL {index}:".to_string(),
+            novel: "NOVEL CONNECTION:".to_string(),
+            novel_bridge: "NOVEL CONNECTION ({bridge}):".to_string(),
+            source: "[Source: {source}]".to_string(),
+            decided: "[DECIDED]".to_string(),
+            preference: "[PREFERENCE]".to_string(),
+            plan: "[PLAN]".to_string(),
+        }
+    }
+}
+
+impl LabelSet {
+    /// Field names, as used in `compose.labels.<name>` overrides.
+    pub const NAMES: [&'static str; 9] = [
+        "conscious",
+        "conscious_source",
+        "subconscious",
+        "novel",
+        "novel_bridge",
+        "source",
+        "decided",
+        "preference",
+        "plan",
+    ];
+
+    /// Replace the label called `name`. Returns false for an unknown name.
+    pub fn set(&mut self, name: &str, value: String) -> bool {
+        let field = match name {
+            "conscious" => &mut self.conscious,
+            "conscious_source" => &mut self.conscious_source,
+            "subconscious" => &mut self.subconscious,
+            "novel" => &mut self.novel,
+            "novel_bridge" => &mut self.novel_bridge,
+            "source" => &mut self.source,
+            "decided" => &mut self.decided,
+            "preference" => &mut self.preference,
+            "plan" => &mut self.plan,
+            _ => return false,
+        };
+        *field = value;
+        true
+    }
+}
+
+/// Which `ComposeConfig` cutoff dropped a neighborhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutoffReason {
+    TooFewWords,
+    LowScore,
+    /// The score came out NaN or infinite, e.g. from a corrupt phasor,
+    /// position, or weight. Never recalled, whatever the cutoffs.
+    InvalidScore,
+}
+
+/// A neighborhood dropped by a `ComposeConfig` cutoff, for explaining
+/// why something expected did not show up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilteredCandidate {
+    pub neighborhood_id: Uuid,
+    pub activated_words: usize,
+    pub score: f64,
+    pub reason: CutoffReason,
+}
+
+/// Read-only view of a ranked candidate handed to a `Reranker`.
+#[derive(Debug, Clone, Copy)]
+pub struct CandidateView<'a> {
+    pub neighborhood_id: Uuid,
+    pub category: RecallCategory,
+    /// Geometric score from `rank_candidates`.
+    pub score: f64,
+    pub text: &'a str,
+    pub neighborhood_type: NeighborhoodType,
+}
+
+/// Second-pass scorer over the top geometric candidates, e.g. a
+/// cross-encoder the embedding application runs itself.
+///
+/// `rerank` returns one relevance score per candidate, in input order,
+/// in `[0, 1]`. Out-of-range values are clamped, non-finite values count
+/// as 0, and a result of the wrong length leaves the geometric order alone.
+pub trait Reranker: Send + Sync {
+    fn rerank(&self, query: &str, candidates: &[CandidateView<'_>]) -> Vec<f64>;
+}
+
+/// Reranker that reproduces the geometric order: each candidate's score
+/// divided by the best score, so any blend weight is a no-op.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReranker;
+
+impl Reranker for NoopReranker {
+    fn rerank(&self, _query: &str, candidates: &[CandidateView<'_>]) -> Vec<f64> {
+        let max = candidates.iter().map(|c| c.score).fold(0.0_f64, f64::max);
+        candidates
+            .iter()
+            .map(|c| if max > 0.0 { c.score / max } else { 0.0 })
+            .collect()
+    }
+}
+
+/// Reference reranker: the fraction of distinct query tokens that appear
+/// in the candidate text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LexicalOverlapReranker;
+
+impl Reranker for LexicalOverlapReranker {
+    fn rerank(&self, query: &str, candidates: &[CandidateView<'_>]) -> Vec<f64> {
+        let query_tokens: HashSet<String> = tokenize(query).into_iter().collect();
+        if query_tokens.is_empty() {
+            return vec![0.0; candidates.len()];
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let total = query_tokens.len() as f64;
+        candidates
+            .iter()
+            .map(|c| {
+                let text_tokens: HashSet<String> = tokenize(c.text).into_iter().collect();
+                #[allow(clippy::cast_precision_loss)]
+                let hits = query_tokens.intersection(&text_tokens).count() as f64;
+                hits / total
+            })
+            .collect()
+    }
+}
+
+/// A `Reranker` plus how much of the ranking it controls.
+///
+/// The top `top_k` candidates by geometric score are reranked, and each
+/// final score is `(1 - weight) * geometric + weight * rerank * best`,
+/// where `best` is the top geometric score so both terms share a scale.
+/// Candidates outside the top K keep their geometric score.
+#[derive(Clone)]
+pub struct RerankStage {
+    pub reranker: Arc<dyn Reranker>,
+    pub top_k: usize,
+    pub weight: f64,
+}
+
+impl RerankStage {
+    /// Wrap `reranker` with the default top K and blend weight.
+    pub fn new(reranker: impl Reranker + 'static) -> Self {
+        Self {
+            reranker: Arc::new(reranker),
+            top_k: DEFAULT_RERANK_TOP_K,
+            weight: DEFAULT_RERANK_WEIGHT,
+        }
+    }
+}
+
+impl fmt::Debug for RerankStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RerankStage")
+            .field("top_k", &self.top_k)
+            .field("weight", &self.weight)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Blend the system's `RerankStage` (if any) into the top-K candidates.
+pub(crate) fn apply_rerank(system: &DAESystem, query: &str, candidates: &mut [RankedCandidate]) {
+    let Some(stage) = system.rerank_stage() else {
+        return;
+    };
+    if stage.top_k == 0 || candidates.is_empty() {
+        return;
+    }
+
+    let mut top: Vec<usize> = (0..candidates.len()).collect();
+    top.sort_by(|&a, &b| candidates[a].rank_cmp(&candidates[b]));
+    top.truncate(stage.top_k);
+
+    let views: Vec<CandidateView<'_>> = top
+        .iter()
+        .map(|&i| {
+            let c = &candidates[i];
+            CandidateView {
+                neighborhood_id: c.neighborhood_id,
+                category: c.category,
+                score: c.score,
+                text: &c.text,
+                neighborhood_type: c.neighborhood_type,
+            }
+        })
+        .collect();
+    let scores = stage.reranker.rerank(query, &views);
+    if scores.len() != views.len() {
+        return;
+    }
+
+    let best = candidates[top[0]].score;
+    let weight = if stage.weight.is_finite() {
+        stage.weight.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    for (&i, r) in top.iter().zip(scores) {
+        let r = if r.is_finite() {
+            r.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let c = &mut candidates[i];
+        c.score = (1.0 - weight) * c.score + weight * r * best;
+    }
+}
+
+/// Candidate counts from ranking, before any selection or cutoff.
+///
+/// Lets a caller tell "memory has nothing about this" (no activation)
+/// apart from "memory has matches that scored low". A neighborhood that
+/// qualifies as novel is counted in both `subconscious` and `novel`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CandidateCounts {
+    pub conscious: usize,
+    pub subconscious: usize,
+    pub novel: usize,
+    /// Distinct query words that activated at least one occurrence.
+    pub activated_words: usize,
+    /// Candidates scoring at or above the overflow score cutoff.
+    pub above_cutoff: usize,
+    /// Neighborhoods dropped by `ComposeConfig` cutoffs (not counted above).
+    pub filtered: usize,
+}
+
+impl CandidateCounts {
+    fn from_ranked(
+        system: &DAESystem,
+        query_result: &QueryResult,
+        candidates: &[RankedCandidate],
+        filtered: &[FilteredCandidate],
+    ) -> Self {
+        let activation = &query_result.activation;
+        let activated_words: HashSet<String> = activation
+            .subconscious
+            .iter()
+            .chain(&activation.conscious)
+            .map(|r| system.get_occurrence(*r).word.to_lowercase())
+            .collect();
+        let mut counts = Self {
+            activated_words: activated_words.len(),
+            filtered: filtered.len(),
+            ..Self::default()
+        };
+        for c in candidates {
+            match c.category {
+                RecallCategory::Conscious => counts.conscious += 1,
+                RecallCategory::Subconscious => counts.subconscious += 1,
+                RecallCategory::Novel => counts.novel += 1,
+            }
+            if c.score >= MIN_SCORE_THRESHOLD {
+                counts.above_cutoff += 1;
+            }
+        }
+        counts
+    }
+
+    /// Total candidates across categories (novel overlaps subconscious).
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.conscious + self.subconscious + self.novel
+    }
+
+    /// Hint for the caller when recall is empty or weak, `None` otherwise.
+    #[must_use]
+    pub fn suggestion(&self) -> Option<&'static str> {
+        if self.activated_words == 0 {
+            Some("no activation - try more specific terms")
+        } else if self.total() == 0 {
+            Some("activated words matched no current memories - try different terms")
+        } else if self.above_cutoff == 0 {
+            Some("candidates exist but scored low - broaden the query")
+        } else {
+            None
+        }
+    }
+}
+
+/// Why a composed context came back empty, so the caller can say so
+/// instead of guessing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyReason {
+    /// No query word activated anything in memory.
+    NoMatchingWords,
+    /// Candidates existed, but all were already recalled this session and
+    /// decayed below `ComposeConfig::min_score`.
+    AllDeduped,
+    /// Words activated, but every match fell to a score cutoff, the token
+    /// budget, or supersession.
+    BelowThresholds,
+}
+
+impl EmptyReason {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NoMatchingWords => "no_matching_words",
+            Self::AllDeduped => "all_deduped",
+            Self::BelowThresholds => "below_thresholds",
+        }
+    }
+
+    /// Reason for an empty selection, given the counts from ranking and
+    /// how many candidates session decay removed. Checked in order:
+    /// no activation, then everything deduplicated, then cutoffs.
+    fn classify(counts: &CandidateCounts, remaining: usize, deduped: usize) -> Self {
+        if counts.activated_words == 0 {
+            Self::NoMatchingWords
+        } else if deduped > 0 && remaining == 0 {
+            Self::AllDeduped
+        } else {
+            Self::BelowThresholds
+        }
+    }
+}
+
+/// How far a composed context can be trusted, from the score of its best
+/// entry. `None` means nothing was recalled; `Low` means something was,
+/// but only weak matches an agent should not lean on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// Level for a context whose best entry scored `top_score`, in a
+    /// system of `neighborhoods` neighborhoods (`None` when nothing was
+    /// included).
+    ///
+    /// A word found in one neighborhood adds about 1.0 to its score, and
+    /// a word found in `k` adds `1/k`, so a raw score counts the distinct
+    /// words a match shares with the query. A bigger brain turns up a
+    /// strong-looking best match by chance more often, and its activation
+    /// counts are higher, so the score is damped by the brain's size:
+    ///
+    /// `strength = top_score / (1 + log10(1 + neighborhoods))`
+    ///
+    /// `Low` below [`CONFIDENCE_MEDIUM_STRENGTH`], `High` from
+    /// [`CONFIDENCE_HIGH_STRENGTH`], `Medium` in between.
+    #[must_use]
+    pub fn from_top_score(top_score: Option<f64>, neighborhoods: usize) -> Self {
+        let Some(score) = top_score else {
+            return Self::None;
+        };
+        let strength = score / (1.0 + (1.0 + neighborhoods as f64).log10());
+        if strength >= CONFIDENCE_HIGH_STRENGTH {
+            Self::High
+        } else if strength >= CONFIDENCE_MEDIUM_STRENGTH {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+
+    fn of_scores(system: &DAESystem, scores: impl Iterator<Item = f64>) -> Self {
+        Self::from_top_score(scores.reduce(f64::max), system.total_neighborhoods())
+    }
+}
+
 /// Result of context composition.
 pub struct ContextResult {
     pub context: String,
@@ -55,9 +614,18 @@ pub struct ContextResult {
     pub recalled_ids: CategorizedIds,
     /// Estimated LLM token cost of the recalled content.
     pub token_estimate: TokenEstimate,
+    /// Candidate counts before selection.
+    pub candidates: CandidateCounts,
+    /// Neighborhoods dropped by the system's `ComposeConfig` cutoffs.
+    pub filtered: Vec<FilteredCandidate>,
+    /// Set only when nothing was selected.
+    pub empty_reason: Option<EmptyReason>,
+    /// Trust in the selection, from its best score.
+    pub confidence: Confidence,
 }
 
 /// Configuration for budget-constrained context composition.
+#[derive(Debug, Clone, Copy)]
 pub struct BudgetConfig {
     /// Maximum token budget for the composed context.
     pub max_tokens: usize,
@@ -67,6 +635,12 @@ pub struct BudgetConfig {
     pub min_subconscious: usize,
     /// Minimum novel connection entries to include (if available).
     pub min_novel: usize,
+    /// Largest share of the composed context that novel connections may
+    /// take, as a fraction of `tokens_used`. Applies to `min_novel` too.
+    pub novel_budget_fraction: f64,
+    /// Top-ranked candidates to return in [`BudgetedContextResult::ranked`],
+    /// selected or not. 0 returns none.
+    pub candidate_limit: usize,
 }
 
 impl Default for BudgetConfig {
@@ -76,6 +650,8 @@ impl Default for BudgetConfig {
             min_conscious: 1,
             min_subconscious: 1,
             min_novel: 0,
+            novel_budget_fraction: DEFAULT_NOVEL_BUDGET_FRACTION,
+            candidate_limit: 0,
         }
     }
 }
@@ -90,6 +666,24 @@ pub struct IncludedFragment {
     pub tokens: usize,
     pub text: String,
     pub neighborhood_type: NeighborhoodType,
+    /// Set for novel connections only.
+    pub bridge: Option<NovelBridge>,
+}
+
+/// A ranked candidate, whether or not it fit the budget, for callers
+/// that rerank recall themselves.
+#[derive(Debug)]
+pub struct RankedFragment {
+    pub neighborhood_id: Uuid,
+    pub episode_name: String,
+    pub category: RecallCategory,
+    /// Score as selection saw it, after diminishing returns.
+    pub score: f64,
+    /// Estimated LLM tokens of the text alone, without entry framing.
+    pub tokens: usize,
+    pub text: String,
+    /// Whether it is in the composed context.
+    pub included: bool,
 }
 
 /// Result of budget-constrained context composition.
@@ -102,44 +696,81 @@ pub struct BudgetedContextResult {
     pub tokens_budget: usize,
     /// Estimated LLM token cost of the recalled content.
     pub token_estimate: TokenEstimate,
+    /// Candidate counts before selection.
+    pub candidates: CandidateCounts,
+    /// Neighborhoods dropped by the system's `ComposeConfig` cutoffs.
+    pub filtered: Vec<FilteredCandidate>,
+    /// Set only when nothing was selected.
+    pub empty_reason: Option<EmptyReason>,
+    /// Trust in the selection, from its best score.
+    pub confidence: Confidence,
+    /// Up to `BudgetConfig::candidate_limit` candidates in rank order, one
+    /// per neighborhood.
+    pub ranked: Vec<RankedFragment>,
 }
 
 /// Format a single entry for the composed context string.
 fn format_entry(
+    labels: &LabelSet,
     category: RecallCategory,
     index: usize,
     ep_name: &str,
     text: &str,
     nbhd_type: NeighborhoodType,
+    bridge: Option<&NovelBridge>,
 ) -> Vec<String> {
     let mut lines = Vec::new();
     match category {
         RecallCategory::Conscious => {
-            lines.push("CONSCIOUS RECALL:".to_string());
-            lines.push("[Source: Previously marked salient]".to_string());
+            lines.push(labels.conscious.clone());
+            lines.push(labels.conscious_source.clone());
         }
         RecallCategory::Subconscious => {
-            lines.push(format!("SUBCONSCIOUS RECALL {index}:"));
-            lines.push(format!("[Source: {ep_name}]"));
+            lines.push(labels.subconscious.replace("{index}", &index.to_string()));
+            lines.push(labels.source.replace("{source}", ep_name));
         }
         RecallCategory::Novel => {
-            lines.push("NOVEL CONNECTION:".to_string());
-            lines.push(format!("[Source: {ep_name}]"));
+            match bridge.filter(|b| !b.via.is_empty()) {
+                Some(bridge) => {
+                    lines.push(labels.novel_bridge.replace("{bridge}", &bridge.to_string()));
+                }
+                None => lines.push(labels.novel.clone()),
+            }
+            lines.push(labels.source.replace("{source}", ep_name));
         }
     }
     // Decisions get [DECIDED] prefix so the AI knows not to re-litigate
-    let formatted_text = if nbhd_type == NeighborhoodType::Decision {
-        format!("[DECIDED] {text}")
-    } else if nbhd_type == NeighborhoodType::Preference {
-        format!("[PREFERENCE] {text}")
-    } else {
-        text.to_string()
+    let formatted_text = match nbhd_type {
+        NeighborhoodType::Decision => format!("{} {text}", labels.decided),
+        NeighborhoodType::Preference => format!("{} {text}", labels.preference),
+        // Plans keep one step per line so the sequence stays readable
+        NeighborhoodType::Plan => format!("{}\n{}", labels.plan, format_plan_steps(text)),
+        _ => text.to_string(),
     };
     lines.push(format!("\"{formatted_text}\""));
     lines
 }
 
-const ENTRY_HEADER_OVERHEAD_TOKENS: usize = 20;
+/// Budget cost of an entry's framing: its header and source lines, type
+/// prefix, and quotes, as rendered with `labels`. Counted on top of the
+/// entry's own text.
+fn entry_overhead_tokens(
+    labels: &LabelSet,
+    candidate: &RankedCandidate,
+    index: usize,
+    ep_name: &str,
+) -> usize {
+    let framing = format_entry(
+        labels,
+        candidate.category,
+        index,
+        ep_name,
+        "",
+        candidate.neighborhood_type,
+        candidate.bridge.as_ref(),
+    );
+    estimate_llm_tokens(&framing.join("\n"))
+}
 
 /// Apply diminishing returns to previously-recalled candidates.
 /// Decision/Preference types get softer decay (0.5x rate) instead of full exemption.
@@ -162,6 +793,18 @@ fn apply_diminishing_returns(
         .collect()
 }
 
+/// Drop previously-recalled candidates whose decayed score fell below
+/// `min_score`. Returns how many were dropped.
+fn drop_decayed(
+    candidates: &mut Vec<RankedCandidate>,
+    recalled: &HashMap<Uuid, u32>,
+    min_score: f64,
+) -> usize {
+    let before = candidates.len();
+    candidates.retain(|c| !recalled.contains_key(&c.neighborhood_id) || c.score >= min_score);
+    before - candidates.len()
+}
+
 /// Compose human-readable context from surface and activation results.
 ///
 /// `session_recalled` tracks how many times each neighborhood ID has been
@@ -201,11 +844,18 @@ pub fn compose_context(
     query_result: &QueryResult,
     session_recalled: Option<&HashMap<Uuid, u32>>,
 ) -> ContextResult {
-    let candidates = rank_candidates(system, query_result, &query_result.interference, surface);
+    system.rebuild_indexes();
+    let (mut candidates, filtered) =
+        rank_candidates(system, query_result, &query_result.interference, surface);
+    apply_rerank(system, &query_result.query, &mut candidates);
+    let counts = CandidateCounts::from_ranked(system, query_result, &candidates, &filtered);
 
     let empty_map = HashMap::new();
     let recalled = session_recalled.unwrap_or(&empty_map);
-    let candidates = apply_diminishing_returns(candidates, recalled);
+    let mut candidates = apply_diminishing_returns(candidates, recalled);
+    let deduped = drop_decayed(&mut candidates, recalled, system.compose_config().min_score);
+    let labels = system.compose_config().labels.clone();
+    let highlighter = highlighter(system, query_result);
 
     let mut selected_ids: HashSet<Uuid> = HashSet::new();
     let mut parts: Vec<String> = Vec::new();
@@ -227,18 +877,20 @@ pub fn compose_context(
         .iter()
         .filter(|c| c.category == RecallCategory::Conscious)
         .collect();
-    con.sort_by(|a, b| b.score.total_cmp(&a.score));
+    con.sort_by(|a, b| a.rank_cmp(b));
 
     if let Some(best) = con.first() {
         selected_ids.insert(best.neighborhood_id);
         conscious_ids.push(best.neighborhood_id);
         te_conscious += estimate_llm_tokens(&best.text);
         let entry = format_entry(
+            &labels,
             RecallCategory::Conscious,
             0,
             "",
-            &best.text,
+            &highlighted(highlighter.as_ref(), &best.text),
             best.neighborhood_type,
+            best.bridge.as_ref(),
         );
         parts.extend(entry);
         metrics.conscious = 1;
@@ -251,7 +903,7 @@ pub fn compose_context(
             c.category == RecallCategory::Subconscious && !selected_ids.contains(&c.neighborhood_id)
         })
         .collect();
-    sub.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sub.sort_by(|a, b| a.rank_cmp(b));
 
     for (i, entry) in sub.iter().take(2).enumerate() {
         selected_ids.insert(entry.neighborhood_id);
@@ -262,11 +914,13 @@ pub fn compose_context(
             parts.push(String::new());
         }
         let lines = format_entry(
+            &labels,
             RecallCategory::Subconscious,
             i + 1,
             &ep_name,
-            &entry.text,
+            &highlighted(highlighter.as_ref(), &entry.text),
             entry.neighborhood_type,
+            entry.bridge.as_ref(),
         );
         parts.extend(lines);
         metrics.subconscious += 1;
@@ -279,7 +933,7 @@ pub fn compose_context(
             c.category == RecallCategory::Novel && !selected_ids.contains(&c.neighborhood_id)
         })
         .collect();
-    novel.sort_by(|a, b| b.score.total_cmp(&a.score));
+    novel.sort_by(|a, b| a.rank_cmp(b));
 
     if let Some(best) = novel.first() {
         selected_ids.insert(best.neighborhood_id);
@@ -290,16 +944,29 @@ pub fn compose_context(
             parts.push(String::new());
         }
         let lines = format_entry(
+            &labels,
             RecallCategory::Novel,
             0,
             &ep_name,
-            &best.text,
+            &highlighted(highlighter.as_ref(), &best.text),
             best.neighborhood_type,
+            best.bridge.as_ref(),
         );
         parts.extend(lines);
         metrics.novel = 1;
     }
 
+    let empty_reason = selected_ids
+        .is_empty()
+        .then(|| EmptyReason::classify(&counts, candidates.len(), deduped));
+    let confidence = Confidence::of_scores(
+        system,
+        candidates
+            .iter()
+            .filter(|c| selected_ids.contains(&c.neighborhood_id))
+            .map(|c| c.score),
+    );
+
     ContextResult {
         context: parts.join("\n"),
         metrics,
@@ -315,6 +982,10 @@ pub fn compose_context(
             novel: te_novel,
             total: te_conscious + te_subconscious + te_novel,
         },
+        candidates: counts,
+        filtered,
+        empty_reason,
+        confidence,
     }
 }
 
@@ -335,30 +1006,50 @@ pub fn compose_context_budgeted(
     budget: &BudgetConfig,
     session_recalled: Option<&HashMap<Uuid, u32>>,
 ) -> BudgetedContextResult {
-    let candidates = rank_candidates(system, query_result, &query_result.interference, surface);
+    system.rebuild_indexes();
+    compose_budgeted_indexed(system, surface, query_result, budget, session_recalled)
+}
+
+/// [`compose_context_budgeted`] through a shared reference, for callers
+/// that have already rebuilt the indexes. The batch engine composes its
+/// queries in parallel this way.
+pub(crate) fn compose_budgeted_indexed(
+    system: &DAESystem,
+    surface: &SurfaceResult,
+    query_result: &QueryResult,
+    budget: &BudgetConfig,
+    session_recalled: Option<&HashMap<Uuid, u32>>,
+) -> BudgetedContextResult {
+    let (mut candidates, filtered) =
+        rank_candidates(system, query_result, &query_result.interference, surface);
+    apply_rerank(system, &query_result.query, &mut candidates);
+    let counts = CandidateCounts::from_ranked(system, query_result, &candidates, &filtered);
 
     let empty_map = HashMap::new();
     let recalled = session_recalled.unwrap_or(&empty_map);
-    let candidates = apply_diminishing_returns(candidates, recalled);
+    let mut candidates = apply_diminishing_returns(candidates, recalled);
+    let deduped = drop_decayed(&mut candidates, recalled, system.compose_config().min_score);
+    let labels = system.compose_config().labels.clone();
+    let highlighter = highlighter(system, query_result);
 
     // Split candidates by category, sorted by score desc
     let mut conscious: Vec<&RankedCandidate> = candidates
         .iter()
         .filter(|c| c.category == RecallCategory::Conscious)
         .collect();
-    conscious.sort_by(|a, b| b.score.total_cmp(&a.score));
+    conscious.sort_by(|a, b| a.rank_cmp(b));
 
     let mut subconscious: Vec<&RankedCandidate> = candidates
         .iter()
         .filter(|c| c.category == RecallCategory::Subconscious)
         .collect();
-    subconscious.sort_by(|a, b| b.score.total_cmp(&a.score));
+    subconscious.sort_by(|a, b| a.rank_cmp(b));
 
     let mut novel: Vec<&RankedCandidate> = candidates
         .iter()
         .filter(|c| c.category == RecallCategory::Novel)
         .collect();
-    novel.sort_by(|a, b| b.score.total_cmp(&a.score));
+    novel.sort_by(|a, b| a.rank_cmp(b));
 
     // Deduplicate: a neighborhood can appear as both Subconscious and Novel.
     // Track which neighborhood_ids are included to avoid duplicates.
@@ -368,7 +1059,6 @@ pub fn compose_context_budgeted(
     // Count unique neighborhoods across all categories (a neighborhood may appear as both Subconscious and Novel)
     let unique_candidate_ids: HashSet<Uuid> =
         candidates.iter().map(|c| c.neighborhood_id).collect();
-    let total_unique_candidates = unique_candidate_ids.len();
 
     let try_add = |candidate: &RankedCandidate,
                    selected_ids: &mut HashSet<Uuid>,
@@ -380,13 +1070,27 @@ pub fn compose_context_budgeted(
         if selected_ids.contains(&candidate.neighborhood_id) {
             return false;
         }
-        let cost = candidate.tokens + ENTRY_HEADER_OVERHEAD_TOKENS;
+        let ep_name = get_episode_name(system, candidate.episode_ref);
+        let labels = &system.compose_config().labels;
+        let cost = candidate.tokens
+            + entry_overhead_tokens(labels, candidate, included.len() + 1, &ep_name);
         if *tokens_used + cost > budget_limit {
             return false;
         }
+        // Novelty must stay within its share of the context, counting itself
+        if candidate.category == RecallCategory::Novel {
+            let novel_used: usize = included
+                .iter()
+                .filter(|f| f.category == RecallCategory::Novel)
+                .map(|f| f.tokens)
+                .sum();
+            let cap = budget.novel_budget_fraction * (*tokens_used + cost) as f64;
+            if (novel_used + cost) as f64 > cap {
+                return false;
+            }
+        }
         selected_ids.insert(candidate.neighborhood_id);
         *tokens_used += cost;
-        let ep_name = get_episode_name(system, candidate.episode_ref);
         included.push(IncludedFragment {
             neighborhood_id: candidate.neighborhood_id,
             episode_name: ep_name,
@@ -395,6 +1099,7 @@ pub fn compose_context_budgeted(
             tokens: cost,
             text: candidate.text.clone(),
             neighborhood_type: candidate.neighborhood_type,
+            bridge: candidate.bridge.clone(),
         });
         true
     };
@@ -458,12 +1163,28 @@ pub fn compose_context_budgeted(
         .iter()
         .filter(|c| !selected_ids.contains(&c.neighborhood_id) && c.score >= MIN_SCORE_THRESHOLD)
         .collect();
-    remaining.sort_by(|a, b| b.score.total_cmp(&a.score));
+    remaining.sort_by(|a, b| a.rank_cmp(b));
 
+    // Novel candidates refused by the novelty cap get another chance once
+    // the rest of the context has grown.
+    let mut deferred: Vec<&RankedCandidate> = Vec::new();
     for c in &remaining {
         if tokens_used >= budget.max_tokens {
             break;
         }
+        let added = try_add(
+            c,
+            &mut selected_ids,
+            &mut included,
+            &mut tokens_used,
+            budget.max_tokens,
+            system,
+        );
+        if !added && c.category == RecallCategory::Novel {
+            deferred.push(c);
+        }
+    }
+    for c in deferred {
         try_add(
             c,
             &mut selected_ids,
@@ -474,7 +1195,19 @@ pub fn compose_context_budgeted(
         );
     }
 
-    let excluded_count = total_unique_candidates.saturating_sub(included.len());
+    expand_groups(
+        system,
+        &mut included,
+        &mut selected_ids,
+        &mut tokens_used,
+        budget.max_tokens,
+    );
+
+    let excluded_count = unique_candidate_ids
+        .iter()
+        .filter(|id| !selected_ids.contains(id))
+        .count();
+    let ranked = ranked_fragments(system, &candidates, &selected_ids, budget.candidate_limit);
 
     // Format output, grouping by category in standard order
     let mut parts: Vec<String> = Vec::new();
@@ -494,11 +1227,13 @@ pub fn compose_context_budgeted(
             parts.push(String::new());
         }
         let lines = format_entry(
+            &labels,
             RecallCategory::Conscious,
             0,
             "",
-            &entry.text,
+            &highlighted(highlighter.as_ref(), &entry.text),
             entry.neighborhood_type,
+            entry.bridge.as_ref(),
         );
         parts.extend(lines);
         metrics.conscious += 1;
@@ -514,11 +1249,13 @@ pub fn compose_context_budgeted(
             parts.push(String::new());
         }
         let lines = format_entry(
+            &labels,
             RecallCategory::Subconscious,
             i + 1,
             &entry.episode_name,
-            &entry.text,
+            &highlighted(highlighter.as_ref(), &entry.text),
             entry.neighborhood_type,
+            entry.bridge.as_ref(),
         );
         parts.extend(lines);
         metrics.subconscious += 1;
@@ -534,11 +1271,13 @@ pub fn compose_context_budgeted(
             parts.push(String::new());
         }
         let lines = format_entry(
+            &labels,
             RecallCategory::Novel,
             0,
             &entry.episode_name,
-            &entry.text,
+            &highlighted(highlighter.as_ref(), &entry.text),
             entry.neighborhood_type,
+            entry.bridge.as_ref(),
         );
         parts.extend(lines);
         metrics.novel += 1;
@@ -561,6 +1300,11 @@ pub fn compose_context_budgeted(
         .map(|f| estimate_llm_tokens(&f.text))
         .sum();
 
+    let empty_reason = included
+        .is_empty()
+        .then(|| EmptyReason::classify(&counts, candidates.len(), deduped));
+    let confidence = Confidence::of_scores(system, included.iter().map(|f| f.score));
+
     BudgetedContextResult {
         context: parts.join("\n"),
         metrics,
@@ -574,9 +1318,104 @@ pub fn compose_context_budgeted(
             novel: te_novel,
             total: te_conscious + te_subconscious + te_novel,
         },
+        candidates: counts,
+        filtered,
+        empty_reason,
+        confidence,
+        ranked,
     }
 }
 
+/// Offer the whole text of a chunked salient memory in place of its
+/// selected chunk when the group fits the remaining budget. Other chunks
+/// of the group that were selected on their own fold into it, giving back
+/// their cost. A group that does not fit keeps just the matched chunk.
+fn expand_groups(
+    system: &DAESystem,
+    included: &mut Vec<IncludedFragment>,
+    selected_ids: &mut HashSet<Uuid>,
+    tokens_used: &mut usize,
+    max_tokens: usize,
+) {
+    let mut i = 0;
+    while i < included.len() {
+        let fragment = &included[i];
+        let group_id = (fragment.category == RecallCategory::Conscious)
+            .then(|| {
+                system
+                    .conscious_episode
+                    .neighborhoods
+                    .iter()
+                    .find(|n| n.id == fragment.neighborhood_id)
+            })
+            .flatten()
+            .and_then(|n| n.group_id);
+        let members = group_id
+            .map(|g| system.conscious_group(g))
+            .unwrap_or_default();
+        if members.len() < 2 {
+            i += 1;
+            continue;
+        }
+        let text = members
+            .iter()
+            .map(|n| n.source_text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let absorbed: Vec<usize> = (0..included.len())
+            .filter(|&j| j != i && members.iter().any(|m| m.id == included[j].neighborhood_id))
+            .collect();
+        let refund: usize = absorbed.iter().map(|&j| included[j].tokens).sum();
+        let cost = fragment.tokens - token_count(&fragment.text) + token_count(&text);
+        let total = *tokens_used - refund - fragment.tokens + cost;
+        if total > max_tokens {
+            i += 1;
+            continue;
+        }
+        *tokens_used = total;
+        selected_ids.extend(members.iter().map(|m| m.id));
+        included[i].text = text;
+        included[i].tokens = cost;
+        for &j in absorbed.iter().rev() {
+            included.remove(j);
+            if j < i {
+                i -= 1;
+            }
+        }
+        i += 1;
+    }
+}
+
+/// The best `limit` candidates in rank order, keeping a neighborhood that
+/// ranks in two categories only where it ranks higher.
+fn ranked_fragments(
+    system: &DAESystem,
+    candidates: &[RankedCandidate],
+    selected_ids: &HashSet<Uuid>,
+    limit: usize,
+) -> Vec<RankedFragment> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let mut sorted: Vec<&RankedCandidate> = candidates.iter().collect();
+    sorted.sort_by(|a, b| a.rank_cmp(b));
+    let mut seen = HashSet::new();
+    sorted
+        .into_iter()
+        .filter(|c| seen.insert(c.neighborhood_id))
+        .take(limit)
+        .map(|c| RankedFragment {
+            neighborhood_id: c.neighborhood_id,
+            episode_name: get_episode_name(system, c.episode_ref),
+            category: c.category,
+            score: c.score,
+            tokens: estimate_llm_tokens(&c.text),
+            text: c.text.clone(),
+            included: selected_ids.contains(&c.neighborhood_id),
+        })
+        .collect()
+}
+
 /// Compact index entry for two-phase retrieval.
 /// ~50-100 tokens per entry vs ~500-1000 for full content.
 pub struct IndexEntry {
@@ -620,7 +1459,10 @@ pub fn compose_index(
     query_result: &QueryResult,
     session_recalled: Option<&HashMap<Uuid, u32>>,
 ) -> IndexResult {
-    let candidates = rank_candidates(system, query_result, &query_result.interference, surface);
+    system.rebuild_indexes();
+    let (mut candidates, _) =
+        rank_candidates(system, query_result, &query_result.interference, surface);
+    apply_rerank(system, &query_result.query, &mut candidates);
     let total_candidates = candidates.len();
 
     // Deduplicate: same neighborhood may appear in multiple categories,
@@ -652,7 +1494,11 @@ pub fn compose_index(
         })
         .collect();
 
-    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.sort_by(|a, b| {
+        score_desc(a.1, b.1)
+            .then(a.0.seq.cmp(&b.0.seq))
+            .then(a.0.neighborhood_id.cmp(&b.0.neighborhood_id))
+    });
 
     let mut total_tokens_if_fetched = 0;
     let entries: Vec<IndexEntry> = scored
@@ -694,6 +1540,84 @@ pub fn compose_index(
     }
 }
 
+/// What memory holds about a query, without recalling any of it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProbeResult {
+    pub candidates: CandidateCounts,
+    /// Best candidate score before reranking, `None` without candidates.
+    pub top_score: Option<f64>,
+}
+
+impl ProbeResult {
+    /// Whether any neighborhood would be a recall candidate.
+    #[must_use]
+    pub fn is_known(&self) -> bool {
+        self.candidates.total() > 0
+    }
+}
+
+/// Cheap existence check: rank a query's candidates the way recall would,
+/// but through [`peek`], so nothing is drifted or composed.
+pub fn probe(system: &mut DAESystem, query: &str) -> ProbeResult {
+    peek(system, query, |system, query_result, surface| {
+        system.rebuild_indexes();
+        let (candidates, filtered) =
+            rank_candidates(system, query_result, &query_result.interference, surface);
+        ProbeResult {
+            candidates: CandidateCounts::from_ranked(system, query_result, &candidates, &filtered),
+            top_score: candidates.iter().map(|c| c.score).reduce(f64::max),
+        }
+    })
+}
+
+/// Every neighborhood `query` ranks, best first and each once, as recall
+/// would score them, leaving the system as it was (see [`peek`]). For
+/// reviewing what a query matches before acting on it, as
+/// `am forget --query` does.
+pub fn ranked_matches(system: &mut DAESystem, query: &str) -> Vec<RankedFragment> {
+    peek(system, query, |system, query_result, surface| {
+        system.rebuild_indexes();
+        let (candidates, _) =
+            rank_candidates(system, query_result, &query_result.interference, surface);
+        ranked_fragments(system, &candidates, &HashSet::new(), candidates.len())
+    })
+}
+
+/// Run `f` on a query as recall would see it, without learning from it:
+/// the query goes through [`QueryEngine::probe`], so nothing is drifted or
+/// coupled, and the manifest `f` sees is empty.
+///
+/// Scores weigh activation counts, and a real query counts its own
+/// activation before ranking. `peek` does the same, then restores every
+/// count once `f` returns, so the system is left exactly as it was. Pass
+/// `None` for `session_recalled` inside `f` to keep the session's
+/// diminishing returns untouched as well.
+pub fn peek<T>(
+    system: &mut DAESystem,
+    query: &str,
+    f: impl FnOnce(&mut DAESystem, &QueryResult, &SurfaceResult) -> T,
+) -> T {
+    let query_result = QueryEngine::probe(system, query);
+    let activation = &query_result.activation;
+    let saved: Vec<(OccurrenceRef, u32)> = activation
+        .subconscious
+        .iter()
+        .chain(&activation.conscious)
+        .map(|r| (*r, system.get_occurrence(*r).activation_count))
+        .collect();
+    for (r, _) in &saved {
+        system.get_occurrence_mut(*r).activate();
+    }
+
+    let surface = compute_surface(system, &query_result);
+    let result = f(system, &query_result, &surface);
+
+    for (r, count) in saved {
+        system.get_occurrence_mut(r).activation_count = count;
+    }
+    result
+}
+
 /// Retrieve full content for specific neighborhood IDs.
 /// Phase 2 of two-phase retrieval: after reviewing the index, fetch
 /// only the neighborhoods you actually need.
@@ -734,6 +1658,7 @@ pub fn retrieve_by_ids(system: &mut DAESystem, ids: &[Uuid]) -> Vec<IncludedFrag
             tokens: token_count(&text),
             text,
             neighborhood_type: nbhd.neighborhood_type,
+            bridge: None,
         });
     }
 
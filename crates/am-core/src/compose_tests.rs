@@ -1,9 +1,14 @@
 use super::*;
+use crate::constants::DEFAULT_PROJECT_DISCOUNT;
 use crate::episode::Episode;
 use crate::neighborhood::Neighborhood;
 use crate::query::QueryEngine;
-use crate::salient::{detect_neighborhood_type, extract_salient, mark_salient_typed};
+use crate::salient::{
+    chunk_salient, detect_neighborhood_type, extract_salient, extract_stated_salient,
+    looks_like_plan, mark_salient, mark_salient_typed, tag_plans,
+};
 use crate::scoring::idf_weighted_overlap;
+use crate::scoring::{normalize_novel_scores, rank_candidates};
 use crate::surface::compute_surface;
 use crate::system::EpisodeRef;
 use rand::SeedableRng;
@@ -133,6 +138,32 @@ fn test_extract_salient_multiple() {
     assert_eq!(sys.conscious_episode.neighborhoods.len(), 2);
 }
 
+#[test]
+fn test_extract_stated_salient_from_conversation() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    sys.add_to_conscious("keep the WAL", &mut rng);
+    let text = "Should we keep the WAL?\n\
+        DECISION: keep the WAL\n\
+        Agreed. <salient>PREFERENCE: short commit subjects</salient>\n\
+        - DECISION: ship the CLI first\n\
+        We discussed a DECISION: mid-sentence, which does not count.\n\
+        DECISION:\n";
+    let ids = extract_stated_salient(&mut sys, text, &mut rng);
+    assert_eq!(ids.len(), 2);
+    let added: Vec<(NeighborhoodType, &str)> = sys.conscious_episode.neighborhoods[1..]
+        .iter()
+        .map(|n| (n.neighborhood_type, n.source_text.as_str()))
+        .collect();
+    assert_eq!(
+        added,
+        [
+            (NeighborhoodType::Preference, "short commit subjects"),
+            (NeighborhoodType::Decision, "ship the CLI first"),
+        ]
+    );
+}
+
 #[test]
 fn test_deterministic_scoring() {
     let mut sys1 = make_full_system();
@@ -161,6 +192,7 @@ fn test_budgeted_respects_token_limit() {
         min_conscious: 0,
         min_subconscious: 0,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -183,6 +215,7 @@ fn test_budgeted_includes_minimums() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -210,6 +243,7 @@ fn test_budgeted_tracks_excluded() {
         min_conscious: 0,
         min_subconscious: 0,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -219,6 +253,51 @@ fn test_budgeted_tracks_excluded() {
     );
 }
 
+#[test]
+fn test_budgeted_returns_ranked_candidates_beyond_budget() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "quantum physics neural");
+    let surface = compute_surface(&sys, &result);
+
+    let budget = BudgetConfig {
+        max_tokens: 30,
+        min_conscious: 0,
+        min_subconscious: 0,
+        min_novel: 0,
+        ..BudgetConfig::default()
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert!(ctx.ranked.is_empty(), "off unless asked for");
+
+    let budget = BudgetConfig {
+        candidate_limit: 50,
+        ..budget
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert_eq!(ctx.ranked.len(), ctx.included.len() + ctx.excluded_count);
+    assert!(ctx.ranked.iter().any(|r| !r.included));
+    for f in &ctx.included {
+        assert!(
+            ctx.ranked
+                .iter()
+                .any(|r| r.included && r.neighborhood_id == f.neighborhood_id)
+        );
+    }
+    assert!(
+        ctx.ranked.windows(2).all(|w| w[0].score >= w[1].score),
+        "ranked best first"
+    );
+    let ids: HashSet<Uuid> = ctx.ranked.iter().map(|r| r.neighborhood_id).collect();
+    assert_eq!(ids.len(), ctx.ranked.len(), "one entry per neighborhood");
+
+    let budget = BudgetConfig {
+        candidate_limit: 1,
+        ..budget
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert_eq!(ctx.ranked.len(), 1);
+}
+
 #[test]
 fn test_budgeted_full_budget() {
     let mut sys = make_full_system();
@@ -230,6 +309,7 @@ fn test_budgeted_full_budget() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -276,6 +356,157 @@ fn test_compose_context_unchanged() {
     assert_eq!(ctx.context, ctx2.context);
 }
 
+#[test]
+fn test_default_labels_render_unchanged() {
+    let labels = LabelSet::default();
+    assert_eq!(
+        format_entry(
+            &labels,
+            RecallCategory::Conscious,
+            0,
+            "",
+            "keep it",
+            NeighborhoodType::Decision,
+            None,
+        ),
+        vec![
+            "CONSCIOUS RECALL:",
+            "[Source: Previously marked salient]",
+            "\"[DECIDED] keep it\"",
+        ]
+    );
+    assert_eq!(
+        format_entry(
+            &labels,
+            RecallCategory::Subconscious,
+            2,
+            "Notes",
+            "tabs",
+            NeighborhoodType::Preference,
+            None,
+        ),
+        vec![
+            "SUBCONSCIOUS RECALL 2:",
+            "[Source: Notes]",
+            "\"[PREFERENCE] tabs\"",
+        ]
+    );
+    assert_eq!(
+        format_entry(
+            &labels,
+            RecallCategory::Novel,
+            0,
+            "Notes",
+            "idea",
+            NeighborhoodType::Memory,
+            None,
+        ),
+        vec!["NOVEL CONNECTION:", "[Source: Notes]", "\"idea\""]
+    );
+}
+
+#[test]
+fn test_custom_labels_replace_defaults() {
+    let mut sys = make_full_system();
+    let mut config = ComposeConfig::default();
+    config.labels.conscious = "BEWUSST:".to_string();
+    config.labels.subconscious = "UNTERBEWUSST {index}:".to_string();
+    assert!(
+        config
+            .labels
+            .set("source", "[Quelle: {source}]".to_string())
+    );
+    assert!(!config.labels.set("unknown", String::new()));
+    sys.set_compose_config(config);
+
+    let result = QueryEngine::process_query(&mut sys, "quantum physics neural");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.contains("BEWUSST:"), "{}", ctx.context);
+    assert!(ctx.context.contains("UNTERBEWUSST 1:"));
+    assert!(ctx.context.contains("[Quelle: Science memories]"));
+    assert!(!ctx.context.contains("CONSCIOUS RECALL:"));
+    assert!(!ctx.context.contains("[Source: Science"));
+
+    let budget = BudgetConfig::default();
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert!(ctx.context.contains("UNTERBEWUSST 1:"), "{}", ctx.context);
+}
+
+#[test]
+fn test_highlight_wraps_query_words_in_recalled_text() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "quantum physics");
+    let surface = compute_surface(&sys, &result);
+    let plain = compose_context(&mut sys, &surface, &result, None);
+    assert!(!plain.context.contains("**"), "off by default");
+
+    sys.set_compose_config(ComposeConfig {
+        highlight: Some(Highlight {
+            open: "**".to_string(),
+            close: "**".to_string(),
+        }),
+        ..ComposeConfig::default()
+    });
+    let result = QueryEngine::process_query(&mut sys, "quantum physics");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(
+        ctx.context
+            .contains("\"**quantum** **physics** particle wave\""),
+        "{}",
+        ctx.context
+    );
+    assert!(ctx.context.contains("\"**quantum** computing research\""));
+
+    let budget = BudgetConfig::default();
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert!(
+        ctx.context.contains("**quantum** **physics**"),
+        "{}",
+        ctx.context
+    );
+    // Fragments keep the stored text
+    assert!(ctx.included.iter().all(|f| !f.text.contains("**")));
+}
+
+#[test]
+fn test_highlight_apply_keeps_punctuation_and_case() {
+    let highlight = Highlight {
+        open: "<b>".to_string(),
+        close: "</b>".to_string(),
+    };
+    let words: HashSet<String> = ["crème", "don't"].map(String::from).into();
+    assert_eq!(
+        highlight.apply("Crème brûlée? 'Don't' skip the crème.", &words),
+        "<b>Crème</b> brûlée? '<b>Don't</b>' skip the <b>crème</b>."
+    );
+    assert_eq!(highlight.apply("nothing here", &words), "nothing here");
+}
+
+#[test]
+fn test_entry_overhead_follows_label_length() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "quantum physics neural");
+    let surface = compute_surface(&sys, &result);
+    let budget = BudgetConfig::default();
+    let short = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+
+    let mut config = ComposeConfig::default();
+    config.labels.conscious = "RECALLED FROM THE CONSCIOUS MEMORY OF THIS PROJECT:".repeat(4);
+    sys.set_compose_config(config);
+    let long = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+
+    let cost = |ctx: &BudgetedContextResult| {
+        ctx.included
+            .iter()
+            .find(|f| f.category == RecallCategory::Conscious)
+            .map(|f| f.tokens)
+            .unwrap()
+    };
+    assert!(cost(&long) > cost(&short) + 40);
+}
+
 // =====================================================================
 // Decision-aware tests
 // =====================================================================
@@ -350,6 +581,151 @@ fn test_mark_salient_typed_decision() {
     assert!(!nbhd.source_text.contains("DECISION:"));
 }
 
+const THREE_PARAGRAPHS: &str = "\
+Kafka consumers commit offsets only after the batch is written, so a crash \
+replays the batch instead of dropping it. Consumer groups rebalance when a \
+member stalls for longer than the session timeout.
+
+Postgres holds the canonical order state. Writes go through one primary and \
+replicas serve the dashboards, which tolerate a few seconds of lag. Vacuum \
+runs nightly because the orders table churns heavily.
+
+Redis caches the pricing lookups with a five minute expiry. Eviction uses \
+allkeys-lru, so a cold key may vanish early, and callers must fall back to \
+Postgres rather than treating a miss as an error.";
+
+#[test]
+fn test_chunk_salient_packs_paragraphs_then_sentences() {
+    assert_eq!(chunk_salient("short insight", 600), vec!["short insight"]);
+    assert_eq!(chunk_salient(THREE_PARAGRAPHS, 0).len(), 1);
+
+    let chunks = chunk_salient(THREE_PARAGRAPHS, 300);
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks[0].starts_with("Kafka") && chunks[2].starts_with("Redis"));
+    // Two short paragraphs share a chunk when they fit together
+    assert_eq!(chunk_salient(THREE_PARAGRAPHS, 500).len(), 2);
+    // A paragraph longer than the limit is packed by sentence
+    let sentences = chunk_salient(THREE_PARAGRAPHS, 150);
+    assert!(sentences.len() > 3);
+    assert!(sentences.iter().all(|c| c.chars().count() <= 150));
+}
+
+#[test]
+fn test_long_salient_mark_is_grouped_and_recalled_partially_under_tight_budget() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    sys.set_salient_chunk_chars(300);
+    let first = mark_salient_typed(&mut sys, THREE_PARAGRAPHS, &mut rng);
+
+    let chunks = &sys.conscious_episode.neighborhoods;
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].id, first);
+    let group = chunks[0].group_id.expect("chunks share a group");
+    assert!(chunks.iter().all(|n| n.group_id == Some(group)));
+    assert_eq!(sys.conscious_group(group).len(), 3);
+
+    let recall = |sys: &mut DAESystem, max_tokens: usize| {
+        let result = QueryEngine::process_query(sys, "redis eviction expiry");
+        let surface = compute_surface(sys, &result);
+        let budget = BudgetConfig {
+            max_tokens,
+            min_conscious: 1,
+            min_subconscious: 0,
+            min_novel: 0,
+            ..BudgetConfig::default()
+        };
+        compose_context_budgeted(sys, &surface, &result, &budget, None)
+    };
+
+    // Room for the matched chunk only
+    let tight = recall(&mut sys, 70);
+    assert_eq!(tight.metrics.conscious, 1);
+    assert!(tight.context.contains("allkeys-lru"));
+    assert!(!tight.context.contains("Kafka"));
+    assert!(tight.tokens_used <= 70);
+
+    // Room for the whole text: offered once, in order
+    let roomy = recall(&mut sys, 4096);
+    assert_eq!(roomy.metrics.conscious, 1);
+    let kafka = roomy.context.find("Kafka").unwrap();
+    let redis = roomy.context.find("allkeys-lru").unwrap();
+    assert!(kafka < redis);
+    assert_eq!(roomy.included.len(), 1);
+    assert_eq!(roomy.excluded_count, 0);
+}
+
+#[test]
+fn test_repeated_salient_mark_reinforces_existing_memory() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let first = mark_salient(
+        &mut sys,
+        "PREFERENCE: user prefers tabs over spaces",
+        false,
+        &mut rng,
+    );
+    assert!(!first.deduplicated);
+    sys.conscious_episode.neighborhoods[0].created_at = Some("2020-01-01T00:00:00Z".to_string());
+
+    let again = mark_salient(
+        &mut sys,
+        "PREFERENCE: The user prefers tabs over spaces.",
+        false,
+        &mut rng,
+    );
+    assert_eq!(again.id, first.id);
+    assert!(again.deduplicated);
+    let conscious = &sys.conscious_episode.neighborhoods;
+    assert_eq!(conscious.len(), 1);
+    assert!(
+        conscious[0]
+            .occurrences
+            .iter()
+            .all(|o| o.activation_count == 2)
+    );
+    assert_ne!(
+        conscious[0].created_at.as_deref(),
+        Some("2020-01-01T00:00:00Z")
+    );
+
+    // A different insight, the same words under another type, and a forced
+    // repeat are all stored
+    let other = mark_salient(
+        &mut sys,
+        "PREFERENCE: user prefers dark mode",
+        false,
+        &mut rng,
+    );
+    let typed = mark_salient(
+        &mut sys,
+        "DECISION: user prefers tabs over spaces",
+        false,
+        &mut rng,
+    );
+    let forced = mark_salient(
+        &mut sys,
+        "PREFERENCE: user prefers tabs over spaces",
+        true,
+        &mut rng,
+    );
+    for mark in [other, typed, forced] {
+        assert!(!mark.deduplicated);
+        assert_ne!(mark.id, first.id);
+    }
+    assert_eq!(sys.conscious_episode.neighborhoods.len(), 4);
+
+    // Off at 0: the repeat is stored
+    sys.set_salient_dedup_threshold(0.0);
+    let off = mark_salient(
+        &mut sys,
+        "PREFERENCE: user prefers dark mode",
+        false,
+        &mut rng,
+    );
+    assert!(!off.deduplicated);
+    assert_eq!(sys.conscious_episode.neighborhoods.len(), 5);
+}
+
 #[test]
 fn test_decision_flat_score() {
     // Decisions should surface with [DECIDED] prefix when query matches
@@ -415,6 +791,7 @@ fn test_decision_competitive_scoring_high_overlap() {
         min_conscious: 1,
         min_subconscious: 0,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -465,6 +842,7 @@ fn test_decision_competitive_scoring_low_overlap() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -536,6 +914,7 @@ fn test_session_diminishing_returns_non_decisions() {
         min_conscious: 0,
         min_subconscious: 2,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, Some(&recalled));
 
@@ -585,6 +964,7 @@ fn test_session_diminishing_returns_decisions_softer() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx1 = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -805,6 +1185,56 @@ fn test_superseded_decision_excluded() {
     );
 }
 
+#[test]
+fn test_actor_attribution_and_filter() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+
+    sys.set_actor(Some("claude-code".to_string()));
+    sys.add_to_conscious("deployment runs through the blue cluster", &mut rng);
+    let mut ep = Episode::new("claude session");
+    ep.add_neighborhood(Neighborhood::from_tokens(
+        &to_tokens(&["deployment", "canary", "rollout"]),
+        None,
+        "deployment canary rollout",
+        &mut rng,
+    ));
+    sys.add_episode(ep);
+
+    sys.set_actor(Some("codex".to_string()));
+    sys.add_to_conscious("deployment runs through the green cluster", &mut rng);
+
+    assert_eq!(sys.episodes[0].actor.as_deref(), Some("claude-code"));
+    let actors: Vec<_> = sys
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.actor.as_deref())
+        .collect();
+    assert_eq!(actors, vec![Some("claude-code"), Some("codex")]);
+
+    sys.set_actor_filter(Some("claude-code".to_string()));
+    let result = QueryEngine::process_query(&mut sys, "deployment cluster");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(
+        ctx.context.contains("blue cluster"),
+        "filtered actor's memory should surface, got:\n{}",
+        ctx.context,
+    );
+    assert!(
+        !ctx.context.contains("green cluster"),
+        "other actor's memory should be filtered, got:\n{}",
+        ctx.context,
+    );
+
+    sys.set_actor_filter(None);
+    let result = QueryEngine::process_query(&mut sys, "deployment cluster");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.contains("green cluster"));
+}
+
 // =====================================================================
 // Overlap detection / contradiction suppression tests (ALP-681)
 // =====================================================================
@@ -950,6 +1380,7 @@ fn test_overlap_in_subconscious_episodes() {
         min_conscious: 0,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1014,6 +1445,7 @@ fn test_density_bonus_many_words_scores_higher() {
         min_conscious: 0,
         min_subconscious: 2,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1085,6 +1517,7 @@ fn test_min_score_threshold_excludes_weak_overflow() {
         min_conscious: 0,
         min_subconscious: 1, // Only need 1 minimum
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1129,6 +1562,7 @@ fn test_empty_results_when_nothing_matches() {
         min_conscious: 0,
         min_subconscious: 0,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1160,6 +1594,7 @@ fn test_idf_weighted_overlap_computation() {
         &mut rng,
     ));
     sys.add_episode(ep);
+    sys.rebuild_indexes();
 
     // Identical word sets should have overlap = 1.0
     let words_a: HashSet<String> = ["alpha", "beta"]
@@ -1170,7 +1605,7 @@ fn test_idf_weighted_overlap_computation() {
         .iter()
         .map(std::string::ToString::to_string)
         .collect();
-    let overlap = idf_weighted_overlap(&words_a, &words_b, &mut sys);
+    let overlap = idf_weighted_overlap(&words_a, &words_b, &sys);
     assert!(
         (overlap - 1.0).abs() < 0.01,
         "identical sets should have overlap ~1.0, got {overlap}",
@@ -1181,7 +1616,7 @@ fn test_idf_weighted_overlap_computation() {
         .iter()
         .map(std::string::ToString::to_string)
         .collect();
-    let overlap2 = idf_weighted_overlap(&words_a, &words_c, &mut sys);
+    let overlap2 = idf_weighted_overlap(&words_a, &words_c, &sys);
     assert!(
         overlap2 < 0.01,
         "disjoint sets should have overlap ~0.0, got {overlap2}",
@@ -1189,7 +1624,7 @@ fn test_idf_weighted_overlap_computation() {
 
     // Empty sets
     let empty: HashSet<String> = HashSet::new();
-    let overlap3 = idf_weighted_overlap(&empty, &words_a, &mut sys);
+    let overlap3 = idf_weighted_overlap(&empty, &words_a, &sys);
     assert!(
         overlap3 < 0.01,
         "empty set overlap should be ~0.0, got {overlap3}",
@@ -1359,6 +1794,7 @@ fn test_decision_does_not_surface_on_unrelated_query() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1446,6 +1882,7 @@ fn test_vividness_boost_improves_vivid_score() {
         min_conscious: 1,
         min_subconscious: 2,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1473,6 +1910,8 @@ fn test_decisions_get_softer_diminishing_returns() {
             text: "decision".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Decision,
+            bridge: None,
+            seq: 0,
         },
         RankedCandidate {
             neighborhood_id: standard_id,
@@ -1482,6 +1921,8 @@ fn test_decisions_get_softer_diminishing_returns() {
             text: "standard".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Memory,
+            bridge: None,
+            seq: 0,
         },
     ];
 
@@ -1556,6 +1997,7 @@ fn test_irrelevant_query_no_conscious_recall() {
         min_conscious: 1,
         min_subconscious: 1,
         min_novel: 0,
+        ..BudgetConfig::default()
     };
     let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
 
@@ -1568,8 +2010,8 @@ fn test_irrelevant_query_no_conscious_recall() {
 
 #[test]
 fn test_sort_with_nan_scores_does_not_panic() {
-    // Verifies that total_cmp handles NaN without panicking.
-    // Previously partial_cmp().unwrap() would crash on NaN scores.
+    // Verifies that rank_cmp handles NaN without panicking and ranks it
+    // last. Previously partial_cmp().unwrap() would crash on NaN scores.
     let mut candidates = [
         RankedCandidate {
             neighborhood_id: Uuid::new_v4(),
@@ -1579,6 +2021,8 @@ fn test_sort_with_nan_scores_does_not_panic() {
             text: "normal".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Ingested,
+            bridge: None,
+            seq: 0,
         },
         RankedCandidate {
             neighborhood_id: Uuid::new_v4(),
@@ -1588,6 +2032,8 @@ fn test_sort_with_nan_scores_does_not_panic() {
             text: "degenerate".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Ingested,
+            bridge: None,
+            seq: 0,
         },
         RankedCandidate {
             neighborhood_id: Uuid::new_v4(),
@@ -1597,6 +2043,8 @@ fn test_sort_with_nan_scores_does_not_panic() {
             text: "high".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Ingested,
+            bridge: None,
+            seq: 0,
         },
         RankedCandidate {
             neighborhood_id: Uuid::new_v4(),
@@ -1606,13 +2054,1243 @@ fn test_sort_with_nan_scores_does_not_panic() {
             text: "inf".to_string(),
             tokens: 1,
             neighborhood_type: NeighborhoodType::Ingested,
+            bridge: None,
+            seq: 0,
         },
     ];
 
     // This would panic with partial_cmp().unwrap() if any score is NaN
-    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.sort_by(RankedCandidate::rank_cmp);
+
+    // Plain descending total_cmp would put NaN first (NaN > everything)
+    let order: Vec<&str> = candidates.iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(order, ["inf", "high", "normal", "degenerate"]);
+}
+
+// =====================================================================
+// Candidate count tests
+// =====================================================================
+
+#[test]
+fn test_candidate_counts_no_activation() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "zeppelin marmalade");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+
+    assert!(ctx.context.is_empty());
+    assert_eq!(ctx.candidates, CandidateCounts::default());
+    assert_eq!(
+        ctx.candidates.suggestion(),
+        Some("no activation - try more specific terms")
+    );
+}
+
+#[test]
+fn test_candidate_counts_low_scores() {
+    // One common word shared by many neighborhoods activates them all
+    // with a low IDF weight, so every candidate lands below the cutoff.
+    let mut sys = make_novel_heavy_system();
+    let result = QueryEngine::process_query(&mut sys, "harbor");
+    let surface = compute_surface(&sys, &result);
+    let budget = BudgetConfig {
+        max_tokens: 4096,
+        min_conscious: 0,
+        min_subconscious: 0,
+        min_novel: 0,
+        ..BudgetConfig::default()
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+
+    let counts = ctx.candidates;
+    assert_eq!(counts.activated_words, 1);
+    assert_eq!(counts.conscious, 0);
+    assert_eq!(counts.subconscious, 12);
+    assert_eq!(counts.novel, 12);
+    assert_eq!(counts.above_cutoff, 0);
+    assert!(ctx.included.is_empty());
+    assert_eq!(
+        counts.suggestion(),
+        Some("candidates exist but scored low - broaden the query")
+    );
+}
+
+#[test]
+fn test_candidate_counts_healthy_query_has_no_suggestion() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "quantum physics particle");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
 
-    // NaN sorts to the end with total_cmp (NaN > everything)
-    assert_eq!(candidates.len(), 4);
-    // The sort completed without panicking - that's the key assertion
+    let counts = ctx.candidates;
+    assert_eq!(counts.activated_words, 3);
+    assert_eq!(counts.conscious, 1);
+    assert!(counts.subconscious >= 1);
+    assert!(counts.above_cutoff >= 1);
+    assert_eq!(counts.suggestion(), None);
+}
+
+#[test]
+fn test_min_activated_words_drops_single_word_matches() {
+    let query = "quantum physics learning";
+
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.contains("neural network deep learning"));
+    assert!(ctx.filtered.is_empty());
+
+    // Same query with min_activated_words = 2: the neighborhood sharing only
+    // "learning" (and the conscious entry sharing only "quantum") is dropped,
+    // the two-word "quantum physics" match stays.
+    let mut sys = make_full_system();
+    sys.set_compose_config(ComposeConfig {
+        min_activated_words: 2,
+        ..ComposeConfig::default()
+    });
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+
+    assert!(ctx.context.contains("quantum physics particle wave"));
+    assert!(!ctx.context.contains("neural network deep learning"));
+    assert_eq!(ctx.metrics.conscious, 0);
+    assert_eq!(ctx.candidates.filtered, 2);
+    assert!(
+        ctx.filtered
+            .iter()
+            .all(|f| f.reason == CutoffReason::TooFewWords && f.activated_words == 1)
+    );
+}
+
+#[test]
+fn test_min_score_drops_low_scoring_candidates() {
+    let mut sys = make_full_system();
+    sys.set_compose_config(ComposeConfig {
+        min_score: f64::MAX,
+        ..ComposeConfig::default()
+    });
+    let result = QueryEngine::process_query(&mut sys, "quantum physics learning");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+
+    assert!(ctx.context.is_empty());
+    assert_eq!(ctx.candidates.total(), 0);
+    assert_eq!(ctx.candidates.filtered, 3);
+    assert!(
+        ctx.filtered
+            .iter()
+            .all(|f| f.reason == CutoffReason::LowScore)
+    );
+}
+
+#[test]
+fn test_nan_score_is_filtered_not_ranked_first() {
+    let mut sys = make_full_system();
+    // A corrupt phasor makes every interference term of "quantum" NaN
+    sys.episodes[0].neighborhoods[0].occurrences[0].phasor.theta = f64::NAN;
+    let poisoned = sys.episodes[0].neighborhoods[0].id;
+
+    let result = QueryEngine::probe(&mut sys, "quantum physics");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.contains("quantum computing research"));
+    assert!(!ctx.context.contains("quantum physics particle wave"));
+    assert_eq!(ctx.filtered.len(), 1);
+    assert_eq!(ctx.filtered[0].neighborhood_id, poisoned);
+    assert_eq!(ctx.filtered[0].reason, CutoffReason::InvalidScore);
+    assert!(ctx.filtered[0].score.is_nan());
+
+    let budgeted =
+        compose_context_budgeted(&mut sys, &surface, &result, &BudgetConfig::default(), None);
+    assert!(
+        budgeted
+            .included
+            .iter()
+            .all(|f| f.neighborhood_id != poisoned)
+    );
+    assert_eq!(budgeted.filtered[0].reason, CutoffReason::InvalidScore);
+}
+
+#[test]
+fn test_empty_reason_no_matching_words() {
+    let mut sys = make_full_system();
+    let result = QueryEngine::process_query(&mut sys, "zebra xylophone");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.is_empty());
+    assert_eq!(ctx.empty_reason, Some(EmptyReason::NoMatchingWords));
+}
+
+#[test]
+fn test_empty_reason_below_thresholds() {
+    let mut sys = make_full_system();
+    sys.set_compose_config(ComposeConfig {
+        min_activated_words: 10,
+        ..ComposeConfig::default()
+    });
+    let result = QueryEngine::process_query(&mut sys, "quantum physics learning");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert_eq!(ctx.empty_reason, Some(EmptyReason::BelowThresholds));
+
+    // A budget too small for any entry is also a threshold
+    sys.set_compose_config(ComposeConfig::default());
+    let budget = BudgetConfig {
+        max_tokens: 1,
+        ..BudgetConfig::default()
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+    assert!(ctx.included.is_empty());
+    assert_eq!(ctx.empty_reason, Some(EmptyReason::BelowThresholds));
+}
+
+#[test]
+fn test_empty_reason_all_deduped_after_repeated_recall() {
+    let mut sys = make_full_system();
+    let query = "quantum physics learning";
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let (ranked, _) = rank_candidates(&sys, &result, &result.interference, &surface);
+    let floor = ranked.iter().map(|c| c.score).fold(f64::INFINITY, f64::min);
+    sys.set_compose_config(ComposeConfig {
+        min_score: floor,
+        ..ComposeConfig::default()
+    });
+
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(!ctx.context.is_empty());
+    assert_eq!(ctx.empty_reason, None);
+
+    // Everything was recalled many times already this session
+    let recalled: HashMap<Uuid, u32> = ranked.iter().map(|c| (c.neighborhood_id, 1000)).collect();
+    let ctx = compose_context(&mut sys, &surface, &result, Some(&recalled));
+    assert!(ctx.context.is_empty());
+    assert_eq!(ctx.empty_reason, Some(EmptyReason::AllDeduped));
+
+    // Without a score floor, decay alone never hides a memory
+    sys.set_compose_config(ComposeConfig::default());
+    let ctx = compose_context(&mut sys, &surface, &result, Some(&recalled));
+    assert!(!ctx.context.is_empty());
+}
+
+#[test]
+fn test_confidence_thresholds() {
+    assert_eq!(Confidence::from_top_score(None, 1000), Confidence::None);
+    // 9 neighborhoods: strength = score / 2
+    assert_eq!(Confidence::from_top_score(Some(0.99), 9), Confidence::Low);
+    assert_eq!(Confidence::from_top_score(Some(1.0), 9), Confidence::Medium);
+    assert_eq!(
+        Confidence::from_top_score(Some(2.99), 9),
+        Confidence::Medium
+    );
+    assert_eq!(Confidence::from_top_score(Some(3.0), 9), Confidence::High);
+    // 999 neighborhoods: strength = score / 4, so the same score is weaker
+    assert_eq!(Confidence::from_top_score(Some(1.99), 999), Confidence::Low);
+    assert_eq!(
+        Confidence::from_top_score(Some(2.0), 999),
+        Confidence::Medium
+    );
+    assert_eq!(
+        Confidence::from_top_score(Some(3.0), 999),
+        Confidence::Medium
+    );
+    assert_eq!(Confidence::from_top_score(Some(6.0), 999), Confidence::High);
+}
+
+/// Twenty chunks sharing one filler word, each with a word of its own.
+fn make_filler_system() -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("filler");
+    for i in 0..20 {
+        let unique = format!("topic{i}");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["filler", &unique]),
+            None,
+            &format!("filler {unique}"),
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    sys
+}
+
+fn confidence_for(sys: &mut DAESystem, query: &str) -> (Confidence, Confidence) {
+    let result = QueryEngine::process_query(sys, query);
+    let surface = compute_surface(sys, &result);
+    let fixed = compose_context(sys, &surface, &result, None).confidence;
+    let budgeted =
+        compose_context_budgeted(sys, &surface, &result, &BudgetConfig::default(), None).confidence;
+    (fixed, budgeted)
+}
+
+#[test]
+fn test_confidence_levels_on_fixture_systems() {
+    let mut sys = make_full_system();
+    let none = (Confidence::None, Confidence::None);
+    assert_eq!(confidence_for(&mut sys, "zebra xylophone"), none);
+    let medium = (Confidence::Medium, Confidence::Medium);
+    assert_eq!(confidence_for(&mut sys, "biology"), medium);
+    let high = (Confidence::High, Confidence::High);
+    assert_eq!(confidence_for(&mut sys, "quantum physics particle"), high);
+
+    // A word every chunk shares still recalls something, but weakly
+    let mut sys = make_filler_system();
+    let low = (Confidence::Low, Confidence::Low);
+    assert_eq!(confidence_for(&mut sys, "filler"), low);
+}
+
+#[test]
+fn test_min_score_drops_weak_matches_to_no_confidence() {
+    let mut sys = make_filler_system();
+    sys.set_compose_config(ComposeConfig {
+        min_score: 0.5,
+        ..ComposeConfig::default()
+    });
+    let result = QueryEngine::process_query(&mut sys, "filler");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.is_empty());
+    assert_eq!(ctx.confidence, Confidence::None);
+    assert_eq!(ctx.empty_reason, Some(EmptyReason::BelowThresholds));
+
+    // A distinctive word clears the same floor
+    let result = QueryEngine::process_query(&mut sys, "topic7");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(ctx.context.contains("filler topic7"));
+    assert_eq!(ctx.confidence, Confidence::Medium);
+}
+
+// =====================================================================
+// Budget-aware novel tests
+// =====================================================================
+
+fn candidate(category: RecallCategory, score: f64) -> RankedCandidate {
+    RankedCandidate {
+        neighborhood_id: Uuid::new_v4(),
+        episode_ref: EpisodeRef::Subconscious(0),
+        category,
+        score,
+        text: String::new(),
+        tokens: 1,
+        neighborhood_type: NeighborhoodType::Ingested,
+        bridge: None,
+        seq: 0,
+    }
+}
+
+#[test]
+fn test_novel_scores_mapped_onto_subconscious_scale() {
+    let mut candidates = vec![
+        candidate(RecallCategory::Subconscious, 10.0),
+        candidate(RecallCategory::Subconscious, 5.0),
+        candidate(RecallCategory::Subconscious, 1.0),
+        candidate(RecallCategory::Novel, 500.0),
+        candidate(RecallCategory::Novel, 0.001),
+    ];
+    normalize_novel_scores(&mut candidates);
+
+    let top_novel = candidates[3].score;
+    let low_novel = candidates[4].score;
+    // Raw novelty of 500 no longer outranks the best subconscious score
+    assert!(top_novel <= 10.0, "top novel mapped to {top_novel}");
+    assert!(top_novel > low_novel);
+    // A low-percentile novel lands at the bottom of the subconscious range
+    assert!((low_novel - 1.0).abs() < f64::EPSILON);
+    // Subconscious scores are untouched
+    assert!((candidates[0].score - 10.0).abs() < f64::EPSILON);
+}
+
+/// Twelve one-word-match neighborhoods among unrelated ones: with no
+/// conscious memory every matching candidate also qualifies as novel.
+fn make_novel_heavy_system() -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("Field notes");
+    for i in 0..12 {
+        let word = format!("unrelated{i}");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&[&word, "inland", "desert"]),
+            None,
+            &format!("{word} inland desert"),
+            &mut rng,
+        ));
+    }
+    for i in 0..12 {
+        let filler = format!("filler{i}");
+        let other = format!("detail{i}");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["harbor", &filler, &other, "weather", "tide"]),
+            None,
+            &format!("harbor {filler} {other} weather tide"),
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    sys
+}
+
+#[test]
+fn test_novel_budget_fraction_caps_novel_tokens() {
+    let novel_share = |fraction: f64| {
+        let mut sys = make_novel_heavy_system();
+        let result = QueryEngine::process_query(&mut sys, "harbor");
+        let surface = compute_surface(&sys, &result);
+        let budget = BudgetConfig {
+            max_tokens: 4096,
+            min_conscious: 0,
+            min_subconscious: 4,
+            min_novel: 6,
+            novel_budget_fraction: fraction,
+            ..BudgetConfig::default()
+        };
+        let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+        let novel_tokens: usize = ctx
+            .included
+            .iter()
+            .filter(|f| f.category == RecallCategory::Novel)
+            .map(|f| f.tokens)
+            .sum();
+        (novel_tokens, ctx.tokens_used)
+    };
+
+    let (novel, used) = novel_share(0.1);
+    assert!(used > 0);
+    assert!(
+        novel as f64 <= 0.1 * used as f64,
+        "novel took {novel} of {used} tokens"
+    );
+
+    // Uncapped, the six guaranteed novel slots are all filled
+    let (uncapped, used) = novel_share(1.0);
+    assert!(uncapped > novel);
+    assert!(uncapped <= used);
+}
+
+#[test]
+fn test_low_percentile_novel_never_displaces_top_subconscious() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("Notes");
+    // Strong match: all three query words
+    ep.add_neighborhood(Neighborhood::from_tokens(
+        &to_tokens(&["sqlite", "wal", "checkpoint"]),
+        None,
+        "sqlite wal checkpoint",
+        &mut rng,
+    ));
+    // Weak one-word matches that qualify as novel
+    for i in 0..6 {
+        let word = format!("aside{i}");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["checkpoint", &word]),
+            None,
+            &format!("checkpoint {word}"),
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+
+    let result = QueryEngine::process_query(&mut sys, "sqlite wal checkpoint");
+    let surface = compute_surface(&sys, &result);
+    // Room for exactly one fragment, filled greedily by score
+    let budget = BudgetConfig {
+        max_tokens: 25,
+        min_conscious: 0,
+        min_subconscious: 0,
+        min_novel: 0,
+        novel_budget_fraction: 1.0,
+        ..BudgetConfig::default()
+    };
+    let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+
+    assert_eq!(ctx.included.len(), 1, "context:\n{}", ctx.context);
+    assert_eq!(ctx.included[0].category, RecallCategory::Subconscious);
+    assert_eq!(ctx.included[0].text, "sqlite wal checkpoint");
+}
+
+// =====================================================================
+// Plan tests
+// =====================================================================
+
+#[test]
+fn test_detect_neighborhood_type_plan() {
+    let (typ, text) = detect_neighborhood_type("PLAN: 1. add the column 2. backfill");
+    assert_eq!(typ, NeighborhoodType::Plan);
+    assert_eq!(text, "1. add the column 2. backfill");
+}
+
+#[test]
+fn test_looks_like_plan() {
+    assert!(looks_like_plan(
+        "1. add the column, 2. backfill, 3. flip the flag"
+    ));
+    assert!(looks_like_plan("Steps:\n1) add the column\n2) backfill"));
+    // A single numbered item or out-of-order numbers are not a plan
+    assert!(!looks_like_plan("1. add the column and call it done"));
+    assert!(!looks_like_plan(
+        "We moved from version 2. Then 1. was gone."
+    ));
+    assert!(!looks_like_plan("no numbers here at all"));
+}
+
+#[test]
+fn test_tag_plans_on_ingested_conversation() {
+    let mut rng = rng();
+    let mut ep = crate::tokenizer::ingest_text(
+        "We talked about the schema. The plan: 1. add the column, 2. backfill, 3. flip the flag. Later we discussed lunch.",
+        Some("conversation"),
+        &mut rng,
+    );
+    assert_eq!(tag_plans(&mut ep), 1);
+    assert_eq!(
+        ep.neighborhoods[0].neighborhood_type,
+        NeighborhoodType::Plan
+    );
+    // Idempotent
+    assert_eq!(tag_plans(&mut ep), 0);
+}
+
+#[test]
+fn test_plan_formatted_with_steps_on_own_lines() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    mark_salient_typed(
+        &mut sys,
+        "PLAN: schema rollout 1. add the column, 2. backfill rows, 3. flip the flag",
+        &mut rng,
+    );
+
+    let result = QueryEngine::process_query(&mut sys, "schema column backfill flag");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+
+    assert!(
+        ctx.context.contains(
+            "[PLAN]\nschema rollout\n1. add the column\n2. backfill rows\n3. flip the flag"
+        ),
+        "plan should keep list structure, got:\n{}",
+        ctx.context,
+    );
+}
+
+#[test]
+fn test_plan_boost_requires_planning_verb() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("notes");
+    let mut plan = Neighborhood::from_tokens(
+        &to_tokens(&["billing", "column", "backfill", "flag"]),
+        None,
+        "1. add billing column 2. backfill 3. flip flag",
+        &mut rng,
+    );
+    plan.neighborhood_type = NeighborhoodType::Plan;
+    ep.add_neighborhood(plan);
+    sys.add_episode(ep);
+
+    let plan_score = |sys: &mut DAESystem, query: &str| {
+        let result = QueryEngine::process_query(sys, query);
+        let surface = compute_surface(sys, &result);
+        let intent = result.plan_intent;
+        let score = rank_candidates(sys, &result, &result.interference, &surface)
+            .0
+            .iter()
+            .find(|c| c.category == RecallCategory::Subconscious)
+            .map(|c| c.score)
+            .unwrap();
+        (intent, score)
+    };
+
+    let (intent, unboosted) = plan_score(&mut sys, "billing backfill");
+    assert!(!intent);
+    let (intent, boosted) = plan_score(&mut sys, "billing backfill migrate");
+    assert!(intent);
+    assert!(
+        boosted > unboosted,
+        "planning verb should boost the plan: {boosted} vs {unboosted}"
+    );
+
+    // The verb list is configurable
+    sys.set_plan_verbs(vec!["Deploy".to_string()]);
+    let (intent, _) = plan_score(&mut sys, "billing backfill migrate");
+    assert!(!intent);
+    let (intent, _) = plan_score(&mut sys, "deploy billing backfill");
+    assert!(intent);
+}
+
+/// Scores of two equally-activated decisions, an untagged one and a newer
+/// one tagged for `repo-y`, recalled while working in `project`.
+fn project_scores(project: Option<&str>) -> (f64, f64) {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let untagged = mark_salient_typed(&mut sys, "DECISION: postgres replicas", &mut rng);
+    let tagged = mark_salient_typed(&mut sys, "DECISION (repo-y): sqlite files", &mut rng);
+    sys.set_project(project.map(str::to_owned));
+
+    let result = QueryEngine::process_query(&mut sys, "postgres replicas sqlite files");
+    let surface = compute_surface(&sys, &result);
+    let (candidates, _) = rank_candidates(&sys, &result, &result.interference, &surface);
+    let score = |id| {
+        candidates
+            .iter()
+            .find(|c| c.neighborhood_id == id)
+            .map(|c| c.score)
+            .unwrap()
+    };
+    (score(untagged), score(tagged))
+}
+
+#[test]
+fn test_other_project_decision_is_discounted() {
+    // Equally activated; the newer, tagged decision leads on recency alone
+    let (untagged, tagged) = project_scores(None);
+    assert!(tagged > untagged, "{tagged} vs {untagged}");
+
+    // Working in repo-x: the repo-y decision drops below the untagged one
+    let (elsewhere_untagged, elsewhere_tagged) = project_scores(Some("repo-x"));
+    assert_eq!(
+        elsewhere_untagged, untagged,
+        "untagged memories apply everywhere"
+    );
+    assert!((elsewhere_tagged - tagged * DEFAULT_PROJECT_DISCOUNT).abs() < 1e-9);
+    assert!(elsewhere_untagged > elsewhere_tagged);
+
+    // Working in repo-y (any case): its own decision keeps full weight
+    let (home_untagged, home_tagged) = project_scores(Some("Repo-Y"));
+    assert_eq!(home_untagged, untagged);
+    assert_eq!(home_tagged, tagged);
+}
+
+#[test]
+fn test_query_project_overrides_system_project() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    mark_salient_typed(&mut sys, "DECISION: postgres replicas", &mut rng);
+    let tagged = mark_salient_typed(&mut sys, "DECISION (repo-y): sqlite files", &mut rng);
+    sys.set_project(Some("repo-x".to_string()));
+
+    let mut result = QueryEngine::process_query(&mut sys, "postgres replicas sqlite files");
+    let surface = compute_surface(&sys, &result);
+    let tagged_score = |result: &QueryResult| {
+        let (candidates, _) = rank_candidates(&sys, result, &result.interference, &surface);
+        candidates
+            .iter()
+            .find(|c| c.neighborhood_id == tagged)
+            .map(|c| c.score)
+            .unwrap()
+    };
+    let discounted = tagged_score(&result);
+    result.project = Some("repo-y".to_string());
+    let home = tagged_score(&result);
+    assert!((discounted - home * DEFAULT_PROJECT_DISCOUNT).abs() < 1e-9);
+    assert_eq!(
+        sys.project(),
+        Some("repo-x"),
+        "the system keeps its project"
+    );
+}
+
+#[test]
+fn test_word_bias_boosts_and_mutes_recall() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let nancy = mark_salient_typed(&mut sys, "nancy routes workers", &mut rng);
+    let deploy = mark_salient_typed(&mut sys, "deploy runs nightly", &mut rng);
+
+    let score_of = |sys: &mut DAESystem, id: Uuid| {
+        let result = QueryEngine::process_query(sys, "nancy deploy");
+        let surface = compute_surface(sys, &result);
+        sys.rebuild_indexes();
+        let (candidates, _) = rank_candidates(sys, &result, &result.interference, &surface);
+        candidates
+            .iter()
+            .find(|c| c.neighborhood_id == id)
+            .map(|c| c.score)
+    };
+
+    let plain = score_of(&mut sys, nancy).unwrap();
+    sys.set_word_bias("nancy", 3.0);
+    let boosted = score_of(&mut sys, nancy).unwrap();
+    assert!(boosted > 2.0 * plain, "boosted {boosted} vs plain {plain}");
+
+    // Muted: no longer a candidate, but its occurrences are still stored
+    sys.set_word_bias("nancy", 0.0);
+    assert_eq!(score_of(&mut sys, nancy), None);
+    assert!(score_of(&mut sys, deploy).is_some());
+    assert!(!sys.get_word_occurrences("nancy").is_empty());
+}
+
+#[test]
+fn test_salient_project_tags() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let text = "<salient project=\"Repo-X\">DECISION: use wal mode</salient>\n\
+                - DECISION (repo-y): ship nightly builds\n\
+                PREFERENCE: tabs over spaces";
+    extract_stated_salient(&mut sys, text, &mut rng);
+
+    let tagged: Vec<(&str, Option<&str>, NeighborhoodType)> = sys
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| {
+            (
+                n.source_text.as_str(),
+                n.project.as_deref(),
+                n.neighborhood_type,
+            )
+        })
+        .collect();
+    assert_eq!(
+        tagged,
+        vec![
+            ("use wal mode", Some("repo-x"), NeighborhoodType::Decision),
+            (
+                "ship nightly builds",
+                Some("repo-y"),
+                NeighborhoodType::Decision
+            ),
+            ("tabs over spaces", None, NeighborhoodType::Preference),
+        ]
+    );
+
+    // Auto-capture stamps the current project on memories that name none
+    sys.set_project(Some("repo-z".to_string()));
+    sys.set_tag_conscious_project(true);
+    mark_salient_typed(&mut sys, "PLAN: cut the release", &mut rng);
+    mark_salient_typed(&mut sys, "PLAN (repo-y): cut the release", &mut rng);
+    let nbhds = &sys.conscious_episode.neighborhoods;
+    assert_eq!(nbhds[3].project.as_deref(), Some("repo-z"));
+    assert_eq!(nbhds[4].project.as_deref(), Some("repo-y"));
+}
+
+#[test]
+fn test_project_discount_never_zero() {
+    let mut sys = DAESystem::new("test");
+    sys.set_project_discount(0.0);
+    assert_eq!(sys.project_discount(), DEFAULT_PROJECT_DISCOUNT);
+    sys.set_project_discount(f64::NAN);
+    assert_eq!(sys.project_discount(), DEFAULT_PROJECT_DISCOUNT);
+    sys.set_project_discount(3.0);
+    assert_eq!(sys.project_discount(), 1.0);
+}
+
+fn rerank_pair() -> Vec<RankedCandidate> {
+    let mut a = candidate(RecallCategory::Subconscious, 10.0);
+    a.text = "alpha beta".to_string();
+    let mut b = candidate(RecallCategory::Subconscious, 5.0);
+    b.text = "gamma delta".to_string();
+    vec![a, b]
+}
+
+fn reranked_scores(stage: Option<RerankStage>, query: &str) -> Vec<f64> {
+    let mut sys = DAESystem::new("test");
+    sys.set_rerank_stage(stage);
+    let mut candidates = rerank_pair();
+    apply_rerank(&sys, query, &mut candidates);
+    candidates.iter().map(|c| c.score).collect()
+}
+
+#[test]
+fn test_rerank_weight_zero_keeps_geometric_order() {
+    let stage = RerankStage {
+        weight: 0.0,
+        ..RerankStage::new(LexicalOverlapReranker)
+    };
+    assert_eq!(reranked_scores(Some(stage), "gamma delta"), vec![10.0, 5.0]);
+}
+
+#[test]
+fn test_rerank_weight_one_flips_order_by_lexical_overlap() {
+    let stage = RerankStage {
+        weight: 1.0,
+        ..RerankStage::new(LexicalOverlapReranker)
+    };
+    let scores = reranked_scores(Some(stage), "gamma delta");
+    // Pure reranker score, scaled by the best geometric score
+    assert_eq!(scores, vec![0.0, 10.0]);
+
+    let half = RerankStage::new(LexicalOverlapReranker);
+    let scores = reranked_scores(Some(half), "gamma delta");
+    assert!(
+        scores[1] > scores[0],
+        "default blend should still flip: {scores:?}"
+    );
+}
+
+#[test]
+fn test_noop_reranker_and_missing_stage_leave_scores_alone() {
+    assert_eq!(reranked_scores(None, "gamma delta"), vec![10.0, 5.0]);
+    let stage = RerankStage {
+        weight: 1.0,
+        ..RerankStage::new(NoopReranker)
+    };
+    assert_eq!(reranked_scores(Some(stage), "gamma delta"), vec![10.0, 5.0]);
+}
+
+#[test]
+fn test_rerank_only_touches_top_k() {
+    let stage = RerankStage {
+        top_k: 1,
+        weight: 1.0,
+        ..RerankStage::new(LexicalOverlapReranker)
+    };
+    // Only the top candidate is reranked; it has no overlap and drops to 0
+    assert_eq!(reranked_scores(Some(stage), "gamma delta"), vec![0.0, 5.0]);
+}
+
+struct WrongLength;
+
+impl Reranker for WrongLength {
+    fn rerank(&self, _query: &str, _candidates: &[CandidateView<'_>]) -> Vec<f64> {
+        vec![1.0]
+    }
+}
+
+#[test]
+fn test_rerank_ignores_result_of_wrong_length() {
+    let stage = RerankStage {
+        weight: 1.0,
+        ..RerankStage::new(WrongLength)
+    };
+    assert_eq!(reranked_scores(Some(stage), "gamma delta"), vec![10.0, 5.0]);
+}
+
+#[test]
+fn test_compose_context_applies_rerank_stage() {
+    let mut sys = make_full_system();
+    let query = "quantum physics learning";
+    let baseline = {
+        let result = QueryEngine::process_query(&mut sys, query);
+        let surface = compute_surface(&sys, &result);
+        compose_context(&mut sys, &surface, &result, None).context
+    };
+
+    sys.set_rerank_stage(Some(RerankStage {
+        weight: 1.0,
+        ..RerankStage::new(NoopReranker)
+    }));
+    let result = QueryEngine::process_query(&mut sys, query);
+    assert_eq!(result.query, query);
+    let surface = compute_surface(&sys, &result);
+    let reranked = compose_context(&mut sys, &surface, &result, None).context;
+    assert_eq!(baseline, reranked);
+}
+
+#[test]
+fn test_novel_connection_names_its_bridging_words() {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("notes");
+    for text in [
+        "quantum physics particle wave",
+        "quantum physics lab notes",
+        "backpressure slows the producer when the queue fills",
+    ] {
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &crate::tokenizer::tokenize(text),
+            None,
+            text,
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    sys.add_to_conscious("quantum computing research", &mut rng);
+
+    let query = "quantum physics particle wave lab backpressure";
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let (candidates, _) = rank_candidates(&sys, &result, &result.interference, &surface);
+    let novel = candidates
+        .iter()
+        .find(|c| c.category == RecallCategory::Novel)
+        .expect("backpressure note should be a novel candidate");
+    let via = &novel.bridge.as_ref().unwrap().via;
+    let query_tokens = crate::tokenizer::tokenize(query);
+    let novel_tokens = crate::tokenizer::tokenize(&novel.text);
+    assert!(!via.is_empty());
+    for word in via {
+        assert!(query_tokens.contains(word), "{word} not in query");
+        assert!(novel_tokens.contains(word), "{word} not in novel text");
+    }
+    assert!(
+        candidates
+            .iter()
+            .filter(|c| c.category != RecallCategory::Novel)
+            .all(|c| c.bridge.is_none())
+    );
+
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert!(
+        ctx.context
+            .contains("NOVEL CONNECTION (via: 'backpressure'):"),
+        "{}",
+        ctx.context
+    );
+}
+
+fn system_of(subconscious: &[&str], conscious: &[&str]) -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("notes");
+    for text in subconscious {
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &crate::tokenizer::tokenize(text),
+            None,
+            text,
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    for text in conscious {
+        sys.add_to_conscious(text, &mut rng);
+    }
+    sys
+}
+
+fn novel_texts(sys: &mut DAESystem, query: &str) -> Vec<String> {
+    let result = QueryEngine::process_query(sys, query);
+    let surface = compute_surface(sys, &result);
+    ranked_novel(sys, &result, &surface)
+}
+
+fn ranked_novel(sys: &mut DAESystem, result: &QueryResult, surface: &SurfaceResult) -> Vec<String> {
+    let (mut candidates, _) = rank_candidates(sys, result, &result.interference, surface);
+    candidates.retain(|c| c.category == RecallCategory::Novel);
+    candidates.sort_by(RankedCandidate::rank_cmp);
+    candidates.into_iter().map(|c| c.text).collect()
+}
+
+#[test]
+fn test_novel_without_conscious_ranks_sparse_rare_matches_first() {
+    let mut sys = system_of(
+        &[
+            "deploy pipeline release checklist",
+            "deploy pipeline release notes",
+            "deploy pipeline rollback",
+            "canary rollout",
+            "release cadence",
+        ],
+        &[],
+    );
+    let query = "deploy pipeline release canary";
+    // One activated word each, the rarer first; then two; then three
+    let ranked = [
+        "canary rollout",
+        "release cadence",
+        "deploy pipeline rollback",
+        "deploy pipeline release checklist",
+        "deploy pipeline release notes",
+    ];
+    assert_eq!(novel_texts(&mut sys, query), ranked);
+
+    // The novel slot takes the best-ranked match not already recalled
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let ranked = ranked_novel(&mut sys, &result, &surface);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert_eq!(ctx.metrics.novel, 1);
+    let (_, novel_entry) = ctx.context.split_once("NOVEL CONNECTION").unwrap();
+    let pick = ranked
+        .iter()
+        .find(|t| !ctx.context.contains(t.as_str()) || novel_entry.contains(t.as_str()))
+        .unwrap();
+    assert!(novel_entry.contains(pick.as_str()), "{}", ctx.context);
+}
+
+#[test]
+fn test_novel_in_mature_system_where_every_match_hits_several_words() {
+    let subconscious = [
+        "staging cluster canary rollout health",
+        "staging cluster canary metrics",
+        "cluster canary rollout alerts",
+        "staging canary rollout dashboards",
+        "deploy pipeline staging cluster",
+    ];
+    let mut sys = system_of(&subconscious, &["deploy pipeline notes"]);
+    let query = "deploy pipeline staging cluster canary rollout";
+
+    // Nothing matches two or fewer words, so the default falls back to
+    // ranking every match that avoids conscious words
+    let fallback = novel_texts(&mut sys, query);
+    assert_eq!(fallback.len(), 4);
+    assert!(!fallback.iter().any(|t| t.starts_with("deploy")));
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    assert_eq!(
+        compose_context(&mut sys, &surface, &result, None)
+            .metrics
+            .novel,
+        1
+    );
+
+    // Allowing three words picks only the three-word matches
+    sys.set_compose_config(ComposeConfig {
+        novel: NovelConfig {
+            max_activated_words: 3,
+            ..NovelConfig::default()
+        },
+        ..ComposeConfig::default()
+    });
+    let mut three = novel_texts(&mut sys, query);
+    three.sort();
+    assert_eq!(three, [subconscious[2], subconscious[3], subconscious[1]]);
+    let result = QueryEngine::process_query(&mut sys, query);
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+    assert_eq!(ctx.metrics.novel, 1);
+
+    // Dropping the overlap rule lets the conscious-adjacent note in
+    sys.set_compose_config(ComposeConfig {
+        novel: NovelConfig {
+            max_activated_words: 4,
+            require_no_conscious_overlap: false,
+            ..NovelConfig::default()
+        },
+        ..ComposeConfig::default()
+    });
+    assert!(
+        novel_texts(&mut sys, query)
+            .iter()
+            .any(|t| t == subconscious[4])
+    );
+
+    // A novelty floor above every match leaves no novel candidates
+    sys.set_compose_config(ComposeConfig {
+        novel: NovelConfig {
+            min_novelty_score: 10.0,
+            ..NovelConfig::default()
+        },
+        ..ComposeConfig::default()
+    });
+    assert!(novel_texts(&mut sys, query).is_empty());
+}
+
+// =====================================================================
+// Deterministic tie-breaking
+// =====================================================================
+
+const TIED_TAGS: [&str; 6] = [
+    "amber", "basalt", "cobalt", "dolomite", "emerald", "feldspar",
+];
+
+/// Six neighborhoods that each match one query word of the same rarity,
+/// so they score identically, plus filler that keeps those words rare.
+/// Distinct matched words keep overlap suppression out of the picture.
+fn make_tied_system() -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("Consensus notes");
+    for tag in TIED_TAGS {
+        let text = format!("{tag} deposit survey");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &crate::tokenizer::tokenize(&text),
+            None,
+            &text,
+            &mut rng,
+        ));
+    }
+    for filler in [
+        "tokio runtime scheduler",
+        "sqlite index btree",
+        "tensor kernel fusion",
+    ] {
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &crate::tokenizer::tokenize(filler),
+            None,
+            filler,
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    sys
+}
+
+#[test]
+fn test_equal_scores_rank_in_insertion_order() {
+    let run = || {
+        let mut sys = make_tied_system();
+        let result = QueryEngine::process_query(&mut sys, &TIED_TAGS.join(" "));
+        let surface = compute_surface(&sys, &result);
+        let budget = BudgetConfig {
+            min_conscious: 0,
+            min_novel: 0,
+            ..BudgetConfig::default()
+        };
+        let ctx = compose_context_budgeted(&mut sys, &surface, &result, &budget, None);
+        ctx.included
+            .iter()
+            .map(|f| (f.text.clone(), f.score))
+            .collect::<Vec<_>>()
+    };
+
+    let first = run();
+    assert_eq!(first.len(), TIED_TAGS.len(), "{first:?}");
+    assert!(
+        first.iter().all(|(_, s)| (s - first[0].1).abs() < 1e-12),
+        "scores should tie: {first:?}"
+    );
+    // Ties rank in insertion order, not hash-map order
+    for ((text, _), tag) in first.iter().zip(TIED_TAGS) {
+        assert!(text.starts_with(tag), "{text} out of order");
+    }
+    for _ in 0..10 {
+        assert_eq!(run(), first);
+    }
+}
+
+#[test]
+fn test_rank_cmp_breaks_score_ties_by_seq() {
+    let mut candidates: Vec<RankedCandidate> = [(2.0, 3), (5.0, 9), (2.0, 1), (2.0, 2)]
+        .into_iter()
+        .map(|(score, seq)| RankedCandidate {
+            seq,
+            ..candidate(RecallCategory::Subconscious, score)
+        })
+        .collect();
+    candidates.sort_by(RankedCandidate::rank_cmp);
+    let order: Vec<u64> = candidates.iter().map(|c| c.seq).collect();
+    assert_eq!(order, vec![9, 1, 2, 3]);
+}
+
+#[test]
+fn test_reused_neighborhood_recalled_once() {
+    let shared =
+        "Retries use exponential backoff. Each attempt doubles the wait. Jitter avoids herds.";
+    let mut sys = DAESystem::new("test");
+    sys.set_dedup_neighborhoods(true);
+    for rest in [
+        "Alpha covers webhooks. It signs payloads. It retries failures.",
+        "Beta covers queues. It batches work. It acks late.",
+    ] {
+        let episode = crate::tokenizer::ingest_text(&format!("{shared} {rest}"), None, &mut rng());
+        sys.add_episode(episode);
+    }
+
+    let result = QueryEngine::process_query(&mut sys, "exponential backoff jitter");
+    let surface = compute_surface(&sys, &result);
+    let ctx = compose_context(&mut sys, &surface, &result, None);
+
+    assert_eq!(
+        ctx.context.matches("exponential backoff").count(),
+        1,
+        "{}",
+        ctx.context
+    );
+    let shared_id = sys.episodes[0].neighborhoods[0].id;
+    assert_eq!(sys.episodes[1].reused_neighborhoods, vec![shared_id]);
+    assert_eq!(
+        ctx.included_ids
+            .iter()
+            .filter(|id| **id == shared_id)
+            .count(),
+        1
+    );
+}
+
+/// Activation count and phase of every occurrence, in storage order.
+fn occurrence_state(sys: &DAESystem) -> Vec<(u32, f64)> {
+    sys.episodes
+        .iter()
+        .flat_map(|e| &e.neighborhoods)
+        .chain(&sys.conscious_episode.neighborhoods)
+        .flat_map(|n| &n.occurrences)
+        .map(|o| (o.activation_count, o.phasor.theta))
+        .collect()
+}
+
+#[test]
+fn test_probe_counts_candidates_without_mutating() {
+    let mut sys = make_full_system();
+    let before = occurrence_state(&sys);
+
+    let known = probe(&mut sys, "quantum physics");
+    assert!(known.is_known());
+    assert_eq!(known.candidates.activated_words, 2);
+    assert_eq!(known.candidates.conscious, 1);
+    assert!(known.candidates.subconscious >= 1);
+    assert!(known.top_score.is_some_and(|s| s > 0.0));
+
+    let after = occurrence_state(&sys);
+    assert_eq!(before, after);
+
+    let unknown = probe(&mut sys, "zeppelin");
+    assert!(!unknown.is_known());
+    assert_eq!(unknown, ProbeResult::default());
+}
+
+#[test]
+fn test_peek_composes_without_mutating() {
+    let mut sys = make_full_system();
+    let before = occurrence_state(&sys);
+
+    let composed = peek(&mut sys, "quantum physics", |sys, query_result, surface| {
+        assert!(query_result.manifest.activated.is_empty());
+        compose_context_budgeted(sys, surface, query_result, &BudgetConfig::default(), None)
+    });
+    assert!(composed.context.contains("quantum physics particle wave"));
+    assert!(composed.context.contains("quantum computing research"));
+    assert_eq!(occurrence_state(&sys), before);
+
+    // Peeking again recalls the same thing: nothing was learned
+    let again = peek(&mut sys, "quantum physics", |sys, query_result, surface| {
+        compose_context_budgeted(sys, surface, query_result, &BudgetConfig::default(), None)
+    });
+    assert_eq!(again.context, composed.context);
+}
+
+/// Subconscious ranking of the tied system for a query on every tag, as
+/// the tag each ranked neighborhood starts with.
+fn tied_ranking(sys: &mut DAESystem) -> Vec<String> {
+    let result = QueryEngine::process_query(sys, &TIED_TAGS.join(" "));
+    let surface = compute_surface(sys, &result);
+    let (mut candidates, _) = rank_candidates(sys, &result, &result.interference, &surface);
+    candidates.retain(|c| c.category == RecallCategory::Subconscious);
+    candidates.sort_by(RankedCandidate::rank_cmp);
+    candidates
+        .iter()
+        .map(|c| c.text.split(' ').next().unwrap().to_string())
+        .collect()
+}
+
+/// The tied system after two demotes of the amber neighborhood and a
+/// later query on "amber" alone, which wins back the activation the
+/// demotes took. `keep_score` false then forgets the feedback score.
+fn demoted_tied_system(keep_score: bool) -> DAESystem {
+    use crate::feedback::{FeedbackSignal, apply_feedback};
+
+    let mut sys = make_tied_system();
+    let all_tags = TIED_TAGS.join(" ");
+    let _ = QueryEngine::process_query(&mut sys, &all_tags);
+    let amber = sys.episodes[0].neighborhoods[0].id;
+    for _ in 0..2 {
+        apply_feedback(&mut sys, &all_tags, &[amber], FeedbackSignal::Demote);
+    }
+    assert!((sys.episodes[0].neighborhoods[0].feedback_score + 1.8).abs() < 1e-9);
+
+    let _ = QueryEngine::process_query(&mut sys, "amber");
+    if !keep_score {
+        sys.episodes[0].neighborhoods[0].feedback_score = 0.0;
+    }
+    sys
+}
+
+#[test]
+fn test_demoted_neighborhood_stays_down_after_reactivation() {
+    // Geometry alone ranks it first again once activation catches up
+    let mut forgotten = demoted_tied_system(false);
+    assert_eq!(tied_ranking(&mut forgotten)[0], "amber");
+
+    // The feedback score keeps it out of the top slots, query after query
+    let mut sys = demoted_tied_system(true);
+    for _ in 0..3 {
+        let ranking = tied_ranking(&mut sys);
+        assert_eq!(ranking.len(), TIED_TAGS.len());
+        let rank = ranking.iter().position(|t| t == "amber").unwrap();
+        assert!(rank >= 3, "demoted neighborhood ranked {rank}: {ranking:?}");
+    }
 }
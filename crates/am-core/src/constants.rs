@@ -62,3 +62,115 @@ pub const DEFAULT_RETENTION_DAYS: u64 = 3;
 /// 2 activation counts.
 /// Exported for `am-store` retention policy defaults.
 pub const DEFAULT_RECENCY_WEIGHT: f64 = 2.0;
+
+/// Default lower clamp on IDF word weight: 0.0, no floor. IDF is `1 / df`,
+/// so ubiquitous words approach zero; a positive `word_weight_floor` keeps
+/// common words from going to zero.
+/// A brain's `word_weight_floor` metadata overrides it.
+pub const DEFAULT_WORD_WEIGHT_FLOOR: f64 = 0.0;
+
+/// Default upper clamp on IDF word weight. A word seen in a single
+/// neighborhood gets weight 1.0; lowering the ceiling stops one typo from
+/// dominating drift and scoring.
+/// A brain's `word_weight_ceiling` metadata overrides it.
+pub const DEFAULT_WORD_WEIGHT_CEILING: f64 = 1.0;
+
+/// Upper clamp on a per-word bias (`DAESystem::set_word_bias`), the
+/// multiplier applied to a word's IDF weight when scoring.
+pub const MAX_WORD_BIAS: f64 = 10.0;
+
+/// Default cap on the subconscious occurrences one query word activates
+/// (`DAESystem::set_max_word_activations`). A word in thousands of
+/// neighborhoods would otherwise hand drift, interference, and scoring
+/// thousands of refs; IDF already makes them count for little.
+pub const DEFAULT_MAX_WORD_ACTIVATIONS: usize = 256;
+
+/// Default length in characters above which a salient text is split into
+/// grouped chunks (`DAESystem::set_salient_chunk_chars`), so one long
+/// insight does not take the whole context whenever any word matches.
+pub const DEFAULT_SALIENT_CHUNK_CHARS: usize = 600;
+
+/// Default token-set similarity (Jaccard) at or above which a new salient
+/// mark counts as a repeat of a live conscious memory
+/// (`DAESystem::set_salient_dedup_threshold`). A repeat reinforces the
+/// existing memory instead of adding another copy for recall to surface.
+pub const DEFAULT_SALIENT_DEDUP_THRESHOLD: f64 = 0.8;
+
+/// Drift scale for queries: the full SLERP step.
+pub const QUERY_DRIFT_SCALE: f64 = 1.0;
+
+/// Default drift scale for `activate_response`: the fraction of the full
+/// SLERP step that a response's activated occurrences drift toward each
+/// other. Response text is long and noisy, so it consolidates at a
+/// fraction of a focused query's rate. A brain's `response_drift_scale`
+/// metadata overrides it.
+pub const DEFAULT_RESPONSE_DRIFT_SCALE: f64 = 0.3;
+
+/// Number of distinctive words appended to generated episode names
+/// ("conversation: retries, idempotency, webhook").
+pub const EPISODE_LABEL_WORDS: usize = 4;
+
+/// Words shorter than this are never used in generated episode names.
+pub const EPISODE_LABEL_MIN_WORD_LEN: usize = 4;
+
+/// Default cap on the share of a budgeted context that novel connections
+/// may take (`BudgetConfig::novel_budget_fraction`).
+pub const DEFAULT_NOVEL_BUDGET_FRACTION: f64 = 0.25;
+
+/// Default cap on the distinct query words a subconscious neighborhood may
+/// match and still be a novel connection (`NovelConfig::max_activated_words`).
+/// A neighborhood matching more words is a direct hit on the query rather
+/// than a lateral one; it is a novel candidate only when nothing under the
+/// cap qualifies.
+/// A brain's `compose.novel.max_activated_words` metadata overrides it.
+pub const DEFAULT_NOVEL_MAX_ACTIVATED_WORDS: usize = 2;
+
+/// Default number of distinct query words a neighborhood must match to be a
+/// recall candidate (`ComposeConfig::min_activated_words`). 1 keeps every
+/// activated neighborhood; 2 drops single-common-word matches. A brain's
+/// `min_activated_words` metadata overrides it.
+pub const DEFAULT_MIN_ACTIVATED_WORDS: usize = 1;
+
+/// Default score a neighborhood must reach, after interference, vividness,
+/// and plan boosts, to be a recall candidate (`ComposeConfig::min_score`).
+/// 0.0 disables the cutoff, so weak matches still fill the budget. A
+/// brain's `min_candidate_score` metadata overrides it.
+pub const DEFAULT_MIN_CANDIDATE_SCORE: f64 = 0.0;
+
+/// Normalized strength (see `Confidence::from_top_score`) at which a
+/// composed context counts as medium rather than low confidence. One
+/// query word unique to the best match reaches it in a brain of up to
+/// ~1000 neighborhoods.
+pub const CONFIDENCE_MEDIUM_STRENGTH: f64 = 0.5;
+
+/// Normalized strength at which a composed context counts as high
+/// confidence. A three-word query whose words are all unique to the best
+/// match reaches it at ~1000 neighborhoods.
+pub const CONFIDENCE_HIGH_STRENGTH: f64 = 1.5;
+
+/// Default number of top-scoring candidates handed to a `Reranker`.
+pub const DEFAULT_RERANK_TOP_K: usize = 20;
+
+/// Default blend weight of reranker scores against geometric scores
+/// (0.0 = geometric only, 1.0 = reranker only).
+pub const DEFAULT_RERANK_WEIGHT: f64 = 0.5;
+
+/// Default score factor for conscious memories tagged for a project other
+/// than the current one (`DAESystem::set_project_discount`). Never 0, so
+/// cross-project memories stay recallable.
+pub const DEFAULT_PROJECT_DISCOUNT: f64 = 0.6;
+
+/// Query words that signal task planning. A query containing any of them
+/// boosts Plan neighborhoods. Replaceable via `DAESystem::set_plan_verbs`.
+pub const DEFAULT_PLAN_VERBS: &[&str] = &[
+    "implement",
+    "implementing",
+    "migrate",
+    "migrating",
+    "migration",
+    "refactor",
+    "refactoring",
+    "plan",
+    "planning",
+    "rollout",
+];
@@ -14,6 +14,19 @@ pub struct Episode {
     pub is_conscious: bool,
     pub timestamp: String,
     pub neighborhoods: Vec<Neighborhood>,
+    /// Model or agent that created the episode, if known.
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// The timestamp an import found in the future and replaced with the
+    /// import time: the exporting machine's clock was ahead.
+    #[serde(default)]
+    pub original_timestamp: Option<String>,
+    /// Neighborhoods of earlier episodes whose text this episode repeated.
+    /// With neighborhood dedup on, an identical chunk is not stored twice;
+    /// the episode records the existing neighborhood here instead. An id
+    /// may dangle once that neighborhood is forgotten or collected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reused_neighborhoods: Vec<Uuid>,
 }
 
 impl Episode {
@@ -25,6 +38,9 @@ impl Episode {
             is_conscious: false,
             timestamp: now_iso8601(),
             neighborhoods: Vec::new(),
+            actor: None,
+            original_timestamp: None,
+            reused_neighborhoods: Vec::new(),
         }
     }
 
@@ -36,6 +52,9 @@ impl Episode {
             is_conscious: true,
             timestamp: now_iso8601(),
             neighborhoods: Vec::new(),
+            actor: None,
+            original_timestamp: None,
+            reused_neighborhoods: Vec::new(),
         }
     }
 
@@ -61,6 +80,16 @@ impl Episode {
             .sum()
     }
 
+    /// Estimated LLM tokens across all neighborhoods' source text: what
+    /// recalling the whole episode would cost.
+    #[must_use]
+    pub fn llm_tokens(&self) -> usize {
+        self.neighborhoods
+            .iter()
+            .map(super::neighborhood::Neighborhood::llm_tokens)
+            .sum()
+    }
+
     /// Episode mass relative to total system occurrences.
     ///
     /// `n` is the total number of occurrences across all episodes in the system,
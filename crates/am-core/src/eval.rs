@@ -0,0 +1,321 @@
+//! Recall quality evaluation over labeled fixture corpora.
+//!
+//! An [`EvalSuite`] is a small corpus plus a list of queries, each annotated
+//! with the documents or snippets it should recall. [`run_suite`] rebuilds
+//! the corpus from a fixed seed for every query (so queries cannot influence
+//! each other through drift), runs the normal query → surface → index
+//! pipeline, and scores the ranked results with precision@k and reciprocal
+//! rank. The clock is frozen at `suite.now` for the whole run so recency
+//! decay is reproducible.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::compose::{RecallCategory, compose_index};
+use crate::query::QueryEngine;
+use crate::salient::mark_salient_typed;
+use crate::surface::compute_surface;
+use crate::system::DAESystem;
+use crate::time::{freeze_clock, unix_to_iso8601};
+use crate::tokenizer::ingest_text;
+
+fn default_k() -> usize {
+    5
+}
+
+fn default_seed() -> u64 {
+    42
+}
+
+/// 2026-01-01T00:00:00Z - a fixed "now" so suites do not age between runs.
+fn default_now() -> u64 {
+    1_767_225_600
+}
+
+/// A labeled recall benchmark.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSuite {
+    #[serde(default)]
+    pub name: String,
+    /// Cutoff for precision@k.
+    #[serde(default = "default_k")]
+    pub k: usize,
+    /// RNG seed used to place the corpus on the manifold.
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+    /// Unix seconds the clock is frozen at during the run.
+    #[serde(default = "default_now")]
+    pub now: u64,
+    pub corpus: Vec<EvalDocument>,
+    pub queries: Vec<EvalQuery>,
+}
+
+/// One document in the fixture corpus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalDocument {
+    /// Label that queries refer to in `expect_docs`.
+    pub name: String,
+    pub text: String,
+    /// Store as a conscious (salient) memory instead of an ingested episode.
+    /// `DECISION:` / `PREFERENCE:` prefixes are honored.
+    #[serde(default)]
+    pub conscious: bool,
+    /// Episode age relative to `now`, for exercising recency decay.
+    #[serde(default)]
+    pub age_days: u64,
+}
+
+/// A query and the memories it is expected to recall.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalQuery {
+    pub query: String,
+    /// Document names whose neighborhoods count as relevant.
+    #[serde(default)]
+    pub expect_docs: Vec<String>,
+    /// Case-insensitive substrings of neighborhood text that count as relevant.
+    #[serde(default)]
+    pub expect_snippets: Vec<String>,
+}
+
+/// One ranked result for a query.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EvalHit {
+    /// 1-based rank.
+    pub rank: usize,
+    /// `conscious`, `subconscious`, or `novel`.
+    pub category: String,
+    pub document: String,
+    pub score: f64,
+    pub relevant: bool,
+}
+
+/// Scores for a single query.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct QueryEval {
+    pub query: String,
+    pub precision_at_k: f64,
+    pub reciprocal_rank: f64,
+    /// Top-k hits in rank order.
+    pub hits: Vec<EvalHit>,
+}
+
+/// Retrieved/relevant counts for one recall category across all queries.
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CategoryStats {
+    pub retrieved: usize,
+    pub relevant: usize,
+}
+
+/// Aggregate results of [`run_suite`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EvalReport {
+    pub suite: String,
+    pub k: usize,
+    pub mean_precision_at_k: f64,
+    pub mrr: f64,
+    /// Keyed by category name (`conscious`, `subconscious`, `novel`).
+    pub categories: BTreeMap<String, CategoryStats>,
+    pub queries: Vec<QueryEval>,
+}
+
+/// Build the suite corpus into a fresh system. Returns the system and a map
+/// from neighborhood ID to the document it came from.
+fn build_corpus(suite: &EvalSuite) -> (DAESystem, HashMap<Uuid, String>) {
+    let mut rng = SmallRng::seed_from_u64(suite.seed);
+    let mut system = DAESystem::new("eval");
+    let mut origin = HashMap::new();
+
+    for doc in &suite.corpus {
+        if doc.conscious {
+            let id = mark_salient_typed(&mut system, &doc.text, &mut rng);
+            origin.insert(id, doc.name.clone());
+        } else {
+            let mut episode = ingest_text(&doc.text, Some(&doc.name), &mut rng);
+            episode.timestamp = unix_to_iso8601(suite.now.saturating_sub(doc.age_days * 86_400));
+            for nbhd in &episode.neighborhoods {
+                origin.insert(nbhd.id, doc.name.clone());
+            }
+            system.add_episode(episode);
+        }
+    }
+
+    (system, origin)
+}
+
+fn category_name(category: RecallCategory) -> &'static str {
+    match category {
+        RecallCategory::Conscious => "conscious",
+        RecallCategory::Subconscious => "subconscious",
+        RecallCategory::Novel => "novel",
+    }
+}
+
+/// Run every query in `suite` and score the ranked recall.
+#[must_use]
+pub fn run_suite(suite: &EvalSuite) -> EvalReport {
+    let _clock = freeze_clock(suite.now);
+    let k = suite.k.max(1);
+
+    let mut categories: BTreeMap<String, CategoryStats> = BTreeMap::new();
+    let mut queries = Vec::with_capacity(suite.queries.len());
+
+    for q in &suite.queries {
+        let (mut system, origin) = build_corpus(suite);
+
+        let query_result = QueryEngine::process_query(&mut system, &q.query);
+        let surface = compute_surface(&system, &query_result);
+        let index = compose_index(&mut system, &surface, &query_result, None);
+
+        // Stable order: score, then document name, so ties do not depend
+        // on HashMap iteration.
+        let mut entries: Vec<_> = index
+            .entries
+            .iter()
+            .map(|e| {
+                let doc = origin.get(&e.neighborhood_id).cloned().unwrap_or_default();
+                (e, doc)
+            })
+            .collect();
+        entries.sort_by(|(a, da), (b, db)| b.score.total_cmp(&a.score).then_with(|| da.cmp(db)));
+
+        let snippets: Vec<String> = q.expect_snippets.iter().map(|s| s.to_lowercase()).collect();
+
+        let hits: Vec<EvalHit> = entries
+            .into_iter()
+            .take(k)
+            .enumerate()
+            .map(|(i, (entry, document))| {
+                let text = system
+                    .get_neighborhood_ref(entry.neighborhood_id)
+                    .map(|r| system.get_neighborhood(r).source_text.to_lowercase())
+                    .unwrap_or_default();
+                let relevant = q.expect_docs.contains(&document)
+                    || snippets.iter().any(|s| text.contains(s.as_str()));
+                EvalHit {
+                    rank: i + 1,
+                    category: category_name(entry.category).to_string(),
+                    document,
+                    score: entry.score,
+                    relevant,
+                }
+            })
+            .collect();
+
+        for hit in &hits {
+            let stats = categories.entry(hit.category.clone()).or_default();
+            stats.retrieved += 1;
+            if hit.relevant {
+                stats.relevant += 1;
+            }
+        }
+
+        let relevant_in_k = hits.iter().filter(|h| h.relevant).count();
+        let reciprocal_rank = hits
+            .iter()
+            .find(|h| h.relevant)
+            .map_or(0.0, |h| 1.0 / h.rank as f64);
+
+        queries.push(QueryEval {
+            query: q.query.clone(),
+            precision_at_k: relevant_in_k as f64 / k as f64,
+            reciprocal_rank,
+            hits,
+        });
+    }
+
+    let n = queries.len().max(1) as f64;
+    EvalReport {
+        suite: suite.name.clone(),
+        k,
+        mean_precision_at_k: queries.iter().map(|q| q.precision_at_k).sum::<f64>() / n,
+        mrr: queries.iter().map(|q| q.reciprocal_rank).sum::<f64>() / n,
+        categories,
+        queries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(name: &str, text: &str) -> EvalDocument {
+        EvalDocument {
+            name: name.to_string(),
+            text: text.to_string(),
+            conscious: false,
+            age_days: 0,
+        }
+    }
+
+    fn suite() -> EvalSuite {
+        EvalSuite {
+            name: "unit".to_string(),
+            k: 3,
+            seed: default_seed(),
+            now: default_now(),
+            corpus: vec![
+                doc(
+                    "sqlite",
+                    "SQLite runs in WAL mode with a busy timeout. Checkpoints truncate the WAL on shutdown.",
+                ),
+                doc(
+                    "tokenizer",
+                    "The tokenizer lowercases words and keeps apostrophes. Sentences are chunked in threes.",
+                ),
+            ],
+            queries: vec![EvalQuery {
+                query: "sqlite wal checkpoint".to_string(),
+                expect_docs: vec!["sqlite".to_string()],
+                expect_snippets: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_run_suite_scores_relevant_hit() {
+        let report = run_suite(&suite());
+        assert_eq!(report.queries.len(), 1);
+        let q = &report.queries[0];
+        assert!(!q.hits.is_empty());
+        assert!(q.hits[0].relevant, "sqlite doc should rank first");
+        assert!((q.reciprocal_rank - 1.0).abs() < 1e-10);
+        assert!((report.mrr - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_run_suite_is_deterministic() {
+        let a = run_suite(&suite());
+        let b = run_suite(&suite());
+        assert_eq!(a.queries[0].hits.len(), b.queries[0].hits.len());
+        for (x, y) in a.queries[0].hits.iter().zip(&b.queries[0].hits) {
+            assert_eq!(x.document, y.document);
+            assert!((x.score - y.score).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_snippet_expectation() {
+        let mut s = suite();
+        s.queries[0].expect_docs.clear();
+        s.queries[0].expect_snippets = vec!["BUSY TIMEOUT".to_string()];
+        let report = run_suite(&s);
+        assert!(report.queries[0].hits.iter().any(|h| h.relevant));
+    }
+
+    #[test]
+    fn test_no_relevant_hits_scores_zero() {
+        let mut s = suite();
+        s.queries[0].expect_docs = vec!["nonexistent".to_string()];
+        let report = run_suite(&s);
+        assert!(report.mrr.abs() < 1e-10);
+        assert!(report.mean_precision_at_k.abs() < 1e-10);
+    }
+}
@@ -8,6 +8,10 @@
 //!
 //! This is the geometric equivalent of reinforcement: the manifold reshapes
 //! itself based on what worked.
+//!
+//! Geometry alone forgets quickly: later queries re-activate and re-anchor
+//! a demoted memory. So each signal is also recorded on the neighborhood as
+//! a decayed feedback score, which ranking applies as a durable factor.
 
 use crate::constants::EPSILON;
 use crate::quaternion::Quaternion;
@@ -63,6 +67,14 @@ const BOOST_DRIFT_FACTOR: f64 = 0.15;
 /// drop below the vivid threshold after 1-2 demote signals.
 const DEMOTE_DECAY: u32 = 2;
 
+/// Weight kept by a neighborhood's earlier feedback each time a new signal
+/// arrives: `score = score * FEEDBACK_SCORE_DECAY ± 1`.
+///
+/// At 0.8: the latest signal always counts most, so a boost after two
+/// demotes is felt at once, and a long run of one signal saturates at
+/// ±1 / (1 - 0.8) = ±5 instead of growing without bound.
+const FEEDBACK_SCORE_DECAY: f64 = 0.8;
+
 /// Apply relevance feedback to neighborhoods that were recalled for a query.
 ///
 /// `query` - the original query text (used to compute the centroid for boosting).
@@ -77,6 +89,9 @@ const DEMOTE_DECAY: u32 = 2;
 /// activation count reduced. This makes them less anchored, more likely to
 /// drift away in future queries, and eventually GC-eligible.
 ///
+/// Either way, each specified neighborhood's `feedback_score` records the
+/// signal, whether or not any of its words match the query.
+///
 /// # Examples
 ///
 /// ```
@@ -120,12 +135,17 @@ pub fn apply_feedback(
         .flat_map(|token| system.get_word_occurrences(token))
         .collect();
 
+    let feedback_scores = record_feedback_scores(system, neighborhood_ids, signal);
+
     if query_refs.is_empty() {
         return FeedbackResult {
             boosted: 0,
             demoted: 0,
             centroid: None,
-            manifest: QueryManifest::default(),
+            manifest: QueryManifest {
+                feedback_scores,
+                ..QueryManifest::default()
+            },
         };
     }
 
@@ -143,10 +163,39 @@ pub fn apply_feedback(
         .copied()
         .collect();
 
-    match signal {
+    let mut result = match signal {
         FeedbackSignal::Boost => apply_boost(system, &query_refs, &target_refs),
         FeedbackSignal::Demote => apply_demote(system, &target_refs),
+    };
+    result.manifest.feedback_scores = feedback_scores;
+    result
+}
+
+/// Fold `signal` into the feedback score of each listed neighborhood that
+/// exists, once per neighborhood. Returns the new scores.
+fn record_feedback_scores(
+    system: &mut DAESystem,
+    neighborhood_ids: &[uuid::Uuid],
+    signal: FeedbackSignal,
+) -> Vec<(uuid::Uuid, f64)> {
+    let delta = match signal {
+        FeedbackSignal::Boost => 1.0,
+        FeedbackSignal::Demote => -1.0,
+    };
+    let mut seen = std::collections::HashSet::new();
+    let mut scores = Vec::new();
+    for &id in neighborhood_ids {
+        if !seen.insert(id) {
+            continue;
+        }
+        let Some(r) = system.get_neighborhood_ref(id) else {
+            continue;
+        };
+        let nbhd = &mut system.resolve_episode_mut(r.episode_ref).neighborhoods[r.neighborhood_idx];
+        nbhd.feedback_score = nbhd.feedback_score * FEEDBACK_SCORE_DECAY + delta;
+        scores.push((id, nbhd.feedback_score));
     }
+    scores
 }
 
 /// Boost: SLERP target occurrences toward the IDF-weighted query centroid.
@@ -169,7 +218,7 @@ fn apply_boost(
         .iter()
         .map(|r| {
             let word = system.get_occurrence(*r).word.clone();
-            system.get_word_weight(&word)
+            system.idf_weight(&word)
         })
         .collect();
 
@@ -194,7 +243,7 @@ fn apply_boost(
         .iter()
         .map(|r| {
             let word = system.get_occurrence(*r).word.clone();
-            system.get_word_weight(&word)
+            system.idf_weight(&word)
         })
         .collect();
 
@@ -211,7 +260,7 @@ fn apply_boost(
         if factor > EPSILON {
             let new_pos = occ.position.slerp(centroid, factor);
             let occ = system.get_occurrence_mut(*r);
-            occ.position = new_pos;
+            occ.move_to(new_pos);
             // Also bump activation - this memory proved useful
             occ.activation_count = occ.activation_count.saturating_add(1);
             drifted.push(occ.id);
@@ -228,6 +277,8 @@ fn apply_boost(
             drifted,
             activated,
             demoted_activations: Vec::new(),
+            spilled: Vec::new(),
+            feedback_scores: Vec::new(),
         },
     }
 }
@@ -255,6 +306,8 @@ fn apply_demote(system: &mut DAESystem, target_refs: &[OccurrenceRef]) -> Feedba
             drifted: Vec::new(),
             activated: Vec::new(),
             demoted_activations,
+            spilled: Vec::new(),
+            feedback_scores: Vec::new(),
         },
     }
 }
@@ -560,6 +613,7 @@ mod tests {
         let requests = vec![BatchQueryRequest {
             query: "quantum physics".to_string(),
             max_tokens: Some(4096),
+            project: None,
         }];
 
         let output = BatchQueryEngine::batch_query(&mut sys, &requests);
@@ -570,4 +624,33 @@ mod tests {
             "batch query should track activated occurrence IDs"
         );
     }
+
+    #[test]
+    fn test_feedback_score_decays_and_saturates() {
+        let mut sys = make_feedback_system();
+        let nbhd_id = sys.episodes[0].neighborhoods[0].id;
+        let score = |sys: &DAESystem| sys.episodes[0].neighborhoods[0].feedback_score;
+
+        // Recorded once per neighborhood, even when no query word matches
+        let result = apply_feedback(
+            &mut sys,
+            "unrelated words",
+            &[nbhd_id, nbhd_id],
+            FeedbackSignal::Demote,
+        );
+        assert_eq!(result.demoted, 0);
+        assert_eq!(result.manifest.feedback_scores, vec![(nbhd_id, -1.0)]);
+
+        apply_feedback(&mut sys, "quantum", &[nbhd_id], FeedbackSignal::Demote);
+        assert!((score(&sys) + 1.8).abs() < 1e-9);
+        // A boost counts fully against the decayed demotes
+        apply_feedback(&mut sys, "quantum", &[nbhd_id], FeedbackSignal::Boost);
+        assert!((score(&sys) + 0.44).abs() < 1e-9);
+
+        for _ in 0..100 {
+            apply_feedback(&mut sys, "quantum", &[nbhd_id], FeedbackSignal::Boost);
+        }
+        assert!(score(&sys) < 1.0 / (1.0 - FEEDBACK_SCORE_DECAY));
+        assert_eq!(sys.episodes[0].neighborhoods[1].feedback_score, 0.0);
+    }
 }
@@ -0,0 +1,253 @@
+//! Structural and geometric invariants of a [`DAESystem`].
+//!
+//! Every operation on the manifold assumes unit quaternions, phasors in
+//! `[0, τ)`, and occurrences that point back at the neighborhood holding
+//! them. A break in any of these rarely fails loudly; it shows up later as
+//! odd recall. [`check_system`] walks the whole system and reports every
+//! violation it finds, for property tests and `am doctor --deep`.
+
+use std::collections::HashSet;
+use std::f64::consts::TAU;
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::episode::Episode;
+use crate::quaternion::Quaternion;
+use crate::system::DAESystem;
+
+/// Allowed deviation of a quaternion's norm from 1. Looser than the load
+/// check in am-store because drift accumulates rounding between saves.
+pub const NORM_TOLERANCE: f64 = 1e-6;
+
+/// One broken invariant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// What the violation is attached to, e.g. `occurrence <uuid>`.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Check every invariant over both manifolds. Returns an empty list for a
+/// healthy system.
+///
+/// Checked:
+/// - the conscious episode is flagged conscious and no other episode is
+/// - neighborhood seeds and occurrence positions are finite unit quaternions
+/// - phasor angles are finite and in `[0, τ)`
+/// - accumulated drift is finite and not negative
+/// - spillover residue is in `[0, 1)`
+/// - each occurrence's `neighborhood_id` is the neighborhood holding it
+/// - episode, neighborhood, and occurrence IDs are unique
+/// - every epoch is below `next_epoch`
+/// - `superseded_by` points at a neighborhood that exists
+#[must_use]
+pub fn check_system(system: &DAESystem) -> Vec<Violation> {
+    let mut checker = Checker::default();
+
+    if !system.conscious_episode.is_conscious {
+        checker.report(
+            episode_location(&system.conscious_episode),
+            "conscious episode is not flagged conscious",
+        );
+    }
+    for episode in &system.episodes {
+        if episode.is_conscious {
+            checker.report(
+                episode_location(episode),
+                "subconscious episode is flagged conscious",
+            );
+        }
+    }
+
+    let all_episodes = || {
+        system
+            .episodes
+            .iter()
+            .chain(std::iter::once(&system.conscious_episode))
+    };
+    let neighborhood_ids: HashSet<Uuid> = all_episodes()
+        .flat_map(|e| e.neighborhoods.iter().map(|n| n.id))
+        .collect();
+
+    for episode in all_episodes() {
+        checker.check_episode(episode, system.next_epoch, &neighborhood_ids);
+    }
+
+    checker.violations
+}
+
+#[derive(Default)]
+struct Checker {
+    violations: Vec<Violation>,
+    episode_ids: HashSet<Uuid>,
+    neighborhood_ids: HashSet<Uuid>,
+    occurrence_ids: HashSet<Uuid>,
+}
+
+impl Checker {
+    fn report(&mut self, location: String, message: impl Into<String>) {
+        self.violations.push(Violation {
+            location,
+            message: message.into(),
+        });
+    }
+
+    fn check_episode(&mut self, episode: &Episode, next_epoch: u64, known: &HashSet<Uuid>) {
+        if !self.episode_ids.insert(episode.id) {
+            self.report(episode_location(episode), "duplicate episode ID");
+        }
+
+        for nbhd in &episode.neighborhoods {
+            let location = format!("neighborhood {}", nbhd.id);
+            if !self.neighborhood_ids.insert(nbhd.id) {
+                self.report(location.clone(), "duplicate neighborhood ID");
+            }
+            self.check_quaternion(&location, "seed", nbhd.seed);
+            if nbhd.epoch >= next_epoch {
+                self.report(
+                    location.clone(),
+                    format!("epoch {} is not below next_epoch {next_epoch}", nbhd.epoch),
+                );
+            }
+            if let Some(target) = nbhd.superseded_by
+                && !known.contains(&target)
+            {
+                self.report(
+                    location.clone(),
+                    format!("superseded by missing neighborhood {target}"),
+                );
+            }
+
+            for occ in &nbhd.occurrences {
+                let location = format!("occurrence {}", occ.id);
+                if !self.occurrence_ids.insert(occ.id) {
+                    self.report(location.clone(), "duplicate occurrence ID");
+                }
+                self.check_quaternion(&location, "position", occ.position);
+                let theta = occ.phasor.theta;
+                if !theta.is_finite() || !(0.0..TAU).contains(&theta) {
+                    self.report(location.clone(), format!("phasor {theta} outside [0, τ)"));
+                }
+                if !occ.drift.is_finite() || occ.drift < 0.0 {
+                    self.report(location.clone(), format!("drift {} is invalid", occ.drift));
+                }
+                if !(0.0..1.0).contains(&occ.spillover) {
+                    self.report(
+                        location.clone(),
+                        format!("spillover {} outside [0, 1)", occ.spillover),
+                    );
+                }
+                if occ.neighborhood_id != nbhd.id {
+                    self.report(
+                        location,
+                        format!(
+                            "neighborhood_id {} does not match containing neighborhood {}",
+                            occ.neighborhood_id, nbhd.id
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_quaternion(&mut self, location: &str, what: &str, q: Quaternion) {
+        let norm = q.dot(q).sqrt();
+        if !norm.is_finite() || (norm - 1.0).abs() > NORM_TOLERANCE {
+            self.report(
+                location.to_string(),
+                format!("{what} is not a unit quaternion (norm {norm})"),
+            );
+        }
+    }
+}
+
+fn episode_location(episode: &Episode) -> String {
+    format!("episode {} ({:?})", episode.id, episode.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phasor::DaemonPhasor;
+    use crate::query::QueryEngine;
+    use crate::tokenizer::ingest_text;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    fn healthy_system() -> DAESystem {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut sys = DAESystem::new("test");
+        sys.add_episode(ingest_text(
+            "Quantum physics studies particles. Waves and particles interfere.",
+            None,
+            &mut rng,
+        ));
+        let old = sys.add_to_conscious("particles behave like waves", &mut rng);
+        let new = sys.add_to_conscious("particles and waves are one", &mut rng);
+        sys.mark_superseded(old, new);
+        sys
+    }
+
+    #[test]
+    fn healthy_system_has_no_violations() {
+        let mut sys = healthy_system();
+        assert_eq!(check_system(&sys), vec![]);
+        QueryEngine::process_query(&mut sys, "particles waves");
+        assert_eq!(check_system(&sys), vec![]);
+    }
+
+    #[test]
+    fn reports_each_broken_invariant() {
+        let mut sys = healthy_system();
+        let nbhd = &mut sys.episodes[0].neighborhoods[0];
+        nbhd.seed.w *= 2.0;
+        nbhd.occurrences[0].phasor = DaemonPhasor { theta: -1.0 };
+        nbhd.occurrences[1].neighborhood_id = Uuid::new_v4();
+        nbhd.occurrences[2].drift = f64::NAN;
+        nbhd.occurrences[2].spillover = 1.5;
+        nbhd.superseded_by = Some(Uuid::new_v4());
+        sys.conscious_episode.neighborhoods[0].epoch = sys.next_epoch;
+
+        let messages: Vec<String> = check_system(&sys).into_iter().map(|v| v.message).collect();
+        assert_eq!(messages.len(), 7, "{messages:#?}");
+        assert!(messages[0].starts_with("seed is not a unit quaternion"));
+        assert!(messages[1].starts_with("superseded by missing neighborhood"));
+        assert!(messages[2].starts_with("phasor -1 outside"));
+        assert!(messages[3].starts_with("neighborhood_id"));
+        assert!(messages[4].starts_with("drift NaN"));
+        assert!(messages[5].starts_with("spillover 1.5 outside"));
+        assert!(messages[6].starts_with("epoch"));
+    }
+
+    #[test]
+    fn reports_duplicate_ids_and_conscious_flags() {
+        let mut sys = healthy_system();
+        let copy = sys.episodes[0].clone();
+        sys.episodes.push(copy);
+        sys.conscious_episode.is_conscious = false;
+
+        let violations = check_system(&sys);
+        assert!(
+            violations[0]
+                .message
+                .contains("conscious episode is not flagged")
+        );
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.message == "duplicate episode ID")
+        );
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.message == "duplicate occurrence ID")
+        );
+    }
+}
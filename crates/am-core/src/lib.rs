@@ -37,7 +37,9 @@ pub mod batch;
 pub mod compose;
 pub mod constants;
 pub mod episode;
+pub mod eval;
 pub mod feedback;
+pub mod invariants;
 pub mod neighborhood;
 pub mod occurrence;
 pub mod phasor;
@@ -45,10 +47,14 @@ pub mod quaternion;
 pub mod query;
 pub(crate) mod recency;
 pub mod salient;
+pub mod sanitize;
 pub(crate) mod scoring;
 pub mod serde_compat;
+pub(crate) mod speculation;
+pub mod spelling;
 pub mod store_trait;
 pub mod surface;
 pub mod system;
 pub mod time;
 pub mod tokenizer;
+pub mod word_stats;
@@ -6,6 +6,7 @@ use crate::constants::{M, NEIGHBORHOOD_RADIUS, THRESHOLD};
 use crate::occurrence::Occurrence;
 use crate::phasor::DaemonPhasor;
 use crate::quaternion::Quaternion;
+use crate::tokenizer::Token;
 
 /// Classification of a neighborhood's content.
 /// Decisions and preferences get special treatment in scoring and composition.
@@ -23,8 +24,14 @@ pub enum NeighborhoodType {
     Insight,
     /// Bulk-imported reference material (via `am_ingest`).
     Ingested,
+    /// A step-by-step plan (`PLAN:` prefix or a detected numbered list).
+    Plan,
 }
 
+/// Types listed by the decision registry (`DAESystem::decisions`): settled
+/// choices an agent should look up rather than re-derive.
+pub const DECISION_TYPES: &[NeighborhoodType] = &[NeighborhoodType::Decision];
+
 impl NeighborhoodType {
     #[must_use]
     pub fn as_str(&self) -> &'static str {
@@ -34,6 +41,7 @@ impl NeighborhoodType {
             Self::Preference => "preference",
             Self::Insight => "insight",
             Self::Ingested => "ingested",
+            Self::Plan => "plan",
         }
     }
 
@@ -44,6 +52,7 @@ impl NeighborhoodType {
             "preference" => Self::Preference,
             "insight" => Self::Insight,
             "ingested" => Self::Ingested,
+            "plan" => Self::Plan,
             _ => Self::Memory,
         }
     }
@@ -66,6 +75,32 @@ pub struct Neighborhood {
     /// Superseded neighborhoods are excluded from recall.
     #[serde(default)]
     pub superseded_by: Option<Uuid>,
+    /// Model or agent that created this neighborhood. Only set on conscious
+    /// neighborhoods; subconscious ones inherit their episode's actor.
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// Project a conscious memory belongs to, lowercase. Recall discounts
+    /// memories tagged for another project; `None` applies everywhere.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// When this neighborhood was created (ISO 8601). Only stamped on
+    /// conscious neighborhoods; `None` for those that predate the field.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Decayed sum of `am_feedback` signals: +1 per boost, -1 per demote,
+    /// earlier signals shrinking by `FEEDBACK_SCORE_DECAY` each time a new
+    /// one arrives. Ranking multiplies scores by a factor derived from it.
+    #[serde(default)]
+    pub feedback_score: f64,
+    /// Shared by the chunks of one long salient text, which are stored and
+    /// scored separately; `None` for a memory stored whole.
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
+    /// Insertion order within the store (the neighborhood row's rowid once
+    /// saved); 0 until the system assigns one. Ranking breaks score ties on
+    /// it, so equal scores never fall back to hash-map iteration order.
+    #[serde(skip)]
+    pub seq: u64,
 }
 
 impl Neighborhood {
@@ -79,6 +114,12 @@ impl Neighborhood {
             neighborhood_type: NeighborhoodType::default(),
             epoch: 0,
             superseded_by: None,
+            actor: None,
+            project: None,
+            created_at: None,
+            feedback_score: 0.0,
+            group_id: None,
+            seq: 0,
         }
     }
 
@@ -103,6 +144,16 @@ impl Neighborhood {
         neighborhood
     }
 
+    /// Record where each word sits in `source_text`, from the spans of the
+    /// tokens the occurrences were built from. `tokens` lines up with the
+    /// first occurrences; any after it (label words) keep no span.
+    pub fn record_spans(&mut self, tokens: &[Token]) {
+        for (occ, token) in self.occurrences.iter_mut().zip(tokens) {
+            debug_assert_eq!(occ.word, token.text);
+            occ.span = Some((token.byte_start, token.byte_end));
+        }
+    }
+
     #[must_use]
     pub fn count(&self) -> usize {
         self.occurrences.len()
@@ -113,6 +164,40 @@ impl Neighborhood {
         self.occurrences.iter().map(|o| o.activation_count).sum()
     }
 
+    /// Hash of the source text with case and whitespace normalized, so the
+    /// same chunk stored by two episodes hashes alike. `None` when the text
+    /// has no content to compare.
+    #[must_use]
+    pub fn content_hash(&self) -> Option<u64> {
+        let normalized = normalize_content(&self.source_text);
+        (!normalized.is_empty()).then(|| crate::tokenizer::fnv1a(normalized.as_bytes()))
+    }
+
+    /// Whether two neighborhoods hold the same text up to case and
+    /// whitespace. Confirms a [`Self::content_hash`] match.
+    #[must_use]
+    pub fn same_content(&self, other: &Self) -> bool {
+        normalize_content(&self.source_text) == normalize_content(&other.source_text)
+    }
+
+    /// Estimated LLM tokens of the source text, as budgets count it.
+    #[must_use]
+    pub fn llm_tokens(&self) -> usize {
+        crate::compose::estimate_llm_tokens(&self.source_text)
+    }
+
+    /// Mean and largest occurrence `drift`, in radians: how far recall has
+    /// reshaped this memory since it was stored. `(0.0, 0.0)` when empty.
+    #[must_use]
+    pub fn drift(&self) -> (f64, f64) {
+        if self.occurrences.is_empty() {
+            return (0.0, 0.0);
+        }
+        let total: f64 = self.occurrences.iter().map(|o| o.drift).sum();
+        let max = self.occurrences.iter().map(|o| o.drift).fold(0.0, f64::max);
+        (total / self.occurrences.len() as f64, max)
+    }
+
     /// Neighborhood mass relative to total system occurrences.
     ///
     /// `n` is the total number of occurrences across all episodes in the system,
@@ -155,6 +240,14 @@ impl Neighborhood {
     }
 }
 
+/// Lowercased words of `text` joined by single spaces.
+fn normalize_content(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,6 +17,19 @@ pub struct Occurrence {
     pub activation_count: u32,
     pub id: Uuid,
     pub neighborhood_id: Uuid,
+    /// Total angle (radians) this occurrence has moved since it was
+    /// placed. Drift and coupling add to it; it never shrinks.
+    #[serde(default)]
+    pub drift: f64,
+    /// Fractional activation credited by activated neighbors, always
+    /// below 1.0. Whole units move into `activation_count`.
+    #[serde(default)]
+    pub spillover: f64,
+    /// Byte range of this word in its neighborhood's `source_text`, for
+    /// words ingested from that text. `None` for label words, query
+    /// text, and occurrences stored before spans were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<(usize, usize)>,
 }
 
 impl Occurrence {
@@ -34,14 +47,33 @@ impl Occurrence {
             activation_count: 0,
             id: Uuid::new_v4(),
             neighborhood_id,
+            drift: 0.0,
+            spillover: 0.0,
+            span: None,
         }
     }
 
+    /// Move to `position`, adding the angle travelled to `drift`.
+    pub fn move_to(&mut self, position: Quaternion) {
+        self.drift += self.position.angular_distance(position);
+        self.position = position;
+    }
+
     /// Increment activation count.
     pub fn activate(&mut self) {
         self.activation_count = self.activation_count.saturating_add(1);
     }
 
+    /// Add fractional activation `credit` (at most 1.0), moving any whole
+    /// unit it completes into `activation_count`.
+    pub fn credit(&mut self, credit: f64) {
+        self.spillover += credit;
+        while self.spillover >= 1.0 {
+            self.spillover -= 1.0;
+            self.activate();
+        }
+    }
+
     /// `OpenClaw` drift rate formula: ratio / THRESHOLD, capped at 0.
     /// Fresh words (c=0) don't drift. Drift increases with activation
     /// until anchored at c/C > THRESHOLD.
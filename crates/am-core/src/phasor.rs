@@ -11,10 +11,18 @@ pub struct DaemonPhasor {
 
 impl DaemonPhasor {
     /// Create a phasor with the given phase angle, normalized to [0, 2π).
+    /// A non-finite angle stays non-finite; the store rejects or repairs it
+    /// on load.
     #[must_use]
     pub fn new(theta: f64) -> Self {
+        let theta = theta.rem_euclid(std::f64::consts::TAU);
+        // rem_euclid rounds a tiny negative angle up to exactly 2π
         Self {
-            theta: theta.rem_euclid(std::f64::consts::TAU),
+            theta: if theta < std::f64::consts::TAU {
+                theta
+            } else {
+                0.0
+            },
         }
     }
 
@@ -25,6 +33,13 @@ impl DaemonPhasor {
         Self::new(base_theta + index as f64 * GOLDEN_ANGLE)
     }
 
+    /// Advance the phase by `delta` radians, wrapping into [0, 2π). Every
+    /// phase update goes through this, so theta never accumulates turns.
+    #[must_use]
+    pub fn wrapping_add(self, delta: f64) -> Self {
+        Self::new(self.theta + delta)
+    }
+
     /// Phasor interference: cos(self.theta - other.theta).
     /// Range: [-1, +1]. +1 = in phase, -1 = out of phase.
     #[must_use]
@@ -39,7 +54,7 @@ impl DaemonPhasor {
         let diff = ((other.theta - self.theta) + std::f64::consts::PI)
             .rem_euclid(std::f64::consts::TAU)
             - std::f64::consts::PI;
-        Self::new(self.theta + t * diff)
+        self.wrapping_add(t * diff)
     }
 }
 
@@ -62,6 +77,21 @@ mod tests {
         assert!(p2.theta >= 0.0 && p2.theta < std::f64::consts::TAU);
     }
 
+    #[test]
+    fn test_tiny_negative_wraps_below_tau() {
+        let p = DaemonPhasor::new(-1e-17);
+        assert!(p.theta < std::f64::consts::TAU, "theta {}", p.theta);
+        assert!(DaemonPhasor::new(0.0).wrapping_add(-1e-17).theta < std::f64::consts::TAU);
+    }
+
+    #[test]
+    fn test_wrapping_add_wraps_both_ways() {
+        let p = DaemonPhasor::new(6.0).wrapping_add(1.0);
+        assert!((p.theta - (7.0 - std::f64::consts::TAU)).abs() < 1e-12);
+        let q = DaemonPhasor::new(0.5).wrapping_add(-1.0);
+        assert!((q.theta - (std::f64::consts::TAU - 0.5)).abs() < 1e-12);
+    }
+
     #[test]
     fn test_golden_angle_spacing() {
         let p0 = DaemonPhasor::from_index(0, 0.0);
@@ -350,6 +350,31 @@ impl Quaternion {
             sum.z / sum.total_weight,
         )
     }
+
+    /// Stereographic projection to R³ from the pole `w = -1`, for plotting.
+    ///
+    /// A point in the `w < 0` hemisphere is projected as its antipode `-q`
+    /// (the same rotation, see [`Self::angular_distance`]), which amounts to
+    /// projecting it from the opposite pole. Every point therefore lands in
+    /// the closed unit ball and nothing near a pole blows up; the price is
+    /// that `q` and `-q` map to the same point, and points on the `w = 0`
+    /// equator to opposite points of the unit sphere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use am_core::quaternion::Quaternion;
+    ///
+    /// assert_eq!(Quaternion::identity().stereographic_project(), [0.0; 3]);
+    /// let p = Quaternion::new(0.0, 1.0, 0.0, 0.0).stereographic_project();
+    /// assert_eq!(p, [1.0, 0.0, 0.0]);
+    /// ```
+    #[must_use]
+    pub fn stereographic_project(self) -> [f64; 3] {
+        let q = if self.w < 0.0 { -self } else { self };
+        let scale = 1.0 / (1.0 + q.w);
+        [q.x * scale, q.y * scale, q.z * scale]
+    }
 }
 
 impl std::ops::Neg for Quaternion {
@@ -640,6 +665,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stereographic_project_handles_the_projection_pole() {
+        // At and near w = -1 the antipodal projection is used: no infinities,
+        // and the result is continuous across the pole
+        let pole = Quaternion::new(-1.0, 0.0, 0.0, 0.0);
+        assert_eq!(pole.stereographic_project(), [0.0; 3]);
+        let near = Quaternion::new(-1.0, 1e-9, -1e-9, 1e-9).stereographic_project();
+        assert!(
+            near.iter().all(|c| c.is_finite() && c.abs() < 1e-8),
+            "{near:?}"
+        );
+
+        let mut rng = rng();
+        for _ in 0..100 {
+            let q = Quaternion::random(&mut rng);
+            let p = q.stereographic_project();
+            assert!(p.iter().all(|c| c.is_finite()));
+            let r2: f64 = p.iter().map(|c| c * c).sum();
+            assert!(r2 <= 1.0 + 1e-12, "outside the unit ball: {p:?}");
+            assert_eq!(p, (-q).stereographic_project());
+
+            // Inverse of the projection from the w = -1 pole
+            let back = Quaternion::new(1.0 - r2, 2.0 * p[0], 2.0 * p[1], 2.0 * p[2]);
+            assert_approx_eq(back, q, 1e-9);
+        }
+    }
+
     #[test]
     fn test_weighted_centroid_result_is_unit() {
         let mut rng = rng();
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use uuid::Uuid;
 
-use crate::constants::{PAIRWISE_DRIFT_MAX_MOBILE, THRESHOLD};
+use crate::constants::{PAIRWISE_DRIFT_MAX_MOBILE, QUERY_DRIFT_SCALE, THRESHOLD};
 use crate::phasor::DaemonPhasor;
 use crate::quaternion::Quaternion;
+use crate::spelling::Correction;
 use crate::system::{ActivationResult, DAESystem, OccurrenceRef};
 use crate::tokenizer::tokenize;
 
@@ -22,6 +24,39 @@ pub struct QueryManifest {
     /// Occurrence IDs with absolute activation counts after demotion.
     /// Used by feedback demote where activation is decremented, not incremented.
     pub demoted_activations: Vec<(Uuid, u32)>,
+    /// Occurrence IDs credited by activation spillover. Their activation
+    /// count and residue are written as absolute values.
+    pub spilled: Vec<Uuid>,
+    /// Neighborhood IDs with absolute feedback scores after a feedback
+    /// signal.
+    pub feedback_scores: Vec<(Uuid, f64)>,
+}
+
+impl QueryManifest {
+    /// Activation increments folded per occurrence (see
+    /// `fold_activation_deltas`), ready for one batched write.
+    #[must_use]
+    pub fn activation_deltas(&self) -> Vec<(Uuid, u32)> {
+        fold_activation_deltas(&self.activated)
+    }
+}
+
+/// Fold repeated occurrence IDs into `(id, delta)` pairs in first-seen
+/// order. A query that activates one occurrence several times (e.g. a
+/// repeated word) then costs one write for it instead of several.
+#[must_use]
+pub fn fold_activation_deltas(ids: &[Uuid]) -> Vec<(Uuid, u32)> {
+    let mut index: HashMap<Uuid, usize> = HashMap::with_capacity(ids.len());
+    let mut deltas: Vec<(Uuid, u32)> = Vec::with_capacity(ids.len());
+    for &id in ids {
+        if let Some(&i) = index.get(&id) {
+            deltas[i].1 += 1;
+        } else {
+            index.insert(id, deltas.len());
+            deltas.push((id, 1));
+        }
+    }
+    deltas
 }
 
 /// Single interference result between a subconscious and conscious occurrence.
@@ -40,12 +75,99 @@ pub(crate) struct WordGroup {
 
 /// Full result from `process_query`.
 pub struct QueryResult {
+    /// The query text as given, for rerankers that score raw text.
+    pub query: String,
     pub activation: ActivationResult,
     pub(crate) interference: Vec<InterferenceResult>,
     /// Number of unique tokens in the original query (for density scoring).
     pub query_token_count: usize,
+    /// The query contains a planning verb, so Plan neighborhoods are boosted.
+    pub plan_intent: bool,
     /// Manifest of all mutations applied to the system during this query.
     pub manifest: QueryManifest,
+    /// Query words that matched nothing and were corrected to a stored
+    /// word (only with [`QueryOptions::spelling_tolerance`]).
+    pub corrections: Vec<Correction>,
+    /// Query words with more subconscious occurrences than
+    /// [`max_word_activations`](DAESystem::max_word_activations), of which
+    /// only a capped selection was activated.
+    pub capped_words: Vec<CappedWord>,
+    /// Time spent in each stage; zero for results built without running
+    /// the pipeline.
+    pub timings: QueryTimings,
+    /// Project this query's recall favours in place of the system's
+    /// [`project`](DAESystem::project), normalized by
+    /// [`normalize_project`](crate::system::normalize_project).
+    pub project: Option<String>,
+}
+
+/// Wall time of the stages of [`QueryEngine::process_query_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryTimings {
+    /// Activation and spillover.
+    pub activation: Duration,
+    /// Drift, interference, and Kuramoto coupling.
+    pub drift: Duration,
+}
+
+/// A query word whose activation was capped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CappedWord {
+    pub word: String,
+    /// Subconscious occurrences activated.
+    pub activated: usize,
+    /// Subconscious occurrences left out.
+    pub skipped: usize,
+}
+
+/// Per-query switches for [`QueryEngine::process_query_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryOptions {
+    /// Correct query words that activate nothing to the closest stored
+    /// word (see [`crate::spelling`]). Off by default: responses fed back
+    /// through `activate_response` should strengthen only what was said.
+    pub spelling_tolerance: bool,
+}
+
+/// A word strengthened by `activate_response`.
+#[derive(Debug, Clone)]
+pub struct StrengthenedWord {
+    pub word: String,
+    /// Occurrences of the word that were activated.
+    pub occurrences: usize,
+    /// IDF weight times `occurrences`.
+    pub score: f64,
+}
+
+/// A neighborhood whose activated occurrences moved during drift.
+#[derive(Debug, Clone)]
+pub struct MovedNeighborhood {
+    pub id: Uuid,
+    pub episode_name: String,
+    /// Mean angular distance (radians) moved by its activated occurrences.
+    pub mean_delta: f64,
+}
+
+/// What `activate_response` strengthened, for agent feedback.
+#[derive(Debug, Default)]
+pub struct ResponseActivation {
+    pub manifest: QueryManifest,
+    /// Total occurrences activated across both manifolds.
+    pub activated: usize,
+    /// Strongest activated words, by IDF-weighted activation.
+    pub top_words: Vec<StrengthenedWord>,
+    /// Neighborhoods that moved the most, largest mean delta first.
+    pub moved: Vec<MovedNeighborhood>,
+    /// Words present in both manifolds that Kuramoto coupling acted on.
+    pub coupled_word_groups: usize,
+}
+
+/// What [`QueryEngine::activate_with`] activated.
+struct Activated {
+    activation: ActivationResult,
+    ids: Vec<Uuid>,
+    corrections: Vec<Correction>,
+    capped_words: Vec<CappedWord>,
 }
 
 /// Stateless query processor operating on a `DAESystem`.
@@ -56,6 +178,13 @@ impl QueryEngine {
     ///
     /// Returns the activation result and a list of activated occurrence UUIDs.
     pub fn activate(system: &mut DAESystem, query: &str) -> (ActivationResult, Vec<Uuid>) {
+        let activated = Self::activate_with(system, query, QueryOptions::default());
+        (activated.activation, activated.ids)
+    }
+
+    /// [`activate`](Self::activate), also returning the spelling
+    /// corrections applied under `options` and the words that were capped.
+    fn activate_with(system: &mut DAESystem, query: &str, options: QueryOptions) -> Activated {
         let tokens = tokenize(query);
         let mut seen = std::collections::HashSet::new();
         let unique: Vec<String> = tokens
@@ -66,10 +195,33 @@ impl QueryEngine {
         let mut result = ActivationResult {
             subconscious: Vec::new(),
             conscious: Vec::new(),
+            capped: 0,
         };
 
+        let mut corrections: Vec<Correction> = Vec::new();
+        let mut capped_words: Vec<CappedWord> = Vec::new();
         for token in &unique {
-            let activation = system.activate_word(token);
+            let mut word = token.to_lowercase();
+            let mut activation = system.activate_word(token);
+            if options.spelling_tolerance
+                && activation.subconscious.is_empty()
+                && activation.conscious.is_empty()
+                && let Some(correction) = system.closest_word(token)
+                && !seen.contains(&correction.word)
+                && !corrections.iter().any(|c| c.word == correction.word)
+            {
+                activation = system.activate_word(&correction.word);
+                word.clone_from(&correction.word);
+                corrections.push(correction);
+            }
+            if activation.capped > 0 {
+                capped_words.push(CappedWord {
+                    word,
+                    activated: activation.subconscious.len(),
+                    skipped: activation.capped,
+                });
+            }
+            result.capped += activation.capped;
             result.subconscious.extend(activation.subconscious);
             result.conscious.extend(activation.conscious);
         }
@@ -81,7 +233,12 @@ impl QueryEngine {
             .map(|r| system.get_occurrence(*r).id)
             .collect();
 
-        (result, activated_ids)
+        Activated {
+            activation: result,
+            ids: activated_ids,
+            corrections,
+            capped_words,
+        }
     }
 
     /// Full query pipeline: activate, drift, interference, Kuramoto, return.
@@ -103,11 +260,44 @@ impl QueryEngine {
     /// assert!(!result.activation.subconscious.is_empty());
     /// ```
     pub fn process_query(system: &mut DAESystem, query: &str) -> QueryResult {
-        let (activation, activated_ids) = Self::activate(system, query);
+        Self::process_query_with(system, query, QueryOptions::default())
+    }
+
+    /// [`process_query`](Self::process_query) with per-query options, e.g.
+    /// spelling tolerance for interactive queries.
+    pub fn process_query_with(
+        system: &mut DAESystem,
+        query: &str,
+        options: QueryOptions,
+    ) -> QueryResult {
+        let span = tracing::debug_span!(
+            "process_query",
+            tokens = tracing::field::Empty,
+            activated = tracing::field::Empty,
+            drifted = tracing::field::Empty,
+        )
+        .entered();
+        let started = Instant::now();
+        let Activated {
+            activation,
+            ids: activated_ids,
+            corrections,
+            capped_words,
+        } = Self::activate_with(system, query, options);
+        let spilled = system.spill_activation(
+            &[
+                activation.subconscious.as_slice(),
+                activation.conscious.as_slice(),
+            ]
+            .concat(),
+        );
+        let activation_time = started.elapsed();
+        let drift_started = Instant::now();
 
         // Unique token count (matches activate's dedup and batch_query's HashSet)
+        let tokens = tokenize(query);
+        let plan_intent = system.is_plan_query(&tokens);
         let query_token_count = {
-            let tokens = tokenize(query);
             let unique: std::collections::HashSet<String> =
                 tokens.into_iter().map(|t| t.to_lowercase()).collect();
             unique.len()
@@ -129,12 +319,12 @@ impl QueryEngine {
                 .collect();
             let sub: Vec<OccurrenceRef> = sub_words
                 .into_iter()
-                .filter(|(_, word)| system.get_word_weight(word) >= weight_floor)
+                .filter(|(_, word)| system.idf_weight(word) >= weight_floor)
                 .map(|(r, _)| r)
                 .collect();
             let con: Vec<OccurrenceRef> = con_words
                 .into_iter()
-                .filter(|(_, word)| system.get_word_weight(word) >= weight_floor)
+                .filter(|(_, word)| system.idf_weight(word) >= weight_floor)
                 .map(|(r, _)| r)
                 .collect();
             (sub, con)
@@ -145,32 +335,127 @@ impl QueryEngine {
             )
         };
 
-        let mut drifted = Self::drift_and_consolidate(system, &drift_sub);
-        drifted.extend(Self::drift_and_consolidate(system, &drift_con));
+        let mut drifted = Self::drift_and_consolidate(system, &drift_sub, QUERY_DRIFT_SCALE);
+        drifted.extend(Self::drift_and_consolidate(
+            system,
+            &drift_con,
+            QUERY_DRIFT_SCALE,
+        ));
 
         let (interference, word_groups) =
             Self::compute_interference(system, &activation.subconscious, &activation.conscious);
 
         drifted.extend(Self::apply_kuramoto_coupling(system, &word_groups));
 
+        span.record("tokens", query_token_count);
+        span.record("activated", activated_ids.len());
+        span.record("drifted", drifted.len());
+        let timings = QueryTimings {
+            activation: activation_time,
+            drift: drift_started.elapsed(),
+        };
+
         QueryResult {
+            query: query.to_string(),
             activation,
             interference,
             query_token_count,
+            plan_intent,
             manifest: QueryManifest {
                 drifted,
                 activated: activated_ids,
                 demoted_activations: Vec::new(),
+                spilled,
+                feedback_scores: Vec::new(),
             },
+            corrections,
+            capped_words,
+            timings,
+            project: None,
+        }
+    }
+
+    /// Look up a query's occurrences without touching the system: no
+    /// activation counts, spillover, drift, or Kuramoto coupling. The
+    /// result scores like a real query's but its manifest is empty, so
+    /// there is nothing to persist.
+    pub fn probe(system: &mut DAESystem, query: &str) -> QueryResult {
+        let tokens = tokenize(query);
+        let plan_intent = system.is_plan_query(&tokens);
+        let mut seen = std::collections::HashSet::new();
+        let unique: Vec<String> = tokens
+            .into_iter()
+            .map(|t| t.to_lowercase())
+            .filter(|t| seen.insert(t.clone()))
+            .collect();
+
+        let mut activation = ActivationResult {
+            subconscious: Vec::new(),
+            conscious: Vec::new(),
+            capped: 0,
+        };
+        let mut capped_words = Vec::new();
+        for token in &unique {
+            let (refs, skipped) = system.capped_word_occurrences(token);
+            let before = activation.subconscious.len();
+            for r in refs {
+                if r.is_conscious() {
+                    activation.conscious.push(r);
+                } else {
+                    activation.subconscious.push(r);
+                }
+            }
+            if skipped > 0 {
+                capped_words.push(CappedWord {
+                    word: token.clone(),
+                    activated: activation.subconscious.len() - before,
+                    skipped,
+                });
+            }
+            activation.capped += skipped;
+        }
+
+        let (interference, _) =
+            Self::compute_interference(system, &activation.subconscious, &activation.conscious);
+
+        QueryResult {
+            query: query.to_string(),
+            activation,
+            interference,
+            query_token_count: unique.len(),
+            plan_intent,
+            manifest: QueryManifest::default(),
+            corrections: Vec::new(),
+            capped_words,
+            timings: QueryTimings::default(),
+            project: None,
         }
     }
 
     /// Drift activated occurrences toward each other.
     /// Pairwise O(n^2) for <200 mobile, centroid O(n) for >=200.
     ///
+    /// `scale` in `[0, 1]` multiplies every SLERP factor: 1.0 is a full
+    /// query-strength step ([`QUERY_DRIFT_SCALE`]), 0.0 moves nothing.
+    /// Values outside the range are clamped; NaN moves nothing.
+    /// Occurrences of [drift-frozen](DAESystem::set_drift_frozen) words
+    /// never join the mobile set.
+    ///
     /// Returns the UUIDs of occurrences whose position or phasor changed.
-    pub fn drift_and_consolidate(system: &mut DAESystem, activated: &[OccurrenceRef]) -> Vec<Uuid> {
-        if activated.len() < 2 {
+    pub fn drift_and_consolidate(
+        system: &mut DAESystem,
+        activated: &[OccurrenceRef],
+        scale: f64,
+    ) -> Vec<Uuid> {
+        let _span =
+            tracing::debug_span!("drift_and_consolidate", activated = activated.len(), scale)
+                .entered();
+        let scale = if scale.is_nan() {
+            0.0
+        } else {
+            scale.clamp(0.0, 1.0)
+        };
+        if activated.len() < 2 || scale <= 0.0 {
             return Vec::new();
         }
 
@@ -184,7 +469,7 @@ impl QueryEngine {
             .collect();
 
         // Pre-filter: only mobile (drift rate > 0)
-        let mobile: Vec<OccurrenceRef> = activated
+        let mut mobile: Vec<OccurrenceRef> = activated
             .iter()
             .filter(|r| {
                 let occ = system.get_occurrence(**r);
@@ -194,14 +479,27 @@ impl QueryEngine {
             .copied()
             .collect();
 
+        // Frozen words activate and score but never move.
+        if !system.drift_frozen().is_empty() {
+            mobile.retain(|r| !system.is_drift_frozen(&system.get_occurrence(*r).word));
+        }
+
+        // Hapax words (df = 1) may activate but do not pull on drift.
+        if system.word_weight_policy().skip_hapax_drift {
+            mobile.retain(|r| {
+                let word = system.get_occurrence(*r).word.clone();
+                !system.is_hapax(&word)
+            });
+        }
+
         if mobile.len() < 2 {
             return Vec::new();
         }
 
         if mobile.len() >= PAIRWISE_DRIFT_MAX_MOBILE {
-            Self::centroid_drift(system, &mobile, &container_activations)
+            Self::centroid_drift(system, &mobile, &container_activations, scale)
         } else {
-            Self::pairwise_drift(system, &mobile, &container_activations)
+            Self::pairwise_drift(system, &mobile, &container_activations, scale)
         }
     }
 
@@ -213,6 +511,7 @@ impl QueryEngine {
         system: &mut DAESystem,
         mobile: &[OccurrenceRef],
         container_activations: &HashMap<OccurrenceRef, u32>,
+        scale: f64,
     ) -> Vec<Uuid> {
         // Snapshot current state to avoid read-after-write issues
         let states: Vec<(Quaternion, DaemonPhasor, f64, String)> = mobile
@@ -228,7 +527,7 @@ impl QueryEngine {
         // Compute IDF weights
         let weights: Vec<f64> = states
             .iter()
-            .map(|(_, _, _, w)| system.get_word_weight(w))
+            .map(|(_, _, _, w)| system.idf_weight(w))
             .collect();
 
         // Collect all deltas
@@ -257,12 +556,12 @@ impl QueryEngine {
                 let meeting = pos1.slerp(*pos2, weight);
 
                 if t1 > 0.0 {
-                    let factor = t1 * THRESHOLD;
+                    let factor = t1 * THRESHOLD * scale;
                     position_deltas[i].push((meeting, factor));
                     phasor_deltas[i].push((*phasor2, factor));
                 }
                 if t2 > 0.0 {
-                    let factor = t2 * THRESHOLD;
+                    let factor = t2 * THRESHOLD * scale;
                     position_deltas[j].push((meeting, factor));
                     phasor_deltas[j].push((*phasor1, factor));
                 }
@@ -281,7 +580,7 @@ impl QueryEngine {
             }
 
             let occ = system.get_occurrence_mut(*r);
-            occ.position = pos;
+            occ.move_to(pos);
             occ.phasor = phasor;
         }
 
@@ -304,13 +603,14 @@ impl QueryEngine {
         system: &mut DAESystem,
         mobile: &[OccurrenceRef],
         container_activations: &HashMap<OccurrenceRef, u32>,
+        scale: f64,
     ) -> Vec<Uuid> {
         // Snapshot in separate passes to avoid borrow conflicts
         let words: Vec<String> = mobile
             .iter()
             .map(|r| system.get_occurrence(*r).word.clone())
             .collect();
-        let idf_weights: Vec<f64> = words.iter().map(|w| system.get_word_weight(w)).collect();
+        let idf_weights: Vec<f64> = words.iter().map(|w| system.idf_weight(w)).collect();
         let positions: Vec<Quaternion> = mobile
             .iter()
             .map(|r| system.get_occurrence(*r).position)
@@ -337,10 +637,10 @@ impl QueryEngine {
                 continue;
             };
 
-            let factor = drift_rates[idx] * idf_weights[idx] * 0.5;
+            let factor = drift_rates[idx] * idf_weights[idx] * 0.5 * scale;
             if factor > 0.0 {
                 let occ = system.get_occurrence_mut(*r);
-                occ.position = occ.position.slerp(target, factor);
+                occ.move_to(occ.position.slerp(target, factor));
                 drifted_ids.push(occ.id);
             }
         }
@@ -362,6 +662,94 @@ impl QueryEngine {
         Self::apply_kuramoto_coupling(system, &word_groups)
     }
 
+    /// Activate a response text, drift and couple the activated occurrences,
+    /// and summarize what changed. `limit` caps `top_words` and `moved`.
+    ///
+    /// Drift runs at `drift_scale` (see [`Self::drift_and_consolidate`]),
+    /// or at the policy's `response_drift_scale` when `None`, so long
+    /// responses reshape the manifold more gently than a focused query.
+    pub fn activate_response(
+        system: &mut DAESystem,
+        text: &str,
+        limit: usize,
+        drift_scale: Option<f64>,
+    ) -> ResponseActivation {
+        let drift_scale =
+            drift_scale.unwrap_or_else(|| system.word_weight_policy().response_drift_scale);
+        let (activation, activated_ids) = Self::activate(system, text);
+        let all_refs: Vec<OccurrenceRef> = activation
+            .subconscious
+            .iter()
+            .chain(activation.conscious.iter())
+            .copied()
+            .collect();
+        let spilled = system.spill_activation(&all_refs);
+        let before: Vec<Quaternion> = all_refs
+            .iter()
+            .map(|r| system.get_occurrence(*r).position)
+            .collect();
+
+        let mut drifted = Self::drift_and_consolidate(system, &all_refs, drift_scale);
+        let (_, word_groups) =
+            Self::compute_interference(system, &activation.subconscious, &activation.conscious);
+        drifted.extend(Self::apply_kuramoto_coupling(system, &word_groups));
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for r in &all_refs {
+            *counts
+                .entry(system.get_occurrence(*r).word.clone())
+                .or_default() += 1;
+        }
+        let mut top_words: Vec<StrengthenedWord> = counts
+            .into_iter()
+            .map(|(word, occurrences)| {
+                let score = system.get_word_weight(&word) * occurrences as f64;
+                StrengthenedWord {
+                    word,
+                    occurrences,
+                    score,
+                }
+            })
+            .collect();
+        top_words.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.word.cmp(&b.word)));
+        top_words.truncate(limit);
+
+        // Per-neighborhood (first ref, delta sum, count)
+        let mut deltas: HashMap<Uuid, (OccurrenceRef, f64, usize)> = HashMap::new();
+        for (r, pos) in all_refs.iter().zip(&before) {
+            let delta = pos.angular_distance(system.get_occurrence(*r).position);
+            let nbhd_id = system.get_neighborhood_for_occurrence(*r).id;
+            let entry = deltas.entry(nbhd_id).or_insert((*r, 0.0, 0));
+            entry.1 += delta;
+            entry.2 += 1;
+        }
+        let mut moved: Vec<MovedNeighborhood> = deltas
+            .into_iter()
+            .filter(|(_, (_, sum, _))| *sum > 0.0)
+            .map(|(id, (r, sum, n))| MovedNeighborhood {
+                id,
+                episode_name: system.get_episode_for_occurrence(r).name.clone(),
+                mean_delta: sum / n as f64,
+            })
+            .collect();
+        moved.sort_by(|a, b| b.mean_delta.total_cmp(&a.mean_delta).then(a.id.cmp(&b.id)));
+        moved.truncate(limit);
+
+        ResponseActivation {
+            manifest: QueryManifest {
+                drifted,
+                activated: activated_ids,
+                demoted_activations: Vec::new(),
+                spilled,
+                feedback_scores: Vec::new(),
+            },
+            activated: all_refs.len(),
+            top_words,
+            moved,
+            coupled_word_groups: word_groups.len(),
+        }
+    }
+
     /// Compute interference between subconscious and conscious occurrences.
     /// Returns interference results and word groups for Kuramoto.
     #[must_use]
@@ -370,6 +758,13 @@ impl QueryEngine {
         subconscious: &[OccurrenceRef],
         conscious: &[OccurrenceRef],
     ) -> (Vec<InterferenceResult>, Vec<WordGroup>) {
+        let span = tracing::debug_span!(
+            "compute_interference",
+            subconscious = subconscious.len(),
+            conscious = conscious.len(),
+            pairs = tracing::field::Empty,
+        )
+        .entered();
         // Group by word
         let mut sub_by_word: HashMap<String, Vec<OccurrenceRef>> = HashMap::new();
         let mut con_by_word: HashMap<String, Vec<OccurrenceRef>> = HashMap::new();
@@ -425,16 +820,19 @@ impl QueryEngine {
             });
         }
 
+        span.record("pairs", results.len());
         (results, word_groups)
     }
 
-    /// Apply Kuramoto phase coupling across manifolds.
+    /// Apply Kuramoto phase coupling across manifolds. Drift-frozen words
+    /// keep their phases.
     ///
     /// Returns UUIDs of occurrences whose phasor was modified.
     pub(crate) fn apply_kuramoto_coupling(
         system: &mut DAESystem,
         word_groups: &[WordGroup],
     ) -> Vec<Uuid> {
+        let _span = tracing::debug_span!("kuramoto_coupling", groups = word_groups.len()).entered();
         if word_groups.is_empty() {
             return Vec::new();
         }
@@ -449,7 +847,10 @@ impl QueryEngine {
         let mut coupled_ids = Vec::new();
 
         for group in word_groups {
-            let w = system.get_word_weight(&group.word);
+            if system.is_drift_frozen(&group.word) {
+                continue;
+            }
+            let w = system.idf_weight(&group.word);
             let coupling = w * w;
 
             // Circular mean phases
@@ -491,13 +892,13 @@ impl QueryEngine {
             for r in &group.sub_refs {
                 let occ = system.get_occurrence_mut(*r);
                 let plasticity = occ.plasticity();
-                occ.phasor = DaemonPhasor::new(occ.phasor.theta + base_delta_sub * plasticity);
+                occ.phasor = occ.phasor.wrapping_add(base_delta_sub * plasticity);
                 coupled_ids.push(occ.id);
             }
             for r in &group.con_refs {
                 let occ = system.get_occurrence_mut(*r);
                 let plasticity = occ.plasticity();
-                occ.phasor = DaemonPhasor::new(occ.phasor.theta + base_delta_con * plasticity);
+                occ.phasor = occ.phasor.wrapping_add(base_delta_con * plasticity);
                 coupled_ids.push(occ.id);
             }
         }
@@ -1,6 +1,8 @@
 use super::*;
+use crate::constants::DEFAULT_RESPONSE_DRIFT_SCALE;
 use crate::episode::Episode;
 use crate::neighborhood::Neighborhood;
+use crate::system::WordWeightPolicy;
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -91,7 +93,7 @@ fn test_pairwise_drift_moves_closer() {
     let pos_before_1 = sys.get_occurrence(alpha_refs[1]).position;
     let dist_before = pos_before_0.angular_distance(pos_before_1);
 
-    QueryEngine::drift_and_consolidate(&mut sys, &activation.subconscious);
+    QueryEngine::drift_and_consolidate(&mut sys, &activation.subconscious, 1.0);
 
     let pos_after_0 = sys.get_occurrence(alpha_refs[0]).position;
     let pos_after_1 = sys.get_occurrence(alpha_refs[1]).position;
@@ -126,7 +128,7 @@ fn test_anchored_dont_move() {
 
     // Activate and drift
     let (activation, _) = QueryEngine::activate(&mut sys, "word1 word2");
-    QueryEngine::drift_and_consolidate(&mut sys, &activation.subconscious);
+    QueryEngine::drift_and_consolidate(&mut sys, &activation.subconscious, 1.0);
 
     let pos_after = sys.get_occurrence(refs[0]).position;
     assert_eq!(pos_before, pos_after, "anchored word should not move");
@@ -249,6 +251,66 @@ fn test_manifest_contains_activated_ids() {
     }
 }
 
+#[test]
+fn test_fold_activation_deltas_merges_repeats_in_order() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    assert_eq!(
+        fold_activation_deltas(&[a, b, a, c, a, b]),
+        vec![(a, 3), (b, 2), (c, 1)]
+    );
+    assert!(fold_activation_deltas(&[]).is_empty());
+}
+
+#[test]
+fn test_manifest_activation_deltas_cover_every_increment() {
+    let mut sys = make_test_system();
+    let result = QueryEngine::process_query(&mut sys, "quantum quantum physics");
+    let deltas = result.manifest.activation_deltas();
+    let total: u32 = deltas.iter().map(|(_, d)| d).sum();
+    assert_eq!(total as usize, result.manifest.activated.len());
+    for (id, delta) in &deltas {
+        let count = result
+            .manifest
+            .activated
+            .iter()
+            .filter(|a| *a == id)
+            .count();
+        assert_eq!(*delta as usize, count);
+    }
+}
+
+#[test]
+fn test_query_result_records_capped_words() {
+    let mut rng = rng();
+    let mut system = DAESystem::new("test");
+    for i in 0..12 {
+        let mut ep = Episode::new(&format!("log-{i}"));
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["error", "timeout"]),
+            None,
+            "error timeout",
+            &mut rng,
+        ));
+        system.add_episode(ep);
+    }
+    system.set_max_word_activations(4);
+
+    let result = QueryEngine::process_query(&mut system, "error retry");
+    assert_eq!(
+        result.capped_words,
+        vec![CappedWord {
+            word: "error".to_string(),
+            activated: 4,
+            skipped: 8,
+        }]
+    );
+    assert_eq!(result.activation.subconscious.len(), 4);
+    assert_eq!(result.manifest.activated.len(), 4);
+
+    let probed = QueryEngine::probe(&mut system, "error");
+    assert_eq!(probed.capped_words, result.capped_words);
+}
+
 #[test]
 fn test_manifest_contains_drifted_ids() {
     let mut sys = make_test_system();
@@ -309,6 +371,105 @@ fn test_idf_rare_words_drift_more() {
     );
 }
 
+fn make_hapax_system(skip_hapax_drift: bool) -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test");
+    let mut ep = Episode::new("test");
+    // "shared" appears in both neighborhoods, "typo" only in the first
+    ep.add_neighborhood(Neighborhood::from_tokens(
+        &to_tokens(&["shared", "typo", "alpha", "beta"]),
+        None,
+        "",
+        &mut rng,
+    ));
+    ep.add_neighborhood(Neighborhood::from_tokens(
+        &to_tokens(&["shared", "gamma", "delta", "epsilon"]),
+        None,
+        "",
+        &mut rng,
+    ));
+    sys.add_episode(ep);
+    sys.set_word_weight_policy(WordWeightPolicy {
+        skip_hapax_drift,
+        ..WordWeightPolicy::default()
+    });
+    sys
+}
+
+#[test]
+fn test_hapax_words_activate_but_skip_drift() {
+    const QUERY: &str = "shared typo alpha gamma";
+
+    // Baseline: without the exclusion the hapax word drifts
+    let mut baseline = make_hapax_system(false);
+    let typo_id = baseline.episodes[0].neighborhoods[0].occurrences[1].id;
+    let result = QueryEngine::process_query(&mut baseline, QUERY);
+    assert!(result.manifest.drifted.contains(&typo_id));
+
+    let mut sys = make_hapax_system(true);
+    let typo_id = sys.episodes[0].neighborhoods[0].occurrences[1].id;
+    let typo_pos = sys.episodes[0].neighborhoods[0].occurrences[1].position;
+    let result = QueryEngine::process_query(&mut sys, QUERY);
+
+    // Still activated...
+    assert!(result.manifest.activated.contains(&typo_id));
+    assert_eq!(
+        sys.episodes[0].neighborhoods[0].occurrences[1].activation_count,
+        1
+    );
+    // ...but not drifted
+    assert!(!result.manifest.drifted.contains(&typo_id));
+    let after = sys.episodes[0].neighborhoods[0].occurrences[1].position;
+    assert!(typo_pos.angular_distance(after) < 1e-12);
+    // "shared" is not hapax and still drifts
+    let shared_id = sys.episodes[0].neighborhoods[0].occurrences[0].id;
+    assert!(result.manifest.drifted.contains(&shared_id));
+}
+
+#[test]
+fn test_frozen_word_keeps_position_and_phase() {
+    const QUERY: &str = "quantum physics particle computing";
+
+    let mut sys = make_test_system();
+    sys.set_drift_frozen(["Quantum".to_string()]);
+    let frozen: Vec<(Uuid, Quaternion, f64, u32)> = sys
+        .episodes
+        .iter()
+        .flat_map(|e| &e.neighborhoods)
+        .chain(&sys.conscious_episode.neighborhoods)
+        .flat_map(|n| &n.occurrences)
+        .filter(|o| o.word == "quantum")
+        .map(|o| (o.id, o.position, o.phasor.theta, o.activation_count))
+        .collect();
+    assert_eq!(frozen.len(), 3);
+
+    for _ in 0..3 {
+        let result = QueryEngine::process_query(&mut sys, QUERY);
+        // Activated and scored like any other word...
+        for (id, ..) in &frozen {
+            assert!(result.manifest.activated.contains(id));
+            assert!(!result.manifest.drifted.contains(id));
+        }
+        // ...while its neighbors still drift
+        let physics_id = sys.episodes[0].neighborhoods[0].occurrences[1].id;
+        assert!(result.manifest.drifted.contains(&physics_id));
+    }
+
+    for (id, position, theta, count) in frozen {
+        let occ = sys
+            .episodes
+            .iter()
+            .flat_map(|e| &e.neighborhoods)
+            .chain(&sys.conscious_episode.neighborhoods)
+            .flat_map(|n| &n.occurrences)
+            .find(|o| o.id == id)
+            .unwrap();
+        assert_eq!(occ.activation_count, count + 3);
+        assert!(position.angular_distance(occ.position) < 1e-12);
+        assert!((occ.phasor.theta - theta).abs() < 1e-12);
+    }
+}
+
 /// Generate a query string with >50 unique tokens.
 fn make_large_query(unique_words: &[&str], filler_count: usize) -> String {
     let mut words: Vec<String> = unique_words.iter().map(|w| (*w).to_string()).collect();
@@ -431,3 +592,140 @@ fn large_query_weight_floor_one_with_few_neighborhoods() {
         "pipeline should produce non-empty activation"
     );
 }
+
+#[test]
+fn test_activate_response_reports_strengthened_words_and_moves() {
+    let mut sys = make_test_system();
+    let text = "Quantum physics and quantum computing share a particle algorithm.";
+
+    let report = QueryEngine::activate_response(&mut sys, text, 3, None);
+
+    assert!(report.activated > 0);
+    assert!(!report.top_words.is_empty() && report.top_words.len() <= 3);
+    let content: std::collections::HashSet<String> = tokenize(text).into_iter().collect();
+    for w in &report.top_words {
+        assert!(content.contains(&w.word), "{} not in response", w.word);
+        assert!(w.score > 0.0);
+    }
+    assert!(
+        report
+            .top_words
+            .windows(2)
+            .all(|p| p[0].score >= p[1].score)
+    );
+
+    // Drift happened, so at least one neighborhood moved, each by a positive delta
+    assert!(!report.manifest.drifted.is_empty());
+    assert!(!report.moved.is_empty() && report.moved.len() <= 3);
+    assert!(report.moved.iter().all(|m| m.mean_delta > 0.0));
+    assert!(report.moved.iter().all(|m| !m.episode_name.is_empty()));
+}
+
+/// Total angular distance moved by the occurrences "quantum" activates in
+/// a fresh test system when drifted at `scale`.
+fn displacement_at_scale(scale: f64) -> f64 {
+    let mut sys = make_test_system();
+    let (activation, _) = QueryEngine::activate(&mut sys, "quantum computing algorithm");
+    let refs = activation.subconscious;
+    let before: Vec<Quaternion> = refs
+        .iter()
+        .map(|r| sys.get_occurrence(*r).position)
+        .collect();
+    QueryEngine::drift_and_consolidate(&mut sys, &refs, scale);
+    refs.iter()
+        .zip(&before)
+        .map(|(r, pos)| pos.angular_distance(sys.get_occurrence(*r).position))
+        .sum()
+}
+
+#[test]
+fn test_drift_scale_shrinks_displacement() {
+    let full = displacement_at_scale(1.0);
+    let gentle = displacement_at_scale(0.3);
+    assert!(full > 0.0);
+    assert!(
+        gentle > 0.0 && gentle < full * 0.5,
+        "scale 0.3 moved {gentle}, scale 1.0 moved {full}"
+    );
+    assert!(displacement_at_scale(0.0).abs() < 1e-12);
+    // Out-of-range scales are clamped
+    assert!((displacement_at_scale(2.0) - full).abs() < 1e-12);
+}
+
+#[test]
+fn test_activate_response_defaults_to_policy_drift_scale() {
+    let text = "quantum computing algorithm";
+    let mean_moved = |drift_scale: Option<f64>, policy_scale: f64| {
+        let mut sys = make_test_system();
+        sys.set_word_weight_policy(WordWeightPolicy {
+            response_drift_scale: policy_scale,
+            ..WordWeightPolicy::default()
+        });
+        let report = QueryEngine::activate_response(&mut sys, text, 8, drift_scale);
+        report.moved.iter().map(|m| m.mean_delta).sum::<f64>()
+    };
+
+    let default = mean_moved(None, DEFAULT_RESPONSE_DRIFT_SCALE);
+    let full = mean_moved(Some(1.0), DEFAULT_RESPONSE_DRIFT_SCALE);
+    assert!(default > 0.0 && default < full, "{default} vs {full}");
+    // The per-call scale wins over the policy
+    assert!((mean_moved(Some(0.3), 1.0) - default).abs() < 1e-12);
+    assert!((mean_moved(None, 1.0) - full).abs() < 1e-12);
+}
+
+#[test]
+fn test_activate_response_counts_coupled_word_groups() {
+    // "quantum" is in both manifolds; "neural network" only subconscious
+    let mut sys = make_test_system();
+
+    let report = QueryEngine::activate_response(&mut sys, "quantum", 8, None);
+    assert_eq!(report.coupled_word_groups, 1);
+
+    let report = QueryEngine::activate_response(&mut sys, "neural network", 8, None);
+    assert_eq!(report.coupled_word_groups, 0);
+}
+
+#[test]
+fn test_spelling_tolerance_corrects_one_edit_typo() {
+    let mut sys = make_test_system();
+    let spelling = QueryOptions {
+        spelling_tolerance: true,
+    };
+
+    let plain = QueryEngine::process_query(&mut sys, "neurl network");
+    assert!(plain.corrections.is_empty());
+
+    let result = QueryEngine::process_query_with(&mut sys, "nueral algorthm", spelling);
+    let reported: Vec<String> = result.corrections.iter().map(ToString::to_string).collect();
+    assert_eq!(reported, vec!["nueral → neural", "algorthm → algorithm"]);
+
+    let surface = crate::surface::compute_surface(&sys, &result);
+    let ctx = crate::compose::compose_context(&mut sys, &surface, &result, None);
+    assert!(
+        ctx.context.contains("neural network learning"),
+        "{}",
+        ctx.context
+    );
+    assert!(
+        ctx.context.contains("quantum computing algorithm"),
+        "{}",
+        ctx.context
+    );
+}
+
+#[test]
+fn test_spelling_tolerance_ignores_unrelated_words() {
+    let mut sys = make_test_system();
+    let spelling = QueryOptions {
+        spelling_tolerance: true,
+    };
+
+    let result = QueryEngine::process_query_with(&mut sys, "giraffe spreadsheet", spelling);
+    assert!(result.corrections.is_empty());
+    assert!(result.activation.subconscious.is_empty());
+    assert!(result.activation.conscious.is_empty());
+
+    // A word that is already stored, or already in the query, is left alone
+    let result = QueryEngine::process_query_with(&mut sys, "neural neurals", spelling);
+    assert!(result.corrections.is_empty());
+}
@@ -1,52 +1,301 @@
 //! Salient content extraction and neighborhood type detection.
 //!
 //! Extracts `<salient>...</salient>` tagged content from text and adds it
-//! to the conscious episode. Detects `DECISION:` and `PREFERENCE:` prefixes
-//! to set neighborhood types automatically.
+//! to the conscious episode. Detects `DECISION:`, `PREFERENCE:` and `PLAN:`
+//! prefixes to set neighborhood types automatically, and recognizes numbered
+//! step lists as plans in conversation text. A project named in the tag
+//! (`<salient project="x">`) or the prefix (`DECISION (x):`) is recorded on
+//! the memory so recall can favour the project it belongs to. Text longer
+//! than `DAESystem::salient_chunk_chars` is stored as grouped chunks, and
+//! a mark that repeats a live conscious memory can reinforce it instead of
+//! adding a copy (see [`mark_salient`]).
 
+use std::collections::HashSet;
 use std::sync::LazyLock;
 
 use rand::Rng;
 use regex::Regex;
 use uuid::Uuid;
 
+use crate::episode::Episode;
 use crate::neighborhood::NeighborhoodType;
 use crate::system::DAESystem;
+use crate::tokenizer::split_sentences;
 
-static SALIENT_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?s)<salient>(.*?)</salient>").unwrap());
+/// A `<salient>` tag, optionally with a `project="..."` attribute.
+static SALIENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<salient(?:\s+project="([^"]*)")?\s*>(.*?)</salient>"#).unwrap()
+});
 
-/// Detect neighborhood type from text prefix (DECISION: / PREFERENCE:).
+/// A conversation line that opens with `DECISION:` or `PREFERENCE:`
+/// (optionally `DECISION (project):`), allowing for list bullets and quote
+/// markers before it.
+static STATED_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[\s>*-]*((?:DECISION|PREFERENCE)(?:\s*\([^()\n]*\))?:[^\n]*)$").unwrap()
+});
+
+/// A type prefix with an optional parenthesized project.
+static TYPE_PREFIX_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(DECISION|PREFERENCE|PLAN)(?:\s*\(([^()\n]*)\))?:").unwrap());
+
+/// A list step marker (`1.` or `2)`) at the start of the text or after
+/// whitespace or a separator.
+static PLAN_STEP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s,;:])(\d{1,2})[.)]\s+\S").unwrap());
+
+/// A step marker that continues a list, with any separator before it.
+static PLAN_STEP_BREAK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[,;]?\s+(\d{1,2}[.)])\s+").unwrap());
+
+/// A blank line between paragraphs.
+static PARAGRAPH_BREAK_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\n\s*\n").unwrap());
+
+/// Detect neighborhood type from text prefix (DECISION: / PREFERENCE: / PLAN:).
 /// Returns the detected type and the text with the prefix stripped.
 #[must_use]
 pub fn detect_neighborhood_type(text: &str) -> (NeighborhoodType, &str) {
+    let (nbhd_type, _, rest) = detect_type_and_project(text);
+    (nbhd_type, rest)
+}
+
+/// Like [`detect_neighborhood_type`], also returning the project named in
+/// a `DECISION (project):` style prefix.
+#[must_use]
+pub fn detect_type_and_project(text: &str) -> (NeighborhoodType, Option<&str>, &str) {
     let trimmed = text.trim();
-    if let Some(rest) = trimmed.strip_prefix("DECISION:") {
-        (NeighborhoodType::Decision, rest.trim())
-    } else if let Some(rest) = trimmed.strip_prefix("PREFERENCE:") {
-        (NeighborhoodType::Preference, rest.trim())
-    } else {
-        (NeighborhoodType::Insight, trimmed)
-    }
+    let Some(cap) = TYPE_PREFIX_RE.captures(trimmed) else {
+        return (NeighborhoodType::Insight, None, trimmed);
+    };
+    let nbhd_type = match &cap[1] {
+        "DECISION" => NeighborhoodType::Decision,
+        "PREFERENCE" => NeighborhoodType::Preference,
+        _ => NeighborhoodType::Plan,
+    };
+    let project = cap
+        .get(2)
+        .map(|m| m.as_str().trim())
+        .filter(|p| !p.is_empty());
+    (nbhd_type, project, trimmed[cap[0].len()..].trim())
+}
+
+/// Where a salient mark landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SalientMark {
+    /// The new neighborhood (the first chunk when split), or the existing
+    /// one the mark repeated.
+    pub id: Uuid,
+    /// The mark repeated a live conscious memory, which was reinforced
+    /// instead of adding a new one.
+    pub deduplicated: bool,
 }
 
 /// Extract salient-tagged content and add to conscious episode.
 /// Detects DECISION: and PREFERENCE: prefixes to set neighborhood type.
+/// Every tag is stored, repeats included.
 pub fn extract_salient(system: &mut DAESystem, text: &str, rng: &mut impl Rng) -> u32 {
     let mut count = 0u32;
     for cap in SALIENT_RE.captures_iter(text) {
-        if let Some(content) = cap.get(1) {
-            let (nbhd_type, clean_text) = detect_neighborhood_type(content.as_str());
-            system.add_to_conscious_typed(clean_text, nbhd_type, rng);
+        if let Some(content) = cap.get(2) {
+            let (nbhd_type, project, clean_text) = detect_type_and_project(content.as_str());
+            let project = cap.get(1).map(|m| m.as_str()).or(project);
+            add_chunked(system, clean_text, nbhd_type, project, rng);
             count += 1;
         }
     }
     count
 }
 
+/// [`extract_salient`], merging repeats unless `force`: a tag whose text
+/// repeats a live conscious memory reinforces it (see [`mark_salient`]).
+/// Returns one mark per tag, in text order.
+pub fn extract_salient_marks(
+    system: &mut DAESystem,
+    text: &str,
+    force: bool,
+    rng: &mut impl Rng,
+) -> Vec<SalientMark> {
+    SALIENT_RE
+        .captures_iter(text)
+        .filter_map(|cap| {
+            let content = cap.get(2)?;
+            let (nbhd_type, project, clean_text) = detect_type_and_project(content.as_str());
+            let project = cap.get(1).map(|m| m.as_str()).or(project);
+            Some(add_mark(system, clean_text, nbhd_type, project, force, rng))
+        })
+        .collect()
+}
+
+/// Add salient content stated outright in conversation text to the
+/// conscious episode: `<salient>` tags, and lines that open with
+/// `DECISION:` or `PREFERENCE:`. Anything whose text is already conscious
+/// is skipped. Returns the new neighborhood IDs in text order.
+pub fn extract_stated_salient(system: &mut DAESystem, text: &str, rng: &mut impl Rng) -> Vec<Uuid> {
+    let mut seen: HashSet<String> = system
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.source_text.clone())
+        .collect();
+    let tagged = SALIENT_RE.captures_iter(text).filter_map(|cap| {
+        let content = cap.get(2)?;
+        Some((
+            content.start(),
+            cap.get(1).map(|m| m.as_str()),
+            content.as_str(),
+        ))
+    });
+    let stated = STATED_LINE_RE
+        .captures_iter(text)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| (m.start(), None, m.as_str()));
+    let mut found: Vec<(usize, Option<&str>, &str)> = tagged.chain(stated).collect();
+    found.sort_by_key(|(start, _, _)| *start);
+
+    let mut added = Vec::new();
+    for (_, tag_project, content) in found {
+        let (nbhd_type, project, clean_text) = detect_type_and_project(content);
+        if clean_text.is_empty() || !seen.insert(clean_text.to_string()) {
+            continue;
+        }
+        let project = tag_project.or(project);
+        added.extend(add_chunked(system, clean_text, nbhd_type, project, rng));
+    }
+    added
+}
+
 /// Mark text as salient with automatic type detection from prefix.
-/// Used by `am_salient` when no `<salient>` tags are present.
+/// Used by `am_salient` when no `<salient>` tags are present. Returns the
+/// ID of the first chunk when the text is split.
 pub fn mark_salient_typed(system: &mut DAESystem, text: &str, rng: &mut impl Rng) -> Uuid {
-    let (nbhd_type, clean_text) = detect_neighborhood_type(text);
-    system.add_to_conscious_typed(clean_text, nbhd_type, rng)
+    let (nbhd_type, project, clean_text) = detect_type_and_project(text);
+    add_chunked(system, clean_text, nbhd_type, project, rng)[0]
+}
+
+/// [`mark_salient_typed`], merging repeats unless `force`. When the text
+/// fits in one chunk and [`DAESystem::find_conscious_duplicate`] finds a
+/// live memory it repeats, that memory is reinforced (activated and
+/// restamped) and returned instead of adding another.
+pub fn mark_salient(
+    system: &mut DAESystem,
+    text: &str,
+    force: bool,
+    rng: &mut impl Rng,
+) -> SalientMark {
+    let (nbhd_type, project, clean_text) = detect_type_and_project(text);
+    add_mark(system, clean_text, nbhd_type, project, force, rng)
+}
+
+/// Reinforce the memory `text` repeats, or add it chunked.
+fn add_mark(
+    system: &mut DAESystem,
+    text: &str,
+    nbhd_type: NeighborhoodType,
+    project: Option<&str>,
+    force: bool,
+    rng: &mut impl Rng,
+) -> SalientMark {
+    let chunks = chunk_salient(text, system.salient_chunk_chars());
+    if !force
+        && chunks.len() == 1
+        && let Some(id) = system.find_conscious_duplicate(text, nbhd_type, project)
+    {
+        system.reinforce_conscious(id);
+        return SalientMark {
+            id,
+            deduplicated: true,
+        };
+    }
+    let ids = system.add_conscious_group(&chunks, nbhd_type, project, rng);
+    SalientMark {
+        id: ids[0],
+        deduplicated: false,
+    }
+}
+
+/// Add salient text to the conscious episode, split by [`chunk_salient`]
+/// at the system's `salient_chunk_chars`.
+fn add_chunked(
+    system: &mut DAESystem,
+    text: &str,
+    nbhd_type: NeighborhoodType,
+    project: Option<&str>,
+    rng: &mut impl Rng,
+) -> Vec<Uuid> {
+    let chunks = chunk_salient(text, system.salient_chunk_chars());
+    system.add_conscious_group(&chunks, nbhd_type, project, rng)
+}
+
+/// Split salient text longer than `max_chars` characters into chunks of
+/// whole paragraphs, packing consecutive paragraphs while they fit. A
+/// paragraph that is longer on its own is packed by sentence, and a
+/// single longer sentence stays whole. Shorter text, or `max_chars` 0,
+/// gives one chunk.
+#[must_use]
+pub fn chunk_salient(text: &str, max_chars: usize) -> Vec<String> {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return vec![text.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut pack = |piece: &str, separator: &str| {
+        let len = current.chars().count() + separator.len() + piece.chars().count();
+        if !current.is_empty() && len > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+        }
+        current.push_str(piece);
+    };
+    for paragraph in PARAGRAPH_BREAK_RE.split(text).map(str::trim) {
+        if paragraph.is_empty() {
+            continue;
+        }
+        if paragraph.chars().count() <= max_chars {
+            pack(paragraph, "\n\n");
+            continue;
+        }
+        for (i, sentence) in split_sentences(paragraph).iter().enumerate() {
+            pack(sentence, if i == 0 { "\n\n" } else { " " });
+        }
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Whether text reads as a numbered plan: steps numbered `1.` then `2.`
+/// (or `1)` / `2)`), inline or one per line.
+#[must_use]
+pub fn looks_like_plan(text: &str) -> bool {
+    let mut expected = 1u32;
+    for cap in PLAN_STEP_RE.captures_iter(text) {
+        if cap[1].parse::<u32>().ok() == Some(expected) {
+            expected += 1;
+        }
+    }
+    expected > 2
+}
+
+/// Lay out plan steps one per line. Conversation text often arrives with
+/// the list flattened ("1. add the column, 2. backfill"); this restores it.
+#[must_use]
+pub fn format_plan_steps(text: &str) -> String {
+    PLAN_STEP_BREAK_RE
+        .replace_all(text.trim(), "\n$1 ")
+        .into_owned()
+}
+
+/// Mark neighborhoods whose text looks like a numbered plan as `Plan`.
+/// Returns how many were marked. Opt-in for buffered and synced episodes.
+pub fn tag_plans(episode: &mut Episode) -> usize {
+    let mut tagged = 0;
+    for nbhd in &mut episode.neighborhoods {
+        if nbhd.neighborhood_type != NeighborhoodType::Plan && looks_like_plan(&nbhd.source_text) {
+            nbhd.neighborhood_type = NeighborhoodType::Plan;
+            tagged += 1;
+        }
+    }
+    tagged
 }
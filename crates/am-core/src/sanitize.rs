@@ -0,0 +1,196 @@
+//! Scrub terminal noise out of text before it becomes memory.
+//!
+//! Terminal logs carry ANSI escape sequences (`\x1b[32m`) and stray control
+//! bytes. Left in `source_text` they are echoed back into composed context,
+//! and the tokenizer turns their fragments (`32m`, `0m`) into words.
+//! [`clean_text`] runs on every `ingest_text` call; [`scrub_system`] cleans
+//! memories stored before it did.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+
+use rand::Rng;
+use regex::Regex;
+use uuid::Uuid;
+
+use crate::constants::NEIGHBORHOOD_RADIUS;
+use crate::neighborhood::Neighborhood;
+use crate::occurrence::Occurrence;
+use crate::phasor::DaemonPhasor;
+use crate::quaternion::Quaternion;
+use crate::system::DAESystem;
+use crate::tokenizer::tokenize_with_spans;
+
+/// CSI (`ESC [ ... final`), OSC (`ESC ] ... BEL|ST`), DCS/SOS/PM/APC
+/// strings, charset selection (`ESC ( B`), and two-byte escapes.
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[PX^_][^\x1b]*\x1b\\|\x1b[()*+][ -~]|\x1b[@-Z\\-_]",
+    )
+    .unwrap()
+});
+
+/// Strip ANSI escape sequences and control characters other than newline
+/// and tab. A carriage return becomes a newline unless one follows it, so
+/// progress-bar redraws stay on separate lines. Borrows when there is
+/// nothing to remove.
+#[must_use]
+pub fn clean_text(text: &str) -> Cow<'_, str> {
+    if !text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Cow::Borrowed(text);
+    }
+    let stripped = ANSI_ESCAPE.replace_all(text, "");
+    let mut out = String::with_capacity(stripped.len());
+    let mut chars = stripped.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() != Some(&'\n') => out.push('\n'),
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Neighborhoods changed by [`scrub_system`].
+#[derive(Debug, Default)]
+pub struct ScrubReport {
+    /// Neighborhoods whose `source_text` changed.
+    pub cleaned: Vec<Uuid>,
+    /// The subset whose tokens changed, so their occurrences were rebuilt.
+    pub retokenized: Vec<Uuid>,
+}
+
+impl ScrubReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cleaned.is_empty()
+    }
+}
+
+/// Run [`clean_text`] over every stored neighborhood in both manifolds.
+///
+/// Where cleaning changes the tokens, occurrences are rebuilt in the new
+/// token order. An occurrence whose word survives keeps its position,
+/// phasor, and activation. Words that only exist once the escapes are gone
+/// (`pass` out of `32mpass`) are placed near the seed like fresh ingest.
+pub fn scrub_system(system: &mut DAESystem, rng: &mut impl Rng) -> ScrubReport {
+    let mut report = ScrubReport::default();
+    let episodes = system
+        .episodes
+        .iter_mut()
+        .chain(std::iter::once(&mut system.conscious_episode));
+    for nbhd in episodes.flat_map(|e| e.neighborhoods.iter_mut()) {
+        let Cow::Owned(cleaned) = clean_text(&nbhd.source_text) else {
+            continue;
+        };
+        nbhd.source_text = cleaned;
+        report.cleaned.push(nbhd.id);
+
+        let spans = tokenize_with_spans(&nbhd.source_text);
+        let tokens: Vec<String> = spans.iter().map(|t| t.text.clone()).collect();
+        if nbhd.occurrences.iter().map(|o| &o.word).ne(tokens.iter()) {
+            rebuild_occurrences(nbhd, &tokens, rng);
+            report.retokenized.push(nbhd.id);
+        }
+        // Byte offsets moved with the text either way
+        nbhd.record_spans(&spans);
+    }
+    if !report.retokenized.is_empty() {
+        system.mark_dirty();
+    }
+    report
+}
+
+fn rebuild_occurrences(nbhd: &mut Neighborhood, tokens: &[String], rng: &mut impl Rng) {
+    let mut by_word: HashMap<String, VecDeque<Occurrence>> = HashMap::new();
+    for occ in nbhd.occurrences.drain(..) {
+        by_word.entry(occ.word.clone()).or_default().push_back(occ);
+    }
+    nbhd.occurrences = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, token)| {
+            by_word
+                .get_mut(token)
+                .and_then(VecDeque::pop_front)
+                .unwrap_or_else(|| {
+                    let position = Quaternion::random_near(nbhd.seed, NEIGHBORHOOD_RADIUS, rng);
+                    let phasor = DaemonPhasor::from_index(i, 0.0);
+                    Occurrence::new(token.clone(), position, phasor, nbhd.id)
+                })
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{ingest_text, tokenize};
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    const ANSI_LOG: &str = "\x1b[1m\x1b[32mPASS\x1b[0m src/cache.rs\r\n\
+        \x1b]0;cargo test\x07Compiling cache \x1b(B\x1b[mv0.1.0\x07.\r\
+        Finished in 2.1s.";
+
+    #[test]
+    fn clean_text_strips_escapes_and_controls() {
+        assert_eq!(
+            clean_text(ANSI_LOG),
+            "PASS src/cache.rs\nCompiling cache v0.1.0.\nFinished in 2.1s."
+        );
+        assert!(matches!(clean_text("plain\ttext\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn ingest_drops_escape_fragments() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let episode = ingest_text(ANSI_LOG, None, &mut rng);
+        for nbhd in &episode.neighborhoods {
+            assert!(!nbhd.source_text.contains('\x1b'), "{:?}", nbhd.source_text);
+            let words: Vec<&str> = nbhd.occurrences.iter().map(|o| o.word.as_str()).collect();
+            assert!(
+                !words.contains(&"32m") && !words.contains(&"0m"),
+                "{words:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn scrub_system_cleans_stored_text_and_keeps_surviving_occurrences() {
+        let mut rng = SmallRng::seed_from_u64(6);
+        let mut sys = DAESystem::new("test");
+        // Stored before ingest cleaned its input
+        let tokens = tokenize(ANSI_LOG);
+        let mut ep = crate::episode::Episode::new("log");
+        ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, ANSI_LOG, &mut rng));
+        sys.add_episode(ep);
+        sys.add_to_conscious("already clean", &mut rng);
+        let cache = sys.episodes[0].neighborhoods[0]
+            .occurrences
+            .iter()
+            .find(|o| o.word == "cache")
+            .unwrap()
+            .clone();
+
+        let report = scrub_system(&mut sys, &mut rng);
+        let nbhd = &sys.episodes[0].neighborhoods[0];
+        assert_eq!(report.cleaned, vec![nbhd.id]);
+        assert_eq!(report.retokenized, vec![nbhd.id]);
+        assert!(!nbhd.source_text.contains('\x1b'));
+        let words: Vec<&str> = nbhd.occurrences.iter().map(|o| o.word.as_str()).collect();
+        assert_eq!(words, tokenize(&nbhd.source_text));
+        let kept = nbhd.occurrences.iter().find(|o| o.word == "cache").unwrap();
+        assert_eq!(kept.id, cache.id);
+        assert_eq!(kept.position, cache.position);
+        assert!(crate::invariants::check_system(&sys).is_empty());
+
+        assert!(scrub_system(&mut sys, &mut rng).is_empty());
+    }
+}
@@ -4,16 +4,18 @@
 //! Handles IDF-weighted activation scoring, phasor interference modulation,
 //! vividness boosting, recency decay, overlap suppression, and density bonuses.
 
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
 use uuid::Uuid;
 
-use crate::compose::RecallCategory;
+use crate::compose::{CutoffReason, FilteredCandidate, NovelBridge, RecallCategory};
 use crate::neighborhood::NeighborhoodType;
 use crate::query::{InterferenceResult, QueryResult};
 use crate::recency::{RECENCY_DECAY_RATE, days_since_episode};
+use crate::spelling::CORRECTION_DISCOUNT;
 use crate::surface::SurfaceResult;
-use crate::system::{DAESystem, EpisodeRef, OccurrenceRef};
+use crate::system::{DAESystem, EpisodeRef, NeighborhoodRef, OccurrenceRef};
 use crate::tokenizer::token_count;
 
 /// Multiplier for Decision/Preference neighborhoods.
@@ -29,9 +31,24 @@ pub(crate) const CONSCIOUS_MIN_OVERLAP: f64 = 0.2;
 /// Positive interference (in-phase) boosts, negative (anti-phase) suppresses.
 pub(crate) const INTERFERENCE_WEIGHT: f64 = 0.3;
 
+/// Multiplier for Plan neighborhoods when the query contains a planning verb.
+/// Moderate on purpose: a plan should win ties on "how do we migrate X",
+/// not outrank a decision that actually answers the question.
+pub(crate) const PLAN_QUERY_BOOST: f64 = 1.5;
+
 /// Boost multiplier for vivid neighborhoods (>50% surfaced occurrences).
 pub(crate) const VIVIDNESS_BOOST: f64 = 1.5;
 
+/// Base of the feedback factor: a neighborhood's score is multiplied by
+/// `FEEDBACK_FACTOR_BASE ^ feedback_score`. At 1.25, two demotes (score
+/// -1.8) cut it to about 0.67x and two boosts raise it to about 1.5x.
+pub(crate) const FEEDBACK_FACTOR_BASE: f64 = 1.25;
+
+/// Feedback scores beyond ±this are clamped before the factor is taken,
+/// bounding it to roughly 0.41x..2.4x so feedback never buries or pins a
+/// memory outright.
+pub(crate) const FEEDBACK_SCORE_LIMIT: f64 = 4.0;
+
 /// IDF-weighted word overlap threshold for contradiction detection.
 /// Pairs of neighborhoods above this threshold are considered overlapping.
 pub(crate) const OVERLAP_THRESHOLD: f64 = 0.3;
@@ -43,6 +60,12 @@ pub(crate) const OVERLAP_SUPPRESSION: f64 = 0.1;
 /// Candidates scoring below this are excluded to avoid padding with weak matches.
 pub(crate) const MIN_SCORE_THRESHOLD: f64 = 1.0;
 
+/// Query words named on a novel connection's bridge line.
+const BRIDGE_VIA_WORDS: usize = 3;
+
+/// Words of the resonating conscious note quoted on the bridge line.
+const BRIDGE_NOTE_WORDS: usize = 6;
+
 pub(crate) struct ScoredNeighborhood {
     pub neighborhood_id: Uuid,
     pub episode_ref: EpisodeRef,
@@ -54,6 +77,7 @@ pub(crate) struct ScoredNeighborhood {
     pub max_plasticity: f64,
     pub neighborhood_type: NeighborhoodType,
     pub epoch: u64,
+    pub seq: u64,
 }
 
 pub(crate) struct RankedCandidate {
@@ -64,19 +88,55 @@ pub(crate) struct RankedCandidate {
     pub text: String,
     pub tokens: usize,
     pub neighborhood_type: NeighborhoodType,
+    /// Set for novel candidates only.
+    pub bridge: Option<NovelBridge>,
+    /// The neighborhood's insertion order, for breaking score ties.
+    pub seq: u64,
+}
+
+/// Descending score order with NaN last, so a score that slipped past
+/// the cutoffs as NaN can never rank first. A total order, unlike
+/// `partial_cmp`, so sorts never panic.
+pub(crate) fn score_desc(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+impl RankedCandidate {
+    /// Ranking order: score descending (NaN last), then insertion order
+    /// ascending, so equal scores rank the same way on every run and after
+    /// a save/load. The id settles candidates that have no `seq` yet.
+    pub(crate) fn rank_cmp(&self, other: &Self) -> Ordering {
+        score_desc(self.score, other.score)
+            .then(self.seq.cmp(&other.seq))
+            .then(self.neighborhood_id.cmp(&other.neighborhood_id))
+    }
 }
 
 /// Score and categorize all activated neighborhoods into ranked candidates.
 /// Conscious neighborhoods scored by IDF-weighted activation.
 /// Subconscious neighborhoods scored by IDF-weighted activation.
-/// Novel candidates: subconscious with `activated_count` <= 2, no words in common
-/// with conscious, scored by `max_word_weight` * `max_plasticity` / `activated_count`.
+/// Novel candidates: subconscious neighborhoods picked by the system's
+/// `NovelConfig` (see `select_novel`), then rescaled onto the subconscious
+/// score distribution (see `normalize_novel_scores`).
+/// Neighborhoods failing the system's `ComposeConfig` cutoffs are returned
+/// separately and never become candidates.
 pub(crate) fn rank_candidates(
-    system: &mut DAESystem,
+    system: &DAESystem,
     query_result: &QueryResult,
     interference: &[InterferenceResult],
     surface: &SurfaceResult,
-) -> Vec<RankedCandidate> {
+) -> (Vec<RankedCandidate>, Vec<FilteredCandidate>) {
+    let span = tracing::debug_span!(
+        "rank_candidates",
+        candidates = tracing::field::Empty,
+        filtered = tracing::field::Empty,
+    )
+    .entered();
     let conscious_words: HashSet<String> = query_result
         .activation
         .conscious
@@ -85,9 +145,25 @@ pub(crate) fn rank_candidates(
         .collect();
 
     let qtc = query_result.query_token_count;
-    let mut con_scored = score_neighborhoods(system, &query_result.activation.conscious, true, qtc);
-    let mut sub_scored =
-        score_neighborhoods(system, &query_result.activation.subconscious, false, qtc);
+    let corrected: HashSet<&str> = query_result
+        .corrections
+        .iter()
+        .map(|c| c.word.as_str())
+        .collect();
+    let mut con_scored = score_neighborhoods(
+        system,
+        &query_result.activation.conscious,
+        true,
+        qtc,
+        &corrected,
+    );
+    let mut sub_scored = score_neighborhoods(
+        system,
+        &query_result.activation.subconscious,
+        false,
+        qtc,
+        &corrected,
+    );
 
     // Suppress older neighborhoods that overlap with newer ones (contradiction handling)
     overlap_suppress(&mut con_scored, &mut sub_scored, system);
@@ -123,8 +199,66 @@ pub(crate) fn rank_candidates(
         }
     }
 
+    // Planning queries ("implement", "migrate", ...) favor stored plans
+    if query_result.plan_intent {
+        for sn in con_scored.values_mut().chain(sub_scored.values_mut()) {
+            if sn.neighborhood_type == NeighborhoodType::Plan {
+                sn.score *= PLAN_QUERY_BOOST;
+            }
+        }
+    }
+
+    // Conscious memories tagged for another project count for less;
+    // untagged ones apply everywhere and keep full weight
+    if let Some(project) = query_result.project.as_deref().or(system.project()) {
+        let discount = system.project_discount();
+        let neighborhoods = &system.conscious_episode.neighborhoods;
+        for sn in con_scored.values_mut() {
+            let tagged = neighborhoods
+                .get(sn.neighborhood_idx)
+                .and_then(|n| n.project.as_deref());
+            if tagged.is_some_and(|p| !p.eq_ignore_ascii_case(project)) {
+                sn.score *= discount;
+            }
+        }
+    }
+
+    // Durable feedback from am_feedback, which outlasts the drift and
+    // activation changes that later queries make
+    for sn in con_scored.values_mut().chain(sub_scored.values_mut()) {
+        sn.score *= feedback_factor(neighborhood_feedback(system, sn));
+    }
+
+    // Minimum-match and minimum-score cutoffs, on final pre-selection
+    // scores. A NaN score fails no comparison, so it is dropped first.
+    let config = system.compose_config();
+    let mut filtered = Vec::new();
+    for scored in [&mut con_scored, &mut sub_scored] {
+        scored.retain(|_, sn| {
+            let reason = if !sn.score.is_finite() {
+                CutoffReason::InvalidScore
+            } else if sn.words.len() < config.min_activated_words {
+                CutoffReason::TooFewWords
+            } else if sn.score < config.min_score {
+                CutoffReason::LowScore
+            } else {
+                return true;
+            };
+            filtered.push(FilteredCandidate {
+                neighborhood_id: sn.neighborhood_id,
+                activated_words: sn.words.len(),
+                score: sn.score,
+                reason,
+            });
+            false
+        });
+    }
+    filtered.sort_by(|a, b| {
+        score_desc(a.score, b.score).then(a.neighborhood_id.cmp(&b.neighborhood_id))
+    });
+
     let mut candidates = Vec::new();
-    let mut selected_for_novel: HashSet<Uuid> = HashSet::new();
+    let novelty = select_novel(system, &sub_scored, &conscious_words);
 
     // Conscious candidates
     for sn in con_scored.values() {
@@ -143,6 +277,8 @@ pub(crate) fn rank_candidates(
             text,
             tokens,
             neighborhood_type: sn.neighborhood_type,
+            bridge: None,
+            seq: sn.seq,
         });
     }
 
@@ -163,21 +299,16 @@ pub(crate) fn rank_candidates(
             text,
             tokens,
             neighborhood_type: sn.neighborhood_type,
+            bridge: None,
+            seq: sn.seq,
         });
-
-        // Check if this is also a novel candidate
-        if sn.activated_count <= 2 && !sn.words.iter().any(|w| conscious_words.contains(w)) {
-            selected_for_novel.insert(sn.neighborhood_id);
-        }
     }
 
     // Add novel candidates (these are subconscious neighborhoods that qualify)
     for sn in sub_scored.values() {
-        if !selected_for_novel.contains(&sn.neighborhood_id) {
+        let Some(&novelty_score) = novelty.get(&sn.neighborhood_id) else {
             continue;
-        }
-        let novelty_score =
-            sn.max_word_weight * sn.max_plasticity / sn.activated_count.max(1) as f64;
+        };
         let text = get_neighborhood_text(
             system,
             sn.neighborhood_id,
@@ -185,6 +316,7 @@ pub(crate) fn rank_candidates(
             sn.neighborhood_idx,
         );
         let tokens = token_count(&text);
+        let bridge = novel_bridge(system, sn, interference);
         candidates.push(RankedCandidate {
             neighborhood_id: sn.neighborhood_id,
             episode_ref: sn.episode_ref,
@@ -193,10 +325,155 @@ pub(crate) fn rank_candidates(
             text,
             tokens,
             neighborhood_type: sn.neighborhood_type,
+            bridge: Some(bridge),
+            seq: sn.seq,
+        });
+    }
+
+    normalize_novel_scores(&mut candidates);
+    span.record("candidates", candidates.len());
+    span.record("filtered", filtered.len());
+    (candidates, filtered)
+}
+
+/// Raw novelty scores of the subconscious neighborhoods that become novel
+/// candidates, under the system's `NovelConfig`.
+fn select_novel(
+    system: &DAESystem,
+    sub_scored: &HashMap<Uuid, ScoredNeighborhood>,
+    conscious_words: &HashSet<String>,
+) -> HashMap<Uuid, f64> {
+    let config = &system.compose_config().novel;
+    let check_overlap = config.require_no_conscious_overlap && !conscious_words.is_empty();
+    let eligible: Vec<(&ScoredNeighborhood, f64)> = sub_scored
+        .values()
+        .filter(|sn| !check_overlap || !sn.words.iter().any(|w| conscious_words.contains(w)))
+        .map(|sn| {
+            let novelty = sn.max_word_weight * sn.max_plasticity / sn.activated_count.max(1) as f64
+                * feedback_factor(neighborhood_feedback(system, sn));
+            (sn, novelty)
+        })
+        .filter(|&(_, novelty)| novelty >= config.min_novelty_score)
+        .collect();
+
+    let qualifying: HashMap<Uuid, f64> = eligible
+        .iter()
+        .filter(|(sn, _)| sn.words.len() <= config.max_activated_words)
+        .map(|&(sn, novelty)| (sn.neighborhood_id, novelty))
+        .collect();
+    if !conscious_words.is_empty() && !qualifying.is_empty() {
+        return qualifying;
+    }
+
+    // No conscious anchor, or nothing sparse enough: fewest activated
+    // occurrences first, then rarest word. Only the order matters, since
+    // normalize_novel_scores maps ranks onto the subconscious scale.
+    let mut ranked: Vec<&ScoredNeighborhood> = eligible.iter().map(|&(sn, _)| sn).collect();
+    ranked.sort_by(|a, b| {
+        a.activated_count
+            .cmp(&b.activated_count)
+            .then(b.max_word_weight.total_cmp(&a.max_word_weight))
+            .then(a.neighborhood_id.cmp(&b.neighborhood_id))
+    });
+    let n = ranked.len();
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(rank, sn)| (sn.neighborhood_id, (n - rank) as f64))
+        .collect()
+}
+
+/// Score multiplier for a neighborhood's feedback score.
+#[must_use]
+pub(crate) fn feedback_factor(feedback_score: f64) -> f64 {
+    if feedback_score.is_nan() {
+        return 1.0;
+    }
+    FEEDBACK_FACTOR_BASE.powf(feedback_score.clamp(-FEEDBACK_SCORE_LIMIT, FEEDBACK_SCORE_LIMIT))
+}
+
+fn neighborhood_feedback(system: &DAESystem, sn: &ScoredNeighborhood) -> f64 {
+    system
+        .resolve_episode(sn.episode_ref)
+        .neighborhoods
+        .get(sn.neighborhood_idx)
+        .map_or(0.0, |n| n.feedback_score)
+}
+
+/// Explain a novel candidate: the query words it was activated by, and
+/// the conscious neighborhood its activated occurrences interfere with
+/// most constructively, if any pair with one.
+fn novel_bridge(
+    system: &DAESystem,
+    sn: &ScoredNeighborhood,
+    interference: &[InterferenceResult],
+) -> NovelBridge {
+    let mut via: Vec<(f64, &String)> = sn
+        .words
+        .iter()
+        .map(|w| (system.word_weight(w), w))
+        .collect();
+    via.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    let mut resonance: HashMap<Uuid, f64> = HashMap::new();
+    for ir in interference {
+        if ir.interference > 0.0
+            && system.get_neighborhood_for_occurrence(ir.sub_ref).id == sn.neighborhood_id
+        {
+            let con_id = system.get_neighborhood_for_occurrence(ir.con_ref).id;
+            *resonance.entry(con_id).or_default() += ir.interference;
+        }
+    }
+    let resonates_with = resonance
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .and_then(|(id, _)| {
+            let n_ref = system.neighborhood_ref(id)?;
+            let text = &system.get_neighborhood(n_ref).source_text;
+            let note: Vec<&str> = text.split_whitespace().take(BRIDGE_NOTE_WORDS).collect();
+            Some((id, note.join(" ")))
         });
+
+    NovelBridge {
+        via: via
+            .into_iter()
+            .take(BRIDGE_VIA_WORDS)
+            .map(|(_, w)| w.clone())
+            .collect(),
+        resonates_with,
     }
+}
 
-    candidates
+/// Put novel scores on the subconscious scale so the two compete fairly in
+/// greedy fill. Raw novelty (`weight * plasticity / count`) is not comparable
+/// to activation scores. Each novel candidate takes its mid-rank percentile
+/// within the novel set and is assigned the subconscious score at that
+/// percentile. The best novel candidate lands below the best subconscious
+/// one, and a low-percentile novel one lands near the bottom.
+pub(crate) fn normalize_novel_scores(candidates: &mut [RankedCandidate]) {
+    let mut sub_scores: Vec<f64> = candidates
+        .iter()
+        .filter(|c| c.category == RecallCategory::Subconscious)
+        .map(|c| c.score)
+        .collect();
+    if sub_scores.is_empty() {
+        return;
+    }
+    sub_scores.sort_by(f64::total_cmp);
+
+    let mut novel_idx: Vec<usize> = (0..candidates.len())
+        .filter(|&i| candidates[i].category == RecallCategory::Novel)
+        .collect();
+    // Ascending raw novelty, so rank 0 is the weakest
+    novel_idx.sort_by(|&a, &b| candidates[b].rank_cmp(&candidates[a]));
+
+    let n = novel_idx.len();
+    let m = sub_scores.len();
+    for (rank, &i) in novel_idx.iter().enumerate() {
+        // Nearest-rank quantile at percentile (rank + 0.5) / n, in integers
+        let pos = ((2 * rank + 1) * m).div_ceil(2 * n).clamp(1, m) - 1;
+        candidates[i].score = sub_scores[pos];
+    }
 }
 
 /// Aggregate per-neighborhood mean interference from pairwise results.
@@ -226,13 +503,16 @@ pub(crate) fn aggregate_interference(
 }
 
 fn score_neighborhoods(
-    system: &mut DAESystem,
+    system: &DAESystem,
     refs: &[OccurrenceRef],
     is_conscious: bool,
     query_token_count: usize,
+    corrected: &HashSet<&str>,
 ) -> HashMap<Uuid, ScoredNeighborhood> {
     // Pre-collect data to avoid borrow conflicts.
     // Superseded neighborhoods are excluded - they've been explicitly replaced.
+    // With an actor filter, so are other actors' memories; activation and
+    // drift still ran over the whole manifold.
     struct OccData {
         nbhd_id: Uuid,
         episode_ref: EpisodeRef,
@@ -242,6 +522,7 @@ fn score_neighborhoods(
         plasticity: f64,
         nbhd_type: NeighborhoodType,
         epoch: u64,
+        seq: u64,
     }
 
     let data: Vec<OccData> = refs
@@ -252,6 +533,15 @@ fn score_neighborhoods(
             if nbhd.superseded_by.is_some() {
                 return None;
             }
+            if let Some(actor) = system.actor_filter() {
+                let nbhd_ref = NeighborhoodRef {
+                    episode_ref: r.episode_ref,
+                    neighborhood_idx: r.neighborhood_idx,
+                };
+                if system.neighborhood_actor(nbhd_ref) != Some(actor) {
+                    return None;
+                }
+            }
             Some(OccData {
                 nbhd_id: nbhd.id,
                 episode_ref: r.episode_ref,
@@ -261,6 +551,7 @@ fn score_neighborhoods(
                 plasticity: occ.plasticity(),
                 nbhd_type: nbhd.neighborhood_type,
                 epoch: nbhd.epoch,
+                seq: nbhd.seq,
             })
         })
         .collect();
@@ -303,7 +594,14 @@ fn score_neighborhoods(
 
     let mut scored: HashMap<Uuid, ScoredNeighborhood> = HashMap::new();
     for d in &data {
-        let weight = system.get_word_weight(&d.word);
+        // A muted word neither scores nor counts as a match
+        if system.word_bias(&d.word) == 0.0 {
+            continue;
+        }
+        let mut weight = system.word_weight(&d.word);
+        if corrected.contains(d.word.as_str()) {
+            weight *= CORRECTION_DISCOUNT;
+        }
 
         let entry = scored
             .entry(d.nbhd_id)
@@ -318,6 +616,7 @@ fn score_neighborhoods(
                 max_plasticity: 0.0,
                 neighborhood_type: d.nbhd_type,
                 epoch: d.epoch,
+                seq: d.seq,
             });
 
         entry.score += weight * f64::from(d.activation_count);
@@ -375,16 +674,13 @@ fn score_neighborhoods(
 pub(crate) fn idf_weighted_overlap(
     words_a: &HashSet<String>,
     words_b: &HashSet<String>,
-    system: &mut DAESystem,
+    system: &DAESystem,
 ) -> f64 {
     let intersection: f64 = words_a
         .intersection(words_b)
-        .map(|w| system.get_word_weight(w))
-        .sum();
-    let union: f64 = words_a
-        .union(words_b)
-        .map(|w| system.get_word_weight(w))
+        .map(|w| system.word_weight(w))
         .sum();
+    let union: f64 = words_a.union(words_b).map(|w| system.word_weight(w)).sum();
     if union < f64::EPSILON {
         return 0.0;
     }
@@ -399,7 +695,7 @@ pub(crate) fn idf_weighted_overlap(
 fn overlap_suppress(
     con_scored: &mut HashMap<Uuid, ScoredNeighborhood>,
     sub_scored: &mut HashMap<Uuid, ScoredNeighborhood>,
-    system: &mut DAESystem,
+    system: &DAESystem,
 ) {
     // Collect references to word sets and epochs - no cloning needed since
     // we only read words during pairwise comparison, then mutate scores after.
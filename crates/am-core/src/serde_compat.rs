@@ -6,17 +6,26 @@
 //! and phasors as bare f64 theta values. State files exported from the original
 //! Node.js implementation - including Echo's 27,712-occurrence consciousness -
 //! can be imported directly.
+//!
+//! Exports are always written at [`CURRENT_VERSION`]. Older minor versions
+//! are migrated on import and newer ones read with their unknown fields
+//! ignored; see [`FormatVersion`].
+
+use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::episode::Episode;
+use crate::invariants::NORM_TOLERANCE;
 use crate::neighborhood::{Neighborhood, NeighborhoodType};
 use crate::occurrence::Occurrence;
 use crate::phasor::DaemonPhasor;
 use crate::quaternion::Quaternion;
 use crate::system::DAESystem;
+use crate::time::{iso8601_to_unix, now_unix_secs, unix_to_iso8601};
 
+/// The wire format version this build reads natively and always writes.
 pub const CURRENT_VERSION: &str = "0.7.2";
 
 // --- Wire format types ---
@@ -59,6 +68,20 @@ pub(crate) struct WireEpisode {
     pub id: String,
     #[serde(default)]
     pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(
+        rename = "originalTimestamp",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub original_timestamp: Option<String>,
+    #[serde(
+        rename = "reusedNeighborhoods",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub reused_neighborhoods: Vec<String>,
     pub neighborhoods: Vec<WireNeighborhood>,
 }
 
@@ -78,6 +101,18 @@ pub(crate) struct WireNeighborhood {
         skip_serializing_if = "Option::is_none"
     )]
     pub superseded_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(rename = "createdAt", default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// Decayed feedback signal sum; absent before feedback was scored.
+    #[serde(rename = "feedbackScore", default)]
+    pub feedback_score: f64,
+    /// Shared by the chunks of one long salient text.
+    #[serde(rename = "groupId", default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
     pub occurrences: Vec<WireOccurrence>,
 }
 
@@ -92,30 +127,599 @@ pub(crate) struct WireOccurrence {
     pub activation_count: u32,
     #[serde(rename = "neighborhoodId", default)]
     pub neighborhood_id: String,
+    /// Always written on export. Absent in files from the JavaScript DAE,
+    /// which never persisted occurrence IDs.
+    #[serde(default)]
+    pub id: String,
+    /// Radians moved since placement; absent before it was tracked.
+    #[serde(default)]
+    pub drift: f64,
+    /// Fractional activation credit not yet whole; absent before
+    /// spillover existed.
+    #[serde(default)]
+    pub spillover: f64,
+    /// Byte range of the word in the neighborhood's text; absent for
+    /// words without one and in files from before spans were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<(usize, usize)>,
 }
 
-// --- Conversion: Wire → Domain ---
+// --- Format versions ---
+
+/// An export format version, `major.minor.patch`.
+///
+/// Every minor version within this build's major version can be read:
+/// older ones are brought up to [`CURRENT_VERSION`] by the migration chain,
+/// and newer ones import with the fields this build does not know ignored.
+/// Another major version is refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl FormatVersion {
+    /// [`CURRENT_VERSION`], parsed.
+    pub const CURRENT: Self = Self::new(0, 7, 2);
 
-impl WireExport {
-    /// Convert wire format to domain `DAESystem`.
     #[must_use]
-    pub fn into_system(self) -> DAESystem {
-        let mut sys = DAESystem::new(&self.system.agent_name);
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::str::FromStr for FormatVersion {
+    type Err = String;
 
-        // Convert subconscious episodes
-        for wire_ep in self.system.episodes {
-            sys.add_episode(wire_episode_to_domain(wire_ep));
+    /// Parse `major.minor[.patch]`, with an optional leading `v`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid version {s:?}: expected major.minor.patch");
+        let mut parts = s.strip_prefix('v').unwrap_or(s).split('.');
+        let mut next = |required: bool| match parts.next() {
+            Some(part) => part.parse::<u32>().map_err(|_| invalid()),
+            None if required => Err(invalid()),
+            None => Ok(0),
+        };
+        let version = Self::new(next(true)?, next(true)?, next(false)?);
+        if parts.next().is_some() {
+            return Err(invalid());
         }
+        Ok(version)
+    }
+}
 
-        // Convert conscious episode
-        sys.conscious_episode = wire_episode_to_domain(self.system.conscious_episode);
-        sys.conscious_episode.is_conscious = true;
+impl std::fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
 
-        sys.mark_dirty();
-        sys.sync_next_epoch();
-        sys
+/// One step of the migration chain: rewrites an episode written before
+/// `until` into the shape that version expects.
+struct Migration {
+    until: FormatVersion,
+    /// What the step changes, for the import report.
+    change: &'static str,
+    /// Applied to each episode, with whether it is the conscious one.
+    apply: fn(&mut serde_json::Value, bool),
+}
+
+/// Oldest first. An export gets every step whose `until` is newer than
+/// its version, in order, before its episodes are validated.
+const MIGRATIONS: &[Migration] = &[Migration {
+    until: FormatVersion::new(0, 7, 0),
+    change: "conscious neighborhoods without a neighborhoodType are insights",
+    apply: type_conscious_neighborhoods,
+}];
+
+/// Before 0.7 neighborhoods carried no type. Everything in the conscious
+/// episode had been marked with `am_salient`, whose untyped marks are
+/// insights; subconscious neighborhoods keep the `memory` default.
+fn type_conscious_neighborhoods(episode: &mut serde_json::Value, conscious: bool) {
+    if !conscious {
+        return;
+    }
+    let Some(nbhds) = episode
+        .get_mut("neighborhoods")
+        .and_then(serde_json::Value::as_array_mut)
+    else {
+        return;
+    };
+    for nbhd in nbhds
+        .iter_mut()
+        .filter_map(serde_json::Value::as_object_mut)
+    {
+        nbhd.entry("neighborhoodType")
+            .or_insert_with(|| serde_json::Value::from("insight"));
     }
+}
+
+/// Field names this build reads at each level of an export. Anything else
+/// (typically from a newer minor version) is ignored with a warning.
+const EXPORT_FIELDS: &[&str] = &[
+    "version",
+    "timestamp",
+    "system",
+    "conversationBuffer",
+    "conversationHistory",
+];
+const SYSTEM_FIELDS: &[&str] = &[
+    "episodes",
+    "consciousEpisode",
+    "N",
+    "totalActivation",
+    "agentName",
+];
+const EPISODE_FIELDS: &[&str] = &[
+    "name",
+    "isConscious",
+    "id",
+    "timestamp",
+    "actor",
+    "originalTimestamp",
+    "reusedNeighborhoods",
+    "neighborhoods",
+];
+const NEIGHBORHOOD_FIELDS: &[&str] = &[
+    "seed",
+    "id",
+    "sourceText",
+    "neighborhoodType",
+    "epoch",
+    "supersededBy",
+    "actor",
+    "project",
+    "createdAt",
+    "feedbackScore",
+    "occurrences",
+];
+const OCCURRENCE_FIELDS: &[&str] = &[
+    "word",
+    "position",
+    "phasor",
+    "theta",
+    "activationCount",
+    "neighborhoodId",
+    "id",
+    "drift",
+    "spillover",
+    "span",
+];
+
+// --- ID handling ---
+
+/// How import treats missing or malformed UUIDs.
+///
+/// Exports from this crate always carry episode, neighborhood, and
+/// occurrence IDs, and session dedup, feedback targets, and supersession
+/// links all key on them. Files from the original JavaScript DAE lack
+/// occurrence IDs, so lenient is the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdPolicy {
+    /// Mint a fresh UUID for each missing or malformed ID (dropping bad
+    /// `supersededBy` links) and count every repair.
+    #[default]
+    Lenient,
+    /// Fail the import on the first missing or malformed ID.
+    Strict,
+}
+
+/// What import does with an episode or neighborhood that fails validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationPolicy {
+    /// Refuse the whole import if anything fails, listing every problem.
+    #[default]
+    Strict,
+    /// Drop the episodes and neighborhoods that fail and import the rest.
+    Lenient,
+}
+
+/// The policies an import runs under.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub ids: IdPolicy,
+    pub validation: ValidationPolicy,
+}
+
+/// One problem found while validating an import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportIssue {
+    /// Where in the file, e.g. `system.episodes[3] ("notes").neighborhoods[5]`.
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// What an import changed or rejected on the way in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    /// IDs regenerated or dropped (always 0 under [`IdPolicy::Strict`]).
+    pub repaired_ids: usize,
+    /// Episodes dated in the future, reset to the import time.
+    pub clamped_timestamps: usize,
+    /// Episodes left out because they failed validation.
+    pub dropped_episodes: usize,
+    /// Neighborhoods left out because they failed validation.
+    pub dropped_neighborhoods: usize,
+    /// Problems that drop data: a lenient import skips what they point at,
+    /// a strict one refuses the file.
+    pub errors: Vec<ImportIssue>,
+    /// Problems repaired in place, and an unknown version string.
+    pub warnings: Vec<ImportIssue>,
+}
+
+/// Issues listed in a strict import's error before the rest are counted.
+const MAX_LISTED_ISSUES: usize = 10;
+
+/// Applies [`ImportOptions`] during wire → domain conversion, and clamps
+/// episode timestamps that lie in the future.
+///
+/// A machine whose clock ran ahead exports episodes dated after the import;
+/// recency treats them as brand new until that date comes, so they would
+/// top recall for as long as the skew lasts. Each is reset to the import
+/// time, keeping the original in `Episode::original_timestamp`.
+struct ImportRepairs {
+    policy: IdPolicy,
+    validation: ValidationPolicy,
+    now_secs: u64,
+    report: ImportReport,
+    /// The part of the file being parsed, named in syntax errors.
+    location: String,
+    seen_episodes: HashSet<Uuid>,
+    seen_neighborhoods: HashSet<Uuid>,
+    seen_occurrences: HashSet<Uuid>,
+    /// Steps the export's version needs, chosen by `negotiate`.
+    migrations: Vec<&'static Migration>,
+    /// Set once the first episode is parsed; a version read after that
+    /// comes too late to migrate anything.
+    started: bool,
+    /// `level.field` for every unknown field already warned about.
+    unknown_fields: HashSet<String>,
+}
+
+impl ImportRepairs {
+    fn new(options: ImportOptions) -> Self {
+        Self {
+            policy: options.ids,
+            validation: options.validation,
+            now_secs: now_unix_secs(),
+            report: ImportReport::default(),
+            location: String::from("top level"),
+            seen_episodes: HashSet::new(),
+            seen_neighborhoods: HashSet::new(),
+            seen_occurrences: HashSet::new(),
+            migrations: Vec::new(),
+            started: false,
+            unknown_fields: HashSet::new(),
+        }
+    }
+
+    fn clamp_timestamp(&mut self, episode: &mut Episode) {
+        if iso8601_to_unix(&episode.timestamp).is_some_and(|secs| secs > self.now_secs) {
+            let original =
+                std::mem::replace(&mut episode.timestamp, unix_to_iso8601(self.now_secs));
+            episode.original_timestamp.get_or_insert(original);
+            self.report.clamped_timestamps += 1;
+        }
+    }
+
+    fn fail(&mut self, raw: &str, what: &dyn std::fmt::Display) -> Result<(), String> {
+        match self.policy {
+            IdPolicy::Lenient => {
+                self.report.repaired_ids += 1;
+                Ok(())
+            }
+            IdPolicy::Strict if raw.is_empty() => Err(format!("{what} has no id")),
+            IdPolicy::Strict => Err(format!("{what} has invalid id {raw:?}")),
+        }
+    }
+
+    fn resolve(&mut self, raw: &str, what: &dyn std::fmt::Display) -> Result<Uuid, String> {
+        if let Ok(id) = Uuid::parse_str(raw) {
+            return Ok(id);
+        }
+        self.fail(raw, what)?;
+        Ok(Uuid::new_v4())
+    }
+
+    fn warn(&mut self, location: &str, message: impl Into<String>) {
+        self.report.warnings.push(ImportIssue {
+            location: location.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Whether converted episodes should still reach the caller. A strict
+    /// import keeps validating after its first error, to report them all,
+    /// but stops delivering.
+    fn delivering(&self) -> bool {
+        self.validation == ValidationPolicy::Lenient || self.report.errors.is_empty()
+    }
+
+    /// The error a strict import ends with, if anything failed.
+    fn refusal(&self) -> Option<String> {
+        let errors = &self.report.errors;
+        if self.validation == ValidationPolicy::Lenient || errors.is_empty() {
+            return None;
+        }
+        let mut lines = vec![format!(
+            "export failed validation with {} error(s):",
+            errors.len()
+        )];
+        lines.extend(
+            errors
+                .iter()
+                .take(MAX_LISTED_ISSUES)
+                .map(|issue| format!("  {issue}")),
+        );
+        if errors.len() > MAX_LISTED_ISSUES {
+            lines.push(format!(
+                "  ... and {} more",
+                errors.len() - MAX_LISTED_ISSUES
+            ));
+        }
+        Some(lines.join("\n"))
+    }
+
+    /// Check the export's version against this build and pick the
+    /// migrations it needs. Fails only for another major version, or for
+    /// an old version read after episodes it should have migrated.
+    fn negotiate(&mut self, raw: &str) -> Result<(), String> {
+        let current = FormatVersion::CURRENT;
+        let version = match raw.parse::<FormatVersion>() {
+            Ok(version) => version,
+            Err(message) => {
+                self.warn(
+                    "version",
+                    format!("{message}; reading it as {CURRENT_VERSION}"),
+                );
+                return Ok(());
+            }
+        };
+        if version.major != current.major {
+            return Err(format!(
+                "export version {version} cannot be read by this build, which reads \
+                 {CURRENT_VERSION}: the major versions differ"
+            ));
+        }
+        if version.minor > current.minor {
+            self.warn(
+                "version",
+                format!(
+                    "export version {version} is newer than {CURRENT_VERSION}; \
+                     fields this build does not know are ignored"
+                ),
+            );
+        }
+        let steps: Vec<&'static Migration> =
+            MIGRATIONS.iter().filter(|m| version < m.until).collect();
+        if !steps.is_empty() {
+            if self.started {
+                return Err(format!(
+                    "export version {version} needs migrating, but \"version\" comes \
+                     after the episodes; move it to the top of the file"
+                ));
+            }
+            let changes: Vec<&str> = steps.iter().map(|m| m.change).collect();
+            self.warn(
+                "version",
+                format!(
+                    "migrated from {version} to {CURRENT_VERSION}: {}",
+                    changes.join("; ")
+                ),
+            );
+        }
+        self.migrations = steps;
+        Ok(())
+    }
+
+    /// Warn about a field this build does not read, once per `level`.
+    fn unknown_field(&mut self, level: &str, field: &str, location: &str) {
+        if self.unknown_fields.insert(format!("{level}.{field}")) {
+            self.warn(
+                location,
+                format!("unknown {level} field `{field}` ignored (reported once)"),
+            );
+        }
+    }
+
+    fn unknown_fields_in(
+        &mut self,
+        value: &serde_json::Value,
+        known: &[&str],
+        level: &str,
+        location: &str,
+    ) {
+        if let Some(object) = value.as_object() {
+            for field in object.keys() {
+                if !known.contains(&field.as_str()) {
+                    self.unknown_field(level, field, location);
+                }
+            }
+        }
+    }
+
+    /// Migrate, convert, and validate one parsed episode element. Returns
+    /// `None`, with the reason recorded, if the episode itself is
+    /// unusable; neighborhoods that fail are left out of the episode.
+    fn episode(
+        &mut self,
+        mut value: serde_json::Value,
+        location: &str,
+        conscious: bool,
+    ) -> Option<Episode> {
+        self.started = true;
+        for migration in &self.migrations {
+            (migration.apply)(&mut value, conscious);
+        }
+        self.unknown_fields_in(&value, EPISODE_FIELDS, "episode", location);
+        let parsed = take_array(&mut value, "neighborhoods").and_then(|nbhds| {
+            let wire: WireEpisode = serde_json::from_value(value).map_err(|e| e.to_string())?;
+            Ok((wire, nbhds))
+        });
+        let (wire, nbhds) = match parsed {
+            Ok(parsed) => parsed,
+            Err(message) => return self.drop_episode(location, message),
+        };
+        let location = format!("{location} ({:?})", wire.name);
+        let name = wire.name.clone();
+        let ep = match wire_episode_to_domain(wire, self) {
+            Ok(ep) => ep,
+            Err(message) => return self.drop_episode(&location, message),
+        };
+        if !self.seen_episodes.insert(ep.id) {
+            return self.drop_episode(&location, format!("duplicate episode id {}", ep.id));
+        }
+        let mut ep = ep;
+        for (i, value) in nbhds.into_iter().enumerate() {
+            let at = match value.get("id").and_then(serde_json::Value::as_str) {
+                Some(id) => format!("{location}.neighborhoods[{i}] (id {id})"),
+                None => format!("{location}.neighborhoods[{i}]"),
+            };
+            match self.neighborhood(value, &at, &name) {
+                Ok(nbhd) => ep.add_neighborhood(nbhd),
+                Err(issue) => {
+                    self.report.errors.push(issue);
+                    self.report.dropped_neighborhoods += 1;
+                }
+            }
+        }
+        Some(ep)
+    }
+
+    fn drop_episode(&mut self, location: &str, message: String) -> Option<Episode> {
+        self.report.errors.push(ImportIssue {
+            location: location.to_string(),
+            message,
+        });
+        self.report.dropped_episodes += 1;
+        None
+    }
+
+    /// Convert and validate one neighborhood element. Quaternions that are
+    /// not unit length are normalized with a warning; zero-length ones,
+    /// duplicate IDs, and anything serde rejects fail the neighborhood.
+    fn neighborhood(
+        &mut self,
+        mut value: serde_json::Value,
+        location: &str,
+        episode_name: &str,
+    ) -> Result<Neighborhood, ImportIssue> {
+        let at = |message: String| ImportIssue {
+            location: location.to_string(),
+            message,
+        };
+        let occ_at = |k: usize, message: String| ImportIssue {
+            location: format!("{location}.occurrences[{k}]"),
+            message,
+        };
+
+        self.unknown_fields_in(&value, NEIGHBORHOOD_FIELDS, "neighborhood", location);
+        let items = take_array(&mut value, "occurrences").map_err(at)?;
+        let mut wire: WireNeighborhood =
+            serde_json::from_value(value).map_err(|e| at(e.to_string()))?;
+        for (k, item) in items.into_iter().enumerate() {
+            self.unknown_fields_in(
+                &item,
+                OCCURRENCE_FIELDS,
+                "occurrence",
+                &format!("{location}.occurrences[{k}]"),
+            );
+            wire.occurrences
+                .push(serde_json::from_value(item).map_err(|e| occ_at(k, e.to_string()))?);
+        }
+
+        let mut normalized =
+            usize::from(unit_quaternion(&mut wire.seed).map_err(|m| at(format!("seed {m}")))?);
+        for (k, occ) in wire.occurrences.iter_mut().enumerate() {
+            normalized += usize::from(
+                unit_quaternion(&mut occ.position)
+                    .map_err(|m| occ_at(k, format!("position of {:?} {m}", occ.word)))?,
+            );
+        }
+
+        let nbhd = wire_neighborhood_to_domain(wire, episode_name, self).map_err(at)?;
+        if self.seen_neighborhoods.contains(&nbhd.id) {
+            return Err(at(format!("duplicate neighborhood id {}", nbhd.id)));
+        }
+        let mut occurrence_ids = HashSet::with_capacity(nbhd.occurrences.len());
+        for (k, occ) in nbhd.occurrences.iter().enumerate() {
+            if self.seen_occurrences.contains(&occ.id) || !occurrence_ids.insert(occ.id) {
+                return Err(occ_at(k, format!("duplicate occurrence id {}", occ.id)));
+            }
+        }
+        self.seen_neighborhoods.insert(nbhd.id);
+        self.seen_occurrences.extend(occurrence_ids);
+        if normalized > 0 {
+            self.warn(
+                location,
+                format!("normalized {normalized} quaternion(s) that were not unit length"),
+            );
+        }
+        Ok(nbhd)
+    }
+}
 
+/// Remove the array `field` from a JSON object, leaving an empty one in
+/// its place so the rest still deserializes, and return its elements.
+fn take_array(
+    value: &mut serde_json::Value,
+    field: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    let Some(object) = value.as_object_mut() else {
+        return Err(format!("expected an object, found {}", json_kind(value)));
+    };
+    match object.insert(field.to_string(), serde_json::Value::Array(Vec::new())) {
+        Some(serde_json::Value::Array(items)) => Ok(items),
+        Some(other) => Err(format!(
+            "`{field}` should be an array, found {}",
+            json_kind(&other)
+        )),
+        None => Err(format!("missing field `{field}`")),
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a boolean",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "an array",
+        serde_json::Value::Object(_) => "an object",
+    }
+}
+
+/// Normalize `q` in place if its norm is off by more than
+/// [`NORM_TOLERANCE`], returning whether it was. A zero-length quaternion
+/// has no direction to keep and is an error.
+fn unit_quaternion(q: &mut [f64; 4]) -> Result<bool, String> {
+    let norm = q.iter().map(|c| c * c).sum::<f64>().sqrt();
+    if !norm.is_finite() || norm < NORM_TOLERANCE {
+        return Err(format!("{q:?} is not a rotation (norm {norm})"));
+    }
+    if (norm - 1.0).abs() <= NORM_TOLERANCE {
+        return Ok(false);
+    }
+    for c in q.iter_mut() {
+        *c /= norm;
+    }
+    Ok(true)
+}
+
+// --- Conversion: Wire → Domain ---
+
+impl WireExport {
     /// Create wire export from domain `DAESystem`.
     pub fn from_system(system: &DAESystem) -> Self {
         let conscious = domain_episode_to_wire(&system.conscious_episode);
@@ -145,45 +749,89 @@ impl WireExport {
     }
 }
 
-fn wire_episode_to_domain(wire: WireEpisode) -> Episode {
+fn wire_episode_to_domain(
+    wire: WireEpisode,
+    repairs: &mut ImportRepairs,
+) -> Result<Episode, String> {
     let mut ep = Episode::new(&wire.name);
-    ep.id = Uuid::parse_str(&wire.id).unwrap_or_else(|_| Uuid::new_v4());
+    ep.id = repairs.resolve(&wire.id, &format_args!("episode {:?}", wire.name))?;
     ep.is_conscious = wire.is_conscious;
     ep.timestamp = wire.timestamp;
+    ep.actor = wire.actor;
+    ep.original_timestamp = wire.original_timestamp;
+    repairs.clamp_timestamp(&mut ep);
+    ep.reused_neighborhoods = wire
+        .reused_neighborhoods
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect();
 
     for wire_nbhd in wire.neighborhoods {
-        ep.add_neighborhood(wire_neighborhood_to_domain(wire_nbhd));
+        ep.add_neighborhood(wire_neighborhood_to_domain(wire_nbhd, &wire.name, repairs)?);
     }
 
-    ep
+    Ok(ep)
 }
 
-fn wire_neighborhood_to_domain(wire: WireNeighborhood) -> Neighborhood {
+fn wire_neighborhood_to_domain(
+    wire: WireNeighborhood,
+    episode_name: &str,
+    repairs: &mut ImportRepairs,
+) -> Result<Neighborhood, String> {
     let seed = Quaternion::from_array(wire.seed);
     let mut nbhd = Neighborhood::new(seed, wire.source_text);
-    nbhd.id = Uuid::parse_str(&wire.id).unwrap_or_else(|_| Uuid::new_v4());
+    nbhd.id = repairs.resolve(
+        &wire.id,
+        &format_args!("neighborhood in episode {episode_name:?}"),
+    )?;
     nbhd.neighborhood_type = NeighborhoodType::from_str_lossy(&wire.neighborhood_type);
     nbhd.epoch = wire.epoch;
-    nbhd.superseded_by = wire
-        .superseded_by
+    nbhd.actor = wire.actor;
+    nbhd.project = wire.project;
+    nbhd.created_at = wire.created_at;
+    nbhd.feedback_score = wire.feedback_score;
+    nbhd.group_id = wire
+        .group_id
         .as_deref()
-        .and_then(|s| Uuid::parse_str(s).ok());
+        .and_then(|raw| Uuid::parse_str(raw).ok());
+    nbhd.superseded_by = None;
+    if let Some(raw) = wire.superseded_by.as_deref() {
+        if let Ok(id) = Uuid::parse_str(raw) {
+            nbhd.superseded_by = Some(id);
+        } else {
+            repairs.fail(
+                raw,
+                &format_args!("supersededBy of neighborhood {}", nbhd.id),
+            )?;
+        }
+    }
 
-    for wire_occ in wire.occurrences {
+    for (i, wire_occ) in wire.occurrences.into_iter().enumerate() {
+        let id = repairs.resolve(
+            &wire_occ.id,
+            &format_args!(
+                "occurrence {i} ({:?}) of neighborhood {}",
+                wire_occ.word, nbhd.id
+            ),
+        )?;
         let mut occ = Occurrence::new(
             wire_occ.word,
             Quaternion::from_array(wire_occ.position),
             DaemonPhasor::new(wire_occ.phasor),
             nbhd.id,
         );
+        occ.id = id;
         occ.activation_count = wire_occ.activation_count;
+        occ.drift = wire_occ.drift;
+        occ.spillover = wire_occ.spillover;
+        occ.span = wire_occ.span;
         if let Ok(id) = Uuid::parse_str(&wire_occ.neighborhood_id) {
             occ.neighborhood_id = id;
         }
         nbhd.occurrences.push(occ);
     }
 
-    nbhd
+    Ok(nbhd)
 }
 
 fn domain_episode_to_wire(ep: &Episode) -> WireEpisode {
@@ -192,6 +840,13 @@ fn domain_episode_to_wire(ep: &Episode) -> WireEpisode {
         is_conscious: ep.is_conscious,
         id: ep.id.to_string(),
         timestamp: ep.timestamp.clone(),
+        actor: ep.actor.clone(),
+        original_timestamp: ep.original_timestamp.clone(),
+        reused_neighborhoods: ep
+            .reused_neighborhoods
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
         neighborhoods: ep
             .neighborhoods
             .iter()
@@ -208,6 +863,11 @@ fn domain_neighborhood_to_wire(nbhd: &Neighborhood) -> WireNeighborhood {
         neighborhood_type: nbhd.neighborhood_type.as_str().to_string(),
         epoch: nbhd.epoch,
         superseded_by: nbhd.superseded_by.map(|id| id.to_string()),
+        actor: nbhd.actor.clone(),
+        project: nbhd.project.clone(),
+        created_at: nbhd.created_at.clone(),
+        feedback_score: nbhd.feedback_score,
+        group_id: nbhd.group_id.map(|id| id.to_string()),
         occurrences: nbhd
             .occurrences
             .iter()
@@ -217,6 +877,10 @@ fn domain_neighborhood_to_wire(nbhd: &Neighborhood) -> WireNeighborhood {
                 phasor: occ.phasor.theta,
                 activation_count: occ.activation_count,
                 neighborhood_id: occ.neighborhood_id.to_string(),
+                id: occ.id.to_string(),
+                drift: occ.drift,
+                spillover: occ.spillover,
+                span: occ.span,
             })
             .collect(),
     }
@@ -224,13 +888,79 @@ fn domain_neighborhood_to_wire(nbhd: &Neighborhood) -> WireNeighborhood {
 
 /// Deserialize a v0.7.2 JSON export into a `DAESystem`.
 ///
+/// Missing or malformed IDs are regenerated ([`IdPolicy::Lenient`]) and
+/// anything that fails validation refuses the import; use
+/// [`import_json_with`] to change either or to read the report.
+///
 /// # Errors
 ///
-/// Returns `serde_json::Error` if the JSON is malformed or does not match
-/// the v0.7.2 wire format schema.
+/// Returns `serde_json::Error` if the JSON is malformed, does not match
+/// the v0.7.2 wire format schema, or fails validation.
 pub fn import_json(json: &str) -> Result<DAESystem, serde_json::Error> {
-    let wire: WireExport = serde_json::from_str(json)?;
-    Ok(wire.into_system())
+    import_json_with(json, ImportOptions::default()).map(|(system, _)| system)
+}
+
+/// Deserialize a v0.7.2 JSON export under an explicit [`IdPolicy`],
+/// refusing anything that fails validation.
+///
+/// # Errors
+///
+/// As [`import_json_with`].
+pub fn import_json_with_policy(
+    json: &str,
+    policy: IdPolicy,
+) -> Result<(DAESystem, ImportReport), serde_json::Error> {
+    import_json_with(
+        json,
+        ImportOptions {
+            ids: policy,
+            ..ImportOptions::default()
+        },
+    )
+}
+
+/// Deserialize a v0.7.2 JSON export under explicit [`ImportOptions`].
+///
+/// Returns the system and a report of the IDs that were regenerated, the
+/// future timestamps that were clamped, and every validation problem.
+/// Under [`ValidationPolicy::Lenient`] the report also counts the
+/// episodes and neighborhoods left out.
+///
+/// # Errors
+///
+/// Returns `serde_json::Error` if the JSON is malformed or does not match
+/// the v0.7.2 wire format schema, naming the episode it stopped in, or,
+/// under [`ValidationPolicy::Strict`], listing every validation error.
+pub fn import_json_with(
+    json: &str,
+    options: ImportOptions,
+) -> Result<(DAESystem, ImportReport), serde_json::Error> {
+    let mut episodes = Vec::new();
+    let mut conscious = None;
+    let summary = stream_with(
+        serde_json::Deserializer::from_str(json),
+        options,
+        |ep: Episode| {
+            if ep.is_conscious {
+                conscious = Some(ep);
+            } else {
+                episodes.push(ep);
+            }
+            Ok::<(), String>(())
+        },
+    )?;
+
+    let mut sys = DAESystem::new(&summary.agent_name);
+    for ep in episodes {
+        sys.add_episode(ep);
+    }
+    if let Some(ep) = conscious {
+        sys.conscious_episode = ep;
+    }
+    sys.mark_dirty();
+    sys.sync_next_epoch();
+    sys.sync_seq(0);
+    Ok((sys, summary.report))
 }
 
 /// Serialize a `DAESystem` to v0.7.2 JSON wire format.
@@ -244,11 +974,352 @@ pub fn export_json(system: &DAESystem) -> Result<String, serde_json::Error> {
     serde_json::to_string_pretty(&wire)
 }
 
+// --- Streaming import ---
+
+/// Top-level facts gathered while streaming a v0.7.2 export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamSummary {
+    pub version: String,
+    pub agent_name: String,
+    /// Subconscious episodes seen (the conscious episode is not counted).
+    pub episodes: usize,
+    pub report: ImportReport,
+}
+
+/// Stream a v0.7.2 export episode-by-episode without materializing the
+/// full `DAESystem`, refusing it if anything fails validation.
+///
+/// See [`stream_import_json_with`].
+///
+/// # Errors
+///
+/// As [`stream_import_json_with`].
+pub fn stream_import_json<R, F, E>(
+    reader: R,
+    policy: IdPolicy,
+    on_episode: F,
+) -> Result<StreamSummary, serde_json::Error>
+where
+    R: std::io::Read,
+    F: FnMut(Episode) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    let options = ImportOptions {
+        ids: policy,
+        ..ImportOptions::default()
+    };
+    stream_import_json_with(reader, options, on_episode)
+}
+
+/// Stream a v0.7.2 export episode-by-episode under explicit
+/// [`ImportOptions`].
+///
+/// Each subconscious episode is converted, validated, and handed to
+/// `on_episode` as soon as it has been parsed, so peak memory is bounded by
+/// the largest single episode. The conscious episode is delivered through
+/// the same callback with `is_conscious` set. An error returned by the
+/// callback aborts the parse and is surfaced as a `serde_json::Error`.
+///
+/// Under [`ValidationPolicy::Strict`] the first validation error stops
+/// delivery, but parsing continues so the error can list every problem in
+/// the file; the caller should discard what it was given.
+///
+/// # Errors
+///
+/// Returns `serde_json::Error` if the JSON is malformed or does not match
+/// the v0.7.2 wire format schema (naming the episode it stopped in), fails
+/// strict validation, or the callback rejects an episode.
+pub fn stream_import_json_with<R, F, E>(
+    reader: R,
+    options: ImportOptions,
+    on_episode: F,
+) -> Result<StreamSummary, serde_json::Error>
+where
+    R: std::io::Read,
+    F: FnMut(Episode) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    stream_with(
+        serde_json::Deserializer::from_reader(reader),
+        options,
+        on_episode,
+    )
+}
+
+fn stream_with<'de, R, F, E>(
+    mut de: serde_json::Deserializer<R>,
+    options: ImportOptions,
+    mut on_episode: F,
+) -> Result<StreamSummary, serde_json::Error>
+where
+    R: serde_json::de::Read<'de>,
+    F: FnMut(Episode) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    let mut repairs = ImportRepairs::new(options);
+    let parsed = serde::de::DeserializeSeed::deserialize(
+        stream::ExportSeed {
+            sink: Some(&mut on_episode),
+            repairs: &mut repairs,
+        },
+        &mut de,
+    )
+    .and_then(|summary| de.end().map(|()| summary));
+    let mut summary = parsed.map_err(|e| locate(&e, &repairs.location))?;
+    if let Some(msg) = repairs.refusal() {
+        return Err(serde::de::Error::custom(msg));
+    }
+    summary.report = repairs.report;
+    Ok(summary)
+}
+
+/// Name the part of the file a parse error happened in, since serde's
+/// line and column alone say little about a large export.
+fn locate(e: &serde_json::Error, location: &str) -> serde_json::Error {
+    let hint = if e.is_eof() {
+        "; the file ends early, so it may be truncated"
+    } else {
+        ""
+    };
+    serde::de::Error::custom(format!("{e} (in {location}{hint})"))
+}
+
+/// Count the subconscious episodes in a v0.7.2 export without building them.
+///
+/// Used to size progress reporting ahead of [`stream_import_json`].
+///
+/// # Errors
+///
+/// Returns `serde_json::Error` if the JSON is malformed or does not match
+/// the v0.7.2 wire format schema.
+pub fn count_export_episodes<R: std::io::Read>(reader: R) -> Result<usize, serde_json::Error> {
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let mut repairs = ImportRepairs::new(ImportOptions::default());
+    let summary = serde::de::DeserializeSeed::deserialize(
+        stream::ExportSeed::<fn(Episode) -> Result<(), String>> {
+            sink: None,
+            repairs: &mut repairs,
+        },
+        &mut de,
+    )
+    .and_then(|summary| de.end().map(|()| summary))
+    .map_err(|e| locate(&e, &repairs.location))?;
+    Ok(summary.episodes)
+}
+
+/// Seeded visitors that walk `{ system: { episodes: [...] } }` one element at
+/// a time, keeping `ImportRepairs::location` on the element being parsed.
+/// Each episode is read as a `serde_json::Value` and validated piece by
+/// piece, so a bad field is reported where it is instead of failing the
+/// parse. With no sink, episodes are skipped via `IgnoredAny` and only
+/// counted.
+mod stream {
+    use std::fmt;
+
+    use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+    use super::{EXPORT_FIELDS, ImportRepairs, SYSTEM_FIELDS, StreamSummary};
+    use crate::episode::Episode;
+
+    pub(super) struct ExportSeed<'a, F> {
+        pub sink: Option<&'a mut F>,
+        pub repairs: &'a mut ImportRepairs,
+    }
+
+    impl<'de, F, E> DeserializeSeed<'de> for ExportSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = StreamSummary;
+
+        fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_map(self)
+        }
+    }
+
+    impl<'de, F, E> Visitor<'de> for ExportSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = StreamSummary;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a v0.7.2 export object")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut version = None;
+            let mut system = None;
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "version" => {
+                        let v = map.next_value::<String>()?;
+                        self.repairs.negotiate(&v).map_err(de::Error::custom)?;
+                        version = Some(v);
+                    }
+                    "system" => {
+                        self.repairs.location = String::from("system");
+                        system = Some(map.next_value_seed(SystemSeed {
+                            sink: self.sink.as_deref_mut(),
+                            repairs: &mut *self.repairs,
+                        })?);
+                        self.repairs.location = String::from("top level");
+                    }
+                    other => {
+                        if !EXPORT_FIELDS.contains(&other) {
+                            self.repairs.unknown_field("export", other, "top level");
+                        }
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+            let mut summary = system.ok_or_else(|| de::Error::missing_field("system"))?;
+            summary.version = version;
+            Ok(summary)
+        }
+    }
+
+    struct SystemSeed<'a, F> {
+        sink: Option<&'a mut F>,
+        repairs: &'a mut ImportRepairs,
+    }
+
+    impl<'de, F, E> DeserializeSeed<'de> for SystemSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = StreamSummary;
+
+        fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_map(self)
+        }
+    }
+
+    impl<'de, F, E> Visitor<'de> for SystemSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = StreamSummary;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a v0.7.2 system object")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut episodes = None;
+            let mut saw_conscious = false;
+            let mut agent_name = String::new();
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "episodes" => {
+                        episodes = Some(map.next_value_seed(EpisodesSeed {
+                            sink: self.sink.as_deref_mut(),
+                            repairs: &mut *self.repairs,
+                        })?);
+                    }
+                    "consciousEpisode" => {
+                        saw_conscious = true;
+                        let location = "system.consciousEpisode";
+                        self.repairs.location = String::from(location);
+                        if let Some(sink) = self.sink.as_deref_mut() {
+                            let value = map.next_value::<serde_json::Value>()?;
+                            if let Some(mut ep) = self.repairs.episode(value, location, true) {
+                                ep.is_conscious = true;
+                                if self.repairs.delivering() {
+                                    sink(ep).map_err(de::Error::custom)?;
+                                }
+                            }
+                        } else {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                    "agentName" => agent_name = map.next_value::<String>()?,
+                    other => {
+                        if !SYSTEM_FIELDS.contains(&other) {
+                            self.repairs.unknown_field("system", other, "system");
+                        }
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+                self.repairs.location = String::from("system");
+            }
+            if !saw_conscious {
+                return Err(de::Error::missing_field("consciousEpisode"));
+            }
+            Ok(StreamSummary {
+                agent_name,
+                episodes: episodes.ok_or_else(|| de::Error::missing_field("episodes"))?,
+                ..StreamSummary::default()
+            })
+        }
+    }
+
+    struct EpisodesSeed<'a, F> {
+        sink: Option<&'a mut F>,
+        repairs: &'a mut ImportRepairs,
+    }
+
+    impl<'de, F, E> DeserializeSeed<'de> for EpisodesSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = usize;
+
+        fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_seq(self)
+        }
+    }
+
+    impl<'de, F, E> Visitor<'de> for EpisodesSeed<'_, F>
+    where
+        F: FnMut(Episode) -> Result<(), E>,
+        E: fmt::Display,
+    {
+        type Value = usize;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an array of episodes")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut count = 0;
+            loop {
+                let location = format!("system.episodes[{count}]");
+                self.repairs.location.clone_from(&location);
+                let Some(sink) = self.sink.as_deref_mut() else {
+                    if seq.next_element::<IgnoredAny>()?.is_none() {
+                        break;
+                    }
+                    count += 1;
+                    continue;
+                };
+                let Some(value) = seq.next_element::<serde_json::Value>()? else {
+                    break;
+                };
+                if let Some(ep) = self.repairs.episode(value, &location, false)
+                    && self.repairs.delivering()
+                {
+                    sink(ep).map_err(de::Error::custom)?;
+                }
+                count += 1;
+            }
+            Ok(count)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::episode::Episode;
-    use crate::neighborhood::Neighborhood;
+    use crate::neighborhood::{Neighborhood, NeighborhoodType};
+    use crate::tokenizer::ingest_text;
     use rand::SeedableRng;
     use rand::rngs::SmallRng;
 
@@ -505,6 +1576,122 @@ mod tests {
         assert_eq!(sys2.conscious_episode.neighborhoods[1].superseded_by, None);
     }
 
+    #[test]
+    fn test_actor_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        sys.set_actor(Some("claude-code".to_string()));
+        sys.add_episode(ingest_text("attributed episode text", None, &mut rng));
+        sys.add_to_conscious("attributed insight", &mut rng);
+        sys.set_actor(None);
+        sys.add_episode(ingest_text("anonymous episode text", None, &mut rng));
+
+        let json = export_json(&sys).unwrap();
+        let sys2 = import_json(&json).unwrap();
+
+        assert_eq!(sys2.episodes[0].actor.as_deref(), Some("claude-code"));
+        assert_eq!(sys2.episodes[1].actor, None);
+        assert_eq!(
+            sys2.conscious_episode.neighborhoods[0].actor.as_deref(),
+            Some("claude-code")
+        );
+        assert_eq!(
+            sys2.conscious_episode.neighborhoods[0].created_at,
+            sys.conscious_episode.neighborhoods[0].created_at
+        );
+        assert!(sys2.conscious_episode.neighborhoods[0].created_at.is_some());
+    }
+
+    #[test]
+    fn test_project_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        let kind = crate::neighborhood::NeighborhoodType::Decision;
+        sys.add_to_conscious_for("use sqlite", kind, Some("Repo-X"), &mut rng);
+        sys.add_to_conscious("applies everywhere", &mut rng);
+
+        let json = export_json(&sys).unwrap();
+        let sys2 = import_json(&json).unwrap();
+
+        let nbhds = &sys2.conscious_episode.neighborhoods;
+        assert_eq!(nbhds[0].project.as_deref(), Some("repo-x"));
+        assert_eq!(nbhds[1].project, None);
+    }
+
+    #[test]
+    fn test_feedback_score_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        sys.add_to_conscious("keep answers short", &mut rng);
+        sys.conscious_episode.neighborhoods[0].feedback_score = -1.8;
+
+        let json = export_json(&sys).unwrap();
+        let sys2 = import_json(&json).unwrap();
+        assert_eq!(sys2.conscious_episode.neighborhoods[0].feedback_score, -1.8);
+    }
+
+    #[test]
+    fn test_group_id_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        let chunks = ["part one".to_string(), "part two".to_string()];
+        sys.add_conscious_group(&chunks, NeighborhoodType::Insight, None, &mut rng);
+        sys.add_to_conscious("whole", &mut rng);
+        let group = sys.conscious_episode.neighborhoods[0].group_id;
+
+        let json = export_json(&sys).unwrap();
+        assert_eq!(json.matches("groupId").count(), 2);
+        let sys2 = import_json(&json).unwrap();
+        let groups: Vec<_> = sys2
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.group_id)
+            .collect();
+        assert_eq!(groups, [group, group, None]);
+    }
+
+    #[test]
+    fn test_occurrence_span_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        sys.add_episode(ingest_text("Crème brûlée wins", None, &mut rng));
+        sys.add_to_conscious("no spans here", &mut rng);
+
+        let json = export_json(&sys).unwrap();
+        let sys2 = import_json(&json).unwrap();
+        let spans: Vec<_> = sys2.episodes[0].neighborhoods[0]
+            .occurrences
+            .iter()
+            .map(|o| o.span)
+            .collect();
+        assert_eq!(spans, vec![Some((0, 6)), Some((7, 15)), Some((16, 20))]);
+        assert!(
+            sys2.conscious_episode.neighborhoods[0]
+                .occurrences
+                .iter()
+                .all(|o| o.span.is_none())
+        );
+    }
+
+    #[test]
+    fn test_reused_neighborhoods_roundtrip() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test-agent");
+        sys.set_dedup_neighborhoods(true);
+        sys.add_episode(ingest_text("shared chunk text", None, &mut rng));
+        sys.add_episode(ingest_text("shared chunk text", None, &mut rng));
+        let shared_id = sys.episodes[0].neighborhoods[0].id;
+
+        let json = export_json(&sys).unwrap();
+        assert_eq!(json.matches("reusedNeighborhoods").count(), 1);
+        let sys2 = import_json(&json).unwrap();
+
+        assert!(sys2.episodes[0].reused_neighborhoods.is_empty());
+        assert_eq!(sys2.episodes[1].reused_neighborhoods, vec![shared_id]);
+        assert!(sys2.episodes[1].neighborhoods.is_empty());
+    }
+
     #[test]
     fn test_old_format_without_superseded_by() {
         // Old wire format without supersededBy - should default to None
@@ -545,4 +1732,472 @@ mod tests {
         assert_eq!(sys.episodes[0].neighborhoods[0].superseded_by, None);
         assert_eq!(sys.episodes[0].neighborhoods[0].epoch, 5);
     }
+
+    #[test]
+    fn test_stream_import_matches_import_json() {
+        let sys = make_test_system();
+        let json = export_json(&sys).unwrap();
+
+        let mut streamed = Vec::new();
+        let summary = stream_import_json(json.as_bytes(), IdPolicy::Lenient, |ep| {
+            streamed.push(ep);
+            Ok::<(), String>(())
+        })
+        .unwrap();
+
+        assert_eq!(summary.version, CURRENT_VERSION);
+        assert_eq!(summary.agent_name, "test-agent");
+        assert_eq!(summary.episodes, 1);
+        // One subconscious episode plus the conscious episode
+        assert_eq!(streamed.len(), 2);
+        assert!(!streamed[0].is_conscious);
+        assert!(streamed[1].is_conscious);
+        assert_eq!(streamed[0].id, sys.episodes[0].id);
+        assert_eq!(
+            streamed[1].neighborhoods.len(),
+            sys.conscious_episode.neighborhoods.len()
+        );
+    }
+
+    #[test]
+    fn test_count_export_episodes() {
+        let mut sys = make_test_system();
+        sys.add_episode(Episode::new("second"));
+        let json = export_json(&sys).unwrap();
+        assert_eq!(count_export_episodes(json.as_bytes()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_stream_import_callback_error_aborts() {
+        let mut sys = make_test_system();
+        sys.add_episode(Episode::new("second"));
+        let json = export_json(&sys).unwrap();
+
+        let mut seen = 0;
+        let err = stream_import_json(json.as_bytes(), IdPolicy::Lenient, |_| {
+            seen += 1;
+            Err("sink full")
+        })
+        .unwrap_err();
+        assert_eq!(seen, 1, "parse must stop at the first rejected episode");
+        assert!(err.to_string().contains("sink full"));
+    }
+
+    #[test]
+    fn test_stream_import_rejects_missing_system() {
+        let err = stream_import_json(
+            br#"{"version": "0.7.2"}"#.as_slice(),
+            IdPolicy::Lenient,
+            |_| Ok::<(), String>(()),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("system"));
+    }
+
+    fn all_ids(sys: &DAESystem) -> Vec<Uuid> {
+        let mut ids = Vec::new();
+        for ep in sys
+            .episodes
+            .iter()
+            .chain(std::iter::once(&sys.conscious_episode))
+        {
+            ids.push(ep.id);
+            for nbhd in &ep.neighborhoods {
+                ids.push(nbhd.id);
+                ids.extend(nbhd.occurrences.iter().map(|o| o.id));
+            }
+        }
+        ids
+    }
+
+    /// Export, then strip the ID of the first occurrence of the first episode.
+    fn export_without_first_occurrence_id(sys: &DAESystem) -> String {
+        let mut value: serde_json::Value =
+            serde_json::from_str(&export_json(sys).unwrap()).unwrap();
+        value["system"]["episodes"][0]["neighborhoods"][0]["occurrences"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("id");
+        value.to_string()
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_every_uuid() {
+        let mut rng = rng();
+        let mut sys = make_test_system();
+        let old = sys.add_to_conscious("old memory", &mut rng);
+        let new = sys.add_to_conscious("new memory", &mut rng);
+        sys.mark_superseded(old, new);
+
+        let json = export_json(&sys).unwrap();
+        let (sys2, report) = import_json_with_policy(&json, IdPolicy::Strict).unwrap();
+        assert_eq!(report, ImportReport::default());
+
+        let reimported: std::collections::HashSet<Uuid> = all_ids(&sys2).into_iter().collect();
+        for id in all_ids(&sys) {
+            assert!(reimported.contains(&id), "{id} lost on reimport");
+        }
+        assert_eq!(all_ids(&sys).len(), reimported.len());
+
+        // Streaming path preserves them too
+        let mut streamed = Vec::new();
+        stream_import_json(json.as_bytes(), IdPolicy::Strict, |ep| {
+            streamed.extend(ep.all_occurrences().map(|o| o.id).collect::<Vec<_>>());
+            Ok::<(), String>(())
+        })
+        .unwrap();
+        let original: Vec<Uuid> = sys
+            .episodes
+            .iter()
+            .chain(std::iter::once(&sys.conscious_episode))
+            .flat_map(|e| e.all_occurrences().map(|o| o.id).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(streamed, original);
+    }
+
+    #[test]
+    fn test_strict_rejects_missing_occurrence_id() {
+        let json = export_without_first_occurrence_id(&make_test_system());
+
+        let Err(err) = import_json_with_policy(&json, IdPolicy::Strict) else {
+            panic!("strict import must reject a missing occurrence id");
+        };
+        assert!(err.to_string().contains("has no id"), "{err}");
+
+        let err = stream_import_json(json.as_bytes(), IdPolicy::Strict, |_| Ok::<(), String>(()))
+            .unwrap_err();
+        assert!(err.to_string().contains("has no id"), "{err}");
+    }
+
+    #[test]
+    fn test_lenient_regenerates_and_reports() {
+        let sys = make_test_system();
+        let json = export_without_first_occurrence_id(&sys);
+
+        let (sys2, report) = import_json_with_policy(&json, IdPolicy::Lenient).unwrap();
+        assert_eq!(report.repaired_ids, 1);
+        assert_eq!(sys.n(), sys2.n());
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["system"]["consciousEpisode"]["id"] = serde_json::json!("not-a-uuid");
+        let summary = stream_import_json(value.to_string().as_bytes(), IdPolicy::Lenient, |_| {
+            Ok::<(), String>(())
+        })
+        .unwrap();
+        assert_eq!(summary.report.repaired_ids, 2);
+    }
+
+    const FUTURE_DATED: &str = include_str!("../tests/fixtures/future_dated_export.json");
+
+    // 2026-03-13T00:00:00Z, before the fixture's 2031 episode
+    const IMPORT_SECS: u64 = 1_773_360_000;
+
+    #[test]
+    fn test_future_timestamps_clamped_to_import_time() {
+        let _clock = crate::time::freeze_clock(IMPORT_SECS);
+        let (sys, report) = import_json_with_policy(FUTURE_DATED, IdPolicy::Strict).unwrap();
+        assert_eq!(report.clamped_timestamps, 1);
+
+        let future = &sys.episodes[0];
+        assert_eq!(future.timestamp, "2026-03-13T00:00:00Z");
+        assert_eq!(
+            future.original_timestamp.as_deref(),
+            Some("2031-06-01T08:30:00Z")
+        );
+        let past = &sys.episodes[1];
+        assert_eq!(past.timestamp, "2026-03-01T12:00:00Z");
+        assert_eq!(past.original_timestamp, None);
+
+        // The provenance survives a re-export, and nothing is clamped twice
+        let (again, report) =
+            import_json_with_policy(&export_json(&sys).unwrap(), IdPolicy::Strict).unwrap();
+        assert_eq!(report.clamped_timestamps, 0);
+        assert_eq!(
+            again.episodes[0].original_timestamp,
+            future.original_timestamp
+        );
+
+        let summary = stream_import_json(FUTURE_DATED.as_bytes(), IdPolicy::Strict, |_| {
+            Ok::<(), String>(())
+        })
+        .unwrap();
+        assert_eq!(summary.report.clamped_timestamps, 1);
+    }
+
+    #[test]
+    fn test_clamped_episode_decays_from_import_time() {
+        let sys = {
+            let _clock = crate::time::freeze_clock(IMPORT_SECS);
+            import_json(FUTURE_DATED).unwrap()
+        };
+
+        // Ten days on, the skewed episode has aged ten days instead of
+        // staying "new" until 2031
+        let _later = crate::time::freeze_clock(IMPORT_SECS + 10 * 86400);
+        let days =
+            crate::recency::days_since_episode(&sys, crate::system::EpisodeRef::Subconscious(0));
+        assert!((days - 10.0).abs() < f64::EPSILON, "got {days}");
+    }
+
+    fn export_value(sys: &DAESystem) -> serde_json::Value {
+        serde_json::from_str(&export_json(sys).unwrap()).unwrap()
+    }
+
+    fn import_error(json: &str) -> String {
+        let Err(err) = import_json(json) else {
+            panic!("import must fail");
+        };
+        err.to_string()
+    }
+
+    const LENIENT: ImportOptions = ImportOptions {
+        ids: IdPolicy::Lenient,
+        validation: ValidationPolicy::Lenient,
+    };
+
+    #[test]
+    fn test_truncated_export_names_the_episode() {
+        let mut sys = make_test_system();
+        sys.add_episode(Episode::new("second"));
+        let json = export_json(&sys).unwrap();
+        let cut = json.find("\"second\"").unwrap();
+
+        let err = import_error(&json[..cut]);
+        assert!(err.contains("system.episodes[1]"), "{err}");
+        assert!(err.contains("truncated"), "{err}");
+
+        let err = count_export_episodes(&json.as_bytes()[..cut])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("system.episodes[1]"), "{err}");
+    }
+
+    #[test]
+    fn test_bad_field_is_located() {
+        let mut value = export_value(&make_test_system());
+        let nbhd = &mut value["system"]["episodes"][0]["neighborhoods"][1];
+        let id = nbhd["id"].as_str().unwrap().to_string();
+        nbhd.as_object_mut().unwrap().remove("seed");
+        value["system"]["episodes"][0]["neighborhoods"][0]["occurrences"][1]["phasor"] =
+            serde_json::json!("north");
+
+        let err = import_error(&value.to_string());
+        assert!(err.contains("2 error(s)"), "{err}");
+        assert!(
+            err.contains(&format!(
+                "system.episodes[0] (\"memories\").neighborhoods[1] (id {id}): missing field `seed`"
+            )),
+            "{err}"
+        );
+        assert!(err.contains("neighborhoods[0]"), "{err}");
+        assert!(err.contains(".occurrences[1]: invalid type"), "{err}");
+    }
+
+    #[test]
+    fn test_lenient_validation_drops_bad_neighborhoods() {
+        let sys = make_test_system();
+        let mut value = export_value(&sys);
+        value["system"]["episodes"][0]["neighborhoods"][0]["seed"] =
+            serde_json::json!([0.0, 0.0, 0.0, 0.0]);
+
+        let (sys2, report) = import_json_with(&value.to_string(), LENIENT).unwrap();
+        assert_eq!(report.dropped_neighborhoods, 1);
+        assert_eq!(report.dropped_episodes, 0);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].message.contains("not a rotation"));
+        assert_eq!(sys2.episodes[0].neighborhoods.len(), 1);
+        assert_eq!(
+            sys2.episodes[0].neighborhoods[0].id,
+            sys.episodes[0].neighborhoods[1].id
+        );
+        assert_eq!(
+            sys2.conscious_episode.neighborhoods.len(),
+            sys.conscious_episode.neighborhoods.len()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_errors() {
+        let mut value = export_value(&make_test_system());
+        let first = value["system"]["episodes"][0]["neighborhoods"][0]["id"].clone();
+        value["system"]["episodes"][0]["neighborhoods"][1]["id"] = first;
+
+        let err = import_error(&value.to_string());
+        assert!(err.contains("duplicate neighborhood id"), "{err}");
+
+        let mut value = export_value(&make_test_system());
+        let episode = value["system"]["episodes"][0].clone();
+        value["system"]["episodes"]
+            .as_array_mut()
+            .unwrap()
+            .push(episode);
+        let (sys, report) = import_json_with(&value.to_string(), LENIENT).unwrap();
+        assert_eq!(sys.episodes.len(), 1);
+        assert_eq!(report.dropped_episodes, 1);
+        assert!(report.errors[0].message.contains("duplicate episode id"));
+    }
+
+    #[test]
+    fn test_non_unit_quaternions_normalized_with_warning() {
+        let mut value = export_value(&make_test_system());
+        value["system"]["episodes"][0]["neighborhoods"][0]["seed"] =
+            serde_json::json!([2.0, 0.0, 0.0, 0.0]);
+        value["version"] = serde_json::json!("0.9.0");
+
+        let (sys, report) = import_json_with_policy(&value.to_string(), IdPolicy::Strict).unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 2);
+        // Value orders keys, so "version" comes after "system"
+        assert!(
+            report.warnings[0]
+                .message
+                .contains("normalized 1 quaternion")
+        );
+        assert_eq!(report.warnings[1].location, "version");
+        assert!((sys.episodes[0].neighborhoods[0].seed.w - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_strict_validation_stops_delivery() {
+        let mut sys = make_test_system();
+        sys.add_episode(Episode::new("second"));
+        // Value sorts keys, so the conscious episode comes first
+        let mut value = export_value(&sys);
+        value["system"]["consciousEpisode"]["neighborhoods"][0]["seed"] =
+            serde_json::json!([0.0, 0.0, 0.0, 0.0]);
+
+        let mut delivered = 0;
+        let err = stream_import_json(value.to_string().as_bytes(), IdPolicy::Lenient, |_| {
+            delivered += 1;
+            Ok::<(), String>(())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("not a rotation"), "{err}");
+        assert_eq!(delivered, 0);
+
+        let summary = stream_import_json_with(value.to_string().as_bytes(), LENIENT, |_| {
+            delivered += 1;
+            Ok::<(), String>(())
+        })
+        .unwrap();
+        assert_eq!(delivered, 3);
+        assert_eq!(summary.report.dropped_neighborhoods, 1);
+    }
+
+    const V0_6: &str = include_str!("../tests/fixtures/export_v0_6_0.json");
+    const V0_8: &str = include_str!("../tests/fixtures/export_v0_8_0.json");
+
+    /// Every occurrence's word, position, phase, and neighborhood type.
+    fn occurrence_data(sys: &DAESystem) -> Vec<(String, [f64; 4], f64, NeighborhoodType)> {
+        sys.episodes
+            .iter()
+            .chain(std::iter::once(&sys.conscious_episode))
+            .flat_map(|ep| &ep.neighborhoods)
+            .flat_map(|nbhd| {
+                nbhd.occurrences.iter().map(|occ| {
+                    (
+                        occ.word.clone(),
+                        occ.position.to_array(),
+                        occ.phasor.theta,
+                        nbhd.neighborhood_type,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Export `sys` and read it back strictly: the result must be clean.
+    fn reexport(sys: &DAESystem) -> DAESystem {
+        let json = export_json(sys).unwrap();
+        assert!(json.contains(&format!("\"version\": \"{CURRENT_VERSION}\"")));
+        let (again, report) = import_json_with_policy(&json, IdPolicy::Strict).unwrap();
+        assert_eq!(report, ImportReport::default());
+        again
+    }
+
+    #[test]
+    fn test_format_version_parsing() {
+        assert_eq!(
+            CURRENT_VERSION.parse::<FormatVersion>(),
+            Ok(FormatVersion::CURRENT)
+        );
+        assert_eq!("v0.6".parse(), Ok(FormatVersion::new(0, 6, 0)));
+        assert_eq!(FormatVersion::new(1, 0, 3).to_string(), "1.0.3");
+        assert!(FormatVersion::new(0, 6, 9) < FormatVersion::new(0, 7, 0));
+        for bad in ["", "7", "0.x.1", "0.7.2.1"] {
+            assert!(bad.parse::<FormatVersion>().is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_migrates_older_minor_export() {
+        let (sys, report) = import_json_with(V0_6, ImportOptions::default()).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        // The 0.6 fixture predates occurrence IDs
+        assert_eq!(report.repaired_ids, 3);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(
+            report.warnings[0]
+                .message
+                .starts_with("migrated from 0.6.0 to 0.7.2")
+        );
+
+        let conscious = &sys.conscious_episode.neighborhoods[0];
+        assert_eq!(conscious.neighborhood_type, NeighborhoodType::Insight);
+        let subconscious = &sys.episodes[0].neighborhoods[0];
+        assert_eq!(subconscious.neighborhood_type, NeighborhoodType::Memory);
+        assert!((subconscious.occurrences[1].phasor.theta - 1.25).abs() < 1e-12);
+        assert_eq!(subconscious.occurrences[0].activation_count, 3);
+
+        let again = reexport(&sys);
+        assert_eq!(occurrence_data(&again), occurrence_data(&sys));
+        assert_eq!(all_ids(&again), all_ids(&sys));
+    }
+
+    #[test]
+    fn test_reads_newer_minor_export() {
+        let (sys, report) = import_json_with_policy(V0_8, IdPolicy::Strict).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings[0].message.contains("newer than 0.7.2"));
+        let mut ignored: Vec<&str> = report.warnings[1..]
+            .iter()
+            .map(|w| w.message.split('`').nth(1).unwrap())
+            .collect();
+        ignored.sort_unstable();
+        // `confidence` appears on both occurrences but is reported once
+        assert_eq!(
+            ignored,
+            ["confidence", "embedding", "exportedBy", "shards", "tags"]
+        );
+
+        let nbhd = &sys.episodes[0].neighborhoods[0];
+        assert_eq!(nbhd.neighborhood_type, NeighborhoodType::Decision);
+        assert_eq!(nbhd.epoch, 4);
+        assert!((nbhd.feedback_score - 0.5).abs() < 1e-12);
+        assert!((nbhd.occurrences[0].drift - 0.01).abs() < 1e-12);
+        assert!((nbhd.occurrences[1].spillover - 0.25).abs() < 1e-12);
+
+        let again = reexport(&sys);
+        assert_eq!(occurrence_data(&again), occurrence_data(&sys));
+        assert_eq!(all_ids(&again), all_ids(&sys));
+        assert_eq!(again.episodes[0].neighborhoods[0].epoch, 4);
+    }
+
+    #[test]
+    fn test_rejects_other_major_version() {
+        let json = V0_8.replacen("\"0.8.0\"", "\"1.0.0\"", 1);
+        let err = import_error(&json);
+        assert!(err.contains("1.0.0") && err.contains("0.7.2"), "{err}");
+        assert!(count_export_episodes(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_late_version_cannot_migrate() {
+        // Value sorts keys, putting "version" after "system"
+        let sorted = serde_json::from_str::<serde_json::Value>(V0_6)
+            .unwrap()
+            .to_string();
+        let err = import_error(&sorted);
+        assert!(err.contains("move it to the top"), "{err}");
+    }
 }
@@ -0,0 +1,79 @@
+//! Undo journal for speculative queries.
+//!
+//! A query activates, drifts, and couples the occurrences it touches.
+//! `DAESystem::begin_speculation` starts a [`Journal`], and from then on
+//! every occurrence handed out by `get_occurrence_mut` has its state
+//! recorded the first time, before the caller can change it.
+//! `DAESystem::rollback` writes those states back; `DAESystem::commit`
+//! drops them. The journal grows with the occurrences a query touches,
+//! not with the size of the system, so a what-if query costs about as
+//! much as a real one.
+//!
+//! Entries are addressed by [`OccurrenceRef`], so the episode layout must
+//! not change while speculating: episodes may be appended, but nothing
+//! may be removed or reordered until the journal is rolled back or
+//! committed.
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::occurrence::Occurrence;
+use crate::phasor::DaemonPhasor;
+use crate::quaternion::Quaternion;
+use crate::system::OccurrenceRef;
+
+/// The mutable state of one occurrence, as it was before speculation
+/// first touched it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Saved {
+    id: Uuid,
+    position: Quaternion,
+    phasor: DaemonPhasor,
+    activation_count: u32,
+    drift: f64,
+    spillover: f64,
+}
+
+/// Prior states of the occurrences changed since speculation began.
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    saved: HashMap<OccurrenceRef, Saved>,
+}
+
+impl Journal {
+    /// Record `occ` as the prior state of `r`, unless one is recorded
+    /// already: the first state seen is the one a rollback restores.
+    pub(crate) fn record(&mut self, r: OccurrenceRef, occ: &Occurrence) {
+        self.saved.entry(r).or_insert(Saved {
+            id: occ.id,
+            position: occ.position,
+            phasor: occ.phasor,
+            activation_count: occ.activation_count,
+            drift: occ.drift,
+            spillover: occ.spillover,
+        });
+    }
+
+    /// Occurrences recorded.
+    pub(crate) fn len(&self) -> usize {
+        self.saved.len()
+    }
+
+    /// Every recorded occurrence with its prior state.
+    pub(crate) fn into_entries(self) -> impl Iterator<Item = (OccurrenceRef, Saved)> {
+        self.saved.into_iter()
+    }
+}
+
+impl Saved {
+    /// Put `occ` back the way it was recorded.
+    pub(crate) fn restore(self, occ: &mut Occurrence) {
+        debug_assert_eq!(occ.id, self.id, "episode layout changed while speculating");
+        occ.position = self.position;
+        occ.phasor = self.phasor;
+        occ.activation_count = self.activation_count;
+        occ.drift = self.drift;
+        occ.spillover = self.spillover;
+    }
+}
@@ -0,0 +1,123 @@
+//! Spelling tolerance for query words.
+//!
+//! A typo in a query ("kubernets") activates nothing, so recall silently
+//! loses what is often the most specific word in the query. With
+//! [`QueryOptions::spelling_tolerance`](crate::query::QueryOptions) set, a
+//! query word that matches no stored word is corrected to the closest
+//! stored word within [`max_edits`] edits. The corrected word activates as
+//! usual, but its IDF weight is scaled by [`CORRECTION_DISCOUNT`] when
+//! scoring, so a guess never outranks a word the user actually typed.
+
+use std::fmt;
+
+/// Weight multiplier for a word reached through a correction.
+pub const CORRECTION_DISCOUNT: f64 = 0.5;
+
+/// Query words shorter than this are never corrected. One edit turns most
+/// short words into other real words ("cat" → "car").
+pub const MIN_CORRECTABLE_LEN: usize = 4;
+
+/// A query word replaced by the closest stored word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// The word as typed (lowercased).
+    pub typed: String,
+    /// The stored word it was corrected to.
+    pub word: String,
+    /// Edit distance between the two.
+    pub edits: usize,
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.typed, self.word)
+    }
+}
+
+/// Most edits tolerated for a word of `len` characters: 1, or 2 from eight
+/// characters on.
+#[must_use]
+pub fn max_edits(len: usize) -> usize {
+    if len >= 8 { 2 } else { 1 }
+}
+
+/// Whether `token` is a candidate for correction: long enough, and purely
+/// alphabetic so version numbers and identifiers like `v2` or `2024` are
+/// never "corrected" into their neighbors.
+#[must_use]
+pub fn is_correctable(token: &str) -> bool {
+    token.chars().count() >= MIN_CORRECTABLE_LEN && token.chars().all(char::is_alphabetic)
+}
+
+/// Damerau-Levenshtein distance (optimal string alignment: insertions,
+/// deletions, substitutions, and adjacent transpositions) between `a` and
+/// `b` in characters, or `None` once it exceeds `limit`.
+#[must_use]
+pub fn edit_distance_within(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    // Rows i-2, i-1, and i of the DP table over prefixes of `a`
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        let mut row_min = cur[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(before[j - 2] + 1);
+            }
+            cur[j] = d;
+            row_min = row_min.min(d);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= limit).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_each_edit_kind_once() {
+        assert_eq!(edit_distance_within("kubernets", "kubernetes", 2), Some(1));
+        assert_eq!(edit_distance_within("sqlite", "sqlitr", 1), Some(1));
+        assert_eq!(edit_distance_within("recieve", "receive", 1), Some(1));
+        assert_eq!(edit_distance_within("tokio", "tokio", 1), Some(0));
+        assert_eq!(
+            edit_distance_within("intreference", "interference", 2),
+            Some(1)
+        );
+        assert_eq!(edit_distance_within("kuramoto", "kuranmoot", 2), Some(2));
+    }
+
+    #[test]
+    fn edit_distance_stops_past_the_limit() {
+        assert_eq!(edit_distance_within("banana", "bandana", 0), None);
+        assert_eq!(edit_distance_within("quaternion", "question", 2), None);
+        assert_eq!(edit_distance_within("rust", "rusty crab", 2), None);
+    }
+
+    #[test]
+    fn short_and_numeric_tokens_are_not_correctable() {
+        assert!(is_correctable("drift"));
+        assert!(!is_correctable("cat"));
+        assert!(!is_correctable("2024"));
+        assert!(!is_correctable("http2"));
+        assert_eq!(max_edits(7), 1);
+        assert_eq!(max_edits(8), 2);
+    }
+}
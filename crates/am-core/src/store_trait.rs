@@ -1,18 +1,143 @@
 use uuid::Uuid;
 
 use crate::{
-    activation_stats::ActivationStats, episode::Episode, neighborhood::Neighborhood,
-    phasor::DaemonPhasor, quaternion::Quaternion, system::DAESystem,
+    activation_stats::ActivationStats,
+    episode::Episode,
+    neighborhood::Neighborhood,
+    phasor::DaemonPhasor,
+    quaternion::Quaternion,
+    query::fold_activation_deltas,
+    system::{DAESystem, GcDetail},
 };
 
+/// One user/assistant exchange drained from the conversation buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferedExchange {
+    pub user: String,
+    pub assistant: String,
+    /// ISO-8601 UTC time the exchange was buffered.
+    pub buffered_at: String,
+}
+
+/// What kind of change to memory an audit log entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditKind {
+    /// Conscious memories marked salient.
+    Salient,
+    /// A document ingested as an episode.
+    Ingest,
+    /// Buffered conversation flushed into an episode.
+    Buffer,
+    /// Feedback boosting or demoting recalled neighborhoods.
+    Feedback,
+    /// An episode, conscious memory, or term deleted on request.
+    Forget,
+    /// Cold occurrences evicted, or episodes archived, by GC.
+    Gc,
+    /// State replaced or merged from an export.
+    Import,
+    /// Rows a forget or GC deleted, put back from the undo stack.
+    Undo,
+}
+
+impl AuditKind {
+    pub const ALL: [Self; 8] = [
+        Self::Salient,
+        Self::Ingest,
+        Self::Buffer,
+        Self::Feedback,
+        Self::Forget,
+        Self::Gc,
+        Self::Import,
+        Self::Undo,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Salient => "salient",
+            Self::Ingest => "ingest",
+            Self::Buffer => "buffer",
+            Self::Feedback => "feedback",
+            Self::Forget => "forget",
+            Self::Gc => "gc",
+            Self::Import => "import",
+            Self::Undo => "undo",
+        }
+    }
+
+    /// The kind named `name`, as [`Self::as_str`] spells it.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+}
+
+/// One change to memory, for the store's append-only audit log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub kind: AuditKind,
+    /// How many things changed: neighborhoods stored, occurrences removed,
+    /// episodes imported, depending on the kind.
+    pub count: u64,
+    /// Project current when the change was made.
+    pub project: Option<String>,
+    /// Model or agent that made the change.
+    pub actor: Option<String>,
+    /// Short human-readable summary, e.g. the episode name.
+    pub detail: String,
+}
+
+impl AuditEvent {
+    #[must_use]
+    pub fn new(kind: AuditKind, count: u64) -> Self {
+        Self {
+            kind,
+            count,
+            project: None,
+            actor: None,
+            detail: String::new(),
+        }
+    }
+
+    /// Stamp the event with the system's current project and actor.
+    #[must_use]
+    pub fn with_context(mut self, system: &DAESystem) -> Self {
+        self.project = system.project().map(str::to_owned);
+        self.actor = system.actor().map(str::to_owned);
+        self
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+}
+
+/// Broad class of a store failure, for callers that report errors without
+/// knowing the concrete store error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreErrorKind {
+    /// Another connection holds the lock; retrying may succeed.
+    Busy,
+    /// Stored data failed validation.
+    Corrupt,
+    /// The filesystem under the store failed.
+    Io,
+    /// Anything else.
+    Other,
+}
+
 /// Hexagonal port for DAE persistence.
 ///
 /// Defines the storage surface required by `AmServer` (MCP tool handlers).
 /// am-core owns the trait (port); am-store provides the adapter (`BrainStore`).
 ///
-/// Sync signatures: rusqlite is synchronous and a single-client stdio server
-/// gains nothing from async wrappers. If a future HTTP adapter needs async,
-/// `spawn_blocking` wraps at that boundary.
+/// Sync signatures: rusqlite is synchronous. `AmServer` runs every call on
+/// a dedicated store thread (`am_server::AsyncStore`), which is where the
+/// async boundary sits.
 ///
 /// Scope: MCP server operations only. CLI-only helpers (`forget_*`,
 /// `import_json_file`, `export_json_file`, `mark_salient`) stay on concrete
@@ -21,12 +146,64 @@ pub trait AmStore {
     /// Error type for fallible operations.
     type Error: std::error::Error + Send + Sync + 'static;
 
+    /// Classify `error` so callers can tell a transient failure (a locked
+    /// database) from a permanent one. Stores without a finer taxonomy
+    /// report [`StoreErrorKind::Other`].
+    fn error_kind(error: &Self::Error) -> StoreErrorKind {
+        let _ = error;
+        StoreErrorKind::Other
+    }
+
     /// Load the full `DAESystem` from persistence.
     ///
     /// # Errors
     /// Returns `Self::Error` if the underlying storage is unreachable or data is corrupt.
     fn load_system(&self) -> Result<DAESystem, Self::Error>;
 
+    /// Load archived episodes, which `load_system` leaves out, so a single
+    /// query can opt into deep recall. Stores without archival have none.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the underlying storage is unreachable or data is corrupt.
+    fn load_archived_episodes(&self) -> Result<Vec<Episode>, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    /// Load episodes moved out of the active store into epoch files, which
+    /// `load_system` also leaves out, for federated recall. Stores without
+    /// epochs have none.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the underlying storage is unreachable or data is corrupt.
+    fn load_epoch_episodes(&self) -> Result<Vec<Episode>, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    /// Whether any epoch files are registered, so a query can skip the
+    /// federated fallback cheaply when there are none.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the underlying storage is unreachable.
+    fn has_epochs(&self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    /// Run the writes in `write` and append `event` to the audit log as
+    /// one transaction, so the event is recorded exactly when the writes
+    /// land. `write` should only write to this store; it runs once.
+    /// Stores without an audit log just run it.
+    ///
+    /// # Errors
+    /// Returns the first error from `write`, or `Self::Error` if the audit
+    /// row or the commit fails. Nothing is written either way.
+    fn audited<T>(
+        &self,
+        _event: &AuditEvent,
+        write: impl FnOnce() -> Result<T, Self::Error>,
+    ) -> Result<T, Self::Error> {
+        write()
+    }
+
     /// Persist a full `DAESystem` (DELETE + reinsert).
     ///
     /// Reserved for operations that replace the entire system state:
@@ -54,11 +231,21 @@ pub trait AmStore {
         neighborhood: &Neighborhood,
     ) -> Result<(), Self::Error>;
 
-    /// Increment activation counts for a batch of occurrences.
+    /// Add per-occurrence deltas to activation counts, atomically: either
+    /// every delta lands or none does.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the batch update fails.
+    fn increment_activations(&self, deltas: &[(Uuid, u32)]) -> Result<(), Self::Error>;
+
+    /// Increment activation counts for a batch of occurrences. Repeated IDs
+    /// are folded into one delta each.
     ///
     /// # Errors
     /// Returns `Self::Error` if the batch update fails.
-    fn batch_increment_activation(&self, ids: &[Uuid]) -> Result<(), Self::Error>;
+    fn batch_increment_activation(&self, ids: &[Uuid]) -> Result<(), Self::Error> {
+        self.increment_activations(&fold_activation_deltas(ids))
+    }
 
     /// Set activation counts to absolute values for a batch of occurrences.
     ///
@@ -66,21 +253,42 @@ pub trait AmStore {
     /// Returns `Self::Error` if the batch update fails.
     fn batch_set_activation_counts(&self, batch: &[(Uuid, u32)]) -> Result<(), Self::Error>;
 
-    /// Persist position and phasor updates for a batch of occurrences.
+    /// Set activation counts and spillover residues to absolute values for
+    /// a batch of occurrences credited by activation spillover.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the batch update fails.
+    fn save_activation_spillover(&self, batch: &[(Uuid, u32, f64)]) -> Result<(), Self::Error>;
+
+    /// Persist position, phasor, and accumulated drift updates for a batch
+    /// of occurrences.
     ///
     /// # Errors
     /// Returns `Self::Error` if the batch update fails.
     fn save_occurrence_positions(
         &self,
-        batch: &[(Uuid, Quaternion, DaemonPhasor)],
+        batch: &[(Uuid, Quaternion, DaemonPhasor, f64)],
     ) -> Result<(), Self::Error>;
 
+    /// Set feedback scores to absolute values for a batch of neighborhoods.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the batch update fails.
+    fn save_feedback_scores(&self, batch: &[(Uuid, f64)]) -> Result<(), Self::Error>;
+
     /// Mark a neighborhood as superseded by another.
     ///
     /// # Errors
     /// Returns `Self::Error` if the old neighborhood ID is not found.
     fn mark_superseded(&self, old_id: Uuid, new_id: Uuid) -> Result<(), Self::Error>;
 
+    /// Replace a neighborhood's `created_at`, as when a repeated salient
+    /// mark reinforces it.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the neighborhood ID is not found.
+    fn restamp_neighborhood(&self, id: Uuid, created_at: &str) -> Result<(), Self::Error>;
+
     /// Append a user/assistant exchange to the conversation buffer.
     /// Returns the new buffer size.
     ///
@@ -92,7 +300,7 @@ pub trait AmStore {
     ///
     /// # Errors
     /// Returns `Self::Error` if the read or delete transaction fails.
-    fn drain_buffer(&self) -> Result<Vec<(String, String)>, Self::Error>;
+    fn drain_buffer(&self) -> Result<Vec<BufferedExchange>, Self::Error>;
 
     /// Number of exchanges currently in the conversation buffer.
     ///
@@ -106,9 +314,35 @@ pub trait AmStore {
     /// Returns `Self::Error` if the aggregation query fails.
     fn activation_distribution(&self) -> Result<ActivationStats, Self::Error>;
 
+    /// Number of stored occurrences. Cheaper than `load_system`, so a
+    /// server can size a load before running it.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the count query fails.
+    fn occurrence_count(&self) -> Result<u64, Self::Error> {
+        Ok(self.activation_distribution()?.total)
+    }
+
+    /// Evict occurrences activated at most `_activation_floor` times, under
+    /// the store's retention policy, and report what was removed so a
+    /// caller holding a loaded system can pass it to
+    /// [`DAESystem::apply_gc`]. Stores without GC remove nothing.
+    ///
+    /// # Errors
+    /// Returns `Self::Error` if the delete transaction fails.
+    fn gc_pass(&self, _activation_floor: u32) -> Result<GcDetail, Self::Error> {
+        Ok(GcDetail::default())
+    }
+
     /// Database file size in bytes (0 for in-memory stores).
     fn db_size(&self) -> u64;
 
+    /// Records repaired by load-time invariant checks since the store was
+    /// opened (non-unit quaternions, phasor angles outside `[0, τ)`).
+    fn repaired_records(&self) -> u64 {
+        0
+    }
+
     /// Verify the connection is still usable.
     ///
     /// # Errors
@@ -1,12 +1,26 @@
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
 
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::compose::{ComposeConfig, RerankStage};
+use crate::constants::{
+    DEFAULT_MAX_WORD_ACTIVATIONS, DEFAULT_PLAN_VERBS, DEFAULT_PROJECT_DISCOUNT,
+    DEFAULT_RESPONSE_DRIFT_SCALE, DEFAULT_SALIENT_CHUNK_CHARS, DEFAULT_SALIENT_DEDUP_THRESHOLD,
+    DEFAULT_WORD_WEIGHT_CEILING, DEFAULT_WORD_WEIGHT_FLOOR, EPISODE_LABEL_MIN_WORD_LEN,
+    EPISODE_LABEL_WORDS, MAX_WORD_BIAS,
+};
 use crate::episode::Episode;
-use crate::neighborhood::{Neighborhood, NeighborhoodType};
+use crate::neighborhood::{DECISION_TYPES, Neighborhood, NeighborhoodType};
+use crate::speculation::Journal;
+use crate::spelling::{Correction, edit_distance_within, is_correctable, max_edits};
+use crate::time::now_iso8601;
 use crate::tokenizer::tokenize;
+use crate::word_stats::WordStats;
 
 /// Identifies which episode an occurrence or neighborhood belongs to.
 ///
@@ -63,6 +77,70 @@ impl NeighborhoodRef {
 pub struct ActivationResult {
     pub subconscious: Vec<OccurrenceRef>,
     pub conscious: Vec<OccurrenceRef>,
+    /// Subconscious occurrences left unactivated by
+    /// [`max_word_activations`](DAESystem::max_word_activations).
+    pub capped: usize,
+}
+
+/// Rows a store-side GC pass removed, so a process holding a loaded
+/// system can drop them too (see [`DAESystem::apply_gc`]). Without that,
+/// the next full save writes the evicted rows straight back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcDetail {
+    /// Occurrences evicted below the activation floor.
+    pub occurrences: Vec<Uuid>,
+    /// Neighborhoods deleted once they had no occurrences left.
+    pub neighborhoods: Vec<Uuid>,
+    /// Episodes deleted once empty, or archived.
+    pub episodes: Vec<Uuid>,
+}
+
+impl GcDetail {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty() && self.neighborhoods.is_empty() && self.episodes.is_empty()
+    }
+}
+
+/// Clamps and drift exclusions applied to IDF word weights.
+///
+/// Raw IDF is `1 / df` where `df` is the number of neighborhoods containing
+/// the word. `get_word_weight` clamps that into `[floor, ceiling]`. With
+/// `skip_hapax_drift`, words found in exactly one neighborhood still
+/// activate but are left out of drift, so a one-off typo cannot pull
+/// neighbors toward it. `response_drift_scale` is the drift scale
+/// `activate_response` uses when the caller does not pass one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WordWeightPolicy {
+    pub floor: f64,
+    pub ceiling: f64,
+    pub skip_hapax_drift: bool,
+    pub response_drift_scale: f64,
+}
+
+impl WordWeightPolicy {
+    /// IDF weight for a word found in `neighborhood_count` neighborhoods.
+    /// Unknown words (count 0) get the maximum raw weight of 1.0.
+    #[must_use]
+    pub fn weight_for(&self, neighborhood_count: usize) -> f64 {
+        let raw = if neighborhood_count == 0 {
+            1.0
+        } else {
+            1.0 / neighborhood_count as f64
+        };
+        raw.clamp(self.floor, self.ceiling)
+    }
+}
+
+impl Default for WordWeightPolicy {
+    fn default() -> Self {
+        Self {
+            floor: DEFAULT_WORD_WEIGHT_FLOOR,
+            ceiling: DEFAULT_WORD_WEIGHT_CEILING,
+            skip_hapax_drift: false,
+            response_drift_scale: DEFAULT_RESPONSE_DRIFT_SCALE,
+        }
+    }
 }
 
 /// Top-level DAE system container with lazy-rebuilt indexes.
@@ -78,36 +156,100 @@ pub struct ActivationResult {
 /// stored in the `episodes` Vec. This enum makes the branching explicit and
 /// eliminates sentinel-value bugs.
 ///
-/// # Public API (20 methods, as of v0.1.15)
+/// # Public API (62 methods, as of v0.2.2)
 ///
-/// **Read-only queries** (7):
+/// **Read-only queries** (12):
 /// - `n()` - total occurrence count across both manifolds
+/// - `word_stats()` - per-word neighborhood counts behind IDF weights
 /// - `total_neighborhoods()` - total neighborhood count
 /// - `get_occurrence(ref)` - immutable occurrence by ref
 /// - `get_neighborhood(ref)` - immutable neighborhood by ref
 /// - `get_neighborhood_for_occurrence(ref)` - neighborhood containing an occurrence
 /// - `get_episode_for_occurrence(ref)` - episode containing an occurrence
+/// - `neighborhood_actor(ref)` - who created a neighborhood, if known
+/// - `decisions(keyword)` - conscious decisions, newest first
+/// - `conscious_group(group_id)` - the chunks of one long salient text
+/// - `find_conscious_duplicate(text, type, project)` - live memory a salient mark repeats
 /// - `get_occurrence_mut(ref)` - mutable occurrence access (read-write but listed
 ///   here because it returns a reference, does not drive a mutation workflow)
 ///
-/// **Index-dependent lookups** (4, trigger lazy rebuild):
-/// - `get_word_weight(word)` - IDF weight for a word
+/// **Index-dependent lookups** (8, trigger lazy rebuild):
+/// - `get_word_weight(word)` - clamped IDF weight for a word, times its bias
+/// - `idf_weight(word)` - the clamped IDF weight alone, for drift
+/// - `is_hapax(word)` - whether a word appears in exactly one neighborhood
+/// - `distinctive_words(idx, limit)` - top tf-idf words of an episode
 /// - `get_word_occurrences(word)` - all occurrence refs for a word
+/// - `capped_word_occurrences(word)` - the refs a query activates for a word
 /// - `get_neighborhood_ref(id)` - neighborhood ref by UUID
 /// - `get_episode_ref_for_neighborhood(id)` - episode ref for a neighborhood
 ///
-/// **Mutating writes** (6):
+/// **Index reads through `&self`** (2, require `rebuild_indexes()` since the
+/// last `mark_dirty()`, so threads can share the system):
+/// - `word_weight(word)` - same as `get_word_weight`
+/// - `neighborhood_ref(id)` - same as `get_neighborhood_ref`
+///
+/// **Mutating writes** (14):
 /// - `activate_word(word)` - increment activation across both manifolds
+/// - `spill_activation(refs)` - credit the neighborhood siblings of activated refs
 /// - `add_to_conscious(text, rng)` - add insight to conscious episode
 /// - `add_to_conscious_typed(text, type, rng)` - add typed entry to conscious
+/// - `add_to_conscious_for(text, type, project, rng)` - same, tagged for a project
+/// - `add_conscious_group(chunks, type, project, rng)` - add chunks sharing a group
+/// - `reinforce_conscious(id)` - activate and restamp a memory marked again
 /// - `add_episode(episode)` - add subconscious episode with epoch assignment
+/// - `label_episode(idx, base)` - rename an episode from its distinctive words
 /// - `mark_superseded(old_id, new_id)` - mark neighborhood as superseded
-/// - `mark_dirty()` - flag indexes for rebuild
+/// - `forget_episode(id)` - drop a subconscious episode, as the store did
+/// - `forget_conscious(id)` - drop a conscious neighborhood, as the store did
+/// - `forget_term(term)` - drop a word everywhere, as the store did
+/// - `mark_dirty()` - flag indexes and word stats for rebuild
+///
+/// **Configuration** (28):
+/// - `word_weight_policy()` - current IDF clamps and hapax drift setting
+/// - `set_word_weight_policy(policy)` - replace them
+/// - `compose_config()` - recall candidate cutoffs
+/// - `set_compose_config(config)` - replace them
+/// - `rerank_stage()` - optional reranker blended over top candidates
+/// - `set_rerank_stage(stage)` - install or remove it
+/// - `plan_verbs()` - query words that boost Plan neighborhoods
+/// - `set_plan_verbs(verbs)` - replace them
+/// - `is_plan_query(tokens)` - whether a tokenized query asks for a plan
+/// - `actor()` - model or agent stamped on new memories
+/// - `set_actor(actor)` - replace it
+/// - `actor_filter()` - restrict recall to one actor's memories
+/// - `set_actor_filter(actor)` - replace it
+/// - `dedup_neighborhoods()` - reuse stored neighborhoods for repeated text
+/// - `set_dedup_neighborhoods(dedup)` - turn it on or off
+/// - `activation_spillover()` - credit an activation passes to its siblings
+/// - `set_activation_spillover(credit)` - replace it
+/// - `max_word_activations()` - subconscious occurrences one query word activates
+/// - `set_max_word_activations(cap)` - replace it
+/// - `salient_chunk_chars()` - length above which salient text is chunked
+/// - `set_salient_chunk_chars(chars)` - replace it
+/// - `salient_dedup_threshold()` - similarity at which a salient mark is a repeat
+/// - `set_salient_dedup_threshold(threshold)` - replace it
+/// - `project()` - project recall favours and new memories may be tagged with
+/// - `set_project(project)` - replace it
+/// - `project_memory_count(project)` - conscious memories tagged for a project
+/// - `project_discount()` - weight of memories tagged for another project
+/// - `set_project_discount(discount)` - replace it
+/// - `set_tag_conscious_project(tag)` - stamp the project on new memories
+/// - `word_bias(word)` - multiplier on a word's scoring weight, 1.0 when unset
+/// - `word_biases()` - every word with a bias
+/// - `set_word_bias(word, multiplier)` - boost or mute a word
+/// - `clear_word_bias(word)` - back to its plain IDF weight
 ///
-/// **Lifecycle** (3):
+/// **Speculation** (4, undo journal over `get_occurrence_mut`):
+/// - `begin_speculation()` - start recording occurrence changes
+/// - `is_speculating()` - whether changes are being recorded
+/// - `rollback()` - restore recorded occurrences and stop recording
+/// - `commit()` - keep the changes and stop recording
+///
+/// **Lifecycle** (4):
 /// - `new(agent_name)` - constructor
 /// - `rebuild_indexes()` - full index rebuild from episode data
 /// - `sync_next_epoch()` - align epoch counter after load
+/// - `sync_seq(floor)` - align insertion-order counter after load
 ///
 /// # Decomposition threshold
 ///
@@ -147,17 +289,99 @@ pub struct DAESystem {
     /// current value, then it increments.
     #[serde(default)]
     pub next_epoch: u64,
+    /// Highest neighborhood `seq` handed out so far.
+    #[serde(skip)]
+    last_seq: u64,
 
     #[serde(skip)]
     word_neighborhood_index: HashMap<String, HashSet<Uuid>>,
     #[serde(skip)]
     word_occurrence_index: HashMap<String, Vec<OccurrenceRef>>,
+    /// Stored words bucketed by character count, for spelling correction.
+    #[serde(skip)]
+    words_by_length: HashMap<usize, Vec<String>>,
     #[serde(skip)]
     neighborhood_index: HashMap<Uuid, NeighborhoodRef>,
     #[serde(skip)]
     neighborhood_episode_index: HashMap<Uuid, EpisodeRef>,
     #[serde(skip)]
     index_dirty: bool,
+    /// Document frequencies for IDF, kept current as neighborhoods are
+    /// added and removed rather than rebuilt with the indexes.
+    #[serde(skip)]
+    word_stats: WordStats,
+    #[serde(skip)]
+    word_weight_policy: WordWeightPolicy,
+    #[serde(skip)]
+    compose_config: ComposeConfig,
+    /// Optional reranking pass over the top candidates; `None` keeps the
+    /// purely geometric order.
+    #[serde(skip)]
+    rerank_stage: Option<RerankStage>,
+    /// Planning verbs that trigger the Plan boost; `None` uses
+    /// `DEFAULT_PLAN_VERBS`.
+    #[serde(skip)]
+    plan_verbs: Option<Vec<String>>,
+    /// Stamped on new episodes and conscious neighborhoods.
+    #[serde(skip)]
+    actor: Option<String>,
+    /// When set, recall only scores memories created by this actor.
+    #[serde(skip)]
+    actor_filter: Option<String>,
+    /// Reuse stored neighborhoods instead of adding identical chunks.
+    #[serde(skip)]
+    dedup_neighborhoods: bool,
+    /// Fraction of an activation credited to the other occurrences of its
+    /// neighborhood. 0.0 turns spillover off.
+    #[serde(skip)]
+    activation_spillover: f64,
+    /// Most subconscious occurrences one query word activates. 0 means no
+    /// cap.
+    #[serde(skip)]
+    max_word_activations: usize,
+    /// Salient text longer than this many characters is stored as grouped
+    /// chunks. 0 stores it whole.
+    #[serde(skip)]
+    salient_chunk_chars: usize,
+    /// Token-set similarity at or above which a salient mark reinforces an
+    /// existing conscious memory instead of adding one. 0 never merges.
+    #[serde(skip)]
+    salient_dedup_threshold: f64,
+    /// Lowercase words that activate and score normally but never drift
+    /// or take part in Kuramoto coupling.
+    #[serde(skip)]
+    drift_frozen: HashSet<String>,
+    /// Lowercase word to the multiplier applied to its IDF weight, in
+    /// `[0, MAX_WORD_BIAS]`.
+    #[serde(skip)]
+    word_bias: HashMap<String, f64>,
+    /// Project recall is running in, lowercase.
+    #[serde(skip)]
+    project: Option<String>,
+    /// Score factor for conscious memories tagged for another project.
+    #[serde(skip)]
+    project_discount: f64,
+    /// Stamp `project` on conscious neighborhoods added from now on.
+    #[serde(skip)]
+    tag_conscious_project: bool,
+    /// Content hash to (episode index, neighborhood index) of the first
+    /// subconscious neighborhood with that text. Built on first use by
+    /// `add_episode` and kept current as episodes are added; hits are
+    /// re-checked against the episode, so removals only cost a rebuild.
+    #[serde(skip)]
+    content_index: Option<HashMap<u64, (usize, usize)>>,
+    /// Prior occurrence states while a speculative query runs; `None`
+    /// when not speculating.
+    #[serde(skip)]
+    speculation: Option<Journal>,
+}
+
+/// A project name as memories are tagged with it: trimmed and lowercased,
+/// `None` when blank.
+#[must_use]
+pub fn normalize_project(name: &str) -> Option<String> {
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_lowercase())
 }
 
 impl DAESystem {
@@ -168,11 +392,32 @@ impl DAESystem {
             conscious_episode: Episode::new_conscious(),
             agent_name: agent_name.to_string(),
             next_epoch: 0,
+            last_seq: 0,
             word_neighborhood_index: HashMap::new(),
             word_occurrence_index: HashMap::new(),
+            words_by_length: HashMap::new(),
             neighborhood_index: HashMap::new(),
             neighborhood_episode_index: HashMap::new(),
             index_dirty: true,
+            word_stats: WordStats::default(),
+            word_weight_policy: WordWeightPolicy::default(),
+            compose_config: ComposeConfig::default(),
+            rerank_stage: None,
+            plan_verbs: None,
+            actor: None,
+            actor_filter: None,
+            dedup_neighborhoods: false,
+            activation_spillover: 0.0,
+            max_word_activations: DEFAULT_MAX_WORD_ACTIVATIONS,
+            salient_chunk_chars: DEFAULT_SALIENT_CHUNK_CHARS,
+            salient_dedup_threshold: DEFAULT_SALIENT_DEDUP_THRESHOLD,
+            drift_frozen: HashSet::new(),
+            word_bias: HashMap::new(),
+            project: None,
+            project_discount: DEFAULT_PROJECT_DISCOUNT,
+            tag_conscious_project: false,
+            content_index: None,
+            speculation: None,
         }
     }
 
@@ -189,12 +434,14 @@ impl DAESystem {
 
     /// Rebuild all indexes from scratch. Skips if not dirty.
     pub fn rebuild_indexes(&mut self) {
+        self.ensure_word_stats();
         if !self.index_dirty {
             return;
         }
 
         self.word_neighborhood_index.clear();
         self.word_occurrence_index.clear();
+        self.words_by_length.clear();
         self.neighborhood_index.clear();
         self.neighborhood_episode_index.clear();
 
@@ -255,6 +502,24 @@ impl DAESystem {
             }
         }
 
+        for word in self.word_neighborhood_index.keys() {
+            self.words_by_length
+                .entry(word.chars().count())
+                .or_default()
+                .push(word.clone());
+        }
+        for bucket in self.words_by_length.values_mut() {
+            bucket.sort_unstable();
+        }
+
+        debug_assert!(
+            self.word_stats.len() == self.word_neighborhood_index.len()
+                && self
+                    .word_neighborhood_index
+                    .iter()
+                    .all(|(word, ids)| self.word_stats.document_frequency(word) == ids.len()),
+            "incremental word stats differ from the rebuilt index"
+        );
         self.index_dirty = false;
     }
 
@@ -265,30 +530,362 @@ impl DAESystem {
         }
     }
 
-    /// IDF weight: 1.0 / number of neighborhoods containing the word.
-    pub fn get_word_weight(&mut self, word: &str) -> f64 {
-        self.ensure_indexes();
-        let word_lower = word.to_lowercase();
-        match self.word_neighborhood_index.get(&word_lower) {
-            Some(neighborhoods) if !neighborhoods.is_empty() => 1.0 / neighborhoods.len() as f64,
-            _ => 1.0,
+    /// Recount word stats if an edit they did not see made them stale.
+    fn ensure_word_stats(&mut self) {
+        if self.word_stats.is_stale() {
+            let neighborhoods = self
+                .episodes
+                .iter()
+                .chain(std::iter::once(&self.conscious_episode))
+                .flat_map(|e| &e.neighborhoods);
+            self.word_stats.rebuild(neighborhoods);
         }
     }
 
-    /// Activate a word across both manifolds. Returns refs split by manifold.
-    pub fn activate_word(&mut self, word: &str) -> ActivationResult {
-        self.ensure_indexes();
-        let word_lower = word.to_lowercase();
+    /// Per-word neighborhood counts behind IDF weights. Stale after
+    /// `mark_dirty` until the next weight lookup or `rebuild_indexes`.
+    #[must_use]
+    pub fn word_stats(&self) -> &WordStats {
+        &self.word_stats
+    }
+
+    /// Scoring weight of a word: IDF (1.0 / number of neighborhoods
+    /// containing it) clamped to the active `WordWeightPolicy`, then
+    /// multiplied by its [`word_bias`](Self::word_bias).
+    pub fn get_word_weight(&mut self, word: &str) -> f64 {
+        self.ensure_word_stats();
+        self.word_weight(word)
+    }
+
+    /// `get_word_weight` without the lazy recount, for readers sharing the
+    /// system across threads. Word stats must be current, as they are
+    /// after `rebuild_indexes`.
+    #[must_use]
+    pub fn word_weight(&self, word: &str) -> f64 {
+        debug_assert!(
+            !self.word_stats.is_stale(),
+            "word_weight on stale word stats"
+        );
+        let word = word.to_lowercase();
+        let count = self.word_stats.document_frequency(&word);
+        let bias = self.word_bias.get(&word).copied().unwrap_or(1.0);
+        self.word_weight_policy.weight_for(count) * bias
+    }
 
-        let refs = match self.word_occurrence_index.get(&word_lower) {
-            Some(refs) => refs.clone(),
-            None => {
-                return ActivationResult {
-                    subconscious: vec![],
-                    conscious: vec![],
+    /// Clamped IDF weight without the word's bias. Drift and phase
+    /// coupling size their steps by it, so a bias changes what a word
+    /// scores, not how far its occurrences move.
+    pub fn idf_weight(&mut self, word: &str) -> f64 {
+        self.ensure_word_stats();
+        let count = self.word_stats.document_frequency(&word.to_lowercase());
+        self.word_weight_policy.weight_for(count)
+    }
+
+    /// Closest stored word to `token` within `spelling::max_edits` edits,
+    /// for a query word that matches nothing. Ties go to fewer edits, then
+    /// the word in more neighborhoods, then alphabetical order. `None` when
+    /// the token is stored as-is or is not correctable.
+    pub fn closest_word(&mut self, token: &str) -> Option<Correction> {
+        self.ensure_indexes();
+        let typed = token.to_lowercase();
+        if !is_correctable(&typed) || self.word_neighborhood_index.contains_key(&typed) {
+            return None;
+        }
+        let len = typed.chars().count();
+        let limit = max_edits(len);
+        let mut best: Option<(usize, Reverse<usize>, &String)> = None;
+        for bucket_len in len.saturating_sub(limit)..=len + limit {
+            for word in self.words_by_length.get(&bucket_len).into_iter().flatten() {
+                let Some(edits) = edit_distance_within(&typed, word, limit) else {
+                    continue;
                 };
+                let spread = self
+                    .word_neighborhood_index
+                    .get(word)
+                    .map_or(0, HashSet::len);
+                let key = (edits, Reverse(spread), word);
+                if best.as_ref().is_none_or(|b| key < *b) {
+                    best = Some(key);
+                }
             }
+        }
+        best.map(|(edits, _, word)| Correction {
+            typed,
+            word: word.clone(),
+            edits,
+        })
+    }
+
+    /// Whether a word appears in exactly one neighborhood.
+    pub fn is_hapax(&mut self, word: &str) -> bool {
+        self.ensure_word_stats();
+        self.word_stats.document_frequency(&word.to_lowercase()) == 1
+    }
+
+    /// Current IDF clamps and hapax drift setting.
+    #[must_use]
+    pub fn word_weight_policy(&self) -> WordWeightPolicy {
+        self.word_weight_policy
+    }
+
+    /// Replace the IDF clamps. A ceiling below the floor is raised to the
+    /// floor so `clamp` never sees an inverted range, and the response
+    /// drift scale is clamped into `[0, 1]`.
+    pub fn set_word_weight_policy(&mut self, mut policy: WordWeightPolicy) {
+        policy.ceiling = policy.ceiling.max(policy.floor);
+        policy.response_drift_scale = policy.response_drift_scale.clamp(0.0, 1.0);
+        self.word_weight_policy = policy;
+    }
+
+    /// Candidate cutoffs applied when ranking recall.
+    #[must_use]
+    pub fn compose_config(&self) -> &ComposeConfig {
+        &self.compose_config
+    }
+
+    /// Replace the candidate cutoffs.
+    pub fn set_compose_config(&mut self, config: ComposeConfig) {
+        self.compose_config = config;
+    }
+
+    /// Reranking pass applied after geometric ranking, if any.
+    #[must_use]
+    pub fn rerank_stage(&self) -> Option<&RerankStage> {
+        self.rerank_stage.as_ref()
+    }
+
+    /// Install (or with `None`, remove) the reranking pass.
+    pub fn set_rerank_stage(&mut self, stage: Option<RerankStage>) {
+        self.rerank_stage = stage;
+    }
+
+    /// Query words that mark a task-planning query.
+    #[must_use]
+    pub fn plan_verbs(&self) -> Vec<String> {
+        match &self.plan_verbs {
+            Some(verbs) => verbs.clone(),
+            None => DEFAULT_PLAN_VERBS
+                .iter()
+                .map(|v| (*v).to_string())
+                .collect(),
+        }
+    }
+
+    /// Replace the planning verbs. Matching is on whole lowercase tokens,
+    /// so list each inflection that should count.
+    pub fn set_plan_verbs(&mut self, verbs: Vec<String>) {
+        self.plan_verbs = Some(verbs.into_iter().map(|v| v.to_lowercase()).collect());
+    }
+
+    /// Whether any of `tokens` is a planning verb.
+    #[must_use]
+    pub fn is_plan_query<'a>(&self, tokens: impl IntoIterator<Item = &'a String>) -> bool {
+        let mut tokens = tokens.into_iter();
+        match &self.plan_verbs {
+            Some(verbs) => tokens.any(|t| verbs.contains(t)),
+            None => tokens.any(|t| DEFAULT_PLAN_VERBS.contains(&t.as_str())),
+        }
+    }
+
+    /// Model or agent that new memories are attributed to.
+    #[must_use]
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Set the actor stamped on episodes and conscious neighborhoods added
+    /// from now on. Existing memories keep their attribution.
+    pub fn set_actor(&mut self, actor: Option<String>) {
+        self.actor = actor;
+    }
+
+    /// Actor that recall is restricted to, if any.
+    #[must_use]
+    pub fn actor_filter(&self) -> Option<&str> {
+        self.actor_filter.as_deref()
+    }
+
+    /// Restrict recall to memories created by `actor`. Memories with no
+    /// recorded actor never match. `None` recalls from everyone.
+    pub fn set_actor_filter(&mut self, actor: Option<String>) {
+        self.actor_filter = actor;
+    }
+
+    /// Whether `add_episode` reuses stored neighborhoods for repeated text.
+    #[must_use]
+    pub fn dedup_neighborhoods(&self) -> bool {
+        self.dedup_neighborhoods
+    }
+
+    /// Turn neighborhood dedup on or off for episodes added from now on.
+    pub fn set_dedup_neighborhoods(&mut self, dedup: bool) {
+        self.dedup_neighborhoods = dedup;
+    }
+
+    /// Activation credit each sibling of an activated occurrence receives.
+    #[must_use]
+    pub fn activation_spillover(&self) -> f64 {
+        self.activation_spillover
+    }
+
+    /// Set the spillover credit, clamped to `[0, 1]`. 0.0 turns it off.
+    pub fn set_activation_spillover(&mut self, credit: f64) {
+        self.activation_spillover = if credit.is_nan() {
+            0.0
+        } else {
+            credit.clamp(0.0, 1.0)
         };
+    }
+
+    /// Most subconscious occurrences a query word activates; 0 when
+    /// uncapped.
+    #[must_use]
+    pub fn max_word_activations(&self) -> usize {
+        self.max_word_activations
+    }
+
+    /// Cap the subconscious occurrences one query word activates. 0
+    /// activates every occurrence.
+    pub fn set_max_word_activations(&mut self, cap: usize) {
+        self.max_word_activations = cap;
+    }
+
+    /// Length in characters above which salient text is split into grouped
+    /// chunks; 0 when it is always stored whole.
+    #[must_use]
+    pub fn salient_chunk_chars(&self) -> usize {
+        self.salient_chunk_chars
+    }
+
+    /// Split salient text longer than `chars` characters into grouped
+    /// chunks. 0 stores every salient text as one neighborhood.
+    pub fn set_salient_chunk_chars(&mut self, chars: usize) {
+        self.salient_chunk_chars = chars;
+    }
+
+    /// Token-set similarity at or above which a salient mark repeats an
+    /// existing conscious memory; 0 when repeats are always stored.
+    #[must_use]
+    pub fn salient_dedup_threshold(&self) -> f64 {
+        self.salient_dedup_threshold
+    }
+
+    /// Merge salient marks whose token sets are at least `threshold` alike
+    /// (Jaccard, clamped to [0, 1]) with an existing conscious memory. 0
+    /// turns merging off; 1 merges only identical word sets.
+    pub fn set_salient_dedup_threshold(&mut self, threshold: f64) {
+        self.salient_dedup_threshold = threshold.clamp(0.0, 1.0);
+    }
+
+    /// Words frozen out of drift, lowercase.
+    #[must_use]
+    pub fn drift_frozen(&self) -> &HashSet<String> {
+        &self.drift_frozen
+    }
+
+    /// Replace the words frozen out of drift and phase coupling. Frozen
+    /// words still activate and score; their occurrences just hold their
+    /// position and phase.
+    pub fn set_drift_frozen(&mut self, words: impl IntoIterator<Item = String>) {
+        self.drift_frozen = words.into_iter().map(|w| w.to_lowercase()).collect();
+    }
+
+    /// Whether `word` is frozen out of drift.
+    #[must_use]
+    pub fn is_drift_frozen(&self, word: &str) -> bool {
+        !self.drift_frozen.is_empty() && self.drift_frozen.contains(&word.to_lowercase())
+    }
+
+    /// Multiplier on `word`'s scoring weight; 1.0 when it has no bias.
+    #[must_use]
+    pub fn word_bias(&self, word: &str) -> f64 {
+        if self.word_bias.is_empty() {
+            return 1.0;
+        }
+        self.word_bias
+            .get(&word.to_lowercase())
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Every biased word (lowercase) with its multiplier.
+    #[must_use]
+    pub fn word_biases(&self) -> &HashMap<String, f64> {
+        &self.word_bias
+    }
+
+    /// Multiply `word`'s IDF weight by `multiplier` when scoring, clamped to
+    /// `[0, MAX_WORD_BIAS]`. Zero mutes the word without deleting its
+    /// occurrences. NaN leaves the bias unchanged. Returns the multiplier
+    /// stored.
+    pub fn set_word_bias(&mut self, word: &str, multiplier: f64) -> f64 {
+        if multiplier.is_nan() {
+            return self.word_bias(word);
+        }
+        let multiplier = multiplier.clamp(0.0, MAX_WORD_BIAS);
+        self.word_bias.insert(word.to_lowercase(), multiplier);
+        multiplier
+    }
+
+    /// Drop `word`'s bias. Returns whether it had one.
+    pub fn clear_word_bias(&mut self, word: &str) -> bool {
+        self.word_bias.remove(&word.to_lowercase()).is_some()
+    }
+
+    /// Project recall is running in, if known.
+    #[must_use]
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    /// Set the current project. Conscious memories tagged for a different
+    /// project score [`project_discount`](Self::project_discount) of their
+    /// weight; untagged ones, and all of them when `None`, keep full weight.
+    pub fn set_project(&mut self, project: Option<String>) {
+        self.project = project.and_then(|p| normalize_project(&p));
+    }
+
+    /// Conscious memories tagged for `project` (compared after
+    /// [`normalize_project`]).
+    #[must_use]
+    pub fn project_memory_count(&self, project: &str) -> usize {
+        let Some(project) = normalize_project(project) else {
+            return 0;
+        };
+        self.conscious_episode
+            .neighborhoods
+            .iter()
+            .filter(|n| n.project.as_deref() == Some(project.as_str()))
+            .count()
+    }
+
+    /// Score factor for conscious memories tagged for another project.
+    #[must_use]
+    pub fn project_discount(&self) -> f64 {
+        self.project_discount
+    }
+
+    /// Set the other-project discount, clamped to `(0, 1]` so those
+    /// memories never vanish. Zero, negative, or NaN restores the default.
+    pub fn set_project_discount(&mut self, discount: f64) {
+        self.project_discount = if discount > 0.0 {
+            discount.min(1.0)
+        } else {
+            DEFAULT_PROJECT_DISCOUNT
+        };
+    }
+
+    /// Stamp the current project on conscious memories added from now on.
+    pub fn set_tag_conscious_project(&mut self, tag: bool) {
+        self.tag_conscious_project = tag;
+    }
+
+    /// Activate a word across both manifolds. Returns refs split by
+    /// manifold; a word past [`max_word_activations`](Self::max_word_activations)
+    /// activates only the occurrences [`capped_word_occurrences`](Self::capped_word_occurrences)
+    /// picks.
+    pub fn activate_word(&mut self, word: &str) -> ActivationResult {
+        let (refs, capped) = self.capped_word_occurrences(word);
 
         let mut subconscious = Vec::new();
         let mut conscious = Vec::new();
@@ -308,7 +905,104 @@ impl DAESystem {
         ActivationResult {
             subconscious,
             conscious,
+            capped,
+        }
+    }
+
+    /// The occurrences of `word` a query touches, and how many it leaves
+    /// out. Conscious occurrences are always kept. When the word has more
+    /// subconscious occurrences than [`max_word_activations`](Self::max_word_activations),
+    /// three quarters of the cap go to the most activated ones and the
+    /// rest to a reservoir sample of the others, so cold memories of a hot
+    /// word can still surface. The sample is seeded by the word and its
+    /// total activation: the same state picks the same occurrences.
+    pub fn capped_word_occurrences(&mut self, word: &str) -> (Vec<OccurrenceRef>, usize) {
+        self.ensure_indexes();
+        let word_lower = word.to_lowercase();
+        let Some(refs) = self.word_occurrence_index.get(&word_lower) else {
+            return (Vec::new(), 0);
+        };
+        let cap = self.max_word_activations;
+        let subconscious = refs.iter().filter(|r| !r.is_conscious()).count();
+        if cap == 0 || subconscious <= cap {
+            return (refs.clone(), 0);
+        }
+
+        let mut kept: Vec<OccurrenceRef> = refs
+            .iter()
+            .copied()
+            .filter(OccurrenceRef::is_conscious)
+            .collect();
+        let mut ranked: Vec<(u32, OccurrenceRef)> = refs
+            .iter()
+            .filter(|r| !r.is_conscious())
+            .map(|&r| (self.get_occurrence(r).activation_count, r))
+            .collect();
+        // Stable, so ties keep index order
+        ranked.sort_by_key(|&(count, _)| Reverse(count));
+
+        let hot = cap - cap / 4;
+        kept.extend(ranked[..hot].iter().map(|&(_, r)| r));
+
+        let rest = &ranked[hot..];
+        let sample = cap - hot;
+        let mut hasher = DefaultHasher::new();
+        word_lower.hash(&mut hasher);
+        rest.iter()
+            .map(|&(count, _)| u64::from(count))
+            .sum::<u64>()
+            .hash(&mut hasher);
+        let mut rng = SmallRng::seed_from_u64(hasher.finish());
+        let mut reservoir: Vec<OccurrenceRef> = rest[..sample].iter().map(|&(_, r)| r).collect();
+        for (i, &(_, r)) in rest.iter().enumerate().skip(sample) {
+            let j = rng.random_range(0..=i);
+            if j < sample {
+                reservoir[j] = r;
+            }
+        }
+        kept.extend(reservoir);
+
+        (kept, subconscious - cap)
+    }
+
+    /// Credit the neighborhood siblings of each activated occurrence with
+    /// [`activation_spillover`](Self::activation_spillover), so the
+    /// context around a recalled word warms up with it. A ref listed
+    /// twice spills twice. Occurrences in `activated`, and siblings of
+    /// the same word, earn nothing. Returns the IDs of credited
+    /// occurrences, once each; empty when spillover is off.
+    pub fn spill_activation(&mut self, activated: &[OccurrenceRef]) -> Vec<Uuid> {
+        let credit = self.activation_spillover;
+        if credit <= 0.0 || activated.is_empty() {
+            return Vec::new();
         }
+        let activated_set: HashSet<OccurrenceRef> = activated.iter().copied().collect();
+        let mut credited: Vec<Uuid> = Vec::new();
+        let mut seen: HashSet<OccurrenceRef> = HashSet::new();
+
+        for &r in activated {
+            let word = &self.get_occurrence(r).word;
+            let siblings: Vec<OccurrenceRef> = self
+                .get_neighborhood_for_occurrence(r)
+                .occurrences
+                .iter()
+                .enumerate()
+                .filter(|(_, occ)| occ.word != *word)
+                .map(|(occurrence_idx, _)| OccurrenceRef {
+                    occurrence_idx,
+                    ..r
+                })
+                .filter(|sibling| !activated_set.contains(sibling))
+                .collect();
+            for sibling in siblings {
+                let occ = self.get_occurrence_mut(sibling);
+                occ.credit(credit);
+                if seen.insert(sibling) {
+                    credited.push(occ.id);
+                }
+            }
+        }
+        credited
     }
 
     /// Add text to the conscious episode. Tokenizes, creates neighborhood,
@@ -324,22 +1018,148 @@ impl DAESystem {
         text: &str,
         nbhd_type: NeighborhoodType,
         rng: &mut impl Rng,
+    ) -> Uuid {
+        self.add_to_conscious_for(text, nbhd_type, None, rng)
+    }
+
+    /// Add typed text to the conscious episode, tagged for `project`. With
+    /// no project given, the current one is stamped when
+    /// [`set_tag_conscious_project`](Self::set_tag_conscious_project) is on.
+    pub fn add_to_conscious_for(
+        &mut self,
+        text: &str,
+        nbhd_type: NeighborhoodType,
+        project: Option<&str>,
+        rng: &mut impl Rng,
     ) -> Uuid {
         let tokens = tokenize(text);
         let mut neighborhood = Neighborhood::from_tokens(&tokens, None, text, rng);
         neighborhood.neighborhood_type = nbhd_type;
         neighborhood.epoch = self.assign_epoch();
+        neighborhood.seq = self.assign_seq();
+        neighborhood.actor.clone_from(&self.actor);
+        neighborhood.project = self.conscious_project(project);
+        neighborhood.created_at = Some(now_iso8601());
 
         for occ in &mut neighborhood.occurrences {
             occ.activate();
         }
 
         let id = neighborhood.id;
+        self.word_stats.add_neighborhood(&neighborhood);
         self.conscious_episode.add_neighborhood(neighborhood);
         self.index_dirty = true;
         id
     }
 
+    /// Add `chunks` of one salient text to the conscious episode, each its
+    /// own neighborhood, sharing a fresh `group_id` when there is more than
+    /// one. Returns their IDs in order.
+    pub fn add_conscious_group(
+        &mut self,
+        chunks: &[String],
+        nbhd_type: NeighborhoodType,
+        project: Option<&str>,
+        rng: &mut impl Rng,
+    ) -> Vec<Uuid> {
+        let group_id = (chunks.len() > 1).then(Uuid::new_v4);
+        chunks
+            .iter()
+            .map(|chunk| {
+                let id = self.add_to_conscious_for(chunk, nbhd_type, project, rng);
+                if let Some(nbhd) = self.conscious_episode.neighborhoods.last_mut() {
+                    nbhd.group_id = group_id;
+                }
+                id
+            })
+            .collect()
+    }
+
+    /// The project a new conscious memory is tagged with: `project` when
+    /// given, else the current one when tagging is on.
+    fn conscious_project(&self, project: Option<&str>) -> Option<String> {
+        match project.and_then(normalize_project) {
+            Some(p) => Some(p),
+            None if self.tag_conscious_project => self.project.clone(),
+            None => None,
+        }
+    }
+
+    /// The live, ungrouped conscious neighborhood that a salient mark of
+    /// `text` would repeat: same type and project, and token sets at least
+    /// [`salient_dedup_threshold`](Self::salient_dedup_threshold) alike.
+    /// The most similar wins, ties going to the newest. `None` when
+    /// merging is off or nothing is close enough.
+    #[must_use]
+    pub fn find_conscious_duplicate(
+        &self,
+        text: &str,
+        nbhd_type: NeighborhoodType,
+        project: Option<&str>,
+    ) -> Option<Uuid> {
+        if self.salient_dedup_threshold <= 0.0 {
+            return None;
+        }
+        let words: HashSet<String> = tokenize(text).into_iter().collect();
+        if words.is_empty() {
+            return None;
+        }
+        let project = self.conscious_project(project);
+        let mut best: Option<(f64, Uuid)> = None;
+        for nbhd in &self.conscious_episode.neighborhoods {
+            if nbhd.superseded_by.is_some()
+                || nbhd.group_id.is_some()
+                || nbhd.neighborhood_type != nbhd_type
+                || nbhd.project != project
+            {
+                continue;
+            }
+            let theirs: HashSet<&str> = nbhd.occurrences.iter().map(|o| o.word.as_str()).collect();
+            let shared = words.iter().filter(|w| theirs.contains(w.as_str())).count();
+            let union = words.len() + theirs.len() - shared;
+            let similarity = shared as f64 / union as f64;
+            if similarity >= self.salient_dedup_threshold
+                && best.is_none_or(|(top, _)| similarity >= top)
+            {
+                best = Some((similarity, nbhd.id));
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+
+    /// Reinforce a conscious neighborhood that was marked salient again:
+    /// activate each of its occurrences once and restamp `created_at`.
+    /// Returns the activated occurrence IDs, empty when `id` is not a
+    /// conscious neighborhood.
+    pub fn reinforce_conscious(&mut self, id: Uuid) -> Vec<Uuid> {
+        let Some(nbhd) = self
+            .conscious_episode
+            .neighborhoods
+            .iter_mut()
+            .find(|n| n.id == id)
+        else {
+            return Vec::new();
+        };
+        nbhd.created_at = Some(now_iso8601());
+        nbhd.occurrences
+            .iter_mut()
+            .map(|occ| {
+                occ.activate();
+                occ.id
+            })
+            .collect()
+    }
+
+    /// The live conscious neighborhoods of `group_id`, in stored order.
+    #[must_use]
+    pub fn conscious_group(&self, group_id: Uuid) -> Vec<&Neighborhood> {
+        self.conscious_episode
+            .neighborhoods
+            .iter()
+            .filter(|n| n.group_id == Some(group_id) && n.superseded_by.is_none())
+            .collect()
+    }
+
     /// Assign the next epoch value and increment the counter.
     fn assign_epoch(&mut self) -> u64 {
         let epoch = self.next_epoch;
@@ -347,23 +1167,152 @@ impl DAESystem {
         epoch
     }
 
+    /// Hand out the next insertion-order value (the first is 1).
+    fn assign_seq(&mut self) -> u64 {
+        self.last_seq += 1;
+        self.last_seq
+    }
+
     /// Add a subconscious episode. Assigns epochs to any neighborhoods
-    /// that still have the default epoch 0 (i.e., freshly created).
+    /// that still have the default epoch 0 (i.e., freshly created), and
+    /// stamps the system's actor on an episode that has none.
+    ///
+    /// With [`Self::set_dedup_neighborhoods`] on, a neighborhood whose text
+    /// (ignoring case and whitespace) is already stored is dropped and the
+    /// stored neighborhood's id is added to the episode's
+    /// `reused_neighborhoods`, so recall sees the chunk once.
     ///
     /// INVARIANT: Loaded neighborhoods (from Store or import) must not pass
     /// through this method - they go via `episodes.push()` + `sync_next_epoch()`
     /// to preserve their original epochs. This method is only for new episodes
     /// created during the current session.
     pub fn add_episode(&mut self, mut episode: Episode) {
+        if episode.actor.is_none() {
+            episode.actor.clone_from(&self.actor);
+        }
+        if self.dedup_neighborhoods && !episode.is_conscious {
+            self.reuse_stored_neighborhoods(&mut episode);
+        }
+        let ep_idx = self.episodes.len();
+        if let Some(index) = self.content_index.as_mut() {
+            for (n_idx, nbhd) in episode.neighborhoods.iter().enumerate() {
+                if let Some(hash) = nbhd.content_hash() {
+                    index.entry(hash).or_insert((ep_idx, n_idx));
+                }
+            }
+        }
         for nbhd in &mut episode.neighborhoods {
             if nbhd.epoch == 0 {
                 nbhd.epoch = self.assign_epoch();
             }
+            if nbhd.seq == 0 {
+                nbhd.seq = self.assign_seq();
+            }
+            self.word_stats.add_neighborhood(nbhd);
         }
         self.episodes.push(episode);
         self.index_dirty = true;
     }
 
+    /// Drop the neighborhoods of `episode` whose text is already stored,
+    /// in an earlier episode or earlier in this one, recording the stored
+    /// neighborhood in `reused_neighborhoods` instead.
+    fn reuse_stored_neighborhoods(&mut self, episode: &mut Episode) {
+        let mut kept: Vec<Neighborhood> = Vec::with_capacity(episode.neighborhoods.len());
+        let mut kept_by_hash: HashMap<u64, usize> = HashMap::new();
+        for nbhd in std::mem::take(&mut episode.neighborhoods) {
+            let Some(hash) = nbhd.content_hash() else {
+                kept.push(nbhd);
+                continue;
+            };
+            if let Some(id) = self.find_stored_content(hash, &nbhd) {
+                if !episode.reused_neighborhoods.contains(&id) {
+                    episode.reused_neighborhoods.push(id);
+                }
+                continue;
+            }
+            if let Some(&idx) = kept_by_hash.get(&hash)
+                && kept[idx].same_content(&nbhd)
+            {
+                continue;
+            }
+            kept_by_hash.entry(hash).or_insert(kept.len());
+            kept.push(nbhd);
+        }
+        episode.neighborhoods = kept;
+    }
+
+    /// The stored subconscious neighborhood with the same text as `nbhd`,
+    /// if any. `hash` is `nbhd.content_hash()`.
+    fn find_stored_content(&mut self, hash: u64, nbhd: &Neighborhood) -> Option<Uuid> {
+        // A stale entry (episodes removed since the index was built)
+        // triggers one rebuild.
+        for _ in 0..2 {
+            let episodes = &self.episodes;
+            let index = self.content_index.get_or_insert_with(|| {
+                let mut index = HashMap::new();
+                for (e_idx, episode) in episodes.iter().enumerate() {
+                    for (n_idx, stored) in episode.neighborhoods.iter().enumerate() {
+                        if let Some(hash) = stored.content_hash() {
+                            index.entry(hash).or_insert((e_idx, n_idx));
+                        }
+                    }
+                }
+                index
+            });
+            let &(e_idx, n_idx) = index.get(&hash)?;
+            match episodes.get(e_idx).and_then(|e| e.neighborhoods.get(n_idx)) {
+                Some(stored) if stored.content_hash() == Some(hash) => {
+                    return stored.same_content(nbhd).then_some(stored.id);
+                }
+                _ => self.content_index = None,
+            }
+        }
+        None
+    }
+
+    /// The most distinctive words of a subconscious episode, best first.
+    ///
+    /// Ranked by tf-idf: frequency within the episode times the system-wide
+    /// IDF weight, so a single typo does not outrank a repeated topic word.
+    /// Short words and numbers are skipped; ties break alphabetically.
+    pub fn distinctive_words(&mut self, episode_idx: usize, limit: usize) -> Vec<String> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for nbhd in &self.episodes[episode_idx].neighborhoods {
+            for occ in &nbhd.occurrences {
+                if occ.word.chars().count() >= EPISODE_LABEL_MIN_WORD_LEN
+                    && !occ.word.chars().all(|c| c.is_ascii_digit())
+                {
+                    *counts.entry(occ.word.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut scored: Vec<(String, f64)> = counts
+            .into_iter()
+            .map(|(word, tf)| {
+                let score = f64::from(tf) * self.get_word_weight(&word);
+                (word, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scored.into_iter().take(limit).map(|(w, _)| w).collect()
+    }
+
+    /// Rename a subconscious episode to `"{base}: word, word, ..."` using its
+    /// [`distinctive_words`](Self::distinctive_words). Falls back to `base`
+    /// when the episode has no usable words. Returns the new name.
+    pub fn label_episode(&mut self, episode_idx: usize, base: &str) -> String {
+        let words = self.distinctive_words(episode_idx, EPISODE_LABEL_WORDS);
+        let name = if words.is_empty() {
+            base.to_string()
+        } else {
+            format!("{base}: {}", words.join(", "))
+        };
+        self.episodes[episode_idx].name.clone_from(&name);
+        name
+    }
+
     /// Resolve an `EpisodeRef` to an immutable episode reference.
     #[must_use]
     pub fn resolve_episode(&self, ep: EpisodeRef) -> &Episode {
@@ -381,6 +1330,111 @@ impl DAESystem {
         }
     }
 
+    /// Remove what a store-side GC pass removed from the subconscious
+    /// manifold: the listed occurrences, neighborhoods, and episodes, plus
+    /// any neighborhood or episode left empty, as the store deletes those
+    /// too. The conscious episode is never touched. Returns the number of
+    /// occurrences removed.
+    pub fn apply_gc(&mut self, detail: &GcDetail) -> usize {
+        if detail.is_empty() {
+            return 0;
+        }
+        let occurrences: HashSet<Uuid> = detail.occurrences.iter().copied().collect();
+        let neighborhoods: HashSet<Uuid> = detail.neighborhoods.iter().copied().collect();
+        let episodes: HashSet<Uuid> = detail.episodes.iter().copied().collect();
+
+        let before = self.n();
+        for episode in self.episodes.iter().filter(|e| episodes.contains(&e.id)) {
+            for nbhd in &episode.neighborhoods {
+                self.word_stats.remove_neighborhood(nbhd);
+            }
+        }
+        self.episodes.retain(|e| !episodes.contains(&e.id));
+        for episode in &mut self.episodes {
+            for nbhd in &mut episode.neighborhoods {
+                if neighborhoods.contains(&nbhd.id) {
+                    self.word_stats.remove_neighborhood(nbhd);
+                } else if nbhd.occurrences.iter().any(|o| occurrences.contains(&o.id)) {
+                    self.word_stats.remove_neighborhood(nbhd);
+                    nbhd.occurrences.retain(|o| !occurrences.contains(&o.id));
+                    self.word_stats.add_neighborhood(nbhd);
+                }
+            }
+            episode
+                .neighborhoods
+                .retain(|n| !neighborhoods.contains(&n.id) && !n.occurrences.is_empty());
+        }
+        self.episodes.retain(|e| !e.neighborhoods.is_empty());
+
+        self.mark_layout_changed();
+        before - self.n()
+    }
+
+    /// Remove a subconscious episode the store forgot
+    /// (`AmStore::forget_episode`). Returns the occurrences removed, 0 when
+    /// no subconscious episode has that id.
+    pub fn forget_episode(&mut self, id: Uuid) -> usize {
+        let Some(idx) = self.episodes.iter().position(|e| e.id == id) else {
+            return 0;
+        };
+        let episode = self.episodes.remove(idx);
+        for nbhd in &episode.neighborhoods {
+            self.word_stats.remove_neighborhood(nbhd);
+        }
+        self.mark_layout_changed();
+        episode.count()
+    }
+
+    /// Remove a conscious neighborhood the store forgot
+    /// (`AmStore::forget_conscious`). Returns the occurrences removed, 0
+    /// when no conscious neighborhood has that id.
+    pub fn forget_conscious(&mut self, id: Uuid) -> usize {
+        let neighborhoods = &mut self.conscious_episode.neighborhoods;
+        let Some(idx) = neighborhoods.iter().position(|n| n.id == id) else {
+            return 0;
+        };
+        let nbhd = neighborhoods.remove(idx);
+        self.word_stats.remove_neighborhood(&nbhd);
+        self.mark_layout_changed();
+        nbhd.occurrences.len()
+    }
+
+    /// Remove every occurrence of `term` (case-insensitive) from both
+    /// manifolds, then the neighborhoods and subconscious episodes left
+    /// empty, as `AmStore::forget_term` does in the store. Returns the
+    /// occurrences, neighborhoods, and episodes removed.
+    pub fn forget_term(&mut self, term: &str) -> (usize, usize, usize) {
+        let term = term.to_lowercase();
+        let mut removed = (0, 0, 0);
+        for episode in std::iter::once(&mut self.conscious_episode).chain(&mut self.episodes) {
+            for nbhd in &mut episode.neighborhoods {
+                let before = nbhd.occurrences.len();
+                if !nbhd
+                    .occurrences
+                    .iter()
+                    .any(|o| o.word.to_lowercase() == term)
+                {
+                    continue;
+                }
+                self.word_stats.remove_neighborhood(nbhd);
+                nbhd.occurrences.retain(|o| o.word.to_lowercase() != term);
+                self.word_stats.add_neighborhood(nbhd);
+                removed.0 += before - nbhd.occurrences.len();
+            }
+            let before = episode.neighborhoods.len();
+            episode.neighborhoods.retain(|n| !n.occurrences.is_empty());
+            removed.1 += before - episode.neighborhoods.len();
+        }
+        let before = self.episodes.len();
+        self.episodes.retain(|e| !e.neighborhoods.is_empty());
+        removed.2 = before - self.episodes.len();
+
+        if removed != (0, 0, 0) {
+            self.mark_layout_changed();
+        }
+        removed
+    }
+
     /// Mark a neighborhood as superseded by another.
     /// Returns true if the neighborhood was found and marked.
     pub fn mark_superseded(&mut self, old_id: Uuid, new_id: Uuid) -> bool {
@@ -408,6 +1462,34 @@ impl DAESystem {
         self.next_epoch = self.next_epoch.max(max_epoch + 1);
     }
 
+    /// Align the insertion-order counter after load: new neighborhoods
+    /// get a `seq` above both `floor` (the store's highest rowid, which
+    /// other brains share) and every loaded one. Neighborhoods still at 0
+    /// are numbered in system order.
+    pub fn sync_seq(&mut self, floor: u64) {
+        let max_seq = self
+            .episodes
+            .iter()
+            .flat_map(|e| e.neighborhoods.iter())
+            .chain(self.conscious_episode.neighborhoods.iter())
+            .map(|n| n.seq)
+            .max()
+            .unwrap_or(0);
+        self.last_seq = self.last_seq.max(max_seq).max(floor);
+        let mut next = self.last_seq;
+        for nbhd in self
+            .episodes
+            .iter_mut()
+            .flat_map(|e| e.neighborhoods.iter_mut())
+            .chain(self.conscious_episode.neighborhoods.iter_mut())
+            .filter(|n| n.seq == 0)
+        {
+            next += 1;
+            nbhd.seq = next;
+        }
+        self.last_seq = next;
+    }
+
     /// Get immutable occurrence by ref.
     #[must_use]
     pub fn get_occurrence(&self, r: OccurrenceRef) -> &crate::occurrence::Occurrence {
@@ -415,15 +1497,73 @@ impl DAESystem {
         &episode.neighborhoods[r.neighborhood_idx].occurrences[r.occurrence_idx]
     }
 
-    /// Get mutable occurrence by ref.
+    /// Get mutable occurrence by ref. While [speculating](Self::begin_speculation),
+    /// the occurrence's state is journaled first so it can be rolled back.
     pub fn get_occurrence_mut(&mut self, r: OccurrenceRef) -> &mut crate::occurrence::Occurrence {
+        if let Some(journal) = &mut self.speculation {
+            // Fields borrowed apart so the journal can read the occurrence
+            let episode = match r.episode_ref {
+                EpisodeRef::Conscious => &self.conscious_episode,
+                EpisodeRef::Subconscious(idx) => &self.episodes[idx],
+            };
+            journal.record(
+                r,
+                &episode.neighborhoods[r.neighborhood_idx].occurrences[r.occurrence_idx],
+            );
+        }
         let episode = self.resolve_episode_mut(r.episode_ref);
         &mut episode.neighborhoods[r.neighborhood_idx].occurrences[r.occurrence_idx]
     }
 
+    /// Start recording occurrence changes so they can be undone: every
+    /// occurrence reached through [`get_occurrence_mut`](Self::get_occurrence_mut)
+    /// from now on has its position, phasor, activation, drift, and
+    /// spillover saved before it changes. End with [`rollback`](Self::rollback)
+    /// or [`commit`](Self::commit). Speculations do not nest; a second call
+    /// keeps the journal already open. Episodes may be appended while
+    /// speculating, but none may be removed until it ends.
+    pub fn begin_speculation(&mut self) {
+        self.speculation.get_or_insert_with(Journal::default);
+    }
+
+    /// Whether a speculation is open.
+    #[must_use]
+    pub fn is_speculating(&self) -> bool {
+        self.speculation.is_some()
+    }
+
+    /// Restore every occurrence changed since
+    /// [`begin_speculation`](Self::begin_speculation) and close the
+    /// speculation. Returns the number of occurrences restored; 0 when
+    /// none was open.
+    pub fn rollback(&mut self) -> usize {
+        let Some(journal) = self.speculation.take() else {
+            return 0;
+        };
+        let restored = journal.len();
+        for (r, saved) in journal.into_entries() {
+            saved.restore(self.get_occurrence_mut(r));
+        }
+        restored
+    }
+
+    /// Keep every change made since [`begin_speculation`](Self::begin_speculation)
+    /// and close the speculation. Returns the number of occurrences changed.
+    pub fn commit(&mut self) -> usize {
+        self.speculation.take().map_or(0, |journal| journal.len())
+    }
+
     /// Get neighborhood by its UUID.
     pub fn get_neighborhood_ref(&mut self, id: Uuid) -> Option<NeighborhoodRef> {
         self.ensure_indexes();
+        self.neighborhood_ref(id)
+    }
+
+    /// `get_neighborhood_ref` without the lazy rebuild, for readers sharing
+    /// the system across threads. Indexes must be current.
+    #[must_use]
+    pub fn neighborhood_ref(&self, id: Uuid) -> Option<NeighborhoodRef> {
+        debug_assert!(!self.index_dirty, "neighborhood_ref on stale indexes");
         self.neighborhood_index.get(&id).copied()
     }
 
@@ -447,6 +1587,38 @@ impl DAESystem {
         self.resolve_episode(r.episode_ref)
     }
 
+    /// Who created a neighborhood: its own actor for conscious memories,
+    /// otherwise the actor of the episode it belongs to.
+    #[must_use]
+    pub fn neighborhood_actor(&self, r: NeighborhoodRef) -> Option<&str> {
+        let episode = self.resolve_episode(r.episode_ref);
+        episode.neighborhoods[r.neighborhood_idx]
+            .actor
+            .as_deref()
+            .or(episode.actor.as_deref())
+    }
+
+    /// The decision registry: conscious neighborhoods whose type is in
+    /// [`DECISION_TYPES`], newest first, including superseded ones.
+    /// `keyword` keeps only those whose text contains it, ignoring case.
+    #[must_use]
+    pub fn decisions(&self, keyword: Option<&str>) -> Vec<&Neighborhood> {
+        let keyword = keyword.map(str::to_lowercase);
+        let mut decisions: Vec<&Neighborhood> = self
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .filter(|n| DECISION_TYPES.contains(&n.neighborhood_type))
+            .filter(|n| {
+                keyword
+                    .as_deref()
+                    .is_none_or(|k| n.source_text.to_lowercase().contains(k))
+            })
+            .collect();
+        decisions.sort_by_key(|n| std::cmp::Reverse(n.epoch));
+        decisions
+    }
+
     /// Get `EpisodeRef` for a neighborhood UUID.
     pub fn get_episode_ref_for_neighborhood(
         &mut self,
@@ -465,9 +1637,18 @@ impl DAESystem {
         sub + self.conscious_episode.neighborhoods.len()
     }
 
-    /// Mark indexes as needing rebuild.
+    /// Mark indexes and word stats as needing rebuild, after editing
+    /// `episodes` or `conscious_episode` directly.
     pub fn mark_dirty(&mut self) {
+        self.mark_layout_changed();
+        self.word_stats.invalidate();
+    }
+
+    /// Mark the position indexes as needing rebuild after a change that
+    /// already updated the word stats.
+    fn mark_layout_changed(&mut self) {
         self.index_dirty = true;
+        self.content_index = None;
     }
 
     /// Get word occurrence refs (read-only, requires indexes to be current).
@@ -560,6 +1741,95 @@ mod tests {
         assert!((w_unknown - 1.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_word_weight_clamped_to_policy() {
+        let mut sys = make_system_with_data();
+        sys.set_word_weight_policy(WordWeightPolicy {
+            floor: 0.5,
+            ceiling: 0.8,
+            skip_hapax_drift: false,
+            ..WordWeightPolicy::default()
+        });
+
+        // "hello" raw IDF 1/3 is raised to the floor
+        assert!((sys.get_word_weight("hello") - 0.5).abs() < 1e-10);
+        // "rust" raw IDF 1.0 is capped at the ceiling
+        assert!((sys.get_word_weight("rust") - 0.8).abs() < 1e-10);
+        // Unknown words are clamped too
+        assert!((sys.get_word_weight("unknown") - 0.8).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_word_weight_policy_inverted_range() {
+        let mut sys = make_system_with_data();
+        sys.set_word_weight_policy(WordWeightPolicy {
+            floor: 0.6,
+            ceiling: 0.2,
+            skip_hapax_drift: false,
+            ..WordWeightPolicy::default()
+        });
+        assert!((sys.word_weight_policy().ceiling - 0.6).abs() < 1e-10);
+        assert!((sys.get_word_weight("rust") - 0.6).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_word_bias_scales_weight_after_idf() {
+        let mut sys = make_system_with_data();
+        sys.set_word_weight_policy(WordWeightPolicy {
+            ceiling: 0.8,
+            ..WordWeightPolicy::default()
+        });
+
+        // Applied after the clamp: "rust" IDF 1.0 is capped to 0.8 first
+        assert!((sys.set_word_bias("Rust", 2.0) - 2.0).abs() < 1e-10);
+        assert!((sys.get_word_weight("rust") - 1.6).abs() < 1e-10);
+        assert!((sys.idf_weight("rust") - 0.8).abs() < 1e-10);
+
+        // Clamped to [0, MAX_WORD_BIAS]; zero mutes, NaN is ignored
+        assert!((sys.set_word_bias("hello", 50.0) - MAX_WORD_BIAS).abs() < 1e-10);
+        assert!((sys.set_word_bias("hello", -1.0)).abs() < 1e-10);
+        assert!(sys.get_word_weight("hello").abs() < 1e-10);
+        assert!((sys.set_word_bias("hello", f64::NAN)).abs() < 1e-10);
+        assert_eq!(sys.word_biases().len(), 2);
+
+        assert!(sys.clear_word_bias("HELLO"));
+        assert!(!sys.clear_word_bias("hello"));
+        assert!((sys.word_bias("hello") - 1.0).abs() < 1e-10);
+        assert!((sys.get_word_weight("hello") - 1.0 / 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_is_hapax() {
+        let mut sys = make_system_with_data();
+        assert!(sys.is_hapax("rust"));
+        assert!(!sys.is_hapax("hello"));
+        assert!(!sys.is_hapax("unknown"));
+    }
+
+    #[test]
+    fn test_distinctive_words_tf_idf_order() {
+        let mut sys = make_system_with_data();
+        // rust, world: tf 1 * idf 1.0; hello: tf 2 * idf 1/3
+        assert_eq!(sys.distinctive_words(0, 5), ["rust", "world", "hello"]);
+        assert_eq!(sys.distinctive_words(0, 1), ["rust"]);
+    }
+
+    #[test]
+    fn test_label_episode() {
+        let mut sys = make_system_with_data();
+        let name = sys.label_episode(0, "conversation");
+        assert_eq!(name, "conversation: rust, world, hello");
+        assert_eq!(sys.episodes[0].name, name);
+
+        // Nothing long enough to use: keep the base name
+        let mut rng = rng();
+        let mut ep = Episode::new("ep2");
+        let tokens = to_tokens(&["a", "to", "42"]);
+        ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, "", &mut rng));
+        sys.add_episode(ep);
+        assert_eq!(sys.label_episode(1, "conversation"), "conversation");
+    }
+
     #[test]
     fn test_activate_word_partitions() {
         let mut sys = make_system_with_data();
@@ -579,6 +1849,79 @@ mod tests {
         }
     }
 
+    /// `count` subconscious episodes mentioning "error", plus one conscious
+    /// memory of it.
+    fn make_hot_word_system(count: usize) -> DAESystem {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test");
+        for i in 0..count {
+            let mut ep = Episode::new(&format!("log-{i}"));
+            let tokens = vec!["error".to_string(), format!("code{i}")];
+            ep.add_neighborhood(Neighborhood::from_tokens(
+                &tokens,
+                None,
+                "error code",
+                &mut rng,
+            ));
+            sys.add_episode(ep);
+        }
+        sys.add_to_conscious("error budgets matter", &mut rng);
+        sys
+    }
+
+    #[test]
+    fn test_activate_word_caps_hot_words() {
+        let mut sys = make_hot_word_system(40);
+        sys.set_max_word_activations(8);
+        // Warm three occurrences so they rank first
+        for i in [5, 17, 33] {
+            sys.episodes[i].neighborhoods[0].occurrences[0].activation_count = 10;
+        }
+
+        let result = sys.activate_word("error");
+        assert_eq!(result.subconscious.len(), 8);
+        assert_eq!(result.capped, 32);
+        assert_eq!(result.conscious.len(), 1, "conscious is never capped");
+        let activated: HashSet<usize> = result
+            .subconscious
+            .iter()
+            .map(|r| match r.episode_ref {
+                EpisodeRef::Subconscious(i) => i,
+                EpisodeRef::Conscious => unreachable!(),
+            })
+            .collect();
+        assert_eq!(activated.len(), 8);
+        for hot in [5, 17, 33] {
+            assert!(activated.contains(&hot), "{activated:?}");
+        }
+        let untouched = sys
+            .episodes
+            .iter()
+            .filter(|e| e.neighborhoods[0].occurrences[0].activation_count == 0)
+            .count();
+        assert_eq!(untouched, 32);
+    }
+
+    #[test]
+    fn test_capped_selection_is_reproducible_and_uncapped_at_zero() {
+        let mut a = make_hot_word_system(40);
+        let mut b = make_hot_word_system(40);
+        a.set_max_word_activations(8);
+        b.set_max_word_activations(8);
+        assert_eq!(
+            a.capped_word_occurrences("error"),
+            b.capped_word_occurrences("error")
+        );
+
+        a.set_max_word_activations(0);
+        let (refs, skipped) = a.capped_word_occurrences("error");
+        assert_eq!((refs.len(), skipped), (41, 0));
+        assert_eq!(
+            DAESystem::new("test").max_word_activations(),
+            DEFAULT_MAX_WORD_ACTIVATIONS
+        );
+    }
+
     #[test]
     fn test_add_to_conscious_pre_activates() {
         let mut rng = rng();
@@ -694,4 +2037,268 @@ mod tests {
         assert_eq!(sys.next_epoch, 43);
         assert_eq!(sys.episodes[0].neighborhoods[0].epoch, 42);
     }
+
+    #[test]
+    fn test_decisions_newest_first_with_keyword() {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("test");
+        let old = sys.add_to_conscious_typed(
+            "Use SQLite for storage",
+            NeighborhoodType::Decision,
+            &mut rng,
+        );
+        sys.add_to_conscious("SQLite WAL mode halves write latency", &mut rng);
+        let new = sys.add_to_conscious_typed(
+            "Use SQLite with WAL for storage",
+            NeighborhoodType::Decision,
+            &mut rng,
+        );
+        sys.add_to_conscious_typed("Ship the CLI first", NeighborhoodType::Decision, &mut rng);
+        sys.mark_superseded(old, new);
+
+        let texts: Vec<&str> = sys
+            .decisions(None)
+            .iter()
+            .map(|n| n.source_text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Ship the CLI first",
+                "Use SQLite with WAL for storage",
+                "Use SQLite for storage",
+            ]
+        );
+        assert!(sys.decisions(None).iter().all(|n| n.created_at.is_some()));
+
+        let sqlite = sys.decisions(Some("sqlite"));
+        assert_eq!(sqlite.len(), 2);
+        assert_eq!(sqlite[1].superseded_by, Some(new));
+    }
+
+    #[test]
+    fn test_apply_gc_drops_evicted_rows_and_empty_containers() {
+        let mut sys = make_system_with_data();
+        let ep = &sys.episodes[0];
+        let world = ep.neighborhoods[0].occurrences[1].id;
+        let rust_nbhd = ep.neighborhoods[1].clone();
+        let conscious = sys.conscious_episode.neighborhoods[0].occurrences[0].id;
+        assert!(sys.get_word_weight("rust") > 0.0);
+
+        let removed = sys.apply_gc(&GcDetail {
+            occurrences: rust_nbhd
+                .occurrences
+                .iter()
+                .map(|o| o.id)
+                .chain([world, conscious])
+                .collect(),
+            neighborhoods: vec![rust_nbhd.id],
+            episodes: Vec::new(),
+        });
+        assert_eq!(removed, 3);
+        assert_eq!(sys.episodes[0].neighborhoods.len(), 1);
+        assert_eq!(sys.conscious_episode.neighborhoods[0].occurrences.len(), 2);
+        assert!(sys.get_word_occurrences("rust").is_empty());
+        assert!(sys.get_neighborhood_ref(rust_nbhd.id).is_none());
+
+        // Evicting the last occurrence removes the episode as well
+        let hello = sys.episodes[0].neighborhoods[0].occurrences[0].id;
+        let removed = sys.apply_gc(&GcDetail {
+            occurrences: vec![hello],
+            ..GcDetail::default()
+        });
+        assert_eq!(removed, 1);
+        assert!(sys.episodes.is_empty());
+        assert_eq!(sys.n(), 2);
+    }
+
+    const SHARED: &str =
+        "Retries use exponential backoff. Each attempt doubles the wait. Jitter avoids herds.";
+
+    fn doc(rest: &str) -> Episode {
+        crate::tokenizer::ingest_text(&format!("{SHARED} {rest}"), Some(rest), &mut rng())
+    }
+
+    #[test]
+    fn test_dedup_reuses_identical_neighborhoods() {
+        let mut sys = DAESystem::new("test");
+        sys.set_dedup_neighborhoods(true);
+        sys.add_episode(doc(
+            "Alpha covers webhooks. It signs payloads. It retries failures.",
+        ));
+        // Same shared chunk, different case and spacing
+        let shouted = crate::tokenizer::ingest_text(
+            &format!(
+                "{}  Beta covers queues. It batches work. It acks late.",
+                SHARED.to_uppercase()
+            ),
+            Some("beta"),
+            &mut rng(),
+        );
+        sys.add_episode(shouted);
+
+        let shared_id = sys.episodes[0].neighborhoods[0].id;
+        assert_eq!(sys.total_neighborhoods(), 3);
+        assert!(sys.episodes[0].reused_neighborhoods.is_empty());
+        assert_eq!(sys.episodes[1].reused_neighborhoods, vec![shared_id]);
+        assert_eq!(sys.episodes[1].neighborhoods.len(), 1);
+        assert!(
+            sys.episodes[1].neighborhoods[0]
+                .source_text
+                .contains("queues")
+        );
+    }
+
+    #[test]
+    fn test_dedup_off_keeps_duplicates() {
+        let mut sys = DAESystem::new("test");
+        assert!(!sys.dedup_neighborhoods());
+        sys.add_episode(doc(
+            "Alpha covers webhooks. It signs payloads. It retries failures.",
+        ));
+        sys.add_episode(doc("Beta covers queues. It batches work. It acks late."));
+        assert_eq!(sys.total_neighborhoods(), 4);
+        assert!(sys.episodes[1].reused_neighborhoods.is_empty());
+    }
+
+    /// One episode whose only neighborhood is "migration alembic downgrade".
+    fn migration_system() -> DAESystem {
+        let mut sys = DAESystem::new("test");
+        let mut ep = Episode::new("ep");
+        let tokens = to_tokens(&["migration", "alembic", "downgrade"]);
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &tokens,
+            None,
+            "migration alembic downgrade",
+            &mut rng(),
+        ));
+        sys.add_episode(ep);
+        sys
+    }
+
+    fn activate_and_spill(sys: &mut DAESystem, word: &str) -> Vec<Uuid> {
+        let activation = sys.activate_word(word);
+        sys.spill_activation(&activation.subconscious)
+    }
+
+    #[test]
+    fn test_spillover_accumulates_into_sibling_activation() {
+        let mut sys = migration_system();
+        sys.set_activation_spillover(0.25);
+        let sibling_ids: Vec<Uuid> = sys.episodes[0].neighborhoods[0].occurrences[1..]
+            .iter()
+            .map(|o| o.id)
+            .collect();
+
+        for _ in 0..3 {
+            assert_eq!(activate_and_spill(&mut sys, "migration"), sibling_ids);
+        }
+        let occs = &sys.episodes[0].neighborhoods[0].occurrences;
+        assert_eq!(occs[0].activation_count, 3);
+        assert_eq!(occs[0].spillover, 0.0, "activated words earn no credit");
+        for sibling in &occs[1..] {
+            assert_eq!(sibling.activation_count, 0);
+            assert!((sibling.spillover - 0.75).abs() < 1e-12);
+        }
+
+        // The fourth credit completes a whole activation
+        activate_and_spill(&mut sys, "migration");
+        for sibling in &sys.episodes[0].neighborhoods[0].occurrences[1..] {
+            assert_eq!(sibling.activation_count, 1);
+            assert!(sibling.spillover.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_spillover_off_by_default() {
+        let mut sys = migration_system();
+        assert_eq!(sys.activation_spillover(), 0.0);
+        assert!(activate_and_spill(&mut sys, "migration").is_empty());
+        let occs = &sys.episodes[0].neighborhoods[0].occurrences;
+        assert!(occs[1..].iter().all(|o| o.spillover == 0.0));
+
+        sys.set_activation_spillover(3.0);
+        assert_eq!(sys.activation_spillover(), 1.0);
+        sys.set_activation_spillover(f64::NAN);
+        assert_eq!(sys.activation_spillover(), 0.0);
+    }
+
+    #[test]
+    fn test_dedup_index_survives_removed_episodes() {
+        let mut sys = DAESystem::new("test");
+        sys.set_dedup_neighborhoods(true);
+        sys.add_episode(doc(
+            "Alpha covers webhooks. It signs payloads. It retries failures.",
+        ));
+        sys.add_episode(doc("Beta covers queues. It batches work. It acks late."));
+        assert_eq!(sys.total_neighborhoods(), 3);
+
+        // Forgetting the episode that holds the shared chunk leaves a stale
+        // index entry; the next copy is stored rather than reused
+        sys.episodes.remove(0);
+        sys.add_episode(doc(
+            "Gamma covers caches. It evicts cold keys. It warms on boot.",
+        ));
+        let gamma = sys.episodes.last().unwrap();
+        assert!(gamma.reused_neighborhoods.is_empty());
+        assert_eq!(gamma.neighborhoods.len(), 2);
+        // ...and reused by the copy after that
+        sys.add_episode(doc("Delta covers logs. It rotates daily. It ships to S3."));
+        let gamma_shared = sys.episodes[1].neighborhoods[0].id;
+        assert_eq!(
+            sys.episodes.last().unwrap().reused_neighborhoods,
+            vec![gamma_shared]
+        );
+    }
+
+    /// Two episodes sharing "migration", warmed up so queries drift them.
+    fn speculation_system() -> DAESystem {
+        let mut sys = DAESystem::new("test");
+        sys.add_episode(doc(
+            "The migration adds a column. Alembic runs the migration. Downgrade drops it.",
+        ));
+        sys.add_episode(doc(
+            "A migration window is scheduled. The column backfill runs late.",
+        ));
+        sys.add_to_conscious("migration needs a downgrade plan", &mut rng());
+        sys.set_activation_spillover(0.4);
+        for _ in 0..3 {
+            crate::query::QueryEngine::process_query(&mut sys, "migration column downgrade");
+        }
+        sys
+    }
+
+    #[test]
+    fn test_rollback_restores_byte_identical_export() {
+        let mut sys = speculation_system();
+        let before = crate::serde_compat::export_json(&sys).unwrap();
+
+        sys.begin_speculation();
+        assert!(sys.is_speculating());
+        for _ in 0..2 {
+            crate::query::QueryEngine::process_query(&mut sys, "migration column backfill");
+        }
+        assert_ne!(crate::serde_compat::export_json(&sys).unwrap(), before);
+
+        assert!(sys.rollback() > 0);
+        assert!(!sys.is_speculating());
+        assert_eq!(crate::serde_compat::export_json(&sys).unwrap(), before);
+        assert_eq!(sys.rollback(), 0, "nothing left to roll back");
+    }
+
+    #[test]
+    fn test_commit_keeps_speculative_changes() {
+        let mut sys = speculation_system();
+        let before = crate::serde_compat::export_json(&sys).unwrap();
+
+        sys.begin_speculation();
+        crate::query::QueryEngine::process_query(&mut sys, "migration column backfill");
+        // A second begin keeps the first journal
+        sys.begin_speculation();
+        let after = crate::serde_compat::export_json(&sys).unwrap();
+        assert!(sys.commit() > 0);
+        assert_eq!(sys.rollback(), 0);
+        assert_eq!(crate::serde_compat::export_json(&sys).unwrap(), after);
+        assert_ne!(after, before);
+    }
 }
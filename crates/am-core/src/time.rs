@@ -2,17 +2,49 @@
 //!
 //! Uses Howard Hinnant's `civil_from_days` algorithm for Unix-to-date conversion.
 
+use std::cell::Cell;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+thread_local! {
+    /// Per-thread pinned "now", set by [`freeze_clock`].
+    static FROZEN_NOW: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
 /// Current UTC time as Unix seconds.
+///
+/// Returns the pinned value while a [`FrozenClock`] guard is alive on this
+/// thread, so timestamps and recency decay are reproducible in tests and
+/// evaluation runs.
 #[must_use]
 pub fn now_unix_secs() -> u64 {
+    if let Some(secs) = FROZEN_NOW.with(Cell::get) {
+        return secs;
+    }
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs()
 }
 
+/// Pin [`now_unix_secs`] to `secs` on the current thread until the returned
+/// guard is dropped. Guards nest: dropping restores the previous value.
+#[must_use = "the clock unfreezes when the guard is dropped"]
+pub fn freeze_clock(secs: u64) -> FrozenClock {
+    let previous = FROZEN_NOW.with(|c| c.replace(Some(secs)));
+    FrozenClock { previous }
+}
+
+/// Guard returned by [`freeze_clock`].
+pub struct FrozenClock {
+    previous: Option<u64>,
+}
+
+impl Drop for FrozenClock {
+    fn drop(&mut self) {
+        FROZEN_NOW.with(|c| c.set(self.previous));
+    }
+}
+
 /// Current UTC timestamp in ISO-8601 format.
 #[must_use]
 pub fn now_iso8601() -> String {
@@ -32,6 +64,53 @@ pub fn unix_to_iso8601(secs: u64) -> String {
     format!("{y:04}-{m:02}-{d:02}T{hours:02}:{minutes:02}:{seconds:02}Z")
 }
 
+/// Parse an ISO-8601 UTC timestamp (`YYYY-MM-DDTHH:MM:SSZ`, or a bare
+/// `YYYY-MM-DD` read as midnight) into Unix seconds. Returns `None` for
+/// anything else, including dates before 1970.
+#[must_use]
+pub fn iso8601_to_unix(timestamp: &str) -> Option<u64> {
+    let field = |range: std::ops::Range<usize>| -> Option<u64> {
+        let part = timestamp.get(range)?;
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())
+            .flatten()
+    };
+    let date = timestamp.get(..10)?.as_bytes();
+    if date[4] != b'-' || date[7] != b'-' {
+        return None;
+    }
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time_of_day = if timestamp.len() == 10 {
+        0
+    } else {
+        if timestamp.as_bytes()[10] != b'T' {
+            return None;
+        }
+        let (hours, minutes, seconds) = (field(11..13)?, field(14..16)?, field(17..19)?);
+        if hours > 23 || minutes > 59 || seconds > 60 {
+            return None;
+        }
+        hours * 3600 + minutes * 60 + seconds
+    };
+    let days = u64::try_from(days_from_civil(year as i64, month, day)).ok()?;
+    Some(days * 86400 + time_of_day)
+}
+
+/// Howard Hinnant's `days_from_civil`: (year, month, day) → Unix epoch days.
+fn days_from_civil(y: i64, m: u64, d: u64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as u64;
+    let mp = if m > 2 { m - 3 } else { m + 9 };
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe as i64 - 719_468
+}
+
 /// Howard Hinnant's `civil_from_days`: Unix epoch days → (year, month, day).
 fn civil_from_days(days: i64) -> (i64, u64, u64) {
     let z = days + 719_468;
@@ -68,6 +147,20 @@ mod tests {
         assert!(ts.starts_with("202"), "timestamp should be in 2020s: {ts}");
     }
 
+    #[test]
+    fn test_freeze_clock_nests_and_restores() {
+        {
+            let _outer = freeze_clock(1_771_632_000);
+            assert_eq!(now_iso8601(), "2026-02-21T00:00:00Z");
+            {
+                let _inner = freeze_clock(0);
+                assert_eq!(now_unix_secs(), 0);
+            }
+            assert_eq!(now_unix_secs(), 1_771_632_000);
+        }
+        assert!(now_unix_secs() > 1_771_632_000);
+    }
+
     #[test]
     fn test_leap_day() {
         // 2024-02-29T00:00:00Z = 1709164800
@@ -92,6 +185,29 @@ mod tests {
         assert_eq!(unix_to_iso8601(1_773_446_399), "2026-03-13T23:59:59Z");
     }
 
+    #[test]
+    fn test_iso8601_to_unix_inverts_unix_to_iso8601() {
+        for secs in [0, 1_709_164_800, 1_767_225_599, 1_773_446_399] {
+            assert_eq!(iso8601_to_unix(&unix_to_iso8601(secs)), Some(secs));
+        }
+        assert_eq!(iso8601_to_unix("2026-02-21"), Some(1_771_632_000));
+    }
+
+    #[test]
+    fn test_iso8601_to_unix_rejects_malformed() {
+        for bad in [
+            "",
+            "2026",
+            "not-a-date",
+            "2026-13-01",
+            "2026-02-21 10:00:00",
+            "2026-02-21T25:00:00Z",
+            "1969-12-31T00:00:00Z",
+        ] {
+            assert_eq!(iso8601_to_unix(bad), None, "{bad:?}");
+        }
+    }
+
     #[test]
     fn test_civil_from_days_roundtrip() {
         // Verify a few known dates convert correctly through civil_from_days
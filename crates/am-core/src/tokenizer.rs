@@ -1,52 +1,106 @@
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::episode::Episode;
 use crate::neighborhood::Neighborhood;
+use crate::sanitize::clean_text;
 
-static NON_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[^\w\s']").unwrap());
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[\w']+").unwrap());
 static SENTENCE_END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[.!?]\s+").unwrap());
-static APOSTROPHE_TRIM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^'+|'+$").unwrap());
+
+/// A token and where it came from in the tokenized text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The lowercased word, as [`tokenize`] returns it.
+    pub text: String,
+    /// Byte range of the word in the source: `&source[byte_start..byte_end]`
+    /// lowercases to `text`.
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
 
 /// Tokenize text into lowercase words.
 /// Preserves apostrophes within words (e.g., "don't").
 /// No stemming, no stop-word removal - IDF handles frequency naturally.
+#[must_use]
 pub fn tokenize(text: &str) -> Vec<String> {
-    let cleaned = NON_WORD.replace_all(text, " ");
-    cleaned
-        .to_lowercase()
-        .split_whitespace()
-        .map(|t| APOSTROPHE_TRIM.replace_all(t, "").to_string())
-        .filter(|t| !t.is_empty())
+    tokenize_with_spans(text)
+        .into_iter()
+        .map(|t| t.text)
+        .collect()
+}
+
+/// [`tokenize`], keeping each token's byte range in `text` so callers can
+/// point back into the source (highlighting, source links).
+///
+/// A word is a run of word characters and apostrophes; everything else
+/// separates words. Leading and trailing apostrophes are not part of it.
+#[must_use]
+pub fn tokenize_with_spans(text: &str) -> Vec<Token> {
+    WORD.find_iter(text)
+        .filter_map(|m| {
+            let word = m.as_str().trim_matches('\'');
+            if word.is_empty() {
+                return None;
+            }
+            // Apostrophes are one byte, so the trimmed word starts this
+            // many bytes in
+            let byte_start =
+                m.start() + (m.as_str().len() - m.as_str().trim_start_matches('\'').len());
+            Some(Token {
+                text: word.to_lowercase(),
+                byte_start,
+                byte_end: byte_start + word.len(),
+            })
+        })
         .collect()
 }
 
 /// Count tokens in text without allocating the full token vector.
 /// Used for budget estimation in context composition.
 ///
-/// Replicates the same cleaning pipeline as `tokenize()` but counts
-/// matches instead of collecting `String`s. Avoids per-token heap
-/// allocation on every candidate evaluation in context composition.
+/// Matches words the same way as `tokenize()` but counts them instead of
+/// collecting `String`s. Avoids per-token heap allocation on every
+/// candidate evaluation in context composition.
 #[must_use]
 pub fn token_count(text: &str) -> usize {
-    let cleaned = NON_WORD.replace_all(text, " ");
-    cleaned
-        .to_lowercase()
-        .split_whitespace()
-        .filter(|t| {
-            let trimmed = APOSTROPHE_TRIM.replace_all(t, "");
-            !trimmed.is_empty()
-        })
+    WORD.find_iter(text)
+        .filter(|m| !m.as_str().trim_matches('\'').is_empty())
         .count()
 }
 
+/// Whether `before` ends in a list step number ("1" of "1. add the
+/// column"): one or two digits at the start of a line or segment, or after
+/// a `,` `;` `:` separator. A period after such a number is not a
+/// sentence end, so numbered plans stay in one chunk.
+fn ends_with_list_marker(before: &str) -> bool {
+    let head = before.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = before.len() - head.len();
+    if digits == 0 || digits > 2 {
+        return false;
+    }
+    if head.ends_with(|c: char| c.is_alphanumeric()) {
+        // Digits glued to a word ("v2.")
+        return false;
+    }
+    let trimmed = head.trim_end_matches([' ', '\t']);
+    trimmed.is_empty() || trimmed.ends_with(['\n', ',', ';', ':'])
+}
+
 /// Split text into sentences at sentence-ending punctuation followed by whitespace.
-fn split_sentences(text: &str) -> Vec<String> {
+/// List step markers ("1. ", "2. ") are not treated as sentence ends.
+pub(crate) fn split_sentences(text: &str) -> Vec<String> {
     let mut sentences = Vec::new();
     let mut last = 0;
 
     for m in SENTENCE_END.find_iter(text) {
+        if text[m.start()..].starts_with('.') && ends_with_list_marker(&text[last..m.start()]) {
+            continue;
+        }
         let end = m.end();
         let sentence = text[last..=m.start()].trim().to_string(); // include the punctuation
         if !sentence.is_empty() {
@@ -68,14 +122,17 @@ fn split_sentences(text: &str) -> Vec<String> {
 /// Splits into 3-sentence chunks, each becoming a Neighborhood.
 pub fn ingest_text(text: &str, name: Option<&str>, rng: &mut impl Rng) -> Episode {
     let mut episode = Episode::new(name.unwrap_or(""));
-    let sentences = split_sentences(text);
+    let text = clean_text(text);
+    let sentences = split_sentences(&text);
     let chunk_size = 3;
 
     for chunk in sentences.chunks(chunk_size) {
         let combined = chunk.join(" ");
-        let tokens = tokenize(&combined);
-        if !tokens.is_empty() {
+        let spans = tokenize_with_spans(&combined);
+        if !spans.is_empty() {
+            let tokens: Vec<String> = spans.iter().map(|t| t.text.clone()).collect();
             let mut neighborhood = Neighborhood::from_tokens(&tokens, None, &combined, rng);
+            neighborhood.record_spans(&spans);
             neighborhood.neighborhood_type = crate::neighborhood::NeighborhoodType::Ingested;
             episode.add_neighborhood(neighborhood);
         }
@@ -84,6 +141,202 @@ pub fn ingest_text(text: &str, name: Option<&str>, rng: &mut impl Rng) -> Episod
     episode
 }
 
+/// One document for [`ingest_parallel`].
+#[derive(Debug, Clone)]
+pub struct IngestDoc {
+    /// Stable identity of the document (a file path), mixed into its seed.
+    pub key: String,
+    /// Episode name.
+    pub name: String,
+    pub text: String,
+}
+
+/// RNG seed for the document `key` within a batch seeded with `seed`.
+///
+/// Each document gets its own RNG so placement does not depend on which
+/// worker ingests it or in what order. The key is hashed with FNV-1a,
+/// which unlike `DefaultHasher` is fixed across Rust releases.
+#[must_use]
+pub fn document_seed(seed: u64, key: &str) -> u64 {
+    seed ^ fnv1a(key.as_bytes())
+}
+
+/// 64-bit FNV-1a hash of `bytes`.
+#[must_use]
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Ingest `docs` with [`ingest_text`] on up to `threads` worker threads.
+///
+/// Episodes come back in `docs` order, and each is placed with an RNG
+/// seeded by [`document_seed`], so the result depends only on `seed` and
+/// the documents, never on `threads`. `on_done` is called from the worker
+/// as each document finishes, with its index in `docs`.
+pub fn ingest_parallel<F>(docs: &[IngestDoc], seed: u64, threads: usize, on_done: F) -> Vec<Episode>
+where
+    F: Fn(usize, &Episode) + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let ingest_one = || {
+        let mut done = Vec::new();
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(doc) = docs.get(i) else {
+                return done;
+            };
+            let mut rng = SmallRng::seed_from_u64(document_seed(seed, &doc.key));
+            let episode = ingest_text(&doc.text, Some(&doc.name), &mut rng);
+            on_done(i, &episode);
+            done.push((i, episode));
+        }
+    };
+
+    let mut slots: Vec<Option<Episode>> = docs.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, docs.len().max(1)))
+            .map(|_| scope.spawn(ingest_one))
+            .collect();
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (i, episode) in done {
+                slots[i] = Some(episode);
+            }
+        }
+    });
+    slots.into_iter().flatten().collect()
+}
+
+/// Most chunks accepted by one [`ingest_chunks`] call.
+pub const MAX_INGEST_CHUNKS: usize = 10_000;
+
+/// Largest accepted chunk text, in bytes.
+pub const MAX_CHUNK_BYTES: usize = 64 * 1024;
+
+/// One caller-chunked piece of a document. Callers that already split by
+/// function, ticket, or section send these instead of raw text, so the
+/// sentence chunker never redraws their boundaries.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IngestChunk {
+    pub text: String,
+    /// Optional label for the chunk. Its words join the neighborhood's
+    /// words (not its source text), so recall can find the chunk by name.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional tags, searchable the same way as `name`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Ingest pre-chunked text into an Episode: each chunk becomes exactly one
+/// neighborhood whose source text is the chunk's text.
+///
+/// # Errors
+///
+/// Rejects an empty chunk list, more than [`MAX_INGEST_CHUNKS`] chunks, and
+/// any chunk over [`MAX_CHUNK_BYTES`] or without a single word of text.
+pub fn ingest_chunks(
+    chunks: &[IngestChunk],
+    name: Option<&str>,
+    rng: &mut impl Rng,
+) -> Result<Episode, String> {
+    if chunks.is_empty() {
+        return Err("chunks is empty".to_string());
+    }
+    if chunks.len() > MAX_INGEST_CHUNKS {
+        return Err(format!(
+            "{} chunks exceeds the limit of {MAX_INGEST_CHUNKS}",
+            chunks.len()
+        ));
+    }
+
+    let mut episode = Episode::new(name.unwrap_or(""));
+    for (i, chunk) in chunks.iter().enumerate() {
+        if chunk.text.len() > MAX_CHUNK_BYTES {
+            return Err(format!("chunk {i} exceeds {MAX_CHUNK_BYTES} byte limit"));
+        }
+        let text = clean_text(&chunk.text);
+        let spans = tokenize_with_spans(&text);
+        if spans.is_empty() {
+            return Err(format!("chunk {i} has no words"));
+        }
+        let mut tokens: Vec<String> = spans.iter().map(|t| t.text.clone()).collect();
+        for label in chunk.name.iter().chain(&chunk.tags) {
+            tokens.extend(tokenize(label));
+        }
+        let mut neighborhood = Neighborhood::from_tokens(&tokens, None, &text, rng);
+        neighborhood.record_spans(&spans);
+        neighborhood.neighborhood_type = crate::neighborhood::NeighborhoodType::Ingested;
+        episode.add_neighborhood(neighborhood);
+    }
+
+    Ok(episode)
+}
+
+/// Storage assumed per occurrence when the store is empty and there is
+/// nothing to measure: the occurrence row, its share of the neighborhood
+/// row, and their indexes.
+pub const DEFAULT_BYTES_PER_OCCURRENCE: u64 = 256;
+
+/// Chunks an [`IngestPreview`] shows.
+pub const PREVIEW_CHUNKS: usize = 3;
+
+/// Characters kept from each previewed chunk.
+pub const PREVIEW_CHARS: usize = 200;
+
+/// What ingesting an episode would add, for dry runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngestPreview {
+    pub neighborhoods: usize,
+    pub occurrences: usize,
+    /// Average size of a stored occurrence, measured on the current store.
+    pub bytes_per_occurrence: u64,
+    /// `occurrences * bytes_per_occurrence`.
+    pub estimated_bytes: u64,
+    /// Source text of the first [`PREVIEW_CHUNKS`] chunks, cut to
+    /// [`PREVIEW_CHARS`] characters.
+    pub chunks: Vec<String>,
+}
+
+impl IngestPreview {
+    /// Preview `episode` against a store of `db_size` bytes holding
+    /// `stored_occurrences` occurrences. Chunks a deduplicating store would
+    /// reuse are still counted.
+    #[must_use]
+    pub fn new(episode: &Episode, db_size: u64, stored_occurrences: u64) -> Self {
+        let occurrences: usize = episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.occurrences.len())
+            .sum();
+        let bytes_per_occurrence = if stored_occurrences == 0 || db_size == 0 {
+            DEFAULT_BYTES_PER_OCCURRENCE
+        } else {
+            (db_size / stored_occurrences).max(1)
+        };
+        let chunks = episode
+            .neighborhoods
+            .iter()
+            .take(PREVIEW_CHUNKS)
+            .map(|n| match n.source_text.char_indices().nth(PREVIEW_CHARS) {
+                Some((end, _)) => format!("{}…", &n.source_text[..end]),
+                None => n.source_text.clone(),
+            })
+            .collect();
+        Self {
+            neighborhoods: episode.neighborhoods.len(),
+            occurrences,
+            bytes_per_occurrence,
+            estimated_bytes: occurrences as u64 * bytes_per_occurrence,
+            chunks,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +389,27 @@ mod tests {
         assert_eq!(sentences.len(), 4);
     }
 
+    #[test]
+    fn test_list_markers_do_not_split_sentences() {
+        let sentences = split_sentences(
+            "Plan: 1. add the column, 2. backfill, 3. flip the flag. Then ship it.",
+        );
+        assert_eq!(
+            sentences,
+            vec![
+                "Plan: 1. add the column, 2. backfill, 3. flip the flag.",
+                "Then ship it."
+            ]
+        );
+
+        let sentences = split_sentences("Steps:\n1. add the column\n2. backfill");
+        assert_eq!(sentences.len(), 1);
+
+        // A number ending a real sentence still splits
+        let sentences = split_sentences("We scored 10. Then we left. Upgrade to v2. Done.");
+        assert_eq!(sentences.len(), 4);
+    }
+
     #[test]
     fn test_ingest_text_3_sentence_chunks() {
         use rand::SeedableRng;
@@ -201,4 +475,192 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_token_spans_slice_back_to_tokens() {
+        let cases = [
+            "Hello, world!",
+            "'quoted' words, don't stop",
+            "Ünïcödé façade — naïve café 東京 tower",
+            "İstanbul ΣΊΣΥΦΟΣ straße",
+            "",
+        ];
+        for input in cases {
+            let spans = tokenize_with_spans(input);
+            assert_eq!(
+                spans.iter().map(|t| t.text.clone()).collect::<Vec<_>>(),
+                tokenize(input),
+                "{input:?}"
+            );
+            for token in &spans {
+                let slice = &input[token.byte_start..token.byte_end];
+                assert_eq!(slice.to_lowercase(), token.text, "{input:?}");
+            }
+        }
+
+        let spans = tokenize_with_spans("naïve 'café'");
+        assert_eq!(
+            spans,
+            vec![
+                Token {
+                    text: "naïve".to_string(),
+                    byte_start: 0,
+                    byte_end: 6,
+                },
+                Token {
+                    text: "café".to_string(),
+                    byte_start: 8,
+                    byte_end: 13,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ingest_records_occurrence_spans() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(42);
+        let episode = ingest_text("Größe matters. Déjà vu again!", None, &mut rng);
+        let nbhd = &episode.neighborhoods[0];
+        for occ in &nbhd.occurrences {
+            let (start, end) = occ.span.unwrap();
+            assert_eq!(nbhd.source_text[start..end].to_lowercase(), occ.word);
+        }
+
+        // Label words are not in the text, so they get no span
+        let labelled = IngestChunk {
+            text: "Café opens at nine".to_string(),
+            name: Some("hours".to_string()),
+            ..IngestChunk::default()
+        };
+        let episode = ingest_chunks(&[labelled], None, &mut rng).unwrap();
+        let occurrences = &episode.neighborhoods[0].occurrences;
+        assert!(occurrences[..4].iter().all(|o| o.span.is_some()));
+        assert_eq!(occurrences[4].word, "hours");
+        assert_eq!(occurrences[4].span, None);
+    }
+
+    fn chunk(text: &str) -> IngestChunk {
+        IngestChunk {
+            text: text.to_string(),
+            ..IngestChunk::default()
+        }
+    }
+
+    #[test]
+    fn test_ingest_chunks_keeps_caller_boundaries() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(42);
+        // Four sentences in one chunk would be two neighborhoods via ingest_text
+        let chunks = vec![
+            chunk("fn parse() reads input. It returns tokens. It never panics. Errors bubble up."),
+            IngestChunk {
+                text: "TICKET-42: login times out".to_string(),
+                name: Some("auth ticket".to_string()),
+                tags: vec!["oncall".to_string()],
+            },
+            chunk("Closing notes."),
+        ];
+        let episode = ingest_chunks(&chunks, Some("pipeline"), &mut rng).unwrap();
+        assert_eq!(episode.name, "pipeline");
+        let texts: Vec<&str> = episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.source_text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                chunks[0].text.as_str(),
+                "TICKET-42: login times out",
+                "Closing notes."
+            ]
+        );
+        // Name and tag words are searchable but stay out of the source text
+        let words: Vec<&str> = episode.neighborhoods[1]
+            .occurrences
+            .iter()
+            .map(|o| o.word.as_str())
+            .collect();
+        assert!(
+            words.contains(&"auth") && words.contains(&"oncall"),
+            "{words:?}"
+        );
+    }
+
+    #[test]
+    fn test_ingest_chunks_rejects_empty_and_oversized_input() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(42);
+        assert!(ingest_chunks(&[], None, &mut rng).is_err());
+
+        let err = ingest_chunks(&[chunk("ok"), chunk("  ...  ")], None, &mut rng).unwrap_err();
+        assert_eq!(err, "chunk 1 has no words");
+
+        let big = chunk(&"word ".repeat(MAX_CHUNK_BYTES));
+        assert!(ingest_chunks(&[big], None, &mut rng).is_err());
+
+        let many = vec![chunk("word"); MAX_INGEST_CHUNKS + 1];
+        assert!(ingest_chunks(&many, None, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_ingest_preview_counts_and_truncates() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(42);
+        let long = "é".repeat(PREVIEW_CHARS + 10);
+        let chunks: Vec<IngestChunk> = [long.as_str(), "two words", "three", "four"]
+            .iter()
+            .map(|t| chunk(t))
+            .collect();
+        let episode = ingest_chunks(&chunks, None, &mut rng).unwrap();
+
+        let preview = IngestPreview::new(&episode, 0, 0);
+        assert_eq!(preview.neighborhoods, 4);
+        assert_eq!(preview.occurrences, 5);
+        assert_eq!(preview.bytes_per_occurrence, DEFAULT_BYTES_PER_OCCURRENCE);
+        assert_eq!(preview.estimated_bytes, 5 * DEFAULT_BYTES_PER_OCCURRENCE);
+        assert_eq!(preview.chunks.len(), PREVIEW_CHUNKS);
+        assert_eq!(preview.chunks[0].chars().count(), PREVIEW_CHARS + 1);
+        assert!(preview.chunks[0].ends_with('…'));
+        assert_eq!(preview.chunks[1], "two words");
+
+        let measured = IngestPreview::new(&episode, 10_000, 100);
+        assert_eq!(measured.bytes_per_occurrence, 100);
+        assert_eq!(measured.estimated_bytes, 500);
+    }
+
+    #[test]
+    fn test_ingest_parallel_is_independent_of_thread_count() {
+        let docs: Vec<IngestDoc> = (0..12)
+            .map(|i| IngestDoc {
+                key: format!("docs/{i}.md"),
+                name: format!("{i}"),
+                text: format!("Document {i} covers drift. It mentions sqlite and tokio {i} times."),
+            })
+            .collect();
+        let placement = |episodes: &[Episode]| -> Vec<(String, [f64; 5])> {
+            episodes
+                .iter()
+                .flat_map(|e| &e.neighborhoods)
+                .flat_map(|n| &n.occurrences)
+                .map(|o| {
+                    let p = o.position;
+                    (o.word.clone(), [p.w, p.x, p.y, p.z, o.phasor.theta])
+                })
+                .collect()
+        };
+
+        let serial = ingest_parallel(&docs, 7, 1, |_, _| {});
+        let parallel = ingest_parallel(&docs, 7, 4, |_, _| {});
+        let names: Vec<&str> = parallel.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            docs.iter().map(|d| d.name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(placement(&serial), placement(&parallel));
+
+        let reseeded = ingest_parallel(&docs, 8, 4, |_, _| {});
+        assert_ne!(placement(&serial), placement(&reseeded));
+    }
 }
@@ -0,0 +1,159 @@
+//! Document frequencies behind IDF word weights.
+//!
+//! `WordStats` counts, for each lowercase word, how many neighborhoods
+//! contain it. `DAESystem` keeps it current incrementally as neighborhoods
+//! are added and removed, so a new episode or a forgotten term updates the
+//! counts it touches instead of forcing a full reindex before the next
+//! weight lookup. Code that edits `episodes` directly calls
+//! `DAESystem::mark_dirty`, which marks the stats stale until the next
+//! [`rebuild`](WordStats::rebuild).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::neighborhood::Neighborhood;
+
+/// Per-word neighborhood counts with a generation stamp.
+#[derive(Debug, Clone)]
+pub struct WordStats {
+    /// Lowercase word to the number of neighborhoods containing it. Words
+    /// whose count drops to zero are removed.
+    df: HashMap<String, usize>,
+    /// Bumped on every change, so anything derived from the counts can
+    /// tell whether it is still current.
+    generation: u64,
+    /// The counts no longer describe the system and must be rebuilt.
+    stale: bool,
+}
+
+impl Default for WordStats {
+    /// Empty and stale: nothing has been counted yet.
+    fn default() -> Self {
+        Self {
+            df: HashMap::new(),
+            generation: 0,
+            stale: true,
+        }
+    }
+}
+
+impl WordStats {
+    /// Recount from scratch over every neighborhood.
+    pub fn rebuild<'a>(&mut self, neighborhoods: impl IntoIterator<Item = &'a Neighborhood>) {
+        self.df.clear();
+        for nbhd in neighborhoods {
+            for word in distinct_words(nbhd) {
+                *self.df.entry(word).or_default() += 1;
+            }
+        }
+        self.stale = false;
+        self.generation += 1;
+    }
+
+    /// Count a neighborhood that joined the system.
+    pub fn add_neighborhood(&mut self, nbhd: &Neighborhood) {
+        for word in distinct_words(nbhd) {
+            *self.df.entry(word).or_default() += 1;
+        }
+        self.generation += 1;
+    }
+
+    /// Uncount a neighborhood that left the system, or is about to be
+    /// edited and added back.
+    pub fn remove_neighborhood(&mut self, nbhd: &Neighborhood) {
+        for word in distinct_words(nbhd) {
+            if let Some(count) = self.df.get_mut(&word) {
+                *count -= 1;
+                if *count == 0 {
+                    self.df.remove(&word);
+                }
+            }
+        }
+        self.generation += 1;
+    }
+
+    /// Neighborhoods containing `word`, which must be lowercase.
+    #[must_use]
+    pub fn document_frequency(&self, word: &str) -> usize {
+        self.df.get(word).copied().unwrap_or(0)
+    }
+
+    /// Distinct words counted.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.df.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.df.is_empty()
+    }
+
+    /// Changes so far. Two equal readings mean no weight changed between
+    /// them.
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the counts must be rebuilt before use.
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Flag the counts as out of date, after an edit they did not see.
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+        self.generation += 1;
+    }
+
+    /// Every word with its count, for comparing against a rebuild.
+    #[must_use]
+    pub fn counts(&self) -> &HashMap<String, usize> {
+        &self.df
+    }
+}
+
+fn distinct_words(nbhd: &Neighborhood) -> HashSet<String> {
+    nbhd.occurrences
+        .iter()
+        .map(|occ| occ.word.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenize;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    fn nbhd(text: &str) -> Neighborhood {
+        let mut rng = SmallRng::seed_from_u64(7);
+        Neighborhood::from_tokens(&tokenize(text), None, text, &mut rng)
+    }
+
+    #[test]
+    fn test_counts_neighborhoods_not_occurrences() {
+        let a = nbhd("cache cache warms");
+        let b = nbhd("Cache evicts");
+        let mut stats = WordStats::default();
+        assert!(stats.is_stale());
+        stats.rebuild([&a, &b]);
+        assert!(!stats.is_stale());
+        assert_eq!(stats.document_frequency("cache"), 2);
+        assert_eq!(stats.document_frequency("warms"), 1);
+        assert_eq!(stats.document_frequency("missing"), 0);
+
+        let before = stats.generation();
+        stats.remove_neighborhood(&a);
+        assert!(stats.generation() > before);
+        assert_eq!(stats.document_frequency("cache"), 1);
+        assert!(!stats.counts().contains_key("warms"));
+
+        stats.add_neighborhood(&a);
+        let mut rebuilt = WordStats::default();
+        rebuilt.rebuild([&a, &b]);
+        assert_eq!(stats.counts(), rebuilt.counts());
+    }
+}
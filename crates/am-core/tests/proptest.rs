@@ -1,9 +1,21 @@
-//! Property-based tests for quaternion and phasor geometric invariants.
+//! Property-based tests for quaternion and phasor geometric invariants, for
+//! system invariants under drift, and for incremental word statistics.
 
+use std::collections::{HashMap, HashSet};
 use std::f64::consts::{PI, TAU};
 
-use am_core::{phasor::DaemonPhasor, quaternion::Quaternion};
+use am_core::{
+    episode::Episode,
+    invariants::check_system,
+    neighborhood::Neighborhood,
+    phasor::DaemonPhasor,
+    quaternion::Quaternion,
+    query::QueryEngine,
+    system::{DAESystem, GcDetail},
+};
 use proptest::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 
 const EPSILON: f64 = 1e-10;
 
@@ -167,3 +179,363 @@ proptest! {
         );
     }
 }
+
+// --- Slerp, distance, and normalization properties ---
+
+/// Strategy for arbitrary non-zero quaternions, not normalized.
+fn arb_raw_quaternion() -> impl Strategy<Value = Quaternion> {
+    (
+        -10.0..10.0f64,
+        -10.0..10.0f64,
+        -10.0..10.0f64,
+        -10.0..10.0f64,
+    )
+        .prop_filter("non-zero quaternion", |(w, x, y, z)| {
+            w * w + x * x + y * y + z * z > 1e-6
+        })
+        .prop_map(|(w, x, y, z)| Quaternion { w, x, y, z })
+}
+
+/// Shortest angle between two phasors, in [0, pi].
+fn circular_distance(a: DaemonPhasor, b: DaemonPhasor) -> f64 {
+    let d = (a.theta - b.theta).rem_euclid(TAU);
+    d.min(TAU - d)
+}
+
+proptest! {
+    /// 11. Distance from the start grows monotonically along the slerp path.
+    #[test]
+    fn slerp_distance_monotonic_in_t(
+        q1 in arb_unit_quaternion(),
+        q2 in arb_unit_quaternion(),
+        t1 in arb_t(),
+        t2 in arb_t()
+    ) {
+        let (lo, hi) = if t1 <= t2 { (t1, t2) } else { (t2, t1) };
+        let d_lo = q1.angular_distance(Quaternion::slerp(q1, q2, lo));
+        let d_hi = q1.angular_distance(Quaternion::slerp(q1, q2, hi));
+        prop_assert!(
+            d_lo <= d_hi + 1e-6,
+            "distance at t={lo} ({d_lo}) exceeds distance at t={hi} ({d_hi})"
+        );
+    }
+
+    /// 12. Normalizing twice is the same as normalizing once.
+    #[test]
+    fn normalize_is_idempotent(q in arb_raw_quaternion()) {
+        let once = q.normalize();
+        let twice = once.normalize();
+        prop_assert!(quat_approx_eq(once, twice, EPSILON));
+        prop_assert!((norm(once) - 1.0).abs() < EPSILON);
+    }
+
+    /// 13. angular_distance is symmetric.
+    #[test]
+    fn angular_distance_symmetric(a in arb_unit_quaternion(), b in arb_unit_quaternion()) {
+        prop_assert!(approx_eq(a.angular_distance(b), b.angular_distance(a), EPSILON));
+    }
+
+    /// 14. Whole turns wrap away: theta and theta + k * 2pi are the same phasor.
+    #[test]
+    fn phasor_full_turns_wrap(theta in arb_theta(), k in -3i32..=3) {
+        let p = DaemonPhasor::new(theta);
+        let wrapped = DaemonPhasor::new(theta + f64::from(k) * TAU);
+        prop_assert!(
+            circular_distance(p, wrapped) < 1e-9,
+            "theta {theta} + {k} turns landed at {} instead of {}",
+            wrapped.theta,
+            p.theta
+        );
+    }
+
+    /// 15. Phasor slerp moves along the shorter arc, covering t of it.
+    #[test]
+    fn phasor_slerp_follows_shortest_arc(a in arb_theta(), b in arb_theta(), t in arb_t()) {
+        let (pa, pb) = (DaemonPhasor::new(a), DaemonPhasor::new(b));
+        let mid = pa.slerp(pb, t);
+        prop_assert!(mid.theta >= 0.0 && mid.theta < TAU);
+        let expected = t * circular_distance(pa, pb);
+        prop_assert!(
+            approx_eq(circular_distance(pa, mid), expected, 1e-9),
+            "slerp({}, {}, {t}) = {} is {} from start, expected {expected}",
+            pa.theta,
+            pb.theta,
+            mid.theta,
+            circular_distance(pa, mid)
+        );
+    }
+}
+
+// --- Drift over whole systems ---
+
+const VOCAB: &[&str] = &[
+    "quantum", "particle", "wave", "neural", "network", "memory", "sphere", "phase", "drift",
+    "signal",
+];
+
+/// A system of 80 five-word neighborhoods over `VOCAB` plus a few conscious
+/// memories, so each word has ~40 occurrences and activating most of the
+/// vocabulary in one step crosses the centroid drift threshold.
+fn drift_system(seed: u64) -> DAESystem {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut sys = DAESystem::new("proptest");
+    let mut episode = Episode::new("drift");
+    for _ in 0..80 {
+        let tokens: Vec<String> = (0..5)
+            .map(|_| VOCAB[rng.random_range(0..VOCAB.len())].to_string())
+            .collect();
+        let text = tokens.join(" ");
+        episode.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, &mut rng));
+    }
+    sys.add_episode(episode);
+    for words in VOCAB.chunks(3) {
+        sys.add_to_conscious(&words.join(" "), &mut rng);
+    }
+    sys
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    /// 16. Any sequence of pairwise and centroid drift keeps every position a
+    /// unit quaternion, every phasor in [0, 2pi), and the system consistent.
+    #[test]
+    fn drift_sequences_preserve_invariants(
+        seed in any::<u64>(),
+        steps in prop::collection::vec(
+            prop::collection::vec(0..VOCAB.len(), 1..=VOCAB.len()),
+            1..6
+        )
+    ) {
+        let mut sys = drift_system(seed);
+        for step in steps {
+            let mut refs = Vec::new();
+            for i in step {
+                let activated = sys.activate_word(VOCAB[i]);
+                refs.extend(activated.subconscious);
+                refs.extend(activated.conscious);
+            }
+            QueryEngine::drift_and_consolidate(&mut sys, &refs, 1.0);
+            let violations = check_system(&sys);
+            prop_assert!(violations.is_empty(), "{violations:#?}");
+        }
+    }
+}
+
+/// Mean unit vector `(cos, sin)` of a set of phase angles.
+fn circular_mean(thetas: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (mut cos, mut sin, mut n) = (0.0, 0.0, 0.0);
+    for theta in thetas {
+        cos += theta.cos();
+        sin += theta.sin();
+        n += 1.0;
+    }
+    (cos / n, sin / n)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// 17. A long run of wrapping phase updates, as months of Kuramoto
+    /// coupling and pairwise drift apply them, keeps every theta in
+    /// [0, 2pi) and tracks the same angles as the unwrapped sums.
+    #[test]
+    fn accumulated_phase_updates_stay_wrapped(seed in any::<u64>()) {
+        const PHASORS: usize = 8;
+        const STEPS: usize = 100_000;
+
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut unwrapped: Vec<f64> = (0..PHASORS).map(|_| rng.random_range(0.0..TAU)).collect();
+        let mut phasors: Vec<DaemonPhasor> =
+            unwrapped.iter().map(|&t| DaemonPhasor::new(t)).collect();
+        for step in 0..STEPS {
+            for (p, u) in phasors.iter_mut().zip(&mut unwrapped) {
+                // Mostly small coupling deltas, with tiny negatives that
+                // round to exactly 2pi under a bare rem_euclid
+                let delta = if step % 1000 == 0 {
+                    -1e-17
+                } else {
+                    rng.random_range(-0.5..0.5)
+                };
+                *p = p.wrapping_add(delta);
+                *u += delta;
+                prop_assert!(
+                    p.theta >= 0.0 && p.theta < TAU,
+                    "theta {} out of range at step {step}",
+                    p.theta
+                );
+            }
+        }
+
+        for (p, &u) in phasors.iter().zip(&unwrapped) {
+            let d = circular_distance(*p, DaemonPhasor::new(u));
+            prop_assert!(d < 1e-6, "theta {} drifted {d} from unwrapped {u}", p.theta);
+        }
+        let wrapped_mean = circular_mean(phasors.iter().map(|p| p.theta));
+        let unwrapped_mean = circular_mean(unwrapped.iter().copied());
+        prop_assert!(
+            approx_eq(wrapped_mean.0, unwrapped_mean.0, 1e-6)
+                && approx_eq(wrapped_mean.1, unwrapped_mean.1, 1e-6),
+            "circular mean {wrapped_mean:?} != unwrapped {unwrapped_mean:?}"
+        );
+    }
+}
+
+/// One edit to a system's neighborhoods. Indices pick targets modulo
+/// whatever exists when the edit runs.
+#[derive(Debug, Clone)]
+enum Mutation {
+    /// A subconscious episode; each inner list is one neighborhood's words.
+    AddEpisode(Vec<Vec<usize>>),
+    AddConscious(Vec<usize>),
+    ForgetTerm(usize),
+    ForgetEpisode(usize),
+    ForgetConscious(usize),
+    /// Evict every `stride`-th occurrence of one episode, as GC would.
+    Gc {
+        episode: usize,
+        stride: usize,
+    },
+}
+
+fn arb_words() -> impl Strategy<Value = Vec<usize>> {
+    prop::collection::vec(0..VOCAB.len() * 2, 1..6)
+}
+
+fn arb_mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        prop::collection::vec(arb_words(), 1..4).prop_map(Mutation::AddEpisode),
+        arb_words().prop_map(Mutation::AddConscious),
+        (0..VOCAB.len() * 2).prop_map(Mutation::ForgetTerm),
+        any::<usize>().prop_map(Mutation::ForgetEpisode),
+        any::<usize>().prop_map(Mutation::ForgetConscious),
+        (any::<usize>(), 1usize..4).prop_map(|(episode, stride)| Mutation::Gc { episode, stride }),
+    ]
+}
+
+/// Word `i` of `VOCAB`, capitalized for the upper half of the range so
+/// the counts have to fold case.
+fn vocab_word(i: usize) -> String {
+    let word = VOCAB[i % VOCAB.len()];
+    if i < VOCAB.len() {
+        word.to_string()
+    } else {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn apply_mutation(sys: &mut DAESystem, mutation: &Mutation, rng: &mut SmallRng) {
+    match mutation {
+        Mutation::AddEpisode(neighborhoods) => {
+            let mut episode = Episode::new("incremental");
+            for words in neighborhoods {
+                let tokens: Vec<String> = words.iter().map(|&i| vocab_word(i)).collect();
+                let text = tokens.join(" ");
+                episode.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, rng));
+            }
+            sys.add_episode(episode);
+        }
+        Mutation::AddConscious(words) => {
+            let text: Vec<String> = words.iter().map(|&i| vocab_word(i)).collect();
+            sys.add_to_conscious(&text.join(" "), rng);
+        }
+        Mutation::ForgetTerm(i) => {
+            sys.forget_term(&vocab_word(*i));
+        }
+        Mutation::ForgetEpisode(pick) => {
+            if !sys.episodes.is_empty() {
+                let id = sys.episodes[pick % sys.episodes.len()].id;
+                sys.forget_episode(id);
+            }
+        }
+        Mutation::ForgetConscious(pick) => {
+            let neighborhoods = &sys.conscious_episode.neighborhoods;
+            if !neighborhoods.is_empty() {
+                let id = neighborhoods[pick % neighborhoods.len()].id;
+                sys.forget_conscious(id);
+            }
+        }
+        Mutation::Gc { episode, stride } => {
+            if sys.episodes.is_empty() {
+                return;
+            }
+            let episode = &sys.episodes[episode % sys.episodes.len()];
+            let occurrences = episode
+                .neighborhoods
+                .iter()
+                .flat_map(|n| &n.occurrences)
+                .step_by(*stride)
+                .map(|o| o.id)
+                .collect();
+            sys.apply_gc(&GcDetail {
+                occurrences,
+                ..GcDetail::default()
+            });
+        }
+    }
+}
+
+/// Neighborhoods containing each word, counted the slow way.
+fn counted_document_frequencies(sys: &DAESystem) -> HashMap<String, usize> {
+    let mut df = HashMap::new();
+    for nbhd in sys
+        .episodes
+        .iter()
+        .chain(std::iter::once(&sys.conscious_episode))
+        .flat_map(|e| &e.neighborhoods)
+    {
+        let words: HashSet<String> = nbhd
+            .occurrences
+            .iter()
+            .map(|o| o.word.to_lowercase())
+            .collect();
+        for word in words {
+            *df.entry(word).or_default() += 1;
+        }
+    }
+    df
+}
+
+fn vocab_weights(sys: &mut DAESystem) -> Vec<f64> {
+    VOCAB.iter().map(|w| sys.get_word_weight(w)).collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// 18. Word stats kept up incrementally through any sequence of adds,
+    /// forgets, and GC match a recount, and so do the weights built on them.
+    #[test]
+    fn incremental_word_stats_match_full_rebuild(
+        seed in any::<u64>(),
+        mutations in prop::collection::vec(arb_mutation(), 1..24)
+    ) {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut sys = drift_system(seed);
+        sys.rebuild_indexes();
+        for mutation in &mutations {
+            let generation = sys.word_stats().generation();
+            let before = counted_document_frequencies(&sys);
+            apply_mutation(&mut sys, mutation, &mut rng);
+            let expected = counted_document_frequencies(&sys);
+
+            prop_assert!(!sys.word_stats().is_stale(), "{mutation:?} left the stats stale");
+            prop_assert_eq!(sys.word_stats().counts(), &expected, "after {:?}", mutation);
+            if expected != before {
+                prop_assert!(sys.word_stats().generation() > generation, "{mutation:?}");
+            }
+            // Rebuilding the position indexes cross-checks them in debug builds
+            sys.rebuild_indexes();
+        }
+
+        let incremental = vocab_weights(&mut sys);
+        sys.mark_dirty();
+        prop_assert!(sys.word_stats().is_stale());
+        let rebuilt = vocab_weights(&mut sys);
+        prop_assert_eq!(incremental, rebuilt);
+    }
+}
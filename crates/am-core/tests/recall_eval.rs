@@ -0,0 +1,27 @@
+//! Recall quality regression gate over the built-in fixture suite.
+
+use am_core::eval::{EvalSuite, run_suite};
+
+const SUITE: &str = include_str!("fixtures/recall_suite.yaml");
+
+#[test]
+fn builtin_suite_meets_quality_floor() {
+    let suite: EvalSuite = serde_norway::from_str(SUITE).expect("fixture suite parses");
+    let report = run_suite(&suite);
+
+    for q in &report.queries {
+        eprintln!(
+            "p@{} {:.2} rr {:.2}  {}",
+            report.k, q.precision_at_k, q.reciprocal_rank, q.query
+        );
+    }
+
+    assert_eq!(report.queries.len(), suite.queries.len());
+    assert!(report.mrr >= 0.8, "MRR regressed: {:.3}", report.mrr);
+    assert!(
+        report.mean_precision_at_k >= 0.3,
+        "precision@{} regressed: {:.3}",
+        report.k,
+        report.mean_precision_at_k
+    );
+}
@@ -0,0 +1,336 @@
+//! The brain store on a thread of its own.
+//!
+//! rusqlite is synchronous, and saving a large episode or a whole system
+//! takes seconds. [`AsyncStore`] moves the store onto a dedicated thread
+//! and runs every call there, one at a time in the order they were made.
+//! No store call runs on the caller's thread, so none blocks an async
+//! runtime's worker. A write queued before a read has landed by the time
+//! the read runs. Each call returns a [`Pending`] result that can be
+//! awaited or waited for.
+
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use am_core::{
+    store_trait::{AmStore, BufferedExchange},
+    system::DAESystem,
+};
+
+type Job<S> = Box<dyn FnOnce(&S) + Send>;
+
+/// A store owned by a dedicated thread that runs calls in FIFO order.
+///
+/// Dropping it lets queued calls finish, then drops the store on its
+/// thread.
+pub struct AsyncStore<S> {
+    jobs: Option<Sender<Job<S>>>,
+    /// Calls queued or running.
+    queued: Arc<AtomicUsize>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<S: AmStore + Send + 'static> AsyncStore<S> {
+    /// Move `store` onto a new thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread cannot be spawned.
+    pub fn new(store: S) -> Self {
+        let (tx, rx) = mpsc::channel::<Job<S>>();
+        let thread = std::thread::Builder::new()
+            .name("am-store".to_string())
+            .spawn(move || {
+                for job in rx {
+                    job(&store);
+                }
+            })
+            .expect("failed to spawn store thread");
+        Self {
+            jobs: Some(tx),
+            queued: Arc::new(AtomicUsize::new(0)),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue `job` to run against the store after every call queued
+    /// before it. A job that panics leaves the store thread running; its
+    /// [`Pending`] result panics instead.
+    pub fn run<T, F>(&self, job: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&S) -> T + Send + 'static,
+    {
+        let (reply, pending) = Pending::new();
+        let queued = Arc::clone(&self.queued);
+        queued.fetch_add(1, Ordering::SeqCst);
+        let job: Job<S> = Box::new(move |store| {
+            let value = catch_unwind(AssertUnwindSafe(|| job(store)));
+            // Before the reply, so a caller that has its result sees the
+            // store idle
+            queued.fetch_sub(1, Ordering::SeqCst);
+            match value {
+                Ok(value) => reply.send(value),
+                Err(_) => tracing::error!("store call panicked"),
+            }
+        });
+        let sent = self.jobs.as_ref().map(|jobs| jobs.send(job));
+        if !matches!(sent, Some(Ok(()))) {
+            // The job, and with it the reply, was dropped unrun
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+        }
+        pending
+    }
+
+    /// Whether calls are queued or running, so a new one would wait.
+    pub fn is_busy(&self) -> bool {
+        self.queued.load(Ordering::SeqCst) > 0
+    }
+
+    /// [`AmStore::load_system`] on the store thread.
+    pub fn load_system(&self) -> Pending<Result<DAESystem, S::Error>> {
+        self.run(|store| {
+            let started = Instant::now();
+            let loaded = store.load_system();
+            tracing::debug!("loaded system in {}ms", started.elapsed().as_millis());
+            loaded
+        })
+    }
+
+    /// [`AmStore::save_system`] on the store thread. The system moves to
+    /// the thread for the write and comes back with its result.
+    pub fn save_system(&self, system: DAESystem) -> Pending<(DAESystem, Result<(), S::Error>)> {
+        self.run(move |store| {
+            let saved = store.save_system(&system);
+            (system, saved)
+        })
+    }
+
+    /// [`AmStore::append_buffer`] on the store thread.
+    pub fn append_buffer(
+        &self,
+        user: String,
+        assistant: String,
+    ) -> Pending<Result<usize, S::Error>> {
+        self.run(move |store| store.append_buffer(&user, &assistant))
+    }
+
+    /// [`AmStore::drain_buffer`] on the store thread.
+    pub fn drain_buffer(&self) -> Pending<Result<Vec<BufferedExchange>, S::Error>> {
+        self.run(AmStore::drain_buffer)
+    }
+
+    /// [`AmStore::buffer_count`] on the store thread.
+    pub fn buffer_count(&self) -> Pending<Result<usize, S::Error>> {
+        self.run(AmStore::buffer_count)
+    }
+}
+
+impl<S> Drop for AsyncStore<S> {
+    fn drop(&mut self) {
+        drop(self.jobs.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::error!("store thread panicked");
+        }
+    }
+}
+
+/// The result of a call queued on an [`AsyncStore`].
+///
+/// Await it, or [`wait`](Self::wait) for it from synchronous code. The
+/// call runs whether or not anyone waits for it.
+pub struct Pending<T> {
+    slot: Arc<Slot<T>>,
+}
+
+struct Slot<T> {
+    state: Mutex<SlotState<T>>,
+    ready: Condvar,
+}
+
+struct SlotState<T> {
+    value: Option<T>,
+    /// Set once the call finished or was dropped unrun.
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// The store thread's end of a [`Pending`]. Dropped without a value, it
+/// marks the call done so waiters do not hang.
+struct Reply<T> {
+    slot: Arc<Slot<T>>,
+}
+
+impl<T> Reply<T> {
+    fn send(self, value: T) {
+        self.slot.state.lock().expect("poisoned mutex").value = Some(value);
+    }
+}
+
+impl<T> Drop for Reply<T> {
+    fn drop(&mut self) {
+        let mut state = self.slot.state.lock().expect("poisoned mutex");
+        state.done = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        drop(state);
+        self.slot.ready.notify_all();
+    }
+}
+
+impl<T> Pending<T> {
+    fn new() -> (Reply<T>, Self) {
+        let slot = Arc::new(Slot {
+            state: Mutex::new(SlotState {
+                value: None,
+                done: false,
+                waker: None,
+            }),
+            ready: Condvar::new(),
+        });
+        let reply = Reply {
+            slot: Arc::clone(&slot),
+        };
+        (reply, Self { slot })
+    }
+
+    /// Block until the call finishes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the call panicked.
+    pub fn wait(self) -> T {
+        let mut state = self.slot.state.lock().expect("poisoned mutex");
+        while !state.done {
+            state = self.slot.ready.wait(state).expect("poisoned mutex");
+        }
+        state.value.take().expect("store call panicked")
+    }
+
+    /// Block up to `timeout` for the call. `Timeout` if it is still
+    /// queued or running; `Disconnected` if it panicked or its result was
+    /// already taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvTimeoutError`] when there is no result to take.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let state = self.slot.state.lock().expect("poisoned mutex");
+        let (mut state, _) = self
+            .slot
+            .ready
+            .wait_timeout_while(state, timeout, |state| !state.done)
+            .expect("poisoned mutex");
+        if !state.done {
+            return Err(RecvTimeoutError::Timeout);
+        }
+        state.value.take().ok_or(RecvTimeoutError::Disconnected)
+    }
+}
+
+impl<T> Future for Pending<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the call panicked.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.slot.state.lock().expect("poisoned mutex");
+        if state.done {
+            return Poll::Ready(state.value.take().expect("store call panicked"));
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    use am_core::system::DAESystem;
+    use am_store::memory_store::InMemoryStore;
+
+    use super::AsyncStore;
+
+    #[test]
+    fn calls_run_in_the_order_they_were_made() {
+        let store = AsyncStore::new(InMemoryStore::new());
+        let appends: Vec<_> = (0..20)
+            .map(|i| store.append_buffer(format!("user {i}"), "assistant".to_string()))
+            .collect();
+        let drained = store.drain_buffer();
+        let sizes: Vec<usize> = appends.into_iter().map(|p| p.wait().unwrap()).collect();
+        assert_eq!(sizes, (1..=20).collect::<Vec<_>>());
+        let users: Vec<String> = drained
+            .wait()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.user)
+            .collect();
+        assert_eq!(users.first().map(String::as_str), Some("user 0"));
+        assert_eq!(users.len(), 20);
+        assert_eq!(store.buffer_count().wait().unwrap(), 0);
+    }
+
+    #[test]
+    fn save_system_returns_the_system() {
+        let store = AsyncStore::new(InMemoryStore::new());
+        let mut system = DAESystem::new("test");
+        system.set_actor(Some("tester".to_string()));
+        let (system, saved) = store.save_system(system).wait();
+        saved.unwrap();
+        assert_eq!(system.actor(), Some("tester"));
+        assert!(store.load_system().wait().is_ok());
+    }
+
+    #[test]
+    fn a_panicking_call_leaves_the_store_running() {
+        let store = AsyncStore::new(InMemoryStore::new());
+        let panicked = store.run(|_| -> usize { panic!("boom") });
+        assert!(matches!(
+            panicked.wait_timeout(Duration::from_secs(5)),
+            Err(RecvTimeoutError::Disconnected)
+        ));
+        assert_eq!(store.buffer_count().wait().unwrap(), 0);
+        assert!(!store.is_busy());
+    }
+
+    #[test]
+    fn busy_while_a_call_runs() {
+        let store = AsyncStore::new(InMemoryStore::new());
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let blocked = store.run(move |_| gate.recv().is_ok());
+        assert!(store.is_busy());
+        let queued = store.buffer_count();
+        assert!(matches!(
+            queued.wait_timeout(Duration::from_millis(20)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+        release.send(()).unwrap();
+        assert!(blocked.wait());
+        assert_eq!(queued.wait().unwrap(), 0);
+        assert!(!store.is_busy());
+    }
+
+    #[tokio::test]
+    async fn results_can_be_awaited() {
+        let store = AsyncStore::new(InMemoryStore::new());
+        let size = store
+            .append_buffer("user".to_string(), "assistant".to_string())
+            .await
+            .unwrap();
+        assert_eq!(size, 1);
+        assert_eq!(store.buffer_count().await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,225 @@
+//! Typed tool errors.
+//!
+//! Every tool failure carries an [`ErrorKind`] alongside its message, so a
+//! client can tell a bad argument from a locked database without parsing
+//! text. Over JSON-RPC the kind picks the error code and travels in the
+//! error's `data` as `{"kind": ..., "retriable": ...}`.
+
+use std::fmt;
+
+use am_core::store_trait::{AmStore, StoreErrorKind};
+use serde_json::Value;
+
+/// JSON-RPC `Invalid params`.
+pub const INVALID_PARAMS: i32 = -32602;
+/// MCP `Resource not found`.
+pub const RESOURCE_NOT_FOUND: i32 = -32002;
+/// JSON-RPC `Internal error`.
+pub const INTERNAL_ERROR: i32 = -32603;
+
+/// What went wrong in a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// An argument is missing, malformed, or out of range.
+    InvalidParams,
+    /// Imported state is not valid JSON or not a valid export.
+    InvalidState,
+    /// A `path` argument points outside the allowed directories.
+    PathDenied,
+    /// A referenced episode, neighborhood, or file does not exist.
+    NotFound,
+    /// The tool name is not routed.
+    UnknownTool,
+    /// The embedder turned the tool off.
+    ToolDisabled,
+    /// Memory is still loading in the background.
+    Loading,
+    /// Memory failed to load.
+    LoadFailed,
+    /// Another connection holds the database lock.
+    Busy,
+    /// Stored data failed validation.
+    StoreCorrupt,
+    /// A file read or write failed.
+    Io,
+    /// Any other store failure.
+    Store,
+    /// A bug: serialization of in-memory state failed.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Stable name sent as `data.kind`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidParams => "invalid_params",
+            Self::InvalidState => "invalid_state",
+            Self::PathDenied => "path_denied",
+            Self::NotFound => "not_found",
+            Self::UnknownTool => "unknown_tool",
+            Self::ToolDisabled => "tool_disabled",
+            Self::Loading => "loading",
+            Self::LoadFailed => "load_failed",
+            Self::Busy => "busy",
+            Self::StoreCorrupt => "store_corrupt",
+            Self::Io => "io",
+            Self::Store => "store",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// JSON-RPC error code. Anything the caller can fix by changing the
+    /// arguments is `Invalid params`.
+    pub fn code(self) -> i32 {
+        match self {
+            Self::InvalidParams
+            | Self::InvalidState
+            | Self::PathDenied
+            | Self::UnknownTool
+            | Self::ToolDisabled => INVALID_PARAMS,
+            Self::NotFound => RESOURCE_NOT_FOUND,
+            Self::Loading
+            | Self::LoadFailed
+            | Self::Busy
+            | Self::StoreCorrupt
+            | Self::Io
+            | Self::Store
+            | Self::Internal => INTERNAL_ERROR,
+        }
+    }
+
+    /// Whether the same call may succeed if retried unchanged.
+    pub fn retriable(self) -> bool {
+        matches!(self, Self::Loading | Self::Busy)
+    }
+
+    /// The kind for a store failure.
+    pub fn from_store(kind: StoreErrorKind) -> Self {
+        match kind {
+            StoreErrorKind::Busy => Self::Busy,
+            StoreErrorKind::Corrupt => Self::StoreCorrupt,
+            StoreErrorKind::Io => Self::Io,
+            StoreErrorKind::Other => Self::Store,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed tool call: a kind for clients and a message for people.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl ToolError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidParams, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    /// A store failure, classified by the store. The message is prefixed
+    /// with `[store]` like every other store error the tools report.
+    pub fn store<S: AmStore>(error: &S::Error, message: impl fmt::Display) -> Self {
+        Self::new(
+            ErrorKind::from_store(S::error_kind(error)),
+            format!("[store] {message}"),
+        )
+    }
+
+    /// A file operation failure: a missing file is `NotFound`, anything
+    /// else `Io`.
+    pub fn io(error: &std::io::Error, message: impl Into<String>) -> Self {
+        let kind = if error.kind() == std::io::ErrorKind::NotFound {
+            ErrorKind::NotFound
+        } else {
+            ErrorKind::Io
+        };
+        Self::new(kind, message)
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn code(&self) -> i32 {
+        self.kind.code()
+    }
+
+    pub fn retriable(&self) -> bool {
+        self.kind.retriable()
+    }
+
+    /// The JSON-RPC error `data` payload.
+    pub fn data(&self) -> Value {
+        serde_json::json!({
+            "kind": self.kind.as_str(),
+            "retriable": self.retriable(),
+        })
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retriable_kinds_are_transient() {
+        let retriable: Vec<&str> = [
+            ErrorKind::InvalidParams,
+            ErrorKind::InvalidState,
+            ErrorKind::PathDenied,
+            ErrorKind::NotFound,
+            ErrorKind::UnknownTool,
+            ErrorKind::ToolDisabled,
+            ErrorKind::Loading,
+            ErrorKind::LoadFailed,
+            ErrorKind::Busy,
+            ErrorKind::StoreCorrupt,
+            ErrorKind::Io,
+            ErrorKind::Store,
+            ErrorKind::Internal,
+        ]
+        .into_iter()
+        .filter(|kind| kind.retriable())
+        .map(ErrorKind::as_str)
+        .collect();
+        assert_eq!(retriable, ["loading", "busy"]);
+    }
+
+    #[test]
+    fn test_data_payload_shape() {
+        let err = ToolError::new(ErrorKind::Busy, "[store] database is locked");
+        assert_eq!(err.code(), INTERNAL_ERROR);
+        assert_eq!(err.data()["kind"], "busy");
+        assert_eq!(err.data()["retriable"], true);
+        assert_eq!(err.to_string(), "[store] database is locked");
+    }
+}
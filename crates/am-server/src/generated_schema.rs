@@ -0,0 +1,361 @@
+// AUTO-GENERATED by build.rs from tools.toml - do not edit
+#![allow(clippy::all)]
+#[rustfmt::skip]
+
+pub fn generated_tool_list() -> serde_json::Value {
+serde_json::from_str(r##"{
+  "tools": [
+    {
+      "description": "Query geometric memory. Call this at the START of every session with the user's first message to recall relevant context from past sessions. Returns conscious recall (insights you previously marked important), subconscious recall (relevant past conversations/documents), and novel connections (lateral associations; the header names the query words that led there, e.g. `NOVEL CONNECTION (via: 'backpressure')`). Use the returned context silently - weave it into your response naturally without announcing 'I remember...'. When context comes back empty or thin, `candidates` (per-category counts before selection) and `suggestion` tell whether memory has nothing on the topic or had matches that scored low, so you can rephrase or broaden the query. An empty context also carries `empty_reason`: `no_matching_words` (nothing in memory shares a word with the query), `all_deduped` (matches were already recalled this session), `below_thresholds` (matches fell to score cutoffs or the token budget), or `still_loading` (a large memory is still loading at startup; retry shortly). Say plainly that memory has nothing relevant instead of apologizing or inventing recall. `confidence` rates the returned context from its best match, scaled to the size of memory: `none` (nothing recalled), `low` (only weak matches; fall back to exploring the project files), `medium`, or `high`.",
+      "inputSchema": {
+        "properties": {
+          "actor": {
+            "description": "Only recall memories created by this actor (the model or agent name recorded when they were written, e.g. `claude-code`), for this query only. Memories with no recorded actor are left out.",
+            "type": "string"
+          },
+          "candidate_limit": {
+            "description": "How many `ranked_candidates` to return with `include_candidates` (default 20, at most 200).",
+            "type": "integer"
+          },
+          "debug": {
+            "description": "Include a debug payload with the IDF weight of each query token (after any word bias), the active word biases, and the feedback score of each recalled or indexed neighborhood that has one, for diagnosing why a query recalls what it does.",
+            "type": "boolean"
+          },
+          "include_archived": {
+            "description": "Also search episodes archived by `am gc --archive`, for this query only. Archived memories are cold and skipped by default; use this for an explicit deep search when normal recall comes back thin. The response reports how many archived episodes were searched in `archived_episodes`.",
+            "type": "boolean"
+          },
+          "include_candidates": {
+            "description": "Also return `ranked_candidates`: the best-ranked memories, recalled or not, each with its `id`, `category`, `score`, `tokens`, `episode`, a `preview` of its first 200 characters, and whether it is `included` in the context. For callers that rerank recall themselves, e.g. with an embedding model, and want am only for the geometric retrieval. Requires `max_tokens`.",
+            "type": "boolean"
+          },
+          "include_epochs": {
+            "description": "Also search epoch files (`brain-2024.db` and so on), which hold cold episodes moved out of the active database, for this query only. A query that matches nothing in the active database searches them automatically and reports `epoch_fallback: true`. The response reports how many epoch episodes were searched in `epoch_episodes`.",
+            "type": "boolean"
+          },
+          "learn": {
+            "description": "Default true. Set false to preview recall without changing memory: nothing is activated, drifted, saved, or counted as recalled this session, and earlier recall this session is not discounted. Use it to look before committing, e.g. for dashboards or when probing several phrasings.",
+            "type": "boolean"
+          },
+          "max_tokens": {
+            "description": "Optional maximum token budget for composed context. When provided, uses budget-aware composition that fits the best-scoring fragments within the token limit. Nancy's prompt compiler uses this to say \"give me the best context that fits in N tokens\".",
+            "type": "integer"
+          },
+          "min_activated_words": {
+            "description": "Minimum distinct query words a memory must match to be recalled, for this query only (default from the store, normally 1). Set 2 to drop memories that share a single common word with the query. Dropped matches are counted in `candidates.filtered`.",
+            "type": "integer"
+          },
+          "min_score": {
+            "description": "Minimum candidate score, for this query only (default from the store, normally 0 = off). Memories scoring below it are dropped before selection and counted in `candidates.filtered`.",
+            "type": "number"
+          },
+          "project": {
+            "description": "Favour memories tagged for this project, for this query only, instead of the server's current project. Memories tagged for other projects score lower but stay recallable. When no memory is tagged for the project, nothing is favoured and the response says so in `project_note`.",
+            "type": "string"
+          },
+          "speculative": {
+            "description": "Run the query exactly as a learning query would, activation, drift, and phase coupling included, then roll memory back once the context is composed. Nothing is saved or counted as recalled, and the buffer is not flushed. Unlike `learn: false`, recall reflects the drift this query would cause, so it answers \"what would this prompt recall?\" for comparing candidate prompts. The response carries `speculative: true`. Ignored when `learn` is false.",
+            "type": "boolean"
+          },
+          "text": {
+            "description": "The text to query the memory system with",
+            "type": "string"
+          }
+        },
+        "required": [
+          "text"
+        ],
+        "type": "object"
+      },
+      "name": "am_query"
+    },
+    {
+      "description": "Two-phase retrieval: get a compact index of matching memories without full content. Returns neighborhood IDs, types, scores, summaries (first 100 chars), and token estimates. Use this first to see what's available (~50-100 tokens/entry vs ~500-1000 for full content), then call am_retrieve with selected IDs to fetch only the memories you need. Reduces context pollution for large manifolds.",
+      "inputSchema": {
+        "properties": {
+          "text": {
+            "description": "The query text to search memory for",
+            "type": "string"
+          }
+        },
+        "required": [
+          "text"
+        ],
+        "type": "object"
+      },
+      "name": "am_query_index"
+    },
+    {
+      "description": "Retrieve full content for specific neighborhood IDs. Phase 2 of two-phase retrieval: after reviewing am_query_index results, call this with the IDs of memories you want to see in full. Returns complete text for each requested neighborhood.",
+      "inputSchema": {
+        "properties": {
+          "ids": {
+            "description": "Neighborhood UUIDs to retrieve full content for (from am_query_index results)",
+            "items": {
+              "type": "string"
+            },
+            "type": "array"
+          }
+        },
+        "required": [
+          "ids"
+        ],
+        "type": "object"
+      },
+      "name": "am_retrieve"
+    },
+    {
+      "description": "Check whether memory holds anything about a topic, without recalling it. Returns `known`, per-category candidate counts (`candidates`, as in am_query), and the best candidate's `top_score`. Read-only and cheap: nothing is activated, drifted, or marked as recalled, so probing never changes what later queries return. Use it to decide whether an am_query is worth its tokens, or to answer \"have we discussed X?\".",
+      "inputSchema": {
+        "properties": {
+          "text": {
+            "description": "The text to check memory for",
+            "type": "string"
+          }
+        },
+        "required": [
+          "text"
+        ],
+        "type": "object"
+      },
+      "name": "am_knows"
+    },
+    {
+      "description": "Strengthen memory connections from your response text. Call this after giving a substantive response - it activates matching memories, drifts related concepts closer together on the manifold, and applies phase coupling. This is how the memory system consolidates over time. Returns the strongest activated words, the neighborhoods that moved most, and whether phase coupling fired. Not needed for every response - use after meaningful technical exchanges, not simple acknowledgements.",
+      "inputSchema": {
+        "properties": {
+          "drift_scale": {
+            "description": "How far activated memories drift, from 0.0 (no drift) to 1.0 (as far as a query). Defaults to the configured response_drift_scale (0.3): responses are long and noisy, so they consolidate more gently than queries.",
+            "type": "number"
+          },
+          "text": {
+            "description": "Response text to strengthen connections for",
+            "type": "string"
+          }
+        },
+        "required": [
+          "text"
+        ],
+        "type": "object"
+      },
+      "name": "am_activate_response"
+    },
+    {
+      "description": "Mark an insight as conscious memory - something worth remembering across sessions and across projects. Use for: architecture decisions, user preferences, recurring patterns, hard-won debugging insights, project conventions. These surface as CONSCIOUS RECALL in future queries. Be selective - mark only genuinely reusable insights, not routine facts. Writes to brain-wide memory, queryable from any project. A memory specific to one repository can say so with a prefix like 'DECISION (repo-name):'; it still recalls everywhere, ranked lower outside that project. To replace outdated memories, pass their UUIDs (from am_query recalled_ids) in the supersedes array. A text longer than about 600 characters is stored as several chunks (the result's `chunks`); recall offers the whole text when it fits the budget and only the matching chunk when it does not, so one long insight is fine but a focused one recalls better. Marking something already in conscious memory (nearly the same words, same type and project) does not add a copy: the existing memory is reinforced and the result carries `deduplicated: true` with its ID in `reinforced`; pass `force: true` to store it anyway.",
+      "inputSchema": {
+        "properties": {
+          "force": {
+            "description": "Store the text as a new memory even when it repeats an existing conscious memory, instead of reinforcing that memory.",
+            "type": "boolean"
+          },
+          "supersedes": {
+            "description": "Optional list of neighborhood UUIDs that this new memory supersedes. Superseded neighborhoods are permanently excluded from future recall. Use recalled_ids from am_query to identify which memories to replace.",
+            "items": {
+              "type": "string"
+            },
+            "type": "array"
+          },
+          "text": {
+            "description": "Text to mark as conscious memory (may contain salient tags)",
+            "type": "string"
+          }
+        },
+        "required": [
+          "text"
+        ],
+        "type": "object"
+      },
+      "name": "am_salient"
+    },
+    {
+      "description": "Buffer a conversation exchange. Call with each substantive user/assistant exchange pair. After 3 exchanges (the server's buffer.threshold), automatically creates a memory episode on the geometric manifold. This is how conversations become searchable memories in future sessions. Skip trivial exchanges (greetings, confirmations) - buffer the ones with real content. When the server enables buffer.auto_salient, lines opening with DECISION: or PREFERENCE: in the flushed exchanges also become conscious memories, listed by ID in `auto_salient`.",
+      "inputSchema": {
+        "properties": {
+          "assistant": {
+            "description": "Assistant's response text",
+            "type": "string"
+          },
+          "user": {
+            "description": "User's message text",
+            "type": "string"
+          }
+        },
+        "required": [
+          "user",
+          "assistant"
+        ],
+        "type": "object"
+      },
+      "name": "am_buffer"
+    },
+    {
+      "description": "Ingest a document as a memory episode. Use when the user shares important reference material (design docs, specs, READMEs) that should be searchable in future sessions. Text is chunked into neighborhoods and placed on the geometric manifold; pass `chunks` instead of `text` to keep your own chunk boundaries.",
+      "inputSchema": {
+        "properties": {
+          "chunks": {
+            "description": "Pre-chunked document: each {text, name?, tags?} becomes exactly one neighborhood, all in one episode. Use when you already split by function, ticket, or section. name and tags words are searchable but not part of the stored text. Pass this or text, not both. At most 10000 chunks of 64KB each; empty chunks are rejected.",
+            "items": {
+              "properties": {
+                "name": {
+                  "description": "Optional chunk label; its words are searchable",
+                  "type": "string"
+                },
+                "tags": {
+                  "description": "Optional tags; their words are searchable",
+                  "items": {
+                    "type": "string"
+                  },
+                  "type": "array"
+                },
+                "text": {
+                  "description": "Chunk text, stored as one neighborhood",
+                  "type": "string"
+                }
+              },
+              "required": [
+                "text"
+              ],
+              "type": "object"
+            },
+            "type": "array"
+          },
+          "dry_run": {
+            "description": "Chunk and tokenize without storing anything. Returns the would-be neighborhoods and occurrences, estimated_bytes of database growth (at the store's current bytes_per_occurrence), and the first few chunks. Default false.",
+            "type": "boolean"
+          },
+          "name": {
+            "description": "Optional name for the episode",
+            "type": "string"
+          },
+          "text": {
+            "description": "Document text to ingest, split into 3-sentence chunks. Pass this or chunks, not both.",
+            "type": "string"
+          }
+        },
+        "type": "object"
+      },
+      "name": "am_ingest"
+    },
+    {
+      "description": "Get memory system statistics: total occurrences (N), episode count, and conscious memory count. dirty_unsaved is true when a store write failed and memory holds changes the database does not. store_busy is true when another call's writes were still running; database size and activation figures are then from before them. While a large memory is still loading at startup, only `loading` (occurrence count, elapsed and estimated ms) and dirty_unsaved are returned. Useful for understanding memory state. Not needed routinely - call when the user asks about memory or for diagnostics.",
+      "inputSchema": {
+        "properties": {},
+        "type": "object"
+      },
+      "name": "am_stats"
+    },
+    {
+      "description": "Export the full DAE system state as v0.7.2 compatible JSON.",
+      "inputSchema": {
+        "properties": {
+          "path": {
+            "description": "Write the export to this file and return its path and size instead of the JSON. Must be inside an allowed directory (the project root, the am data directory, or mcp.allowed_paths in config).",
+            "type": "string"
+          }
+        },
+        "type": "object"
+      },
+      "name": "am_export"
+    },
+    {
+      "description": "Import a full DAE system state from v0.7.2 compatible JSON. Replaces current state. The file is validated first: non-unit quaternions are normalized with a warning, while unparseable fields, zero-length quaternions, and duplicate IDs are errors that refuse the import unless `lenient` is set. The response's `report` lists every error and warning with its location (e.g. `system.episodes[3] (\"notes\").neighborhoods[5]`) and counts what was dropped.",
+      "inputSchema": {
+        "properties": {
+          "lenient": {
+            "description": "Skip the neighborhoods and episodes that fail validation and import the rest, instead of refusing the whole file. Default false.",
+            "type": "boolean"
+          },
+          "path": {
+            "description": "Read the state JSON from this file instead of passing it inline. Must be inside an allowed directory (the project root, the am data directory, or mcp.allowed_paths in config).",
+            "type": "string"
+          },
+          "state": {
+            "description": "Full state JSON to import. Pass this or path, not both.",
+            "type": "object"
+          }
+        },
+        "type": "object"
+      },
+      "name": "am_import"
+    },
+    {
+      "description": "Provide relevance feedback on recalled memories. Call this when you know whether a recalled memory was actually helpful (boost) or unhelpful (demote). Boost drifts the memory's occurrences closer to where they were needed on the manifold and increases activation. Demote decays activation, making the memory less prominent in future queries. This is how the memory system learns what works.",
+      "inputSchema": {
+        "properties": {
+          "neighborhood_ids": {
+            "description": "UUIDs of the neighborhoods that were recalled and shown to the user",
+            "items": {
+              "type": "string"
+            },
+            "type": "array"
+          },
+          "query": {
+            "description": "The original query text that produced the recall",
+            "type": "string"
+          },
+          "signal": {
+            "description": "Feedback signal: \"boost\" if the recall was helpful, \"demote\" if not",
+            "enum": [
+              "boost",
+              "demote"
+            ],
+            "type": "string"
+          }
+        },
+        "required": [
+          "query",
+          "neighborhood_ids",
+          "signal"
+        ],
+        "type": "object"
+      },
+      "name": "am_feedback"
+    },
+    {
+      "description": "Batch query: process multiple queries in a single pass with amortized IDF computation. Use when dispatching context to multiple workers simultaneously - activates the union of all query tokens once, drifts once, then scores every query against that same post-drift state. The batch learns once (one drift per batch, not per query), so results do not depend on query order. Much more efficient than N separate am_query calls. Each query can have its own token budget.",
+      "inputSchema": {
+        "properties": {
+          "queries": {
+            "description": "List of queries to process in a single batch, each `{query, max_tokens?, project?}`. IDF computation is amortized across all queries - much more efficient than querying one at a time when dispatching to multiple workers. `project` makes that query favour memories tagged for the given project, so one batch can serve workers in different repositories; a result whose project has no tagged memories carries a `project_note`.",
+            "items": {
+              "properties": {
+                "max_tokens": {
+                  "description": "Optional token budget for this query's context",
+                  "type": "integer"
+                },
+                "query": {
+                  "description": "The query text",
+                  "type": "string"
+                }
+              },
+              "required": [
+                "query"
+              ],
+              "type": "object"
+            },
+            "type": "array"
+          }
+        },
+        "required": [
+          "queries"
+        ],
+        "type": "object"
+      },
+      "name": "am_batch_query"
+    },
+    {
+      "description": "List settled decisions: every conscious memory marked as a decision (via a DECISION: prefix on am_salient), newest first. Each entry has its id, text, created_at, and whether a later memory superseded it. Use before re-opening a design question to check what was already decided - no query wording needed. Superseded decisions are listed but flagged; follow superseded_by to the replacement.",
+      "inputSchema": {
+        "properties": {
+          "keyword": {
+            "description": "Only list decisions whose text contains this keyword (case-insensitive).",
+            "type": "string"
+          }
+        },
+        "type": "object"
+      },
+      "name": "am_decisions"
+    }
+  ]
+}"##).expect("generated tool list is valid JSON")
+}
@@ -8,8 +8,7 @@ use std::io::{self, BufRead, Write};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[path = "generated_schema.rs"]
-mod generated_schema;
+use crate::error::ToolError;
 
 // ── Constants ────────────────────────────────────────────────────
 
@@ -18,7 +17,13 @@ const PROTOCOL_VERSION: &str = "2024-11-05";
 const SERVER_INSTRUCTIONS: &str = "\
 Query geometric memory at the START of every session with am_query. \
 Buffer substantive exchanges with am_buffer. Mark important insights \
-with am_salient. Use am_feedback to reinforce helpful recall.";
+with am_salient. Use am_feedback to reinforce helpful recall. \
+When am_query returns an empty context, its empty_reason field says why \
+(no_matching_words, all_deduped, below_thresholds): state plainly that \
+memory has nothing relevant rather than apologizing or inventing recall. \
+Its confidence field (none, low, medium, high) rates the recalled \
+context; at none or low, treat recall as unreliable and explore the \
+project files instead.";
 
 // ── JSON-RPC Types ───────────────────────────────────────────────
 
@@ -92,16 +97,24 @@ pub fn tool_result_error(message: &str) -> Value {
 
 /// Run the JSON-RPC stdio loop.
 ///
-/// `dispatch_tool` is called for each `tools/call` request with the
-/// tool name and arguments. Returns `Ok(Value)` on success or
-/// `Err(String)` on tool-level error.
+/// `tool_list` answers `tools/list` (see `AmServer::tool_list`).
+/// `on_initialize` receives the `initialize` request's params (see
+/// `AmServer::observe_client_info`). `dispatch_tool` is called for each `tools/call` request with the
+/// tool name and arguments. Returns `Ok(Value)` on success or a
+/// [`ToolError`], which is answered with a JSON-RPC error carrying the
+/// error's code and `{"kind", "retriable"}` data.
 ///
 /// # Errors
 /// Returns an error if stdin/stdout I/O fails (not for protocol errors,
 /// which are handled inline).
-pub fn run_stdio_loop<F>(mut dispatch_tool: F) -> anyhow::Result<()>
+pub fn run_stdio_loop<I, F>(
+    tool_list: &Value,
+    mut on_initialize: I,
+    mut dispatch_tool: F,
+) -> anyhow::Result<()>
 where
-    F: FnMut(&str, &Value) -> Result<Value, String>,
+    I: FnMut(&Value),
+    F: FnMut(&str, &Value) -> Result<Value, ToolError>,
 {
     let stdin = io::stdin();
     let mut stdout = io::stdout();
@@ -132,12 +145,14 @@ where
         let id = request.id.clone().unwrap_or(Value::Null);
 
         let response = match request.method.as_str() {
-            "initialize" => Some(JsonRpcResponse::success(id, handle_initialize())),
+            "initialize" => {
+                if let Some(params) = &request.params {
+                    on_initialize(params);
+                }
+                Some(JsonRpcResponse::success(id, handle_initialize()))
+            }
             _ if request.method.starts_with("notifications/") => None,
-            "tools/list" => Some(JsonRpcResponse::success(
-                id,
-                generated_schema::generated_tool_list(),
-            )),
+            "tools/list" => Some(JsonRpcResponse::success(id, tool_list.clone())),
             "tools/call" => Some(handle_tool_call(id, &request.params, &mut dispatch_tool)),
             "ping" => Some(JsonRpcResponse::success(id, serde_json::json!({}))),
             _ => Some(JsonRpcResponse::error(
@@ -181,7 +196,7 @@ fn handle_initialize() -> Value {
 
 fn handle_tool_call<F>(id: Value, params: &Option<Value>, dispatch_tool: &mut F) -> JsonRpcResponse
 where
-    F: FnMut(&str, &Value) -> Result<Value, String>,
+    F: FnMut(&str, &Value) -> Result<Value, ToolError>,
 {
     let Some(params) = params.as_ref() else {
         return JsonRpcResponse::error(
@@ -212,7 +227,14 @@ where
 
     match dispatch_tool(tool_name, &arguments) {
         Ok(result) => JsonRpcResponse::success(id, result),
-        Err(msg) => JsonRpcResponse::success(id, tool_result_error(&msg)),
+        Err(e) => JsonRpcResponse::error(
+            id,
+            JsonRpcError {
+                code: e.code(),
+                message: e.message().to_owned(),
+                data: Some(e.data()),
+            },
+        ),
     }
 }
 
@@ -229,10 +251,10 @@ mod tests {
     }
 
     #[test]
-    fn test_tool_list_has_12_tools() {
-        let list = generated_schema::generated_tool_list();
+    fn test_tool_list_has_14_tools() {
+        let list = crate::generated_schema::generated_tool_list();
         let tools = list["tools"].as_array().expect("tools should be an array");
-        assert_eq!(tools.len(), 12);
+        assert_eq!(tools.len(), 14);
     }
 
     #[test]
@@ -277,4 +299,20 @@ mod tests {
         assert!(resp.result.is_some());
         assert!(resp.error.is_none());
     }
+
+    #[test]
+    fn test_handle_tool_call_error_carries_kind() {
+        let params = serde_json::json!({"name": "am_feedback", "arguments": {}});
+        let resp = handle_tool_call(Value::Number(1.into()), &Some(params), &mut |_, _| {
+            Err(ToolError::invalid_params("invalid UUID: nope"))
+        });
+        assert!(resp.result.is_none());
+        let error = resp.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert_eq!(error.message, "invalid UUID: nope");
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({"kind": "invalid_params", "retriable": false}))
+        );
+    }
 }
@@ -0,0 +1,20 @@
+//! MCP tool server for the DAE attention engine.
+//!
+//! `AmServer` exposes a brain store as the `am_*` tools. The `am` binary
+//! wires it to stdio and HTTP; embedders configure their own with
+//! [`AmServer::builder`] and feed tool calls to
+//! [`AmServer::dispatch_tool`] or [`jsonrpc::run_stdio_loop`]. The server
+//! keeps its store on an [`AsyncStore`] thread.
+
+mod async_store;
+pub mod error;
+mod generated_schema;
+pub mod jsonrpc;
+mod server;
+
+pub use async_store::{AsyncStore, Pending};
+pub use error::{ErrorKind, ToolError};
+pub use server::{
+    AmServer, AmServerBuilder, FlushOptions, FlushedEpisode, LAZY_LOAD_THRESHOLD, LOAD_WAIT,
+    TOOL_NAMES, flush_orphaned_buffer,
+};
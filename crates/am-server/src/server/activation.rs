@@ -0,0 +1,299 @@
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use am_core::{
+    feedback::{FeedbackSignal, apply_feedback},
+    query::QueryEngine,
+    salient::{extract_salient_marks, mark_salient},
+    store_trait::{AmStore, AuditEvent, AuditKind},
+};
+
+use super::{AmServer, ServerState, check_input_size, episode_header, parse_params};
+use crate::error::ToolError;
+use crate::jsonrpc::tool_result_text;
+
+/// Cap on words and neighborhoods reported by `am_activate_response`.
+const ACTIVATE_REPORT_LIMIT: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ActivateResponseRequest {
+    /// Response text to strengthen connections for
+    text: String,
+    /// Drift scale in [0, 1]. Defaults to the configured response scale.
+    drift_scale: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct SalientRequest {
+    /// Text to mark as conscious memory (may contain salient tags)
+    text: String,
+    /// Optional list of neighborhood UUIDs that this new memory supersedes.
+    #[serde(default)]
+    supersedes: Vec<String>,
+    /// Store the text even when it repeats an existing conscious memory.
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct FeedbackRequest {
+    /// The original query text that produced the recall
+    query: String,
+    /// UUIDs of the neighborhoods that were recalled and shown to the user
+    neighborhood_ids: Vec<String>,
+    /// Feedback signal: "boost" if the recall was helpful, "demote" if not
+    signal: String,
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    pub(super) fn am_activate_response(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: ActivateResponseRequest = parse_params(args)?;
+        check_input_size(&req.text, "text")?;
+        if req.drift_scale.is_some_and(|s| !(0.0..=1.0).contains(&s)) {
+            return Err(ToolError::invalid_params(
+                "invalid params: drift_scale must be between 0 and 1",
+            ));
+        }
+
+        let mut state = self.lock_state()?;
+        let system = &mut state.system;
+
+        let report = QueryEngine::activate_response(
+            system,
+            &req.text,
+            ACTIVATE_REPORT_LIMIT,
+            req.drift_scale,
+        );
+        let write = self.queue_manifest(system, &report.manifest, None);
+        let stats = Self::stats_json(system);
+        drop(state);
+        self.finish_save(write)
+            .map_err(|e| self.save_failed("activate_response", e))?;
+
+        let top_words: Vec<Value> = report
+            .top_words
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "word": w.word,
+                    "occurrences": w.occurrences,
+                    "score": w.score,
+                })
+            })
+            .collect();
+        let moved: Vec<Value> = report
+            .moved
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "id": m.id.to_string(),
+                    "episode": m.episode_name,
+                    "mean_delta": m.mean_delta,
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "activated": report.activated,
+            "strengthened": {
+                "top_words": top_words,
+                "moved_neighborhoods": moved,
+                "coupling": {
+                    "fired": report.coupled_word_groups > 0,
+                    "word_groups": report.coupled_word_groups,
+                },
+            },
+            "stats": stats,
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_salient(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: SalientRequest = parse_params(args)?;
+        check_input_size(&req.text, "text")?;
+
+        let mut state = self.lock_state()?;
+        let ServerState { system, rng, .. } = &mut *state;
+
+        // Track how many neighborhoods exist before adding new ones
+        let nbhd_before = system.conscious_episode.neighborhoods.len();
+
+        let mut marks = extract_salient_marks(system, &req.text, req.force, rng);
+        let new_id = if marks.is_empty() {
+            // No <salient> tags found - mark the whole text as salient
+            // with automatic type detection from DECISION:/PREFERENCE: prefix
+            let mark = mark_salient(system, &req.text, req.force, rng);
+            marks.push(mark);
+            Some(mark.id)
+        } else {
+            None
+        };
+
+        let stored = marks.len() as u32;
+
+        // Repeats reinforced an existing memory; those already stored need
+        // their activation and timestamp written back
+        let mut reinforced: Vec<Uuid> = Vec::new();
+        for mark in marks.iter().filter(|m| m.deduplicated) {
+            if !reinforced.contains(&mark.id) {
+                reinforced.push(mark.id);
+            }
+        }
+        let existing = &system.conscious_episode.neighborhoods[..nbhd_before];
+        let activated: Vec<Uuid> = marks
+            .iter()
+            .filter(|m| m.deduplicated)
+            .filter_map(|m| existing.iter().find(|n| n.id == m.id))
+            .flat_map(|n| n.occurrences.iter().map(|o| o.id))
+            .collect();
+        let restamped: Vec<(Uuid, String)> = existing
+            .iter()
+            .filter(|n| reinforced.contains(&n.id))
+            .filter_map(|n| Some((n.id, n.created_at.clone()?)))
+            .collect();
+
+        // Process supersedes: mark old neighborhoods as superseded by the new one
+        let mut superseded = Vec::new();
+        if let Some(new_id) = new_id {
+            for old_id_str in &req.supersedes {
+                if let Ok(old_id) = Uuid::parse_str(old_id_str) {
+                    if old_id == new_id {
+                        tracing::warn!(
+                            "supersedes target is the memory being marked: {old_id_str}"
+                        );
+                    } else if system.mark_superseded(old_id, new_id) {
+                        superseded.push((old_id, new_id));
+                    } else {
+                        tracing::warn!("supersedes target not found: {old_id_str}");
+                    }
+                } else {
+                    tracing::warn!("invalid UUID in supersedes: {old_id_str}");
+                }
+            }
+        } else if !req.supersedes.is_empty() {
+            tracing::warn!(
+                "supersedes ignored: multiple salient tags produce multiple neighborhoods, \
+                 supersession only applies to single-neighborhood salient calls"
+            );
+        }
+
+        // Persist the new neighborhoods and supersessions, audited together
+        let mut event = AuditEvent::new(AuditKind::Salient, u64::from(stored)).with_context(system);
+        let mut detail = Vec::new();
+        if !superseded.is_empty() {
+            detail.push(format!("superseded {}", superseded.len()));
+        }
+        if !reinforced.is_empty() {
+            detail.push(format!("reinforced {}", reinforced.len()));
+        }
+        if !detail.is_empty() {
+            event = event.with_detail(detail.join(", "));
+        }
+        let conscious = &system.conscious_episode;
+        let header = episode_header(conscious);
+        let created = conscious.neighborhoods[nbhd_before..].to_vec();
+        let written = superseded.clone();
+        let write = self.store.run(move |store| {
+            store.audited(&event, || {
+                for nbhd in &created {
+                    store.save_neighborhood(&header, nbhd)?;
+                }
+                for &(old_id, new_id) in &written {
+                    store.mark_superseded(old_id, new_id)?;
+                }
+                for (id, created_at) in &restamped {
+                    store.restamp_neighborhood(*id, created_at)?;
+                }
+                store.batch_increment_activation(&activated)
+            })
+        });
+
+        let mut result = serde_json::json!({
+            "stored": stored,
+            "stats": Self::stats_json(system),
+        });
+        if !superseded.is_empty() {
+            result["superseded"] = serde_json::json!(superseded.len());
+        }
+        if !reinforced.is_empty() {
+            let ids: Vec<String> = reinforced.iter().map(Uuid::to_string).collect();
+            result["deduplicated"] = serde_json::json!(true);
+            result["reinforced"] = serde_json::json!(ids);
+        }
+        // Long texts were split; say how many neighborhoods hold them
+        let created = conscious.neighborhoods.len() - nbhd_before;
+        if created > stored as usize {
+            result["chunks"] = serde_json::json!(created);
+        }
+        drop(state);
+        write.wait().map_err(|e| self.save_failed("salient", e))?;
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_feedback(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: FeedbackRequest = parse_params(args)?;
+        check_input_size(&req.query, "query")?;
+
+        let mut state = self.lock_state()?;
+        let system = &mut state.system;
+
+        let signal = match req.signal.to_lowercase().as_str() {
+            "boost" => FeedbackSignal::Boost,
+            "demote" => FeedbackSignal::Demote,
+            other => {
+                return Err(ToolError::invalid_params(format!(
+                    "signal must be 'boost' or 'demote', got '{other}'"
+                )));
+            }
+        };
+
+        let neighborhood_ids: Vec<Uuid> = req
+            .neighborhood_ids
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .collect();
+
+        if neighborhood_ids.is_empty() {
+            return Err(ToolError::invalid_params(
+                "no valid neighborhood UUIDs provided",
+            ));
+        }
+
+        let feedback = apply_feedback(system, &req.query, &neighborhood_ids, signal);
+
+        let event = AuditEvent::new(
+            AuditKind::Feedback,
+            (feedback.boosted + feedback.demoted) as u64,
+        )
+        .with_context(system)
+        .with_detail(format!(
+            "{} on {} neighborhoods",
+            req.signal.to_lowercase(),
+            neighborhood_ids.len()
+        ));
+        let write = self.queue_manifest(system, &feedback.manifest, Some(event));
+
+        let result = serde_json::json!({
+            "boosted": feedback.boosted,
+            "demoted": feedback.demoted,
+            "centroid": feedback.centroid.map(|c| serde_json::json!({
+                "w": c.w, "x": c.x, "y": c.y, "z": c.z
+            })),
+            "stats": Self::stats_json(system),
+        });
+        drop(state);
+        self.finish_save(write)
+            .map_err(|e| self.save_failed("feedback", e))?;
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use am_core::{
+    compose::{BudgetConfig, ComposeConfig, RerankStage},
+    store_trait::AmStore,
+    system::DAESystem,
+};
+use am_store::config::{BufferConfig, PlanConfig};
+
+use super::loading::{LAZY_LOAD_THRESHOLD, LOAD_WAIT, PendingLoad, Preflight};
+use super::metrics;
+use super::paths::PathPolicy;
+use super::{AmServer, FlushOptions, ServerState, TOOL_NAMES};
+use crate::async_store::AsyncStore;
+
+/// Settings applied to the system once it is loaded.
+pub(super) struct SystemSettings {
+    plan_verbs: Option<Vec<String>>,
+    compose: Option<ComposeConfig>,
+    rerank: Option<RerankStage>,
+    actor: Option<String>,
+}
+
+impl SystemSettings {
+    pub fn apply(self, system: &mut DAESystem) {
+        if let Some(verbs) = self.plan_verbs {
+            system.set_plan_verbs(verbs);
+        }
+        if let Some(config) = self.compose {
+            system.set_compose_config(config);
+        }
+        if self.rerank.is_some() {
+            system.set_rerank_stage(self.rerank);
+        }
+        if self.actor.is_some() {
+            system.set_actor(self.actor);
+        }
+    }
+}
+
+/// Configures an [`AmServer`] before it loads the brain.
+///
+/// ```
+/// use am_core::compose::{ComposeConfig, LexicalOverlapReranker, RerankStage};
+/// use am_server::AmServer;
+/// use am_store::project::BrainStore;
+///
+/// let server = AmServer::builder(BrainStore::open_in_memory()?)
+///     .compose_config(ComposeConfig { min_activated_words: 2, ..ComposeConfig::default() })
+///     .reranker(RerankStage::new(LexicalOverlapReranker))
+///     .disable_tool("am_import")
+///     .build()?;
+/// assert!(server.dispatch_tool("am_import", &serde_json::json!({})).is_err());
+/// # Ok::<(), am_store::error::StoreError>(())
+/// ```
+pub struct AmServerBuilder<S: AmStore> {
+    store: S,
+    plans: PlanConfig,
+    buffer: BufferConfig,
+    compose: Option<ComposeConfig>,
+    budget: BudgetConfig,
+    rerank: Option<RerankStage>,
+    actor: Option<String>,
+    disabled_tools: HashSet<&'static str>,
+    unsaved_marker: Option<PathBuf>,
+    allowed_paths: Option<Vec<PathBuf>>,
+    max_ingest_bytes: Option<usize>,
+    lazy_load_threshold: u64,
+    load_wait: Duration,
+}
+
+impl<S: AmStore> AmServerBuilder<S> {
+    pub(super) fn new(store: S) -> Self {
+        Self {
+            store,
+            plans: PlanConfig::default(),
+            buffer: BufferConfig::default(),
+            compose: None,
+            budget: BudgetConfig::default(),
+            rerank: None,
+            actor: None,
+            disabled_tools: HashSet::new(),
+            unsaved_marker: None,
+            allowed_paths: None,
+            max_ingest_bytes: None,
+            lazy_load_threshold: LAZY_LOAD_THRESHOLD,
+            load_wait: LOAD_WAIT,
+        }
+    }
+
+    /// Plan settings: opt-in plan tagging for buffered conversation and
+    /// the query verbs that boost plans in recall.
+    pub fn plans(mut self, plans: &PlanConfig) -> Self {
+        self.plans = plans.clone();
+        self
+    }
+
+    /// When buffered conversation becomes an episode and what else
+    /// happens then, e.g. storing stated decisions as conscious memories.
+    pub fn buffer(mut self, buffer: &BufferConfig) -> Self {
+        self.buffer = buffer.clone();
+        self
+    }
+
+    /// Candidate cutoffs for recall. `am_query` callers can still
+    /// override them per call.
+    pub fn compose_config(mut self, config: ComposeConfig) -> Self {
+        self.compose = Some(config);
+        self
+    }
+
+    /// Minimum per-category entries and novel share for budgeted
+    /// `am_query` calls. `max_tokens` is ignored; each call supplies it.
+    pub fn budget_config(mut self, budget: BudgetConfig) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Install a reranking pass over the top recall candidates. The server
+    /// never calls out to models itself; embedders inject one here.
+    pub fn reranker(mut self, stage: RerankStage) -> Self {
+        self.rerank = Some(stage);
+        self
+    }
+
+    /// Attribute new memories to `actor`, overriding the store's configured
+    /// actor and the MCP client name.
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Hide a tool from `tool_list` and reject calls to it, e.g.
+    /// `am_import` in a multi-tenant deployment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not one of [`TOOL_NAMES`], so a typo cannot
+    /// leave the tool silently enabled.
+    pub fn disable_tool(mut self, name: &str) -> Self {
+        let Some(known) = TOOL_NAMES.iter().find(|t| **t == name) else {
+            panic!("unknown tool name: {name}");
+        };
+        self.disabled_tools.insert(known);
+        self
+    }
+
+    /// Write a marker file at `path` when a store write fails, so tools
+    /// outside the process (`am doctor`) can see that memory and store
+    /// diverged. A marker left by an earlier process is removed on build,
+    /// since the fresh load is back in sync.
+    pub fn unsaved_marker(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unsaved_marker = Some(path.into());
+        self
+    }
+
+    /// Directories that `path` arguments (`am_export`, `am_import`) may
+    /// read and write, replacing the default of the directory the server
+    /// starts in. Paths are resolved through symlinks before the check.
+    pub fn allowed_paths(mut self, dirs: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.allowed_paths = Some(dirs.into_iter().collect());
+        self
+    }
+
+    /// Reject `am_ingest` calls over `limit` bytes of text; `None` (the
+    /// default) keeps only the per-call input limit.
+    pub fn max_ingest_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_ingest_bytes = limit;
+        self
+    }
+
+    /// Load in the background when the brain holds more than `threshold`
+    /// occurrences. Defaults to [`LAZY_LOAD_THRESHOLD`].
+    pub fn lazy_load_threshold(mut self, threshold: u64) -> Self {
+        self.lazy_load_threshold = threshold;
+        self
+    }
+
+    /// How long a tool call waits for a background load before answering
+    /// "still loading". Defaults to [`LOAD_WAIT`].
+    pub fn load_wait(mut self, wait: Duration) -> Self {
+        self.load_wait = wait;
+        self
+    }
+
+    /// Load the system from the store and apply the settings.
+    ///
+    /// A preflight counts occurrences first. Over the lazy-load threshold
+    /// the load runs on the store thread and the server is returned at
+    /// once; tool calls wait for it (see [`load_wait`](Self::load_wait)).
+    pub fn build(self) -> std::result::Result<AmServer<S>, S::Error>
+    where
+        S: Send + 'static,
+    {
+        let settings = SystemSettings {
+            plan_verbs: self.plans.verbs.clone(),
+            compose: self.compose,
+            rerank: self.rerank,
+            actor: self.actor,
+        };
+        let flush = FlushOptions {
+            detect_plans: self.plans.detect,
+            auto_salient: self.buffer.auto_salient,
+        };
+        let occurrences = self.store.occurrence_count()?;
+        let lazy = occurrences > self.lazy_load_threshold;
+
+        let (store, state, pending, preflight) = if lazy {
+            let preflight = Preflight::new(occurrences);
+            tracing::info!(
+                "loading {occurrences} occurrences in the background (estimated {}s)",
+                preflight.estimated().as_secs()
+            );
+            let store = AsyncStore::new(self.store);
+            let load = store.run(move |store| {
+                let started = Instant::now();
+                let loaded = store.load_system();
+                tracing::info!(
+                    "loaded {occurrences} occurrences in {}ms",
+                    started.elapsed().as_millis()
+                );
+                loaded
+            });
+            let pending = PendingLoad {
+                load,
+                settings,
+                flush,
+                client_actor: None,
+            };
+            (store, None, Some(pending), Some(preflight))
+        } else {
+            let mut system = self.store.load_system()?;
+            settings.apply(&mut system);
+            let state = ServerState::new(system, flush);
+            (AsyncStore::new(self.store), Some(state), None, None)
+        };
+
+        if let Some(path) = &self.unsaved_marker {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(AmServer {
+            loaded: AtomicBool::new(state.is_some()),
+            state: Mutex::new(state),
+            store,
+            store_figures: Mutex::new(None),
+            pending: Mutex::new(pending),
+            preflight,
+            load_error: OnceLock::new(),
+            load_wait: self.load_wait,
+            budget: self.budget,
+            buffer_threshold: self.buffer.threshold.max(1),
+            disabled_tools: self.disabled_tools,
+            dirty_unsaved: AtomicBool::new(false),
+            unsaved_marker: self.unsaved_marker,
+            path_policy: self
+                .allowed_paths
+                .map_or_else(PathPolicy::project_root, PathPolicy::new),
+            max_ingest_bytes: self.max_ingest_bytes,
+            metrics: metrics::Metrics::default(),
+        })
+    }
+}
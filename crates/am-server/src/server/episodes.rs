@@ -0,0 +1,149 @@
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use am_core::store_trait::AmStore;
+
+use super::{AmServer, parse_params};
+use crate::error::ToolError;
+use crate::jsonrpc::tool_result_text;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct DecisionsRequest {
+    /// Only list decisions whose text contains this keyword.
+    keyword: Option<String>,
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    pub(super) fn am_episodes(&self) -> Result<Value, ToolError> {
+        let state = self.lock_state()?;
+
+        let episodes: Vec<Value> = state
+            .system
+            .episodes
+            .iter()
+            .map(|ep| {
+                let total_occurrences: usize =
+                    ep.neighborhoods.iter().map(|n| n.occurrences.len()).sum();
+
+                serde_json::json!({
+                    "id": ep.id.to_string(),
+                    "name": ep.name,
+                    "created": ep.timestamp,
+                    "neighborhood_count": ep.neighborhoods.len(),
+                    "total_occurrences": total_occurrences,
+                    "is_conscious": ep.is_conscious,
+                    "actor": ep.actor,
+                })
+            })
+            .collect();
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&episodes).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_episode_neighborhoods(&self, args: &Value) -> Result<Value, ToolError> {
+        let episode_id = args
+            .get("episode_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::invalid_params("missing episode_id"))?;
+
+        let target_id = Uuid::parse_str(episode_id)
+            .map_err(|e| ToolError::invalid_params(format!("invalid UUID: {e}")))?;
+
+        let state = self.lock_state()?;
+
+        let episode = state
+            .system
+            .episodes
+            .iter()
+            .find(|ep| ep.id == target_id)
+            .ok_or_else(|| ToolError::not_found(format!("episode {episode_id} not found")))?;
+
+        let neighborhoods: Vec<Value> = episode
+            .neighborhoods
+            .iter()
+            .map(|nbhd| {
+                let text = &nbhd.source_text;
+                let tokens = nbhd.occurrences.len();
+                let (mean_drift, max_drift) = nbhd.drift();
+
+                serde_json::json!({
+                    "id": nbhd.id.to_string(),
+                    "type": format!("{:?}", nbhd.neighborhood_type),
+                    "epoch": nbhd.epoch,
+                    "tokens": tokens,
+                    "text": text,
+                    "episode": episode.name,
+                    "is_conscious": episode.is_conscious,
+                    "superseded_by": nbhd.superseded_by.map(|id| id.to_string()),
+                    "drift": {"mean": mean_drift, "max": max_drift},
+                })
+            })
+            .collect();
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&neighborhoods).unwrap_or_default(),
+        ))
+    }
+
+    /// Every conscious memory in stored order, superseded ones included.
+    /// Not an MCP tool: `am_query` is how agents recall; this feeds the
+    /// HTTP read API.
+    pub(super) fn am_conscious(&self) -> Result<Value, ToolError> {
+        let state = self.lock_state()?;
+
+        let memories: Vec<Value> = state
+            .system
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(|nbhd| {
+                serde_json::json!({
+                    "id": nbhd.id.to_string(),
+                    "type": nbhd.neighborhood_type.as_str(),
+                    "text": nbhd.source_text,
+                    "created_at": nbhd.created_at,
+                    "superseded_by": nbhd.superseded_by.map(|id| id.to_string()),
+                    "actor": nbhd.actor,
+                    "project": nbhd.project,
+                })
+            })
+            .collect();
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&memories).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_decisions(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: DecisionsRequest = parse_params(args)?;
+
+        let state = self.lock_state()?;
+        let decisions: Vec<Value> = state
+            .system
+            .decisions(req.keyword.as_deref())
+            .iter()
+            .map(|nbhd| {
+                serde_json::json!({
+                    "id": nbhd.id.to_string(),
+                    "text": nbhd.source_text,
+                    "created_at": nbhd.created_at,
+                    "superseded": nbhd.superseded_by.is_some(),
+                    "superseded_by": nbhd.superseded_by.map(|id| id.to_string()),
+                    "actor": nbhd.actor,
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "count": decisions.len(),
+            "decisions": decisions,
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+}
@@ -0,0 +1,359 @@
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use am_core::{
+    batch::{BatchQueryEngine, BatchQueryRequest},
+    compose::RecallCategory,
+    store_trait::{AmStore, AuditEvent, AuditKind},
+    system::normalize_project,
+    tokenizer::{IngestChunk, IngestPreview, ingest_chunks, ingest_text},
+};
+
+use super::{
+    AmServer, MAX_TOOL_INPUT_BYTES, ServerState, check_input_size, flush_exchanges, parse_params,
+    project_note, store_error,
+};
+use crate::error::ToolError;
+use crate::jsonrpc::tool_result_text;
+
+#[cfg(test)]
+thread_local! {
+    /// Parks an `am_ingest` on this thread between forking the RNG and
+    /// tokenizing: it signals the sender, then waits on the receiver.
+    pub(super) static TOKENIZE_GATE: std::cell::RefCell<
+        Option<(std::sync::mpsc::Sender<()>, std::sync::mpsc::Receiver<()>)>,
+    > = const { std::cell::RefCell::new(None) };
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct BufferRequest {
+    /// User's message text
+    user: String,
+    /// Assistant's response text
+    assistant: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct IngestRequest {
+    /// Document text to ingest, chunked by sentence
+    text: Option<String>,
+    /// Pre-chunked document, one neighborhood per chunk
+    chunks: Option<Vec<IngestChunk>>,
+    /// Optional name for the episode
+    name: Option<String>,
+    /// Report what would be ingested without storing anything
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct BatchQueryItem {
+    /// The query text
+    query: String,
+    /// Optional token budget for this query's context
+    max_tokens: Option<usize>,
+    /// Project this query's recall favours, in place of the server's own
+    #[serde(default)]
+    project: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct McpBatchQueryRequest {
+    /// List of queries to process in a single batch.
+    queries: Vec<BatchQueryItem>,
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    pub(super) fn am_buffer(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: BufferRequest = parse_params(args)?;
+
+        let total_len = req.user.len() + req.assistant.len();
+        if total_len > MAX_TOOL_INPUT_BYTES {
+            return Err(ToolError::invalid_params(format!(
+                "combined input exceeds {} byte limit",
+                MAX_TOOL_INPUT_BYTES
+            )));
+        }
+
+        // Dedup check: hash the exchange and check against recent hashes
+        let hash = Self::content_hash(&req.user, &req.assistant);
+        let (duplicate, auto_salient) = {
+            let mut state = self.lock_state()?;
+            Self::clean_dedup_window(&mut state.dedup_window);
+            let duplicate = state.dedup_window.contains_key(&hash);
+            if !duplicate {
+                state.dedup_window.insert(hash, std::time::Instant::now());
+            }
+            (duplicate, state.flush.auto_salient)
+        };
+
+        // The buffer lives only in the store; the state lock is only needed
+        // again if the exchanges make an episode
+        if duplicate {
+            let result = serde_json::json!({
+                "deduplicated": true,
+                "buffer_size": self.store.buffer_count().wait().unwrap_or(0),
+            });
+            return Ok(tool_result_text(
+                &serde_json::to_string_pretty(&result).unwrap_or_default(),
+            ));
+        }
+
+        let buffer_size = self
+            .store
+            .append_buffer(req.user, req.assistant)
+            .wait()
+            .map_err(store_error::<S>)?;
+
+        let mut flushed = None;
+        if buffer_size >= self.buffer_threshold {
+            let exchanges = self.store.drain_buffer().wait().map_err(store_error::<S>)?;
+            // A concurrent call may have drained the buffer first
+            if !exchanges.is_empty() {
+                let mut state = self.lock_state()?;
+                let ServerState {
+                    system, rng, flush, ..
+                } = &mut *state;
+                let (episode, writes) = flush_exchanges(system, &exchanges, rng, *flush);
+                let write = self.store.run(move |store| writes.persist(store));
+                drop(state);
+                write
+                    .wait()
+                    .map_err(|e| self.save_failed("buffer episode", e))?;
+                flushed = Some(episode);
+            }
+        }
+
+        let mut result = serde_json::json!({
+            "buffer_size": buffer_size,
+            "episode_created": flushed.as_ref().map(|f| &f.name),
+        });
+        if auto_salient {
+            let ids: Vec<String> = flushed
+                .iter()
+                .flat_map(|f| &f.auto_salient)
+                .map(Uuid::to_string)
+                .collect();
+            result["auto_salient"] = serde_json::json!(ids);
+        }
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_ingest(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: IngestRequest = parse_params(args)?;
+        let size = match (&req.text, &req.chunks) {
+            (Some(text), None) => {
+                check_input_size(text, "text")?;
+                text.len()
+            }
+            (None, Some(chunks)) => {
+                let total_len: usize = chunks.iter().map(|c| c.text.len()).sum();
+                if total_len > MAX_TOOL_INPUT_BYTES {
+                    return Err(ToolError::invalid_params(format!(
+                        "aggregate chunk text ({total_len} bytes) exceeds {} byte limit",
+                        MAX_TOOL_INPUT_BYTES
+                    )));
+                }
+                total_len
+            }
+            _ => {
+                return Err(ToolError::invalid_params(
+                    "invalid params: pass exactly one of text or chunks",
+                ));
+            }
+        };
+        if let Some(limit) = self.max_ingest_bytes
+            && size > limit
+        {
+            return Err(ToolError::invalid_params(format!(
+                "ingest of {size} bytes exceeds the {limit} byte limit (AM_MAX_INGEST_BYTES)"
+            )));
+        }
+
+        // Tokenize and place outside the lock so a large document does not
+        // stall concurrent tools. Only the RNG fork needs the state.
+        let mut rng = {
+            let mut state = self.lock_state()?;
+            SmallRng::from_rng(&mut state.rng)
+        };
+        #[cfg(test)]
+        TOKENIZE_GATE.with_borrow(|gate| {
+            if let Some((parked, release)) = gate {
+                parked.send(()).unwrap();
+                release.recv().unwrap();
+            }
+        });
+        let episode = match (&req.text, &req.chunks) {
+            (Some(text), _) => ingest_text(text, req.name.as_deref(), &mut rng),
+            (None, Some(chunks)) => ingest_chunks(chunks, req.name.as_deref(), &mut rng)
+                .map_err(|e| ToolError::invalid_params(format!("invalid params: {e}")))?,
+            (None, None) => unreachable!("checked above"),
+        };
+        if req.dry_run {
+            let (stored, db_size) = self
+                .store
+                .run(|store| Ok((store.occurrence_count()?, store.db_size())))
+                .wait()
+                .map_err(store_error::<S>)?;
+            let preview = IngestPreview::new(&episode, db_size, stored);
+            let mut result = serde_json::to_value(&preview).unwrap_or_default();
+            result["dry_run"] = serde_json::json!(true);
+            return Ok(tool_result_text(
+                &serde_json::to_string_pretty(&result).unwrap_or_default(),
+            ));
+        }
+
+        let mut state = self.lock_state()?;
+        let system = &mut state.system;
+        system.add_episode(episode);
+        let episode = system.episodes.last().unwrap();
+        let event = AuditEvent::new(AuditKind::Ingest, episode.neighborhoods.len() as u64)
+            .with_context(system)
+            .with_detail(&episode.name);
+        let saved = episode.clone();
+        let write = self
+            .store
+            .run(move |store| store.audited(&event, || store.save_episode(&saved)));
+
+        // Counted after add_episode, which drops chunks that are reused
+        let occurrences: usize = episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.occurrences.len())
+            .sum();
+        let mut result = serde_json::json!({
+            "episode": episode.name,
+            "neighborhoods": episode.neighborhoods.len(),
+            "occurrences": occurrences,
+        });
+        if !episode.reused_neighborhoods.is_empty() {
+            result["reused"] = serde_json::json!(episode.reused_neighborhoods.len());
+        }
+        drop(state);
+        write.wait().map_err(|e| self.save_failed("ingest", e))?;
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_batch_query(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: McpBatchQueryRequest = parse_params(args)?;
+
+        let total_len: usize = req.queries.iter().map(|q| q.query.len()).sum();
+        if total_len > MAX_TOOL_INPUT_BYTES {
+            return Err(ToolError::invalid_params(format!(
+                "aggregate query text ({total_len} bytes) exceeds {} byte limit",
+                MAX_TOOL_INPUT_BYTES
+            )));
+        }
+
+        let requests: Vec<BatchQueryRequest> = req
+            .queries
+            .iter()
+            .map(|q| BatchQueryRequest {
+                query: q.query.clone(),
+                max_tokens: q.max_tokens,
+                project: q.project.clone(),
+            })
+            .collect();
+
+        // Scoring mutates the system (activation, drift), so it runs under
+        // the lock; response assembly below does not.
+        let buffered = self.buffer_waiting();
+        let (batch_output, stats, project_notes, flush_write, write) = {
+            let mut state = self.lock_state()?;
+            let flush_write = if buffered {
+                self.queue_buffer_flush(&mut state)
+            } else {
+                None
+            };
+            let system = &mut state.system;
+            let batch_output = BatchQueryEngine::batch_query(system, &requests);
+            let write = self.queue_manifest(system, &batch_output.manifest, None);
+            let project_notes: Vec<Option<String>> = requests
+                .iter()
+                .map(|r| {
+                    let project = r.project.as_deref().and_then(normalize_project)?;
+                    (system.project_memory_count(&project) == 0).then(|| project_note(&project))
+                })
+                .collect();
+            let stats = Self::stats_json(system);
+            (batch_output, stats, project_notes, flush_write, write)
+        };
+        if let Some(flush_write) = flush_write {
+            flush_write
+                .wait()
+                .map_err(|e| self.save_failed("buffer flush", e))?;
+        }
+        self.finish_save(write)
+            .map_err(|e| self.save_failed("batch_query", e))?;
+
+        let results_json: Vec<serde_json::Value> = batch_output
+            .results
+            .iter()
+            .zip(&project_notes)
+            .map(|(r, note)| {
+                let mut con_ids = Vec::new();
+                let mut sub_ids = Vec::new();
+                let mut nov_ids = Vec::new();
+                for f in &r.context.included {
+                    match f.category {
+                        RecallCategory::Conscious => con_ids.push(f.neighborhood_id.to_string()),
+                        RecallCategory::Subconscious => sub_ids.push(f.neighborhood_id.to_string()),
+                        RecallCategory::Novel => nov_ids.push(f.neighborhood_id.to_string()),
+                    }
+                }
+
+                let mut json = serde_json::json!({
+                    "query": r.query,
+                    "context": r.context.context,
+                    "metrics": {
+                        "conscious": r.context.metrics.conscious,
+                        "subconscious": r.context.metrics.subconscious,
+                        "novel": r.context.metrics.novel,
+                    },
+                    "recalled_ids": {
+                        "conscious": con_ids,
+                        "subconscious": sub_ids,
+                        "novel": nov_ids,
+                    },
+                    "token_estimate": {
+                        "conscious": r.context.token_estimate.conscious,
+                        "subconscious": r.context.token_estimate.subconscious,
+                        "novel": r.context.token_estimate.novel,
+                        "total": r.context.token_estimate.total,
+                    },
+                    "budget": {
+                        "tokens_used": r.context.tokens_used,
+                        "tokens_budget": r.context.tokens_budget,
+                        "included_count": r.context.included.len(),
+                        "excluded_count": r.context.excluded_count,
+                    },
+                    "confidence": r.context.confidence.as_str(),
+                    "activated_count": r.activated_count,
+                });
+                if let Some(note) = note {
+                    json["project_note"] = serde_json::json!(note);
+                }
+                json
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "results": results_json,
+            "batch_size": results_json.len(),
+            "stats": stats,
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+}
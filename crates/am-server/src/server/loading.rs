@@ -0,0 +1,194 @@
+//! Startup preflight and background loading of large brains.
+//!
+//! Loading a brain with hundreds of thousands of occurrences can outlast an
+//! MCP client's initialize timeout. The builder counts occurrences first;
+//! above the lazy-load threshold the server starts without a system and
+//! loads it on the store thread. Tool calls wait for the load up to a
+//! per-call timeout, `am_stats` answers at once with load progress, and
+//! `am_query` returns a structured "still loading" result instead of an
+//! error.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::MutexGuard;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use am_core::{store_trait::AmStore, system::DAESystem};
+
+use super::builder::SystemSettings;
+use super::{AmServer, FlushOptions, ServerState};
+use crate::async_store::Pending;
+use crate::error::{ErrorKind, ToolError};
+
+/// Occurrence count above which the brain loads in the background.
+pub const LAZY_LOAD_THRESHOLD: u64 = 200_000;
+
+/// How long a tool call waits for a background load before giving up.
+pub const LOAD_WAIT: Duration = Duration::from_secs(10);
+
+/// Rough load rate, only used for the preflight estimate.
+const EST_OCCURRENCES_PER_SEC: u64 = 100_000;
+
+/// Result of the startup row count.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Preflight {
+    pub occurrences: u64,
+    pub started: Instant,
+}
+
+impl Preflight {
+    pub fn new(occurrences: u64) -> Self {
+        Self {
+            occurrences,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn estimated(&self) -> Duration {
+        Duration::from_millis(self.occurrences.saturating_mul(1000) / EST_OCCURRENCES_PER_SEC)
+    }
+
+    pub fn to_json(self) -> Value {
+        serde_json::json!({
+            "occurrences": self.occurrences,
+            "elapsed_ms": self.started.elapsed().as_millis() as u64,
+            "estimated_ms": self.estimated().as_millis() as u64,
+        })
+    }
+}
+
+/// A load running on the store thread, and what to apply once it lands.
+pub(super) struct PendingLoad<S: AmStore> {
+    pub load: Pending<Result<DAESystem, S::Error>>,
+    pub settings: SystemSettings,
+    pub flush: FlushOptions,
+    /// MCP client name seen by `observe_client_info` before the load
+    /// finished.
+    pub client_actor: Option<String>,
+}
+
+/// Why a tool call could not get the system.
+#[derive(Debug)]
+pub(super) enum NotReady {
+    Loading(Preflight),
+    Failed(String),
+}
+
+impl From<NotReady> for ToolError {
+    fn from(not_ready: NotReady) -> Self {
+        match not_ready {
+            NotReady::Loading(preflight) => ToolError::new(
+                ErrorKind::Loading,
+                format!(
+                    "memory still loading ({} occurrences, {}s elapsed); retry shortly",
+                    preflight.occurrences,
+                    preflight.started.elapsed().as_secs()
+                ),
+            ),
+            NotReady::Failed(message) => ToolError::new(ErrorKind::LoadFailed, message),
+        }
+    }
+}
+
+/// Locked server state that is known to be loaded.
+pub(super) struct StateGuard<'a>(MutexGuard<'a, Option<ServerState>>);
+
+impl Deref for StateGuard<'_> {
+    type Target = ServerState;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref().expect("state guard holds a loaded state")
+    }
+}
+
+impl DerefMut for StateGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut().expect("state guard holds a loaded state")
+    }
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    /// Whether the brain is still loading in the background.
+    pub fn is_loading(&self) -> bool {
+        !self.loaded.load(Ordering::Acquire) && self.load_error.get().is_none()
+    }
+
+    /// Lock the server state, waiting up to the load timeout for a
+    /// background load to finish.
+    pub(super) fn lock_state(&self) -> Result<StateGuard<'_>, NotReady> {
+        let mut guard = self.state.lock().expect("poisoned mutex");
+        if guard.is_none() {
+            self.finish_load(&mut guard, self.load_wait)?;
+        }
+        Ok(StateGuard(guard))
+    }
+
+    /// Lock the server state only if it is loaded (or the load has just
+    /// landed) and no other call holds it. Never waits.
+    pub(super) fn try_lock_state(&self) -> Option<StateGuard<'_>> {
+        let mut guard = self.state.try_lock().ok()?;
+        if guard.is_none() {
+            self.finish_load(&mut guard, Duration::ZERO).ok()?;
+        }
+        Some(StateGuard(guard))
+    }
+
+    /// Progress of the background load, for `am_stats` and `am_query`.
+    pub(super) fn loading_json(&self) -> Value {
+        self.preflight.map_or(Value::Null, Preflight::to_json)
+    }
+
+    /// Install the background load's result into `slot`, waiting up to
+    /// `wait` for it.
+    fn finish_load(&self, slot: &mut Option<ServerState>, wait: Duration) -> Result<(), NotReady> {
+        if let Some(message) = self.load_error.get() {
+            return Err(NotReady::Failed(message.clone()));
+        }
+        let mut pending = self.pending.lock().expect("poisoned mutex");
+        let Some(load) = pending.as_ref() else {
+            return Err(NotReady::Failed("memory is not loaded".to_string()));
+        };
+        let preflight = self.preflight.unwrap_or_else(|| Preflight::new(0));
+        if !wait.is_zero() {
+            tracing::debug!(
+                "waiting up to {}s for memory load ({}s elapsed)",
+                wait.as_secs(),
+                preflight.started.elapsed().as_secs()
+            );
+        }
+        let loaded = match load.load.wait_timeout(wait) {
+            Ok(loaded) => loaded,
+            Err(RecvTimeoutError::Timeout) => return Err(NotReady::Loading(preflight)),
+            Err(RecvTimeoutError::Disconnected) => {
+                pending.take();
+                return Err(self.load_failed("memory load panicked".to_string()));
+            }
+        };
+        let load = pending.take().expect("pending load checked above");
+        match loaded {
+            Ok(mut system) => {
+                load.settings.apply(&mut system);
+                if system.actor().is_none() && load.client_actor.is_some() {
+                    system.set_actor(load.client_actor);
+                }
+                *slot = Some(ServerState::new(system, load.flush));
+                self.loaded.store(true, Ordering::Release);
+                tracing::info!(
+                    "memory ready after {}ms",
+                    preflight.started.elapsed().as_millis()
+                );
+                Ok(())
+            }
+            Err(e) => Err(self.load_failed(format!("[store] failed to load memory: {e}"))),
+        }
+    }
+
+    fn load_failed(&self, message: String) -> NotReady {
+        tracing::error!("{message}");
+        let _ = self.load_error.set(message.clone());
+        NotReady::Failed(message)
+    }
+}
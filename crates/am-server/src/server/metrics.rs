@@ -0,0 +1,254 @@
+//! Counters and histograms for `am serve --metrics-addr`, rendered in the
+//! Prometheus text format.
+//!
+//! A tiny registry rather than a metrics framework: the server records
+//! tool calls, saves, and GC runs as they happen, and the gauges (memory
+//! size, database size, buffer) are read from the loaded state at scrape
+//! time by [`AmServer::metrics_text`](super::AmServer::metrics_text).
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Histogram bucket bounds in seconds, from a cheap tool call to a full save.
+const BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Cumulative-bucket histogram of durations in seconds.
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations at or under each bound of [`BUCKETS`], not cumulative.
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(i) = BUCKETS.iter().position(|&bound| secs <= bound) {
+            self.counts[i] += 1;
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+
+    /// Write the `_bucket`, `_sum`, and `_count` series, with `labels`
+    /// (already formatted, without braces) on each.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, count) in BUCKETS.iter().zip(self.counts) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}{sep}le=\"{bound}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {}",
+            self.count
+        );
+        let braced = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let _ = writeln!(out, "{name}_sum{braced} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{braced} {}", self.count);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ToolMetrics {
+    ok: u64,
+    errors: u64,
+    latency: Histogram,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// By tool name; a `BTreeMap` so scrapes list tools in a stable order.
+    tools: BTreeMap<&'static str, ToolMetrics>,
+    saves: Histogram,
+    gc_runs: u64,
+    gc_evicted: u64,
+}
+
+/// Point-in-time values read from the loaded brain for one scrape.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Gauges {
+    pub occurrences: usize,
+    pub episodes: usize,
+    pub conscious: usize,
+    pub db_size: u64,
+    pub buffer: usize,
+}
+
+/// What the server has done since it started.
+#[derive(Debug, Default)]
+pub(super) struct Metrics {
+    registry: Mutex<Registry>,
+}
+
+impl Metrics {
+    /// Count one call of `tool` and its latency.
+    pub fn record_tool(&self, tool: &'static str, elapsed: Duration, ok: bool) {
+        let mut registry = self.registry.lock().expect("poisoned mutex");
+        let entry = registry.tools.entry(tool).or_default();
+        if ok {
+            entry.ok += 1;
+        } else {
+            entry.errors += 1;
+        }
+        entry.latency.observe(elapsed);
+    }
+
+    /// Record how long a write of query or import results to the store took.
+    pub fn record_save(&self, elapsed: Duration) {
+        self.registry
+            .lock()
+            .expect("poisoned mutex")
+            .saves
+            .observe(elapsed);
+    }
+
+    /// Count one maintenance GC pass and the occurrences it evicted.
+    pub fn record_gc(&self, evicted: usize) {
+        let mut registry = self.registry.lock().expect("poisoned mutex");
+        registry.gc_runs += 1;
+        registry.gc_evicted += evicted as u64;
+    }
+
+    /// Everything in the Prometheus text exposition format. `gauges` is
+    /// `None` while the brain is still loading.
+    pub fn render(&self, gauges: Option<Gauges>, loading: bool, dirty_unsaved: bool) -> String {
+        let registry = self.registry.lock().expect("poisoned mutex");
+        let mut out = String::new();
+
+        out.push_str("# HELP am_tool_calls_total Tool calls by tool and outcome.\n");
+        out.push_str("# TYPE am_tool_calls_total counter\n");
+        for (tool, m) in &registry.tools {
+            let _ = writeln!(
+                out,
+                "am_tool_calls_total{{tool=\"{tool}\",outcome=\"ok\"}} {}",
+                m.ok
+            );
+            let _ = writeln!(
+                out,
+                "am_tool_calls_total{{tool=\"{tool}\",outcome=\"error\"}} {}",
+                m.errors
+            );
+        }
+        out.push_str("# HELP am_tool_duration_seconds Tool call latency.\n");
+        out.push_str("# TYPE am_tool_duration_seconds histogram\n");
+        for (tool, m) in &registry.tools {
+            m.latency.render(
+                &mut out,
+                "am_tool_duration_seconds",
+                &format!("tool=\"{tool}\""),
+            );
+        }
+
+        out.push_str("# HELP am_save_duration_seconds Writes of tool results to the store.\n");
+        out.push_str("# TYPE am_save_duration_seconds histogram\n");
+        registry
+            .saves
+            .render(&mut out, "am_save_duration_seconds", "");
+
+        out.push_str("# HELP am_gc_runs_total Maintenance GC passes.\n");
+        out.push_str("# TYPE am_gc_runs_total counter\n");
+        let _ = writeln!(out, "am_gc_runs_total {}", registry.gc_runs);
+        out.push_str("# HELP am_gc_evicted_occurrences_total Occurrences evicted by GC.\n");
+        out.push_str("# TYPE am_gc_evicted_occurrences_total counter\n");
+        let _ = writeln!(
+            out,
+            "am_gc_evicted_occurrences_total {}",
+            registry.gc_evicted
+        );
+        drop(registry);
+
+        let mut gauge = |name: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            let _ = writeln!(out, "{name} {value}");
+        };
+        gauge(
+            "am_loading",
+            "1 while the brain loads in the background.",
+            u8::from(loading).to_string(),
+        );
+        gauge(
+            "am_dirty_unsaved",
+            "1 when memory holds changes a failed save did not write.",
+            u8::from(dirty_unsaved).to_string(),
+        );
+        if let Some(g) = gauges {
+            gauge(
+                "am_occurrences",
+                "Occurrences in memory, conscious included.",
+                g.occurrences.to_string(),
+            );
+            gauge(
+                "am_episodes",
+                "Subconscious episodes in memory.",
+                g.episodes.to_string(),
+            );
+            gauge(
+                "am_conscious_neighborhoods",
+                "Conscious memories.",
+                g.conscious.to_string(),
+            );
+            gauge(
+                "am_db_size_bytes",
+                "Size of the brain database.",
+                g.db_size.to_string(),
+            );
+            gauge(
+                "am_buffer_exchanges",
+                "Exchanges waiting in the conversation buffer.",
+                g.buffer.to_string(),
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut h = Histogram::default();
+        h.observe(Duration::from_micros(500));
+        h.observe(Duration::from_millis(20));
+        h.observe(Duration::from_secs(60));
+        let mut out = String::new();
+        h.render(&mut out, "x", "tool=\"am_query\"");
+
+        assert!(out.contains("x_bucket{tool=\"am_query\",le=\"0.001\"} 1\n"));
+        assert!(out.contains("x_bucket{tool=\"am_query\",le=\"0.025\"} 2\n"));
+        assert!(out.contains("x_bucket{tool=\"am_query\",le=\"10\"} 2\n"));
+        assert!(out.contains("x_bucket{tool=\"am_query\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("x_count{tool=\"am_query\"} 3\n"));
+    }
+
+    #[test]
+    fn test_render_without_labels_or_gauges() {
+        let metrics = Metrics::default();
+        metrics.record_save(Duration::from_millis(3));
+        metrics.record_gc(7);
+        let out = metrics.render(None, true, false);
+
+        assert!(out.contains("am_save_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(out.contains("am_save_duration_seconds_count 1\n"));
+        assert!(out.contains("am_gc_runs_total 1\n"));
+        assert!(out.contains("am_gc_evicted_occurrences_total 7\n"));
+        assert!(out.contains("am_loading 1\n"));
+        assert!(!out.contains("am_occurrences"), "no gauges while loading");
+    }
+}
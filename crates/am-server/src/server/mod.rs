@@ -0,0 +1,751 @@
+mod activation;
+mod builder;
+mod episodes;
+mod ingestion;
+mod loading;
+mod metrics;
+mod paths;
+mod query;
+mod system;
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use rustc_hash::FxHasher;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use uuid::Uuid;
+
+use am_core::{
+    activation_stats::ActivationStats,
+    compose::BudgetConfig,
+    episode::Episode,
+    neighborhood::Neighborhood,
+    occurrence::Occurrence,
+    phasor::DaemonPhasor,
+    quaternion::Quaternion,
+    query::QueryManifest,
+    salient::{extract_stated_salient, tag_plans},
+    store_trait::{AmStore, AuditEvent, AuditKind, BufferedExchange},
+    system::{DAESystem, GcDetail},
+    tokenizer::ingest_text,
+};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use crate::async_store::{AsyncStore, Pending};
+use crate::error::{ErrorKind, ToolError};
+
+pub use builder::AmServerBuilder;
+pub use loading::{LAZY_LOAD_THRESHOLD, LOAD_WAIT};
+use loading::{PendingLoad, Preflight};
+
+const DEDUP_WINDOW_SECS: u64 = 60;
+/// Maximum input size for text-accepting MCP tools (1 MB).
+const MAX_TOOL_INPUT_BYTES: usize = 1_048_576;
+
+/// Reject input that exceeds the per-tool byte limit.
+fn check_input_size(value: &str, field: &str) -> Result<(), ToolError> {
+    if value.len() > MAX_TOOL_INPUT_BYTES {
+        return Err(ToolError::invalid_params(format!(
+            "{field} exceeds {} byte limit",
+            MAX_TOOL_INPUT_BYTES
+        )));
+    }
+    Ok(())
+}
+
+/// Note attached to a recall result whose project override matches no
+/// tagged memories, so the caller knows the override favoured nothing.
+fn project_note(project: &str) -> String {
+    format!("no memories are tagged for project '{project}', so it favoured nothing")
+}
+
+/// Convert a store error into a tool error, classified by the store.
+fn store_error<S: AmStore>(e: S::Error) -> ToolError {
+    ToolError::store::<S>(&e, &e)
+}
+
+/// Deserialize a tool's arguments into its request type.
+fn parse_params<T: DeserializeOwned>(args: &Value) -> Result<T, ToolError> {
+    serde_json::from_value(args.clone())
+        .map_err(|e| ToolError::invalid_params(format!("invalid params: {e}")))
+}
+
+/// Names of every tool `dispatch_tool` routes, including the HTTP-only
+/// episode browsing tools that the MCP tool list does not advertise.
+pub const TOOL_NAMES: &[&str] = &[
+    "am_query",
+    "am_query_index",
+    "am_retrieve",
+    "am_knows",
+    "am_activate_response",
+    "am_salient",
+    "am_buffer",
+    "am_ingest",
+    "am_stats",
+    "am_export",
+    "am_import",
+    "am_feedback",
+    "am_batch_query",
+    "am_decisions",
+    "am_episodes",
+    "am_conscious",
+    "am_episode_neighborhoods",
+];
+
+/// The `am_*` tool handlers over one brain store.
+///
+/// Build one with [`AmServer::builder`] (or [`AmServer::new`] for the
+/// defaults) and route tool calls through [`AmServer::dispatch_tool`].
+///
+/// The store lives on an [`AsyncStore`] thread, so no store call runs on
+/// the caller's thread. Tool calls are synchronous: each holds the state
+/// lock while it changes the loaded system and queues the matching store
+/// writes before releasing it, so writes land in the order the changes
+/// were made and the next call's reads see them. The call then waits for
+/// its writes without the lock. Reads a call needs partway through (a
+/// buffer flush, deep recall, import, GC) wait under the lock. `am_stats`
+/// never waits for a busy store. Async embedders call `dispatch_tool` off
+/// their runtime's workers, e.g. through `tokio::task::spawn_blocking`,
+/// and await each call before starting the next that depends on it.
+pub struct AmServer<S: AmStore> {
+    /// `None` until a background load lands (see [`AmServerBuilder::build`]).
+    state: Mutex<Option<ServerState>>,
+    /// The brain store, on its own thread.
+    store: AsyncStore<S>,
+    /// Store figures from the last `am_stats` or metrics scrape that found
+    /// the store idle, reported while it is busy.
+    store_figures: Mutex<Option<StoreFigures>>,
+    /// The background load, while it runs.
+    pending: Mutex<Option<PendingLoad<S>>>,
+    /// Startup row count, when the brain was big enough to load lazily.
+    preflight: Option<Preflight>,
+    loaded: AtomicBool,
+    load_error: OnceLock<String>,
+    /// How long a tool call waits for a background load.
+    load_wait: Duration,
+    /// Template for budgeted `am_query` calls; `max_tokens` comes per call.
+    budget: BudgetConfig,
+    /// Buffered exchanges that make an episode.
+    buffer_threshold: usize,
+    /// Tools the embedder turned off; they are absent from `tool_list`
+    /// and rejected by `dispatch_tool`.
+    disabled_tools: HashSet<&'static str>,
+    /// Set when a write to the store failed after the in-memory system
+    /// changed, so the two have diverged. Cleared by a full save.
+    dirty_unsaved: AtomicBool,
+    /// File written on the first failed save so `am doctor` can see the
+    /// divergence from outside the process.
+    unsaved_marker: Option<PathBuf>,
+    /// Directories `path` arguments may point into.
+    path_policy: paths::PathPolicy,
+    /// Largest `am_ingest` payload, in bytes; `None` leaves only
+    /// [`MAX_TOOL_INPUT_BYTES`].
+    max_ingest_bytes: Option<usize>,
+    /// Tool calls, saves, and GC runs since start, for [`Self::metrics_text`].
+    metrics: metrics::Metrics,
+}
+
+/// All mutable server state behind a single `std::sync::Mutex`.
+///
+/// # Concurrency model
+///
+/// Every MCP tool handler acquires `state.lock()` while it reads or changes
+/// the system. This serializes the tool calls' work on the system; their
+/// store writes run on the store thread in the same order, after the lock
+/// is released. This is correct and intentional for the current deployment
+/// model (single client via stdio transport, one Claude Code session per
+/// process).
+///
+/// # What changes for multi-client support
+///
+/// If the transport changes to SSE or WebSocket with concurrent clients, the
+/// single mutex becomes a throughput bottleneck. The recommended decomposition:
+///
+/// - `RwLock<DAESystem>` for the in-memory system (readers: am_query, am_stats,
+///   am_export; writers: am_ingest, am_salient, am_feedback, am_activate_response)
+/// - Separate `Mutex<SessionState>` for session_recalled and dedup_window
+///   (per-session state that does not interact with the core system)
+///
+/// The `SmallRng` would move to per-request construction (already cheap) or
+/// thread-local storage.
+struct ServerState {
+    system: DAESystem,
+    rng: SmallRng,
+    /// Neighborhood recall counts this session (process lifetime).
+    /// Tracks how many times each neighborhood has been returned.
+    /// Non-decision neighborhoods get diminishing returns on repeated recalls.
+    session_recalled: HashMap<Uuid, u32>,
+    /// Content hashes with timestamps for dedup within a time window.
+    /// Prevents duplicate episodes when am_buffer is called with identical content.
+    dedup_window: HashMap<u64, Instant>,
+    /// What buffer flushes do besides creating the episode.
+    flush: FlushOptions,
+}
+
+impl ServerState {
+    fn new(system: DAESystem, flush: FlushOptions) -> Self {
+        Self {
+            system,
+            rng: SmallRng::from_os_rng(),
+            session_recalled: HashMap::new(),
+            dedup_window: HashMap::new(),
+            flush,
+        }
+    }
+}
+
+/// Collect current `(Uuid, Quaternion, DaemonPhasor, drift)` tuples for a set of occurrence IDs.
+///
+/// Used to prepare data for `save_occurrence_positions` after drift or
+/// Kuramoto coupling has modified positions/phasors in memory.
+fn collect_occurrence_positions(
+    system: &DAESystem,
+    ids: &[Uuid],
+) -> Vec<(Uuid, Quaternion, DaemonPhasor, f64)> {
+    collect_occurrences(system, ids, |occ| {
+        (occ.id, occ.position, occ.phasor, occ.drift)
+    })
+}
+
+/// Collect current `(Uuid, activation_count, spillover)` tuples for
+/// occurrences credited by activation spillover.
+fn collect_spillover(system: &DAESystem, ids: &[Uuid]) -> Vec<(Uuid, u32, f64)> {
+    collect_occurrences(system, ids, |occ| {
+        (occ.id, occ.activation_count, occ.spillover)
+    })
+}
+
+/// Map the occurrences matching `ids` through `f`, scanning all episodes
+/// (including conscious).
+fn collect_occurrences<T>(
+    system: &DAESystem,
+    ids: &[Uuid],
+    f: impl Fn(&Occurrence) -> T,
+) -> Vec<T> {
+    if ids.is_empty() {
+        return Vec::new();
+    }
+
+    let id_set: rustc_hash::FxHashSet<Uuid> = ids.iter().copied().collect();
+    let mut result = Vec::with_capacity(ids.len());
+
+    let iter = system
+        .episodes
+        .iter()
+        .chain(std::iter::once(&system.conscious_episode));
+
+    for ep in iter {
+        for nbhd in &ep.neighborhoods {
+            for occ in &nbhd.occurrences {
+                if id_set.contains(&occ.id) {
+                    result.push(f(occ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Store writes for query manifest mutations: drifted positions, activated
+/// occurrence counts, spillover credit, and feedback scores. Gathered from
+/// the system under the state lock so they can run on the store thread.
+struct ManifestWrites {
+    positions: Vec<(Uuid, Quaternion, DaemonPhasor, f64)>,
+    activations: Vec<(Uuid, u32)>,
+    demoted: Vec<(Uuid, u32)>,
+    spillover: Vec<(Uuid, u32, f64)>,
+    feedback_scores: Vec<(Uuid, f64)>,
+}
+
+impl ManifestWrites {
+    fn new(system: &DAESystem, manifest: &QueryManifest) -> Self {
+        Self {
+            positions: collect_occurrence_positions(system, &manifest.drifted),
+            activations: manifest.activation_deltas(),
+            demoted: manifest.demoted_activations.clone(),
+            spillover: collect_spillover(system, &manifest.spilled),
+            feedback_scores: manifest.feedback_scores.clone(),
+        }
+    }
+
+    /// Persist the writes. Every write is attempted; the first failure is
+    /// returned.
+    fn persist<S: AmStore>(&self, store: &S) -> Result<(), S::Error> {
+        let _span = tracing::debug_span!(
+            "persist_manifest",
+            drifted = self.positions.len(),
+            activated = self.activations.len(),
+        )
+        .entered();
+        let mut first_err = None;
+        if !self.positions.is_empty() {
+            first_err = store.save_occurrence_positions(&self.positions).err();
+        }
+        if !self.activations.is_empty()
+            && let Err(e) = store.increment_activations(&self.activations)
+        {
+            first_err.get_or_insert(e);
+        }
+        if !self.demoted.is_empty()
+            && let Err(e) = store.batch_set_activation_counts(&self.demoted)
+        {
+            first_err.get_or_insert(e);
+        }
+        // Absolute values, written after the increments so they win
+        if !self.spillover.is_empty()
+            && let Err(e) = store.save_activation_spillover(&self.spillover)
+        {
+            first_err.get_or_insert(e);
+        }
+        if !self.feedback_scores.is_empty()
+            && let Err(e) = store.save_feedback_scores(&self.feedback_scores)
+        {
+            first_err.get_or_insert(e);
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+}
+
+/// What a buffer flush does beyond creating the conversation episode.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlushOptions {
+    /// Tag numbered step lists as plans.
+    pub detect_plans: bool,
+    /// Store `DECISION:`/`PREFERENCE:` lines and `<salient>` tags as
+    /// conscious memories.
+    pub auto_salient: bool,
+}
+
+/// Episode created by a buffer flush.
+#[derive(Debug, Clone)]
+pub struct FlushedEpisode {
+    pub name: String,
+    /// Conscious neighborhoods created from the exchanges when
+    /// [`FlushOptions::auto_salient`] is set.
+    pub auto_salient: Vec<Uuid>,
+}
+
+/// Turn drained buffer exchanges into one conversation episode, dated by
+/// the first exchange rather than the flush. Returns the episode and the
+/// writes that save it.
+fn flush_exchanges(
+    system: &mut DAESystem,
+    exchanges: &[BufferedExchange],
+    rng: &mut SmallRng,
+    options: FlushOptions,
+) -> (FlushedEpisode, FlushWrites) {
+    let combined: String = exchanges
+        .iter()
+        .map(|e| format!("{}\n{}", e.user, e.assistant))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let mut episode = ingest_text(&combined, Some("conversation"), rng);
+    if let Some(first) = exchanges.first() {
+        episode.timestamp.clone_from(&first.buffered_at);
+    }
+    if options.detect_plans {
+        tag_plans(&mut episode);
+    }
+    system.add_episode(episode);
+    let name = system.label_episode(system.episodes.len() - 1, "conversation");
+
+    let auto_salient = if options.auto_salient {
+        extract_stated_salient(system, &combined, rng)
+    } else {
+        Vec::new()
+    };
+
+    let episode = system.episodes.last().unwrap();
+    let mut detail = format!("{name} from {} exchanges", exchanges.len());
+    if !auto_salient.is_empty() {
+        detail.push_str(&format!(", {} auto-salient", auto_salient.len()));
+    }
+    let event = AuditEvent::new(AuditKind::Buffer, episode.neighborhoods.len() as u64)
+        .with_context(system)
+        .with_detail(detail);
+    let conscious = &system.conscious_episode;
+    let writes = FlushWrites {
+        event,
+        episode: episode.clone(),
+        conscious: episode_header(conscious),
+        salient: conscious
+            .neighborhoods
+            .iter()
+            .filter(|n| auto_salient.contains(&n.id))
+            .cloned()
+            .collect(),
+    };
+    (FlushedEpisode { name, auto_salient }, writes)
+}
+
+/// Store writes for a buffer flush: the new episode and its auto-salient
+/// conscious memories, audited together.
+struct FlushWrites {
+    event: AuditEvent,
+    episode: Episode,
+    /// The conscious episode without its neighborhoods.
+    conscious: Episode,
+    salient: Vec<Neighborhood>,
+}
+
+impl FlushWrites {
+    fn persist<S: AmStore>(&self, store: &S) -> Result<(), S::Error> {
+        store.audited(&self.event, || {
+            store.save_episode(&self.episode)?;
+            for nbhd in &self.salient {
+                store.save_neighborhood(&self.conscious, nbhd)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `episode` without its neighborhoods, for saving single neighborhoods of
+/// it from the store thread.
+fn episode_header(episode: &Episode) -> Episode {
+    Episode {
+        id: episode.id,
+        name: episode.name.clone(),
+        is_conscious: episode.is_conscious,
+        timestamp: episode.timestamp.clone(),
+        neighborhoods: Vec::new(),
+        actor: episode.actor.clone(),
+        original_timestamp: episode.original_timestamp.clone(),
+        reused_neighborhoods: episode.reused_neighborhoods.clone(),
+    }
+}
+
+/// Flush orphaned buffer entries from the store into the system as a conversation episode.
+///
+/// Used by `am buffer flush` to ingest buffered exchanges from previous
+/// sessions; the server flushes the same way at the start of query paths.
+/// Persists the new episode and returns it, if any.
+///
+/// # Errors
+///
+/// Returns the store error if the new episode could not be saved. The
+/// episode is still in `system`, so memory and store have diverged.
+pub fn flush_orphaned_buffer<S: AmStore>(
+    store: &S,
+    system: &mut DAESystem,
+    rng: &mut SmallRng,
+    options: FlushOptions,
+) -> Result<Option<FlushedEpisode>, S::Error> {
+    if store.buffer_count().unwrap_or(0) == 0 {
+        return Ok(None);
+    }
+    let Ok(exchanges) = store.drain_buffer() else {
+        return Ok(None);
+    };
+    let (flushed, writes) = flush_exchanges(system, &exchanges, rng, options);
+    writes.persist(store)?;
+    Ok(Some(flushed))
+}
+
+/// Store figures reported by `am_stats` and the metrics gauges.
+#[derive(Debug, Clone)]
+struct StoreFigures {
+    db_size: u64,
+    repaired_records: u64,
+    buffer: usize,
+    activation: Option<ActivationStats>,
+}
+
+impl StoreFigures {
+    fn read<S: AmStore>(store: &S) -> Self {
+        Self {
+            db_size: store.db_size(),
+            repaired_records: store.repaired_records(),
+            buffer: store.buffer_count().unwrap_or(0),
+            activation: store.activation_distribution().ok(),
+        }
+    }
+}
+
+/// A write queued on the store thread, with how long it ran.
+type TimedWrite<E> = Pending<(Result<(), E>, Duration)>;
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    /// Server with every tool enabled and default settings. A brain over
+    /// [`LAZY_LOAD_THRESHOLD`] occurrences loads in the background.
+    pub fn new(store: S) -> std::result::Result<Self, S::Error> {
+        Self::builder(store).build()
+    }
+
+    /// Start configuring a server over `store`.
+    pub fn builder(store: S) -> AmServerBuilder<S> {
+        AmServerBuilder::new(store)
+    }
+
+    /// MCP `tools/list` result: the generated tool schemas minus any
+    /// disabled tools.
+    pub fn tool_list(&self) -> Value {
+        let mut list = crate::generated_schema::generated_tool_list();
+        if let Some(tools) = list["tools"].as_array_mut() {
+            tools.retain(|tool| {
+                tool["name"]
+                    .as_str()
+                    .is_none_or(|name| !self.disabled_tools.contains(name))
+            });
+        }
+        list
+    }
+
+    /// Attribute new memories to the MCP client named in the `initialize`
+    /// params (`clientInfo.name`). An actor that is already set, from
+    /// config, the builder, or an earlier `initialize`, is kept.
+    pub fn observe_client_info(&self, params: &Value) {
+        let Some(name) = params["clientInfo"]["name"].as_str() else {
+            return;
+        };
+        if name.is_empty() {
+            return;
+        }
+        // `initialize` must not wait for a background load; the name is
+        // applied when the load lands.
+        let mut state = self.state.lock().expect("poisoned mutex");
+        let Some(state) = state.as_mut() else {
+            if let Some(load) = self.pending.lock().expect("poisoned mutex").as_mut() {
+                load.client_actor = Some(name.to_string());
+            }
+            return;
+        };
+        if state.system.actor().is_none() {
+            tracing::info!("attributing new memories to MCP client {name:?}");
+            state.system.set_actor(Some(name.to_string()));
+        }
+    }
+
+    /// Whether a store write has failed since the last full save, leaving
+    /// changes in memory that the store does not have.
+    pub fn dirty_unsaved(&self) -> bool {
+        self.dirty_unsaved.load(Ordering::Relaxed)
+    }
+
+    /// Record a failed store write and build the tool error for it. The
+    /// in-memory change already happened, so the system is flagged as
+    /// diverged from the store until a full save succeeds.
+    fn save_failed(&self, context: &str, e: S::Error) -> ToolError {
+        tracing::error!("failed to persist after {context}: {e}");
+        if !self.dirty_unsaved.swap(true, Ordering::Relaxed)
+            && let Some(path) = &self.unsaved_marker
+        {
+            let note = format!("{} {context}: {e}\n", am_core::time::now_iso8601());
+            if let Err(err) = std::fs::write(path, note) {
+                tracing::warn!("failed to write {}: {err}", path.display());
+            }
+        }
+        ToolError::store::<S>(&e, format_args!("failed to persist after {context}: {e}"))
+    }
+
+    /// Clear the divergence flag after the whole system was saved.
+    fn save_succeeded(&self) {
+        if self.dirty_unsaved.swap(false, Ordering::Relaxed)
+            && let Some(path) = &self.unsaved_marker
+        {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Explicitly flush WAL on the brain store.
+    /// Belt-and-suspenders with Store::Drop, but ensures checkpoint runs
+    /// before process exit.
+    pub fn checkpoint_wal(&self) {
+        if self.is_loading() {
+            tracing::info!("skipping WAL checkpoint: memory is still loading");
+            return;
+        }
+        if let Err(e) = self.store.run(AmStore::checkpoint_truncate).wait() {
+            tracing::warn!("WAL checkpoint failed: {e}");
+        }
+        tracing::info!("WAL checkpoint complete");
+    }
+
+    /// Maintenance GC while serving: evict cold rows from the store and
+    /// drop the same rows from the loaded system under the state lock, so
+    /// no later save writes them back.
+    pub fn run_gc(&self, activation_floor: u32) -> Result<GcDetail, ToolError> {
+        let mut state = self.lock_state()?;
+        let detail = self
+            .store
+            .run(move |store| store.gc_pass(activation_floor))
+            .wait()
+            .map_err(|e| ToolError::store::<S>(&e, format_args!("GC failed: {e}")))?;
+        let removed = state.system.apply_gc(&detail);
+        self.metrics.record_gc(detail.occurrences.len());
+        if removed > 0 {
+            tracing::info!("GC evicted {removed} occurrences from the loaded system");
+        }
+        Ok(detail)
+    }
+
+    /// Dispatch a tool call by name. This is the single entry point wired
+    /// into `jsonrpc::run_stdio_loop`.
+    pub fn dispatch_tool(&self, name: &str, args: &Value) -> Result<Value, ToolError> {
+        if self.disabled_tools.contains(name) {
+            return Err(ToolError::new(
+                ErrorKind::ToolDisabled,
+                format!("tool disabled: {name}"),
+            ));
+        }
+        let started = Instant::now();
+        let result = match name {
+            "am_query" => self.am_query(args),
+            "am_query_index" => self.am_query_index(args),
+            "am_retrieve" => self.am_retrieve(args),
+            "am_knows" => self.am_knows(args),
+            "am_activate_response" => self.am_activate_response(args),
+            "am_salient" => self.am_salient(args),
+            "am_buffer" => self.am_buffer(args),
+            "am_ingest" => self.am_ingest(args),
+            "am_stats" => self.am_stats(),
+            "am_export" => self.am_export(args),
+            "am_import" => self.am_import(args),
+            "am_feedback" => self.am_feedback(args),
+            "am_batch_query" => self.am_batch_query(args),
+            "am_decisions" => self.am_decisions(args),
+            "am_episodes" => self.am_episodes(),
+            "am_conscious" => self.am_conscious(),
+            "am_episode_neighborhoods" => self.am_episode_neighborhoods(args),
+            _ => {
+                return Err(ToolError::new(
+                    ErrorKind::UnknownTool,
+                    format!("unknown tool: {name}"),
+                ));
+            }
+        };
+        if let Some(tool) = TOOL_NAMES.iter().find(|t| **t == name) {
+            self.metrics
+                .record_tool(tool, started.elapsed(), result.is_ok());
+        }
+        result
+    }
+
+    /// Counters, latency histograms, and memory gauges in the Prometheus
+    /// text format, for a metrics scrape. Waits for a running tool call,
+    /// but not for a background load or a busy store; the memory gauges
+    /// are left out until the load lands, and the store gauges report the
+    /// last figures read while the store is busy.
+    pub fn metrics_text(&self) -> String {
+        let (figures, _) = self.store_figures();
+        let gauges = self
+            .state
+            .lock()
+            .expect("poisoned mutex")
+            .as_ref()
+            .map(|state| metrics::Gauges {
+                occurrences: state.system.n(),
+                episodes: state.system.episodes.len(),
+                conscious: state.system.conscious_episode.neighborhoods.len(),
+                db_size: figures.as_ref().map_or(0, |f| f.db_size),
+                buffer: figures.as_ref().map_or(0, |f| f.buffer),
+            });
+        self.metrics
+            .render(gauges, self.is_loading(), self.dirty_unsaved())
+    }
+
+    /// Queue the store writes for `manifest`, audited as `event` when
+    /// given. Call under the state lock that made the changes, then
+    /// [`finish_save`](Self::finish_save) once it is released.
+    fn queue_manifest(
+        &self,
+        system: &DAESystem,
+        manifest: &QueryManifest,
+        event: Option<AuditEvent>,
+    ) -> TimedWrite<S::Error> {
+        let writes = ManifestWrites::new(system, manifest);
+        self.store.run(move |store| {
+            let started = Instant::now();
+            let persisted = match &event {
+                Some(event) => store.audited(event, || writes.persist(store)),
+                None => writes.persist(store),
+            };
+            (persisted, started.elapsed())
+        })
+    }
+
+    /// Wait for a write queued by [`queue_manifest`](Self::queue_manifest)
+    /// and record how long it ran in the save histogram.
+    fn finish_save(&self, write: TimedWrite<S::Error>) -> Result<Duration, S::Error> {
+        let (persisted, elapsed) = write.wait();
+        self.metrics.record_save(elapsed);
+        persisted.map(|()| elapsed)
+    }
+
+    /// Whether orphaned buffer entries may be waiting to be flushed. Read
+    /// before the caller takes the state lock, so a recall does not hold
+    /// the lock behind another call's queued write only to find the buffer
+    /// empty. While memory loads the store is busy loading; the flush then
+    /// checks under the lock.
+    fn buffer_waiting(&self) -> bool {
+        self.is_loading() || self.store.buffer_count().wait().unwrap_or(0) > 0
+    }
+
+    /// Flush orphaned buffer entries into the system, like
+    /// [`flush_orphaned_buffer`], and queue the new episode's save. Called
+    /// under the state lock; `None` when the buffer was empty.
+    fn queue_buffer_flush(&self, state: &mut ServerState) -> Option<Pending<Result<(), S::Error>>> {
+        let exchanges = self.store.drain_buffer().wait().ok()?;
+        if exchanges.is_empty() {
+            return None;
+        }
+        let ServerState {
+            system, rng, flush, ..
+        } = state;
+        let (_, writes) = flush_exchanges(system, &exchanges, rng, *flush);
+        Some(self.store.run(move |store| writes.persist(store)))
+    }
+
+    /// The store's size, repair, buffer, and activation figures, read on
+    /// the store thread when it is idle. While it is busy, the last figures
+    /// read (if any) and `true`, so a caller never waits behind a save.
+    fn store_figures(&self) -> (Option<StoreFigures>, bool) {
+        if self.store.is_busy() {
+            let cached = self.store_figures.lock().expect("poisoned mutex");
+            return (cached.clone(), true);
+        }
+        let figures = self.store.run(StoreFigures::read).wait();
+        *self.store_figures.lock().expect("poisoned mutex") = Some(figures.clone());
+        (Some(figures), false)
+    }
+
+    /// Compute a deterministic content hash for dedup.
+    ///
+    /// Uses `FxHasher` from `rustc-hash`, which produces stable output across
+    /// Rust releases and process restarts (unlike `DefaultHasher`).
+    fn content_hash(user: &str, assistant: &str) -> u64 {
+        let mut hasher = FxHasher::default();
+        user.hash(&mut hasher);
+        b"\n".hash(&mut hasher);
+        assistant.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Remove expired entries from the dedup window.
+    fn clean_dedup_window(window: &mut HashMap<u64, Instant>) {
+        let cutoff = Instant::now() - std::time::Duration::from_secs(DEDUP_WINDOW_SECS);
+        window.retain(|_, ts| *ts > cutoff);
+    }
+
+    fn stats_json(system: &DAESystem) -> serde_json::Value {
+        let n = system.n();
+        let episodes = system.episodes.len();
+        let conscious = system.conscious_episode.neighborhoods.len();
+        serde_json::json!({
+            "n": n,
+            "episodes": episodes,
+            "conscious": conscious,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "server_tests.rs"]
+mod tests;
@@ -0,0 +1,95 @@
+//! Path policy for MCP tools that touch files.
+//!
+//! A prompt-injected agent controls every MCP argument, so a `path` field
+//! is an arbitrary-file read or write as the user. File operations started
+//! over MCP are confined to an allowlist of directories; the CLI calls the
+//! same operations unrestricted.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{ErrorKind, ToolError};
+
+/// Directories MCP file operations may read from and write to.
+#[derive(Debug, Clone)]
+pub(crate) struct PathPolicy {
+    /// Canonical allowed directories. Roots that do not exist are dropped.
+    roots: Vec<PathBuf>,
+}
+
+impl PathPolicy {
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut canonical: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            match root.canonicalize() {
+                Ok(path) if !canonical.contains(&path) => canonical.push(path),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("ignoring allowed path {}: {e}", root.display()),
+            }
+        }
+        Self { roots: canonical }
+    }
+
+    /// The project root: the directory the server was started in.
+    pub fn project_root() -> Self {
+        Self::new(std::env::current_dir().ok())
+    }
+
+    /// Resolve `path` for reading. Symlinks are followed before the check,
+    /// so a link inside an allowed directory cannot point outside it.
+    pub fn check_read(&self, path: &Path) -> Result<PathBuf, ToolError> {
+        let resolved = path.canonicalize().map_err(|e| {
+            ToolError::io(&e, format!("[path] cannot read {}: {e}", path.display()))
+        })?;
+        self.check_resolved(path, resolved)
+    }
+
+    /// Resolve `path` for writing. The file may not exist yet; its parent
+    /// must. An existing file or symlink is resolved to its target, and a
+    /// dangling symlink is refused since writing would create its target.
+    pub fn check_write(&self, path: &Path) -> Result<PathBuf, ToolError> {
+        let resolved = if path.symlink_metadata().is_ok() {
+            path.canonicalize().map_err(|e| {
+                ToolError::io(&e, format!("[path] cannot resolve {}: {e}", path.display()))
+            })?
+        } else {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(ToolError::invalid_params(format!(
+                    "[path] not a file path: {}",
+                    path.display()
+                )));
+            };
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            parent
+                .canonicalize()
+                .map_err(|e| {
+                    ToolError::io(&e, format!("[path] cannot write {}: {e}", path.display()))
+                })?
+                .join(name)
+        };
+        self.check_resolved(path, resolved)
+    }
+
+    fn check_resolved(&self, requested: &Path, resolved: PathBuf) -> Result<PathBuf, ToolError> {
+        if self.roots.iter().any(|root| resolved.starts_with(root)) {
+            return Ok(resolved);
+        }
+        let allowed: Vec<String> = self.roots.iter().map(|r| r.display().to_string()).collect();
+        Err(ToolError::new(
+            ErrorKind::PathDenied,
+            format!(
+                "[path] {} is outside the allowed directories ({}); add its directory to \
+             mcp.allowed_paths in .am.config.toml",
+                requested.display(),
+                if allowed.is_empty() {
+                    "none".to_string()
+                } else {
+                    allowed.join(", ")
+                }
+            ),
+        ))
+    }
+}
@@ -0,0 +1,679 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use am_core::{
+    compose::{
+        BudgetConfig, CandidateCounts, Confidence, CutoffReason, EmptyReason, FilteredCandidate,
+        RankedFragment, RecallCategory, compose_context, compose_context_budgeted, compose_index,
+        peek, probe, retrieve_by_ids,
+    },
+    query::{QueryEngine, QueryResult},
+    store_trait::AmStore,
+    surface::{SurfaceResult, compute_surface},
+    system::{DAESystem, normalize_project},
+    tokenizer::tokenize,
+};
+
+use super::loading::NotReady;
+use super::{AmServer, ServerState, check_input_size, parse_params, project_note};
+use crate::error::ToolError;
+use crate::jsonrpc::tool_result_text;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct QueryRequest {
+    /// The text to query the memory system with
+    text: String,
+    /// Optional maximum token budget for composed context.
+    max_tokens: Option<usize>,
+    /// Include per-token word weights in the response.
+    #[serde(default)]
+    debug: bool,
+    /// Also search archived episodes, for this query only.
+    #[serde(default)]
+    include_archived: bool,
+    /// Also search episodes moved into epoch files, for this query only.
+    #[serde(default)]
+    include_epochs: bool,
+    /// Override the minimum distinct query words a candidate must match.
+    min_activated_words: Option<usize>,
+    /// Override the minimum candidate score.
+    min_score: Option<f64>,
+    /// Only recall memories created by this actor.
+    actor: Option<String>,
+    /// Favour memories tagged with this project, for this query only.
+    project: Option<String>,
+    /// Activate, drift, and count the recall as usual. `false` previews
+    /// recall without changing memory.
+    #[serde(default = "default_learn")]
+    learn: bool,
+    /// Run the full query, drift and all, then roll every change back
+    /// once the context is composed.
+    #[serde(default)]
+    speculative: bool,
+    /// Also return the top-ranked candidates, recalled or not, for the
+    /// caller to rerank. Needs `max_tokens`.
+    #[serde(default)]
+    include_candidates: bool,
+    /// How many candidates `include_candidates` returns.
+    candidate_limit: Option<usize>,
+}
+
+/// Candidates `include_candidates` returns unless `candidate_limit` says.
+const DEFAULT_CANDIDATE_LIMIT: usize = 20;
+
+/// Most candidates one query returns, whatever `candidate_limit` says.
+const MAX_CANDIDATE_LIMIT: usize = 200;
+
+/// Characters of a candidate's text returned as its preview.
+const CANDIDATE_PREVIEW_CHARS: usize = 200;
+
+fn default_learn() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct QueryIndexRequest {
+    /// The query text to search memory for
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct KnowsRequest {
+    /// The text to check memory for
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RetrieveByIdsRequest {
+    /// Neighborhood UUIDs to retrieve full content for
+    ids: Vec<String>,
+}
+
+/// Per-category candidate counts before selection, for agents deciding
+/// whether to broaden a query.
+fn candidates_json(counts: &CandidateCounts) -> Value {
+    serde_json::json!({
+        "conscious": counts.conscious,
+        "subconscious": counts.subconscious,
+        "novel": counts.novel,
+        "activated_words": counts.activated_words,
+        "filtered": counts.filtered,
+    })
+}
+
+/// Ranked candidates for agent-side reranking, best first.
+fn ranked_json(ranked: &[RankedFragment]) -> Value {
+    let entries: Vec<Value> = ranked
+        .iter()
+        .map(|r| {
+            let preview = if r.text.len() <= CANDIDATE_PREVIEW_CHARS {
+                r.text.clone()
+            } else {
+                format!(
+                    "{}...",
+                    &r.text[..r.text.floor_char_boundary(CANDIDATE_PREVIEW_CHARS)]
+                )
+            };
+            serde_json::json!({
+                "id": r.neighborhood_id.to_string(),
+                "category": format!("{:?}", r.category),
+                "score": r.score,
+                "tokens": r.tokens,
+                "episode": r.episode_name,
+                "preview": preview,
+                "included": r.included,
+            })
+        })
+        .collect();
+    serde_json::json!(entries)
+}
+
+/// A stage duration in milliseconds, to the microsecond.
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1e6).round() / 1e3
+}
+
+/// Log neighborhoods dropped for a NaN or infinite score. Recall carries
+/// on without them, but the score means corrupt geometry or weights
+/// somewhere in the store.
+fn warn_invalid_scores(filtered: &[FilteredCandidate]) {
+    for f in filtered {
+        if f.reason == CutoffReason::InvalidScore {
+            tracing::warn!(
+                "neighborhood {} scored {}; left out of recall",
+                f.neighborhood_id,
+                f.score
+            );
+        }
+    }
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    pub(super) fn am_query(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: QueryRequest = parse_params(args)?;
+        check_input_size(&req.text, "text")?;
+        if req.min_activated_words == Some(0) {
+            return Err(ToolError::invalid_params(
+                "invalid params: min_activated_words must be at least 1",
+            ));
+        }
+        if req.min_score.is_some_and(|s| !s.is_finite() || s < 0.0) {
+            return Err(ToolError::invalid_params(
+                "invalid params: min_score must be a finite number >= 0",
+            ));
+        }
+        if req.include_candidates && req.max_tokens.is_none() {
+            return Err(ToolError::invalid_params(
+                "invalid params: include_candidates needs max_tokens",
+            ));
+        }
+
+        // Read before taking the state lock, so the lock is not held while
+        // these queue behind another call's write. During a background
+        // load the store is busy loading; assume both may be set.
+        let speculative = req.learn && req.speculative;
+        let buffered = req.learn && !speculative && self.buffer_waiting();
+        let epochs_stored = !req.include_epochs
+            && (self.is_loading()
+                || self.store.run(AmStore::has_epochs).wait().map_err(|e| {
+                    ToolError::store::<S>(&e, format_args!("failed to read epoch files: {e}"))
+                })?);
+
+        let mut state = match self.lock_state() {
+            Ok(state) => state,
+            // A structured result, so clients can retry instead of
+            // reporting a failure.
+            Err(NotReady::Loading(preflight)) => {
+                let result = serde_json::json!({
+                    "context": "",
+                    "empty_reason": "still_loading",
+                    "confidence": Confidence::None.as_str(),
+                    "suggestion": format!(
+                        "Memory is still loading ({} occurrences); retry shortly",
+                        preflight.occurrences
+                    ),
+                    "loading": preflight.to_json(),
+                });
+                return Ok(tool_result_text(
+                    &serde_json::to_string_pretty(&result).unwrap_or_default(),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let flush_write = if buffered {
+            self.queue_buffer_flush(&mut state)
+        } else {
+            None
+        };
+        let ServerState {
+            system,
+            session_recalled,
+            ..
+        } = &mut *state;
+
+        // Deep recall: splice archived episodes in for this query only. They
+        // are truncated away again before returning, as are epoch episodes.
+        let live_episodes = system.episodes.len();
+        if req.include_archived {
+            let archived = self
+                .store
+                .run(AmStore::load_archived_episodes)
+                .wait()
+                .map_err(|e| {
+                    ToolError::store::<S>(&e, format_args!("failed to load archived episodes: {e}"))
+                })?;
+            system.episodes.extend(archived);
+            system.mark_dirty();
+        }
+
+        // Per-call cutoffs replace the stored ones until this query returns
+        let stored_config = system.compose_config().clone();
+        let mut config = stored_config.clone();
+        if let Some(n) = req.min_activated_words {
+            config.min_activated_words = n;
+        }
+        if let Some(score) = req.min_score {
+            config.min_score = score;
+        }
+        system.set_compose_config(config);
+        let stored_filter = system.actor_filter().map(str::to_owned);
+        if req.actor.is_some() {
+            system.set_actor_filter(req.actor.clone());
+        }
+        let stored_project = system.project().map(str::to_owned);
+        let project_override = req.project.as_deref().and_then(normalize_project);
+        if let Some(project) = &project_override {
+            system.set_project(Some(project.clone()));
+        }
+
+        // Federated recall: epoch files join the same way, when asked or
+        // when nothing in the active database would be recalled.
+        let with_archived = system.episodes.len();
+        let epoch_fallback = epochs_stored && !probe(system, &req.text).is_known();
+        if req.include_epochs || epoch_fallback {
+            let epochs = self
+                .store
+                .run(AmStore::load_epoch_episodes)
+                .wait()
+                .map_err(|e| {
+                    ToolError::store::<S>(&e, format_args!("failed to load epoch episodes: {e}"))
+                })?;
+            if !epochs.is_empty() {
+                system.episodes.extend(epochs);
+                system.mark_dirty();
+            }
+        }
+
+        let (mut result, new_ids, write) = if req.learn {
+            if speculative {
+                system.begin_speculation();
+            }
+            let query_result = QueryEngine::process_query(system, &req.text);
+            let surface = compute_surface(system, &query_result);
+            let (mut result, ids) = self.compose_query(
+                system,
+                &query_result,
+                &surface,
+                &req,
+                Some(session_recalled),
+            );
+            if speculative {
+                // What-if: recall saw the drifted manifold, memory keeps
+                // none of it. Rolled back before any spliced episodes go.
+                system.rollback();
+                result["speculative"] = serde_json::json!(true);
+                (result, Vec::new(), None)
+            } else {
+                let write = self.queue_manifest(system, &query_result.manifest, None);
+                (result, ids, Some(write))
+            }
+        } else {
+            // Preview: nothing is activated, drifted, saved, or counted as
+            // recalled, and this session's earlier recall is not discounted
+            let (result, _) = peek(system, &req.text, |system, query_result, surface| {
+                self.compose_query(system, query_result, surface, &req, None)
+            });
+            (result, Vec::new(), None)
+        };
+
+        if req.include_archived {
+            result["archived_episodes"] = serde_json::json!(with_archived - live_episodes);
+        }
+        let epoch_episodes = system.episodes.len() - with_archived;
+        if req.include_epochs || epoch_episodes > 0 {
+            result["epoch_episodes"] = serde_json::json!(epoch_episodes);
+        }
+        if epoch_fallback && epoch_episodes > 0 {
+            result["epoch_fallback"] = serde_json::json!(true);
+        }
+        if let Some(project) = &project_override
+            && system.project_memory_count(project) == 0
+        {
+            result["project_note"] = serde_json::json!(project_note(project));
+        }
+
+        // Increment recall count for returned neighborhood IDs (diminishing returns)
+        for id in new_ids {
+            *session_recalled.entry(id).or_insert(0) += 1;
+        }
+
+        if system.episodes.len() > live_episodes {
+            system.episodes.truncate(live_episodes);
+            system.mark_dirty();
+        }
+        system.set_compose_config(stored_config);
+        system.set_actor_filter(stored_filter);
+        system.set_project(stored_project);
+        drop(state);
+
+        if let Some(flush_write) = flush_write {
+            flush_write
+                .wait()
+                .map_err(|e| self.save_failed("buffer flush", e))?;
+        }
+        if let Some(write) = write {
+            let saved = self.finish_save(write);
+            if let Ok(elapsed) = &saved {
+                result["timings"]["save_ms"] = serde_json::json!(millis(*elapsed));
+            }
+            saved.map_err(|e| self.save_failed("query", e))?;
+        }
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    /// Compose an `am_query` result: the budgeted or fixed-size context,
+    /// the compact index, and the debug payload. Returns the result and the
+    /// neighborhoods it recalled.
+    fn compose_query(
+        &self,
+        system: &mut DAESystem,
+        query_result: &QueryResult,
+        surface: &SurfaceResult,
+        req: &QueryRequest,
+        session_recalled: Option<&HashMap<Uuid, u32>>,
+    ) -> (Value, Vec<Uuid>) {
+        let compose_started = Instant::now();
+        let (mut result, new_ids) = if let Some(max_tokens) = req.max_tokens {
+            // Budgeted query: Nancy's prompt compiler uses this
+            let candidate_limit = if req.include_candidates {
+                req.candidate_limit
+                    .unwrap_or(DEFAULT_CANDIDATE_LIMIT)
+                    .min(MAX_CANDIDATE_LIMIT)
+            } else {
+                0
+            };
+            let budget = BudgetConfig {
+                max_tokens,
+                candidate_limit,
+                ..self.budget
+            };
+            let composed =
+                compose_context_budgeted(system, surface, query_result, &budget, session_recalled);
+            warn_invalid_scores(&composed.filtered);
+            let ids: Vec<Uuid> = composed
+                .included
+                .iter()
+                .map(|f| f.neighborhood_id)
+                .collect();
+            // Categorize IDs from IncludedFragment for feedback tracking
+            let mut con_ids = Vec::new();
+            let mut sub_ids = Vec::new();
+            let mut nov_ids = Vec::new();
+            for f in &composed.included {
+                match f.category {
+                    RecallCategory::Conscious => con_ids.push(f.neighborhood_id.to_string()),
+                    RecallCategory::Subconscious => sub_ids.push(f.neighborhood_id.to_string()),
+                    RecallCategory::Novel => nov_ids.push(f.neighborhood_id.to_string()),
+                }
+            }
+            let mut json = serde_json::json!({
+                "context": composed.context,
+                "metrics": {
+                    "conscious": composed.metrics.conscious,
+                    "subconscious": composed.metrics.subconscious,
+                    "novel": composed.metrics.novel,
+                },
+                "recalled_ids": {
+                    "conscious": con_ids,
+                    "subconscious": sub_ids,
+                    "novel": nov_ids,
+                },
+                "token_estimate": {
+                    "conscious": composed.token_estimate.conscious,
+                    "subconscious": composed.token_estimate.subconscious,
+                    "novel": composed.token_estimate.novel,
+                    "total": composed.token_estimate.total,
+                },
+                "budget": {
+                    "tokens_used": composed.tokens_used,
+                    "tokens_budget": composed.tokens_budget,
+                    "included_count": composed.included.len(),
+                    "excluded_count": composed.excluded_count,
+                },
+                "candidates": candidates_json(&composed.candidates),
+                "suggestion": composed.candidates.suggestion(),
+                "empty_reason": composed.empty_reason.map(EmptyReason::as_str),
+                "confidence": composed.confidence.as_str(),
+                "stats": Self::stats_json(system),
+            });
+            if req.include_candidates {
+                json["ranked_candidates"] = ranked_json(&composed.ranked);
+            }
+            (json, ids)
+        } else {
+            // Default: fixed-size composition
+            let composed = compose_context(system, surface, query_result, session_recalled);
+            warn_invalid_scores(&composed.filtered);
+            let ids = composed.included_ids.clone();
+            let recalled = &composed.recalled_ids;
+            let json = serde_json::json!({
+                "context": composed.context,
+                "metrics": {
+                    "conscious": composed.metrics.conscious,
+                    "subconscious": composed.metrics.subconscious,
+                    "novel": composed.metrics.novel,
+                },
+                "recalled_ids": {
+                    "conscious": recalled.conscious.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "subconscious": recalled.subconscious.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "novel": recalled.novel.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                },
+                "token_estimate": {
+                    "conscious": composed.token_estimate.conscious,
+                    "subconscious": composed.token_estimate.subconscious,
+                    "novel": composed.token_estimate.novel,
+                    "total": composed.token_estimate.total,
+                },
+                "candidates": candidates_json(&composed.candidates),
+                "suggestion": composed.candidates.suggestion(),
+                "empty_reason": composed.empty_reason.map(EmptyReason::as_str),
+                "confidence": composed.confidence.as_str(),
+                "stats": Self::stats_json(system),
+            });
+            (json, ids)
+        };
+
+        // A hot word only activated part of its occurrences
+        if !query_result.capped_words.is_empty() {
+            let capped: Vec<Value> = query_result
+                .capped_words
+                .iter()
+                .map(|c| {
+                    serde_json::json!({
+                        "word": c.word,
+                        "activated": c.activated,
+                        "skipped": c.skipped,
+                    })
+                })
+                .collect();
+            result["capped_words"] = serde_json::json!(capped);
+        }
+
+        // Compose compact index summary (top 10 entries, most recent first)
+        let index = compose_index(system, surface, query_result, session_recalled);
+        let candidate_tokens = index.total_tokens_if_fetched();
+        let mut sorted_entries = index.entries;
+        sorted_entries.sort_by_key(|e| std::cmp::Reverse(e.epoch));
+        let index_entries: Vec<serde_json::Value> = sorted_entries
+            .iter()
+            .take(10)
+            .map(|e| {
+                serde_json::json!({
+                    "id": e.neighborhood_id.to_string(),
+                    "category": format!("{:?}", e.category),
+                    "type": format!("{:?}", e.neighborhood_type),
+                    "score": (e.score * 100.0).round() / 100.0,
+                    "epoch": e.epoch,
+                    "summary": e.summary,
+                    "token_estimate": e.token_estimate,
+                })
+            })
+            .collect();
+        result["index"] = serde_json::json!(index_entries);
+        // What recalling every candidate would cost, so the caller can size
+        // max_tokens on the next call
+        result["estimate"] = serde_json::json!({
+            "candidates": sorted_entries.len(),
+            "tokens": candidate_tokens,
+        });
+
+        // Per-stage wall time; am_query fills in save_ms once the manifest
+        // is persisted
+        let timings = query_result.timings;
+        result["timings"] = serde_json::json!({
+            "activation_ms": millis(timings.activation),
+            "drift_ms": millis(timings.drift),
+            "compose_ms": millis(compose_started.elapsed()),
+            "save_ms": 0.0,
+        });
+
+        if req.debug {
+            let weights: serde_json::Map<String, Value> = tokenize(&req.text)
+                .into_iter()
+                .map(|word| {
+                    let weight = system.get_word_weight(&word);
+                    (word, serde_json::json!(weight))
+                })
+                .collect();
+            // Feedback scores of the neighborhoods recalled or indexed, so
+            // a memory held down by am_feedback can be told apart from one
+            // that just matched poorly
+            let mut feedback = serde_json::Map::new();
+            for id in new_ids
+                .iter()
+                .chain(sorted_entries.iter().map(|e| &e.neighborhood_id))
+            {
+                if let Some(r) = system.get_neighborhood_ref(*id) {
+                    let score = system.get_neighborhood(r).feedback_score;
+                    if score != 0.0 {
+                        feedback.insert(id.to_string(), serde_json::json!(score));
+                    }
+                }
+            }
+            // Active word biases, already folded into `weights`
+            let biases: serde_json::Map<String, Value> = system
+                .word_biases()
+                .iter()
+                .map(|(word, multiplier)| (word.clone(), serde_json::json!(multiplier)))
+                .collect();
+            result["debug"] = serde_json::json!({
+                "weights": weights,
+                "feedback": feedback,
+                "biases": biases,
+            });
+        }
+
+        (result, new_ids)
+    }
+
+    pub(super) fn am_query_index(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: QueryIndexRequest = parse_params(args)?;
+        check_input_size(&req.text, "text")?;
+
+        let buffered = self.buffer_waiting();
+        let mut state = self.lock_state()?;
+        let flush_write = if buffered {
+            self.queue_buffer_flush(&mut state)
+        } else {
+            None
+        };
+        let ServerState {
+            system,
+            session_recalled,
+            ..
+        } = &mut *state;
+
+        let query_result = QueryEngine::process_query(system, &req.text);
+        let surface = compute_surface(system, &query_result);
+
+        let index = compose_index(system, &surface, &query_result, Some(session_recalled));
+
+        let write = self.queue_manifest(system, &query_result.manifest, None);
+        let stats = Self::stats_json(system);
+        drop(state);
+        if let Some(flush_write) = flush_write {
+            flush_write
+                .wait()
+                .map_err(|e| self.save_failed("buffer flush", e))?;
+        }
+        self.finish_save(write)
+            .map_err(|e| self.save_failed("query_index", e))?;
+
+        let entries_json: Vec<serde_json::Value> = index
+            .entries
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "id": e.neighborhood_id.to_string(),
+                    "category": format!("{:?}", e.category),
+                    "type": format!("{:?}", e.neighborhood_type),
+                    "score": (e.score * 100.0).round() / 100.0,
+                    "epoch": e.epoch,
+                    "summary": e.summary,
+                    "token_estimate": e.token_estimate,
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "entries": entries_json,
+            "total_candidates": index.total_candidates(),
+            "total_tokens_if_fetched": index.total_tokens_if_fetched(),
+            "stats": stats,
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    /// Whether memory holds anything about `text`. Read-only: nothing is
+    /// activated, drifted, composed, or counted as recalled this session.
+    pub(super) fn am_knows(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: KnowsRequest = parse_params(args)?;
+        check_input_size(&req.text, "text")?;
+
+        let mut state = self.lock_state()?;
+        let result = probe(&mut state.system, &req.text);
+
+        let result = serde_json::json!({
+            "known": result.is_known(),
+            "candidates": candidates_json(&result.candidates),
+            "top_score": result.top_score,
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_retrieve(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: RetrieveByIdsRequest = parse_params(args)?;
+
+        let mut state = self.lock_state()?;
+        let ServerState { system, .. } = &mut *state;
+
+        let ids: Vec<Uuid> = req
+            .ids
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .collect();
+
+        let fragments = retrieve_by_ids(system, &ids);
+
+        // Track these as recalled for diminishing returns
+        for f in &fragments {
+            *state.session_recalled.entry(f.neighborhood_id).or_insert(0) += 1;
+        }
+
+        let entries_json: Vec<serde_json::Value> = fragments
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "id": f.neighborhood_id.to_string(),
+                    "category": format!("{:?}", f.category),
+                    "type": format!("{:?}", f.neighborhood_type),
+                    "episode": f.episode_name,
+                    "tokens": f.tokens,
+                    "text": f.text,
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "entries": entries_json,
+            "count": fragments.len(),
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+}
@@ -0,0 +1,3115 @@
+use super::*;
+use am_core::compose::{ComposeConfig, RerankStage};
+use am_core::neighborhood::NeighborhoodType;
+use am_store::project::BrainStore;
+
+fn make_server() -> AmServer<BrainStore> {
+    let store = BrainStore::open_in_memory().unwrap();
+    AmServer::new(store).unwrap()
+}
+
+/// Run `call` on the server's store thread and wait for it.
+fn with_store<S, T, F>(server: &AmServer<S>, call: F) -> T
+where
+    S: AmStore + Send + 'static,
+    T: Send + 'static,
+    F: FnOnce(&S) -> T + Send + 'static,
+{
+    server.store.run(call).wait()
+}
+
+fn parse_tool_result(result: &Value) -> serde_json::Value {
+    let text = result["content"][0]["text"]
+        .as_str()
+        .expect("should have text content");
+    serde_json::from_str(text).expect("handler should return valid JSON in text content")
+}
+
+#[test]
+fn test_am_stats_empty() {
+    let server = make_server();
+    let result = server.am_stats().unwrap();
+    let json = parse_tool_result(&result);
+
+    assert_eq!(json["n"], 0);
+    assert_eq!(json["episodes"], 0);
+    assert_eq!(json["conscious"], 0);
+}
+
+#[test]
+fn test_am_ingest() {
+    let server = make_server();
+
+    let result = server
+            .am_ingest(&serde_json::json!({
+                "text": "The quick brown fox jumps over the lazy dog. Sentence two here. And a third sentence for good measure.",
+                "name": "test-doc"
+            }))
+            .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert_eq!(json["episode"], "test-doc");
+    assert!(json["neighborhoods"].as_u64().unwrap() >= 1);
+    assert!(json["occurrences"].as_u64().unwrap() > 0);
+
+    // Stats should reflect the ingestion
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert!(stats["n"].as_u64().unwrap() > 0);
+    assert_eq!(stats["episodes"], 1);
+}
+
+#[test]
+fn test_am_ingest_chunks_become_one_neighborhood_each() {
+    let server = make_server();
+    let texts = [
+        "fn parse(input: &str) -> Tokens. Splits on whitespace. Never panics. Returns early on EOF.",
+        "TICKET-118: login loop after token refresh",
+        "Deploy checklist: migrate, restart workers, verify health.",
+    ];
+
+    let result = server
+        .am_ingest(&serde_json::json!({
+            "name": "pipeline",
+            "chunks": [
+                {"text": texts[0], "name": "parser"},
+                {"text": texts[1], "tags": ["auth", "oncall"]},
+                {"text": texts[2]},
+            ]
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert_eq!(json["episode"], "pipeline");
+    assert_eq!(json["neighborhoods"], texts.len());
+
+    let state = server.lock_state().unwrap();
+    let stored: Vec<&str> = state.system.episodes[0]
+        .neighborhoods
+        .iter()
+        .map(|n| n.source_text.as_str())
+        .collect();
+    assert_eq!(stored, texts);
+}
+
+#[test]
+fn test_am_ingest_rejects_bad_chunk_input() {
+    let server = make_server();
+    let err = server
+        .am_ingest(&serde_json::json!({"chunks": [{"text": "ok"}, {"text": "   "}]}))
+        .unwrap_err();
+    assert!(err.message().contains("chunk 1 has no words"), "{err}");
+
+    let err = server
+        .am_ingest(&serde_json::json!({"text": "both", "chunks": [{"text": "both"}]}))
+        .unwrap_err();
+    assert!(
+        err.message().contains("exactly one of text or chunks"),
+        "{err}"
+    );
+    assert!(
+        server
+            .am_ingest(&serde_json::json!({"chunks": []}))
+            .is_err()
+    );
+
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(stats["episodes"], 0);
+}
+
+#[test]
+fn test_am_ingest_dry_run_stores_nothing() {
+    let server = make_server();
+    let text = "Dry runs chunk the text. They count occurrences. They save nothing. \
+                A fourth sentence starts a second chunk.";
+
+    let result = server
+        .am_ingest(&serde_json::json!({"text": text, "name": "preview", "dry_run": true}))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["neighborhoods"], 2);
+    let occurrences = json["occurrences"].as_u64().unwrap();
+    assert!(occurrences > 0);
+    assert_eq!(
+        json["estimated_bytes"].as_u64().unwrap(),
+        occurrences * json["bytes_per_occurrence"].as_u64().unwrap()
+    );
+    assert_eq!(
+        json["chunks"][1],
+        "A fourth sentence starts a second chunk."
+    );
+
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(stats["n"], 0);
+    assert_eq!(stats["episodes"], 0);
+}
+
+#[test]
+fn test_am_ingest_enforces_max_ingest_bytes() {
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .max_ingest_bytes(Some(16))
+        .build()
+        .unwrap();
+    let err = server
+        .am_ingest(&serde_json::json!({"text": "this text is longer than sixteen bytes"}))
+        .unwrap_err();
+    assert!(err.message().contains("38 bytes"), "{err}");
+    let err = server
+        .am_ingest(&serde_json::json!({"chunks": [{"text": "ten bytes."}, {"text": "ten more.."}]}))
+        .unwrap_err();
+    assert!(err.message().contains("20 bytes"), "{err}");
+
+    server
+        .am_ingest(&serde_json::json!({"text": "short enough"}))
+        .unwrap();
+}
+
+#[test]
+fn test_am_query_response_structure() {
+    let server = make_server();
+
+    // Ingest content first
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Quantum mechanics describes particle behavior at subatomic scales. Wave functions collapse on measurement.",
+                "name": "science"
+            }))
+            .unwrap();
+
+    // Add conscious content
+    server
+        .am_salient(&serde_json::json!({
+            "text": "quantum computing is revolutionary"
+        }))
+        .unwrap();
+
+    // Query
+    let result = server
+        .am_query(&serde_json::json!({
+            "text": "quantum particles"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+
+    // Verify response structure has required fields
+    assert!(json.get("context").is_some(), "should have context field");
+    assert!(json.get("metrics").is_some(), "should have metrics field");
+    assert!(json.get("stats").is_some(), "should have stats field");
+
+    let metrics = &json["metrics"];
+    assert!(metrics.get("conscious").is_some());
+    assert!(metrics.get("subconscious").is_some());
+    assert!(metrics.get("novel").is_some());
+
+    let stats = &json["stats"];
+    assert!(stats["n"].as_u64().unwrap() > 0);
+
+    // Verify token_estimate field exists with per-category breakdown
+    let te = &json["token_estimate"];
+    assert!(
+        te.get("conscious").is_some(),
+        "should have token_estimate.conscious"
+    );
+    assert!(
+        te.get("subconscious").is_some(),
+        "should have token_estimate.subconscious"
+    );
+    assert!(
+        te.get("novel").is_some(),
+        "should have token_estimate.novel"
+    );
+    assert!(
+        te.get("total").is_some(),
+        "should have token_estimate.total"
+    );
+    // Total should be sum of categories
+    let total = te["total"].as_u64().unwrap();
+    let sum = te["conscious"].as_u64().unwrap()
+        + te["subconscious"].as_u64().unwrap()
+        + te["novel"].as_u64().unwrap();
+    assert_eq!(total, sum, "total should equal sum of categories");
+    // With content ingested, total should be > 0
+    assert!(total > 0, "token estimate should be positive with content");
+}
+
+#[test]
+fn test_am_salient_stores_conscious() {
+    let server = make_server();
+
+    let result = server
+        .am_salient(&serde_json::json!({
+            "text": "important insight about neural networks"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert_eq!(json["stored"], 1);
+
+    // Stats should show conscious memory
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert!(
+        stats["conscious"].as_u64().unwrap() >= 1,
+        "should have at least one conscious neighborhood"
+    );
+}
+
+#[test]
+fn test_am_salient_with_tags() {
+    let server = make_server();
+
+    let result = server
+            .am_salient(&serde_json::json!({
+                "text": "Normal text <salient>first insight</salient> middle <salient>second insight</salient> end"
+            }))
+            .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert_eq!(json["stored"], 2);
+
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert!(stats["conscious"].as_u64().unwrap() >= 2);
+}
+
+#[test]
+fn test_am_salient_merges_paraphrased_repeats() {
+    let server = make_server();
+    let salient = |args: serde_json::Value| parse_tool_result(&server.am_salient(&args).unwrap());
+
+    let first = salient(serde_json::json!({"text": "user prefers tabs over spaces"}));
+    assert!(first.get("deduplicated").is_none());
+    let again = salient(serde_json::json!({"text": "The user prefers tabs over spaces."}));
+    assert_eq!(again["deduplicated"], true);
+    assert_eq!(again["stored"], 1);
+    assert_eq!(again["stats"]["conscious"], 1);
+
+    // The reinforcement is persisted, not just held in memory
+    {
+        let stored = with_store(&server, |store| store.load_system().unwrap());
+        let conscious = &stored.conscious_episode.neighborhoods;
+        assert_eq!(conscious.len(), 1);
+        assert_eq!(again["reinforced"][0], conscious[0].id.to_string());
+        assert!(
+            conscious[0]
+                .occurrences
+                .iter()
+                .all(|o| o.activation_count == 2)
+        );
+    }
+
+    let different = salient(serde_json::json!({"text": "user prefers dark mode in the editor"}));
+    assert!(different.get("deduplicated").is_none());
+    assert_eq!(different["stats"]["conscious"], 2);
+
+    let forced = salient(serde_json::json!({
+        "text": "user prefers tabs over spaces",
+        "force": true
+    }));
+    assert!(forced.get("deduplicated").is_none());
+    assert_eq!(forced["stats"]["conscious"], 3);
+}
+
+#[test]
+fn test_am_activate_response() {
+    let server = make_server();
+
+    // Ingest content first
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Machine learning enables pattern recognition in data. Neural networks learn representations.",
+                "name": "ml-doc"
+            }))
+            .unwrap();
+
+    let result = server
+        .am_activate_response(&serde_json::json!({
+            "text": "machine learning neural networks"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert!(json["activated"].as_u64().unwrap() > 0);
+    assert!(json.get("stats").is_some());
+
+    let strengthened = &json["strengthened"];
+    let words: Vec<&str> = strengthened["top_words"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["word"].as_str().unwrap())
+        .collect();
+    assert!(!words.is_empty());
+    for word in &words {
+        assert!(
+            ["machine", "learning", "neural", "networks"].contains(word),
+            "unexpected word {word}"
+        );
+    }
+    for moved in strengthened["moved_neighborhoods"].as_array().unwrap() {
+        assert_eq!(moved["episode"], "ml-doc");
+        assert!(moved["mean_delta"].as_f64().unwrap() > 0.0);
+    }
+    assert_eq!(strengthened["coupling"]["fired"], false);
+}
+
+#[test]
+fn test_am_activate_response_drift_scale() {
+    let server = make_server();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Machine learning enables pattern recognition in data. Neural networks learn representations.",
+            "name": "ml-doc"
+        }))
+        .unwrap();
+
+    let result = server
+        .am_activate_response(&serde_json::json!({
+            "text": "machine learning neural networks",
+            "drift_scale": 0.0
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert!(json["activated"].as_u64().unwrap() > 0);
+    // Unmoved positions can still report float noise from acos near 1
+    for moved in json["strengthened"]["moved_neighborhoods"]
+        .as_array()
+        .unwrap()
+    {
+        assert!(moved["mean_delta"].as_f64().unwrap() < 1e-6, "{moved}");
+    }
+
+    let err = server
+        .am_activate_response(&serde_json::json!({
+            "text": "machine learning",
+            "drift_scale": 1.5
+        }))
+        .unwrap_err();
+    assert!(err.message().contains("drift_scale"), "{err}");
+}
+
+#[test]
+fn test_am_buffer() {
+    let server = make_server();
+
+    // Buffer exchanges below threshold (threshold is 3)
+    for i in 0..2 {
+        let result = server
+            .am_buffer(&serde_json::json!({
+                "user": format!("User message {i}"),
+                "assistant": format!("Assistant response {i}")
+            }))
+            .unwrap();
+
+        let json = parse_tool_result(&result);
+        assert_eq!(json["buffer_size"], i + 1);
+        assert!(json["episode_created"].is_null());
+    }
+
+    // 3rd exchange should trigger episode creation
+    let result = server
+        .am_buffer(&serde_json::json!({
+            "user": "User message 2",
+            "assistant": "Assistant response 2"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert_eq!(json["buffer_size"], 3);
+    assert!(
+        json["episode_created"].is_string(),
+        "should create episode after 3 exchanges"
+    );
+
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(stats["episodes"], 1);
+}
+
+#[test]
+fn test_am_buffer_names_episode_from_content() {
+    let server = make_server();
+    let exchanges = [
+        (
+            "Why is the postgres migration slow?",
+            "The migration rewrites every index. Postgres locks the table during the rewrite.",
+        ),
+        (
+            "Can we batch the migration?",
+            "Yes, split the postgres index rebuild into batches per table.",
+        ),
+        (
+            "What about the replica?",
+            "The replica replays the migration after the primary commits.",
+        ),
+    ];
+
+    let mut created = None;
+    for (user, assistant) in exchanges {
+        let json = parse_tool_result(
+            &server
+                .am_buffer(&serde_json::json!({"user": user, "assistant": assistant}))
+                .unwrap(),
+        );
+        if let Some(name) = json["episode_created"].as_str() {
+            created = Some(name.to_string());
+        }
+    }
+
+    let name = created.expect("third exchange should flush the buffer");
+    assert!(name.starts_with("conversation: "), "got {name}");
+    assert!(
+        name.contains("postgres") || name.contains("migration"),
+        "name should carry a database-related token: {name}"
+    );
+}
+
+#[test]
+fn test_am_buffer_tags_plans_when_enabled() {
+    let buffer_plan = |server: &AmServer<BrainStore>| {
+        for (user, assistant) in [
+            (
+                "How do we add billing?",
+                "Billing needs a new column on accounts.",
+            ),
+            (
+                "What order?",
+                "1. add the column, 2. backfill, 3. flip the flag",
+            ),
+            (
+                "Sounds good",
+                "I will start with the column migration today.",
+            ),
+        ] {
+            server
+                .am_buffer(&serde_json::json!({"user": user, "assistant": assistant}))
+                .unwrap();
+        }
+        let state = server.lock_state().unwrap();
+        state.system.episodes[0]
+            .neighborhoods
+            .iter()
+            .filter(|n| n.neighborhood_type == NeighborhoodType::Plan)
+            .count()
+    };
+
+    // Off by default
+    assert_eq!(buffer_plan(&make_server()), 0);
+
+    let plans = am_store::config::PlanConfig {
+        detect: true,
+        verbs: None,
+    };
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .plans(&plans)
+        .build()
+        .unwrap();
+    assert_eq!(buffer_plan(&server), 1);
+}
+
+#[test]
+fn test_am_buffer_auto_salient_when_enabled() {
+    let flush_decision = |server: &AmServer<BrainStore>| -> Value {
+        let exchanges = [
+            (
+                "Which store should we use?",
+                "Let's weigh SQLite against Postgres.",
+            ),
+            (
+                "SQLite keeps deployment simple.",
+                "Agreed.\nDECISION: use SQLite for the memory store",
+            ),
+            ("Anything else?", "No, that settles it."),
+        ];
+        let mut last = Value::Null;
+        for (user, assistant) in exchanges {
+            last = parse_tool_result(
+                &server
+                    .am_buffer(&serde_json::json!({ "user": user, "assistant": assistant }))
+                    .unwrap(),
+            );
+        }
+        last
+    };
+    let decisions = |server: &AmServer<BrainStore>| {
+        server
+            .lock_state()
+            .unwrap()
+            .system
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .filter(|n| n.neighborhood_type == NeighborhoodType::Decision)
+            .map(|n| n.source_text.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Off by default: the episode is created, nothing becomes conscious
+    let server = make_server();
+    let result = flush_decision(&server);
+    assert!(result["episode_created"].is_string());
+    assert!(result.get("auto_salient").is_none());
+    assert!(decisions(&server).is_empty());
+
+    let buffer = am_store::config::BufferConfig {
+        auto_salient: true,
+        ..Default::default()
+    };
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .buffer(&buffer)
+        .build()
+        .unwrap();
+    let result = flush_decision(&server);
+    assert_eq!(decisions(&server), ["use SQLite for the memory store"]);
+    let ids = result["auto_salient"].as_array().unwrap();
+    assert_eq!(ids.len(), 1);
+    let stored = with_store(&server, |store| store.load_system().unwrap());
+    assert_eq!(
+        stored.conscious_episode.neighborhoods[0].id.to_string(),
+        ids[0].as_str().unwrap()
+    );
+}
+
+#[test]
+fn test_am_buffer_threshold_from_config() {
+    let buffer = am_store::config::BufferConfig {
+        threshold: 2,
+        ..Default::default()
+    };
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .buffer(&buffer)
+        .build()
+        .unwrap();
+    let exchange = |n: u32| {
+        parse_tool_result(
+            &server
+                .am_buffer(&serde_json::json!({
+                    "user": format!("User message {n}"),
+                    "assistant": format!("Assistant response {n}"),
+                }))
+                .unwrap(),
+        )
+    };
+
+    assert!(exchange(1)["episode_created"].is_null());
+    let second = exchange(2);
+    assert_eq!(second["buffer_size"], 2);
+    assert!(second["episode_created"].is_string());
+}
+
+#[test]
+fn test_am_export_import_roundtrip() {
+    let server = make_server();
+
+    // Ingest content
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Roundtrip test content. Multiple sentences for neighborhoods. And one more sentence.",
+                "name": "roundtrip"
+            }))
+            .unwrap();
+
+    // Get stats before export
+    let stats_before = parse_tool_result(&server.am_stats().unwrap());
+
+    // Export
+    let export_result = server.am_export(&serde_json::json!({})).unwrap();
+    let exported_json = export_result["content"][0]["text"]
+        .as_str()
+        .expect("export should return text");
+    assert!(!exported_json.is_empty());
+
+    // Create a fresh server and import
+    let server2 = make_server();
+    let state_value: serde_json::Value = serde_json::from_str(exported_json).unwrap();
+
+    let import_result = server2
+        .am_import(&serde_json::json!({ "state": state_value }))
+        .unwrap();
+
+    let import_json = parse_tool_result(&import_result);
+    assert_eq!(import_json["imported"], true);
+
+    // Verify stats match
+    let stats_after = parse_tool_result(&server2.am_stats().unwrap());
+    assert_eq!(stats_before["n"], stats_after["n"]);
+    assert_eq!(stats_before["episodes"], stats_after["episodes"]);
+}
+
+#[test]
+fn test_mcp_file_paths_are_confined_to_allowed_dirs() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+
+    // A path under /tmp is outside the default allowlist (the project root)
+    let server = make_server_with_content();
+    let err = server
+        .am_export(&serde_json::json!({"path": export}))
+        .unwrap_err();
+    assert!(err.message().starts_with("[path]"), "{err}");
+    assert!(!export.exists());
+    let err = server
+        .am_import(&serde_json::json!({"path": export}))
+        .unwrap_err();
+    assert!(err.message().starts_with("[path]"), "{err}");
+
+    // Allowed once its directory is on the list
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .allowed_paths([dir.path().to_path_buf()])
+        .build()
+        .unwrap();
+    server
+        .am_ingest(&serde_json::json!({"text": "Export paths stay inside the sandbox."}))
+        .unwrap();
+    let written = parse_tool_result(
+        &server
+            .am_export(&serde_json::json!({"path": export}))
+            .unwrap(),
+    );
+    assert!(written["bytes"].as_u64().unwrap() > 0);
+    let imported = parse_tool_result(
+        &server
+            .am_import(&serde_json::json!({"path": export}))
+            .unwrap(),
+    );
+    assert_eq!(imported["imported"], true);
+    let err = server
+        .am_import(&serde_json::json!({"path": export, "state": {}}))
+        .unwrap_err();
+    assert!(
+        err.message().contains("exactly one of state or path"),
+        "{err}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mcp_file_paths_catch_symlink_escapes() {
+    use std::os::unix::fs::symlink;
+
+    let allowed = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+    let secret = outside.path().join("secret.json");
+    std::fs::write(&secret, "{}").unwrap();
+    symlink(&secret, allowed.path().join("link.json")).unwrap();
+    symlink(outside.path(), allowed.path().join("dir")).unwrap();
+    symlink(
+        outside.path().join("new.json"),
+        allowed.path().join("dangling.json"),
+    )
+    .unwrap();
+
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .allowed_paths([allowed.path().to_path_buf()])
+        .build()
+        .unwrap();
+    let link = allowed.path().join("link.json");
+    for args in [
+        serde_json::json!({"path": link}),
+        serde_json::json!({"path": allowed.path().join("dir/new.json")}),
+        serde_json::json!({"path": allowed.path().join("dangling.json")}),
+    ] {
+        let err = server.am_export(&args).unwrap_err();
+        assert!(err.message().starts_with("[path]"), "{err}");
+    }
+    let err = server
+        .am_import(&serde_json::json!({"path": link}))
+        .unwrap_err();
+    assert!(
+        err.message().contains("outside the allowed directories"),
+        "{err}"
+    );
+    assert_eq!(err.kind(), ErrorKind::PathDenied);
+    assert_eq!(std::fs::read_to_string(&secret).unwrap(), "{}");
+    assert!(!outside.path().join("new.json").exists());
+}
+
+#[test]
+fn test_am_stats_after_operations() {
+    let server = make_server();
+
+    // Ingest
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "First document about testing. With multiple sentences here. And a final line.",
+            "name": "doc1"
+        }))
+        .unwrap();
+
+    let stats1 = parse_tool_result(&server.am_stats().unwrap());
+    let n1 = stats1["n"].as_u64().unwrap();
+    assert!(n1 > 0);
+    assert_eq!(stats1["episodes"], 1);
+
+    // Ingest second document
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Second document about verification. Has different content entirely. Nothing overlaps.",
+                "name": "doc2"
+            }))
+            .unwrap();
+
+    let stats2 = parse_tool_result(&server.am_stats().unwrap());
+    assert!(stats2["n"].as_u64().unwrap() > n1);
+    assert_eq!(stats2["episodes"], 2);
+
+    // Mark salient
+    server
+        .am_salient(&serde_json::json!({
+            "text": "key insight"
+        }))
+        .unwrap();
+
+    let stats3 = parse_tool_result(&server.am_stats().unwrap());
+    assert!(stats3["conscious"].as_u64().unwrap() >= 1);
+}
+
+#[test]
+fn test_am_query_flushes_orphaned_buffer() {
+    let server = make_server();
+
+    // Buffer 2 exchanges (below threshold - simulates a session that ended early)
+    for i in 0..2 {
+        server
+            .am_buffer(&serde_json::json!({
+                "user": format!("Orphaned user message {i}"),
+                "assistant": format!("Orphaned assistant response {i}")
+            }))
+            .unwrap();
+    }
+
+    // No episode yet
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(stats["episodes"], 0);
+
+    // Calling am_query (simulating next session start) should flush the orphaned buffer
+    let result = server
+        .am_query(&serde_json::json!({
+            "text": "orphaned message"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert!(json.get("stats").is_some());
+    // The orphaned buffer should have been flushed into an episode
+    assert_eq!(json["stats"]["episodes"], 1);
+}
+
+#[test]
+fn test_buffer_flush_dates_episode_by_first_exchange() {
+    use am_core::time::{now_unix_secs, unix_to_iso8601};
+
+    let server = make_server();
+    let yesterday = unix_to_iso8601(now_unix_secs() - 86_400);
+    let at = yesterday.clone();
+    with_store(&server, move |store| {
+        for i in 0..2 {
+            store
+                .store()
+                .append_buffer_at(
+                    &format!("Yesterday's question {i} about migrations"),
+                    &format!("Yesterday's answer {i} about migrations"),
+                    &at,
+                )
+                .unwrap();
+        }
+    });
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Today's notes on release tagging.",
+            "name": "today-notes"
+        }))
+        .unwrap();
+
+    server
+        .am_query(&serde_json::json!({"text": "migrations"}))
+        .unwrap();
+
+    let state = server.lock_state().unwrap();
+    let conversation = state
+        .system
+        .episodes
+        .iter()
+        .find(|e| e.name.starts_with("conversation"))
+        .unwrap();
+    assert_eq!(conversation.timestamp, yesterday);
+    // Flushed after today's ingest, but listed after it
+    let listed: Vec<String> = with_store(&server, |store| store.store().list_episodes().unwrap())
+        .into_iter()
+        .filter(|e| !e.is_conscious)
+        .map(|e| e.name)
+        .collect();
+    assert_eq!(listed[0], "today-notes");
+    assert!(listed[1].starts_with("conversation"), "{listed:?}");
+}
+
+#[test]
+fn test_am_query_reports_stage_timings() {
+    let server = make_server();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Borrow checking runs after type inference.",
+            "name": "notes"
+        }))
+        .unwrap();
+
+    for args in [
+        serde_json::json!({"text": "borrow checking"}),
+        serde_json::json!({"text": "borrow checking", "max_tokens": 500}),
+    ] {
+        let json = parse_tool_result(&server.am_query(&args).unwrap());
+        let timings = json["timings"].as_object().unwrap();
+        for stage in ["activation_ms", "drift_ms", "compose_ms", "save_ms"] {
+            let ms = timings[stage].as_f64().unwrap();
+            assert!(ms >= 0.0 && ms.is_finite(), "{stage}: {ms}");
+        }
+    }
+}
+
+#[test]
+fn test_full_archive_carries_buffer_to_a_fresh_store() {
+    use am_core::serde_compat::ImportOptions;
+    use am_core::time::{now_unix_secs, unix_to_iso8601};
+    use am_store::json_bridge::ImportMode;
+
+    let source = make_server();
+    let yesterday = unix_to_iso8601(now_unix_secs() - 86_400);
+    source
+        .am_ingest(&serde_json::json!({
+            "text": "Notes on schema migrations and release tagging.",
+            "name": "notes"
+        }))
+        .unwrap();
+    let at = yesterday.clone();
+    let archive = with_store(&source, move |brain| {
+        let system = brain.load_system().unwrap();
+        let store = brain.store();
+        store
+            .append_buffer_at(
+                "How do we roll back a migration?",
+                "Restore the pre-migration backup.",
+                &at,
+            )
+            .unwrap();
+        store.set_metadata("min_candidate_score", "0.2").unwrap();
+        store.set_word_bias("migration", 3.0).unwrap();
+        store.export_archive_string(&system).unwrap()
+    });
+
+    let target = BrainStore::open_in_memory().unwrap();
+    let (summary, restored) = target
+        .store()
+        .import_archive_str(
+            &archive,
+            ImportMode::Replace,
+            ImportOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+    assert_eq!(summary.episodes, 1);
+    assert_eq!(restored.buffer, 1);
+    let server = AmServer::new(target).unwrap();
+
+    // The orphaned exchange flushes on the new machine as it would have on
+    // the old one: dated by when it was buffered, not by the import
+    server
+        .am_query(&serde_json::json!({"text": "migrations"}))
+        .unwrap();
+    let state = server.lock_state().unwrap();
+    let conversation = state
+        .system
+        .episodes
+        .iter()
+        .find(|e| e.name.starts_with("conversation"))
+        .unwrap();
+    assert_eq!(conversation.timestamp, yesterday);
+    let (buffered, min_score, biases) = with_store(&server, |brain| {
+        let store = brain.store();
+        (
+            store.buffer_count().unwrap(),
+            store.get_metadata("min_candidate_score").unwrap(),
+            store.word_biases().unwrap(),
+        )
+    });
+    assert_eq!(buffered, 0);
+    assert_eq!(min_score.as_deref(), Some("0.2"));
+    assert_eq!(biases, vec![("migration".to_string(), 3.0)]);
+}
+
+#[test]
+fn test_am_decisions_lists_decisions_newest_first() {
+    let server = make_server();
+    for text in [
+        "DECISION: store memories in SQLite",
+        "SQLite WAL mode halves write latency",
+        "DECISION: ship the CLI before the HTTP API",
+    ] {
+        server
+            .am_salient(&serde_json::json!({ "text": text }))
+            .unwrap();
+    }
+
+    let json = parse_tool_result(&server.am_decisions(&serde_json::json!({})).unwrap());
+    assert_eq!(json["count"], 2, "insights are not decisions: {json:#}");
+    let decisions = json["decisions"].as_array().unwrap();
+    assert_eq!(decisions[0]["text"], "ship the CLI before the HTTP API");
+    assert_eq!(decisions[1]["text"], "store memories in SQLite");
+    assert!(decisions[0]["created_at"].is_string());
+    assert_eq!(decisions[1]["superseded"], false);
+
+    let old_id = decisions[1]["id"].as_str().unwrap().to_string();
+    server
+        .am_salient(&serde_json::json!({
+            "text": "DECISION: store memories in SQLite with WAL enabled",
+            "supersedes": [old_id],
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(
+        &server
+            .am_decisions(&serde_json::json!({ "keyword": "sqlite" }))
+            .unwrap(),
+    );
+    let decisions = json["decisions"].as_array().unwrap();
+    assert_eq!(decisions.len(), 2);
+    assert_eq!(decisions[0]["superseded"], false);
+    assert_eq!(decisions[1]["id"], old_id);
+    assert_eq!(decisions[1]["superseded"], true);
+    assert_eq!(decisions[1]["superseded_by"], decisions[0]["id"]);
+}
+
+#[test]
+fn test_am_salient_supersedes_old_memory() {
+    let server = make_server();
+
+    // Create an initial conscious memory
+    let result1 = server
+        .am_salient(&serde_json::json!({
+            "text": "deployment uses monolith architecture pattern"
+        }))
+        .unwrap();
+    let json1 = parse_tool_result(&result1);
+    assert_eq!(json1["stored"], 1);
+
+    // Query to get the recalled_ids of the old memory
+    let query_result = server
+        .am_query(&serde_json::json!({
+            "text": "deployment architecture pattern"
+        }))
+        .unwrap();
+    let query_json = parse_tool_result(&query_result);
+    let old_ids: Vec<String> = query_json["recalled_ids"]["conscious"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect();
+    assert!(
+        !old_ids.is_empty(),
+        "should have conscious recall IDs from the first memory"
+    );
+
+    // Create a new memory that supersedes the old one
+    let result2 = server
+        .am_salient(&serde_json::json!({
+            "text": "deployment uses microservices architecture pattern",
+            "supersedes": old_ids.clone()
+        }))
+        .unwrap();
+    let json2 = parse_tool_result(&result2);
+    assert_eq!(json2["stored"], 1);
+    assert_eq!(
+        json2["superseded"],
+        serde_json::json!(old_ids.len()),
+        "should report superseded count"
+    );
+
+    // Query again - the old memory should not appear
+    let query_result2 = server
+        .am_query(&serde_json::json!({
+            "text": "deployment architecture pattern"
+        }))
+        .unwrap();
+    let query_json2 = parse_tool_result(&query_result2);
+    let context = query_json2["context"].as_str().unwrap_or("");
+
+    assert!(
+        !context.contains("monolith"),
+        "superseded memory should not appear in recall, got:\n{}",
+        context,
+    );
+    assert!(
+        context.contains("microservices"),
+        "replacement memory should appear in recall, got:\n{}",
+        context,
+    );
+}
+
+#[test]
+fn test_am_buffer_dedup_identical_content() {
+    let server = make_server();
+
+    // First buffer call - should succeed
+    let result1 = server
+        .am_buffer(&serde_json::json!({
+            "user": "What is Rust?",
+            "assistant": "Rust is a systems programming language."
+        }))
+        .unwrap();
+    let json1 = parse_tool_result(&result1);
+    assert_eq!(json1["buffer_size"], 1);
+    assert!(json1.get("deduplicated").is_none());
+
+    // Second buffer call with identical content - should be deduplicated
+    let result2 = server
+        .am_buffer(&serde_json::json!({
+            "user": "What is Rust?",
+            "assistant": "Rust is a systems programming language."
+        }))
+        .unwrap();
+    let json2 = parse_tool_result(&result2);
+    assert_eq!(json2["deduplicated"], true);
+    assert_eq!(json2["buffer_size"], 1); // still 1, not 2
+
+    // Third buffer call with different content - should succeed
+    let result3 = server
+        .am_buffer(&serde_json::json!({
+            "user": "What is Go?",
+            "assistant": "Go is a compiled programming language by Google."
+        }))
+        .unwrap();
+    let json3 = parse_tool_result(&result3);
+    assert_eq!(json3["buffer_size"], 2);
+    assert!(json3.get("deduplicated").is_none());
+}
+
+#[test]
+fn test_am_buffer_dedup_different_content_creates_episodes() {
+    let server = make_server();
+
+    // Buffer 3 different exchanges - should create 1 episode
+    for i in 0..3 {
+        server
+            .am_buffer(&serde_json::json!({
+                "user": format!("Unique question {i}"),
+                "assistant": format!("Unique answer {i}")
+            }))
+            .unwrap();
+    }
+
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(
+        stats["episodes"], 1,
+        "3 unique exchanges should create 1 episode"
+    );
+
+    // Now try to buffer the same first exchange again - should be deduplicated
+    let result = server
+        .am_buffer(&serde_json::json!({
+            "user": "Unique question 0",
+            "assistant": "Unique answer 0"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert_eq!(json["deduplicated"], true);
+}
+
+#[test]
+fn test_am_query_index_returns_compact_entries() {
+    let server = make_server();
+
+    // Ingest content
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Quantum mechanics describes particle behavior at subatomic scales. Wave functions collapse on measurement.",
+                "name": "science"
+            }))
+            .unwrap();
+
+    server
+        .am_salient(&serde_json::json!({
+            "text": "quantum computing is revolutionary technology"
+        }))
+        .unwrap();
+
+    // Query the index
+    let result = server
+        .am_query_index(&serde_json::json!({
+            "text": "quantum particles"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+
+    // Verify response structure
+    assert!(json.get("entries").is_some(), "should have entries");
+    assert!(
+        json.get("total_candidates").is_some(),
+        "should have total_candidates"
+    );
+    assert!(
+        json.get("total_tokens_if_fetched").is_some(),
+        "should have total_tokens_if_fetched"
+    );
+    assert!(json.get("stats").is_some(), "should have stats");
+
+    let entries = json["entries"].as_array().unwrap();
+    assert!(!entries.is_empty(), "should have matching entries");
+
+    // Verify each entry has compact structure
+    for entry in entries {
+        assert!(entry.get("id").is_some(), "entry should have id");
+        assert!(
+            entry.get("category").is_some(),
+            "entry should have category"
+        );
+        assert!(entry.get("type").is_some(), "entry should have type");
+        assert!(entry.get("score").is_some(), "entry should have score");
+        assert!(entry.get("epoch").is_some(), "entry should have epoch");
+        assert!(entry.get("summary").is_some(), "entry should have summary");
+        assert!(
+            entry.get("token_estimate").is_some(),
+            "entry should have token_estimate"
+        );
+
+        // Summary should be compact (<=103 chars: 100 + "...")
+        let summary = entry["summary"].as_str().unwrap();
+        assert!(
+            summary.len() <= 103,
+            "summary should be truncated, got {} chars",
+            summary.len()
+        );
+    }
+}
+
+#[test]
+fn test_am_retrieve_returns_full_content() {
+    let server = make_server();
+
+    // Ingest content
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Rust borrow checker enforces ownership rules at compile time. Lifetimes prevent dangling references.",
+                "name": "rust-guide"
+            }))
+            .unwrap();
+
+    // Get index to find IDs
+    let index_result = server
+        .am_query_index(&serde_json::json!({
+            "text": "rust borrow checker"
+        }))
+        .unwrap();
+
+    let index_json = parse_tool_result(&index_result);
+    let entries = index_json["entries"].as_array().unwrap();
+    assert!(!entries.is_empty(), "should have index entries");
+
+    // Pick the first ID
+    let first_id = entries[0]["id"].as_str().unwrap().to_string();
+
+    // Retrieve full content
+    let retrieve_result = server
+        .am_retrieve(&serde_json::json!({
+            "ids": [first_id.clone()]
+        }))
+        .unwrap();
+
+    let retrieve_json = parse_tool_result(&retrieve_result);
+    assert_eq!(retrieve_json["count"], 1);
+
+    let retrieved = &retrieve_json["entries"].as_array().unwrap()[0];
+    assert_eq!(retrieved["id"], first_id);
+    assert!(retrieved.get("text").is_some(), "should have full text");
+    assert!(
+        !retrieved["text"].as_str().unwrap().is_empty(),
+        "text should be non-empty"
+    );
+    assert!(
+        retrieved.get("episode").is_some(),
+        "should have episode name"
+    );
+}
+
+#[test]
+fn test_am_knows_reports_candidates_for_present_term() {
+    let server = make_server_with_content();
+
+    let json = parse_tool_result(
+        &server
+            .am_knows(&serde_json::json!({ "text": "borrow checker" }))
+            .unwrap(),
+    );
+
+    assert_eq!(json["known"], true);
+    assert_eq!(json["candidates"]["activated_words"], 2);
+    assert!(json["candidates"]["subconscious"].as_u64().unwrap() > 0);
+    assert!(json["top_score"].as_f64().unwrap() > 0.0, "{json}");
+    let state = server.lock_state().unwrap();
+    assert!(state.session_recalled.is_empty());
+}
+
+#[test]
+fn test_am_knows_reports_zeros_for_absent_term() {
+    let server = make_server_with_content();
+
+    let json = parse_tool_result(
+        &server
+            .am_knows(&serde_json::json!({ "text": "zeppelin" }))
+            .unwrap(),
+    );
+
+    assert_eq!(json["known"], false);
+    assert_eq!(json["candidates"]["activated_words"], 0);
+    assert_eq!(json["candidates"]["conscious"], 0);
+    assert_eq!(json["candidates"]["subconscious"], 0);
+    assert_eq!(json["candidates"]["novel"], 0);
+    assert!(json["top_score"].is_null());
+}
+
+#[test]
+fn test_am_query_without_learning_leaves_memory_unchanged() {
+    let server = make_server_with_content();
+    let counts = |server: &AmServer<BrainStore>| -> Vec<(u32, f64)> {
+        let state = server.lock_state().unwrap();
+        state.system.episodes[0].neighborhoods[0]
+            .occurrences
+            .iter()
+            .map(|o| (o.activation_count, o.phasor.theta))
+            .collect()
+    };
+    let before = counts(&server);
+
+    let args = serde_json::json!({ "text": "borrow checker", "learn": false });
+    let first = parse_tool_result(&server.am_query(&args).unwrap());
+    assert!(
+        first["context"]
+            .as_str()
+            .unwrap()
+            .contains("borrow checker"),
+        "{first}"
+    );
+    assert_eq!(counts(&server), before);
+    assert!(server.lock_state().unwrap().session_recalled.is_empty());
+
+    // Nothing was counted as recalled, so a repeat is not discounted
+    let second = parse_tool_result(&server.am_query(&args).unwrap());
+    assert_eq!(second["context"], first["context"]);
+
+    // A learning query still activates
+    server
+        .am_query(&serde_json::json!({ "text": "borrow checker" }))
+        .unwrap();
+    assert_ne!(counts(&server), before);
+}
+
+#[test]
+fn test_am_query_speculative_rolls_back_to_identical_export() {
+    let server = make_server_with_content();
+    // Warm up so the speculative query drifts as well as activates
+    for _ in 0..3 {
+        server
+            .am_query(&serde_json::json!({ "text": "borrow checker ownership" }))
+            .unwrap();
+    }
+    let export = |server: &AmServer<BrainStore>| {
+        am_core::serde_compat::export_json(&server.lock_state().unwrap().system).unwrap()
+    };
+    let before = export(&server);
+    let recalled = server.lock_state().unwrap().session_recalled.clone();
+
+    let args = serde_json::json!({ "text": "borrow checker", "speculative": true });
+    let json = parse_tool_result(&server.am_query(&args).unwrap());
+    assert_eq!(json["speculative"], true);
+    assert!(
+        json["context"].as_str().unwrap().contains("borrow checker"),
+        "{json}"
+    );
+    assert_eq!(export(&server), before);
+    let state = server.lock_state().unwrap();
+    assert!(!state.system.is_speculating());
+    assert_eq!(state.session_recalled, recalled);
+}
+
+#[test]
+fn test_am_conscious_lists_every_conscious_memory() {
+    let server = make_server();
+    server
+        .am_salient(&serde_json::json!({ "text": "DECISION: ship on Fridays" }))
+        .unwrap();
+    server
+        .am_salient(&serde_json::json!({ "text": "Tabs render at four spaces" }))
+        .unwrap();
+
+    let json = parse_tool_result(&server.dispatch_tool("am_conscious", &Value::Null).unwrap());
+    let memories = json.as_array().unwrap();
+    assert_eq!(memories.len(), 2);
+    assert_eq!(memories[0]["type"], "decision");
+    assert_eq!(memories[1]["text"], "Tabs render at four spaces");
+    assert!(memories[1]["superseded_by"].is_null());
+}
+
+#[test]
+fn test_am_retrieve_handles_invalid_ids() {
+    let server = make_server();
+
+    let result = server
+        .am_retrieve(&serde_json::json!({
+            "ids": ["not-a-uuid"]
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    assert_eq!(json["count"], 0, "invalid UUIDs should return empty");
+}
+
+#[test]
+fn test_am_query_includes_index() {
+    let server = make_server();
+
+    // Ingest content
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Geometric memory uses hypersphere manifolds for associative recall. Neighborhoods cluster related concepts.",
+                "name": "geo-memory"
+            }))
+            .unwrap();
+
+    // Add conscious content
+    server
+        .am_salient(&serde_json::json!({
+            "text": "hypersphere manifolds enable geometric reasoning"
+        }))
+        .unwrap();
+
+    // Query (default path, no budget)
+    let result = server
+        .am_query(&serde_json::json!({
+            "text": "geometric manifold memory"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+
+    // Verify index field exists
+    assert!(json.get("index").is_some(), "should have index field");
+    let index = json["index"].as_array().unwrap();
+
+    // At most 10 entries
+    assert!(index.len() <= 10, "index should have at most 10 entries");
+
+    // Should have at least one entry (we ingested content + salient)
+    assert!(!index.is_empty(), "index should have entries");
+
+    // Verify each entry has the expected compact structure
+    for entry in index {
+        assert!(entry.get("id").is_some(), "entry should have id");
+        assert!(
+            entry.get("category").is_some(),
+            "entry should have category"
+        );
+        assert!(entry.get("type").is_some(), "entry should have type");
+        assert!(entry.get("score").is_some(), "entry should have score");
+        assert!(entry.get("epoch").is_some(), "entry should have epoch");
+        assert!(entry.get("summary").is_some(), "entry should have summary");
+        assert!(
+            entry.get("token_estimate").is_some(),
+            "entry should have token_estimate"
+        );
+    }
+
+    // Verify budgeted path also includes index
+    let budgeted_result = server
+        .am_query(&serde_json::json!({
+            "text": "geometric manifold memory",
+            "max_tokens": 500
+        }))
+        .unwrap();
+
+    let budgeted_json = parse_tool_result(&budgeted_result);
+    assert!(
+        budgeted_json.get("index").is_some(),
+        "budgeted query should also have index field"
+    );
+    let budgeted_index = budgeted_json["index"].as_array().unwrap();
+    assert!(budgeted_index.len() <= 10);
+}
+
+#[test]
+fn test_dispatch_unknown_tool() {
+    let server = make_server();
+    let result = server.dispatch_tool("nonexistent", &serde_json::json!({}));
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.message().contains("unknown tool"));
+    assert_eq!(err.kind(), ErrorKind::UnknownTool);
+}
+
+#[test]
+fn test_am_ingest_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_ingest(&serde_json::json!({ "text": oversized }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+#[test]
+fn test_am_buffer_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_buffer(&serde_json::json!({
+        "user": oversized,
+        "assistant": ""
+    }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+#[test]
+fn test_am_salient_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_salient(&serde_json::json!({ "text": oversized }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+/// Helper: ingest content and return neighborhood IDs from a query.
+fn ingest_and_get_neighborhood_ids(server: &AmServer<BrainStore>) -> Vec<String> {
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Quantum mechanics describes particle behavior at subatomic scales. Wave functions collapse upon measurement. Entanglement connects distant particles.",
+                "name": "quantum"
+            }))
+            .unwrap();
+
+    let result = server
+        .am_query(&serde_json::json!({
+            "text": "quantum particles entanglement"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    let recalled = &json["recalled_ids"];
+    let mut ids = Vec::new();
+    for cat in &["conscious", "subconscious", "novel"] {
+        if let Some(arr) = recalled[cat].as_array() {
+            for id in arr {
+                if let Some(s) = id.as_str() {
+                    ids.push(s.to_string());
+                }
+            }
+        }
+    }
+    ids
+}
+
+#[test]
+fn test_am_feedback_boost() {
+    let server = make_server();
+    let ids = ingest_and_get_neighborhood_ids(&server);
+    assert!(
+        !ids.is_empty(),
+        "query should recall at least one neighborhood"
+    );
+
+    let result = server
+        .am_feedback(&serde_json::json!({
+            "query": "quantum particles",
+            "neighborhood_ids": ids,
+            "signal": "boost"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert!(
+        json["boosted"].as_u64().unwrap() > 0,
+        "boost should affect at least one neighborhood"
+    );
+}
+
+#[test]
+fn test_am_feedback_demote() {
+    let server = make_server();
+    let ids = ingest_and_get_neighborhood_ids(&server);
+    assert!(
+        !ids.is_empty(),
+        "query should recall at least one neighborhood"
+    );
+
+    let result = server
+        .am_feedback(&serde_json::json!({
+            "query": "quantum particles",
+            "neighborhood_ids": ids,
+            "signal": "demote"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert!(
+        json["demoted"].as_u64().unwrap() > 0,
+        "demote should affect at least one neighborhood"
+    );
+}
+
+#[test]
+fn test_am_feedback_score_is_saved_and_explained() {
+    let server = make_server();
+    let ids = ingest_and_get_neighborhood_ids(&server);
+    server
+        .am_feedback(&serde_json::json!({
+            "query": "quantum particles",
+            "neighborhood_ids": [ids[0]],
+            "signal": "demote"
+        }))
+        .unwrap();
+
+    {
+        let stored = with_store(&server, |store| store.load_system().unwrap());
+        let id = Uuid::parse_str(&ids[0]).unwrap();
+        let score = stored
+            .episodes
+            .iter()
+            .chain(std::iter::once(&stored.conscious_episode))
+            .flat_map(|ep| &ep.neighborhoods)
+            .find(|n| n.id == id)
+            .map(|n| n.feedback_score);
+        assert_eq!(score, Some(-1.0));
+    }
+
+    let result = server
+        .am_query(&serde_json::json!({
+            "text": "quantum particles entanglement",
+            "debug": true
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert_eq!(json["debug"]["feedback"][&ids[0]], -1.0);
+    assert_eq!(json["debug"]["feedback"].as_object().unwrap().len(), 1);
+}
+
+#[test]
+fn test_am_feedback_unknown_signal() {
+    let server = make_server();
+    let result = server.am_feedback(&serde_json::json!({
+        "query": "test",
+        "neighborhood_ids": ["00000000-0000-0000-0000-000000000001"],
+        "signal": "invalid_signal"
+    }));
+    assert!(result.is_err(), "unknown signal should return error");
+}
+
+#[test]
+fn test_am_feedback_empty_ids() {
+    let server = make_server();
+    let result = server.am_feedback(&serde_json::json!({
+        "query": "test",
+        "neighborhood_ids": [],
+        "signal": "boost"
+    }));
+    assert!(
+        result.is_err(),
+        "empty neighborhood_ids should return error"
+    );
+}
+
+#[test]
+fn test_am_feedback_bad_uuid_is_invalid_params() {
+    let server = make_server();
+    let err = server
+        .dispatch_tool(
+            "am_feedback",
+            &serde_json::json!({
+                "query": "test",
+                "neighborhood_ids": ["not-a-uuid"],
+                "signal": "boost"
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), crate::error::INVALID_PARAMS);
+    assert_eq!(err.data()["kind"], "invalid_params");
+    assert_eq!(err.data()["retriable"], false);
+}
+
+#[test]
+fn test_unknown_episode_is_not_found() {
+    let server = make_server();
+    let err = server
+        .dispatch_tool(
+            "am_episode_neighborhoods",
+            &serde_json::json!({"episode_id": Uuid::new_v4().to_string()}),
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), crate::error::RESOURCE_NOT_FOUND);
+    assert_eq!(err.data()["kind"], "not_found");
+}
+
+#[test]
+fn test_malformed_import_is_invalid_state() {
+    let server = make_server();
+    let err = server
+        .dispatch_tool("am_import", &serde_json::json!({"state": {"episodes": 7}}))
+        .unwrap_err();
+    assert!(err.message().starts_with("[serde]"), "{err}");
+    assert_eq!(err.code(), crate::error::INVALID_PARAMS);
+    assert_eq!(err.data()["kind"], "invalid_state");
+    assert_eq!(err.data()["retriable"], false);
+}
+
+#[test]
+fn test_import_validation_report() {
+    let server = make_server_with_content();
+    let export_result = server.am_export(&serde_json::json!({})).unwrap();
+    let export_text = export_result["content"][0]["text"].as_str().unwrap();
+    let mut state: serde_json::Value = serde_json::from_str(export_text).unwrap();
+    state["system"]["episodes"][0]["neighborhoods"][0]["seed"] = serde_json::json!([0, 0, 0, 0]);
+
+    let server2 = make_server();
+    let err = server2
+        .dispatch_tool("am_import", &serde_json::json!({ "state": state }))
+        .unwrap_err();
+    assert!(err.message().contains("neighborhoods[0]"), "{err}");
+    assert_eq!(err.data()["kind"], "invalid_state");
+
+    let result = server2
+        .am_import(&serde_json::json!({ "state": state, "lenient": true }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    assert_eq!(json["imported"], true);
+    assert_eq!(json["report"]["dropped_neighborhoods"], 1);
+    let errors = json["report"]["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0]["location"]
+            .as_str()
+            .unwrap()
+            .starts_with("system.episodes[0]")
+    );
+}
+
+#[test]
+fn test_locked_database_is_retriable() {
+    let dir = tempfile::tempdir().unwrap();
+    // Fail on the first busy error instead of backing off
+    let config = am_store::config::Config {
+        data_dir: dir.path().to_path_buf(),
+        retry: am_store::store::RetryPolicy::none(),
+        ..am_store::config::Config::default()
+    };
+    let store = BrainStore::open(&config).unwrap();
+    store
+        .store()
+        .set_busy_timeout(Duration::from_millis(10))
+        .unwrap();
+    let server = AmServer::new(store).unwrap();
+
+    // Another process mid-write holds the lock past the busy timeout
+    let other = rusqlite::Connection::open(dir.path().join("brain.db")).unwrap();
+    other.execute_batch("BEGIN EXCLUSIVE").unwrap();
+
+    let err = server
+        .dispatch_tool(
+            "am_ingest",
+            &serde_json::json!({"text": "Blocked by a writer."}),
+        )
+        .unwrap_err();
+    assert!(err.message().starts_with("[store]"), "{err}");
+    assert_eq!(err.code(), crate::error::INTERNAL_ERROR);
+    assert_eq!(err.data()["kind"], "busy");
+    assert_eq!(err.data()["retriable"], true);
+
+    other.execute_batch("ROLLBACK").unwrap();
+    server
+        .am_salient(&serde_json::json!({"text": "The lock is gone."}))
+        .unwrap();
+}
+
+#[test]
+fn test_am_batch_query_basic() {
+    let server = make_server();
+
+    // Ingest content
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Rust is a systems programming language focused on safety and performance. Memory safety without garbage collection.",
+                "name": "rust-lang"
+            }))
+            .unwrap();
+
+    let result = server
+        .am_batch_query(&serde_json::json!({
+            "queries": [
+                { "query": "rust safety" },
+                { "query": "memory management" },
+                { "query": "performance optimization" }
+            ]
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    let results = json["results"].as_array().expect("results should be array");
+    assert_eq!(results.len(), 3, "should have 3 results for 3 queries");
+
+    for (i, r) in results.iter().enumerate() {
+        assert!(r.get("query").is_some(), "result {i} should have query");
+        assert!(r.get("context").is_some(), "result {i} should have context");
+        assert!(r.get("metrics").is_some(), "result {i} should have metrics");
+        assert!(
+            r.get("recalled_ids").is_some(),
+            "result {i} should have recalled_ids"
+        );
+        assert!(
+            r.get("token_estimate").is_some(),
+            "result {i} should have token_estimate"
+        );
+    }
+}
+
+#[test]
+fn test_am_batch_query_empty_requests() {
+    let server = make_server();
+
+    let result = server
+        .am_batch_query(&serde_json::json!({ "queries": [] }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    let results = json["results"].as_array().expect("results should be array");
+    assert!(
+        results.is_empty(),
+        "empty queries should produce empty results"
+    );
+}
+
+#[test]
+fn test_am_batch_query_per_budget() {
+    let server = make_server();
+
+    // Ingest enough content to test budget limits
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Quantum mechanics describes the behavior of particles at the smallest scales. Superposition allows particles to exist in multiple states simultaneously. Entanglement connects particles across vast distances instantaneously.",
+                "name": "quantum"
+            }))
+            .unwrap();
+
+    let result = server
+        .am_batch_query(&serde_json::json!({
+            "queries": [
+                { "query": "quantum entanglement", "max_tokens": 50 },
+                { "query": "quantum superposition", "max_tokens": 5000 }
+            ]
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(&result);
+    let results = json["results"].as_array().expect("results should be array");
+    assert_eq!(results.len(), 2);
+
+    // Both should have budget fields reflecting their token limits
+    let budget_small = &results[0]["budget"];
+    let budget_large = &results[1]["budget"];
+    assert_eq!(budget_small["tokens_budget"], 50);
+    assert_eq!(budget_large["tokens_budget"], 5000);
+}
+
+#[test]
+fn test_am_query_reports_candidates_and_suggestion() {
+    let server = make_server();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Webhook retries use exponential backoff. Failed deliveries go to a dead letter queue. Operators can replay them.",
+            "name": "webhooks"
+        }))
+        .unwrap();
+
+    let miss = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "zeppelin marmalade"}))
+            .unwrap(),
+    );
+    assert_eq!(miss["candidates"]["activated_words"], 0);
+    assert_eq!(miss["candidates"]["subconscious"], 0);
+    assert_eq!(
+        miss["suggestion"],
+        "no activation - try more specific terms"
+    );
+
+    let hit = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "webhook retries backoff",
+                "max_tokens": 500
+            }))
+            .unwrap(),
+    );
+    assert_eq!(hit["candidates"]["activated_words"], 3);
+    assert!(hit["candidates"]["subconscious"].as_u64().unwrap() >= 1);
+    assert!(hit["candidates"]["novel"].is_u64());
+    assert!(hit["suggestion"].is_null());
+}
+
+#[test]
+fn test_am_query_debug_reports_word_weights() {
+    let server = make_server();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Cache eviction uses a TTL. The cache warms on startup. Eviction is lazy.",
+            "name": "cache-doc"
+        }))
+        .unwrap();
+
+    let plain = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "cache eviction"}))
+            .unwrap(),
+    );
+    assert!(plain.get("debug").is_none(), "debug is opt-in");
+
+    server
+        .lock_state()
+        .unwrap()
+        .system
+        .set_word_bias("eviction", 2.5);
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "cache eviction unheard", "debug": true}))
+            .unwrap(),
+    );
+    let weights = json["debug"]["weights"].as_object().unwrap();
+    assert_eq!(weights.len(), 3);
+    assert_eq!(
+        json["debug"]["biases"],
+        serde_json::json!({ "eviction": 2.5 })
+    );
+
+    let mut state = server.lock_state().unwrap();
+    for (word, weight) in weights {
+        let expected = state.system.get_word_weight(word);
+        assert!(
+            (weight.as_f64().unwrap() - expected).abs() < 1e-12,
+            "{word}"
+        );
+    }
+    assert!(weights["cache"].as_f64().unwrap() <= weights["unheard"].as_f64().unwrap());
+}
+
+#[test]
+fn test_am_query_include_archived_searches_archived_episodes_for_one_query() {
+    let server = make_server();
+    for (name, text) in [
+        (
+            "deploy-notes",
+            "Kubernetes rollout stalled on the canary. Rollback restored the canary pods.",
+        ),
+        (
+            "cooking",
+            "Sourdough needs a long cold proof. Starter feeding happens twice daily.",
+        ),
+        (
+            "garden",
+            "Tomatoes want full sun. Mulch keeps the beds moist through summer.",
+        ),
+    ] {
+        server
+            .am_ingest(&serde_json::json!({"text": text, "name": name}))
+            .unwrap();
+    }
+
+    // Archive everything, then reload as a fresh session would
+    {
+        let mut state = server.lock_state().unwrap();
+        let no_retention = am_store::config::RetentionPolicy {
+            grace_epochs: 0,
+            retention_days: 0,
+            min_neighborhoods: 0,
+            recency_weight: 0.0,
+        };
+        let (result, system) = with_store(&server, move |store| {
+            let result = store
+                .store()
+                .gc_archive_pass(u32::MAX, &no_retention)
+                .unwrap();
+            (result, store.load_system().unwrap())
+        });
+        assert_eq!(result.archived_episodes, 3);
+        state.system = system;
+        assert!(state.system.episodes.is_empty());
+    }
+
+    let plain = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "kubernetes canary rollout"}))
+            .unwrap(),
+    );
+    assert!(!plain["context"].as_str().unwrap().contains("canary"));
+    assert!(plain.get("archived_episodes").is_none());
+
+    let deep = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "kubernetes canary rollout",
+                "include_archived": true
+            }))
+            .unwrap(),
+    );
+    assert!(deep["context"].as_str().unwrap().contains("canary"));
+    assert_eq!(deep["archived_episodes"], 3);
+
+    // Archived episodes are dropped again once the query returns
+    let state = server.lock_state().unwrap();
+    assert!(state.system.episodes.is_empty());
+}
+
+#[test]
+fn test_am_query_searches_epoch_files_on_request_or_when_nothing_matches() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = am_store::config::Config {
+        data_dir: dir.path().to_path_buf(),
+        ..am_store::config::Config::default()
+    };
+    let server = AmServer::new(BrainStore::open(&config).unwrap()).unwrap();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Kubernetes rollout stalled on the canary. Rollback restored the canary pods.",
+            "name": "deploy-notes",
+        }))
+        .unwrap();
+    server
+        .am_salient(&serde_json::json!({"text": "PREFERENCE: tabs over spaces"}))
+        .unwrap();
+
+    // Move every subconscious episode out, then reload as a fresh session would
+    {
+        let mut state = server.lock_state().unwrap();
+        let no_retention = am_store::config::RetentionPolicy {
+            grace_epochs: 0,
+            retention_days: 0,
+            min_neighborhoods: 0,
+            recency_weight: 0.0,
+        };
+        let (report, system) = with_store(&server, move |store| {
+            let report = store
+                .store()
+                .epoch_pass(u32::MAX, &no_retention, true)
+                .unwrap();
+            (report, store.load_system().unwrap())
+        });
+        assert_eq!(report.episodes(), 1);
+        state.system = system;
+        assert!(state.system.episodes.is_empty());
+        assert_eq!(state.system.conscious_episode.neighborhoods.len(), 1);
+    }
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Tomatoes want full sun. A canary sings in the greenhouse.",
+            "name": "garden",
+        }))
+        .unwrap();
+
+    // Active recall has matches, so the epoch files stay closed
+    let plain = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "canary tomatoes"}))
+            .unwrap(),
+    );
+    assert!(plain["context"].as_str().unwrap().contains("Tomatoes"));
+    assert!(!plain["context"].as_str().unwrap().contains("Rollback"));
+    assert!(plain.get("epoch_episodes").is_none());
+
+    let federated = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "canary rollback",
+                "include_epochs": true
+            }))
+            .unwrap(),
+    );
+    assert!(federated["context"].as_str().unwrap().contains("Rollback"));
+    assert_eq!(federated["epoch_episodes"], 1);
+    assert!(federated.get("epoch_fallback").is_none());
+
+    // Nothing active matches, so the epoch files are searched anyway
+    let fallback = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "kubernetes pods"}))
+            .unwrap(),
+    );
+    assert!(fallback["context"].as_str().unwrap().contains("Kubernetes"));
+    assert_eq!(fallback["epoch_episodes"], 1);
+    assert_eq!(fallback["epoch_fallback"], true);
+
+    // Epoch episodes are dropped again once the query returns
+    let state = server.lock_state().unwrap();
+    assert_eq!(state.system.episodes.len(), 1);
+    let stored = with_store(&server, |store| store.load_system().unwrap());
+    assert_eq!(stored.episodes.len(), 1);
+}
+
+#[test]
+fn test_am_query_min_activated_words_is_per_call() {
+    let server = make_server();
+    for (name, text) in [
+        (
+            "pipeline",
+            "The deploy pipeline runs integration tests before release.",
+        ),
+        (
+            "lunch",
+            "The cafeteria menu changes before every holiday release.",
+        ),
+    ] {
+        server
+            .am_ingest(&serde_json::json!({"text": text, "name": name}))
+            .unwrap();
+    }
+
+    let strict = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "deploy pipeline release",
+                "min_activated_words": 2
+            }))
+            .unwrap(),
+    );
+    let context = strict["context"].as_str().unwrap();
+    assert!(context.contains("pipeline"));
+    assert!(!context.contains("cafeteria"));
+    assert!(strict["candidates"]["filtered"].as_u64().unwrap() >= 1);
+
+    // The override does not outlive the call
+    let state = server.lock_state().unwrap();
+    assert_eq!(
+        state.system.compose_config(),
+        &am_core::compose::ComposeConfig::default()
+    );
+    drop(state);
+
+    let err = server
+        .am_query(&serde_json::json!({"text": "deploy", "min_activated_words": 0}))
+        .unwrap_err();
+    assert!(err.message().contains("min_activated_words"));
+}
+
+#[test]
+fn test_am_query_reports_empty_reason() {
+    let server = make_server();
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "anything at all"}))
+            .unwrap(),
+    );
+    assert_eq!(json["context"], "");
+    assert_eq!(json["empty_reason"], "no_matching_words");
+
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "The deploy pipeline runs integration tests before release.",
+            "name": "pipeline"
+        }))
+        .unwrap();
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "deploy pipeline", "max_tokens": 1}))
+            .unwrap(),
+    );
+    assert_eq!(json["empty_reason"], "below_thresholds");
+
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "deploy pipeline"}))
+            .unwrap(),
+    );
+    assert!(json["empty_reason"].is_null());
+}
+
+#[test]
+fn test_am_query_returns_ranked_candidates_on_request() {
+    let server = make_server();
+    let long = format!("The deploy pipeline {}", "runs checks ".repeat(40));
+    for (name, text) in [
+        ("pipeline", long.as_str()),
+        (
+            "canary",
+            "Canary deploys go to one region before the pipeline.",
+        ),
+    ] {
+        server
+            .am_ingest(&serde_json::json!({"text": text, "name": name}))
+            .unwrap();
+    }
+
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "deploy pipeline", "max_tokens": 500}))
+            .unwrap(),
+    );
+    assert!(json.get("ranked_candidates").is_none(), "off by default");
+
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "deploy pipeline",
+                "max_tokens": 40,
+                "include_candidates": true,
+            }))
+            .unwrap(),
+    );
+    let ranked = json["ranked_candidates"].as_array().unwrap();
+    assert_eq!(ranked.len(), 2);
+    assert!(ranked[0]["score"].as_f64() >= ranked[1]["score"].as_f64());
+    assert!(
+        ranked.iter().any(|c| c["included"] == false),
+        "candidates beyond the budget are listed too"
+    );
+    for c in ranked {
+        for key in ["id", "category", "tokens", "episode"] {
+            assert!(!c[key].is_null(), "{key} missing");
+        }
+        let preview = c["preview"].as_str().unwrap();
+        assert!(preview.len() <= 203, "{preview}");
+    }
+    assert!(
+        ranked
+            .iter()
+            .any(|c| c["preview"].as_str().unwrap().ends_with("...")),
+        "long text is cut to a preview"
+    );
+
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({
+                "text": "deploy pipeline",
+                "max_tokens": 40,
+                "include_candidates": true,
+                "candidate_limit": 1,
+            }))
+            .unwrap(),
+    );
+    assert_eq!(json["ranked_candidates"].as_array().unwrap().len(), 1);
+
+    let err = server
+        .am_query(&serde_json::json!({"text": "deploy", "include_candidates": true}))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParams);
+}
+
+#[test]
+fn test_am_query_reports_confidence() {
+    let server = make_server();
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "anything at all"}))
+            .unwrap(),
+    );
+    assert_eq!(json["confidence"], "none");
+
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "The deploy pipeline runs integration tests before release.",
+            "name": "pipeline"
+        }))
+        .unwrap();
+    for args in [
+        serde_json::json!({"text": "deploy pipeline integration"}),
+        serde_json::json!({"text": "deploy pipeline integration", "max_tokens": 500}),
+    ] {
+        let json = parse_tool_result(&server.am_query(&args).unwrap());
+        assert_eq!(json["confidence"], "high", "{args}");
+    }
+
+    let json = parse_tool_result(
+        &server
+            .am_batch_query(&serde_json::json!({"queries": [{"query": "zebra"}]}))
+            .unwrap(),
+    );
+    assert_eq!(json["results"][0]["confidence"], "none");
+}
+
+struct CountingReranker(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+impl am_core::compose::Reranker for CountingReranker {
+    fn rerank(&self, _query: &str, candidates: &[am_core::compose::CandidateView<'_>]) -> Vec<f64> {
+        self.0
+            .fetch_add(candidates.len(), std::sync::atomic::Ordering::SeqCst);
+        vec![1.0; candidates.len()]
+    }
+}
+
+#[test]
+fn test_builder_reranker_sees_query_candidates() {
+    let seen = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .reranker(RerankStage::new(CountingReranker(seen.clone())))
+        .build()
+        .unwrap();
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "The deploy pipeline runs integration tests before release.",
+            "name": "pipeline"
+        }))
+        .unwrap();
+
+    let json = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "deploy pipeline"}))
+            .unwrap(),
+    );
+    assert!(json["context"].as_str().unwrap().contains("pipeline"));
+    assert!(seen.load(std::sync::atomic::Ordering::SeqCst) > 0);
+}
+
+#[test]
+fn test_builder_disabled_tool_is_unlisted_and_rejected() {
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .disable_tool("am_import")
+        .build()
+        .unwrap();
+
+    let list = server.tool_list();
+    let names: Vec<&str> = list["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert!(!names.contains(&"am_import"));
+    assert!(names.contains(&"am_export"));
+    assert_eq!(
+        names.len(),
+        make_server().tool_list()["tools"].as_array().unwrap().len() - 1
+    );
+
+    let err = server
+        .dispatch_tool("am_import", &serde_json::json!({"state": {}}))
+        .unwrap_err();
+    assert_eq!(err.message(), "tool disabled: am_import");
+    assert_eq!(err.kind(), ErrorKind::ToolDisabled);
+    assert!(
+        server
+            .dispatch_tool("am_export", &serde_json::json!({}))
+            .is_ok()
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown tool name: am_imprt")]
+fn test_builder_rejects_unknown_tool_name() {
+    let _ = AmServer::builder(BrainStore::open_in_memory().unwrap()).disable_tool("am_imprt");
+}
+
+#[test]
+fn test_builder_applies_compose_and_budget_defaults() {
+    let compose = ComposeConfig {
+        min_activated_words: 2,
+        ..ComposeConfig::default()
+    };
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .compose_config(compose.clone())
+        .budget_config(am_core::compose::BudgetConfig {
+            min_conscious: 0,
+            ..am_core::compose::BudgetConfig::default()
+        })
+        .build()
+        .unwrap();
+    assert_eq!(
+        server.lock_state().unwrap().system.compose_config(),
+        &compose
+    );
+    assert_eq!(server.budget.min_conscious, 0);
+}
+
+#[test]
+fn test_client_info_sets_actor_once() {
+    let server = make_server();
+    server.observe_client_info(&serde_json::json!({
+        "protocolVersion": "2024-11-05",
+        "clientInfo": { "name": "claude-code", "version": "1.0" }
+    }));
+    server.observe_client_info(&serde_json::json!({
+        "clientInfo": { "name": "other-client" }
+    }));
+    server
+        .am_salient(&serde_json::json!({ "text": "prefer small commits" }))
+        .unwrap();
+
+    let state = server.lock_state().unwrap();
+    assert_eq!(state.system.actor(), Some("claude-code"));
+    assert_eq!(
+        state.system.conscious_episode.neighborhoods[0]
+            .actor
+            .as_deref(),
+        Some("claude-code")
+    );
+}
+
+#[test]
+fn test_builder_actor_wins_over_client_info() {
+    let server = AmServer::builder(BrainStore::open_in_memory().unwrap())
+        .actor("codex")
+        .build()
+        .unwrap();
+    server.observe_client_info(&serde_json::json!({
+        "clientInfo": { "name": "claude-code" }
+    }));
+    server
+        .am_ingest(&serde_json::json!({
+            "text": "Release notes are drafted from merged pull requests.",
+            "name": "release"
+        }))
+        .unwrap();
+
+    let episodes = parse_tool_result(&server.am_episodes().unwrap());
+    assert_eq!(episodes[0]["actor"], "codex");
+}
+
+#[test]
+fn test_am_query_actor_filter() {
+    let server = make_server();
+    for (actor, text) in [
+        ("claude-code", "deployment runs through the blue cluster"),
+        ("codex", "deployment runs through the green cluster"),
+    ] {
+        server
+            .lock_state()
+            .unwrap()
+            .system
+            .set_actor(Some(actor.to_string()));
+        server
+            .am_salient(&serde_json::json!({ "text": text }))
+            .unwrap();
+    }
+
+    let result = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({ "text": "deployment cluster", "actor": "codex" }))
+            .unwrap(),
+    );
+    let context = result["context"].as_str().unwrap();
+    assert!(context.contains("green cluster"), "got:\n{context}");
+    assert!(!context.contains("blue cluster"), "got:\n{context}");
+    // The filter lasts for one call only
+    assert_eq!(server.lock_state().unwrap().system.actor_filter(), None);
+}
+
+#[test]
+fn test_query_project_override() {
+    let server = make_server();
+    for text in [
+        "DECISION (repo-x): redis cache warms nightly",
+        "DECISION (repo-y): memcached cache evicts hourly",
+    ] {
+        server
+            .am_salient(&serde_json::json!({ "text": text }))
+            .unwrap();
+    }
+    server
+        .lock_state()
+        .unwrap()
+        .system
+        .set_project(Some("repo-x".to_string()));
+
+    // The favoured memory comes first in the recalled context
+    let leads_with = |result: &serde_json::Value, word: &str| {
+        let context = result["context"].as_str().unwrap();
+        let other = if word == "redis" {
+            "memcached"
+        } else {
+            "redis"
+        };
+        context
+            .find(word)
+            .is_some_and(|at| context.find(other).is_none_or(|o| at < o))
+    };
+
+    let result = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({ "text": "redis memcached", "project": "Repo-Y" }))
+            .unwrap(),
+    );
+    assert!(
+        leads_with(&result, "memcached"),
+        "got:\n{}",
+        result["context"]
+    );
+    assert!(result.get("project_note").is_none());
+    // The override lasts for one call only
+    assert_eq!(
+        server.lock_state().unwrap().system.project(),
+        Some("repo-x")
+    );
+
+    let result = parse_tool_result(
+        &server
+            .am_batch_query(&serde_json::json!({ "queries": [
+                { "query": "redis memcached", "project": "repo-y" },
+                { "query": "redis memcached" },
+                { "query": "redis memcached", "project": "repo-z" },
+            ]}))
+            .unwrap(),
+    );
+    let results = result["results"].as_array().unwrap();
+    assert!(
+        leads_with(&results[0], "memcached"),
+        "got:\n{}",
+        results[0]["context"]
+    );
+    assert!(
+        leads_with(&results[1], "redis"),
+        "got:\n{}",
+        results[1]["context"]
+    );
+    assert!(results[0].get("project_note").is_none());
+    assert!(
+        results[2]["project_note"]
+            .as_str()
+            .unwrap()
+            .contains("'repo-z'")
+    );
+}
+
+#[test]
+fn test_am_query_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_query(&serde_json::json!({ "text": oversized }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+#[test]
+fn test_am_activate_response_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_activate_response(&serde_json::json!({ "text": oversized }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+#[test]
+fn test_am_feedback_rejects_oversized_query() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_feedback(&serde_json::json!({
+        "query": oversized,
+        "neighborhood_ids": [],
+        "signal": "boost"
+    }));
+    assert!(result.is_err(), "should reject query exceeding size limit");
+}
+
+#[test]
+fn test_am_batch_query_rejects_oversized_aggregate() {
+    let server = make_server();
+    // Each query is half the limit; together they exceed it
+    let half_plus = "x".repeat(MAX_TOOL_INPUT_BYTES / 2 + 1);
+    let result = server.am_batch_query(&serde_json::json!({
+        "queries": [
+            { "query": half_plus.clone() },
+            { "query": half_plus }
+        ]
+    }));
+    assert!(
+        result.is_err(),
+        "should reject aggregate payload exceeding size limit"
+    );
+}
+
+#[test]
+fn test_am_query_index_rejects_oversized_input() {
+    let server = make_server();
+    let oversized = "x".repeat(MAX_TOOL_INPUT_BYTES + 1);
+    let result = server.am_query_index(&serde_json::json!({ "text": oversized }));
+    assert!(result.is_err(), "should reject input exceeding size limit");
+}
+
+// --- Snapshot tests for MCP tool response shapes ---
+
+/// Server with pre-ingested content for snapshot tests requiring data.
+#[test]
+fn test_metrics_text_counts_tool_calls_and_reads_gauges() {
+    let server = make_server();
+    server
+        .dispatch_tool(
+            "am_ingest",
+            &serde_json::json!({"text": "Canary rollouts restore pods.", "name": "deploy"}),
+        )
+        .unwrap();
+    server
+        .dispatch_tool("am_query", &serde_json::json!({"text": "canary rollout"}))
+        .unwrap();
+    assert!(
+        server
+            .dispatch_tool("am_query", &serde_json::json!({}))
+            .is_err()
+    );
+    assert!(
+        server
+            .dispatch_tool("am_nonsense", &serde_json::json!({}))
+            .is_err()
+    );
+
+    let text = server.metrics_text();
+    assert!(text.contains("am_tool_calls_total{tool=\"am_ingest\",outcome=\"ok\"} 1\n"));
+    assert!(text.contains("am_tool_calls_total{tool=\"am_query\",outcome=\"ok\"} 1\n"));
+    assert!(text.contains("am_tool_calls_total{tool=\"am_query\",outcome=\"error\"} 1\n"));
+    assert!(text.contains("am_tool_duration_seconds_count{tool=\"am_query\"} 2\n"));
+    assert!(!text.contains("am_nonsense"), "unknown tools get no series");
+    assert!(text.contains("am_save_duration_seconds_count 1\n"));
+    assert!(text.contains("am_episodes 1\n"));
+    assert!(text.contains("am_loading 0\n"));
+    let n = server.lock_state().unwrap().system.n();
+    assert!(text.contains(&format!("am_occurrences {n}\n")));
+}
+
+#[test]
+fn test_run_gc_reconciles_the_loaded_system() {
+    let no_retention = am_store::config::RetentionPolicy {
+        grace_epochs: 0,
+        retention_days: 0,
+        min_neighborhoods: 0,
+        recency_weight: 0.0,
+    };
+    let store = BrainStore::open_in_memory()
+        .unwrap()
+        .with_retention(no_retention);
+    let server = AmServer::new(store).unwrap();
+    for (name, text) in [
+        (
+            "deploy-notes",
+            "Kubernetes canary rollout restored the pods.",
+        ),
+        ("cooking", "Sourdough needs a long cold proof."),
+    ] {
+        server
+            .am_ingest(&serde_json::json!({"text": text, "name": name}))
+            .unwrap();
+    }
+    // Warm the deploy notes so only they survive a floor of 0
+    server
+        .am_query(&serde_json::json!({"text": "kubernetes canary rollout"}))
+        .unwrap();
+
+    let detail = server.run_gc(0).unwrap();
+    assert!(!detail.occurrences.is_empty());
+    assert_eq!(detail.episodes.len(), 1);
+
+    let mut state = server.lock_state().unwrap();
+    let names: Vec<&str> = state
+        .system
+        .episodes
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["deploy-notes"]);
+    assert!(am_core::invariants::check_system(&state.system).is_empty());
+
+    // A full save after GC must not bring the evicted rows back
+    let system = std::mem::replace(&mut state.system, DAESystem::new("test"));
+    let (system, reloaded, resurrected) = with_store(&server, move |store| {
+        store.save_system(&system).unwrap();
+        let reloaded = store.load_system().unwrap();
+        let resurrected = store.store().resurrected_count(&system);
+        (system, reloaded, resurrected)
+    });
+    assert_eq!(reloaded.n(), system.n());
+    assert!(reloaded.episodes.iter().all(|e| e.name != "cooking"));
+    assert_eq!(resurrected, 0);
+    state.system = system;
+}
+
+fn make_server_with_content() -> AmServer<BrainStore> {
+    let server = make_server();
+    server
+            .am_ingest(&serde_json::json!({
+                "text": "Rust ownership rules prevent data races at compile time. The borrow checker enforces exclusive mutable access. Lifetimes track reference validity statically.",
+                "name": "rust-safety"
+            }))
+            .unwrap();
+    server
+}
+
+#[test]
+fn snapshot_am_stats_empty() {
+    let server = make_server();
+    let result = server.am_stats().unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_stats_empty", json, {
+        ".db_bytes" => "[db_bytes]",
+    });
+}
+
+#[test]
+fn snapshot_am_stats_with_content() {
+    let server = make_server_with_content();
+    let result = server.am_stats().unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_stats_with_content", json, {
+        ".db_bytes" => "[db_bytes]",
+        ".activation.mean" => insta::rounded_redaction(2),
+        ".tokens.largest_episodes[].id" => "[id]",
+    });
+}
+
+#[test]
+fn snapshot_am_ingest() {
+    let server = make_server();
+    let result = server
+        .am_ingest(&serde_json::json!({
+            "text": "Testing snapshot output format.",
+            "name": "snapshot-test"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_ingest", json, {
+        ".neighborhoods" => "[count]",
+        ".occurrences" => "[count]",
+    });
+}
+
+#[test]
+fn snapshot_am_query() {
+    let server = make_server_with_content();
+    let result = server
+        .am_query(&serde_json::json!({ "text": "rust borrow checker" }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_query", json, {
+        ".context" => "[context_text]",
+        ".metrics.drift_magnitude" => "[float]",
+        ".metrics.phase_coherence" => "[float]",
+        ".metrics.interference_score" => "[float]",
+        ".metrics.query_terms" => "[terms]",
+        ".stats.**" => insta::dynamic_redaction(|value, _| {
+            if value.as_f64().is_some() { insta::internals::Content::String("[number]".into()) }
+            else { value.clone() }
+        }),
+        ".recalled_ids.**" => "[ids]",
+        ".budget.**" => "[budget]",
+        ".index" => "[index]",
+        ".timings.activation_ms" => "[ms]",
+        ".timings.drift_ms" => "[ms]",
+        ".timings.compose_ms" => "[ms]",
+        ".timings.save_ms" => "[ms]",
+    });
+}
+
+#[test]
+fn snapshot_am_query_index() {
+    let server = make_server_with_content();
+    let result = server
+        .am_query_index(&serde_json::json!({ "text": "rust ownership" }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_query_index", json, {
+        ".entries[].id" => "[uuid]",
+        ".entries[].score" => "[float]",
+        ".entries[].preview" => "[text]",
+        ".total" => "[count]",
+    });
+}
+
+#[test]
+fn snapshot_am_salient() {
+    let server = make_server();
+    let result = server
+        .am_salient(&serde_json::json!({ "text": "Important architectural decision" }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_salient", json, {
+        ".id" => "[uuid]",
+        ".occurrences" => "[count]",
+    });
+}
+
+#[test]
+fn snapshot_am_buffer() {
+    let server = make_server();
+    let result = server
+        .am_buffer(&serde_json::json!({
+            "user": "What is ownership?",
+            "assistant": "Ownership is Rust's memory management system."
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_buffer", json);
+}
+
+#[test]
+fn snapshot_am_activate_response() {
+    let server = make_server_with_content();
+    let result = server
+        .am_activate_response(&serde_json::json!({
+            "text": "The borrow checker prevents data races."
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_activate_response", json, {
+        ".activated" => "[count]",
+        ".total_occurrences" => "[count]",
+        ".strengthened.moved_neighborhoods" => "[moved]",
+    });
+}
+
+#[test]
+fn snapshot_am_export() {
+    let server = make_server_with_content();
+    let result = server.am_export(&serde_json::json!({})).unwrap();
+    let json = parse_tool_result(&result);
+
+    // Verify structure rather than snapshot (export contains non-deterministic
+    // quaternion positions and UUIDs that change every run)
+    assert_eq!(json["version"], "0.7.2");
+    assert!(json["system"]["episodes"].is_array());
+    assert!(json["system"]["consciousEpisode"].is_object());
+    assert!(json["system"]["agentName"].is_string());
+    assert!(json["system"]["N"].is_number());
+    assert!(json["conversationBuffer"].is_array());
+    assert!(json["conversationHistory"].is_array());
+}
+
+#[test]
+fn snapshot_am_import() {
+    let server = make_server_with_content();
+    // Export first, parse the JSON text back to a Value for import
+    let export_result = server.am_export(&serde_json::json!({})).unwrap();
+    let export_text = export_result["content"][0]["text"].as_str().unwrap();
+    let state_value: serde_json::Value = serde_json::from_str(export_text).unwrap();
+
+    let server2 = make_server();
+    let result = server2
+        .am_import(&serde_json::json!({ "state": state_value }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_import", json);
+}
+
+#[test]
+fn snapshot_am_feedback_boost() {
+    let server = make_server_with_content();
+    let ids = ingest_and_get_neighborhood_ids(&server);
+    if ids.is_empty() {
+        return; // Skip if no neighborhoods (determinism guard)
+    }
+    let result = server
+        .am_feedback(&serde_json::json!({
+            "query": "quantum",
+            "neighborhood_ids": ids,
+            "signal": "boost"
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+
+    // Structure assertion (neighborhood IDs are non-deterministic across runs)
+    assert!(json["boosted"].is_number());
+    assert!(json["demoted"].is_number());
+    assert!(json.get("stats").is_some());
+}
+
+#[test]
+fn snapshot_am_batch_query() {
+    let server = make_server_with_content();
+    let result = server
+        .am_batch_query(&serde_json::json!({
+            "queries": [
+                { "query": "rust ownership" },
+                { "query": "borrow checker" }
+            ]
+        }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+    insta::assert_json_snapshot!("am_batch_query", json, {
+        ".results[].context" => "[context_text]",
+        ".results[].metrics.**" => "[metric]",
+        ".results[].stats.**" => insta::dynamic_redaction(|value, _| {
+            if value.as_f64().is_some() { insta::internals::Content::String("[number]".into()) }
+            else { value.clone() }
+        }),
+        ".results[].recalled_ids.**" => "[ids]",
+        ".results[].budget.**" => "[budget]",
+        ".results[].token_estimate" => "[count]",
+        ".results[].index" => "[index]",
+    });
+}
+
+#[test]
+fn snapshot_am_retrieve() {
+    let server = make_server_with_content();
+    // Get neighborhood IDs by querying the ingested content
+    let query_result = server
+        .am_query(&serde_json::json!({ "text": "rust ownership borrow" }))
+        .unwrap();
+    let query_json = parse_tool_result(&query_result);
+    let recalled = &query_json["recalled_ids"];
+    let mut ids = Vec::new();
+    for cat in &["conscious", "subconscious", "novel"] {
+        if let Some(arr) = recalled[cat].as_array() {
+            for id in arr {
+                if let Some(s) = id.as_str() {
+                    ids.push(s.to_string());
+                }
+            }
+        }
+    }
+    if ids.is_empty() {
+        return;
+    }
+
+    let result = server
+        .am_retrieve(&serde_json::json!({ "ids": [ids[0]] }))
+        .unwrap();
+    let json = parse_tool_result(&result);
+
+    // Structure assertion (neighborhood data is non-deterministic)
+    let entries = json["entries"].as_array().unwrap();
+    assert!(!entries.is_empty());
+    for entry in entries {
+        assert!(entry["id"].is_string());
+        assert!(entry["episode"].is_string());
+        assert!(entry["text"].is_string());
+        assert!(entry["category"].is_string());
+    }
+    assert!(json["count"].is_number());
+}
+
+#[test]
+fn test_am_ingest_does_not_block_stats() {
+    use std::sync::Arc;
+    use std::sync::mpsc::channel;
+
+    let server = Arc::new(make_server());
+    let (parked, at_gate) = channel::<()>();
+    let (release, gate) = channel::<()>();
+
+    let ingest_server = Arc::clone(&server);
+    let ingest = std::thread::spawn(move || {
+        super::ingestion::TOKENIZE_GATE.set(Some((parked, gate)));
+        ingest_server.am_ingest(&serde_json::json!({
+            "text": "Widgets mesh with gadgets. Gadgets turn the widgets.",
+            "name": "gated",
+        }))
+    });
+
+    // The ingest is parked where it tokenizes; the state must be free
+    at_gate.recv().unwrap();
+    assert!(server.try_lock_state().is_some());
+    let during = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(during["episodes"], 0);
+    assert!(!ingest.is_finished());
+
+    release.send(()).unwrap();
+    ingest.join().unwrap().unwrap();
+    let after = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(after["episodes"], 1);
+}
+
+#[test]
+fn test_am_stats_does_not_wait_for_a_busy_store() {
+    let server = make_server();
+    let idle = parse_tool_result(&server.am_stats().unwrap());
+    assert!(idle.get("store_busy").is_none());
+
+    // Hold the store thread, as a long save would
+    let (release, gate) = std::sync::mpsc::channel::<()>();
+    let held = server.store.run(move |_| gate.recv().is_ok());
+    let busy = parse_tool_result(&server.am_stats().unwrap());
+    assert_eq!(busy["store_busy"], true);
+    assert_eq!(busy["db_size_bytes"], idle["db_size_bytes"]);
+    assert_eq!(busy["episodes"], 0);
+
+    release.send(()).unwrap();
+    assert!(held.wait());
+    let idle = parse_tool_result(&server.am_stats().unwrap());
+    assert!(idle.get("store_busy").is_none());
+}
+
+#[test]
+fn test_save_failure_is_returned_and_flags_divergence() {
+    use am_store::memory_store::InMemoryStore;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let marker = dir.path().join("am-serve.unsaved");
+    let store = InMemoryStore::with_system(&DAESystem::new("test"));
+    let server = AmServer::builder(store)
+        .unsaved_marker(&marker)
+        .build()
+        .unwrap();
+    server
+        .am_ingest(&serde_json::json!({"text": "Saved before the disk filled up."}))
+        .unwrap();
+    assert_eq!(
+        parse_tool_result(&server.am_stats().unwrap())["dirty_unsaved"],
+        false
+    );
+
+    with_store(&server, |store| store.poison_writes(true));
+    let err = server
+        .am_salient(&serde_json::json!({"text": "DECISION: keep the WAL"}))
+        .unwrap_err();
+    assert!(
+        err.message().contains("failed to persist after salient"),
+        "{err}"
+    );
+    assert_eq!(err.kind(), ErrorKind::Store);
+    assert!(!err.retriable());
+    let err = server
+        .am_ingest(&serde_json::json!({"text": "Lost on the way to disk."}))
+        .unwrap_err();
+    assert!(
+        err.message().contains("failed to persist after ingest"),
+        "{err}"
+    );
+    assert!(server.dirty_unsaved());
+    assert_eq!(
+        parse_tool_result(&server.am_stats().unwrap())["dirty_unsaved"],
+        true
+    );
+    assert!(
+        std::fs::read_to_string(&marker)
+            .unwrap()
+            .contains("salient")
+    );
+
+    // A full save brings the store back in line
+    with_store(&server, |store| store.poison_writes(false));
+    let state = parse_tool_result(&server.am_export(&serde_json::json!({})).unwrap());
+    server
+        .am_import(&serde_json::json!({ "state": state }))
+        .unwrap();
+    assert!(!server.dirty_unsaved());
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_large_brain_loads_in_background() {
+    use am_core::tokenizer::ingest_text;
+    use am_store::memory_store::InMemoryStore;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    let mut rng = SmallRng::seed_from_u64(3);
+    let mut sys = DAESystem::new("test");
+    sys.add_episode(ingest_text(
+        "The cache eviction policy orders entries by last access.",
+        None,
+        &mut rng,
+    ));
+    let store = InMemoryStore::with_system(&sys).with_load_delay(Duration::from_millis(300));
+    let server = AmServer::builder(store)
+        .lazy_load_threshold(0)
+        .load_wait(Duration::from_millis(10))
+        .build()
+        .unwrap();
+    assert!(server.is_loading());
+
+    // Stats answer at once with progress
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert!(
+        stats["loading"]["occurrences"].as_u64().unwrap() > 0,
+        "{stats}"
+    );
+    assert_eq!(stats["dirty_unsaved"], false);
+
+    // Recall reports the load instead of failing; writes wait, then give up
+    let query = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "cache eviction"}))
+            .unwrap(),
+    );
+    assert_eq!(query["empty_reason"], "still_loading");
+    let err = server
+        .am_salient(&serde_json::json!({"text": "keep LRU"}))
+        .unwrap_err();
+    assert!(err.message().contains("still loading"), "{err}");
+    assert_eq!(err.kind(), ErrorKind::Loading);
+    assert!(err.retriable());
+
+    std::thread::sleep(Duration::from_millis(400));
+    let query = parse_tool_result(
+        &server
+            .am_query(&serde_json::json!({"text": "cache eviction"}))
+            .unwrap(),
+    );
+    assert!(query["context"].as_str().unwrap().contains("eviction"));
+    assert!(!server.is_loading());
+    let stats = parse_tool_result(&server.am_stats().unwrap());
+    assert!(stats.get("loading").is_none());
+    assert_eq!(stats["episodes"], 1);
+}
+
+fn audit_log(server: &AmServer<BrainStore>) -> Vec<am_store::store::AuditEntry> {
+    with_store(server, |store| {
+        store
+            .store()
+            .audit_log(&am_store::store::AuditFilter::default())
+            .unwrap()
+    })
+}
+
+#[test]
+fn test_mutating_tools_each_write_one_audit_row() {
+    use am_core::store_trait::AuditKind;
+
+    let server = make_server();
+    let ids = ingest_and_get_neighborhood_ids(&server);
+    assert_eq!(audit_log(&server)[0].kind, AuditKind::Ingest);
+
+    server
+        .am_feedback(&serde_json::json!({
+            "query": "quantum particles",
+            "neighborhood_ids": ids,
+            "signal": "boost"
+        }))
+        .unwrap();
+    server
+        .am_salient(&serde_json::json!({"text": "DECISION: keep the audit log"}))
+        .unwrap();
+    for i in 0..3 {
+        server
+            .am_buffer(&serde_json::json!({
+                "user": format!("User message {i}"),
+                "assistant": format!("Assistant response {i}")
+            }))
+            .unwrap();
+    }
+    let state = parse_tool_result(&server.am_export(&serde_json::json!({})).unwrap());
+    server
+        .am_import(&serde_json::json!({ "state": state }))
+        .unwrap();
+
+    let kinds: Vec<_> = audit_log(&server).iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            AuditKind::Import,
+            AuditKind::Buffer,
+            AuditKind::Salient,
+            AuditKind::Feedback,
+            AuditKind::Ingest,
+        ]
+    );
+}
+
+#[test]
+fn test_failed_save_leaves_no_audit_row() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = am_store::config::Config {
+        data_dir: dir.path().to_path_buf(),
+        ..am_store::config::Config::default()
+    };
+    let server = AmServer::new(BrainStore::open(&config).unwrap()).unwrap();
+
+    // Fail the save after its episode and neighborhood rows went in
+    let other = rusqlite::Connection::open(dir.path().join("brain.db")).unwrap();
+    other
+        .execute_batch(
+            "CREATE TRIGGER fail_occurrences BEFORE INSERT ON occurrences
+             BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+        )
+        .unwrap();
+    server
+        .am_ingest(&serde_json::json!({"text": "Lost on the way to disk."}))
+        .unwrap_err();
+    server
+        .am_salient(&serde_json::json!({"text": "Also lost."}))
+        .unwrap_err();
+    assert!(audit_log(&server).is_empty());
+
+    other
+        .execute_batch("DROP TRIGGER fail_occurrences")
+        .unwrap();
+    server
+        .am_ingest(&serde_json::json!({"text": "Saved once the disk has room."}))
+        .unwrap();
+    assert_eq!(audit_log(&server).len(), 1);
+}
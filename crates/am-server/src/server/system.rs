@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use am_core::{
+    episode::Episode,
+    serde_compat::{ImportOptions, ValidationPolicy, export_json, import_json_with},
+    store_trait::{AmStore, AuditEvent, AuditKind},
+};
+
+use super::{AmServer, parse_params};
+use crate::error::{ErrorKind, ToolError};
+use crate::jsonrpc::tool_result_text;
+
+/// Episodes listed by token cost in `am_stats`.
+const LARGEST_EPISODES: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ExportRequest {
+    /// Write the export to this file instead of returning it
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ImportRequest {
+    /// Full state JSON to import
+    state: Option<serde_json::Value>,
+    /// Read the state JSON from this file instead
+    path: Option<PathBuf>,
+    /// Skip what fails validation instead of refusing the file
+    #[serde(default)]
+    lenient: bool,
+}
+
+impl<S: AmStore + Send + 'static> AmServer<S> {
+    pub(super) fn am_stats(&self) -> Result<Value, ToolError> {
+        // Answer at once during a background load instead of waiting on it
+        if self.is_loading() && self.try_lock_state().is_none() {
+            let stats = serde_json::json!({
+                "loading": self.loading_json(),
+                "dirty_unsaved": self.dirty_unsaved(),
+            });
+            return Ok(tool_result_text(
+                &serde_json::to_string_pretty(&stats).unwrap_or_default(),
+            ));
+        }
+        let state = self.lock_state()?;
+        let mut stats = Self::stats_json(&state.system);
+        let policy = state.system.word_weight_policy();
+        stats["word_weights"] = serde_json::json!({
+            "floor": policy.floor,
+            "ceiling": policy.ceiling,
+            "skip_hapax_drift": policy.skip_hapax_drift,
+        });
+
+        // Recall cost of everything in memory, for budget planning
+        let conscious_tokens = state.system.conscious_episode.llm_tokens();
+        let mut episode_tokens: Vec<(&Episode, usize)> = state
+            .system
+            .episodes
+            .iter()
+            .map(|ep| (ep, ep.llm_tokens()))
+            .collect();
+        let subconscious_tokens: usize = episode_tokens.iter().map(|(_, t)| t).sum();
+        episode_tokens.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.timestamp.cmp(&b.0.timestamp)));
+        stats["tokens"] = serde_json::json!({
+            "total": conscious_tokens + subconscious_tokens,
+            "conscious": conscious_tokens,
+            "subconscious": subconscious_tokens,
+            "largest_episodes": episode_tokens
+                .iter()
+                .take(LARGEST_EPISODES)
+                .map(|(ep, tokens)| serde_json::json!({
+                    "id": ep.id.to_string(),
+                    "name": ep.name,
+                    "tokens": tokens,
+                }))
+                .collect::<Vec<_>>(),
+        });
+
+        drop(state);
+
+        // Add store-level stats (DB size, activation distribution). While
+        // another call's writes run, these are the last figures read.
+        let (figures, busy) = self.store_figures();
+        if let Some(figures) = &figures {
+            stats["db_size_bytes"] = serde_json::json!(figures.db_size);
+            stats["repaired_records"] = serde_json::json!(figures.repaired_records);
+        }
+        stats["dirty_unsaved"] = serde_json::json!(self.dirty_unsaved());
+        if let Some(activation) = figures.and_then(|f| f.activation) {
+            stats["activation"] = serde_json::json!({
+                "mean": activation.mean_activation,
+                "max": activation.max_activation,
+                "zero_count": activation.zero_activation,
+            });
+        }
+        if busy {
+            stats["store_busy"] = serde_json::json!(true);
+        }
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&stats).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_export(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: ExportRequest = parse_params(args)?;
+        let target = match &req.path {
+            Some(path) => Some(self.path_policy.check_write(path)?),
+            None => None,
+        };
+
+        let state = self.lock_state()?;
+        let json = export_json(&state.system)
+            .map_err(|e| ToolError::new(ErrorKind::Internal, format!("[serde] {e}")))?;
+        drop(state);
+
+        let Some(target) = target else {
+            return Ok(tool_result_text(&json));
+        };
+        std::fs::write(&target, &json).map_err(|e| {
+            ToolError::io(
+                &e,
+                format!("[io] failed to write {}: {e}", target.display()),
+            )
+        })?;
+        let result = serde_json::json!({
+            "path": target.display().to_string(),
+            "bytes": json.len(),
+        });
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+
+    pub(super) fn am_import(&self, args: &Value) -> Result<Value, ToolError> {
+        let req: ImportRequest = parse_params(args)?;
+
+        let json_str = match (&req.state, &req.path) {
+            (Some(state), None) => serde_json::to_string(state)
+                .map_err(|e| ToolError::new(ErrorKind::Internal, format!("[serde] {e}")))?,
+            (None, Some(path)) => {
+                let source = self.path_policy.check_read(path)?;
+                std::fs::read_to_string(&source).map_err(|e| {
+                    ToolError::io(&e, format!("[io] failed to read {}: {e}", source.display()))
+                })?
+            }
+            _ => {
+                return Err(ToolError::invalid_params(
+                    "invalid params: pass exactly one of state or path",
+                ));
+            }
+        };
+
+        let mut state = self.lock_state()?;
+
+        // Malformed or outdated state is the caller's to fix, not a server fault
+        let options = ImportOptions {
+            validation: if req.lenient {
+                ValidationPolicy::Lenient
+            } else {
+                ValidationPolicy::Strict
+            },
+            ..ImportOptions::default()
+        };
+        let (mut imported, report) = import_json_with(&json_str, options)
+            .map_err(|e| ToolError::new(ErrorKind::InvalidState, format!("[serde] {e}")))?;
+        // Weight clamps come from store metadata, not the export
+        imported.set_word_weight_policy(state.system.word_weight_policy());
+        imported.set_plan_verbs(state.system.plan_verbs());
+        imported.set_compose_config(state.system.compose_config().clone());
+        for (word, multiplier) in state.system.word_biases() {
+            imported.set_word_bias(word, *multiplier);
+        }
+        // The reranker is injected by the embedding application
+        imported.set_rerank_stage(state.system.rerank_stage().cloned());
+        imported.set_actor(state.system.actor().map(str::to_owned));
+
+        // Intentional save_system: import replaces the entire DAE state,
+        // so a full rewrite is the only correct persistence strategy. The
+        // system moves to the store thread for the write and comes back;
+        // the lock is held throughout, so no call sees the old system once
+        // the new one is being written.
+        let event = AuditEvent::new(AuditKind::Import, imported.episodes.len() as u64)
+            .with_context(&imported)
+            .with_detail(format!(
+                "replace: {} episodes, {} conscious",
+                imported.episodes.len(),
+                imported.conscious_episode.neighborhoods.len()
+            ));
+        let (imported, saved, elapsed) = self
+            .store
+            .run(move |store| {
+                let started = Instant::now();
+                let saved = store.audited(&event, || store.save_system(&imported));
+                (imported, saved, started.elapsed())
+            })
+            .wait();
+        self.metrics.record_save(elapsed);
+        state.system = imported;
+        saved.map_err(|e| self.save_failed("import", e))?;
+        self.save_succeeded();
+
+        let result = serde_json::json!({
+            "imported": true,
+            "repaired_ids": report.repaired_ids,
+            "clamped_timestamps": report.clamped_timestamps,
+            "report": report,
+            "stats": Self::stats_json(&state.system),
+        });
+
+        Ok(tool_result_text(
+            &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        ))
+    }
+}
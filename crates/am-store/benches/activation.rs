@@ -0,0 +1,79 @@
+//! Criterion benchmarks for persisting query activation increments.
+//!
+//! Run with: `cargo bench -p am-store --bench activation`
+//!
+//! Benchmarks (file-backed store, ~50k occurrences, 400 increments with
+//! repeats, as from a 40-token query on a hot brain):
+//! - `activation_writes/per_row` - one autocommit UPDATE per increment
+//! - `activation_writes/batched` - folded deltas, one transaction
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use am_core::{
+    episode::Episode, neighborhood::Neighborhood, query::fold_activation_deltas, system::DAESystem,
+};
+use am_store::store::Store;
+use uuid::Uuid;
+
+fn build_system(n_episodes: usize) -> DAESystem {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let mut system = DAESystem::new("bench");
+    let words: Vec<String> = (0..200).map(|i| format!("word{i}")).collect();
+
+    for ep_idx in 0..n_episodes {
+        let mut ep = Episode::new(&format!("episode-{ep_idx}"));
+        for n_idx in 0..5 {
+            let base = ((ep_idx * 5 + n_idx) * 3) % words.len();
+            let tokens: Vec<String> = (0..10)
+                .map(|i| words[(base + i) % words.len()].clone())
+                .collect();
+            let text = tokens.join(" ");
+            ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, &mut rng));
+        }
+        system.add_episode(ep);
+    }
+    system
+}
+
+fn bench_activation_writes(c: &mut Criterion) {
+    let dir = tempfile::TempDir::new().expect("tempdir");
+    let store = Store::open(&dir.path().join("bench.db")).expect("open store");
+    let system = build_system(1_000);
+    store.save_system(&system).expect("save_system");
+
+    let all: Vec<Uuid> = system
+        .episodes
+        .iter()
+        .flat_map(|e| &e.neighborhoods)
+        .flat_map(|n| &n.occurrences)
+        .map(|o| o.id)
+        .collect();
+    let mut rng = SmallRng::seed_from_u64(7);
+    let hot: Vec<Uuid> = (0..300)
+        .map(|_| all[rng.random_range(0..all.len())])
+        .collect();
+    let ids: Vec<Uuid> = (0..400).map(|i| hot[(i * 7) % hot.len()]).collect();
+
+    let mut group = c.benchmark_group("activation_writes");
+    group.sample_size(20);
+    group.bench_function("per_row", |b| {
+        b.iter(|| {
+            for id in &ids {
+                store.increment_activation(*id).expect("increment");
+            }
+        });
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            store
+                .increment_activations(&fold_activation_deltas(&ids))
+                .expect("increment_activations");
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_activation_writes);
+criterion_main!(benches);
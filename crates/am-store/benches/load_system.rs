@@ -0,0 +1,61 @@
+//! Criterion benchmarks for `BrainStore::load_system` at three scale points.
+//!
+//! Run with: `cargo bench -p am-store --bench load_system`
+//!
+//! Benchmarks:
+//! - `load_system/100_episodes` (~5k occurrences)
+//! - `load_system/1000_episodes` (~50k occurrences)
+//! - `load_system/10000_episodes` (~500k occurrences)
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use am_core::{episode::Episode, neighborhood::Neighborhood, system::DAESystem};
+use am_store::project::BrainStore;
+
+/// Build a synthetic DAESystem with `n_episodes` episodes, each containing
+/// 5 neighborhoods of 10 occurrences.
+fn build_system(n_episodes: usize) -> DAESystem {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let mut system = DAESystem::new("bench");
+    let words: Vec<String> = (0..200).map(|i| format!("word{i}")).collect();
+
+    for ep_idx in 0..n_episodes {
+        let mut ep = Episode::new(&format!("episode-{ep_idx}"));
+        for n_idx in 0..5 {
+            let base = ((ep_idx * 5 + n_idx) * 3) % words.len();
+            let tokens: Vec<String> = (0..10)
+                .map(|i| words[(base + i) % words.len()].clone())
+                .collect();
+            let text = tokens.join(" ");
+            ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, &mut rng));
+        }
+        system.add_episode(ep);
+    }
+    system.add_to_conscious("benchmark conscious insight", &mut rng);
+    system
+}
+
+fn bench_load_system(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_system");
+    group.sample_size(10);
+
+    for &n_episodes in &[100, 1_000, 10_000] {
+        let system = build_system(n_episodes);
+        let total_occ = system.n();
+        let store = BrainStore::open_in_memory().expect("in-memory store");
+        store.save_system(&system).expect("save_system");
+
+        group.bench_with_input(
+            BenchmarkId::new("episodes", format!("{n_episodes} ({total_occ} occ)")),
+            &store,
+            |b, store| b.iter(|| store.load_system().expect("load_system")),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_load_system);
+criterion_main!(benches);
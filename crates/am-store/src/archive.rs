@@ -0,0 +1,396 @@
+//! Full archives: a v0.7.2 export plus the state that lives only in the
+//! store.
+//!
+//! A plain export carries the `DAESystem` and nothing else, so moving a
+//! brain to another machine with one loses the conversation buffer
+//! (exchanges not yet flushed into an episode), metadata overrides, frozen
+//! words, and word biases. An archive nests the export unchanged beside a
+//! `store_state` section holding those:
+//!
+//! ```json
+//! { "format": "am-archive", "archive_version": 1,
+//!   "store_state": { "buffer": [...], "metadata": {...},
+//!                    "frozen_words": [...], "word_biases": {...} },
+//!   "export": { "version": "0.7.2", "system": {...} } }
+//! ```
+//!
+//! The plain export stays the default for interop with other DAE tools.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use am_core::constants::MAX_WORD_BIAS;
+use am_core::serde_compat::{ImportOptions, count_export_episodes, export_json};
+use am_core::system::DAESystem;
+
+use crate::error::{Result, StoreError};
+use crate::json_bridge::{ImportMode, ImportSummary};
+use crate::store::Store;
+
+/// Value of an archive's `format` field.
+pub const ARCHIVE_FORMAT: &str = "am-archive";
+
+/// Layout version of the archive wrapper, separate from the version of
+/// the export inside it.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Metadata describing files on this machine rather than the brain: the
+/// database schema and the epoch files beside it. Never archived, and left
+/// alone on restore.
+const FILE_METADATA: &[&str] = &["schema_version", "epoch_files"];
+
+/// One exchange waiting in the conversation buffer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedExchange {
+    pub user: String,
+    pub assistant: String,
+    /// ISO-8601 UTC.
+    pub buffered_at: String,
+}
+
+/// A brain's state that a plain export leaves out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoreState {
+    #[serde(default)]
+    pub buffer: Vec<ArchivedExchange>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    pub frozen_words: Vec<String>,
+    #[serde(default)]
+    pub word_biases: BTreeMap<String, f64>,
+}
+
+/// Rows an archive import wrote from `store_state`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RestoredState {
+    pub buffer: usize,
+    pub metadata: usize,
+    pub frozen_words: usize,
+    pub word_biases: usize,
+}
+
+#[derive(Serialize)]
+struct ArchiveOut<'a> {
+    format: &'static str,
+    archive_version: u32,
+    store_state: &'a StoreState,
+    export: &'a RawValue,
+}
+
+#[derive(Deserialize)]
+struct ArchiveIn<'a> {
+    format: String,
+    archive_version: u32,
+    #[serde(default)]
+    store_state: StoreState,
+    #[serde(borrow)]
+    export: &'a RawValue,
+}
+
+/// Parse the wrapper, leaving the export unparsed for the streaming import.
+fn parse_archive(json: &str) -> Result<ArchiveIn<'_>> {
+    let archive: ArchiveIn<'_> = serde_json::from_str(json)
+        .map_err(|e| StoreError::InvalidData(format!("invalid archive: {e}")))?;
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(StoreError::InvalidData(format!(
+            "not a full archive: format is {:?}, expected {ARCHIVE_FORMAT:?}",
+            archive.format
+        )));
+    }
+    if archive.archive_version > ARCHIVE_VERSION {
+        return Err(StoreError::InvalidData(format!(
+            "archive version {} is newer than this build reads ({ARCHIVE_VERSION})",
+            archive.archive_version
+        )));
+    }
+    Ok(archive)
+}
+
+/// Count the subconscious episodes in an archive's export without building
+/// them, to size progress reporting.
+pub fn count_archive_episodes(json: &str) -> Result<usize> {
+    let archive = parse_archive(json)?;
+    count_export_episodes(archive.export.get().as_bytes())
+        .map_err(|e| StoreError::InvalidData(format!("invalid export in archive: {e}")))
+}
+
+impl Store {
+    /// This brain's buffer, metadata, frozen words, and word biases.
+    pub fn store_state(&self) -> Result<StoreState> {
+        let buffer = self
+            .list_buffer()?
+            .into_iter()
+            .map(|entry| ArchivedExchange {
+                user: entry.user_text,
+                assistant: entry.assistant_text,
+                buffered_at: entry.created_at,
+            })
+            .collect();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM metadata WHERE brain_id = ?1 ORDER BY key")?;
+        let metadata = stmt
+            .query_map([&self.brain], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter(|row: &rusqlite::Result<(String, String)>| {
+                row.as_ref()
+                    .map_or(true, |(key, _)| !FILE_METADATA.contains(&key.as_str()))
+            })
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(StoreState {
+            buffer,
+            metadata,
+            frozen_words: self.frozen_words()?,
+            word_biases: self.word_biases()?.into_iter().collect(),
+        })
+    }
+
+    /// Serialize `system` with this store's [`StoreState`] as a full
+    /// archive. `system` is usually [`Store::load_system`], possibly with
+    /// epoch episodes added.
+    pub fn export_archive_string(&self, system: &DAESystem) -> Result<String> {
+        let export = export_json(system)
+            .and_then(RawValue::from_string)
+            .map_err(|e| StoreError::InvalidData(format!("JSON export failed: {e}")))?;
+        let archive = ArchiveOut {
+            format: ARCHIVE_FORMAT,
+            archive_version: ARCHIVE_VERSION,
+            store_state: &self.store_state()?,
+            export: &export,
+        };
+        serde_json::to_string_pretty(&archive)
+            .map_err(|e| StoreError::InvalidData(format!("archive export failed: {e}")))
+    }
+
+    /// Import a full archive: its export as [`Store::import_json_reader_with`]
+    /// would, then its store state, in one transaction.
+    ///
+    /// Replace mode makes the buffer, metadata, frozen words, and word
+    /// biases those of the archive. Merge mode appends the archived buffer
+    /// and adds only the keys, words, and biases the store lacks.
+    pub fn import_archive_str(
+        &self,
+        json: &str,
+        mode: ImportMode,
+        options: ImportOptions,
+        progress: impl FnMut(usize),
+    ) -> Result<(ImportSummary, RestoredState)> {
+        let archive = parse_archive(json)?;
+        let tx = self.transaction()?;
+        let summary = self.import_on(
+            &tx,
+            archive.export.get().as_bytes(),
+            mode,
+            options,
+            progress,
+        )?;
+        let restored = self.restore_state_on(&tx, &archive.store_state, mode)?;
+        tx.commit()?;
+        let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
+        Ok((summary, restored))
+    }
+
+    fn restore_state_on(
+        &self,
+        tx: &Connection,
+        state: &StoreState,
+        mode: ImportMode,
+    ) -> Result<RestoredState> {
+        let insert = match mode {
+            ImportMode::Replace => {
+                tx.execute(
+                    "DELETE FROM conversation_buffer WHERE brain_id = ?1",
+                    [&self.brain],
+                )?;
+                // agent_name was just written from the export
+                tx.execute(
+                    "DELETE FROM metadata WHERE brain_id = ?1
+                     AND key NOT IN ('schema_version', 'epoch_files', 'agent_name')",
+                    [&self.brain],
+                )?;
+                tx.execute(
+                    "DELETE FROM drift_frozen_words WHERE brain_id = ?1",
+                    [&self.brain],
+                )?;
+                tx.execute("DELETE FROM word_biases WHERE brain_id = ?1", [&self.brain])?;
+                "INSERT OR REPLACE"
+            }
+            ImportMode::Merge => "INSERT OR IGNORE",
+        };
+
+        let mut restored = RestoredState::default();
+        for exchange in &state.buffer {
+            restored.buffer += tx.execute(
+                "INSERT INTO conversation_buffer (user_text, assistant_text, created_at, brain_id)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    exchange.user,
+                    exchange.assistant,
+                    exchange.buffered_at,
+                    self.brain
+                ],
+            )?;
+        }
+        for (key, value) in &state.metadata {
+            if FILE_METADATA.contains(&key.as_str()) {
+                continue;
+            }
+            restored.metadata += tx.execute(
+                &format!("{insert} INTO metadata (brain_id, key, value) VALUES (?1, ?2, ?3)"),
+                params![self.brain, key, value],
+            )?;
+        }
+        for word in &state.frozen_words {
+            restored.frozen_words += tx.execute(
+                "INSERT OR IGNORE INTO drift_frozen_words (brain_id, word) VALUES (?1, ?2)",
+                params![self.brain, word.to_lowercase()],
+            )?;
+        }
+        for (word, multiplier) in &state.word_biases {
+            restored.word_biases += tx.execute(
+                &format!(
+                    "{insert} INTO word_biases (brain_id, word, multiplier) VALUES (?1, ?2, ?3)"
+                ),
+                params![
+                    self.brain,
+                    word.to_lowercase(),
+                    multiplier.clamp(0.0, MAX_WORD_BIAS)
+                ],
+            )?;
+        }
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use am_core::episode::Episode;
+    use am_core::neighborhood::Neighborhood;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    fn populated_store() -> Store {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut sys = DAESystem::new("archivist");
+        let mut ep = Episode::new("notes");
+        let tokens: Vec<String> = ["tides", "follow", "moon"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &tokens,
+            None,
+            "tides follow moon",
+            &mut rng,
+        ));
+        sys.add_episode(ep);
+
+        let store = Store::open_in_memory().unwrap();
+        store.save_system(&sys).unwrap();
+        store
+            .append_buffer_at("what pulls tides", "the moon", "2026-03-01T09:00:00Z")
+            .unwrap();
+        store
+            .append_buffer_at("and the sun", "a little", "2026-03-01T09:05:00Z")
+            .unwrap();
+        store.set_metadata("min_candidate_score", "0.2").unwrap();
+        store.freeze_words(&["moon".to_string()]).unwrap();
+        store.set_word_bias("tides", 2.5).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_archive_round_trip_restores_store_state() {
+        let source = populated_store();
+        let archive = source
+            .export_archive_string(&source.load_system().unwrap())
+            .unwrap();
+        assert!(!archive.contains("schema_version"));
+        assert_eq!(count_archive_episodes(&archive).unwrap(), 1);
+
+        let target = Store::open_in_memory().unwrap();
+        target.set_metadata("min_activated_words", "3").unwrap();
+        target.append_buffer("stale", "exchange").unwrap();
+        let (summary, restored) = target
+            .import_archive_str(
+                &archive,
+                ImportMode::Replace,
+                ImportOptions::default(),
+                |_| {},
+            )
+            .unwrap();
+
+        assert_eq!(summary.episodes, 1);
+        assert_eq!(
+            restored,
+            RestoredState {
+                buffer: 2,
+                metadata: 2,
+                frozen_words: 1,
+                word_biases: 1,
+            }
+        );
+        assert_eq!(target.store_state().unwrap(), source.store_state().unwrap());
+        assert_eq!(target.get_metadata("min_activated_words").unwrap(), None);
+        assert!(target.get_metadata("schema_version").unwrap().is_some());
+        assert_eq!(
+            target.load_system().unwrap().n(),
+            source.load_system().unwrap().n()
+        );
+    }
+
+    #[test]
+    fn test_archive_merge_keeps_existing_state() {
+        let source = populated_store();
+        let archive = source
+            .export_archive_string(&source.load_system().unwrap())
+            .unwrap();
+
+        let target = Store::open_in_memory().unwrap();
+        target.set_word_bias("tides", 0.5).unwrap();
+        target.append_buffer("local", "exchange").unwrap();
+        target
+            .import_archive_str(
+                &archive,
+                ImportMode::Merge,
+                ImportOptions::default(),
+                |_| {},
+            )
+            .unwrap();
+
+        assert_eq!(target.buffer_count().unwrap(), 3);
+        assert_eq!(
+            target.word_biases().unwrap(),
+            vec![("tides".to_string(), 0.5)]
+        );
+        assert_eq!(target.frozen_words().unwrap(), vec!["moon".to_string()]);
+    }
+
+    #[test]
+    fn test_archive_rejects_plain_export_and_newer_layout() {
+        let store = populated_store();
+        let plain = store.export_json_string().unwrap();
+        let err = store
+            .import_archive_str(
+                &plain,
+                ImportMode::Replace,
+                ImportOptions::default(),
+                |_| {},
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid archive"), "{err}");
+
+        let archive = store
+            .export_archive_string(&store.load_system().unwrap())
+            .unwrap()
+            .replacen("\"archive_version\": 1", "\"archive_version\": 2", 1);
+        let err = count_archive_episodes(&archive).unwrap_err();
+        assert!(err.to_string().contains("newer"), "{err}");
+    }
+}
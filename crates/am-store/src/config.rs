@@ -1,11 +1,37 @@
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+use am_core::compose::BudgetConfig;
+use am_core::constants::{
+    DEFAULT_MAX_WORD_ACTIVATIONS, DEFAULT_PROJECT_DISCOUNT, DEFAULT_SALIENT_CHUNK_CHARS,
+    DEFAULT_SALIENT_DEDUP_THRESHOLD,
+};
 use serde::Deserialize;
 
+use crate::store::{
+    DEFAULT_BUSY_TIMEOUT_MS, DEFAULT_COMPRESS_TEXT_MIN_BYTES, DEFAULT_QUARANTINE_DAYS,
+    DEFAULT_RESURRECT_WARN, RetryPolicy, StoreOptions, Synchronous, UndoPolicy,
+};
+
 /// Default DB size limit for GC (50 MB).
 const DEFAULT_DB_SIZE_MB: u64 = 50;
 
+/// Default cap on characters kept from one transcript message during sync.
+const DEFAULT_SYNC_MAX_MESSAGE_CHARS: usize = 4000;
+
+/// Default share of code-fence/diff content above which sync skips a message.
+const DEFAULT_SYNC_MAX_CODE_RATIO: f64 = 0.8;
+
+/// Default number of audit log rows `am gc` keeps.
+pub const DEFAULT_AUDIT_KEEP: usize = 10_000;
+
+/// File name of the config file, in a project directory or the data
+/// directory.
+pub const CONFIG_FILE_NAME: &str = ".am.config.toml";
+
+/// Default number of buffered exchanges that become an episode.
+pub const DEFAULT_BUFFER_THRESHOLD: usize = 3;
+
 /// Partial config deserialized from TOML. All fields optional so that
 /// missing keys fall through to defaults.
 #[derive(Deserialize, Default)]
@@ -13,8 +39,30 @@ struct FileConfig {
     data_dir: Option<String>,
     gc_enabled: Option<bool>,
     db_size_mb: Option<u64>,
+    epoch_size_mb: Option<u64>,
+    gc_resurrect_warn: Option<u64>,
     sync_log_dir: Option<String>,
+    conscious_journal: Option<bool>,
+    actor: Option<String>,
+    dedup_neighborhoods: Option<bool>,
+    activation_spillover: Option<f64>,
+    max_word_activations: Option<usize>,
+    salient_chunk_chars: Option<usize>,
+    salient_dedup_threshold: Option<f64>,
+    compress_text_min_bytes: Option<usize>,
+    audit_keep: Option<usize>,
+    quarantine_days: Option<u32>,
     retention: Option<FileRetentionConfig>,
+    plans: Option<FilePlanConfig>,
+    sync: Option<FileSyncConfig>,
+    buffer: Option<FileBufferConfig>,
+    compose: Option<FileComposeConfig>,
+    mcp: Option<FileMcpConfig>,
+    http: Option<FileHttpConfig>,
+    retry: Option<FileRetryConfig>,
+    undo: Option<FileUndoConfig>,
+    sqlite: Option<FileSqliteConfig>,
+    project: Option<FileProjectConfig>,
 }
 
 /// Partial retention config from TOML.
@@ -26,6 +74,178 @@ struct FileRetentionConfig {
     recency_weight: Option<f64>,
 }
 
+/// Partial plan-detection config from TOML.
+#[derive(Deserialize, Default)]
+struct FilePlanConfig {
+    detect: Option<bool>,
+    verbs: Option<Vec<String>>,
+}
+
+/// Partial transcript-extraction config from TOML.
+#[derive(Deserialize, Default)]
+struct FileSyncConfig {
+    max_message_chars: Option<usize>,
+    max_code_ratio: Option<f64>,
+    roles: Option<Vec<String>>,
+    skip_roles: Option<Vec<String>>,
+}
+
+/// Partial conversation-buffer config from TOML.
+#[derive(Deserialize, Default)]
+struct FileBufferConfig {
+    auto_salient: Option<bool>,
+    threshold: Option<usize>,
+}
+
+/// Partial budgeted-recall config from TOML.
+#[derive(Deserialize, Default)]
+struct FileComposeConfig {
+    min_conscious: Option<usize>,
+    min_subconscious: Option<usize>,
+    min_novel: Option<usize>,
+    novel_budget_fraction: Option<f64>,
+}
+
+/// Partial busy-retry config from TOML.
+#[derive(Deserialize, Default)]
+struct FileRetryConfig {
+    max_attempts: Option<u32>,
+    max_wait_secs: Option<u64>,
+}
+
+/// Partial undo-stack config from TOML.
+#[derive(Deserialize, Default)]
+struct FileUndoConfig {
+    keep: Option<usize>,
+    max_mb: Option<u64>,
+}
+
+/// Partial SQLite connection config from TOML.
+#[derive(Deserialize, Default)]
+struct FileSqliteConfig {
+    busy_timeout_ms: Option<u64>,
+    synchronous: Option<String>,
+    wal: Option<bool>,
+}
+
+/// Partial project-affinity config from TOML.
+#[derive(Deserialize, Default)]
+struct FileProjectConfig {
+    name: Option<String>,
+    discount: Option<f64>,
+    tag_conscious: Option<bool>,
+}
+
+/// Partial MCP config from TOML.
+#[derive(Deserialize, Default)]
+struct FileMcpConfig {
+    allowed_paths: Option<Vec<String>>,
+    max_ingest_bytes: Option<usize>,
+}
+
+/// Partial HTTP config from TOML.
+#[derive(Deserialize, Default)]
+struct FileHttpConfig {
+    token: Option<String>,
+}
+
+/// Plan recall settings.
+#[derive(Debug, Clone, Default)]
+pub struct PlanConfig {
+    /// Tag numbered step lists in buffered and synced conversation as plans.
+    pub detect: bool,
+    /// Query words that boost plans; `None` keeps the built-in list.
+    pub verbs: Option<Vec<String>>,
+}
+
+/// Transcript extraction filters applied by `am sync`.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// Truncate longer messages with a marker; 0 disables truncation.
+    pub max_message_chars: usize,
+    /// Skip a message when more than this share of it is code fences or
+    /// diff hunks; 1.0 disables the check.
+    pub max_code_ratio: f64,
+    /// Roles to keep (`user`, `assistant`); empty keeps every role.
+    pub roles: Vec<String>,
+    /// Roles to drop, applied after `roles`.
+    pub skip_roles: Vec<String>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            max_message_chars: DEFAULT_SYNC_MAX_MESSAGE_CHARS,
+            max_code_ratio: DEFAULT_SYNC_MAX_CODE_RATIO,
+            roles: Vec::new(),
+            skip_roles: Vec::new(),
+        }
+    }
+}
+
+/// When buffered conversation becomes an episode in `am serve`, and
+/// what else happens then.
+#[derive(Debug, Clone)]
+pub struct BufferConfig {
+    /// Mark `DECISION:`/`PREFERENCE:` lines and `<salient>` tags in the
+    /// flushed exchanges as conscious memories.
+    pub auto_salient: bool,
+    /// Buffered exchanges that make an episode.
+    pub threshold: usize,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self {
+            auto_salient: false,
+            threshold: DEFAULT_BUFFER_THRESHOLD,
+        }
+    }
+}
+
+/// Restrictions on what `am serve` tools may touch.
+#[derive(Debug, Clone, Default)]
+pub struct McpConfig {
+    /// Directories MCP tools may read and write files in, on top of the
+    /// data directory and the project root.
+    pub allowed_paths: Vec<PathBuf>,
+    /// Reject `am_ingest` calls whose text or chunks add up to more than
+    /// this many bytes; `None` keeps only the per-call input limit.
+    pub max_ingest_bytes: Option<usize>,
+}
+
+/// Access to the read-only REST API `am serve` offers with `--http` or
+/// `--rest`.
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    /// Bearer token the REST API requires; `None` leaves it open.
+    pub token: Option<String>,
+}
+
+/// How conscious recall favours memories about the current project.
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+    /// Current project. `load` defaults it to the name of the git
+    /// repository the current directory is in; `None` turns affinity off.
+    pub name: Option<String>,
+    /// Score factor, within `(0, 1]`, for conscious memories tagged for a
+    /// different project. Untagged memories always keep full weight.
+    pub discount: f64,
+    /// Tag new conscious memories with the current project when they do
+    /// not name one themselves.
+    pub tag_conscious: bool,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            discount: DEFAULT_PROJECT_DISCOUNT,
+            tag_conscious: false,
+        }
+    }
+}
+
 /// Resolved retention policy with concrete values.
 #[derive(Debug, Clone)]
 pub struct RetentionPolicy {
@@ -56,8 +276,61 @@ pub struct Config {
     pub data_dir: PathBuf,
     pub gc_enabled: bool,
     pub db_size_mb: u64,
+    /// Move cold subconscious episodes into per-year epoch files at
+    /// startup once `brain.db` grows past this many MB. 0 turns it off.
+    pub epoch_size_mb: u64,
+    /// Warn when a full save would write back more than this many
+    /// occurrences GC evicted earlier in the same process.
+    pub gc_resurrect_warn: u64,
     pub sync_log_dir: Option<PathBuf>,
+    /// Mirror every salient addition to `conscious.journal` in `data_dir`.
+    pub conscious_journal: bool,
+    /// Model or agent recorded on new memories. When unset, `am serve`
+    /// uses the MCP client's name.
+    pub actor: Option<String>,
+    /// Store a chunk already held by another episode once, and record the
+    /// new episode as reusing it.
+    pub dedup_neighborhoods: bool,
+    /// Fraction of an activation credited to the other words of its
+    /// neighborhood, accumulated until it adds up to a whole activation.
+    /// 0.0 turns spillover off.
+    pub activation_spillover: f64,
+    /// Most subconscious occurrences one query word activates; a word in
+    /// more neighborhoods activates its hottest occurrences and a sample of
+    /// the rest. 0 activates them all.
+    pub max_word_activations: usize,
+    /// Salient text longer than this many characters is stored as grouped
+    /// chunks that recall scores separately. 0 stores it whole.
+    pub salient_chunk_chars: usize,
+    /// Token-set similarity (Jaccard, 0 to 1) at or above which a salient
+    /// mark reinforces the conscious memory it repeats instead of adding
+    /// another. 0 stores every mark.
+    pub salient_dedup_threshold: f64,
+    /// Deflate the stored text of subconscious chunks at least this many
+    /// bytes long (needs am-store's `compress-text` feature). 0 stores all
+    /// text plain.
+    pub compress_text_min_bytes: usize,
+    /// Newest audit log rows `am gc` keeps; older ones are pruned.
+    pub audit_keep: usize,
+    /// Days `am forget` keeps what it deletes in quarantine before
+    /// `am gc` purges it. 0 deletes outright.
+    pub quarantine_days: u32,
     pub retention: RetentionPolicy,
+    pub plans: PlanConfig,
+    pub sync: SyncConfig,
+    pub buffer: BufferConfig,
+    /// Per-category minimums and novel share for budgeted `am_query`
+    /// calls; `max_tokens` always comes from the call.
+    pub compose: BudgetConfig,
+    pub mcp: McpConfig,
+    pub http: HttpConfig,
+    /// How store writes retry when another process holds the database.
+    pub retry: RetryPolicy,
+    /// How many destructive operations `am undo` can take back.
+    pub undo: UndoPolicy,
+    /// Pragmas set on the `brain.db` connection.
+    pub sqlite: StoreOptions,
+    pub project: ProjectConfig,
 }
 
 impl Default for Config {
@@ -68,8 +341,30 @@ impl Default for Config {
             data_dir,
             gc_enabled: false,
             db_size_mb: DEFAULT_DB_SIZE_MB,
+            epoch_size_mb: 0,
+            gc_resurrect_warn: DEFAULT_RESURRECT_WARN,
             sync_log_dir: None,
+            conscious_journal: true,
+            actor: None,
+            dedup_neighborhoods: false,
+            activation_spillover: 0.0,
+            max_word_activations: DEFAULT_MAX_WORD_ACTIVATIONS,
+            salient_chunk_chars: DEFAULT_SALIENT_CHUNK_CHARS,
+            salient_dedup_threshold: DEFAULT_SALIENT_DEDUP_THRESHOLD,
+            compress_text_min_bytes: DEFAULT_COMPRESS_TEXT_MIN_BYTES,
+            audit_keep: DEFAULT_AUDIT_KEEP,
+            quarantine_days: DEFAULT_QUARANTINE_DAYS,
             retention: RetentionPolicy::default(),
+            plans: PlanConfig::default(),
+            sync: SyncConfig::default(),
+            buffer: BufferConfig::default(),
+            compose: BudgetConfig::default(),
+            mcp: McpConfig::default(),
+            http: HttpConfig::default(),
+            retry: RetryPolicy::default(),
+            undo: UndoPolicy::default(),
+            sqlite: StoreOptions::default(),
+            project: ProjectConfig::default(),
         }
     }
 }
@@ -80,6 +375,15 @@ impl Config {
         self.db_size_mb * 1024 * 1024
     }
 
+    /// Directories MCP file operations may use: the data directory, the
+    /// project root (the current directory), and `mcp.allowed_paths`.
+    pub fn mcp_allowed_paths(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.data_dir.clone()];
+        dirs.extend(env::current_dir());
+        dirs.extend(self.mcp.allowed_paths.iter().cloned());
+        dirs
+    }
+
     /// Validate semantic invariants on the resolved config.
     ///
     /// Parse errors are handled earlier (warn + fallback). This catches
@@ -102,22 +406,67 @@ impl Config {
                 self.db_size_mb
             )));
         }
+        if self.retry.max_attempts < 1 {
+            return Err(crate::error::StoreError::InvalidData(
+                "retry.max_attempts must be >= 1 (1 disables retrying)".into(),
+            ));
+        }
+        if !(self.project.discount > 0.0 && self.project.discount <= 1.0) {
+            return Err(crate::error::StoreError::InvalidData(format!(
+                "project.discount must be within (0, 1], got: {}",
+                self.project.discount
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.salient_dedup_threshold) {
+            return Err(crate::error::StoreError::InvalidData(format!(
+                "salient_dedup_threshold must be within [0, 1], got: {}",
+                self.salient_dedup_threshold
+            )));
+        }
+        if self.buffer.threshold < 1 {
+            return Err(crate::error::StoreError::InvalidData(
+                "buffer.threshold must be >= 1".into(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.compose.novel_budget_fraction) {
+            return Err(crate::error::StoreError::InvalidData(format!(
+                "compose.novel_budget_fraction must be within [0, 1], got: {}",
+                self.compose.novel_budget_fraction
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.sync.max_code_ratio) {
+            return Err(crate::error::StoreError::InvalidData(format!(
+                "sync.max_code_ratio must be within [0, 1], got: {}",
+                self.sync.max_code_ratio
+            )));
+        }
         Ok(())
     }
 }
 
 /// Load configuration with the following precedence (highest wins):
 ///
-/// 1. Environment variables (`AM_DATA_DIR`, `AM_GC_ENABLED`, `AM_DB_SIZE_MB`, `AM_SYNC_LOG_DIR`)
+/// 1. Environment variables (`AM_DATA_DIR`, `AM_GC_ENABLED`, `AM_DB_SIZE_MB`, `AM_SYNC_LOG_DIR`,
+///    `AM_ACTOR`, `AM_PROJECT`, `AM_HTTP_TOKEN`, `AM_MAX_INGEST_BYTES`,
+///    `AM_SQLITE_BUSY_TIMEOUT_MS`, `AM_SQLITE_SYNCHRONOUS`, `AM_SQLITE_WAL`)
 /// 2. Config file (first found wins):
 ///    a. `$CWD/.am.config.toml` (project-local)
 ///    b. `$AM_DATA_DIR/.am.config.toml` (if env var is set)
 ///    c. `~/.attention-matters/.am.config.toml` (global fallback)
-/// 3. Compiled defaults
+/// 3. Compiled defaults, plus the project name taken from the git
+///    repository around the current directory
 ///
 /// The config file's `data_dir` field controls where the database lives.
 /// `AM_DATA_DIR` overrides `data_dir` from the file.
 pub fn load() -> crate::error::Result<Config> {
+    load_recording(&mut Vec::new())
+}
+
+/// [`load`], noting `(key, variable)` for each key an environment
+/// variable set.
+fn load_recording(
+    from_env: &mut Vec<(&'static str, &'static str)>,
+) -> crate::error::Result<Config> {
     // Build runtime defaults. If home is unresolvable, use an empty data_dir
     // placeholder. File config or env vars may override it before validation.
     let mut cfg = match runtime_defaults() {
@@ -126,11 +475,37 @@ pub fn load() -> crate::error::Result<Config> {
             data_dir: PathBuf::new(),
             gc_enabled: false,
             db_size_mb: DEFAULT_DB_SIZE_MB,
+            epoch_size_mb: 0,
+            gc_resurrect_warn: DEFAULT_RESURRECT_WARN,
             sync_log_dir: None,
+            conscious_journal: true,
+            actor: None,
+            dedup_neighborhoods: false,
+            activation_spillover: 0.0,
+            max_word_activations: DEFAULT_MAX_WORD_ACTIVATIONS,
+            salient_chunk_chars: DEFAULT_SALIENT_CHUNK_CHARS,
+            salient_dedup_threshold: DEFAULT_SALIENT_DEDUP_THRESHOLD,
+            compress_text_min_bytes: DEFAULT_COMPRESS_TEXT_MIN_BYTES,
+            audit_keep: DEFAULT_AUDIT_KEEP,
+            quarantine_days: DEFAULT_QUARANTINE_DAYS,
             retention: RetentionPolicy::default(),
+            plans: PlanConfig::default(),
+            sync: SyncConfig::default(),
+            buffer: BufferConfig::default(),
+            compose: BudgetConfig::default(),
+            mcp: McpConfig::default(),
+            http: HttpConfig::default(),
+            retry: RetryPolicy::default(),
+            undo: UndoPolicy::default(),
+            sqlite: StoreOptions::default(),
+            project: ProjectConfig::default(),
         },
     };
 
+    cfg.project.name = env::current_dir()
+        .ok()
+        .and_then(|cwd| detect_project_name(&cwd));
+
     // Find config file: CWD first, then global fallback
     let config_path = find_config_file();
     if let Some(path) = &config_path {
@@ -140,10 +515,14 @@ pub fn load() -> crate::error::Result<Config> {
     // Env vars override everything
     if let Ok(dir) = env::var("AM_DATA_DIR") {
         cfg.data_dir = expand_tilde(&dir)?;
+        from_env.push(("data_dir", "AM_DATA_DIR"));
     }
     if let Ok(val) = env::var("AM_GC_ENABLED") {
         match val.parse::<bool>() {
-            Ok(b) => cfg.gc_enabled = b,
+            Ok(b) => {
+                cfg.gc_enabled = b;
+                from_env.push(("gc_enabled", "AM_GC_ENABLED"));
+            }
             Err(_) => tracing::warn!(
                 "AM_GC_ENABLED={val:?}: expected bool, falling back to {}",
                 cfg.gc_enabled
@@ -152,15 +531,85 @@ pub fn load() -> crate::error::Result<Config> {
     }
     if let Ok(val) = env::var("AM_DB_SIZE_MB") {
         match val.parse::<u64>() {
-            Ok(mb) => cfg.db_size_mb = mb,
+            Ok(mb) => {
+                cfg.db_size_mb = mb;
+                from_env.push(("db_size_mb", "AM_DB_SIZE_MB"));
+            }
             Err(_) => tracing::warn!(
                 "AM_DB_SIZE_MB={val:?}: expected integer, falling back to {}",
                 cfg.db_size_mb
             ),
         }
     }
+    if let Ok(val) = env::var("AM_MAX_INGEST_BYTES") {
+        match val.parse::<usize>() {
+            Ok(n) => {
+                cfg.mcp.max_ingest_bytes = Some(n).filter(|&n| n > 0);
+                from_env.push(("mcp.max_ingest_bytes", "AM_MAX_INGEST_BYTES"));
+            }
+            Err(_) => tracing::warn!(
+                "AM_MAX_INGEST_BYTES={val:?}: expected integer, falling back to {:?}",
+                cfg.mcp.max_ingest_bytes
+            ),
+        }
+    }
+    if let Ok(val) = env::var("AM_SQLITE_BUSY_TIMEOUT_MS") {
+        match val.parse::<u64>() {
+            Ok(ms) => {
+                cfg.sqlite.busy_timeout_ms = ms;
+                from_env.push(("sqlite.busy_timeout_ms", "AM_SQLITE_BUSY_TIMEOUT_MS"));
+            }
+            Err(_) => tracing::warn!(
+                "AM_SQLITE_BUSY_TIMEOUT_MS={val:?}: expected integer, falling back to {}",
+                cfg.sqlite.busy_timeout_ms
+            ),
+        }
+    }
+    if let Ok(val) = env::var("AM_SQLITE_SYNCHRONOUS") {
+        match val.parse::<Synchronous>() {
+            Ok(mode) => {
+                cfg.sqlite.synchronous = mode;
+                from_env.push(("sqlite.synchronous", "AM_SQLITE_SYNCHRONOUS"));
+            }
+            Err(e) => tracing::warn!(
+                "AM_SQLITE_SYNCHRONOUS: {e}, falling back to {}",
+                cfg.sqlite.synchronous
+            ),
+        }
+    }
+    if let Ok(val) = env::var("AM_SQLITE_WAL") {
+        match val.parse::<bool>() {
+            Ok(b) => {
+                cfg.sqlite.wal = b;
+                from_env.push(("sqlite.wal", "AM_SQLITE_WAL"));
+            }
+            Err(_) => tracing::warn!(
+                "AM_SQLITE_WAL={val:?}: expected bool, falling back to {}",
+                cfg.sqlite.wal
+            ),
+        }
+    }
     if let Ok(val) = env::var("AM_SYNC_LOG_DIR") {
         cfg.sync_log_dir = Some(expand_tilde(&val)?);
+        from_env.push(("sync_log_dir", "AM_SYNC_LOG_DIR"));
+    }
+    if let Ok(val) = env::var("AM_ACTOR")
+        && !val.is_empty()
+    {
+        cfg.actor = Some(val);
+        from_env.push(("actor", "AM_ACTOR"));
+    }
+    if let Ok(val) = env::var("AM_PROJECT")
+        && !val.is_empty()
+    {
+        cfg.project.name = Some(val);
+        from_env.push(("project.name", "AM_PROJECT"));
+    }
+    if let Ok(val) = env::var("AM_HTTP_TOKEN")
+        && !val.is_empty()
+    {
+        cfg.http.token = Some(val);
+        from_env.push(("http.token", "AM_HTTP_TOKEN"));
     }
 
     cfg.validate()?;
@@ -176,8 +625,30 @@ fn runtime_defaults() -> crate::error::Result<Config> {
         data_dir: crate::project::default_base_dir()?,
         gc_enabled: false,
         db_size_mb: DEFAULT_DB_SIZE_MB,
+        epoch_size_mb: 0,
+        gc_resurrect_warn: DEFAULT_RESURRECT_WARN,
         sync_log_dir: None,
+        conscious_journal: true,
+        actor: None,
+        dedup_neighborhoods: false,
+        activation_spillover: 0.0,
+        max_word_activations: DEFAULT_MAX_WORD_ACTIVATIONS,
+        salient_chunk_chars: DEFAULT_SALIENT_CHUNK_CHARS,
+        salient_dedup_threshold: DEFAULT_SALIENT_DEDUP_THRESHOLD,
+        compress_text_min_bytes: DEFAULT_COMPRESS_TEXT_MIN_BYTES,
+        audit_keep: DEFAULT_AUDIT_KEEP,
+        quarantine_days: DEFAULT_QUARANTINE_DAYS,
         retention: RetentionPolicy::default(),
+        plans: PlanConfig::default(),
+        sync: SyncConfig::default(),
+        buffer: BufferConfig::default(),
+        compose: BudgetConfig::default(),
+        mcp: McpConfig::default(),
+        http: HttpConfig::default(),
+        retry: RetryPolicy::default(),
+        undo: UndoPolicy::default(),
+        sqlite: StoreOptions::default(),
+        project: ProjectConfig::default(),
     })
 }
 
@@ -186,11 +657,9 @@ fn runtime_defaults() -> crate::error::Result<Config> {
 ///   2. $AM_DATA_DIR/.am.config.toml (if set)
 ///   3. ~/.attention-matters/.am.config.toml
 fn find_config_file() -> Option<PathBuf> {
-    const CONFIG_NAME: &str = ".am.config.toml";
-
     // Check CWD
     if let Ok(cwd) = env::current_dir() {
-        let local = cwd.join(CONFIG_NAME);
+        let local = cwd.join(CONFIG_FILE_NAME);
         if local.exists() {
             return Some(local);
         }
@@ -200,7 +669,7 @@ fn find_config_file() -> Option<PathBuf> {
     if let Ok(dir) = env::var("AM_DATA_DIR")
         && let Ok(expanded) = expand_tilde(&dir)
     {
-        let project = expanded.join(CONFIG_NAME);
+        let project = expanded.join(CONFIG_FILE_NAME);
         if project.exists() {
             return Some(project);
         }
@@ -208,7 +677,7 @@ fn find_config_file() -> Option<PathBuf> {
 
     // Fall back to global (skip if home is unresolvable)
     if let Ok(base) = crate::project::default_base_dir() {
-        let global = base.join(CONFIG_NAME);
+        let global = base.join(CONFIG_FILE_NAME);
         if global.exists() {
             return Some(global);
         }
@@ -218,48 +687,525 @@ fn find_config_file() -> Option<PathBuf> {
 }
 
 fn apply_file_config(cfg: &mut Config, path: &Path) -> crate::error::Result<()> {
-    if let Some(file_cfg) = read_config_file(path) {
-        if let Some(dir) = file_cfg.data_dir {
-            cfg.data_dir = expand_tilde(&dir)?;
+    match read_config_file(path) {
+        Some(file_cfg) => merge_file_config(cfg, file_cfg),
+        None => Ok(()),
+    }
+}
+
+fn merge_file_config(cfg: &mut Config, file_cfg: FileConfig) -> crate::error::Result<()> {
+    if let Some(dir) = file_cfg.data_dir {
+        cfg.data_dir = expand_tilde(&dir)?;
+    }
+    if let Some(gc) = file_cfg.gc_enabled {
+        cfg.gc_enabled = gc;
+    }
+    if let Some(size) = file_cfg.db_size_mb {
+        cfg.db_size_mb = size;
+    }
+    if let Some(size) = file_cfg.epoch_size_mb {
+        cfg.epoch_size_mb = size;
+    }
+    if let Some(n) = file_cfg.gc_resurrect_warn {
+        cfg.gc_resurrect_warn = n;
+    }
+    if let Some(dir) = file_cfg.sync_log_dir {
+        cfg.sync_log_dir = Some(expand_tilde(&dir)?);
+    }
+    if let Some(journal) = file_cfg.conscious_journal {
+        cfg.conscious_journal = journal;
+    }
+    if let Some(actor) = file_cfg.actor {
+        cfg.actor = Some(actor);
+    }
+    if let Some(dedup) = file_cfg.dedup_neighborhoods {
+        cfg.dedup_neighborhoods = dedup;
+    }
+    if let Some(credit) = file_cfg.activation_spillover {
+        cfg.activation_spillover = credit;
+    }
+    if let Some(cap) = file_cfg.max_word_activations {
+        cfg.max_word_activations = cap;
+    }
+    if let Some(chars) = file_cfg.salient_chunk_chars {
+        cfg.salient_chunk_chars = chars;
+    }
+    if let Some(threshold) = file_cfg.salient_dedup_threshold {
+        cfg.salient_dedup_threshold = threshold;
+    }
+    if let Some(bytes) = file_cfg.compress_text_min_bytes {
+        cfg.compress_text_min_bytes = bytes;
+    }
+    if let Some(keep) = file_cfg.audit_keep {
+        cfg.audit_keep = keep;
+    }
+    if let Some(days) = file_cfg.quarantine_days {
+        cfg.quarantine_days = days;
+    }
+    if let Some(ret) = file_cfg.retention {
+        if let Some(v) = ret.grace_epochs {
+            cfg.retention.grace_epochs = v;
         }
-        if let Some(gc) = file_cfg.gc_enabled {
-            cfg.gc_enabled = gc;
+        if let Some(v) = ret.retention_days {
+            cfg.retention.retention_days = v;
         }
-        if let Some(size) = file_cfg.db_size_mb {
-            cfg.db_size_mb = size;
+        if let Some(v) = ret.min_neighborhoods {
+            cfg.retention.min_neighborhoods = v;
         }
-        if let Some(dir) = file_cfg.sync_log_dir {
-            cfg.sync_log_dir = Some(expand_tilde(&dir)?);
+        if let Some(v) = ret.recency_weight {
+            cfg.retention.recency_weight = v;
         }
-        if let Some(ret) = file_cfg.retention {
-            if let Some(v) = ret.grace_epochs {
-                cfg.retention.grace_epochs = v;
-            }
-            if let Some(v) = ret.retention_days {
-                cfg.retention.retention_days = v;
-            }
-            if let Some(v) = ret.min_neighborhoods {
-                cfg.retention.min_neighborhoods = v;
-            }
-            if let Some(v) = ret.recency_weight {
-                cfg.retention.recency_weight = v;
-            }
+    }
+    if let Some(plans) = file_cfg.plans {
+        if let Some(v) = plans.detect {
+            cfg.plans.detect = v;
+        }
+        if let Some(v) = plans.verbs {
+            cfg.plans.verbs = Some(v);
+        }
+    }
+    if let Some(buffer) = file_cfg.buffer {
+        if let Some(v) = buffer.auto_salient {
+            cfg.buffer.auto_salient = v;
+        }
+        if let Some(v) = buffer.threshold {
+            cfg.buffer.threshold = v;
+        }
+    }
+    if let Some(compose) = file_cfg.compose {
+        if let Some(v) = compose.min_conscious {
+            cfg.compose.min_conscious = v;
+        }
+        if let Some(v) = compose.min_subconscious {
+            cfg.compose.min_subconscious = v;
+        }
+        if let Some(v) = compose.min_novel {
+            cfg.compose.min_novel = v;
+        }
+        if let Some(v) = compose.novel_budget_fraction {
+            cfg.compose.novel_budget_fraction = v;
+        }
+    }
+    if let Some(mcp) = file_cfg.mcp {
+        if let Some(dirs) = mcp.allowed_paths {
+            cfg.mcp.allowed_paths = dirs
+                .iter()
+                .map(|d| expand_tilde(d))
+                .collect::<crate::error::Result<_>>()?;
+        }
+        if let Some(v) = mcp.max_ingest_bytes {
+            cfg.mcp.max_ingest_bytes = Some(v).filter(|&n| n > 0);
+        }
+    }
+    if let Some(http) = file_cfg.http
+        && let Some(token) = http.token
+    {
+        cfg.http.token = Some(token).filter(|t| !t.is_empty());
+    }
+    if let Some(retry) = file_cfg.retry {
+        if let Some(v) = retry.max_attempts {
+            cfg.retry.max_attempts = v;
+        }
+        if let Some(v) = retry.max_wait_secs {
+            cfg.retry.max_wait = std::time::Duration::from_secs(v);
+        }
+    }
+    if let Some(undo) = file_cfg.undo {
+        if let Some(v) = undo.keep {
+            cfg.undo.keep = v;
+        }
+        if let Some(v) = undo.max_mb {
+            cfg.undo.max_bytes = v * 1024 * 1024;
+        }
+    }
+    if let Some(sqlite) = file_cfg.sqlite {
+        if let Some(v) = sqlite.busy_timeout_ms {
+            cfg.sqlite.busy_timeout_ms = v;
+        }
+        if let Some(v) = sqlite.synchronous {
+            cfg.sqlite.synchronous = v.parse()?;
+        }
+        if let Some(v) = sqlite.wal {
+            cfg.sqlite.wal = v;
+        }
+    }
+    if let Some(project) = file_cfg.project {
+        if let Some(v) = project.name {
+            cfg.project.name = Some(v).filter(|name| !name.is_empty());
+        }
+        if let Some(v) = project.discount {
+            cfg.project.discount = v;
+        }
+        if let Some(v) = project.tag_conscious {
+            cfg.project.tag_conscious = v;
+        }
+    }
+    if let Some(sync) = file_cfg.sync {
+        if let Some(v) = sync.max_message_chars {
+            cfg.sync.max_message_chars = v;
+        }
+        if let Some(v) = sync.max_code_ratio {
+            cfg.sync.max_code_ratio = v;
+        }
+        if let Some(v) = sync.roles {
+            cfg.sync.roles = v;
+        }
+        if let Some(v) = sync.skip_roles {
+            cfg.sync.skip_roles = v;
         }
     }
     Ok(())
 }
 
+/// Name of the git repository `dir` is in: the directory holding `.git`,
+/// searching upward. `None` outside a repository.
+fn detect_project_name(dir: &Path) -> Option<String> {
+    dir.ancestors()
+        .find(|d| d.join(".git").exists())
+        .and_then(|root| root.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
 fn read_config_file(path: &Path) -> Option<FileConfig> {
     let content = fs::read_to_string(path).ok()?;
-    match toml::from_str(&content) {
+    match parse_config(path, &content) {
         Ok(cfg) => Some(cfg),
         Err(e) => {
-            tracing::warn!("failed to parse {}: {e}", path.display());
+            tracing::warn!("{e}; ignoring the file");
             None
         }
     }
 }
 
+/// Parse config file text. The error names the file, the line, and the
+/// key it is about: `path:LINE: `section.key`: message`.
+fn parse_config(path: &Path, content: &str) -> Result<FileConfig, String> {
+    toml::from_str(content).map_err(|e| describe_parse_error(path, content, &e))
+}
+
+fn describe_parse_error(path: &Path, content: &str, err: &toml::de::Error) -> String {
+    let message = err.message().trim().replace('\n', ", ");
+    let Some(span) = err.span() else {
+        return format!("{}: {message}", path.display());
+    };
+    let before = &content[..span.start.min(content.len())];
+    let line_no = before.matches('\n').count() + 1;
+    let line = content.lines().nth(line_no - 1).unwrap_or_default();
+    let section = before
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| l.starts_with('['))
+        .map(|l| l.trim_matches(['[', ']']).trim());
+    let key = line
+        .split_once('=')
+        .map(|(k, _)| k.trim().trim_matches('"'))
+        .filter(|k| !k.is_empty() && !k.starts_with('['));
+    match (section, key) {
+        (Some(section), Some(key)) => {
+            format!("{}:{line_no}: `{section}.{key}`: {message}", path.display())
+        }
+        (None, Some(key)) => format!("{}:{line_no}: `{key}`: {message}", path.display()),
+        (_, None) => format!("{}:{line_no}: {message}", path.display()),
+    }
+}
+
+/// Where an effective config value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Compiled default.
+    Default,
+    /// Worked out from the surroundings: the git repository name.
+    Detected,
+    /// Set in this config file.
+    File(PathBuf),
+    /// Set by this environment variable.
+    Env(&'static str),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Detected => f.write_str("detected"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Env(var) => write!(f, "env {var}"),
+        }
+    }
+}
+
+/// One config key with its effective value.
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    /// Dotted key as written in the file, e.g. `retention.grace_epochs`.
+    pub key: &'static str,
+    /// The value as a TOML literal, `(unset)` when the key has none, or
+    /// `(set)` for the HTTP token.
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+/// The effective config, key by key, as [`load`] resolves it.
+#[derive(Debug, Clone)]
+pub struct ConfigReport {
+    /// Config file in effect, if one was found.
+    pub path: Option<PathBuf>,
+    /// Why some or all of the file was not used: a parse error (the whole
+    /// file is ignored) or unknown keys (those keys are ignored).
+    pub problems: Vec<String>,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// Resolve the config like [`load`] and report where each value came
+/// from.
+pub fn explain() -> crate::error::Result<ConfigReport> {
+    let mut from_env = Vec::new();
+    let cfg = load_recording(&mut from_env)?;
+    let path = find_config_file();
+    let mut problems = Vec::new();
+    let mut table = toml::Table::new();
+    if let Some(path) = &path {
+        match fs::read_to_string(path) {
+            Ok(content) => match parse_config(path, &content) {
+                Ok(_) => {
+                    table = content.parse().unwrap_or_default();
+                    problems.extend(
+                        unknown_keys(&table)
+                            .into_iter()
+                            .map(|key| format!("{}: unknown key `{key}` ignored", path.display())),
+                    );
+                }
+                Err(e) => problems.push(format!("{e}; the file is ignored")),
+            },
+            Err(e) => problems.push(format!("{}: {e}", path.display())),
+        }
+    }
+
+    let entries = flatten(&cfg)
+        .into_iter()
+        .map(|(key, value)| {
+            let source = if let Some(&(_, var)) = from_env.iter().find(|(k, _)| *k == key) {
+                ConfigSource::Env(var)
+            } else if let (Some(path), true) = (&path, table_has(&table, key)) {
+                ConfigSource::File(path.clone())
+            } else if key == "project.name" && cfg.project.name.is_some() {
+                ConfigSource::Detected
+            } else {
+                ConfigSource::Default
+            };
+            let value = match (key, value) {
+                ("http.token", Some(_)) => "(set)".to_string(),
+                (_, Some(v)) => v.to_string(),
+                (_, None) => "(unset)".to_string(),
+            };
+            ConfigEntry { key, value, source }
+        })
+        .collect();
+    Ok(ConfigReport {
+        path,
+        problems,
+        entries,
+    })
+}
+
+/// Every file key with its value in `cfg`, in file order.
+fn flatten(cfg: &Config) -> Vec<(&'static str, Option<toml::Value>)> {
+    fn int<T: TryInto<i64>>(n: T) -> Option<toml::Value> {
+        Some(toml::Value::Integer(n.try_into().unwrap_or(i64::MAX)))
+    }
+    fn path(p: &Path) -> Option<toml::Value> {
+        Some(toml::Value::String(p.display().to_string()))
+    }
+    fn strings(items: &[String]) -> Option<toml::Value> {
+        Some(toml::Value::Array(
+            items.iter().cloned().map(toml::Value::String).collect(),
+        ))
+    }
+    let b = |v: bool| Some(toml::Value::Boolean(v));
+    let f = |v: f64| Some(toml::Value::Float(v));
+    let s = |v: &Option<String>| v.clone().map(toml::Value::String);
+
+    vec![
+        ("data_dir", path(&cfg.data_dir)),
+        ("gc_enabled", b(cfg.gc_enabled)),
+        ("db_size_mb", int(cfg.db_size_mb)),
+        ("epoch_size_mb", int(cfg.epoch_size_mb)),
+        ("gc_resurrect_warn", int(cfg.gc_resurrect_warn)),
+        ("sync_log_dir", cfg.sync_log_dir.as_deref().and_then(path)),
+        ("conscious_journal", b(cfg.conscious_journal)),
+        ("actor", s(&cfg.actor)),
+        ("dedup_neighborhoods", b(cfg.dedup_neighborhoods)),
+        ("activation_spillover", f(cfg.activation_spillover)),
+        ("max_word_activations", int(cfg.max_word_activations)),
+        ("salient_chunk_chars", int(cfg.salient_chunk_chars)),
+        ("salient_dedup_threshold", f(cfg.salient_dedup_threshold)),
+        ("compress_text_min_bytes", int(cfg.compress_text_min_bytes)),
+        ("audit_keep", int(cfg.audit_keep)),
+        ("quarantine_days", int(cfg.quarantine_days)),
+        ("retention.grace_epochs", int(cfg.retention.grace_epochs)),
+        (
+            "retention.retention_days",
+            int(cfg.retention.retention_days),
+        ),
+        (
+            "retention.min_neighborhoods",
+            int(cfg.retention.min_neighborhoods),
+        ),
+        ("retention.recency_weight", f(cfg.retention.recency_weight)),
+        ("plans.detect", b(cfg.plans.detect)),
+        ("plans.verbs", cfg.plans.verbs.as_deref().and_then(strings)),
+        ("sync.max_message_chars", int(cfg.sync.max_message_chars)),
+        ("sync.max_code_ratio", f(cfg.sync.max_code_ratio)),
+        ("sync.roles", strings(&cfg.sync.roles)),
+        ("sync.skip_roles", strings(&cfg.sync.skip_roles)),
+        ("buffer.auto_salient", b(cfg.buffer.auto_salient)),
+        ("buffer.threshold", int(cfg.buffer.threshold)),
+        ("compose.min_conscious", int(cfg.compose.min_conscious)),
+        (
+            "compose.min_subconscious",
+            int(cfg.compose.min_subconscious),
+        ),
+        ("compose.min_novel", int(cfg.compose.min_novel)),
+        (
+            "compose.novel_budget_fraction",
+            f(cfg.compose.novel_budget_fraction),
+        ),
+        ("retry.max_attempts", int(cfg.retry.max_attempts)),
+        ("retry.max_wait_secs", int(cfg.retry.max_wait.as_secs())),
+        ("undo.keep", int(cfg.undo.keep)),
+        ("undo.max_mb", int(cfg.undo.max_bytes / (1024 * 1024))),
+        ("sqlite.busy_timeout_ms", int(cfg.sqlite.busy_timeout_ms)),
+        (
+            "sqlite.synchronous",
+            Some(toml::Value::String(cfg.sqlite.synchronous.to_string())),
+        ),
+        ("sqlite.wal", b(cfg.sqlite.wal)),
+        ("project.name", s(&cfg.project.name)),
+        ("project.discount", f(cfg.project.discount)),
+        ("project.tag_conscious", b(cfg.project.tag_conscious)),
+        (
+            "mcp.allowed_paths",
+            Some(toml::Value::Array(
+                cfg.mcp
+                    .allowed_paths
+                    .iter()
+                    .filter_map(|p| path(p))
+                    .collect(),
+            )),
+        ),
+        (
+            "mcp.max_ingest_bytes",
+            cfg.mcp.max_ingest_bytes.and_then(int),
+        ),
+        ("http.token", s(&cfg.http.token)),
+    ]
+}
+
+fn is_known_key(key: &str) -> bool {
+    flatten(&Config::default()).iter().any(|(k, _)| *k == key)
+}
+
+fn table_has(table: &toml::Table, key: &str) -> bool {
+    match key.split_once('.') {
+        Some((section, field)) => table
+            .get(section)
+            .and_then(toml::Value::as_table)
+            .is_some_and(|t| t.contains_key(field)),
+        None => table.contains_key(key),
+    }
+}
+
+/// Dotted keys in `table` that no config field reads.
+fn unknown_keys(table: &toml::Table) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        match value.as_table() {
+            Some(section) if !is_known_key(key) => {
+                for field in section.keys() {
+                    let dotted = format!("{key}.{field}");
+                    if !is_known_key(&dotted) {
+                        unknown.push(dotted);
+                    }
+                }
+            }
+            _ if !is_known_key(key) => unknown.push(key.clone()),
+            _ => {}
+        }
+    }
+    unknown
+}
+
+/// Directory holding the global config file: `AM_DATA_DIR` if set,
+/// otherwise `~/.attention-matters`.
+pub fn global_config_dir() -> crate::error::Result<PathBuf> {
+    match env::var("AM_DATA_DIR") {
+        Ok(dir) => expand_tilde(&dir),
+        Err(_) => crate::project::default_base_dir(),
+    }
+}
+
+/// Config file [`load`] reads, if there is one.
+pub fn config_file_path() -> Option<PathBuf> {
+    find_config_file()
+}
+
+/// Set `key` to `value` in the config file at `path`, creating the file
+/// if needed and keeping its comments and layout. `value` is read as a
+/// TOML value (`true`, `25`, `0.3`, `["a", "b"]`) and as a string when it
+/// is not one.
+///
+/// Nothing is written if the key is unknown or the file would no longer
+/// load.
+pub fn set_value(path: &Path, key: &str, value: &str) -> crate::error::Result<()> {
+    if !is_known_key(key) {
+        return Err(crate::error::StoreError::InvalidData(format!(
+            "unknown config key `{key}` (`am config show` lists them)"
+        )));
+    }
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .map_err(|e| crate::error::StoreError::InvalidData(format!("{}: {e}", path.display())))?;
+
+    let parsed = value
+        .parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| value.into());
+    match key.split_once('.') {
+        Some((section, field)) => {
+            let table = doc
+                .entry(section)
+                .or_insert_with(toml_edit::table)
+                .as_table_like_mut()
+                .ok_or_else(|| {
+                    crate::error::StoreError::InvalidData(format!(
+                        "{}: `{section}` is not a table",
+                        path.display()
+                    ))
+                })?;
+            table.insert(field, toml_edit::value(parsed));
+        }
+        None => {
+            doc.insert(key, toml_edit::value(parsed));
+        }
+    }
+
+    let updated = doc.to_string();
+    let file_cfg = parse_config(path, &updated).map_err(crate::error::StoreError::InvalidData)?;
+    let mut cfg = Config::default();
+    merge_file_config(&mut cfg, file_cfg)?;
+    cfg.validate()?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
 /// Generate a fully commented default config file.
 ///
 /// Uses literal documented default paths rather than resolving the live
@@ -267,6 +1213,7 @@ fn read_config_file(path: &Path) -> Option<FileConfig> {
 /// whether $HOME is set.
 pub fn generate_default_toml() -> String {
     let ret = RetentionPolicy::default();
+    let budget = BudgetConfig::default();
 
     format!(
         r#"# attention-matters configuration
@@ -276,7 +1223,9 @@ pub fn generate_default_toml() -> String {
 #   2. ~/.attention-matters/.am.config.toml  (global fallback)
 #
 # Environment variables override all file settings:
-#   AM_DATA_DIR, AM_GC_ENABLED, AM_DB_SIZE_MB, AM_SYNC_LOG_DIR
+#   AM_DATA_DIR, AM_GC_ENABLED, AM_DB_SIZE_MB, AM_SYNC_LOG_DIR, AM_ACTOR,
+#   AM_PROJECT, AM_HTTP_TOKEN, AM_SQLITE_BUSY_TIMEOUT_MS, AM_SQLITE_SYNCHRONOUS,
+#   AM_SQLITE_WAL
 
 # Directory where the database and state files are stored.
 # This is how you point a project at a specific brain.
@@ -291,10 +1240,70 @@ pub fn generate_default_toml() -> String {
 # Override with AM_DB_SIZE_MB env var.
 # db_size_mb = {DEFAULT_DB_SIZE_MB}
 
+# Once brain.db grows past this many MB, move cold subconscious episodes
+# into per-year files (brain-2024.db, ...) next to it at startup. Queries
+# search them with --include-epochs, or when nothing current matches.
+# 0 disables epochs.
+# epoch_size_mb = 0
+
+# Warn when saving would write back more than this many occurrences that
+# GC evicted earlier in the same process (a stale in-memory copy).
+# gc_resurrect_warn = {DEFAULT_RESURRECT_WARN}
+
 # Directory to write sync logs into. Disabled when unset.
 # Override with AM_SYNC_LOG_DIR env var.
 # sync_log_dir = "~/.attention-matters/sync-logs"
 
+# Append every salient memory to conscious.journal next to brain.db, so
+# `am recover-conscious` can restore them if the database is lost.
+# conscious_journal = true
+
+# Model or agent recorded as the creator of new memories. When unset,
+# `am serve` uses the name the MCP client sends at initialization.
+# Override with AM_ACTOR env var.
+# actor = "claude-code"
+
+# Store a chunk of text that another episode already holds (say, a doc
+# quoted in a synced session) once. The new episode records the stored
+# neighborhood as reused instead of adding a duplicate.
+# dedup_neighborhoods = false
+
+# When a word is recalled, credit the other words of its chunk with this
+# fraction of an activation (0.0 to 1.0), so the context around it is not
+# garbage collected while the word itself stays warm. 0.0 turns it off.
+# activation_spillover = 0.0
+
+# A query word found in more subconscious neighborhoods than this activates
+# only its most activated occurrences plus a sample of the rest, keeping
+# drift and scoring fast for words like "error". 0 activates them all.
+# max_word_activations = {DEFAULT_MAX_WORD_ACTIVATIONS}
+
+# A salient memory longer than this many characters is stored as chunks
+# that recall scores separately, offering the whole text only when it fits
+# the context budget. 0 stores every salient memory whole.
+# salient_chunk_chars = {DEFAULT_SALIENT_CHUNK_CHARS}
+
+# A salient mark whose words overlap a live conscious memory of the same
+# type and project at least this much (shared words over all words, 0 to
+# 1) reinforces that memory instead of storing a near-copy. `am_salient`
+# takes `force: true` to store it anyway. 0 stores every mark.
+# salient_dedup_threshold = {DEFAULT_SALIENT_DEDUP_THRESHOLD}
+
+# Store the text of subconscious chunks at least this many bytes long
+# deflated, which roughly halves what text costs on disk. Reads inflate it
+# transparently. Rows saved before are compressed by
+# `am migrate compress-text --apply`. 0 stores all text plain.
+# compress_text_min_bytes = {DEFAULT_COMPRESS_TEXT_MIN_BYTES}
+
+# Newest rows of the audit log (`am log`) that `am gc` keeps; older rows
+# are pruned. `am gc --audit-keep N` overrides it for one run.
+# audit_keep = {DEFAULT_AUDIT_KEEP}
+
+# Days `am forget` keeps what it deletes in quarantine, out of recall but
+# restorable with `am restore-forgotten`, before `am gc` purges it for
+# good. 0 deletes outright, as `am forget --hard` does for one run.
+# quarantine_days = {DEFAULT_QUARANTINE_DAYS}
+
 [retention]
 # Neighborhoods within this many epochs of the max are GC-exempt.
 # grace_epochs = {grace_epochs}
@@ -307,11 +1316,130 @@ pub fn generate_default_toml() -> String {
 
 # Recency bonus weight in composite eviction scoring.
 # recency_weight = {recency_weight}
+
+[plans]
+# Tag numbered step lists in buffered and synced conversation as plans.
+# detect = false
+
+# Query words that boost plans in recall (whole words, lowercase).
+# verbs = ["implement", "migrate", "refactor", "plan"]
+
+[sync]
+# Truncate transcript messages longer than this many characters (0 = off).
+# max_message_chars = {max_message_chars}
+
+# Skip messages where more than this share of the text is code fences or
+# diff hunks (1.0 = off).
+# max_code_ratio = {max_code_ratio}
+
+# Message roles to keep ("user", "assistant"); empty keeps every role.
+# roles = []
+
+# Message roles to drop.
+# skip_roles = []
+
+[buffer]
+# When buffered conversation becomes an episode, also store lines that
+# open with DECISION: or PREFERENCE: (and <salient> tags) as conscious
+# memories.
+# auto_salient = false
+
+# Buffered exchanges (am_buffer calls) that make one episode.
+# threshold = {buffer_threshold}
+
+[compose]
+# Budgeted recall (am_query with max_tokens) first takes this many of the
+# best conscious, subconscious, and novel memories that fit, then fills
+# the rest of the budget by score.
+# min_conscious = {min_conscious}
+# min_subconscious = {min_subconscious}
+# min_novel = {min_novel}
+
+# Largest share of the composed context novel connections may take.
+# novel_budget_fraction = {novel_budget_fraction}
+
+[retry]
+# When another am process holds the database longer than SQLite's 5 s busy
+# timeout (a VACUUM, a large import), writes back off and try again.
+# Attempts in total, the first included; 1 never retries.
+# max_attempts = {retry_attempts}
+
+# Stop retrying once this many seconds have passed since the first attempt.
+# max_wait_secs = {retry_max_wait}
+
+[undo]
+# `am forget` and aggressive `am gc --target-mb` passes keep what they
+# delete so `am undo` can put it back. Newest operations kept; 0 turns
+# undo off.
+# keep = {undo_keep}
+
+# Size the kept rows may take in brain.db, in MB; older operations are
+# dropped first.
+# max_mb = {undo_max_mb}
+
+[sqlite]
+# How long a statement waits for another process's lock before failing
+# (and falling back to [retry]). Env: AM_SQLITE_BUSY_TIMEOUT_MS.
+# busy_timeout_ms = {busy_timeout_ms}
+
+# off, normal, full, or extra. normal with WAL can lose the latest commits
+# on power loss but never corrupts the file. Env: AM_SQLITE_SYNCHRONOUS.
+# synchronous = "normal"
+
+# Write-ahead journaling lets `am inspect` read while `am serve` writes.
+# false uses a rollback journal. Env: AM_SQLITE_WAL.
+# wal = true
+
+[project]
+# Conscious memories can name the project they are about, with
+# <salient project="name"> or a "DECISION (name):" prefix. Recall scores
+# those tagged for another project at `discount` of their weight (never 0,
+# so they stay recallable); untagged memories apply everywhere.
+
+# The current project; defaults to the name of the git repository around
+# the working directory. "" turns project affinity off.
+# Override with AM_PROJECT env var.
+# name = "my-repo"
+
+# Score factor for memories tagged for another project, within (0, 1].
+# discount = {project_discount}
+
+# Tag new conscious memories with the current project when they name none.
+# tag_conscious = false
+
+[mcp]
+# Directories that MCP tools taking a file path (am_export, am_import) may
+# read and write, besides data_dir and the project root. Paths are resolved
+# through symlinks first. The CLI is not restricted.
+# allowed_paths = ["~/backups"]
+# Largest am_ingest call, in bytes of text or chunk text; 0 or unset keeps
+# only the 1 MiB per-call input limit. Env: AM_MAX_INGEST_BYTES.
+# max_ingest_bytes = 262144
+
+[http]
+# Bearer token the read-only REST API (/rest/stats, /rest/conscious,
+# /rest/episodes, /rest/query) requires, as `Authorization: Bearer <token>`.
+# Unset leaves it open, which `am serve --rest` allows only on loopback.
+# Override with AM_HTTP_TOKEN env var.
+# token = "change-me"
 "#,
         grace_epochs = ret.grace_epochs,
         retention_days = ret.retention_days,
         min_neighborhoods = ret.min_neighborhoods,
         recency_weight = ret.recency_weight,
+        max_message_chars = DEFAULT_SYNC_MAX_MESSAGE_CHARS,
+        max_code_ratio = DEFAULT_SYNC_MAX_CODE_RATIO,
+        buffer_threshold = DEFAULT_BUFFER_THRESHOLD,
+        min_conscious = budget.min_conscious,
+        min_subconscious = budget.min_subconscious,
+        min_novel = budget.min_novel,
+        novel_budget_fraction = budget.novel_budget_fraction,
+        retry_attempts = crate::store::DEFAULT_RETRY_ATTEMPTS,
+        retry_max_wait = crate::store::DEFAULT_RETRY_MAX_WAIT.as_secs(),
+        undo_keep = crate::store::DEFAULT_UNDO_KEEP,
+        undo_max_mb = crate::store::DEFAULT_UNDO_MAX_BYTES / (1024 * 1024),
+        busy_timeout_ms = DEFAULT_BUSY_TIMEOUT_MS,
+        project_discount = DEFAULT_PROJECT_DISCOUNT,
     )
 }
 
@@ -358,6 +1486,7 @@ mod tests {
         assert!(!cfg.gc_enabled);
         assert_eq!(cfg.db_size_mb, 50);
         assert_eq!(cfg.db_size_limit_bytes(), 50 * 1024 * 1024);
+        assert!(cfg.conscious_journal);
     }
 
     #[test]
@@ -407,6 +1536,23 @@ recency_weight = 3.0
         assert!((ret.recency_weight.unwrap() - 3.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn parse_toml_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(
+            &path,
+            "[sqlite]\nbusy_timeout_ms = 1500\nsynchronous = \"full\"\nwal = false\n",
+        )
+        .unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.sqlite.busy_timeout_ms, 1500);
+        assert_eq!(cfg.sqlite.synchronous, Synchronous::Full);
+        assert!(!cfg.sqlite.wal);
+        assert!(!cfg.sqlite.read_only);
+    }
+
     #[test]
     fn parse_toml_retention_partial() {
         let content = "[retention]\ngrace_epochs = 10\n";
@@ -418,6 +1564,377 @@ recency_weight = 3.0
         assert_eq!(ret.recency_weight, None);
     }
 
+    #[test]
+    fn parse_toml_plans() {
+        let content = "[plans]\ndetect = true\nverbs = [\"deploy\", \"migrate\"]\n";
+        let file_cfg: FileConfig = toml::from_str(content).unwrap();
+        let plans = file_cfg.plans.unwrap();
+        assert_eq!(plans.detect, Some(true));
+        assert_eq!(
+            plans.verbs,
+            Some(vec!["deploy".to_string(), "migrate".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_toml_buffer() {
+        let file_cfg: FileConfig = toml::from_str("[buffer]\nauto_salient = true\n").unwrap();
+        assert_eq!(file_cfg.buffer.unwrap().auto_salient, Some(true));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "[buffer]\nthreshold = 5\n").unwrap();
+        let mut cfg = Config::default();
+        assert_eq!(cfg.buffer.threshold, DEFAULT_BUFFER_THRESHOLD);
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.buffer.threshold, 5);
+        assert!(!cfg.buffer.auto_salient);
+    }
+
+    #[test]
+    fn parse_toml_compose() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(
+            &path,
+            "[compose]\nmin_conscious = 3\nmin_novel = 1\nnovel_budget_fraction = 0.1\n",
+        )
+        .unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.compose.min_conscious, 3);
+        assert_eq!(cfg.compose.min_subconscious, 1);
+        assert_eq!(cfg.compose.min_novel, 1);
+        assert!((cfg.compose.novel_budget_fraction - 0.1).abs() < 1e-10);
+    }
+
+    #[test]
+    fn parse_error_names_key_and_line() {
+        let path = Path::new("/cfg/.am.config.toml");
+        let content =
+            "gc_enabled = true\n\n[retention]\ngrace_epochs = 5\nretention_days = \"x\"\n";
+        let err = parse_config(path, content).err().unwrap();
+        assert!(
+            err.starts_with("/cfg/.am.config.toml:5: `retention.retention_days`: "),
+            "{err}"
+        );
+
+        let err = parse_config(path, "db_size_mb = true\n").err().unwrap();
+        assert!(
+            err.starts_with("/cfg/.am.config.toml:1: `db_size_mb`: "),
+            "{err}"
+        );
+
+        let err = parse_config(path, "gc_enabled = false\n[buffer\n")
+            .err()
+            .unwrap();
+        assert!(err.starts_with("/cfg/.am.config.toml:2: "), "{err}");
+        assert!(!err.contains('\n'), "{err}");
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let table: toml::Table =
+            "gc_enabled = true\nfoo = 1\n[buffer]\nthreshold = 2\nthresh = 2\n[extra]\nx = 1\n"
+                .parse()
+                .unwrap();
+        assert_eq!(unknown_keys(&table), ["buffer.thresh", "extra.x", "foo"]);
+    }
+
+    #[test]
+    fn set_value_keeps_comments_and_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(CONFIG_FILE_NAME);
+
+        set_value(&path, "buffer.threshold", "5").unwrap();
+        set_value(&path, "gc_enabled", "true").unwrap();
+        set_value(&path, "project.name", "web-app").unwrap();
+        set_value(&path, "sync.roles", r#"["user"]"#).unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.buffer.threshold, 5);
+        assert!(cfg.gc_enabled);
+        assert_eq!(cfg.project.name.as_deref(), Some("web-app"));
+        assert_eq!(cfg.sync.roles, ["user"]);
+
+        fs::write(&path, generate_default_toml()).unwrap();
+        set_value(&path, "retention.grace_epochs", "7").unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("# Neighborhoods within this many epochs"));
+        assert!(written.contains("grace_epochs = 7"));
+
+        for (key, value) in [
+            ("buffer.thresh", "5"),
+            ("buffer.threshold", "many"),
+            ("buffer.threshold", "0"),
+            ("compose.novel_budget_fraction", "2"),
+        ] {
+            assert!(set_value(&path, key, value).is_err(), "{key} = {value}");
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn explain_reports_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "db_size_mb = 10\nbogus = 1\n[buffer]\nthreshold = 4\n[http]\ntoken = \"secret\"\n",
+        )
+        .unwrap();
+        temp_env::with_vars(
+            [
+                ("AM_DATA_DIR", Some(data_dir)),
+                ("AM_DB_SIZE_MB", Some("20")),
+                ("AM_HTTP_TOKEN", None),
+            ],
+            || {
+                let report = explain().unwrap();
+                let file = ConfigSource::File(dir.path().join(CONFIG_FILE_NAME));
+                assert_eq!(
+                    report.path.as_ref(),
+                    Some(&dir.path().join(CONFIG_FILE_NAME))
+                );
+                assert_eq!(report.problems.len(), 1);
+                assert!(report.problems[0].contains("`bogus`"));
+                let entry = |key: &str| {
+                    report
+                        .entries
+                        .iter()
+                        .find(|e| e.key == key)
+                        .unwrap()
+                        .clone()
+                };
+                assert_eq!(entry("data_dir").source, ConfigSource::Env("AM_DATA_DIR"));
+                let size = entry("db_size_mb");
+                assert_eq!(
+                    (size.value.as_str(), size.source),
+                    ("20", ConfigSource::Env("AM_DB_SIZE_MB"))
+                );
+                let threshold = entry("buffer.threshold");
+                assert_eq!(
+                    (threshold.value.as_str(), threshold.source),
+                    ("4", file.clone())
+                );
+                let token = entry("http.token");
+                assert_eq!((token.value.as_str(), token.source), ("(set)", file));
+                assert_eq!(entry("gc_enabled").source, ConfigSource::Default);
+            },
+        );
+    }
+
+    #[test]
+    fn parse_toml_retry() {
+        let file_cfg: FileConfig =
+            toml::from_str("[retry]\nmax_attempts = 1\nmax_wait_secs = 5\n").unwrap();
+        let retry = file_cfg.retry.unwrap();
+        assert_eq!(retry.max_attempts, Some(1));
+        assert_eq!(retry.max_wait_secs, Some(5));
+        assert!(generate_default_toml().contains("# max_attempts = 5"));
+        assert!(generate_default_toml().contains("# max_wait_secs = 30"));
+    }
+
+    #[test]
+    fn parse_toml_dedup_neighborhoods() {
+        let file_cfg: FileConfig = toml::from_str("dedup_neighborhoods = true\n").unwrap();
+        assert_eq!(file_cfg.dedup_neighborhoods, Some(true));
+        assert!(!Config::default().dedup_neighborhoods);
+        assert!(generate_default_toml().contains("# dedup_neighborhoods = false"));
+    }
+
+    #[test]
+    fn parse_toml_salient_dedup_threshold() {
+        let file_cfg: FileConfig = toml::from_str("salient_dedup_threshold = 0.6\n").unwrap();
+        assert_eq!(file_cfg.salient_dedup_threshold, Some(0.6));
+        assert_eq!(Config::default().salient_dedup_threshold, 0.8);
+        assert!(generate_default_toml().contains("# salient_dedup_threshold = 0.8"));
+
+        let cfg = Config {
+            data_dir: PathBuf::from("/tmp/am-test"),
+            salient_dedup_threshold: 1.5,
+            ..Config::default()
+        };
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("salient_dedup_threshold"));
+    }
+
+    #[test]
+    fn parse_toml_activation_spillover() {
+        let file_cfg: FileConfig = toml::from_str("activation_spillover = 0.25\n").unwrap();
+        assert_eq!(file_cfg.activation_spillover, Some(0.25));
+        assert_eq!(Config::default().activation_spillover, 0.0);
+        assert!(generate_default_toml().contains("# activation_spillover = 0.0"));
+    }
+
+    #[test]
+    fn parse_toml_max_word_activations() {
+        let file_cfg: FileConfig = toml::from_str("max_word_activations = 64\n").unwrap();
+        assert_eq!(file_cfg.max_word_activations, Some(64));
+        assert_eq!(
+            Config::default().max_word_activations,
+            DEFAULT_MAX_WORD_ACTIVATIONS
+        );
+        assert!(generate_default_toml().contains("# max_word_activations = 256"));
+    }
+
+    #[test]
+    fn parse_toml_undo() {
+        let file_cfg: FileConfig = toml::from_str("[undo]\nkeep = 3\nmax_mb = 2\n").unwrap();
+        let undo = file_cfg.undo.unwrap();
+        assert_eq!((undo.keep, undo.max_mb), (Some(3), Some(2)));
+        assert_eq!(Config::default().undo, UndoPolicy::default());
+        assert!(generate_default_toml().contains("# keep = 10\n"));
+        assert!(generate_default_toml().contains("# max_mb = 16\n"));
+    }
+
+    #[test]
+    fn parse_toml_quarantine_days() {
+        let file_cfg: FileConfig = toml::from_str("quarantine_days = 7\n").unwrap();
+        assert_eq!(file_cfg.quarantine_days, Some(7));
+        assert_eq!(Config::default().quarantine_days, DEFAULT_QUARANTINE_DAYS);
+        assert!(generate_default_toml().contains("# quarantine_days = 30"));
+    }
+
+    #[test]
+    fn parse_toml_audit_keep() {
+        let file_cfg: FileConfig = toml::from_str("audit_keep = 500\n").unwrap();
+        assert_eq!(file_cfg.audit_keep, Some(500));
+        assert_eq!(Config::default().audit_keep, DEFAULT_AUDIT_KEEP);
+        assert!(generate_default_toml().contains("# audit_keep = 10000"));
+    }
+
+    #[test]
+    fn parse_toml_gc_resurrect_warn() {
+        let file_cfg: FileConfig = toml::from_str("gc_resurrect_warn = 0\n").unwrap();
+        assert_eq!(file_cfg.gc_resurrect_warn, Some(0));
+        assert!(generate_default_toml().contains("gc_resurrect_warn = 100"));
+    }
+
+    #[test]
+    fn parse_toml_mcp_allowed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(
+            &path,
+            "[mcp]\nallowed_paths = [\"/srv/exports\", \"~/backups\"]\n",
+        )
+        .unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.mcp.allowed_paths[0], PathBuf::from("/srv/exports"));
+        assert!(cfg.mcp.allowed_paths[1].ends_with("backups"));
+        assert!(cfg.mcp.allowed_paths[1].is_absolute());
+
+        let allowed = cfg.mcp_allowed_paths();
+        assert_eq!(allowed[0], cfg.data_dir);
+        assert!(allowed.contains(&PathBuf::from("/srv/exports")));
+        assert_eq!(cfg.mcp.max_ingest_bytes, None);
+    }
+
+    #[test]
+    fn parse_toml_mcp_max_ingest_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(&path, "[mcp]\nmax_ingest_bytes = 4096\n").unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.mcp.max_ingest_bytes, Some(4096));
+
+        fs::write(&path, "[mcp]\nmax_ingest_bytes = 0\n").unwrap();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.mcp.max_ingest_bytes, None);
+    }
+
+    #[test]
+    fn parse_toml_http_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(&path, "[http]\ntoken = \"s3cret\"\n").unwrap();
+        let mut cfg = Config::default();
+        assert_eq!(cfg.http.token, None);
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.http.token.as_deref(), Some("s3cret"));
+
+        fs::write(&path, "[http]\ntoken = \"\"\n").unwrap();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.http.token, None, "an empty token leaves the API open");
+        assert!(generate_default_toml().contains("# token = \"change-me\""));
+    }
+
+    #[test]
+    fn parse_toml_compress_text_min_bytes() {
+        let file_cfg: FileConfig = toml::from_str("compress_text_min_bytes = 0\n").unwrap();
+        assert_eq!(file_cfg.compress_text_min_bytes, Some(0));
+        assert_eq!(Config::default().compress_text_min_bytes, 512);
+        assert!(generate_default_toml().contains("# compress_text_min_bytes = 512"));
+    }
+
+    #[test]
+    fn parse_toml_epoch_size_mb() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(&path, "epoch_size_mb = 200\n").unwrap();
+        let mut cfg = Config::default();
+        assert_eq!(cfg.epoch_size_mb, 0, "epochs are off by default");
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.epoch_size_mb, 200);
+        assert!(generate_default_toml().contains("# epoch_size_mb = 0"));
+    }
+
+    #[test]
+    fn parse_toml_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".am.config.toml");
+        fs::write(
+            &path,
+            "[project]\nname = \"repo-x\"\ndiscount = 0.8\ntag_conscious = true\n",
+        )
+        .unwrap();
+        let mut cfg = Config::default();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.project.name.as_deref(), Some("repo-x"));
+        assert_eq!(cfg.project.discount, 0.8);
+        assert!(cfg.project.tag_conscious);
+
+        // An empty name turns affinity off, overriding the detected repo
+        fs::write(&path, "[project]\nname = \"\"\n").unwrap();
+        apply_file_config(&mut cfg, &path).unwrap();
+        assert_eq!(cfg.project.name, None);
+        assert!(generate_default_toml().contains("# discount = 0.6"));
+    }
+
+    #[test]
+    fn detect_project_name_uses_git_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo-x");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("src/nested")).unwrap();
+        assert_eq!(
+            detect_project_name(&repo.join("src/nested")).as_deref(),
+            Some("repo-x")
+        );
+        assert_eq!(detect_project_name(dir.path()), None);
+    }
+
+    #[test]
+    fn parse_toml_conscious_journal() {
+        let file_cfg: FileConfig = toml::from_str("conscious_journal = false\n").unwrap();
+        assert_eq!(file_cfg.conscious_journal, Some(false));
+    }
+
+    #[test]
+    fn parse_toml_sync_filters() {
+        let content =
+            "[sync]\nmax_message_chars = 500\nmax_code_ratio = 0.5\nskip_roles = [\"assistant\"]\n";
+        let file_cfg: FileConfig = toml::from_str(content).unwrap();
+        let sync = file_cfg.sync.unwrap();
+        assert_eq!(sync.max_message_chars, Some(500));
+        assert_eq!(sync.max_code_ratio, Some(0.5));
+        assert!(sync.roles.is_none());
+        assert_eq!(sync.skip_roles, Some(vec!["assistant".to_string()]));
+    }
+
     #[test]
     fn parse_toml_sync_log_dir() {
         let content = "sync_log_dir = \"~/logs/am-sync\"\n";
@@ -425,6 +1942,14 @@ recency_weight = 3.0
         assert_eq!(file_cfg.sync_log_dir.as_deref(), Some("~/logs/am-sync"));
     }
 
+    #[test]
+    fn parse_toml_actor() {
+        let file_cfg: FileConfig = toml::from_str("actor = \"codex\"\n").unwrap();
+        assert_eq!(file_cfg.actor.as_deref(), Some("codex"));
+        let file_cfg: FileConfig = toml::from_str("").unwrap();
+        assert!(file_cfg.actor.is_none());
+    }
+
     #[test]
     fn retention_defaults() {
         let policy = RetentionPolicy::default();
@@ -487,6 +2012,28 @@ recency_weight = 3.0
         assert!(err.to_string().contains(">= 1"));
     }
 
+    #[test]
+    fn validate_rejects_zero_retry_attempts() {
+        let mut cfg = Config {
+            data_dir: PathBuf::from("/tmp/am-test"),
+            ..Config::default()
+        };
+        cfg.retry.max_attempts = 0;
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("retry.max_attempts"));
+    }
+
+    #[test]
+    fn validate_rejects_zero_project_discount() {
+        let mut cfg = Config {
+            data_dir: PathBuf::from("/tmp/am-test"),
+            ..Config::default()
+        };
+        cfg.project.discount = 0.0;
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("project.discount"));
+    }
+
     #[test]
     fn validate_accepts_gc_disabled_with_zero_db_size() {
         let cfg = Config {
@@ -498,6 +2045,29 @@ recency_weight = 3.0
         assert!(cfg.validate().is_ok());
     }
 
+    #[test]
+    fn validate_rejects_bad_buffer_and_compose() {
+        let mut cfg = Config::default();
+        cfg.buffer.threshold = 0;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = Config::default();
+        cfg.compose.novel_budget_fraction = 1.5;
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("compose.novel_budget_fraction"), "{err}");
+    }
+
+    #[test]
+    fn validate_rejects_code_ratio_out_of_range() {
+        let mut cfg = Config {
+            data_dir: PathBuf::from("/tmp/am-test"),
+            ..Config::default()
+        };
+        cfg.sync.max_code_ratio = 1.5;
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("max_code_ratio"));
+    }
+
     #[test]
     fn validate_accepts_valid_config() {
         let cfg = Config {
@@ -611,5 +2181,9 @@ recency_weight = 3.0
         assert!(toml.contains("~/.attention-matters"));
         assert!(toml.contains("gc_enabled = false"));
         assert!(toml.contains(&format!("db_size_mb = {DEFAULT_DB_SIZE_MB}")));
+        assert!(toml.contains("conscious_journal = true"));
+        assert!(toml.contains(&format!(
+            "max_message_chars = {DEFAULT_SYNC_MAX_MESSAGE_CHARS}"
+        )));
     }
 }
@@ -1,3 +1,6 @@
+use am_core::store_trait::StoreErrorKind;
+use rusqlite::ErrorCode;
+
 #[derive(Debug, thiserror::Error)]
 pub enum StoreError {
     #[error("SQLite error: {0}")]
@@ -8,4 +11,24 @@ pub enum StoreError {
     InvalidData(String),
 }
 
+impl StoreError {
+    /// Classify the failure. `SQLITE_BUSY` and `SQLITE_LOCKED` are busy: the
+    /// busy timeout ran out while another connection held the database.
+    pub fn kind(&self) -> StoreErrorKind {
+        match self {
+            Self::Sqlite(rusqlite::Error::SqliteFailure(e, _)) => match e.code {
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => StoreErrorKind::Busy,
+                ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => StoreErrorKind::Corrupt,
+                ErrorCode::SystemIoFailure | ErrorCode::DiskFull | ErrorCode::CannotOpen => {
+                    StoreErrorKind::Io
+                }
+                _ => StoreErrorKind::Other,
+            },
+            Self::Sqlite(_) => StoreErrorKind::Other,
+            Self::Io(_) => StoreErrorKind::Io,
+            Self::InvalidData(_) => StoreErrorKind::Corrupt,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, StoreError>;
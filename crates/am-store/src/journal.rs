@@ -0,0 +1,338 @@
+//! Append-only plain-text journal of conscious (salient) memories.
+//!
+//! Conscious memories are the most valuable and the smallest part of the
+//! brain. Each one is also written as a JSON line to `conscious.journal`
+//! next to `brain.db`, so losing the SQLite file does not lose them.
+//! `recover_conscious` replays a journal into a system. Forgetting a
+//! conscious memory appends a tombstone entry, so replay does not bring
+//! it back.
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use am_core::{
+    neighborhood::{Neighborhood, NeighborhoodType},
+    system::DAESystem,
+    time::now_iso8601,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// File name of the journal inside the data directory.
+pub const JOURNAL_FILE_NAME: &str = "conscious.journal";
+
+/// Journal file name for `brain`: [`JOURNAL_FILE_NAME`] for the default
+/// brain, `conscious.<brain>.journal` for any other, so brains sharing a
+/// data directory never replay each other's memories.
+pub fn journal_file_name(brain: &str) -> String {
+    if brain == crate::store::DEFAULT_BRAIN {
+        JOURNAL_FILE_NAME.to_string()
+    } else {
+        format!("conscious.{brain}.journal")
+    }
+}
+
+/// One salient addition, or the removal of one, as written to the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: String,
+    #[serde(rename = "type")]
+    pub neighborhood_type: NeighborhoodType,
+    pub text: String,
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<Uuid>,
+    /// A tombstone: the memory `id` was forgotten. Replay leaves it out
+    /// unless a later entry adds it back.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forgotten: bool,
+}
+
+impl JournalEntry {
+    /// Entry for a conscious neighborhood, stamped with its creation time
+    /// (or the current time if it has none).
+    pub fn for_neighborhood(neighborhood: &Neighborhood) -> Self {
+        Self {
+            timestamp: neighborhood.created_at.clone().unwrap_or_else(now_iso8601),
+            neighborhood_type: neighborhood.neighborhood_type,
+            text: neighborhood.source_text.clone(),
+            id: neighborhood.id,
+            actor: neighborhood.actor.clone(),
+            project: neighborhood.project.clone(),
+            group_id: neighborhood.group_id,
+            forgotten: false,
+        }
+    }
+
+    /// Tombstone for the conscious neighborhood `id`, stamped now.
+    pub fn forgotten(id: Uuid) -> Self {
+        Self {
+            timestamp: now_iso8601(),
+            neighborhood_type: NeighborhoodType::default(),
+            text: String::new(),
+            id,
+            actor: None,
+            project: None,
+            group_id: None,
+            forgotten: true,
+        }
+    }
+}
+
+/// Handle to an append-only journal file.
+#[derive(Debug, Clone)]
+pub struct ConsciousJournal {
+    path: PathBuf,
+}
+
+impl ConsciousJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one entry and sync it to disk.
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)
+            .map_err(|e| crate::error::StoreError::InvalidData(e.to_string()))?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// Read every entry from a journal file.
+///
+/// Lines that do not parse (e.g. a line torn by a crash mid-write) are
+/// skipped with a warning rather than failing the whole recovery.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>> {
+    let content = fs::read_to_string(path)?;
+    let mut entries = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!(
+                "{}:{}: skipping bad journal line: {e}",
+                path.display(),
+                i + 1
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// IDs whose latest journal entry is a tombstone.
+pub fn forgotten_ids(entries: &[JournalEntry]) -> HashSet<Uuid> {
+    let mut forgotten = HashSet::new();
+    for entry in entries {
+        if entry.forgotten {
+            forgotten.insert(entry.id);
+        } else {
+            forgotten.remove(&entry.id);
+        }
+    }
+    forgotten
+}
+
+/// Outcome of replaying a journal.
+#[derive(Debug, Default)]
+pub struct RecoverReport {
+    /// IDs of conscious neighborhoods added to the system, in journal order.
+    pub restored: Vec<Uuid>,
+    /// Entries already present, by ID or identical text.
+    pub skipped: usize,
+    /// Entries for memories forgotten later in the journal.
+    pub forgotten: usize,
+}
+
+/// Replay journal entries into the system's conscious episode.
+///
+/// An entry is skipped when a conscious neighborhood with the same ID or
+/// the same text already exists, so replaying into an existing store (or
+/// replaying the same journal twice) does not duplicate memories, and when
+/// a later tombstone forgets it. Restored
+/// neighborhoods keep their journaled ID, type, actor, and project, and take
+/// the entry's timestamp as their creation time.
+pub fn recover_conscious(
+    system: &mut DAESystem,
+    entries: &[JournalEntry],
+    rng: &mut impl rand::Rng,
+) -> RecoverReport {
+    let mut seen_ids: HashSet<Uuid> = system
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.id)
+        .collect();
+    let mut seen_texts: HashSet<String> = system
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.source_text.clone())
+        .collect();
+
+    let forgotten = forgotten_ids(entries);
+    let mut report = RecoverReport::default();
+    for entry in entries {
+        if entry.forgotten {
+            continue;
+        }
+        if forgotten.contains(&entry.id) {
+            report.forgotten += 1;
+            continue;
+        }
+        if seen_ids.contains(&entry.id) || seen_texts.contains(&entry.text) {
+            report.skipped += 1;
+            continue;
+        }
+        system.add_to_conscious_typed(&entry.text, entry.neighborhood_type, rng);
+        if let Some(nbhd) = system.conscious_episode.neighborhoods.last_mut() {
+            nbhd.id = entry.id;
+            nbhd.actor.clone_from(&entry.actor);
+            nbhd.project.clone_from(&entry.project);
+            nbhd.group_id = entry.group_id;
+            nbhd.created_at = Some(entry.timestamp.clone());
+            for occ in &mut nbhd.occurrences {
+                occ.neighborhood_id = entry.id;
+            }
+        }
+        seen_ids.insert(entry.id);
+        seen_texts.insert(entry.text.clone());
+        report.restored.push(entry.id);
+    }
+    if !report.restored.is_empty() {
+        // The neighborhood index was keyed on the replaced random IDs
+        system.mark_dirty();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn append_and_read_round_trip_skipping_torn_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = ConsciousJournal::new(dir.path().join(JOURNAL_FILE_NAME));
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut sys = DAESystem::new("test");
+        sys.add_to_conscious_typed("use sqlite wal", NeighborhoodType::Decision, &mut rng);
+        journal
+            .append(&JournalEntry::for_neighborhood(
+                &sys.conscious_episode.neighborhoods[0],
+            ))
+            .unwrap();
+        // Simulate a crash mid-write
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap();
+        file.write_all(b"{\"timestamp\":\"2").unwrap();
+
+        let entries = read_journal(journal.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "use sqlite wal");
+        assert_eq!(entries[0].neighborhood_type, NeighborhoodType::Decision);
+        assert_eq!(entries[0].id, sys.conscious_episode.neighborhoods[0].id);
+    }
+
+    #[test]
+    fn recover_dedups_by_id_and_text() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let mut sys = DAESystem::new("test");
+        sys.add_to_conscious("already here", &mut rng);
+        let existing = sys.conscious_episode.neighborhoods[0].id;
+
+        let entry = |id, text: &str| JournalEntry {
+            timestamp: now_iso8601(),
+            neighborhood_type: NeighborhoodType::Preference,
+            text: text.to_string(),
+            id,
+            actor: None,
+            project: None,
+            group_id: None,
+            forgotten: false,
+        };
+        let fresh = Uuid::new_v4();
+        let entries = vec![
+            entry(existing, "different text, same id"),
+            entry(Uuid::new_v4(), "already here"),
+            entry(fresh, "prefer tabs"),
+            entry(fresh, "prefer tabs"),
+        ];
+
+        let report = recover_conscious(&mut sys, &entries, &mut rng);
+        assert_eq!(report.restored, vec![fresh]);
+        assert_eq!(report.skipped, 3);
+        let restored = sys
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .find(|n| n.id == fresh)
+            .unwrap();
+        assert_eq!(restored.neighborhood_type, NeighborhoodType::Preference);
+        assert!(
+            restored
+                .occurrences
+                .iter()
+                .all(|o| o.neighborhood_id == fresh)
+        );
+        assert!(sys.get_neighborhood_ref(fresh).is_some());
+    }
+
+    #[test]
+    fn recover_skips_memories_a_tombstone_forgets() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut sys = DAESystem::new("test");
+        sys.add_to_conscious("prefer tabs", &mut rng);
+        sys.add_to_conscious("use sqlite wal", &mut rng);
+        let [gone, back] = [0, 1].map(|i| sys.conscious_episode.neighborhoods[i].id);
+        let mut entries: Vec<JournalEntry> = sys
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(JournalEntry::for_neighborhood)
+            .collect();
+        entries.push(JournalEntry::forgotten(gone));
+        entries.push(JournalEntry::forgotten(back));
+        // Undone: added back after its tombstone
+        entries.push(entries[1].clone());
+
+        let line = serde_json::to_string(&entries[2]).unwrap();
+        assert!(line.contains("\"forgotten\":true"));
+        assert!(
+            !serde_json::to_string(&entries[0])
+                .unwrap()
+                .contains("forgotten")
+        );
+        assert_eq!(forgotten_ids(&entries), HashSet::from([gone]));
+
+        let mut fresh = DAESystem::new("test");
+        let report = recover_conscious(&mut fresh, &entries, &mut rng);
+        assert_eq!(report.restored, vec![back]);
+        assert_eq!(report.forgotten, 1);
+        assert_eq!(fresh.conscious_episode.neighborhoods.len(), 1);
+    }
+}
@@ -1,26 +1,241 @@
 use std::fs;
+use std::io::{BufReader, Read};
 use std::path::Path;
 
-use am_core::serde_compat::{export_json, import_json};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use am_core::episode::Episode;
+use am_core::serde_compat::{
+    IdPolicy, ImportOptions, ImportReport, export_json, stream_import_json_with,
+};
+use am_core::store_trait::{AuditEvent, AuditKind};
 
 use crate::error::{Result, StoreError};
-use crate::store::Store;
+use crate::store::{Store, parse_uuid};
+
+/// How an import treats data already in the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Wipe existing episodes first. The wipe and the import share one
+    /// transaction, so a failed import leaves the store untouched.
+    #[default]
+    Replace,
+    /// Keep existing data. Episodes whose ID already exists are skipped and
+    /// conscious neighborhoods are folded into the existing conscious episode.
+    Merge,
+}
+
+/// Counts reported by a streaming import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Subconscious episodes written to the store.
+    pub episodes: usize,
+    /// Subconscious episodes skipped because their ID already existed (merge only).
+    pub skipped_episodes: usize,
+    /// Conscious neighborhoods written to the store.
+    pub conscious_neighborhoods: usize,
+    /// IDs regenerated, future timestamps clamped, and what validation
+    /// found. Anything keyed on a regenerated ID (session dedup, feedback
+    /// targets, supersession) will not match it; a clamped episode keeps
+    /// its original timestamp in `Episode::original_timestamp`.
+    pub report: ImportReport,
+}
 
 impl Store {
-    /// Import a v0.7.2 JSON export file into this store.
+    /// Import a v0.7.2 JSON export file into this store, replacing its contents.
     /// Handles both "phasor" and "theta" field names (via am-core serde alias).
     pub fn import_json_file(&self, path: &Path) -> Result<()> {
-        let json = fs::read_to_string(path)?;
-        let system = import_json(&json)
-            .map_err(|e| StoreError::InvalidData(format!("invalid JSON: {e}")))?;
-        self.save_system(&system)
+        let file = fs::File::open(path)?;
+        self.import_json_reader(file, ImportMode::Replace, IdPolicy::Lenient, |_| {})?;
+        Ok(())
     }
 
-    /// Import a v0.7.2 JSON string into this store.
+    /// Import a v0.7.2 JSON string into this store, replacing its contents.
     pub fn import_json_str(&self, json: &str) -> Result<()> {
-        let system =
-            import_json(json).map_err(|e| StoreError::InvalidData(format!("invalid JSON: {e}")))?;
-        self.save_system(&system)
+        self.import_json_reader(
+            json.as_bytes(),
+            ImportMode::Replace,
+            IdPolicy::Lenient,
+            |_| {},
+        )?;
+        Ok(())
+    }
+
+    /// Stream a v0.7.2 export into the store one episode at a time.
+    ///
+    /// Episodes are inserted as they are parsed instead of building the full
+    /// `DAESystem` first, so peak memory stays near the size of the largest
+    /// episode. The whole import runs in one transaction: a parse error
+    /// midway through rolls back every insert (and, in replace mode, the
+    /// wipe). Missing or malformed IDs are handled per `ids`; repairs are
+    /// counted in the summary. `progress` is called with the number of
+    /// subconscious episodes written so far after each one. Anything that
+    /// fails validation refuses the import; see [`Self::import_json_reader_with`].
+    pub fn import_json_reader<R: Read>(
+        &self,
+        reader: R,
+        mode: ImportMode,
+        ids: IdPolicy,
+        progress: impl FnMut(usize),
+    ) -> Result<ImportSummary> {
+        let options = ImportOptions {
+            ids,
+            ..ImportOptions::default()
+        };
+        self.import_json_reader_with(reader, mode, options, progress)
+    }
+
+    /// [`Self::import_json_reader`] under explicit [`ImportOptions`]. With
+    /// lenient validation, episodes and neighborhoods that fail are left
+    /// out and listed in the summary's report instead of refusing the file.
+    pub fn import_json_reader_with<R: Read>(
+        &self,
+        reader: R,
+        mode: ImportMode,
+        options: ImportOptions,
+        progress: impl FnMut(usize),
+    ) -> Result<ImportSummary> {
+        let tx = self.transaction()?;
+        let summary = self.import_on(&tx, reader, mode, options, progress)?;
+        tx.commit()?;
+        // PASSIVE checkpoint after bulk write - flushes WAL without blocking readers
+        let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
+        Ok(summary)
+    }
+
+    /// The body of [`Self::import_json_reader_with`], inside the caller's
+    /// transaction `tx`.
+    pub(crate) fn import_on<R: Read>(
+        &self,
+        tx: &Connection,
+        reader: R,
+        mode: ImportMode,
+        options: ImportOptions,
+        mut progress: impl FnMut(usize),
+    ) -> Result<ImportSummary> {
+        let existing: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM occurrences WHERE brain_id = ?1",
+            [&self.brain],
+            |r| r.get(0),
+        )?;
+        if mode == ImportMode::Replace {
+            // Neighborhoods and occurrences cascade
+            tx.execute("DELETE FROM episodes WHERE brain_id = ?1", [&self.brain])?;
+        }
+
+        // In merge mode, incoming conscious neighborhoods attach to the
+        // conscious episode already in the store (if there is one).
+        let existing_conscious: Option<String> = match mode {
+            ImportMode::Replace => None,
+            ImportMode::Merge => tx
+                .query_row(
+                    "SELECT id FROM episodes WHERE is_conscious = 1 AND brain_id = ?1 LIMIT 1",
+                    [&self.brain],
+                    |r| r.get(0),
+                )
+                .optional()?,
+        };
+
+        let mut summary = ImportSummary::default();
+        let mut imported_occurrences = 0usize;
+        // The callback can only report errors as strings through serde, so
+        // the original StoreError is stashed here and preferred on failure.
+        let mut sink_error: Option<StoreError> = None;
+
+        let mut on_episode = |episode: Episode| -> Result<()> {
+            if episode.is_conscious {
+                if let Some(conscious_id) = &existing_conscious {
+                    let conscious_id = parse_uuid(conscious_id)?;
+                    for nbhd in &episode.neighborhoods {
+                        let exists: bool = tx.query_row(
+                            "SELECT EXISTS(SELECT 1 FROM neighborhoods WHERE id = ?1 AND brain_id = ?2)",
+                            params![nbhd.id.to_string(), self.brain],
+                            |r| r.get(0),
+                        )?;
+                        if !exists {
+                            self.save_neighborhood_on(tx, nbhd, conscious_id, true)?;
+                            summary.conscious_neighborhoods += 1;
+                            imported_occurrences += nbhd.occurrences.len();
+                        }
+                    }
+                } else {
+                    self.save_episode_on(tx, &episode)?;
+                    summary.conscious_neighborhoods += episode.neighborhoods.len();
+                    imported_occurrences += episode
+                        .neighborhoods
+                        .iter()
+                        .map(|n| n.occurrences.len())
+                        .sum::<usize>();
+                }
+                return Ok(());
+            }
+
+            if mode == ImportMode::Merge {
+                let exists: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM episodes WHERE id = ?1 AND brain_id = ?2)",
+                    params![episode.id.to_string(), self.brain],
+                    |r| r.get(0),
+                )?;
+                if exists {
+                    summary.skipped_episodes += 1;
+                    return Ok(());
+                }
+            }
+
+            self.save_episode_on(tx, &episode)?;
+            imported_occurrences += episode
+                .neighborhoods
+                .iter()
+                .map(|n| n.occurrences.len())
+                .sum::<usize>();
+            summary.episodes += 1;
+            progress(summary.episodes);
+            Ok(())
+        };
+
+        let parsed = stream_import_json_with(BufReader::new(reader), options, |episode| {
+            on_episode(episode).map_err(|e| {
+                let msg = e.to_string();
+                sink_error = Some(e);
+                msg
+            })
+        });
+        let stream_summary = match parsed {
+            Ok(s) => s,
+            Err(e) => {
+                return Err(sink_error
+                    .unwrap_or_else(|| StoreError::InvalidData(format!("invalid export: {e}"))));
+            }
+        };
+
+        // Same guard as save_system: an empty import must not silently wipe
+        // a populated store.
+        if mode == ImportMode::Replace
+            && imported_occurrences == 0
+            && summary.episodes == 0
+            && existing > 0
+        {
+            return Err(StoreError::InvalidData(format!(
+                "refusing to overwrite {existing} existing occurrences with empty import"
+            )));
+        }
+
+        if mode == ImportMode::Replace || self.get_metadata("agent_name")?.is_none() {
+            self.set_metadata_on(tx, "agent_name", &stream_summary.agent_name)?;
+        }
+
+        summary.report = stream_summary.report;
+        let mode_name = match mode {
+            ImportMode::Replace => "replace",
+            ImportMode::Merge => "merge",
+        };
+        let event =
+            AuditEvent::new(AuditKind::Import, summary.episodes as u64).with_detail(format!(
+                "{mode_name}: {} episodes, {} conscious",
+                summary.episodes, summary.conscious_neighborhoods
+            ));
+        self.audit_on(tx, &event)?;
+        Ok(summary)
     }
 
     /// Export the store contents to a v0.7.2 JSON file.
@@ -43,7 +258,8 @@ impl Store {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use am_core::{episode::Episode, neighborhood::Neighborhood, system::DAESystem};
+    use am_core::serde_compat::{ValidationPolicy, import_json};
+    use am_core::{neighborhood::Neighborhood, system::DAESystem};
     use rand::SeedableRng;
     use rand::rngs::SmallRng;
 
@@ -248,4 +464,254 @@ mod tests {
             original.conscious_episode.neighborhoods.len()
         );
     }
+    fn make_large_system(episodes: usize) -> DAESystem {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("bulk-agent");
+        for i in 0..episodes {
+            let mut ep = Episode::new(&format!("episode-{i}"));
+            for j in 0..3 {
+                let text = format!("alpha beta gamma {i} {j}");
+                let tokens: Vec<String> = text.split(' ').map(String::from).collect();
+                ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, &mut rng));
+            }
+            sys.add_episode(ep);
+        }
+        sys.add_to_conscious("bulk conscious insight", &mut rng);
+        sys
+    }
+
+    #[test]
+    fn test_streaming_import_large_export() {
+        let original = make_large_system(250);
+        let json = export_json(&original).unwrap();
+
+        let store = Store::open_in_memory().unwrap();
+        let mut ticks = Vec::new();
+        let summary = store
+            .import_json_reader(
+                json.as_bytes(),
+                ImportMode::Replace,
+                IdPolicy::Lenient,
+                |n| ticks.push(n),
+            )
+            .unwrap();
+
+        assert_eq!(summary.episodes, 250);
+        assert_eq!(summary.conscious_neighborhoods, 1);
+        assert_eq!(ticks.len(), 250);
+        assert_eq!(ticks.last(), Some(&250));
+
+        let loaded = store.load_system().unwrap();
+        assert_eq!(loaded.agent_name, "bulk-agent");
+        assert_eq!(loaded.episodes.len(), 250);
+        assert_eq!(loaded.n(), original.n());
+        assert_eq!(loaded.conscious_episode.neighborhoods.len(), 1);
+    }
+
+    #[test]
+    fn test_streaming_import_parse_error_rolls_back() {
+        let store = Store::open_in_memory().unwrap();
+        let existing = make_system();
+        store.save_system(&existing).unwrap();
+
+        // Truncate a valid export partway through the episodes array so the
+        // first episodes parse and insert before the error is hit.
+        let json = export_json(&make_large_system(50)).unwrap();
+        let truncated = &json[..json.len() / 2];
+
+        let result = store.import_json_reader(
+            truncated.as_bytes(),
+            ImportMode::Replace,
+            IdPolicy::Lenient,
+            |_| {},
+        );
+        assert!(result.is_err());
+
+        let loaded = store.load_system().unwrap();
+        assert_eq!(loaded.agent_name, existing.agent_name);
+        assert_eq!(loaded.episodes.len(), existing.episodes.len());
+        assert_eq!(loaded.n(), existing.n());
+    }
+
+    #[test]
+    fn test_streaming_import_merge_skips_existing() {
+        let store = Store::open_in_memory().unwrap();
+        let original = make_system();
+        store.save_system(&original).unwrap();
+
+        let mut incoming = make_large_system(3);
+        // Re-use one existing episode so merge has something to skip
+        incoming.episodes.push(original.episodes[0].clone());
+        let json = export_json(&incoming).unwrap();
+
+        let summary = store
+            .import_json_reader(
+                json.as_bytes(),
+                ImportMode::Merge,
+                IdPolicy::Lenient,
+                |_| {},
+            )
+            .unwrap();
+        assert_eq!(summary.episodes, 3);
+        assert_eq!(summary.skipped_episodes, 1);
+        assert_eq!(summary.conscious_neighborhoods, 1);
+
+        let loaded = store.load_system().unwrap();
+        assert_eq!(loaded.agent_name, original.agent_name);
+        assert_eq!(loaded.episodes.len(), 4);
+        assert_eq!(loaded.conscious_episode.neighborhoods.len(), 2);
+        assert_eq!(loaded.conscious_episode.id, original.conscious_episode.id);
+    }
+
+    #[test]
+    fn test_replace_import_refuses_empty_export() {
+        let store = Store::open_in_memory().unwrap();
+        store.save_system(&make_system()).unwrap();
+
+        let json = export_json(&DAESystem::new("empty")).unwrap();
+        assert!(store.import_json_str(&json).is_err());
+        assert_eq!(store.load_system().unwrap().episodes.len(), 1);
+    }
+
+    #[test]
+    fn test_import_id_policy() {
+        let original = make_large_system(2);
+        let mut value: serde_json::Value =
+            serde_json::from_str(&export_json(&original).unwrap()).unwrap();
+        value["system"]["episodes"][1]["neighborhoods"][0]["occurrences"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("id");
+        let json = value.to_string();
+
+        let store = Store::open_in_memory().unwrap();
+        let err = store
+            .import_json_reader(
+                json.as_bytes(),
+                ImportMode::Replace,
+                IdPolicy::Strict,
+                |_| {},
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("has no id"), "{err}");
+        assert_eq!(
+            store.load_system().unwrap().n(),
+            0,
+            "strict failure must roll back"
+        );
+
+        let summary = store
+            .import_json_reader(
+                json.as_bytes(),
+                ImportMode::Replace,
+                IdPolicy::Lenient,
+                |_| {},
+            )
+            .unwrap();
+        assert_eq!(summary.report.repaired_ids, 1);
+
+        // Every other occurrence keeps its exported ID
+        let loaded = store.load_system().unwrap();
+        let kept = loaded
+            .episodes
+            .iter()
+            .flat_map(|e| e.all_occurrences())
+            .filter(|o| {
+                original
+                    .episodes
+                    .iter()
+                    .any(|e| e.all_occurrences().any(|p| p.id == o.id))
+            })
+            .count();
+        assert_eq!(
+            kept,
+            original
+                .episodes
+                .iter()
+                .map(|e| e.all_occurrences().count())
+                .sum::<usize>()
+                - 1
+        );
+    }
+
+    #[test]
+    fn test_import_clamps_future_timestamps_and_persists_original() {
+        let json = include_str!("../../am-core/tests/fixtures/future_dated_export.json");
+        let store = Store::open_in_memory().unwrap();
+        let summary = {
+            // 2026-03-13T00:00:00Z
+            let _clock = am_core::time::freeze_clock(1_773_360_000);
+            store
+                .import_json_reader(
+                    json.as_bytes(),
+                    ImportMode::Replace,
+                    IdPolicy::Strict,
+                    |_| {},
+                )
+                .unwrap()
+        };
+        assert_eq!(summary.report.clamped_timestamps, 1);
+
+        let loaded = store.load_system().unwrap();
+        let future = loaded
+            .episodes
+            .iter()
+            .find(|e| e.name == "from the future")
+            .unwrap();
+        assert_eq!(future.timestamp, "2026-03-13T00:00:00Z");
+        assert_eq!(
+            future.original_timestamp.as_deref(),
+            Some("2031-06-01T08:30:00Z")
+        );
+        let past = loaded
+            .episodes
+            .iter()
+            .find(|e| e.name == "from the past")
+            .unwrap();
+        assert_eq!(past.original_timestamp, None);
+    }
+
+    #[test]
+    fn test_import_validation_policy() {
+        let original = make_large_system(3);
+        let mut value: serde_json::Value =
+            serde_json::from_str(&export_json(&original).unwrap()).unwrap();
+        value["system"]["episodes"][1]["neighborhoods"][2]["seed"] =
+            serde_json::json!([0.0, 0.0, 0.0, 0.0]);
+        let json = value.to_string();
+
+        let store = Store::open_in_memory().unwrap();
+        let err = store
+            .import_json_reader(
+                json.as_bytes(),
+                ImportMode::Replace,
+                IdPolicy::Strict,
+                |_| {},
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("system.episodes[1] (\"episode-1\").neighborhoods[2]"),
+            "{err}"
+        );
+        assert_eq!(store.load_system().unwrap().n(), 0);
+
+        let options = ImportOptions {
+            ids: IdPolicy::Strict,
+            validation: ValidationPolicy::Lenient,
+        };
+        let summary = store
+            .import_json_reader_with(json.as_bytes(), ImportMode::Replace, options, |_| {})
+            .unwrap();
+        assert_eq!(summary.episodes, 3);
+        assert_eq!(summary.report.dropped_neighborhoods, 1);
+        assert_eq!(summary.report.errors.len(), 1);
+
+        let loaded = store.load_system().unwrap();
+        assert_eq!(loaded.episodes[1].neighborhoods.len(), 2);
+        assert_eq!(
+            loaded.n(),
+            original.n() - original.episodes[1].neighborhoods[2].occurrences.len()
+        );
+    }
 }
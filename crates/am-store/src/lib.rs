@@ -1,5 +1,7 @@
+pub mod archive;
 pub mod config;
 pub mod error;
+pub mod journal;
 pub mod json_bridge;
 pub mod memory_store;
 pub mod project;
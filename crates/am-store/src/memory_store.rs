@@ -4,6 +4,8 @@
 //! logic without requiring SQLite. Not intended for production use.
 
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use am_core::{
     activation_stats::ActivationStats,
@@ -12,8 +14,9 @@ use am_core::{
     phasor::DaemonPhasor,
     quaternion::Quaternion,
     serde_compat::{export_json, import_json},
-    store_trait::AmStore,
+    store_trait::{AmStore, BufferedExchange},
     system::DAESystem,
+    time::now_iso8601,
 };
 use uuid::Uuid;
 
@@ -35,12 +38,18 @@ pub enum MemoryStoreError {
 /// mirroring the SQLite round-trip behavior of `BrainStore`.
 pub struct InMemoryStore {
     state: Mutex<MemoryState>,
+    /// When set, every write fails, for testing how callers handle a
+    /// store that stops accepting changes.
+    poisoned: AtomicBool,
+    /// Extra time `load_system` takes, for testing servers that start
+    /// before a slow load finishes.
+    load_delay: Duration,
 }
 
 struct MemoryState {
     /// Serialized JSON representation of the system (None = empty store).
     system_json: Option<String>,
-    buffer: Vec<(String, String)>,
+    buffer: Vec<BufferedExchange>,
 }
 
 impl InMemoryStore {
@@ -52,6 +61,8 @@ impl InMemoryStore {
                 system_json: None,
                 buffer: Vec::new(),
             }),
+            poisoned: AtomicBool::new(false),
+            load_delay: Duration::ZERO,
         }
     }
 
@@ -67,12 +78,37 @@ impl InMemoryStore {
                 system_json: Some(json),
                 buffer: Vec::new(),
             }),
+            poisoned: AtomicBool::new(false),
+            load_delay: Duration::ZERO,
         }
     }
 
+    /// Make `load_system` sleep for `delay` before returning, simulating a
+    /// large brain. Writes and counts are not delayed.
+    #[must_use]
+    pub fn with_load_delay(mut self, delay: Duration) -> Self {
+        self.load_delay = delay;
+        self
+    }
+
+    /// Make every subsequent write fail (or succeed again). Reads and the
+    /// conversation buffer are unaffected.
+    pub fn poison_writes(&self, poisoned: bool) {
+        self.poisoned.store(poisoned, Ordering::Relaxed);
+    }
+
     fn load_system_inner(json: &str) -> Result<DAESystem, MemoryStoreError> {
         import_json(json).map_err(|e| MemoryStoreError::Other(format!("deserialize: {e}")))
     }
+
+    /// The stored system, without the simulated load delay.
+    fn stored_system(&self) -> Result<DAESystem, MemoryStoreError> {
+        let state = self.state.lock().unwrap();
+        match &state.system_json {
+            Some(json) => Self::load_system_inner(json),
+            None => Err(MemoryStoreError::Other("no system loaded".into())),
+        }
+    }
 }
 
 impl Default for InMemoryStore {
@@ -85,14 +121,16 @@ impl AmStore for InMemoryStore {
     type Error = MemoryStoreError;
 
     fn load_system(&self) -> Result<DAESystem, Self::Error> {
-        let state = self.state.lock().unwrap();
-        match &state.system_json {
-            Some(json) => Self::load_system_inner(json),
-            None => Err(MemoryStoreError::Other("no system loaded".into())),
+        if !self.load_delay.is_zero() {
+            std::thread::sleep(self.load_delay);
         }
+        self.stored_system()
     }
 
     fn save_system(&self, system: &DAESystem) -> Result<(), Self::Error> {
+        if self.poisoned.load(Ordering::Relaxed) {
+            return Err(MemoryStoreError::Other("store poisoned".into()));
+        }
         let json =
             export_json(system).map_err(|e| MemoryStoreError::Other(format!("serialize: {e}")))?;
         self.state.lock().unwrap().system_json = Some(json);
@@ -100,7 +138,7 @@ impl AmStore for InMemoryStore {
     }
 
     fn save_episode(&self, episode: &Episode) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
+        let mut system = self.stored_system()?;
         if let Some(pos) = system.episodes.iter().position(|e| e.id == episode.id) {
             system.episodes[pos] = episode.clone();
         } else {
@@ -114,7 +152,7 @@ impl AmStore for InMemoryStore {
         episode: &Episode,
         neighborhood: &Neighborhood,
     ) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
+        let mut system = self.stored_system()?;
 
         let ep = if episode.is_conscious {
             &mut system.conscious_episode
@@ -137,16 +175,16 @@ impl AmStore for InMemoryStore {
         self.save_system(&system)
     }
 
-    fn batch_increment_activation(&self, ids: &[Uuid]) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
-        for id in ids {
+    fn increment_activations(&self, deltas: &[(Uuid, u32)]) -> Result<(), Self::Error> {
+        let mut system = self.stored_system()?;
+        for (id, delta) in deltas {
             for ep in
                 std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut())
             {
                 for nbhd in &mut ep.neighborhoods {
                     for occ in &mut nbhd.occurrences {
                         if occ.id == *id {
-                            occ.activation_count += 1;
+                            occ.activation_count += delta;
                         }
                     }
                 }
@@ -156,7 +194,7 @@ impl AmStore for InMemoryStore {
     }
 
     fn batch_set_activation_counts(&self, batch: &[(Uuid, u32)]) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
+        let mut system = self.stored_system()?;
         for (id, count) in batch {
             for ep in
                 std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut())
@@ -173,12 +211,31 @@ impl AmStore for InMemoryStore {
         self.save_system(&system)
     }
 
+    fn save_activation_spillover(&self, batch: &[(Uuid, u32, f64)]) -> Result<(), Self::Error> {
+        let mut system = self.stored_system()?;
+        for (id, count, spillover) in batch {
+            for ep in
+                std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut())
+            {
+                for nbhd in &mut ep.neighborhoods {
+                    for occ in &mut nbhd.occurrences {
+                        if occ.id == *id {
+                            occ.activation_count = *count;
+                            occ.spillover = *spillover;
+                        }
+                    }
+                }
+            }
+        }
+        self.save_system(&system)
+    }
+
     fn save_occurrence_positions(
         &self,
-        batch: &[(Uuid, Quaternion, DaemonPhasor)],
+        batch: &[(Uuid, Quaternion, DaemonPhasor, f64)],
     ) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
-        for (id, pos, phasor) in batch {
+        let mut system = self.stored_system()?;
+        for (id, pos, phasor, drift) in batch {
             for ep in
                 std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut())
             {
@@ -187,6 +244,7 @@ impl AmStore for InMemoryStore {
                         if occ.id == *id {
                             occ.position = *pos;
                             occ.phasor = *phasor;
+                            occ.drift = *drift;
                         }
                     }
                 }
@@ -195,8 +253,24 @@ impl AmStore for InMemoryStore {
         self.save_system(&system)
     }
 
+    fn save_feedback_scores(&self, batch: &[(Uuid, f64)]) -> Result<(), Self::Error> {
+        let mut system = self.stored_system()?;
+        for (id, score) in batch {
+            for ep in
+                std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut())
+            {
+                for nbhd in &mut ep.neighborhoods {
+                    if nbhd.id == *id {
+                        nbhd.feedback_score = *score;
+                    }
+                }
+            }
+        }
+        self.save_system(&system)
+    }
+
     fn mark_superseded(&self, old_id: Uuid, new_id: Uuid) -> Result<(), Self::Error> {
-        let mut system = self.load_system()?;
+        let mut system = self.stored_system()?;
         for ep in std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut()) {
             for nbhd in &mut ep.neighborhoods {
                 if nbhd.id == old_id {
@@ -211,13 +285,33 @@ impl AmStore for InMemoryStore {
         )))
     }
 
+    fn restamp_neighborhood(&self, id: Uuid, created_at: &str) -> Result<(), Self::Error> {
+        let mut system = self.stored_system()?;
+        for ep in std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut()) {
+            for nbhd in &mut ep.neighborhoods {
+                if nbhd.id == id {
+                    nbhd.created_at = Some(created_at.to_owned());
+                    self.save_system(&system)?;
+                    return Ok(());
+                }
+            }
+        }
+        Err(MemoryStoreError::Other(format!(
+            "neighborhood not found: {id}"
+        )))
+    }
+
     fn append_buffer(&self, user: &str, assistant: &str) -> Result<usize, Self::Error> {
         let mut state = self.state.lock().unwrap();
-        state.buffer.push((user.to_owned(), assistant.to_owned()));
+        state.buffer.push(BufferedExchange {
+            user: user.to_owned(),
+            assistant: assistant.to_owned(),
+            buffered_at: now_iso8601(),
+        });
         Ok(state.buffer.len())
     }
 
-    fn drain_buffer(&self) -> Result<Vec<(String, String)>, Self::Error> {
+    fn drain_buffer(&self) -> Result<Vec<BufferedExchange>, Self::Error> {
         let mut state = self.state.lock().unwrap();
         Ok(std::mem::take(&mut state.buffer))
     }
@@ -227,7 +321,7 @@ impl AmStore for InMemoryStore {
     }
 
     fn activation_distribution(&self) -> Result<ActivationStats, Self::Error> {
-        let system = self.load_system()?;
+        let system = self.stored_system()?;
 
         let mut total: u64 = 0;
         let mut zero_count: u64 = 0;
@@ -279,27 +373,17 @@ impl AmStore for InMemoryStore {
             .parse()
             .map_err(|e| MemoryStoreError::Other(format!("invalid UUID: {e}")))?;
 
-        let mut system = self.load_system()?;
-
-        let pos = system.episodes.iter().position(|ep| ep.id == uuid);
-        match pos {
-            None => Ok(0),
-            Some(i) => {
-                if system.episodes[i].is_conscious {
-                    return Err(MemoryStoreError::Other(
-                        "use forget_conscious to remove conscious memories".into(),
-                    ));
-                }
-                let removed: u64 = system.episodes[i]
-                    .neighborhoods
-                    .iter()
-                    .map(|n| n.occurrences.len() as u64)
-                    .sum();
-                system.episodes.remove(i);
-                self.save_system(&system)?;
-                Ok(removed)
-            }
+        let mut system = self.stored_system()?;
+        if system.conscious_episode.id == uuid {
+            return Err(MemoryStoreError::Other(
+                "use forget_conscious to remove conscious memories".into(),
+            ));
         }
+        let removed = system.forget_episode(uuid);
+        if removed > 0 {
+            self.save_system(&system)?;
+        }
+        Ok(removed as u64)
     }
 
     fn forget_conscious(&self, neighborhood_id: &str) -> Result<u64, Self::Error> {
@@ -307,54 +391,19 @@ impl AmStore for InMemoryStore {
             .parse()
             .map_err(|e| MemoryStoreError::Other(format!("invalid UUID: {e}")))?;
 
-        let mut system = self.load_system()?;
-
-        let pos = system
-            .conscious_episode
-            .neighborhoods
-            .iter()
-            .position(|n| n.id == uuid);
-        match pos {
-            None => Ok(0),
-            Some(i) => {
-                let removed = system.conscious_episode.neighborhoods[i].occurrences.len() as u64;
-                system.conscious_episode.neighborhoods.remove(i);
-                self.save_system(&system)?;
-                Ok(removed)
-            }
+        let mut system = self.stored_system()?;
+        let removed = system.forget_conscious(uuid);
+        if removed > 0 {
+            self.save_system(&system)?;
         }
+        Ok(removed as u64)
     }
 
     fn forget_term(&self, term: &str) -> Result<(u64, u64, u64), Self::Error> {
-        let word_lower = term.to_lowercase();
-        let mut system = self.load_system()?;
-        let mut removed_occs: u64 = 0;
-
-        // Remove matching occurrences from all episodes (including conscious)
-        for ep in std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut()) {
-            for nbhd in &mut ep.neighborhoods {
-                let before = nbhd.occurrences.len();
-                nbhd.occurrences
-                    .retain(|occ| occ.word.to_lowercase() != word_lower);
-                removed_occs += (before - nbhd.occurrences.len()) as u64;
-            }
-        }
-
-        // Clean empty neighborhoods
-        let mut removed_nbhds: u64 = 0;
-        for ep in std::iter::once(&mut system.conscious_episode).chain(system.episodes.iter_mut()) {
-            let before = ep.neighborhoods.len();
-            ep.neighborhoods.retain(|n| !n.occurrences.is_empty());
-            removed_nbhds += (before - ep.neighborhoods.len()) as u64;
-        }
-
-        // Clean empty non-conscious episodes
-        let before = system.episodes.len();
-        system.episodes.retain(|ep| !ep.neighborhoods.is_empty());
-        let removed_eps = (before - system.episodes.len()) as u64;
-
+        let mut system = self.stored_system()?;
+        let (occurrences, neighborhoods, episodes) = system.forget_term(term);
         self.save_system(&system)?;
-        Ok((removed_occs, removed_nbhds, removed_eps))
+        Ok((occurrences as u64, neighborhoods as u64, episodes as u64))
     }
 
     fn import_json_str(&self, json: &str) -> Result<(), Self::Error> {
@@ -364,7 +413,7 @@ impl AmStore for InMemoryStore {
     }
 
     fn export_json_string(&self) -> Result<String, Self::Error> {
-        let system = self.load_system()?;
+        let system = self.stored_system()?;
         am_core::serde_compat::export_json(&system)
             .map_err(|e| MemoryStoreError::Other(format!("JSON export failed: {e}")))
     }
@@ -391,7 +440,8 @@ mod tests {
 
         let drained = store.drain_buffer().unwrap();
         assert_eq!(drained.len(), 1);
-        assert_eq!(drained[0], ("hello".to_owned(), "world".to_owned()));
+        assert_eq!(drained[0].user, "hello");
+        assert_eq!(drained[0].assistant, "world");
         assert_eq!(store.buffer_count().unwrap(), 0);
     }
 
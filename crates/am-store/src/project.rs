@@ -2,14 +2,28 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use am_core::{
-    activation_stats::ActivationStats, episode::Episode, neighborhood::Neighborhood,
-    phasor::DaemonPhasor, quaternion::Quaternion, store_trait::AmStore, system::DAESystem,
+    activation_stats::ActivationStats,
+    constants::{
+        DEFAULT_MAX_WORD_ACTIVATIONS, DEFAULT_SALIENT_CHUNK_CHARS, DEFAULT_SALIENT_DEDUP_THRESHOLD,
+    },
+    episode::Episode,
+    neighborhood::Neighborhood,
+    phasor::DaemonPhasor,
+    quaternion::Quaternion,
+    store_trait::{AmStore, AuditEvent, BufferedExchange, StoreErrorKind},
+    system::{DAESystem, GcDetail},
 };
 use uuid::Uuid;
 
-use crate::config::Config;
+use crate::config::{Config, ProjectConfig, RetentionPolicy};
 use crate::error::{Result, StoreError};
-use crate::store::Store;
+use crate::journal::{
+    ConsciousJournal, JournalEntry, RecoverReport, forgotten_ids, journal_file_name, read_journal,
+    recover_conscious,
+};
+use crate::store::{
+    DEFAULT_BRAIN, ForgetReport, RestoreReport, Store, UndoReport, validate_brain_name,
+};
 
 /// Default base directory for all am storage.
 ///
@@ -71,13 +85,39 @@ pub(crate) fn run_gc(store: &Store, config: &Config) {
     }
 }
 
+/// Move cold subconscious episodes into epoch files when `brain.db` has
+/// grown past `epoch_size_mb`. Failures are logged, never fatal.
+pub(crate) fn run_epochs(store: &Store, config: &Config) {
+    let db_size = store.db_size();
+    if db_size < config.epoch_size_mb * 1024 * 1024 {
+        return;
+    }
+
+    match store.epoch_pass(
+        am_core::constants::ACTIVATION_FLOOR,
+        &config.retention,
+        true,
+    ) {
+        Ok(report) => tracing::info!(
+            "epoch pass: moved {} episodes into {} epoch files. DB: {}MB -> {}MB",
+            report.episodes(),
+            report.moved.len(),
+            report.before_size / (1024 * 1024),
+            report.after_size / (1024 * 1024),
+        ),
+        Err(e) => tracing::warn!("epoch pass failed: {e}"),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Migration - one-time merge from old multi-DB layout to single brain.db
 // ---------------------------------------------------------------------------
 
 /// Migrate the old `projects/*.db` + `global.db` layout into a single `brain.db`.
 ///
-/// Only runs when `projects/` exists and `brain.db` does not. After merging,
+/// Only runs when `projects/` or `global.db` exists and `brain.db` does
+/// not. Conscious memories are merged by ID, so one replicated into both
+/// a project DB and `global.db` lands once. After merging,
 /// renames `projects/` to `projects.migrated/` and `global.db` to
 /// `global.db.migrated` (belt and suspenders - never deletes).
 fn migrate_old_layout(base: &Path, brain_path: &Path) {
@@ -175,7 +215,9 @@ fn migrate_old_layout(base: &Path, brain_path: &Path) {
 
     // Rename old dirs to .migrated (don't delete - belt and suspenders)
     let migrated_dir = base.join("projects.migrated");
-    if let Err(e) = fs::rename(&projects_dir, &migrated_dir) {
+    if projects_dir.exists()
+        && let Err(e) = fs::rename(&projects_dir, &migrated_dir)
+    {
         tracing::warn!("failed to rename projects/ → projects.migrated/: {e}");
     }
     if global_path.exists() {
@@ -192,6 +234,13 @@ fn migrate_old_layout(base: &Path, brain_path: &Path) {
     );
 }
 
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // BrainStore - single brain.db for all developer memory
 // ---------------------------------------------------------------------------
@@ -201,49 +250,182 @@ fn migrate_old_layout(base: &Path, brain_path: &Path) {
 /// Layout:
 /// ```text
 /// ~/.attention-matters/
-/// └── brain.db          # unified brain - one product, one memory
+/// ├── brain.db          # unified brain - one product, one memory
+/// └── conscious.journal # append-only copy of salient memories
 /// ```
+///
+/// Several named brains can share `brain.db` (see [`BrainStore::open_named`]);
+/// each non-default brain journals to `conscious.<brain>.journal`.
 pub struct BrainStore {
     store: Store,
+    journal: Option<ConsciousJournal>,
+    actor: Option<String>,
+    dedup_neighborhoods: bool,
+    activation_spillover: f64,
+    max_word_activations: usize,
+    salient_chunk_chars: usize,
+    salient_dedup_threshold: f64,
+    project: ProjectConfig,
+    retention: RetentionPolicy,
 }
 
 impl BrainStore {
     /// Open the brain store using the provided configuration.
     pub fn open(config: &Config) -> Result<Self> {
+        Self::open_named(config, DEFAULT_BRAIN)
+    }
+
+    /// Open the brain named `brain` inside the shared `brain.db`. Every
+    /// query is scoped to that brain, so tenants sharing the file never see
+    /// each other's memories; conscious memories are global only within
+    /// their own brain.
+    pub fn open_named(config: &Config, brain: &str) -> Result<Self> {
+        validate_brain_name(brain)?;
         let base = &config.data_dir;
         fs::create_dir_all(base)?;
 
         let brain_path = base.join("brain.db");
 
-        // Startup migration: if old layout exists, merge into brain.db
-        let projects_dir = base.join("projects");
-        if projects_dir.exists() {
+        // Startup migration: if old layout exists, merge into brain.db.
+        // Those memories predate named brains and belong to the default one.
+        // A layout with only global.db still holds conscious memories, and
+        // this merge is the only place they are read: brain.db is shared by
+        // every project, so there is no global store to consult at load.
+        let legacy = base.join("projects").exists() || base.join("global.db").exists();
+        if brain == DEFAULT_BRAIN && legacy {
             migrate_old_layout(base, &brain_path);
         }
 
-        let store = Store::open(&brain_path)?;
+        let store = Self::configure(
+            Store::open_with(&brain_path, &config.sqlite)?,
+            config,
+            brain,
+        )?;
 
         if config.gc_enabled {
             run_gc(&store, config);
         }
+        if config.epoch_size_mb > 0 {
+            run_epochs(&store, config);
+        }
+
+        let journal = config
+            .conscious_journal
+            .then(|| ConsciousJournal::new(base.join(journal_file_name(brain))));
+
+        Ok(Self::from_parts(store, journal, config))
+    }
+
+    /// Open the brain named `brain` without write access, for inspection
+    /// that must not block a running `am serve`. Skips the startup layout
+    /// migration, GC, and epoch moves, and keeps no journal; writes through
+    /// the returned store fail.
+    pub fn open_readonly(config: &Config, brain: &str) -> Result<Self> {
+        validate_brain_name(brain)?;
+        fs::create_dir_all(&config.data_dir)?;
+        let brain_path = config.data_dir.join("brain.db");
+        let store = Store::open_with(&brain_path, &config.sqlite.read_only())?;
+        let store = Self::configure(store, config, brain)?;
+        Ok(Self::from_parts(store, None, config))
+    }
+
+    /// Apply the per-store settings from `config` to a freshly opened store.
+    fn configure(store: Store, config: &Config, brain: &str) -> Result<Store> {
+        Ok(store
+            .with_brain(brain)?
+            .with_resurrect_warn(config.gc_resurrect_warn)
+            .with_retry(config.retry)
+            .with_undo(config.undo)
+            .with_quarantine_days(config.quarantine_days)
+            .with_text_compression(
+                (config.compress_text_min_bytes > 0).then_some(config.compress_text_min_bytes),
+            ))
+    }
 
-        Ok(Self { store })
+    fn from_parts(store: Store, journal: Option<ConsciousJournal>, config: &Config) -> Self {
+        Self {
+            store,
+            journal,
+            actor: config.actor.clone(),
+            dedup_neighborhoods: config.dedup_neighborhoods,
+            activation_spillover: config.activation_spillover,
+            max_word_activations: config.max_word_activations,
+            salient_chunk_chars: config.salient_chunk_chars,
+            salient_dedup_threshold: config.salient_dedup_threshold,
+            project: config.project.clone(),
+            retention: config.retention.clone(),
+        }
     }
 
     /// Open with an in-memory store (for testing).
     pub fn open_in_memory() -> Result<Self> {
         Ok(Self {
             store: Store::open_in_memory()?,
+            journal: None,
+            actor: None,
+            dedup_neighborhoods: false,
+            activation_spillover: 0.0,
+            max_word_activations: DEFAULT_MAX_WORD_ACTIVATIONS,
+            salient_chunk_chars: DEFAULT_SALIENT_CHUNK_CHARS,
+            salient_dedup_threshold: DEFAULT_SALIENT_DEDUP_THRESHOLD,
+            project: ProjectConfig::default(),
+            retention: RetentionPolicy::default(),
         })
     }
 
+    /// Replace the retention policy that [`AmStore::gc_pass`] applies.
+    #[must_use]
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
     pub fn store(&self) -> &Store {
         &self.store
     }
 
-    /// Load the full DAESystem from brain.db.
+    /// Configured actor stamped on systems this store loads, if any.
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// The conscious journal, if enabled.
+    pub fn journal(&self) -> Option<&ConsciousJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Append a conscious neighborhood to the journal, if enabled. A failed
+    /// write is logged, never fatal: the database remains the primary copy.
+    fn journal_conscious(&self, neighborhood: &Neighborhood) {
+        self.journal_append(&JournalEntry::for_neighborhood(neighborhood));
+    }
+
+    fn journal_append(&self, entry: &JournalEntry) {
+        if let Some(journal) = &self.journal
+            && let Err(e) = journal.append(entry)
+        {
+            tracing::warn!("failed to append to {}: {e}", journal.path().display());
+        }
+    }
+
+    /// Load the full DAESystem from brain.db, attributing new memories to
+    /// the configured actor and applying the configured neighborhood dedup,
+    /// activation spillover, word activation cap, salient chunking, and
+    /// project affinity.
     pub fn load_system(&self) -> Result<DAESystem> {
-        self.store.load_system()
+        let mut system = self.store.load_system()?;
+        if self.actor.is_some() {
+            system.set_actor(self.actor.clone());
+        }
+        system.set_dedup_neighborhoods(self.dedup_neighborhoods);
+        system.set_activation_spillover(self.activation_spillover);
+        system.set_max_word_activations(self.max_word_activations);
+        system.set_salient_chunk_chars(self.salient_chunk_chars);
+        system.set_salient_dedup_threshold(self.salient_dedup_threshold);
+        system.set_project(self.project.name.clone());
+        system.set_project_discount(self.project.discount);
+        system.set_tag_conscious_project(self.project.tag_conscious);
+        Ok(system)
     }
 
     /// Save a full DAESystem to brain.db (DELETE + reinsert all data).
@@ -251,7 +433,7 @@ impl BrainStore {
     /// Reserved for operations that replace the entire system state:
     /// import, CLI batch ingest, and data migration. MCP hot-path handlers
     /// should use targeted writes (`save_episode`, `save_neighborhood`,
-    /// `save_occurrence_positions`, `batch_increment_activation`).
+    /// `save_occurrence_positions`, `increment_activations`).
     pub fn save_system(&self, system: &DAESystem) -> Result<()> {
         self.store.save_system(system)
     }
@@ -262,13 +444,17 @@ impl BrainStore {
     }
 
     /// Persist a single neighborhood under an episode, creating the episode
-    /// row if needed.
+    /// row if needed. Conscious neighborhoods are also journaled.
     pub fn save_neighborhood(
         &self,
         episode: &am_core::episode::Episode,
         neighborhood: &am_core::neighborhood::Neighborhood,
     ) -> Result<()> {
-        self.store.save_neighborhood(episode, neighborhood)
+        self.store.save_neighborhood(episode, neighborhood)?;
+        if episode.is_conscious {
+            self.journal_conscious(neighborhood);
+        }
+        Ok(())
     }
 
     /// Increment activation counts for a batch of occurrences.
@@ -276,18 +462,36 @@ impl BrainStore {
         self.store.batch_increment_activation(ids)
     }
 
+    /// Add per-occurrence deltas to activation counts in one transaction.
+    pub fn increment_activations(&self, deltas: &[(uuid::Uuid, u32)]) -> Result<()> {
+        self.store.increment_activations(deltas)
+    }
+
     /// Set activation counts to absolute values for a batch of occurrences.
     pub fn batch_set_activation_counts(&self, batch: &[(uuid::Uuid, u32)]) -> Result<()> {
         self.store.batch_set_activation_counts(batch)
     }
 
-    /// Persist position and phasor updates for a batch of occurrences.
+    /// Set activation counts and spillover residues for occurrences
+    /// credited by activation spillover.
+    pub fn save_activation_spillover(&self, batch: &[(uuid::Uuid, u32, f64)]) -> Result<()> {
+        self.store.save_activation_spillover(batch)
+    }
+
+    /// Set feedback scores to absolute values for a batch of neighborhoods.
+    pub fn save_feedback_scores(&self, batch: &[(uuid::Uuid, f64)]) -> Result<()> {
+        self.store.save_feedback_scores(batch)
+    }
+
+    /// Persist position, phasor, and accumulated drift updates for a
+    /// batch of occurrences.
     pub fn save_occurrence_positions(
         &self,
         batch: &[(
             uuid::Uuid,
             am_core::quaternion::Quaternion,
             am_core::phasor::DaemonPhasor,
+            f64,
         )],
     ) -> Result<()> {
         self.store.save_occurrence_positions(batch)
@@ -308,9 +512,119 @@ impl BrainStore {
         // Intentional save_system: convenience method for CLI/test use.
         // The MCP handler (am_salient) uses targeted save_neighborhood.
         self.store.save_system(system)?;
+        if let Some(nbhd) = system.conscious_episode.neighborhoods.last() {
+            self.journal_conscious(nbhd);
+        }
         Ok(nbhd_id)
     }
 
+    /// Replay a conscious journal into this store, skipping memories it
+    /// already holds. Restored memories are appended to this store's own
+    /// journal unless `path` is that journal.
+    pub fn recover_conscious(
+        &self,
+        path: &Path,
+        rng: &mut impl rand::Rng,
+    ) -> Result<RecoverReport> {
+        let entries = read_journal(path)?;
+        let mut system = self.store.load_system()?;
+        let report = recover_conscious(&mut system, &entries, rng);
+
+        let rejournal = self.journal.as_ref().filter(|j| !same_file(j.path(), path));
+        for nbhd in &system.conscious_episode.neighborhoods {
+            if !report.restored.contains(&nbhd.id) {
+                continue;
+            }
+            self.store
+                .save_neighborhood(&system.conscious_episode, nbhd)?;
+            if let Some(journal) = rejournal {
+                journal.append(&JournalEntry::for_neighborhood(nbhd))?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Delete a conscious neighborhood by UUID and append a tombstone to
+    /// the journal, so recovering from the journal does not bring it back.
+    /// With `keep_journal` the journal is left alone and recovery can
+    /// restore the memory. Returns the occurrences removed, 0 if not found.
+    pub fn forget_conscious_with(&self, neighborhood_id: &str, keep_journal: bool) -> Result<u64> {
+        let removed = self.store.forget_conscious(neighborhood_id)?;
+        if removed > 0
+            && !keep_journal
+            && let Ok(id) = Uuid::parse_str(neighborhood_id)
+        {
+            self.journal_append(&JournalEntry::forgotten(id));
+        }
+        Ok(removed)
+    }
+
+    /// Delete neighborhoods by ID (see [`Store::forget_neighborhoods`]),
+    /// appending a journal tombstone for each conscious one removed.
+    pub fn forget_neighborhoods(&self, ids: &[Uuid], detail: &str) -> Result<ForgetReport> {
+        let report = self.store.forget_neighborhoods(ids, detail)?;
+        for id in &report.conscious {
+            self.journal_append(&JournalEntry::forgotten(*id));
+        }
+        Ok(report)
+    }
+
+    /// Put back what the newest undo entry holds (see
+    /// [`Store::undo_last`]). A restored conscious memory the journal has
+    /// as forgotten is journaled again, so recovery keeps it.
+    pub fn undo_last(&self) -> Result<Option<UndoReport>> {
+        let report = self.store.undo_last()?;
+        if report.as_ref().is_some_and(|r| r.neighborhoods > 0) {
+            self.rejournal_forgotten()?;
+        }
+        Ok(report)
+    }
+
+    /// Put back forgets held in quarantine (see
+    /// [`Store::restore_forgotten`]), journaling restored conscious
+    /// memories again as [`Self::undo_last`] does.
+    pub fn restore_forgotten(&self, ids: &[i64]) -> Result<RestoreReport> {
+        let report = self.store.restore_forgotten(ids)?;
+        if report.neighborhoods > 0 {
+            self.rejournal_forgotten()?;
+        }
+        Ok(report)
+    }
+
+    /// Journal every live conscious memory whose latest journal entry is a
+    /// tombstone.
+    fn rejournal_forgotten(&self) -> Result<()> {
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+        if !journal.path().exists() {
+            return Ok(());
+        }
+        let forgotten = forgotten_ids(&read_journal(journal.path())?);
+        if forgotten.is_empty() {
+            return Ok(());
+        }
+        for info in self.store.list_conscious_neighborhoods()? {
+            let Ok(id) = Uuid::parse_str(&info.id) else {
+                continue;
+            };
+            if !forgotten.contains(&id) {
+                continue;
+            }
+            self.journal_append(&JournalEntry {
+                timestamp: info.created_at.unwrap_or_else(am_core::time::now_iso8601),
+                neighborhood_type: info.neighborhood_type,
+                text: info.source_text,
+                id,
+                actor: info.actor,
+                project: info.project,
+                group_id: info.group_id.and_then(|g| Uuid::parse_str(&g).ok()),
+                forgotten: false,
+            });
+        }
+        Ok(())
+    }
+
     /// Import a v0.7.2 JSON file into the brain store.
     pub fn import_json_file(&self, path: &Path) -> Result<()> {
         self.store.import_json_file(path)
@@ -325,43 +639,85 @@ impl BrainStore {
 impl AmStore for BrainStore {
     type Error = StoreError;
 
+    fn error_kind(error: &StoreError) -> StoreErrorKind {
+        error.kind()
+    }
+
     fn load_system(&self) -> Result<DAESystem> {
-        self.store.load_system()
+        BrainStore::load_system(self)
+    }
+
+    fn load_archived_episodes(&self) -> Result<Vec<Episode>> {
+        self.store.load_archived_episodes()
+    }
+
+    fn load_epoch_episodes(&self) -> Result<Vec<Episode>> {
+        self.store.load_epoch_episodes()
+    }
+
+    fn has_epochs(&self) -> Result<bool> {
+        Ok(!self.store.epoch_files()?.is_empty())
+    }
+
+    fn audited<T>(&self, event: &AuditEvent, write: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.store.audited(event, write)
     }
 
     fn save_system(&self, system: &DAESystem) -> Result<()> {
         self.store.save_system(system)
     }
 
+    fn gc_pass(&self, activation_floor: u32) -> Result<GcDetail> {
+        Ok(self
+            .store
+            .gc_pass(activation_floor, &self.retention)?
+            .detail)
+    }
+
     fn save_episode(&self, episode: &Episode) -> Result<()> {
         self.store.save_episode(episode)
     }
 
     fn save_neighborhood(&self, episode: &Episode, neighborhood: &Neighborhood) -> Result<()> {
-        self.store.save_neighborhood(episode, neighborhood)
+        BrainStore::save_neighborhood(self, episode, neighborhood)
     }
 
-    fn batch_increment_activation(&self, ids: &[Uuid]) -> Result<()> {
-        self.store.batch_increment_activation(ids)
+    fn increment_activations(&self, deltas: &[(Uuid, u32)]) -> Result<()> {
+        self.store.increment_activations(deltas)
     }
 
     fn batch_set_activation_counts(&self, batch: &[(Uuid, u32)]) -> Result<()> {
         self.store.batch_set_activation_counts(batch)
     }
 
-    fn save_occurrence_positions(&self, batch: &[(Uuid, Quaternion, DaemonPhasor)]) -> Result<()> {
+    fn save_activation_spillover(&self, batch: &[(Uuid, u32, f64)]) -> Result<()> {
+        self.store.save_activation_spillover(batch)
+    }
+
+    fn save_occurrence_positions(
+        &self,
+        batch: &[(Uuid, Quaternion, DaemonPhasor, f64)],
+    ) -> Result<()> {
         self.store.save_occurrence_positions(batch)
     }
 
+    fn save_feedback_scores(&self, batch: &[(Uuid, f64)]) -> Result<()> {
+        self.store.save_feedback_scores(batch)
+    }
+
     fn mark_superseded(&self, old_id: Uuid, new_id: Uuid) -> Result<()> {
         self.store.mark_superseded(old_id, new_id)
     }
 
+    fn restamp_neighborhood(&self, id: Uuid, created_at: &str) -> Result<()> {
+        self.store.restamp_neighborhood(id, created_at)
+    }
+
     fn append_buffer(&self, user: &str, assistant: &str) -> Result<usize> {
         self.store.append_buffer(user, assistant)
     }
 
-    fn drain_buffer(&self) -> Result<Vec<(String, String)>> {
+    fn drain_buffer(&self) -> Result<Vec<BufferedExchange>> {
         self.store.drain_buffer()
     }
 
@@ -373,10 +729,18 @@ impl AmStore for BrainStore {
         self.store.activation_distribution()
     }
 
+    fn occurrence_count(&self) -> Result<u64> {
+        self.store.occurrence_count()
+    }
+
     fn db_size(&self) -> u64 {
         self.store.db_size()
     }
 
+    fn repaired_records(&self) -> u64 {
+        self.store.repaired_records()
+    }
+
     fn health_check(&self) -> Result<()> {
         self.store.health_check()
     }
@@ -390,7 +754,7 @@ impl AmStore for BrainStore {
     }
 
     fn forget_conscious(&self, neighborhood_id: &str) -> Result<u64> {
-        self.store.forget_conscious(neighborhood_id)
+        self.forget_conscious_with(neighborhood_id, false)
     }
 
     fn forget_term(&self, term: &str) -> Result<(u64, u64, u64)> {
@@ -409,7 +773,11 @@ impl AmStore for BrainStore {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use am_core::{episode::Episode, neighborhood::Neighborhood};
+    use crate::journal::JOURNAL_FILE_NAME;
+    use am_core::{
+        episode::Episode,
+        neighborhood::{Neighborhood, NeighborhoodType},
+    };
     use rand::SeedableRng;
     use rand::rngs::SmallRng;
 
@@ -462,6 +830,349 @@ mod tests {
         assert_eq!(loaded.n(), sys.n());
     }
 
+    #[test]
+    fn test_recover_conscious_after_losing_the_database() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        let written = {
+            let bs = BrainStore::open(&config).unwrap();
+            let mut sys = bs.load_system().unwrap();
+            sys.add_to_conscious_typed(
+                "DECISION: keep sqlite",
+                NeighborhoodType::Decision,
+                &mut rng,
+            );
+            sys.add_to_conscious_typed(
+                "PREFERENCE: short commits",
+                NeighborhoodType::Preference,
+                &mut rng,
+            );
+            for nbhd in &sys.conscious_episode.neighborhoods {
+                bs.save_neighborhood(&sys.conscious_episode, nbhd).unwrap();
+            }
+            bs.mark_salient(&mut sys, "insight about caching", &mut rng)
+                .unwrap();
+            sys.conscious_episode
+                .neighborhoods
+                .iter()
+                .map(|n| (n.id, n.source_text.clone(), n.neighborhood_type))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(written.len(), 3);
+
+        for name in ["brain.db", "brain.db-wal", "brain.db-shm"] {
+            let _ = fs::remove_file(dir.path().join(name));
+        }
+
+        let bs = BrainStore::open(&config).unwrap();
+        assert_eq!(
+            bs.load_system()
+                .unwrap()
+                .conscious_episode
+                .neighborhoods
+                .len(),
+            0
+        );
+        let journal = dir.path().join(JOURNAL_FILE_NAME);
+        let report = bs.recover_conscious(&journal, &mut rng).unwrap();
+        assert_eq!(report.restored.len(), 3);
+
+        let recovered = bs.load_system().unwrap();
+        for (id, text, ty) in &written {
+            let nbhd = recovered
+                .conscious_episode
+                .neighborhoods
+                .iter()
+                .find(|n| n.id == *id)
+                .expect("recovered memory keeps its ID");
+            assert_eq!(&nbhd.source_text, text);
+            assert_eq!(nbhd.neighborhood_type, *ty);
+        }
+
+        // Replaying again is a no-op and does not grow the journal
+        let lines = fs::read_to_string(&journal).unwrap().lines().count();
+        let again = bs.recover_conscious(&journal, &mut rng).unwrap();
+        assert!(again.restored.is_empty());
+        assert_eq!(again.skipped, 3);
+        assert_eq!(fs::read_to_string(&journal).unwrap().lines().count(), lines);
+    }
+
+    #[test]
+    fn test_forgotten_conscious_stays_forgotten_through_recovery() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut rng = SmallRng::seed_from_u64(8);
+
+        let [forgotten, kept, undone] = {
+            let bs = BrainStore::open(&config).unwrap();
+            let mut sys = bs.load_system().unwrap();
+            let ids = ["stale insight", "journal-only insight", "undone insight"]
+                .map(|text| bs.mark_salient(&mut sys, text, &mut rng).unwrap());
+            let [forgotten, kept, undone] = ids.map(|id| id.to_string());
+            assert!(AmStore::forget_conscious(&bs, &forgotten).unwrap() > 0);
+            assert!(bs.forget_conscious_with(&kept, true).unwrap() > 0);
+            assert!(bs.forget_conscious_with(&undone, false).unwrap() > 0);
+            // The undo puts the memory back and journals it again
+            assert!(bs.undo_last().unwrap().is_some());
+            ids
+        };
+
+        for name in ["brain.db", "brain.db-wal", "brain.db-shm"] {
+            let _ = fs::remove_file(dir.path().join(name));
+        }
+        let bs = BrainStore::open(&config).unwrap();
+        let journal = dir.path().join(JOURNAL_FILE_NAME);
+        let report = bs.recover_conscious(&journal, &mut rng).unwrap();
+        assert_eq!(report.restored, vec![kept, undone]);
+        assert_eq!(report.forgotten, 1);
+        let ids: Vec<_> = bs
+            .load_system()
+            .unwrap()
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.id)
+            .collect();
+        assert!(!ids.contains(&forgotten));
+        assert_eq!(ids.len(), 2);
+    }
+
+    #[test]
+    fn test_conscious_journal_can_be_disabled() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_path_buf(),
+            conscious_journal: false,
+            ..Config::default()
+        };
+        let bs = BrainStore::open(&config).unwrap();
+        let mut sys = bs.load_system().unwrap();
+        let mut rng = SmallRng::seed_from_u64(8);
+        bs.mark_salient(&mut sys, "not journaled", &mut rng)
+            .unwrap();
+        assert!(bs.journal().is_none());
+        assert!(!dir.path().join(JOURNAL_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_named_brains_keep_separate_conscious_memory_and_journals() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut rng = SmallRng::seed_from_u64(9);
+        let alice = BrainStore::open_named(&config, "alice").unwrap();
+        let mut sys = alice.load_system().unwrap();
+        alice
+            .mark_salient(&mut sys, "alice ships on fridays", &mut rng)
+            .unwrap();
+
+        let default = BrainStore::open(&config).unwrap();
+        assert!(
+            default
+                .load_system()
+                .unwrap()
+                .conscious_episode
+                .neighborhoods
+                .is_empty()
+        );
+        assert!(dir.path().join("conscious.alice.journal").exists());
+        assert!(!dir.path().join(JOURNAL_FILE_NAME).exists());
+        assert!(BrainStore::open_named(&config, "a/b").is_err());
+    }
+
+    #[test]
+    fn test_configured_actor_attributes_new_memories() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let open_as = |actor: &str| {
+            BrainStore::open(&Config {
+                data_dir: dir.path().to_path_buf(),
+                actor: Some(actor.to_string()),
+                ..Config::default()
+            })
+            .unwrap()
+        };
+        let mut rng = SmallRng::seed_from_u64(9);
+
+        for actor in ["claude-code", "codex"] {
+            let bs = open_as(actor);
+            let mut sys = bs.load_system().unwrap();
+            let episode = am_core::tokenizer::ingest_text("session notes", None, &mut rng);
+            sys.add_episode(episode);
+            bs.save_episode(sys.episodes.last().unwrap()).unwrap();
+            bs.mark_salient(&mut sys, &format!("insight from {actor}"), &mut rng)
+                .unwrap();
+        }
+
+        let sys = open_as("reader").load_system().unwrap();
+        let episode_actors: Vec<_> = sys.episodes.iter().map(|e| e.actor.as_deref()).collect();
+        assert_eq!(episode_actors, vec![Some("claude-code"), Some("codex")]);
+        let conscious_actors: Vec<_> = sys
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.actor.as_deref())
+            .collect();
+        assert_eq!(conscious_actors, vec![Some("claude-code"), Some("codex")]);
+        assert_eq!(sys.actor(), Some("reader"));
+    }
+
+    #[test]
+    fn test_configured_dedup_stores_shared_chunk_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bs = BrainStore::open(&Config {
+            data_dir: dir.path().to_path_buf(),
+            dedup_neighborhoods: true,
+            ..Config::default()
+        })
+        .unwrap();
+        let shared = "The deploy runs nightly. It tags the release. It notifies the channel.";
+        let mut rng = SmallRng::seed_from_u64(9);
+        for rest in [
+            "Docs explain rollbacks. They list owners. They link dashboards.",
+            "The session asked about tags. We checked the job. It was green.",
+        ] {
+            let mut sys = bs.load_system().unwrap();
+            let episode =
+                am_core::tokenizer::ingest_text(&format!("{shared} {rest}"), None, &mut rng);
+            sys.add_episode(episode);
+            bs.save_episode(sys.episodes.last().unwrap()).unwrap();
+        }
+
+        let sys = bs.load_system().unwrap();
+        assert!(sys.dedup_neighborhoods());
+        let copies = sys
+            .episodes
+            .iter()
+            .flat_map(|e| &e.neighborhoods)
+            .filter(|n| n.source_text == shared)
+            .count();
+        assert_eq!(copies, 1);
+        let shared_id = sys.episodes[0].neighborhoods[0].id;
+        assert_eq!(sys.episodes[1].reused_neighborhoods, vec![shared_id]);
+        assert_eq!(sys.episodes[1].neighborhoods.len(), 1);
+    }
+
+    /// A legacy store at `path` holding one conscious memory per text.
+    fn legacy_conscious(path: &Path, texts: &[&str], ids: &[uuid::Uuid]) {
+        let mut rng = rng();
+        let mut sys = DAESystem::new("legacy");
+        for (text, id) in texts.iter().zip(ids) {
+            sys.add_to_conscious(text, &mut rng);
+            let nbhd = sys.conscious_episode.neighborhoods.last_mut().unwrap();
+            nbhd.id = *id;
+            for occ in &mut nbhd.occurrences {
+                occ.neighborhood_id = *id;
+            }
+        }
+        Store::open(path).unwrap().save_system(&sys).unwrap();
+    }
+
+    fn conscious_ids(bs: &BrainStore) -> Vec<uuid::Uuid> {
+        let mut ids: Vec<_> = bs
+            .load_system()
+            .unwrap()
+            .conscious_episode
+            .neighborhoods
+            .iter()
+            .map(|n| n.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_legacy_global_conscious_merges_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (shared, global_only) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        fs::create_dir_all(dir.path().join("projects")).unwrap();
+        legacy_conscious(
+            &dir.path().join("projects/repo-a.db"),
+            &["tabs over spaces"],
+            &[shared],
+        );
+        legacy_conscious(
+            &dir.path().join("global.db"),
+            &["tabs over spaces", "prefer sqlite for local state"],
+            &[shared, global_only],
+        );
+
+        let bs = BrainStore::open(&Config {
+            data_dir: dir.path().to_path_buf(),
+            ..Config::default()
+        })
+        .unwrap();
+        let mut expected = vec![shared, global_only];
+        expected.sort();
+        assert_eq!(conscious_ids(&bs), expected);
+        assert!(dir.path().join("global.db.migrated").exists());
+        assert!(dir.path().join("projects.migrated").exists());
+    }
+
+    #[test]
+    fn test_legacy_global_db_alone_is_migrated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let id = uuid::Uuid::new_v4();
+        legacy_conscious(
+            &dir.path().join("global.db"),
+            &["prefer sqlite for local state"],
+            &[id],
+        );
+
+        let bs = BrainStore::open(&Config {
+            data_dir: dir.path().to_path_buf(),
+            ..Config::default()
+        })
+        .unwrap();
+        assert_eq!(conscious_ids(&bs), vec![id]);
+        assert!(!dir.path().join("global.db").exists());
+        assert!(!dir.path().join("projects.migrated").exists());
+    }
+
+    #[test]
+    fn test_conscious_memory_recalls_in_another_project() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let open_in = |project: &str| {
+            BrainStore::open(&Config {
+                data_dir: dir.path().to_path_buf(),
+                project: ProjectConfig {
+                    name: Some(project.to_string()),
+                    tag_conscious: true,
+                    ..ProjectConfig::default()
+                },
+                ..Config::default()
+            })
+            .unwrap()
+        };
+        let mut rng = rng();
+        let bs = open_in("repo-a");
+        let mut sys = bs.load_system().unwrap();
+        bs.mark_salient(
+            &mut sys,
+            "retries use exponential backoff with jitter",
+            &mut rng,
+        )
+        .unwrap();
+        drop(bs);
+
+        let mut sys = open_in("repo-b").load_system().unwrap();
+        assert_eq!(sys.project(), Some("repo-b"));
+        let result = am_core::query::QueryEngine::process_query(&mut sys, "backoff jitter");
+        let surface = am_core::surface::compute_surface(&sys, &result);
+        let context = am_core::compose::compose_context(&mut sys, &surface, &result, None);
+        assert_eq!(context.metrics.conscious, 1);
+        assert!(context.context.contains("exponential backoff"));
+    }
+
     #[test]
     fn test_directory_creation() {
         let dir = std::env::temp_dir().join("am-brain-store-test-dirs");
@@ -3,7 +3,69 @@ use rusqlite::Connection;
 
 use crate::error::Result;
 
-pub const SCHEMA_VERSION: i64 = 7;
+pub const SCHEMA_VERSION: i64 = 28;
+
+/// Column definitions of `episodes`, shared by table creation and the
+/// v28 rebuild. IDs are unique per brain, so one export imports into
+/// several brains of a file.
+const EPISODES_COLUMNS: &str = "
+    id           TEXT NOT NULL,
+    name         TEXT NOT NULL,
+    is_conscious INTEGER NOT NULL DEFAULT 0,
+    timestamp    TEXT NOT NULL DEFAULT '',
+    archived     INTEGER NOT NULL DEFAULT 0,
+    actor        TEXT,
+    brain_id     TEXT NOT NULL DEFAULT 'default',
+    original_timestamp TEXT,
+    reused_neighborhoods TEXT,
+    PRIMARY KEY (brain_id, id)
+";
+
+/// Column definitions of `neighborhoods`, shared by table creation and
+/// the v23 and v28 rebuilds.
+const NEIGHBORHOODS_COLUMNS: &str = "
+    id                 TEXT NOT NULL,
+    episode_id         TEXT NOT NULL,
+    seed_w             REAL NOT NULL,
+    seed_x             REAL NOT NULL,
+    seed_y             REAL NOT NULL,
+    seed_z             REAL NOT NULL,
+    source_text        TEXT NOT NULL DEFAULT '',
+    neighborhood_type  TEXT NOT NULL DEFAULT 'memory',
+    epoch              INTEGER NOT NULL DEFAULT 0,
+    superseded_by      TEXT,
+    actor              TEXT,
+    project            TEXT,
+    source_zip         BLOB,
+    feedback_score     REAL NOT NULL DEFAULT 0,
+    group_id           TEXT,
+    created_at         TEXT,
+    brain_id           TEXT NOT NULL DEFAULT 'default',
+    llm_tokens         INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (brain_id, id),
+    FOREIGN KEY (brain_id, episode_id) REFERENCES episodes(brain_id, id) ON DELETE CASCADE
+";
+
+/// Column definitions of `occurrences`, shared by table creation and the
+/// v23 and v28 rebuilds.
+const OCCURRENCES_COLUMNS: &str = "
+    id               TEXT NOT NULL,
+    neighborhood_id  TEXT NOT NULL,
+    word             TEXT NOT NULL,
+    pos_w            REAL NOT NULL,
+    pos_x            REAL NOT NULL,
+    pos_y            REAL NOT NULL,
+    pos_z            REAL NOT NULL,
+    phasor_theta     REAL NOT NULL,
+    activation_count INTEGER NOT NULL DEFAULT 0,
+    drift            REAL NOT NULL DEFAULT 0,
+    brain_id         TEXT NOT NULL DEFAULT 'default',
+    spillover        REAL NOT NULL DEFAULT 0,
+    span_start       INTEGER,
+    span_end         INTEGER,
+    PRIMARY KEY (brain_id, id),
+    FOREIGN KEY (brain_id, neighborhood_id) REFERENCES neighborhoods(brain_id, id) ON DELETE CASCADE
+";
 
 pub fn initialize(conn: &Connection) -> Result<()> {
     conn.execute_batch("PRAGMA journal_mode = WAL;")?;
@@ -23,58 +85,85 @@ pub fn initialize(conn: &Connection) -> Result<()> {
     }
 
     // Create tables. For existing databases, CREATE TABLE IF NOT EXISTS is a no-op.
-    conn.execute_batch(
+    conn.execute_batch(&format!(
         "
         CREATE TABLE IF NOT EXISTS metadata (
-            key   TEXT PRIMARY KEY,
-            value TEXT NOT NULL
+            brain_id TEXT NOT NULL DEFAULT 'default',
+            key      TEXT NOT NULL,
+            value    TEXT NOT NULL,
+            PRIMARY KEY (brain_id, key)
         );
 
-        CREATE TABLE IF NOT EXISTS episodes (
-            id           TEXT PRIMARY KEY,
-            name         TEXT NOT NULL,
-            is_conscious INTEGER NOT NULL DEFAULT 0,
-            timestamp    TEXT NOT NULL DEFAULT ''
-        );
+        CREATE TABLE IF NOT EXISTS episodes ({EPISODES_COLUMNS});
 
-        CREATE TABLE IF NOT EXISTS neighborhoods (
-            id                 TEXT PRIMARY KEY,
-            episode_id         TEXT NOT NULL REFERENCES episodes(id),
-            seed_w             REAL NOT NULL,
-            seed_x             REAL NOT NULL,
-            seed_y             REAL NOT NULL,
-            seed_z             REAL NOT NULL,
-            source_text        TEXT NOT NULL DEFAULT '',
-            neighborhood_type  TEXT NOT NULL DEFAULT 'memory',
-            epoch              INTEGER NOT NULL DEFAULT 0,
-            superseded_by      TEXT
-        );
+        CREATE TABLE IF NOT EXISTS neighborhoods ({NEIGHBORHOODS_COLUMNS});
 
-        CREATE TABLE IF NOT EXISTS occurrences (
-            id               TEXT PRIMARY KEY,
-            neighborhood_id  TEXT NOT NULL REFERENCES neighborhoods(id),
-            word             TEXT NOT NULL,
-            pos_w            REAL NOT NULL,
-            pos_x            REAL NOT NULL,
-            pos_y            REAL NOT NULL,
-            pos_z            REAL NOT NULL,
-            phasor_theta     REAL NOT NULL,
-            activation_count INTEGER NOT NULL DEFAULT 0
-        );
+        CREATE TABLE IF NOT EXISTS occurrences ({OCCURRENCES_COLUMNS});
 
         CREATE TABLE IF NOT EXISTS conversation_buffer (
             id             INTEGER PRIMARY KEY AUTOINCREMENT,
             user_text      TEXT NOT NULL,
             assistant_text TEXT NOT NULL,
-            created_at     TEXT NOT NULL DEFAULT (datetime('now'))
+            created_at     TEXT NOT NULL DEFAULT (datetime('now')),
+            brain_id       TEXT NOT NULL DEFAULT 'default'
+        );
+
+        CREATE TABLE IF NOT EXISTS drift_frozen_words (
+            brain_id TEXT NOT NULL DEFAULT 'default',
+            word     TEXT NOT NULL,
+            PRIMARY KEY (brain_id, word)
+        );
+
+        CREATE TABLE IF NOT EXISTS word_biases (
+            brain_id   TEXT NOT NULL DEFAULT 'default',
+            word       TEXT NOT NULL,
+            multiplier REAL NOT NULL,
+            PRIMARY KEY (brain_id, word)
+        );
+
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id       INTEGER PRIMARY KEY AUTOINCREMENT,
+            brain_id TEXT NOT NULL DEFAULT 'default',
+            at       TEXT NOT NULL,
+            kind     TEXT NOT NULL,
+            count    INTEGER NOT NULL DEFAULT 0,
+            project  TEXT,
+            actor    TEXT,
+            detail   TEXT NOT NULL DEFAULT ''
+        );
+
+        CREATE TABLE IF NOT EXISTS undo_stack (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            brain_id      TEXT NOT NULL DEFAULT 'default',
+            at            TEXT NOT NULL,
+            kind          TEXT NOT NULL,
+            detail        TEXT NOT NULL DEFAULT '',
+            episodes      INTEGER NOT NULL DEFAULT 0,
+            neighborhoods INTEGER NOT NULL DEFAULT 0,
+            occurrences   INTEGER NOT NULL DEFAULT 0,
+            bytes         INTEGER NOT NULL DEFAULT 0,
+            payload       TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS quarantine (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            brain_id      TEXT NOT NULL DEFAULT 'default',
+            forgotten_at  TEXT NOT NULL,
+            detail        TEXT NOT NULL DEFAULT '',
+            episodes      INTEGER NOT NULL DEFAULT 0,
+            neighborhoods INTEGER NOT NULL DEFAULT 0,
+            occurrences   INTEGER NOT NULL DEFAULT 0,
+            undo_id       INTEGER,
+            payload       TEXT NOT NULL
         );
 
         CREATE INDEX IF NOT EXISTS idx_occ_word ON occurrences(word);
-        CREATE INDEX IF NOT EXISTS idx_occ_neighborhood ON occurrences(neighborhood_id);
-        CREATE INDEX IF NOT EXISTS idx_nbhd_episode ON neighborhoods(episode_id);
+        CREATE INDEX IF NOT EXISTS idx_audit_brain ON audit_log(brain_id, id);
+        CREATE INDEX IF NOT EXISTS idx_undo_brain ON undo_stack(brain_id, id);
+        CREATE INDEX IF NOT EXISTS idx_quarantine_brain ON quarantine(brain_id, id);
 
-        ",
-    )?;
+        "
+    ))?;
 
     // Read stored version. Returns 0 for fresh databases (no metadata row yet).
     let stored_version = get_schema_version(conn)?.unwrap_or(0);
@@ -138,7 +227,231 @@ pub fn initialize(conn: &Connection) -> Result<()> {
         )?;
     }
 
-    // Store current schema version
+    // v8: Add archived flag - GC --archive parks cold episodes here instead
+    // of deleting them; load_system skips them unless asked.
+    if stored_version < 8
+        && conn
+            .prepare("SELECT archived FROM episodes LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE episodes ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;")?;
+    }
+
+    // v9: Record which model or agent created each episode and conscious
+    // neighborhood. NULL for memories from before attribution existed.
+    if stored_version < 9 {
+        if conn.prepare("SELECT actor FROM episodes LIMIT 0").is_err() {
+            conn.execute_batch("ALTER TABLE episodes ADD COLUMN actor TEXT;")?;
+        }
+        if conn
+            .prepare("SELECT actor FROM neighborhoods LIMIT 0")
+            .is_err()
+        {
+            conn.execute_batch("ALTER TABLE neighborhoods ADD COLUMN actor TEXT;")?;
+        }
+    }
+
+    // v10: Creation time of conscious neighborhoods, for the decision
+    // registry. NULL for neighborhoods from before it was recorded.
+    if stored_version < 10
+        && conn
+            .prepare("SELECT created_at FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE neighborhoods ADD COLUMN created_at TEXT;")?;
+    }
+
+    // v11: Angle each occurrence has drifted since placement. Existing
+    // occurrences start at 0; their earlier movement was never recorded.
+    if stored_version < 11
+        && conn
+            .prepare("SELECT drift FROM occurrences LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE occurrences ADD COLUMN drift REAL NOT NULL DEFAULT 0;")?;
+    }
+
+    // v12: Namespace every table by brain so several brains can share one
+    // file. Existing rows belong to the 'default' brain. Metadata keys are
+    // per brain, so that table is rebuilt with a composite primary key.
+    if stored_version < 12 {
+        for table in [
+            "episodes",
+            "neighborhoods",
+            "occurrences",
+            "conversation_buffer",
+        ] {
+            if conn
+                .prepare(&format!("SELECT brain_id FROM {table} LIMIT 0"))
+                .is_err()
+            {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE {table} ADD COLUMN brain_id TEXT NOT NULL DEFAULT 'default';"
+                ))?;
+            }
+        }
+        if conn
+            .prepare("SELECT brain_id FROM metadata LIMIT 0")
+            .is_err()
+        {
+            conn.execute_batch(
+                "
+                BEGIN;
+                CREATE TABLE metadata_v12 (
+                    brain_id TEXT NOT NULL DEFAULT 'default',
+                    key      TEXT NOT NULL,
+                    value    TEXT NOT NULL,
+                    PRIMARY KEY (brain_id, key)
+                );
+                INSERT INTO metadata_v12 (key, value) SELECT key, value FROM metadata;
+                DROP TABLE metadata;
+                ALTER TABLE metadata_v12 RENAME TO metadata;
+                COMMIT;
+                ",
+            )?;
+        }
+        conn.execute_batch(
+            "
+            CREATE INDEX IF NOT EXISTS idx_ep_brain ON episodes(brain_id);
+            CREATE INDEX IF NOT EXISTS idx_nbhd_brain ON neighborhoods(brain_id);
+            CREATE INDEX IF NOT EXISTS idx_occ_brain_word ON occurrences(brain_id, word);
+            CREATE INDEX IF NOT EXISTS idx_buffer_brain ON conversation_buffer(brain_id);
+            ",
+        )?;
+    }
+
+    // v13: Estimated LLM tokens of each neighborhood's source text, so
+    // episode and store totals are a SUM instead of a scan of every text.
+    // Backfilled with the same bytes / 4 estimate the writer uses.
+    if stored_version < 13
+        && conn
+            .prepare("SELECT llm_tokens FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch(
+            "
+            ALTER TABLE neighborhoods ADD COLUMN llm_tokens INTEGER NOT NULL DEFAULT 0;
+            UPDATE neighborhoods SET llm_tokens = (length(CAST(source_text AS BLOB)) + 3) / 4;
+            ",
+        )?;
+    }
+
+    // v14: Timestamp an import found in the future (the exporting clock
+    // ran ahead) before clamping it. NULL for every other episode.
+    if stored_version < 14
+        && conn
+            .prepare("SELECT original_timestamp FROM episodes LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE episodes ADD COLUMN original_timestamp TEXT;")?;
+    }
+
+    // v15: Neighborhoods an episode repeated and reused instead of storing
+    // (JSON array of ids). NULL when it reused none.
+    if stored_version < 15
+        && conn
+            .prepare("SELECT reused_neighborhoods FROM episodes LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE episodes ADD COLUMN reused_neighborhoods TEXT;")?;
+    }
+
+    // v16: Fractional activation credit from spillover, below one whole
+    // activation. Existing occurrences have earned none.
+    if stored_version < 16
+        && conn
+            .prepare("SELECT spillover FROM occurrences LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch(
+            "ALTER TABLE occurrences ADD COLUMN spillover REAL NOT NULL DEFAULT 0;",
+        )?;
+    }
+
+    // v17: drift_frozen_words, created above like any new table. Words
+    // listed there activate and score but never drift.
+
+    // v18: Project a conscious neighborhood belongs to. NULL applies to
+    // every project, as all memories from before did.
+    if stored_version < 18
+        && conn
+            .prepare("SELECT project FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE neighborhoods ADD COLUMN project TEXT;")?;
+    }
+
+    // v19: Deflated source_text of long subconscious neighborhoods
+    // (source_text is then ''). NULL keeps the text plain, as before.
+    if stored_version < 19
+        && conn
+            .prepare("SELECT source_zip FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE neighborhoods ADD COLUMN source_zip BLOB;")?;
+    }
+
+    // v20: Decayed sum of feedback signals per neighborhood. 0 is neutral,
+    // which is where every neighborhood from before starts.
+    if stored_version < 20
+        && conn
+            .prepare("SELECT feedback_score FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch(
+            "ALTER TABLE neighborhoods ADD COLUMN feedback_score REAL NOT NULL DEFAULT 0;",
+        )?;
+    }
+
+    // v21: Byte range of each ingested word in its neighborhood's text.
+    // NULL for label words and for occurrences stored before.
+    if stored_version < 21
+        && conn
+            .prepare("SELECT span_start FROM occurrences LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch(
+            "ALTER TABLE occurrences ADD COLUMN span_start INTEGER;
+             ALTER TABLE occurrences ADD COLUMN span_end INTEGER;",
+        )?;
+    }
+
+    // v22: word_biases, created above like any new table. Each row
+    // multiplies a word's IDF weight when scoring.
+
+    // v23: Deleting an episode deletes its neighborhoods, and deleting a
+    // neighborhood its occurrences. SQLite cannot alter a foreign key, so
+    // older tables are rebuilt, by the v28 rebuild below.
+
+    // v24: audit_log, created above like any new table. One row per
+    // change to memory, written in the change's own transaction.
+
+    // v25: undo_stack, likewise. The rows a forget or aggressive GC
+    // deleted, serialized so `am undo` can put them back.
+
+    // v26: Group shared by the chunks of one long salient text. NULL for
+    // memories stored whole, as all were before.
+    if stored_version < 26
+        && conn
+            .prepare("SELECT group_id FROM neighborhoods LIMIT 0")
+            .is_err()
+    {
+        conn.execute_batch("ALTER TABLE neighborhoods ADD COLUMN group_id TEXT;")?;
+    }
+
+    // v27: quarantine, created above. The rows a forget deleted, kept
+    // until `am gc` purges them after the retention window or
+    // `am restore-forgotten` puts them back.
+
+    // v28: Episode, neighborhood, and occurrence IDs are unique per brain,
+    // keyed (brain_id, id), and foreign keys match on brain_id too. Tables
+    // from before, including any v23 left without cascades, are rebuilt.
+    if stored_version < 28 {
+        rebuild_memory_tables(conn)?;
+    }
+    conn.execute_batch(BRAIN_JOIN_INDEXES)?;
+
+    // Store current schema version (file-wide, kept under the default brain)
     conn.execute(
         "INSERT OR REPLACE INTO metadata (key, value) VALUES ('schema_version', ?1)",
         [SCHEMA_VERSION.to_string()],
@@ -147,6 +460,151 @@ pub fn initialize(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Indexes for the joins from an episode to its neighborhoods and from a
+/// neighborhood to its occurrences. Every such join matches on brain_id
+/// too, so the index leads with it; SQLite would otherwise search
+/// `idx_occ_brain_word` by brain alone and scan the whole brain for each
+/// neighborhood. Replaces the single-column indexes of earlier versions.
+const BRAIN_JOIN_INDEXES: &str = "
+    DROP INDEX IF EXISTS idx_occ_neighborhood;
+    DROP INDEX IF EXISTS idx_nbhd_episode;
+    CREATE INDEX IF NOT EXISTS idx_occ_brain_nbhd ON occurrences(brain_id, neighborhood_id);
+    CREATE INDEX IF NOT EXISTS idx_nbhd_brain_episode ON neighborhoods(brain_id, episode_id);
+";
+
+type MemoryTable = (
+    &'static str,
+    &'static str,
+    Option<(&'static str, &'static str)>,
+);
+
+/// Memory tables with their canonical columns and, for a child table,
+/// the column and parent table its foreign key references, rebuilt in
+/// this order.
+const MEMORY_TABLES: [MemoryTable; 3] = [
+    ("episodes", EPISODES_COLUMNS, None),
+    (
+        "neighborhoods",
+        NEIGHBORHOODS_COLUMNS,
+        Some(("episode_id", "episodes")),
+    ),
+    (
+        "occurrences",
+        OCCURRENCES_COLUMNS,
+        Some(("neighborhood_id", "neighborhoods")),
+    ),
+];
+
+/// Rebuild the tables of [`MEMORY_TABLES`] not keyed per brain or whose
+/// foreign key does not cascade yet, keeping their rows and indexes. Rows
+/// whose parent is already gone could never be loaded or deleted with it,
+/// so they are dropped with a warning.
+fn rebuild_memory_tables(conn: &Connection) -> Result<()> {
+    let mut pending = Vec::new();
+    for entry in &MEMORY_TABLES {
+        let current =
+            keyed_per_brain(conn, entry.0)? && (entry.2.is_none() || cascades(conn, entry.0)?);
+        if !current {
+            pending.push(entry);
+        }
+    }
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    // Foreign keys cannot be toggled inside a transaction, and must be off
+    // while a parent table is dropped and replaced.
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+    let rebuilt = rebuild_tables(conn, &pending);
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    rebuilt
+}
+
+fn rebuild_tables(conn: &Connection, tables: &[&MemoryTable]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for &&(table, columns, parent) in tables {
+        let mut stmt = tx.prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE type = 'index' AND tbl_name = ?1 AND sql IS NOT NULL",
+        )?;
+        let indexes = stmt
+            .query_map([table], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        tx.execute_batch(&format!("CREATE TABLE memory_rebuild ({columns});"))?;
+        let canonical = column_names(&tx, "memory_rebuild")?;
+        let existing = column_names(&tx, table)?
+            .into_iter()
+            .filter(|c| canonical.contains(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let filter = parent.map_or(String::new(), |(key, parent)| {
+            format!("WHERE (brain_id, {key}) IN (SELECT brain_id, id FROM {parent})")
+        });
+        let copied = tx.execute(
+            &format!(
+                "INSERT INTO memory_rebuild ({existing}) SELECT {existing} FROM {table} {filter}"
+            ),
+            [],
+        )?;
+        let total: usize = tx.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })?;
+        if total > copied {
+            let parent = parent.map_or("parent", |(_, parent)| parent);
+            tracing::warn!(
+                "dropped {} {table} rows whose {parent} row no longer exists",
+                total - copied
+            );
+        }
+        tx.execute_batch(&format!(
+            "DROP TABLE {table}; ALTER TABLE memory_rebuild RENAME TO {table};"
+        ))?;
+        for index in indexes {
+            tx.execute_batch(&index)?;
+        }
+    }
+    tx.execute_batch(BRAIN_JOIN_INDEXES)?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn column_names(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+/// Whether `table`'s primary key is `(brain_id, id)`.
+fn keyed_per_brain(conn: &Connection, table: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    // Columns: cid, name, type, notnull, dflt_value, pk (position in the key)
+    let mut key = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(5)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    key.retain(|(position, _)| *position > 0);
+    key.sort();
+    Ok(key
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .eq(["brain_id", "id"]))
+}
+
+/// Whether `table`'s foreign key deletes it along with its parent.
+fn cascades(conn: &Connection, table: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA foreign_key_list({table})"))?;
+    // Columns: id, seq, table, from, to, on_update, on_delete, match
+    let actions = stmt
+        .query_map([], |row| row.get::<_, String>(6))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(!actions.is_empty() && actions.iter().all(|a| a.eq_ignore_ascii_case("CASCADE")))
+}
+
 /// Backfill empty timestamps on episodes using rowid ordering.
 /// Only runs once - skips if no episodes have empty timestamps.
 fn backfill_empty_timestamps(conn: &Connection) -> Result<()> {
@@ -226,6 +684,14 @@ mod tests {
                 "conversation_buffer",
                 "SELECT count(*) FROM conversation_buffer",
             ),
+            (
+                "drift_frozen_words",
+                "SELECT count(*) FROM drift_frozen_words",
+            ),
+            ("word_biases", "SELECT count(*) FROM word_biases"),
+            ("audit_log", "SELECT count(*) FROM audit_log"),
+            ("undo_stack", "SELECT count(*) FROM undo_stack"),
+            ("quarantine", "SELECT count(*) FROM quarantine"),
         ];
         for (table, sql) in table_counts {
             let count: i64 = conn.query_row(sql, [], |row| row.get(0)).unwrap();
@@ -348,6 +814,81 @@ mod tests {
             )
             .unwrap();
         assert_eq!(nbhd_type, "decision");
+
+        // Pre-existing episodes come through the v8 migration unarchived
+        let archived: i64 = conn
+            .query_row(
+                "SELECT archived FROM episodes WHERE id = 'ep1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(archived, 0);
+
+        // ...and with no recorded actor (v9)
+        let actor: Option<String> = conn
+            .query_row("SELECT actor FROM episodes WHERE id = 'ep1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(actor, None);
+
+        // ...and neighborhoods have no creation time (v10)
+        let created_at: Option<String> = conn
+            .query_row(
+                "SELECT created_at FROM neighborhoods WHERE id = 'n1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(created_at, None);
+
+        // ...and occurrences start with no recorded drift (v11)
+        conn.execute_batch(
+            "INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta) \
+             VALUES ('o1', 'n1', 'test', 1.0, 0.0, 0.0, 0.0, 0.0);",
+        )
+        .unwrap();
+        let drift: f64 = conn
+            .query_row("SELECT drift FROM occurrences WHERE id = 'o1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(drift, 0.0);
+
+        // ...nor any spillover credit (v16)
+        let spillover: f64 = conn
+            .query_row(
+                "SELECT spillover FROM occurrences WHERE id = 'o1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(spillover, 0.0);
+
+        // ...and every row belongs to the default brain (v12), with the
+        // metadata copied across the rebuild
+        let brain: String = conn
+            .query_row(
+                "SELECT brain_id FROM episodes WHERE id = 'ep1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(brain, "default");
+        let brain: String = conn
+            .query_row(
+                "SELECT brain_id FROM metadata WHERE key = 'schema_version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(brain, "default");
+        conn.execute_batch(
+            "INSERT INTO metadata (brain_id, key, value) VALUES ('default', 'agent_name', 'a');
+             INSERT INTO metadata (brain_id, key, value) VALUES ('other', 'agent_name', 'b');",
+        )
+        .unwrap();
     }
 
     #[test]
@@ -366,6 +907,188 @@ mod tests {
         assert_eq!(version2, Some(SCHEMA_VERSION));
     }
 
+    #[test]
+    fn test_v13_backfills_neighborhood_tokens() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize(&conn).unwrap();
+        // Roll back to a v12 table with a neighborhood already stored
+        conn.execute_batch(
+            "ALTER TABLE neighborhoods DROP COLUMN llm_tokens;
+             UPDATE metadata SET value = '12' WHERE key = 'schema_version';
+             INSERT INTO episodes (id, name) VALUES ('ep1', 'test');
+             INSERT INTO neighborhoods (id, episode_id, source_text, seed_w, seed_x, seed_y, seed_z) \
+             VALUES ('n1', 'ep1', 'naïve tokens', 1.0, 0.0, 0.0, 0.0);",
+        )
+        .unwrap();
+
+        initialize(&conn).unwrap();
+
+        let tokens: usize = conn
+            .query_row(
+                "SELECT llm_tokens FROM neighborhoods WHERE id = 'n1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(
+            tokens,
+            am_core::compose::estimate_llm_tokens("naïve tokens")
+        );
+    }
+
+    #[test]
+    fn test_v23_adds_delete_cascades() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize(&conn).unwrap();
+        // Roll back to v22 tables whose foreign keys do not cascade, with
+        // a neighborhood and an occurrence whose parents are already gone
+        let old_neighborhoods = NEIGHBORHOODS_COLUMNS.replace(" ON DELETE CASCADE", "");
+        let old_occurrences = OCCURRENCES_COLUMNS.replace(" ON DELETE CASCADE", "");
+        conn.execute_batch(&format!(
+            "PRAGMA foreign_keys = OFF;
+             DROP TABLE occurrences;
+             DROP TABLE neighborhoods;
+             CREATE TABLE neighborhoods ({old_neighborhoods});
+             CREATE TABLE occurrences ({old_occurrences});
+             CREATE INDEX idx_occ_word ON occurrences(word);
+             UPDATE metadata SET value = '22' WHERE key = 'schema_version';
+             INSERT INTO episodes (id, name) VALUES ('ep1', 'test');
+             INSERT INTO neighborhoods (id, episode_id, seed_w, seed_x, seed_y, seed_z) \
+             VALUES ('n1', 'ep1', 1.0, 0.0, 0.0, 0.0), ('n2', 'gone', 1.0, 0.0, 0.0, 0.0);
+             INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta) \
+             VALUES ('o1', 'n1', 'kept', 1.0, 0.0, 0.0, 0.0, 0.0), \
+                    ('o2', 'n2', 'orphan', 1.0, 0.0, 0.0, 0.0, 0.0);
+             PRAGMA foreign_keys = ON;"
+        ))
+        .unwrap();
+        assert!(!cascades(&conn, "neighborhoods").unwrap());
+
+        initialize(&conn).unwrap();
+
+        assert!(cascades(&conn, "neighborhoods").unwrap());
+        assert!(cascades(&conn, "occurrences").unwrap());
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(
+            count("SELECT COUNT(*) FROM neighborhoods"),
+            1,
+            "orphan dropped"
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM occurrences"),
+            1,
+            "orphan dropped"
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_occ_word'"),
+            1,
+            "indexes survive the rebuild"
+        );
+
+        conn.execute("DELETE FROM episodes WHERE id = 'ep1'", [])
+            .unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM neighborhoods"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM occurrences"), 0);
+    }
+
+    #[test]
+    fn test_v28_keys_memory_tables_per_brain() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize(&conn).unwrap();
+        // Roll back to v27 tables whose IDs are unique across the file
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             DROP TABLE occurrences;
+             DROP TABLE neighborhoods;
+             DROP TABLE episodes;
+             CREATE TABLE episodes (
+                 id TEXT PRIMARY KEY,
+                 name TEXT NOT NULL,
+                 brain_id TEXT NOT NULL DEFAULT 'default'
+             );
+             CREATE TABLE neighborhoods (
+                 id TEXT PRIMARY KEY,
+                 episode_id TEXT NOT NULL REFERENCES episodes(id) ON DELETE CASCADE,
+                 seed_w REAL NOT NULL, seed_x REAL NOT NULL,
+                 seed_y REAL NOT NULL, seed_z REAL NOT NULL,
+                 brain_id TEXT NOT NULL DEFAULT 'default'
+             );
+             CREATE TABLE occurrences (
+                 id TEXT PRIMARY KEY,
+                 neighborhood_id TEXT NOT NULL REFERENCES neighborhoods(id) ON DELETE CASCADE,
+                 word TEXT NOT NULL,
+                 pos_w REAL NOT NULL, pos_x REAL NOT NULL,
+                 pos_y REAL NOT NULL, pos_z REAL NOT NULL,
+                 phasor_theta REAL NOT NULL,
+                 brain_id TEXT NOT NULL DEFAULT 'default'
+             );
+             CREATE INDEX idx_nbhd_episode ON neighborhoods(episode_id);
+             CREATE INDEX idx_nbhd_seed ON neighborhoods(seed_w);
+             UPDATE metadata SET value = '27' WHERE key = 'schema_version';
+             INSERT INTO episodes (id, name, brain_id) VALUES ('ep1', 'test', 'alice');
+             INSERT INTO neighborhoods (id, episode_id, seed_w, seed_x, seed_y, seed_z, brain_id) \
+             VALUES ('n1', 'ep1', 1.0, 0.0, 0.0, 0.0, 'alice');
+             INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, brain_id) \
+             VALUES ('o1', 'n1', 'kept', 1.0, 0.0, 0.0, 0.0, 0.0, 'alice');
+             PRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+        assert!(!keyed_per_brain(&conn, "episodes").unwrap());
+
+        initialize(&conn).unwrap();
+
+        for table in ["episodes", "neighborhoods", "occurrences"] {
+            assert!(keyed_per_brain(&conn, table).unwrap(), "{table}");
+        }
+        assert!(cascades(&conn, "occurrences").unwrap());
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(
+            count("SELECT COUNT(*) FROM occurrences WHERE brain_id = 'alice'"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_nbhd_seed'"),
+            1,
+            "indexes survive the rebuild"
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_nbhd_episode'"),
+            0,
+            "the single-column join index gives way to the per-brain one"
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_nbhd_brain_episode'"),
+            1
+        );
+
+        // The same IDs fit in another brain, and deletes stay in theirs
+        conn.execute_batch(
+            "INSERT INTO episodes (id, name, brain_id) VALUES ('ep1', 'test', 'bob');
+             INSERT INTO neighborhoods (id, episode_id, seed_w, seed_x, seed_y, seed_z, brain_id) \
+             VALUES ('n1', 'ep1', 1.0, 0.0, 0.0, 0.0, 'bob');
+             INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, brain_id) \
+             VALUES ('o1', 'n1', 'kept', 1.0, 0.0, 0.0, 0.0, 0.0, 'bob');
+             DELETE FROM episodes WHERE id = 'ep1' AND brain_id = 'alice';",
+        )
+        .unwrap();
+        assert_eq!(
+            count("SELECT COUNT(*) FROM occurrences WHERE brain_id = 'alice'"),
+            0
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM occurrences WHERE brain_id = 'bob'"),
+            1
+        );
+        let orphan = conn.execute(
+            "INSERT INTO neighborhoods (id, episode_id, seed_w, seed_x, seed_y, seed_z, brain_id) \
+             VALUES ('n2', 'ep1', 1.0, 0.0, 0.0, 0.0, 'alice')",
+            [],
+        );
+        assert!(
+            orphan.is_err(),
+            "a neighborhood cannot join another brain's episode"
+        );
+    }
+
     #[test]
     fn test_indexes_created() {
         let conn = Connection::open_in_memory().unwrap();
@@ -374,11 +1097,13 @@ mod tests {
         // Query sqlite_master for all indexes we expect
         let expected = [
             "idx_occ_word",
-            "idx_occ_neighborhood",
-            "idx_nbhd_episode",
+            "idx_occ_brain_nbhd",
+            "idx_nbhd_brain_episode",
             "idx_ep_conscious",
             "idx_nbhd_episode_epoch",
             "idx_occ_nbhd_activation",
+            "idx_ep_brain",
+            "idx_occ_brain_word",
         ];
         let mut stmt = conn
             .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND name = ?1")
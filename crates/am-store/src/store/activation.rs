@@ -1,46 +1,63 @@
 use rusqlite::params;
 use uuid::Uuid;
 
-use am_core::activation_stats::ActivationStats;
+use am_core::{
+    activation_stats::ActivationStats, query::fold_activation_deltas,
+    store_trait::BufferedExchange, time::now_iso8601,
+};
 
 use crate::error::{Result, StoreError};
 
-use super::Store;
+use super::{BufferEntry, Store};
 
 impl Store {
     pub fn increment_activation(&self, occurrence_id: Uuid) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE occurrences SET activation_count = activation_count + 1 WHERE id = ?1",
-            [occurrence_id.to_string()],
-        )?;
-        if rows == 0 {
-            return Err(StoreError::InvalidData(format!(
-                "occurrence not found: {occurrence_id}"
-            )));
-        }
-        Ok(())
+        self.retry_busy("increment_activation", || {
+            let rows = self.conn.execute(
+                "UPDATE occurrences SET activation_count = activation_count + 1
+                 WHERE id = ?1 AND brain_id = ?2",
+                params![occurrence_id.to_string(), self.brain],
+            )?;
+            if rows == 0 {
+                return Err(StoreError::InvalidData(format!(
+                    "occurrence not found: {occurrence_id}"
+                )));
+            }
+            Ok(())
+        })
     }
 
     /// Increment `activation_count` for multiple occurrences in a single transaction.
     ///
-    /// Silently skips IDs that do not exist in the store (common when the
-    /// system has occurrences that were never persisted, e.g. from conscious
-    /// memory added after the last full save).
+    /// Repeated IDs are folded into one `+n` write each. Silently skips IDs
+    /// that do not exist in the store (common when the system has
+    /// occurrences that were never persisted, e.g. from conscious memory
+    /// added after the last full save).
     pub fn batch_increment_activation(&self, ids: &[Uuid]) -> Result<()> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-        let tx = self.conn.unchecked_transaction()?;
-        {
-            let mut stmt = tx.prepare(
-                "UPDATE occurrences SET activation_count = activation_count + 1 WHERE id = ?1",
-            )?;
-            for id in ids {
-                stmt.execute([id.to_string()])?;
+        self.increment_activations(&fold_activation_deltas(ids))
+    }
+
+    /// Add a delta to `activation_count` for each occurrence, in one
+    /// transaction through one prepared statement. Unknown IDs are skipped;
+    /// any other failure rolls the whole batch back.
+    pub fn increment_activations(&self, deltas: &[(Uuid, u32)]) -> Result<()> {
+        self.retry_busy("increment_activations", || {
+            if deltas.is_empty() {
+                return Ok(());
             }
-        }
-        tx.commit()?;
-        Ok(())
+            let tx = self.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE occurrences SET activation_count = activation_count + ?1
+                     WHERE id = ?2 AND brain_id = ?3",
+                )?;
+                for (id, delta) in deltas {
+                    stmt.execute(params![delta, id.to_string(), self.brain])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
     }
 
     /// Set activation counts to absolute values for a batch of occurrences.
@@ -49,55 +66,111 @@ impl Store {
     /// incremented. Silently skips unknown IDs (common for unpersisted
     /// conscious occurrences).
     pub fn batch_set_activation_counts(&self, batch: &[(Uuid, u32)]) -> Result<()> {
-        if batch.is_empty() {
-            return Ok(());
-        }
-        let tx = self.conn.unchecked_transaction()?;
-        {
-            let mut stmt =
-                tx.prepare("UPDATE occurrences SET activation_count = ?1 WHERE id = ?2")?;
-            for (id, count) in batch {
-                stmt.execute(rusqlite::params![count, id.to_string()])?;
+        self.retry_busy("batch_set_activation_counts", || {
+            if batch.is_empty() {
+                return Ok(());
+            }
+            let tx = self.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE occurrences SET activation_count = ?1 WHERE id = ?2 AND brain_id = ?3",
+                )?;
+                for (id, count) in batch {
+                    stmt.execute(params![count, id.to_string(), self.brain])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Set activation counts and spillover residues for occurrences
+    /// credited by activation spillover. Unknown IDs are skipped.
+    pub fn save_activation_spillover(&self, batch: &[(Uuid, u32, f64)]) -> Result<()> {
+        self.retry_busy("save_activation_spillover", || {
+            if batch.is_empty() {
+                return Ok(());
             }
-        }
-        tx.commit()?;
-        Ok(())
+            let tx = self.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE occurrences SET activation_count = ?1, spillover = ?2
+                     WHERE id = ?3 AND brain_id = ?4",
+                )?;
+                for (id, count, spillover) in batch {
+                    stmt.execute(params![count, spillover, id.to_string(), self.brain])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Set feedback scores to absolute values for a batch of neighborhoods.
+    /// Unknown IDs are skipped.
+    pub fn save_feedback_scores(&self, batch: &[(Uuid, f64)]) -> Result<()> {
+        self.retry_busy("save_feedback_scores", || {
+            if batch.is_empty() {
+                return Ok(());
+            }
+            let tx = self.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE neighborhoods SET feedback_score = ?1 WHERE id = ?2 AND brain_id = ?3",
+                )?;
+                for (id, score) in batch {
+                    stmt.execute(params![score, id.to_string(), self.brain])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
     }
 
     /// Mark a neighborhood as superseded by another (targeted update, no full save).
     pub fn mark_superseded(&self, old_id: Uuid, new_id: Uuid) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE neighborhoods SET superseded_by = ?1 WHERE id = ?2",
-            params![new_id.to_string(), old_id.to_string()],
-        )?;
-        if rows == 0 {
-            return Err(StoreError::InvalidData(format!(
-                "neighborhood not found: {old_id}"
-            )));
-        }
-        Ok(())
+        self.retry_busy("mark_superseded", || {
+            let rows = self.conn.execute(
+                "UPDATE neighborhoods SET superseded_by = ?1 WHERE id = ?2 AND brain_id = ?3",
+                params![new_id.to_string(), old_id.to_string(), self.brain],
+            )?;
+            if rows == 0 {
+                return Err(StoreError::InvalidData(format!(
+                    "neighborhood not found: {old_id}"
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    /// Replace a neighborhood's `created_at` (targeted update, no full save).
+    pub fn restamp_neighborhood(&self, id: Uuid, created_at: &str) -> Result<()> {
+        self.retry_busy("restamp_neighborhood", || {
+            let rows = self.conn.execute(
+                "UPDATE neighborhoods SET created_at = ?1 WHERE id = ?2 AND brain_id = ?3",
+                params![created_at, id.to_string(), self.brain],
+            )?;
+            if rows == 0 {
+                return Err(StoreError::InvalidData(format!(
+                    "neighborhood not found: {id}"
+                )));
+            }
+            Ok(())
+        })
     }
 
     /// Get activation count distribution for stats.
     pub fn activation_distribution(&self) -> Result<ActivationStats> {
-        let total: u64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM occurrences", [], |row| row.get(0))?;
-        let zero_activation: u64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM occurrences WHERE activation_count = 0",
-            [],
-            |row| row.get(0),
-        )?;
-        let max_activation: u32 = self.conn.query_row(
-            "SELECT COALESCE(MAX(activation_count), 0) FROM occurrences",
-            [],
-            |row| row.get(0),
-        )?;
-        let sum_activation: u64 = self.conn.query_row(
-            "SELECT COALESCE(SUM(activation_count), 0) FROM occurrences",
-            [],
-            |row| row.get(0),
-        )?;
+        let (total, zero_activation, max_activation, sum_activation): (u64, u64, u32, u64) =
+            self.conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(activation_count = 0), 0),
+                        COALESCE(MAX(activation_count), 0),
+                        COALESCE(SUM(activation_count), 0)
+                 FROM occurrences WHERE brain_id = ?1",
+                [&self.brain],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )?;
 
         Ok(ActivationStats {
             total,
@@ -114,55 +187,116 @@ impl Store {
     // --- Conversation buffer ---
 
     pub fn append_buffer(&self, user_text: &str, assistant_text: &str) -> Result<usize> {
-        self.conn.execute(
-            "INSERT INTO conversation_buffer (user_text, assistant_text) VALUES (?1, ?2)",
-            params![user_text, assistant_text],
-        )?;
-        let count: usize =
-            self.conn
-                .query_row("SELECT COUNT(*) FROM conversation_buffer", [], |row| {
-                    row.get(0)
-                })?;
-        Ok(count)
+        self.append_buffer_at(user_text, assistant_text, &now_iso8601())
     }
 
-    pub fn drain_buffer(&self) -> Result<Vec<(String, String)>> {
-        let tx = self.conn.unchecked_transaction()?;
-
-        let mut stmt = tx
-            .prepare("SELECT id, user_text, assistant_text FROM conversation_buffer ORDER BY id")?;
-        let entries: Vec<(i64, String, String)> = stmt
-            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
-            .collect::<std::result::Result<_, _>>()?;
-        drop(stmt);
-
-        if !entries.is_empty() {
-            // Delete exactly the rows we read using a parameterized range
-            // on the rowid. Since we fetched ORDER BY id, entries are
-            // contiguous. Any rows arriving from another connection after
-            // our SELECT will have id > max and survive for the next drain
-            // (at-least-once semantics).
-            let min_id = entries.first().expect("non-empty").0;
-            let max_id = entries.last().expect("non-empty").0;
-            tx.execute(
-                "DELETE FROM conversation_buffer WHERE id >= ?1 AND id <= ?2",
-                params![min_id, max_id],
+    /// Append an exchange buffered at `buffered_at` (ISO-8601 UTC), e.g.
+    /// when replaying exchanges whose time is already known.
+    pub fn append_buffer_at(
+        &self,
+        user_text: &str,
+        assistant_text: &str,
+        buffered_at: &str,
+    ) -> Result<usize> {
+        self.retry_busy("append_buffer", || {
+            self.conn.execute(
+                "INSERT INTO conversation_buffer (user_text, assistant_text, created_at, brain_id)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![user_text, assistant_text, buffered_at, self.brain],
             )?;
-        }
+            Ok(())
+        })?;
+        self.buffer_count()
+    }
 
-        let results: Vec<(String, String)> = entries.into_iter().map(|(_, u, a)| (u, a)).collect();
+    pub fn drain_buffer(&self) -> Result<Vec<BufferedExchange>> {
+        self.retry_busy("drain_buffer", || {
+            let tx = self.transaction()?;
 
-        tx.commit()?;
+            let mut stmt = tx.prepare(
+                "SELECT id, user_text, assistant_text, created_at FROM conversation_buffer
+                 WHERE brain_id = ?1 ORDER BY id",
+            )?;
+            let entries: Vec<(i64, BufferedExchange)> = stmt
+                .query_map([&self.brain], |row| {
+                    let exchange = BufferedExchange {
+                        user: row.get(1)?,
+                        assistant: row.get(2)?,
+                        buffered_at: iso8601_from_sqlite(row.get(3)?),
+                    };
+                    Ok((row.get(0)?, exchange))
+                })?
+                .collect::<std::result::Result<_, _>>()?;
+            drop(stmt);
+
+            if !entries.is_empty() {
+                // Delete exactly the rows we read using a parameterized range
+                // on the rowid. Since we fetched ORDER BY id, this brain's
+                // entries in the range are the ones we read. Any rows arriving
+                // from another connection after our SELECT will have id > max
+                // and survive for the next drain (at-least-once semantics).
+                let min_id = entries.first().expect("non-empty").0;
+                let max_id = entries.last().expect("non-empty").0;
+                tx.execute(
+                    "DELETE FROM conversation_buffer
+                     WHERE id >= ?1 AND id <= ?2 AND brain_id = ?3",
+                    params![min_id, max_id, self.brain],
+                )?;
+            }
 
-        Ok(results)
+            let results: Vec<BufferedExchange> = entries.into_iter().map(|(_, e)| e).collect();
+
+            tx.commit()?;
+
+            Ok(results)
+        })
+    }
+
+    /// Pending exchanges in insertion order, without removing them.
+    pub fn list_buffer(&self) -> Result<Vec<BufferEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, user_text, assistant_text, created_at
+             FROM conversation_buffer WHERE brain_id = ?1 ORDER BY id",
+        )?;
+        let entries = stmt
+            .query_map([&self.brain], |row| {
+                Ok(BufferEntry {
+                    id: row.get(0)?,
+                    user_text: row.get(1)?,
+                    assistant_text: row.get(2)?,
+                    created_at: iso8601_from_sqlite(row.get(3)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Discard every pending exchange. Returns how many were dropped.
+    pub fn clear_buffer(&self) -> Result<usize> {
+        self.retry_busy("clear_buffer", || {
+            Ok(self.conn.execute(
+                "DELETE FROM conversation_buffer WHERE brain_id = ?1",
+                [&self.brain],
+            )?)
+        })
     }
 
     pub fn buffer_count(&self) -> Result<usize> {
-        let count: usize =
-            self.conn
-                .query_row("SELECT COUNT(*) FROM conversation_buffer", [], |row| {
-                    row.get(0)
-                })?;
+        let count: usize = self.conn.query_row(
+            "SELECT COUNT(*) FROM conversation_buffer WHERE brain_id = ?1",
+            [&self.brain],
+            |row| row.get(0),
+        )?;
         Ok(count)
     }
 }
+
+/// Rows buffered before `append_buffer` stamped them carry SQLite's
+/// `datetime('now')` format (`2026-01-02 03:04:05`); rewrite those as
+/// ISO-8601 so they compare with episode timestamps.
+fn iso8601_from_sqlite(created_at: String) -> String {
+    match created_at.split_once(' ') {
+        Some((date, time)) if !created_at.contains('T') => format!("{date}T{time}Z"),
+        _ => created_at,
+    }
+}
@@ -0,0 +1,136 @@
+//! Append-only audit log of changes to memory.
+//!
+//! Each salient mark, ingest, buffer flush, feedback, forget, GC pass, and
+//! import appends one `audit_log` row inside the transaction that made the
+//! change, so the log never records a change that did not land. Rows are
+//! only ever deleted by [`Store::prune_audit`], which `am gc` runs.
+
+use am_core::store_trait::{AuditEvent, AuditKind};
+use am_core::time::now_iso8601;
+use rusqlite::{Connection, params};
+
+use crate::error::Result;
+
+use super::Store;
+
+/// One audit log row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Increases with every row, across brains.
+    pub id: i64,
+    /// ISO-8601 UTC time the change committed.
+    pub at: String,
+    pub kind: AuditKind,
+    pub count: u64,
+    pub project: Option<String>,
+    pub actor: Option<String>,
+    pub detail: String,
+}
+
+/// Which audit rows [`Store::audit_log`] returns.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Only rows at or after this ISO-8601 time.
+    pub since: Option<String>,
+    pub kind: Option<AuditKind>,
+    /// At most this many of the newest matching rows.
+    pub limit: Option<usize>,
+}
+
+impl Store {
+    /// Append `event` to the audit log. Inside an open transaction the row
+    /// commits or rolls back with it.
+    pub fn audit(&self, event: &AuditEvent) -> Result<()> {
+        self.retry_busy("audit", || self.audit_on(&self.conn, event))
+    }
+
+    pub(crate) fn audit_on(&self, conn: &Connection, event: &AuditEvent) -> Result<()> {
+        conn.execute(
+            "INSERT INTO audit_log (brain_id, at, kind, count, project, actor, detail)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.brain,
+                now_iso8601(),
+                event.kind.as_str(),
+                i64::try_from(event.count).unwrap_or(i64::MAX),
+                event.project,
+                event.actor,
+                event.detail,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Run the store writes in `write` and append `event` as one
+    /// transaction: the event is recorded if and only if every write
+    /// commits. The write lock is taken up front, so nothing inside waits
+    /// on `SQLITE_BUSY`; taking it is retried like any write.
+    pub fn audited<T>(&self, event: &AuditEvent, write: impl FnOnce() -> Result<T>) -> Result<T> {
+        let tx = self.retry_busy("audited", || self.immediate_transaction())?;
+        let value = write()?;
+        self.audit_on(&tx, event)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Audit rows matching `filter`, newest first. Rows of a kind this
+    /// version does not know are skipped.
+    pub fn audit_log(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let limit = filter.limit.map_or(-1, |n| i64::try_from(n).unwrap_or(-1));
+        let mut stmt = self.conn.prepare(
+            "SELECT id, at, kind, count, project, actor, detail FROM audit_log
+             WHERE brain_id = ?1 AND (?2 IS NULL OR at >= ?2) AND (?3 IS NULL OR kind = ?3)
+             ORDER BY id DESC LIMIT ?4",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![
+                    self.brain,
+                    filter.since,
+                    filter.kind.map(AuditKind::as_str),
+                    limit
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                        row.get::<_, String>(6)?,
+                    ))
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, at, kind, count, project, actor, detail)| {
+                Some(AuditEntry {
+                    id,
+                    at,
+                    kind: AuditKind::parse(&kind)?,
+                    count: u64::try_from(count).unwrap_or(0),
+                    project,
+                    actor,
+                    detail,
+                })
+            })
+            .collect())
+    }
+
+    /// Delete all but the newest `keep` audit rows of this brain. Returns
+    /// how many were deleted.
+    pub fn prune_audit(&self, keep: usize) -> Result<u64> {
+        let keep = i64::try_from(keep).unwrap_or(i64::MAX);
+        self.retry_busy("prune_audit", || {
+            let deleted = self.conn.execute(
+                "DELETE FROM audit_log WHERE brain_id = ?1 AND id NOT IN (
+                     SELECT id FROM audit_log WHERE brain_id = ?1 ORDER BY id DESC LIMIT ?2
+                 )",
+                params![self.brain, keep],
+            )?;
+            Ok(deleted as u64)
+        })
+    }
+}
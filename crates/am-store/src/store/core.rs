@@ -1,23 +1,107 @@
 use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
+use am_core::{
+    compose::{ComposeConfig, Highlight, LabelSet},
+    constants::MAX_WORD_BIAS,
+    system::WordWeightPolicy,
+};
 use rusqlite::{Connection, params};
 
 use crate::error::{Result, StoreError};
 use crate::schema;
 
-use super::Store;
+use super::{
+    DEFAULT_BRAIN, DEFAULT_COMPRESS_TEXT_MIN_BYTES, DEFAULT_QUARANTINE_DAYS,
+    DEFAULT_RESURRECT_WARN, RetryPolicy, Store, StoreOptions, UndoPolicy, validate,
+    validate_brain_name,
+};
 
 impl Store {
+    /// Open the database at `path` with the default [`StoreOptions`].
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)?;
-        schema::initialize(&conn)?;
-        Ok(Self { conn })
+        Self::open_with(path, &StoreOptions::default())
     }
 
     pub fn open_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
         schema::initialize(&conn)?;
-        Ok(Self { conn })
+        Ok(Self::from_conn(conn))
+    }
+
+    pub(super) fn from_conn(conn: Connection) -> Self {
+        Self {
+            conn,
+            repaired_records: AtomicU64::new(0),
+            strict_load: validate::strict_load_from_env(),
+            brain: DEFAULT_BRAIN.to_string(),
+            gc_evicted: Mutex::default(),
+            resurrect_warn: DEFAULT_RESURRECT_WARN,
+            retry: RetryPolicy::default(),
+            compress_min: Some(DEFAULT_COMPRESS_TEXT_MIN_BYTES),
+            undo: UndoPolicy::default(),
+            quarantine_days: DEFAULT_QUARANTINE_DAYS,
+        }
+    }
+
+    /// Scope every read and write to the brain named `brain` instead of
+    /// [`DEFAULT_BRAIN`]. Brains sharing a file never see each other's
+    /// episodes, buffer, or metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the name is not a valid brain name (see
+    /// [`validate_brain_name`]).
+    pub fn with_brain(mut self, brain: &str) -> Result<Self> {
+        validate_brain_name(brain)?;
+        self.brain = brain.to_string();
+        Ok(self)
+    }
+
+    /// The brain this store reads and writes.
+    pub fn brain(&self) -> &str {
+        &self.brain
+    }
+
+    /// Error on invalid geometry during loads instead of repairing it.
+    /// Defaults to the `AM_STRICT_LOAD` environment variable.
+    #[must_use]
+    pub fn with_strict_load(mut self, strict: bool) -> Self {
+        self.strict_load = strict;
+        self
+    }
+
+    /// Warn when `save_system` would write back more than `threshold`
+    /// occurrences that GC evicted through this store, a sign the caller
+    /// skipped `DAESystem::apply_gc`. Defaults to [`DEFAULT_RESURRECT_WARN`].
+    #[must_use]
+    pub fn with_resurrect_warn(mut self, threshold: u64) -> Self {
+        self.resurrect_warn = threshold;
+        self
+    }
+
+    /// How long a statement waits on another connection's lock before
+    /// failing with `SQLITE_BUSY`. Set at open from
+    /// [`StoreOptions::busy_timeout_ms`].
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<()> {
+        self.conn.busy_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Number of rows repaired by load invariant checks since this store
+    /// was opened (non-unit quaternions, phasor angles outside `[0, τ)`).
+    pub fn repaired_records(&self) -> u64 {
+        self.repaired_records.load(Ordering::Relaxed)
+    }
+
+    /// Add one load's repairs to the running total, logging if any.
+    pub(crate) fn record_repairs(&self, what: &str, repaired: u64) {
+        if repaired > 0 {
+            tracing::warn!("{what}: repaired {repaired} records with invalid geometry");
+            self.repaired_records.fetch_add(repaired, Ordering::Relaxed);
+        }
     }
 
     /// Verify the connection is still usable.
@@ -35,22 +119,241 @@ impl Store {
         Ok(())
     }
 
+    /// SQLite-level damage: `PRAGMA quick_check` failures and rows whose
+    /// foreign key points at a missing parent. Empty for a healthy file.
+    pub fn integrity_problems(&self) -> Result<Vec<String>> {
+        let mut problems = Vec::new();
+
+        let mut stmt = self.conn.prepare("PRAGMA quick_check")?;
+        for message in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let message = message?;
+            if message != "ok" {
+                problems.push(message);
+            }
+        }
+
+        // Columns: table, rowid, parent table, foreign key index
+        let mut stmt = self.conn.prepare("PRAGMA foreign_key_check")?;
+        let orphans = stmt.query_map([], |row| {
+            Ok(format!(
+                "{} row {} references a missing {} row",
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<i64>>(1)?.unwrap_or_default(),
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for orphan in orphans {
+            problems.push(orphan?);
+        }
+
+        Ok(problems)
+    }
+
     // --- Metadata ---
 
     pub fn get_metadata(&self, key: &str) -> Result<Option<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT value FROM metadata WHERE key = ?1")?;
-        let result = stmt.query_row([key], |row| row.get(0)).ok();
+            .prepare("SELECT value FROM metadata WHERE key = ?1 AND brain_id = ?2")?;
+        let result = stmt
+            .query_row(params![key, self.brain], |row| row.get(0))
+            .ok();
         Ok(result)
     }
 
     pub fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
-        self.conn.execute(
-            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
-            params![key, value],
+        self.retry_busy("set_metadata", || {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO metadata (brain_id, key, value) VALUES (?1, ?2, ?3)",
+                params![self.brain, key, value],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Word weight clamps with metadata overrides applied.
+    ///
+    /// Reads `word_weight_floor`, `word_weight_ceiling`, `skip_hapax_drift`,
+    /// and `response_drift_scale` from the metadata table. Missing keys use
+    /// the am-core defaults; unparseable values are logged and ignored.
+    pub fn word_weight_policy(&self) -> Result<WordWeightPolicy> {
+        let mut policy = WordWeightPolicy::default();
+        if let Some(v) = self.get_metadata("word_weight_floor")? {
+            match v.parse::<f64>() {
+                Ok(f) if f.is_finite() && f >= 0.0 => policy.floor = f,
+                _ => tracing::warn!("metadata word_weight_floor={v:?}: expected float >= 0"),
+            }
+        }
+        if let Some(v) = self.get_metadata("word_weight_ceiling")? {
+            match v.parse::<f64>() {
+                Ok(c) if c.is_finite() && c > 0.0 => policy.ceiling = c,
+                _ => tracing::warn!("metadata word_weight_ceiling={v:?}: expected float > 0"),
+            }
+        }
+        if let Some(v) = self.get_metadata("skip_hapax_drift")? {
+            match v.parse::<bool>() {
+                Ok(b) => policy.skip_hapax_drift = b,
+                Err(_) => tracing::warn!("metadata skip_hapax_drift={v:?}: expected bool"),
+            }
+        }
+        if let Some(v) = self.get_metadata("response_drift_scale")? {
+            match v.parse::<f64>() {
+                Ok(s) if (0.0..=1.0).contains(&s) => policy.response_drift_scale = s,
+                _ => tracing::warn!("metadata response_drift_scale={v:?}: expected float in 0..=1"),
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Words frozen out of drift, alphabetically.
+    pub fn frozen_words(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT word FROM drift_frozen_words WHERE brain_id = ?1 ORDER BY word")?;
+        let words = stmt
+            .query_map([&self.brain], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(words)
+    }
+
+    /// Freeze `words` (lowercased) out of drift and phase coupling. Returns
+    /// how many were not already frozen.
+    pub fn freeze_words(&self, words: &[String]) -> Result<usize> {
+        self.retry_busy("freeze_words", || {
+            let tx = self.transaction()?;
+            let mut added = 0;
+            for word in words {
+                added += tx.execute(
+                    "INSERT OR IGNORE INTO drift_frozen_words (brain_id, word) VALUES (?1, ?2)",
+                    params![self.brain, word.to_lowercase()],
+                )?;
+            }
+            tx.commit()?;
+            Ok(added)
+        })
+    }
+
+    /// Let `words` drift again. Returns how many were frozen.
+    pub fn unfreeze_words(&self, words: &[String]) -> Result<usize> {
+        self.retry_busy("unfreeze_words", || {
+            let tx = self.transaction()?;
+            let mut removed = 0;
+            for word in words {
+                removed += tx.execute(
+                    "DELETE FROM drift_frozen_words WHERE brain_id = ?1 AND word = ?2",
+                    params![self.brain, word.to_lowercase()],
+                )?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Per-word scoring multipliers, alphabetically.
+    pub fn word_biases(&self) -> Result<Vec<(String, f64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT word, multiplier FROM word_biases WHERE brain_id = ?1 ORDER BY word",
         )?;
-        Ok(())
+        let biases = stmt
+            .query_map([&self.brain], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<(String, f64)>, _>>()?;
+        Ok(biases)
+    }
+
+    /// Bias `word` (lowercased) by `multiplier`, clamped to
+    /// `[0, MAX_WORD_BIAS]`, replacing any bias it had. NaN stores the
+    /// neutral 1.0. Returns the multiplier stored.
+    pub fn set_word_bias(&self, word: &str, multiplier: f64) -> Result<f64> {
+        let multiplier = if multiplier.is_nan() {
+            1.0
+        } else {
+            multiplier.clamp(0.0, MAX_WORD_BIAS)
+        };
+        self.retry_busy("set_word_bias", || {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO word_biases (brain_id, word, multiplier)
+                 VALUES (?1, ?2, ?3)",
+                params![self.brain, word.to_lowercase(), multiplier],
+            )?;
+            Ok(())
+        })?;
+        Ok(multiplier)
+    }
+
+    /// Drop the bias on `words`. Returns how many had one.
+    pub fn clear_word_biases(&self, words: &[String]) -> Result<usize> {
+        self.retry_busy("clear_word_biases", || {
+            let tx = self.transaction()?;
+            let mut removed = 0;
+            for word in words {
+                removed += tx.execute(
+                    "DELETE FROM word_biases WHERE brain_id = ?1 AND word = ?2",
+                    params![self.brain, word.to_lowercase()],
+                )?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Recall candidate cutoffs with metadata overrides applied.
+    ///
+    /// Reads `min_activated_words` and `min_candidate_score` from the
+    /// metadata table, each context label from `compose.labels.<name>`
+    /// (see [`LabelSet`]), and highlight markers from
+    /// `compose.highlight.open` and `compose.highlight.close` (the opening
+    /// marker when unset). Missing keys use the am-core defaults;
+    /// unparseable values are logged and ignored.
+    pub fn compose_config(&self) -> Result<ComposeConfig> {
+        let mut config = ComposeConfig::default();
+        if let Some(v) = self.get_metadata("min_activated_words")? {
+            match v.parse::<usize>() {
+                Ok(n) if n >= 1 => config.min_activated_words = n,
+                _ => tracing::warn!("metadata min_activated_words={v:?}: expected integer >= 1"),
+            }
+        }
+        if let Some(v) = self.get_metadata("min_candidate_score")? {
+            match v.parse::<f64>() {
+                Ok(f) if f.is_finite() && f >= 0.0 => config.min_score = f,
+                _ => tracing::warn!("metadata min_candidate_score={v:?}: expected float >= 0"),
+            }
+        }
+        if let Some(v) = self.get_metadata("compose.novel.max_activated_words")? {
+            match v.parse::<usize>() {
+                Ok(n) => config.novel.max_activated_words = n,
+                _ => tracing::warn!(
+                    "metadata compose.novel.max_activated_words={v:?}: expected integer"
+                ),
+            }
+        }
+        if let Some(v) = self.get_metadata("compose.novel.require_no_conscious_overlap")? {
+            match v.parse::<bool>() {
+                Ok(b) => config.novel.require_no_conscious_overlap = b,
+                _ => tracing::warn!(
+                    "metadata compose.novel.require_no_conscious_overlap={v:?}: expected bool"
+                ),
+            }
+        }
+        if let Some(v) = self.get_metadata("compose.novel.min_novelty_score")? {
+            match v.parse::<f64>() {
+                Ok(f) if f.is_finite() && f >= 0.0 => config.novel.min_novelty_score = f,
+                _ => tracing::warn!(
+                    "metadata compose.novel.min_novelty_score={v:?}: expected float >= 0"
+                ),
+            }
+        }
+        for name in LabelSet::NAMES {
+            if let Some(v) = self.get_metadata(&format!("compose.labels.{name}"))? {
+                config.labels.set(name, v);
+            }
+        }
+        if let Some(open) = self.get_metadata("compose.highlight.open")? {
+            let close = self
+                .get_metadata("compose.highlight.close")?
+                .unwrap_or_else(|| open.clone());
+            config.highlight = Some(Highlight { open, close });
+        }
+        Ok(config)
     }
 
     /// Get the database file size in bytes (0 for in-memory databases).
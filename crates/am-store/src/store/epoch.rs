@@ -0,0 +1,368 @@
+//! Epoch files: cold subconscious episodes moved out of `brain.db`.
+//!
+//! Old synced sessions nobody recalls end up dominating a long-lived
+//! store, and every load, GC pass, and backup pays for them. An epoch pass
+//! ([`Store::epoch_pass`]) moves cold episodes, as `gc_archive_pass` picks
+//! them plus any already archived, into one file per year of their
+//! timestamp (`brain-2024.db` next to `brain.db`) and registers the file in
+//! the brain's `epoch_files` metadata. Conscious episodes never move.
+//!
+//! `load_system` reads only the active database. Federated recall asks for
+//! [`Store::load_epoch_episodes`], which opens each registered file
+//! read-only and returns its episodes for a single query.
+//!
+//! Rows are copied into the epoch file and committed before they are
+//! deleted from `brain.db`, so an interrupted pass leaves duplicates (which
+//! the next pass replaces), never missing episodes.
+
+use std::path::{Path, PathBuf};
+
+use am_core::episode::Episode;
+use rusqlite::{Connection, OpenFlags, params};
+
+use crate::error::{Result, StoreError};
+use crate::schema::{self, SCHEMA_VERSION};
+
+use super::Store;
+
+/// Metadata key holding the JSON array of this brain's epoch file names,
+/// relative to the directory of `brain.db`.
+const EPOCH_FILES_KEY: &str = "epoch_files";
+
+/// Tables an epoch pass moves, parents first.
+const EPOCH_TABLES: [&str; 3] = ["episodes", "neighborhoods", "occurrences"];
+
+/// What [`Store::epoch_pass`] moved, or would move, into one epoch file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochMove {
+    /// File name, e.g. `brain-2024.db`.
+    pub file: String,
+    pub episodes: u64,
+    pub neighborhoods: u64,
+}
+
+/// Result of an epoch pass, one entry per epoch file touched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EpochReport {
+    pub moved: Vec<EpochMove>,
+    pub before_size: u64,
+    pub after_size: u64,
+}
+
+impl EpochReport {
+    pub fn episodes(&self) -> u64 {
+        self.moved.iter().map(|m| m.episodes).sum()
+    }
+}
+
+/// One registered epoch file, for `am stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochInfo {
+    pub path: PathBuf,
+    /// This brain's episodes in the file; 0 when the file is missing.
+    pub episodes: u64,
+    /// File size in bytes; 0 when the file is missing.
+    pub size: u64,
+}
+
+/// Epoch file name for an episode timestamp: its year, or `undated` when
+/// the timestamp does not start with one.
+fn epoch_file_name(timestamp: &str) -> String {
+    match timestamp.get(..4) {
+        Some(year) if year.bytes().all(|b| b.is_ascii_digit()) => format!("brain-{year}.db"),
+        _ => "brain-undated.db".to_string(),
+    }
+}
+
+/// Create or upgrade the epoch file at `path`. Epoch files use a rollback
+/// journal rather than WAL, so read-only connections need no `-shm` file.
+fn prepare_epoch_file(path: &Path) -> Result<()> {
+    let conn = Connection::open(path)?;
+    schema::initialize(&conn)?;
+    conn.execute_batch("PRAGMA journal_mode = DELETE;")?;
+    Ok(())
+}
+
+/// Open a registered epoch file read-only, upgrading its schema first if
+/// an older am wrote it.
+fn open_epoch_file(path: &Path) -> Result<Connection> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    if schema::get_schema_version(&conn)?.unwrap_or(0) >= SCHEMA_VERSION {
+        return Ok(conn);
+    }
+    drop(conn);
+    prepare_epoch_file(path)?;
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?)
+}
+
+/// Column names of `table` in the database attached as `db`.
+fn table_columns(conn: &Connection, db: &str, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {db}.table_info({table})"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+impl Store {
+    /// Directory epoch files live in: the one holding this database.
+    fn epoch_dir(&self) -> Result<PathBuf> {
+        self.conn
+            .path()
+            .filter(|p| !p.is_empty())
+            .and_then(|p| Path::new(p).parent())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| StoreError::InvalidData("epoch files need a file-backed store".into()))
+    }
+
+    /// File names in this brain's `epoch_files` metadata.
+    fn epoch_file_names(&self) -> Result<Vec<String>> {
+        let Some(json) = self.get_metadata(EPOCH_FILES_KEY)? else {
+            return Ok(Vec::new());
+        };
+        serde_json::from_str(&json)
+            .map_err(|e| StoreError::InvalidData(format!("metadata {EPOCH_FILES_KEY}: {e}")))
+    }
+
+    /// Paths of this brain's registered epoch files, oldest first.
+    pub fn epoch_files(&self) -> Result<Vec<PathBuf>> {
+        let names = self.epoch_file_names()?;
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let dir = self.epoch_dir()?;
+        Ok(names.iter().map(|name| dir.join(name)).collect())
+    }
+
+    fn register_epoch_file(&self, name: &str) -> Result<()> {
+        let mut names = self.epoch_file_names()?;
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+            names.sort();
+            let json = serde_json::Value::from(names).to_string();
+            self.set_metadata(EPOCH_FILES_KEY, &json)?;
+        }
+        Ok(())
+    }
+
+    /// Subconscious episodes an epoch pass would move, with their epoch
+    /// file names: archived ones, and cold ones as `gc_archive_pass`
+    /// picks them.
+    fn epoch_candidates(
+        &self,
+        activation_floor: u32,
+        retention: &crate::config::RetentionPolicy,
+    ) -> Result<Vec<(String, String)>> {
+        if self.neighborhood_count()? < retention.min_neighborhoods {
+            return Ok(Vec::new());
+        }
+        let mut ids: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT id FROM episodes
+                 WHERE brain_id = ?1 AND is_conscious = 0 AND archived = 1
+                 ORDER BY rowid",
+            )?;
+            stmt.query_map([&self.brain], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?
+        };
+        ids.extend(self.cold_episode_ids(activation_floor, retention)?);
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT timestamp FROM episodes WHERE id = ?1 AND brain_id = ?2")?;
+        ids.into_iter()
+            .map(|id| {
+                let timestamp: String =
+                    stmt.query_row(params![id, self.brain], |row| row.get(0))?;
+                Ok((id, epoch_file_name(&timestamp)))
+            })
+            .collect()
+    }
+
+    /// Move cold subconscious episodes into per-year epoch files next to
+    /// this database, then VACUUM it. With `apply` false, only reports what
+    /// would move.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` for an in-memory store.
+    pub fn epoch_pass(
+        &self,
+        activation_floor: u32,
+        retention: &crate::config::RetentionPolicy,
+        apply: bool,
+    ) -> Result<EpochReport> {
+        let dir = self.epoch_dir()?;
+        let before_size = self.db_size();
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        for (id, file) in self.epoch_candidates(activation_floor, retention)? {
+            match groups.iter_mut().find(|(f, _)| *f == file) {
+                Some((_, ids)) => ids.push(id),
+                None => groups.push((file, vec![id])),
+            }
+        }
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut report = EpochReport {
+            before_size,
+            ..EpochReport::default()
+        };
+        for (file, ids) in groups {
+            let neighborhoods = self.count_neighborhoods_of(&ids)?;
+            if apply {
+                self.move_to_epoch(&dir.join(&file), &file, &ids)?;
+            }
+            report.moved.push(EpochMove {
+                file,
+                episodes: ids.len() as u64,
+                neighborhoods,
+            });
+        }
+
+        if apply && !report.moved.is_empty() {
+            // VACUUM to reclaim disk space (must run outside transaction)
+            let _ = self.conn.execute_batch("VACUUM;");
+        }
+        report.after_size = self.db_size();
+        Ok(report)
+    }
+
+    fn count_neighborhoods_of(&self, episode_ids: &[String]) -> Result<u64> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COUNT(*) FROM neighborhoods WHERE episode_id = ?1 AND brain_id = ?2",
+        )?;
+        let mut total = 0u64;
+        for id in episode_ids {
+            total += stmt.query_row(params![id, self.brain], |row| row.get::<_, u64>(0))?;
+        }
+        Ok(total)
+    }
+
+    /// Copy the episodes `ids` with their neighborhoods and occurrences
+    /// into the epoch file at `path`, register it, then delete them here.
+    fn move_to_epoch(&self, path: &Path, file: &str, ids: &[String]) -> Result<()> {
+        prepare_epoch_file(path)?;
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| StoreError::InvalidData(format!("non-UTF-8 path {}", path.display())))?;
+        self.conn
+            .execute("ATTACH DATABASE ?1 AS epoch", [path_str])?;
+        let moved = self.retry_busy("move_to_epoch", || {
+            self.conn.execute_batch(
+                "CREATE TEMP TABLE IF NOT EXISTS epoch_move (id TEXT PRIMARY KEY);
+                 DELETE FROM temp.epoch_move;",
+            )?;
+            {
+                let mut insert = self
+                    .conn
+                    .prepare("INSERT OR IGNORE INTO temp.epoch_move (id) VALUES (?1)")?;
+                for id in ids {
+                    insert.execute([id])?;
+                }
+            }
+
+            let filters = [
+                "id IN (SELECT id FROM temp.epoch_move)",
+                "episode_id IN (SELECT id FROM temp.epoch_move)",
+                "neighborhood_id IN (SELECT n.id FROM main.neighborhoods n
+                                     WHERE n.brain_id = ?1
+                                       AND n.episode_id IN (SELECT id FROM temp.epoch_move))",
+            ];
+
+            // Copy and commit first, so a failure below loses nothing. Rows
+            // left by an interrupted pass are cleared first; neighborhoods and
+            // occurrences cascade from their episode.
+            let tx = self.transaction()?;
+            tx.execute(
+                "DELETE FROM epoch.episodes WHERE brain_id = ?1
+                 AND id IN (SELECT id FROM temp.epoch_move)",
+                [&self.brain],
+            )?;
+            for (table, filter) in EPOCH_TABLES.iter().zip(filters) {
+                let epoch_columns = table_columns(&tx, "epoch", table)?;
+                let columns = table_columns(&tx, "main", table)?
+                    .into_iter()
+                    .filter(|c| epoch_columns.contains(c))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                tx.execute(
+                    &format!(
+                        "INSERT INTO epoch.{table} ({columns})
+                         SELECT {columns} FROM main.{table}
+                         WHERE brain_id = ?1 AND {filter}"
+                    ),
+                    [&self.brain],
+                )?;
+            }
+            // Everything in an epoch file is searched, archived or not
+            tx.execute(
+                "UPDATE epoch.episodes SET archived = 0
+                 WHERE brain_id = ?1 AND id IN (SELECT id FROM temp.epoch_move)",
+                [&self.brain],
+            )?;
+            tx.commit()?;
+            self.register_epoch_file(file)?;
+
+            let tx = self.transaction()?;
+            tx.execute(
+                "DELETE FROM main.episodes WHERE brain_id = ?1
+                 AND id IN (SELECT id FROM temp.epoch_move)",
+                [&self.brain],
+            )?;
+            tx.execute("DELETE FROM temp.epoch_move", [])?;
+            tx.commit()?;
+            Ok(())
+        });
+        let detached = self.conn.execute("DETACH DATABASE epoch", []);
+        moved?;
+        detached?;
+        Ok(())
+    }
+
+    /// Subconscious episodes of this brain in every registered epoch file,
+    /// each opened read-only. Missing files are logged and skipped.
+    pub fn load_epoch_episodes(&self) -> Result<Vec<Episode>> {
+        let mut episodes = Vec::new();
+        for path in self.epoch_files()? {
+            if !path.exists() {
+                tracing::warn!("epoch file {} is missing; skipping it", path.display());
+                continue;
+            }
+            let epoch = Store::from_conn(open_epoch_file(&path)?).with_brain(&self.brain)?;
+            episodes.extend(
+                epoch
+                    .load_episodes(false)?
+                    .into_iter()
+                    .filter(|ep| !ep.is_conscious),
+            );
+        }
+        Ok(episodes)
+    }
+
+    /// Registered epoch files with this brain's episode count in each.
+    pub fn epoch_info(&self) -> Result<Vec<EpochInfo>> {
+        let mut info = Vec::new();
+        for path in self.epoch_files()? {
+            let (episodes, size) = if path.exists() {
+                let conn = open_epoch_file(&path)?;
+                let episodes = conn.query_row(
+                    "SELECT COUNT(*) FROM episodes WHERE brain_id = ?1",
+                    [&self.brain],
+                    |row| row.get(0),
+                )?;
+                (episodes, std::fs::metadata(&path)?.len())
+            } else {
+                (0, 0)
+            };
+            info.push(EpochInfo {
+                path,
+                episodes,
+                size,
+            });
+        }
+        Ok(info)
+    }
+}
@@ -1,122 +1,239 @@
+use am_core::store_trait::{AuditEvent, AuditKind};
+use rusqlite::{OptionalExtension, params};
+use uuid::Uuid;
+
 use crate::error::{Result, StoreError};
 
+use super::undo::UndoRows;
 use super::{Store, parse_uuid};
 
+/// What [`Store::forget_neighborhoods`] removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ForgetReport {
+    pub occurrences: u64,
+    pub neighborhoods: u64,
+    /// Subconscious episodes left empty and removed with them.
+    pub episodes: u64,
+    /// The removed neighborhoods that were conscious.
+    pub conscious: Vec<Uuid>,
+}
+
 impl Store {
     /// Delete a specific subconscious episode and all its contents.
     /// Returns the number of occurrences removed, or 0 if not found.
     pub fn forget_episode(&self, episode_id: &str) -> Result<u64> {
-        let uuid = parse_uuid(episode_id)?;
-        let id_str = uuid.to_string();
-
-        // Verify it exists and is not conscious
-        let is_conscious: Option<bool> = self
-            .conn
-            .query_row(
-                "SELECT is_conscious FROM episodes WHERE id = ?1",
-                [&id_str],
-                |row| row.get(0),
-            )
-            .ok();
-
-        match is_conscious {
-            None => return Ok(0),
-            Some(true) => {
-                return Err(StoreError::InvalidData(
-                    "use forget_conscious to remove conscious memories".into(),
-                ));
+        self.retry_busy("forget_episode", || {
+            let uuid = parse_uuid(episode_id)?;
+            let id_str = uuid.to_string();
+
+            // Verify it exists and is not conscious
+            let is_conscious: Option<bool> = self
+                .conn
+                .query_row(
+                    "SELECT is_conscious FROM episodes WHERE id = ?1 AND brain_id = ?2",
+                    params![id_str, self.brain],
+                    |row| row.get(0),
+                )
+                .ok();
+
+            match is_conscious {
+                None => return Ok(0),
+                Some(true) => {
+                    return Err(StoreError::InvalidData(
+                        "use forget_conscious to remove conscious memories".into(),
+                    ));
+                }
+                Some(false) => {}
             }
-            Some(false) => {}
-        }
-
-        let tx = self.conn.unchecked_transaction()?;
-
-        let removed: u64 = tx.execute(
-            "DELETE FROM occurrences WHERE neighborhood_id IN (
-                 SELECT id FROM neighborhoods WHERE episode_id = ?1
-             )",
-            [&id_str],
-        )? as u64;
 
-        tx.execute("DELETE FROM neighborhoods WHERE episode_id = ?1", [&id_str])?;
-
-        tx.execute("DELETE FROM episodes WHERE id = ?1", [&id_str])?;
-
-        tx.commit()?;
-        Ok(removed)
+            let tx = self.transaction()?;
+
+            // Neighborhoods and occurrences go with it (ON DELETE CASCADE),
+            // so copy them for undo first
+            let mut rows = UndoRows::default();
+            let removed = rows.occurrences.take(
+                &tx,
+                "SELECT o.rowid, o.* FROM occurrences o
+                 JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+                 WHERE n.episode_id = ?1 AND n.brain_id = ?2",
+                params![id_str, self.brain],
+            )?;
+            rows.neighborhoods.take(
+                &tx,
+                "SELECT rowid, * FROM neighborhoods WHERE episode_id = ?1 AND brain_id = ?2",
+                params![id_str, self.brain],
+            )?;
+            rows.episodes.take(
+                &tx,
+                "DELETE FROM episodes WHERE id = ?1 AND brain_id = ?2 RETURNING rowid, *",
+                params![id_str, self.brain],
+            )?;
+            let event = AuditEvent::new(AuditKind::Forget, removed)
+                .with_detail(format!("episode {id_str}"));
+            self.audit_forget(&tx, &event, &rows)?;
+
+            tx.commit()?;
+            Ok(removed)
+        })
     }
 
     /// Delete a specific conscious neighborhood by UUID.
     /// Returns the number of occurrences removed, or 0 if not found.
     pub fn forget_conscious(&self, neighborhood_id: &str) -> Result<u64> {
-        let uuid = parse_uuid(neighborhood_id)?;
-        let id_str = uuid.to_string();
-
-        // Verify it's a conscious neighborhood
-        let is_conscious: Option<bool> = self
-            .conn
-            .query_row(
-                "SELECT e.is_conscious FROM neighborhoods n
-                 JOIN episodes e ON n.episode_id = e.id
-                 WHERE n.id = ?1",
-                [&id_str],
-                |row| row.get(0),
-            )
-            .ok();
-
-        match is_conscious {
-            None => return Ok(0),
-            Some(false) => {
-                return Err(StoreError::InvalidData(
-                    "neighborhood is not conscious - use forget_episode instead".into(),
-                ));
+        self.retry_busy("forget_conscious", || {
+            let uuid = parse_uuid(neighborhood_id)?;
+            let id_str = uuid.to_string();
+
+            // Verify it's a conscious neighborhood
+            let is_conscious: Option<bool> = self
+                .conn
+                .query_row(
+                    "SELECT e.is_conscious FROM neighborhoods n
+                     JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                     WHERE n.id = ?1 AND n.brain_id = ?2",
+                    params![id_str, self.brain],
+                    |row| row.get(0),
+                )
+                .ok();
+
+            match is_conscious {
+                None => return Ok(0),
+                Some(false) => {
+                    return Err(StoreError::InvalidData(
+                        "neighborhood is not conscious - use forget_episode instead".into(),
+                    ));
+                }
+                Some(true) => {}
             }
-            Some(true) => {}
-        }
-
-        let tx = self.conn.unchecked_transaction()?;
-
-        let removed: u64 = tx.execute(
-            "DELETE FROM occurrences WHERE neighborhood_id = ?1",
-            [&id_str],
-        )? as u64;
 
-        tx.execute("DELETE FROM neighborhoods WHERE id = ?1", [&id_str])?;
-
-        tx.commit()?;
-        Ok(removed)
+            let tx = self.transaction()?;
+
+            // Its occurrences go with it (ON DELETE CASCADE), so copy them
+            // for undo first
+            let mut rows = UndoRows::default();
+            let removed = rows.occurrences.take(
+                &tx,
+                "SELECT rowid, * FROM occurrences WHERE neighborhood_id = ?1 AND brain_id = ?2",
+                params![id_str, self.brain],
+            )?;
+            rows.neighborhoods.take(
+                &tx,
+                "DELETE FROM neighborhoods WHERE id = ?1 AND brain_id = ?2 RETURNING rowid, *",
+                params![id_str, self.brain],
+            )?;
+            let event = AuditEvent::new(AuditKind::Forget, removed)
+                .with_detail(format!("conscious {id_str}"));
+            self.audit_forget(&tx, &event, &rows)?;
+
+            tx.commit()?;
+            Ok(removed)
+        })
     }
 
     /// Delete all occurrences matching a word (case-insensitive), clean empty structures.
     /// Returns (removed_occurrences, removed_neighborhoods, removed_episodes).
     pub fn forget_term(&self, term: &str) -> Result<(u64, u64, u64)> {
-        let word_lower = term.to_lowercase();
-
-        let tx = self.conn.unchecked_transaction()?;
-
-        let removed_occs: u64 = tx.execute(
-            "DELETE FROM occurrences WHERE LOWER(word) = ?1",
-            [&word_lower],
-        )? as u64;
-
-        // Clean empty neighborhoods (both conscious and subconscious)
-        let removed_neighborhoods: u64 = tx.execute(
-            "DELETE FROM neighborhoods WHERE id NOT IN (
-                 SELECT DISTINCT neighborhood_id FROM occurrences
-             )",
-            [],
-        )? as u64;
-
-        // Clean empty non-conscious episodes
-        let removed_episodes: u64 = tx.execute(
-            "DELETE FROM episodes WHERE is_conscious = 0
-             AND id NOT IN (
-                 SELECT DISTINCT episode_id FROM neighborhoods
-             )",
-            [],
-        )? as u64;
-
-        tx.commit()?;
-        Ok((removed_occs, removed_neighborhoods, removed_episodes))
+        self.retry_busy("forget_term", || {
+            let word_lower = term.to_lowercase();
+
+            let tx = self.transaction()?;
+            let mut rows = UndoRows::default();
+
+            let removed_occs = rows.occurrences.take(
+                &tx,
+                "DELETE FROM occurrences WHERE LOWER(word) = ?1 AND brain_id = ?2 RETURNING rowid, *",
+                params![word_lower, self.brain],
+            )?;
+
+            // Clean empty neighborhoods (both conscious and subconscious)
+            let removed_neighborhoods = rows.neighborhoods.take(
+                &tx,
+                "DELETE FROM neighborhoods WHERE brain_id = ?1 AND id NOT IN (
+                     SELECT DISTINCT neighborhood_id FROM occurrences WHERE brain_id = ?1
+                 )
+                 RETURNING rowid, *",
+                [&self.brain],
+            )?;
+
+            // Clean empty non-conscious episodes
+            let removed_episodes = rows.episodes.take(
+                &tx,
+                "DELETE FROM episodes WHERE is_conscious = 0 AND brain_id = ?1
+                 AND id NOT IN (
+                     SELECT DISTINCT episode_id FROM neighborhoods WHERE brain_id = ?1
+                 )
+                 RETURNING rowid, *",
+                [&self.brain],
+            )?;
+            let event = AuditEvent::new(AuditKind::Forget, removed_occs)
+                .with_detail(format!("term {word_lower}"));
+            self.audit_forget(&tx, &event, &rows)?;
+
+            tx.commit()?;
+            Ok((removed_occs, removed_neighborhoods, removed_episodes))
+        })
+    }
+
+    /// Delete neighborhoods by ID, conscious or not, with their
+    /// occurrences and any subconscious episode they leave empty. Unknown
+    /// IDs are skipped. Audited as one `forget` with `detail` and one undo
+    /// point.
+    pub fn forget_neighborhoods(&self, ids: &[Uuid], detail: &str) -> Result<ForgetReport> {
+        self.retry_busy("forget_neighborhoods", || {
+            let tx = self.transaction()?;
+            let mut rows = UndoRows::default();
+            let mut report = ForgetReport::default();
+            let mut episodes: Vec<String> = Vec::new();
+
+            for id in ids {
+                let id_str = id.to_string();
+                let Some((episode_id, is_conscious)) = tx
+                    .query_row(
+                        "SELECT n.episode_id, e.is_conscious FROM neighborhoods n
+                         JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                         WHERE n.id = ?1 AND n.brain_id = ?2",
+                        params![id_str, self.brain],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)),
+                    )
+                    .optional()?
+                else {
+                    continue;
+                };
+                report.occurrences += rows.occurrences.take(
+                    &tx,
+                    "DELETE FROM occurrences WHERE neighborhood_id = ?1 AND brain_id = ?2
+                     RETURNING rowid, *",
+                    params![id_str, self.brain],
+                )?;
+                report.neighborhoods += rows.neighborhoods.take(
+                    &tx,
+                    "DELETE FROM neighborhoods WHERE id = ?1 AND brain_id = ?2 RETURNING rowid, *",
+                    params![id_str, self.brain],
+                )?;
+                if is_conscious {
+                    report.conscious.push(*id);
+                } else if !episodes.contains(&episode_id) {
+                    episodes.push(episode_id);
+                }
+            }
+
+            for episode_id in &episodes {
+                report.episodes += rows.episodes.take(
+                    &tx,
+                    "DELETE FROM episodes WHERE id = ?1 AND brain_id = ?2 AND is_conscious = 0
+                     AND NOT EXISTS (SELECT 1 FROM neighborhoods WHERE episode_id = ?1 AND brain_id = ?2)
+                     RETURNING rowid, *",
+                    params![episode_id, self.brain],
+                )?;
+            }
+
+            if report.neighborhoods == 0 {
+                return Ok(report);
+            }
+            let event = AuditEvent::new(AuditKind::Forget, report.occurrences).with_detail(detail);
+            self.audit_forget(&tx, &event, &rows)?;
+            tx.commit()?;
+            Ok(report)
+        })
     }
 }
@@ -1,27 +1,74 @@
+use am_core::store_trait::{AuditEvent, AuditKind};
+use am_core::system::GcDetail;
+use rusqlite::{Connection, Params};
+use uuid::Uuid;
+
 use crate::error::Result;
 
-use super::Store;
+use super::undo::{RowSet, UndoRows};
+use super::{Store, parse_uuid};
 
 #[derive(Debug)]
 pub struct GcResult {
     pub evicted_occurrences: u64,
     pub removed_neighborhoods: u64,
     pub removed_episodes: u64,
+    pub archived_episodes: u64,
     pub before_occurrences: u64,
     pub before_size: u64,
     pub after_size: u64,
+    /// IDs behind the counts above, for `DAESystem::apply_gc`. Archived
+    /// episodes are listed with the removed ones: neither is part of a
+    /// loaded system any more.
+    pub detail: GcDetail,
+}
+
+/// Clears this brain's subconscious neighborhoods that have no occurrences left.
+const DELETE_EMPTY_NEIGHBORHOODS: &str =
+    "DELETE FROM neighborhoods WHERE brain_id = ?1 AND id NOT IN (
+         SELECT DISTINCT neighborhood_id FROM occurrences WHERE brain_id = ?1
+     ) AND episode_id IN (
+         SELECT id FROM episodes WHERE is_conscious = 0 AND brain_id = ?1
+     )
+     RETURNING rowid, *";
+
+/// Clears this brain's subconscious episodes that have no neighborhoods left.
+const DELETE_EMPTY_EPISODES: &str = "DELETE FROM episodes WHERE is_conscious = 0 AND brain_id = ?1
+     AND id NOT IN (
+         SELECT DISTINCT episode_id FROM neighborhoods WHERE brain_id = ?1
+     )
+     RETURNING rowid, *";
+
+/// Run a `... RETURNING` statement and parse the `id`s it returns. With
+/// `undo`, the statement returns `rowid, *` and the rows are kept there.
+fn returning_ids(
+    conn: &Connection,
+    sql: &str,
+    params: impl Params,
+    undo: Option<&mut RowSet>,
+) -> Result<Vec<Uuid>> {
+    let ids = match undo {
+        Some(rows) => rows.take_ids(conn, sql, params)?,
+        None => {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map(params, |row| row.get::<_, String>("id"))?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        }
+    };
+    ids.iter().map(|id| parse_uuid(id)).collect()
 }
 
 impl Store {
     /// Count occurrences eligible for GC eviction at the given activation floor.
-    /// Excludes conscious episodes.
+    /// Excludes conscious and archived episodes.
     pub fn gc_eligible_count(&self, activation_floor: u32) -> Result<u64> {
         let count: u64 = self.conn.query_row(
             "SELECT COUNT(*) FROM occurrences o
-             JOIN neighborhoods n ON o.neighborhood_id = n.id
-             JOIN episodes e ON n.episode_id = e.id
-             WHERE e.is_conscious = 0 AND o.activation_count <= ?1",
-            [activation_floor],
+             JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             WHERE e.brain_id = ?2 AND e.is_conscious = 0 AND e.archived = 0
+               AND o.activation_count <= ?1",
+            rusqlite::params![activation_floor, self.brain],
             |row| row.get(0),
         )?;
         Ok(count)
@@ -29,246 +76,414 @@ impl Store {
 
     /// Run a GC pass: evict cold occurrences, clean empty structures, VACUUM.
     /// Returns (evicted_occurrences, removed_episodes).
-    /// Conscious episodes (is_conscious = 1) are never touched.
+    /// Conscious episodes (is_conscious = 1) and archived episodes are never touched.
     /// Respects retention policy: grace epoch window and retention days.
     pub fn gc_pass(
         &self,
         activation_floor: u32,
         retention: &crate::config::RetentionPolicy,
     ) -> Result<GcResult> {
-        // Early return if below min_neighborhoods floor
-        let total_nbhds = self.neighborhood_count()?;
-        if total_nbhds < retention.min_neighborhoods {
-            return Ok(GcResult {
-                evicted_occurrences: 0,
-                removed_neighborhoods: 0,
-                removed_episodes: 0,
-                before_occurrences: self.occurrence_count()?,
-                before_size: self.db_size(),
-                after_size: self.db_size(),
-            });
-        }
+        self.retry_busy("gc_pass", || {
+            // Early return if below min_neighborhoods floor
+            let total_nbhds = self.neighborhood_count()?;
+            if total_nbhds < retention.min_neighborhoods {
+                return Ok(GcResult {
+                    evicted_occurrences: 0,
+                    removed_neighborhoods: 0,
+                    removed_episodes: 0,
+                    archived_episodes: 0,
+                    before_occurrences: self.occurrence_count()?,
+                    before_size: self.db_size(),
+                    after_size: self.db_size(),
+                    detail: GcDetail::default(),
+                });
+            }
 
-        let before_occs = self.occurrence_count()?;
-        let before_size = self.db_size();
+            let before_occs = self.occurrence_count()?;
+            let before_size = self.db_size();
 
-        // Compute retention parameters. When a retention dimension is disabled,
-        // use sentinel -1 which makes the SQL clause a no-op via short-circuit:
-        //   ?2 = -1 bypasses the epoch filter
-        //   ?3 = -1 bypasses the timestamp filter
-        let epoch_floor: i64 = if retention.grace_epochs > 0 {
-            let max_epoch: u64 = self
-                .conn
-                .query_row(
-                    "SELECT COALESCE(MAX(epoch), 0) FROM neighborhoods",
-                    [],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0);
-            max_epoch.saturating_sub(retention.grace_epochs) as i64
-        } else {
-            -1
-        };
-        let retention_secs: i64 = if retention.retention_days > 0 {
-            (retention.retention_days as i64) * 86400
-        } else {
-            -1
-        };
+            // Compute retention parameters. When a retention dimension is disabled,
+            // use sentinel -1 which makes the SQL clause a no-op via short-circuit:
+            //   ?2 = -1 bypasses the epoch filter
+            //   ?3 = -1 bypasses the timestamp filter
+            let epoch_floor: i64 = if retention.grace_epochs > 0 {
+                self.max_epoch().saturating_sub(retention.grace_epochs) as i64
+            } else {
+                -1
+            };
+            let retention_secs: i64 = if retention.retention_days > 0 {
+                (retention.retention_days as i64) * 86400
+            } else {
+                -1
+            };
+
+            let tx = self.transaction()?;
 
-        let tx = self.conn.unchecked_transaction()?;
-
-        // 1. Delete occurrences at or below the activation floor,
-        //    but only from non-conscious episodes, and respecting retention.
-        // Fixed SQL shape: ?2 = -1 disables epoch check, ?3 = -1 disables retention check.
-        let evicted_occs: u64 = tx.execute(
-            "DELETE FROM occurrences WHERE activation_count <= ?1
-             AND neighborhood_id IN (
-                 SELECT n.id FROM neighborhoods n
-                 JOIN episodes e ON n.episode_id = e.id
-                 WHERE e.is_conscious = 0
-                   AND (?2 = -1 OR n.epoch < ?2)
-                   AND (?3 = -1 OR e.timestamp = ''
-                        OR REPLACE(REPLACE(e.timestamp, 'T', ' '), 'Z', '')
-                           < datetime('now', '-' || ?3 || ' seconds'))
-             )",
-            rusqlite::params![activation_floor, epoch_floor, retention_secs],
-        )? as u64;
-
-        // 2. Delete neighborhoods that have no remaining occurrences
-        //    (only from non-conscious episodes)
-        let removed_neighborhoods: u64 = tx.execute(
-            "DELETE FROM neighborhoods WHERE id NOT IN (
-                 SELECT DISTINCT neighborhood_id FROM occurrences
-             ) AND episode_id IN (
-                 SELECT id FROM episodes WHERE is_conscious = 0
-             )",
-            [],
-        )? as u64;
-
-        // 3. Delete episodes that have no remaining neighborhoods
-        //    (only non-conscious)
-        let removed_episodes: u64 = tx.execute(
-            "DELETE FROM episodes WHERE is_conscious = 0
-             AND id NOT IN (
-                 SELECT DISTINCT episode_id FROM neighborhoods
-             )",
-            [],
-        )? as u64;
-
-        tx.commit()?;
-
-        // 4. VACUUM to reclaim disk space (must run outside transaction)
-        let _ = self.conn.execute_batch("VACUUM;");
-
-        let after_size = self.db_size();
-
-        Ok(GcResult {
-            evicted_occurrences: evicted_occs,
-            removed_neighborhoods,
-            removed_episodes,
-            before_occurrences: before_occs,
-            before_size,
-            after_size,
+            // 1. Delete occurrences at or below the activation floor,
+            //    but only from non-conscious episodes, and respecting retention.
+            // Fixed SQL shape: ?2 = -1 disables epoch check, ?3 = -1 disables retention check.
+            let occurrences = returning_ids(
+                &tx,
+                "DELETE FROM occurrences WHERE activation_count <= ?1 AND brain_id = ?4
+                 AND neighborhood_id IN (
+                     SELECT n.id FROM neighborhoods n
+                     JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                     WHERE e.brain_id = ?4 AND e.is_conscious = 0 AND e.archived = 0
+                       AND (?2 = -1 OR n.epoch < ?2)
+                       AND (?3 = -1 OR e.timestamp = ''
+                            OR REPLACE(REPLACE(e.timestamp, 'T', ' '), 'Z', '')
+                               < datetime('now', '-' || ?3 || ' seconds'))
+                 )
+                 RETURNING id",
+                rusqlite::params![activation_floor, epoch_floor, retention_secs, self.brain],
+                None,
+            )?;
+
+            // 2. Delete neighborhoods that have no remaining occurrences
+            //    (only from non-conscious episodes)
+            let neighborhoods =
+                returning_ids(&tx, DELETE_EMPTY_NEIGHBORHOODS, [&self.brain], None)?;
+
+            // 3. Delete episodes that have no remaining neighborhoods
+            //    (only non-conscious)
+            let episodes = returning_ids(&tx, DELETE_EMPTY_EPISODES, [&self.brain], None)?;
+            self.audit_eviction(&tx, occurrences.len(), episodes.len(), None)?;
+
+            tx.commit()?;
+
+            // 4. VACUUM to reclaim disk space (must run outside transaction)
+            let _ = self.conn.execute_batch("VACUUM;");
+
+            let after_size = self.db_size();
+
+            let detail = GcDetail {
+                occurrences,
+                neighborhoods,
+                episodes,
+            };
+            self.record_evicted(&detail);
+            Ok(GcResult {
+                evicted_occurrences: detail.occurrences.len() as u64,
+                removed_neighborhoods: detail.neighborhoods.len() as u64,
+                removed_episodes: detail.episodes.len() as u64,
+                archived_episodes: 0,
+                before_occurrences: before_occs,
+                before_size,
+                after_size,
+                detail,
+            })
         })
     }
 
     /// Aggressive GC: evict coldest occurrences until DB is under target size.
     /// Only used when activation-floor eviction wasn't sufficient.
-    /// Conscious episodes are never touched.
+    /// Conscious and archived episodes are never touched.
     /// Uses composite eviction score: lower activation and older epoch = evicted first.
     pub fn gc_to_target_size(
         &self,
         target_bytes: u64,
         retention: &crate::config::RetentionPolicy,
     ) -> Result<GcResult> {
-        let before_occs = self.occurrence_count()?;
-        let before_size = self.db_size();
+        self.retry_busy("gc_to_target_size", || {
+            let before_occs = self.occurrence_count()?;
+            let before_size = self.db_size();
 
-        // Parameters: ?1 = max_epoch_f, ?2 = recency_weight,
-        // ?3 = epoch_floor (-1 sentinel disables), ?4 = retention_secs (-1 sentinel disables).
-        let max_epoch: u64 = self
-            .conn
-            .query_row(
-                "SELECT COALESCE(MAX(epoch), 0) FROM neighborhoods",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
-        let max_epoch_f = (max_epoch as f64).max(1.0);
+            // Parameters: ?1 = max_epoch_f, ?2 = recency_weight,
+            // ?3 = epoch_floor (-1 sentinel disables), ?4 = retention_secs (-1 sentinel disables),
+            // ?5 = brain.
+            let max_epoch = self.max_epoch();
+            let max_epoch_f = (max_epoch as f64).max(1.0);
 
-        // Sentinel -1 disables the clause via short-circuit in SQL.
-        let epoch_floor: i64 = if retention.grace_epochs > 0 {
-            max_epoch.saturating_sub(retention.grace_epochs) as i64
-        } else {
-            -1
-        };
-        let retention_secs: i64 = if retention.retention_days > 0 {
-            (retention.retention_days as i64) * 86400
-        } else {
-            -1
-        };
+            // Sentinel -1 disables the clause via short-circuit in SQL.
+            let epoch_floor: i64 = if retention.grace_epochs > 0 {
+                max_epoch.saturating_sub(retention.grace_epochs) as i64
+            } else {
+                -1
+            };
+            let retention_secs: i64 = if retention.retention_days > 0 {
+                (retention.retention_days as i64) * 86400
+            } else {
+                -1
+            };
 
-        // Get occurrences sorted by composite eviction score (most evictable first).
-        // Score = activation_count - (epoch / max_epoch) * recency_weight
-        // Lower score = higher eviction priority.
-        // Fixed SQL shape: ?3 = -1 disables epoch check, ?4 = -1 disables retention check.
-        let mut stmt = self.conn.prepare(
-            "SELECT o.id, o.activation_count FROM occurrences o
-                 JOIN neighborhoods n ON o.neighborhood_id = n.id
-                 JOIN episodes e ON n.episode_id = e.id
-                 WHERE e.is_conscious = 0
-                   AND (?3 = -1 OR n.epoch < ?3)
-                   AND (?4 = -1 OR e.timestamp = ''
-                        OR REPLACE(REPLACE(e.timestamp, 'T', ' '), 'Z', '')
-                           < datetime('now', '-' || ?4 || ' seconds'))
-                 ORDER BY (o.activation_count - (CAST(n.epoch AS REAL) / ?1) * ?2) ASC",
-        )?;
+            // Get occurrences sorted by composite eviction score (most evictable first).
+            // Score = activation_count - (epoch / max_epoch) * recency_weight
+            // Lower score = higher eviction priority.
+            // Fixed SQL shape: ?3 = -1 disables epoch check, ?4 = -1 disables retention check.
+            let mut stmt = self.conn.prepare(
+                "SELECT o.id, o.activation_count FROM occurrences o
+                     JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+                     JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                     WHERE e.brain_id = ?5 AND e.is_conscious = 0 AND e.archived = 0
+                       AND (?3 = -1 OR n.epoch < ?3)
+                       AND (?4 = -1 OR e.timestamp = ''
+                            OR REPLACE(REPLACE(e.timestamp, 'T', ' '), 'Z', '')
+                               < datetime('now', '-' || ?4 || ' seconds'))
+                     ORDER BY (o.activation_count - (CAST(n.epoch AS REAL) / ?1) * ?2) ASC",
+            )?;
 
-        let rows: Vec<(String, u32)> = stmt
-            .query_map(
-                rusqlite::params![
-                    max_epoch_f,
-                    retention.recency_weight,
-                    epoch_floor,
-                    retention_secs
-                ],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )?
-            .collect::<std::result::Result<_, _>>()?;
+            let rows: Vec<(String, u32)> = stmt
+                .query_map(
+                    rusqlite::params![
+                        max_epoch_f,
+                        retention.recency_weight,
+                        epoch_floor,
+                        retention_secs,
+                        self.brain
+                    ],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?
+                .collect::<std::result::Result<_, _>>()?;
 
-        if rows.is_empty() {
-            return Ok(GcResult {
-                evicted_occurrences: 0,
-                removed_neighborhoods: 0,
-                removed_episodes: 0,
+            if rows.is_empty() {
+                return Ok(GcResult {
+                    evicted_occurrences: 0,
+                    removed_neighborhoods: 0,
+                    removed_episodes: 0,
+                    archived_episodes: 0,
+                    before_occurrences: before_occs,
+                    before_size,
+                    after_size: before_size,
+                    detail: GcDetail::default(),
+                });
+            }
+
+            // Estimate bytes per occurrence: total_size / total_occurrences
+            let total_occs = before_occs.max(1);
+            let bytes_per_occ = before_size / total_occs;
+
+            // Calculate how many we need to evict
+            let excess = before_size.saturating_sub(target_bytes);
+            let to_evict = excess
+                .checked_div(bytes_per_occ)
+                .map_or(0, |n| n.min(rows.len() as u64));
+
+            if to_evict == 0 {
+                return Ok(GcResult {
+                    evicted_occurrences: 0,
+                    removed_neighborhoods: 0,
+                    removed_episodes: 0,
+                    archived_episodes: 0,
+                    before_occurrences: before_occs,
+                    before_size,
+                    after_size: before_size,
+                    detail: GcDetail::default(),
+                });
+            }
+
+            // Delete the coldest occurrences + clean up empty structures
+            // atomically, keeping what goes for `undo_last`
+            let tx = self.transaction()?;
+            let mut undo = self.undo_enabled().then(UndoRows::default);
+            let mut occurrences = Vec::with_capacity(to_evict as usize);
+            for (id, _) in rows.iter().take(to_evict as usize) {
+                occurrences.extend(returning_ids(
+                    &tx,
+                    "DELETE FROM occurrences WHERE id = ?1 AND brain_id = ?2 RETURNING rowid, *",
+                    rusqlite::params![id, self.brain],
+                    undo.as_mut().map(|u| &mut u.occurrences),
+                )?);
+            }
+
+            let neighborhoods = returning_ids(
+                &tx,
+                DELETE_EMPTY_NEIGHBORHOODS,
+                [&self.brain],
+                undo.as_mut().map(|u| &mut u.neighborhoods),
+            )?;
+            let episodes = returning_ids(
+                &tx,
+                DELETE_EMPTY_EPISODES,
+                [&self.brain],
+                undo.as_mut().map(|u| &mut u.episodes),
+            )?;
+            self.audit_eviction(&tx, occurrences.len(), episodes.len(), undo.as_ref())?;
+
+            tx.commit()?;
+
+            // VACUUM to reclaim disk space (must run outside transaction)
+            let _ = self.conn.execute_batch("VACUUM;");
+            let after_size = self.db_size();
+
+            let detail = GcDetail {
+                occurrences,
+                neighborhoods,
+                episodes,
+            };
+            self.record_evicted(&detail);
+            Ok(GcResult {
+                evicted_occurrences: detail.occurrences.len() as u64,
+                removed_neighborhoods: detail.neighborhoods.len() as u64,
+                removed_episodes: detail.episodes.len() as u64,
+                archived_episodes: 0,
                 before_occurrences: before_occs,
                 before_size,
-                after_size: before_size,
-            });
-        }
+                after_size,
+                detail,
+            })
+        })
+    }
 
-        // Estimate bytes per occurrence: total_size / total_occurrences
-        let total_occs = before_occs.max(1);
-        let bytes_per_occ = before_size / total_occs;
+    /// Count episodes `gc_archive_pass` would archive at this activation floor.
+    pub fn gc_archive_eligible_count(
+        &self,
+        activation_floor: u32,
+        retention: &crate::config::RetentionPolicy,
+    ) -> Result<u64> {
+        Ok(self.cold_episode_ids(activation_floor, retention)?.len() as u64)
+    }
 
-        // Calculate how many we need to evict
-        let excess = before_size.saturating_sub(target_bytes);
-        let to_evict = if bytes_per_occ > 0 {
-            (excess / bytes_per_occ).min(rows.len() as u64)
-        } else {
-            0
-        };
+    /// Archive mode GC: instead of evicting occurrences, flag whole cold
+    /// episodes as archived. An episode is cold when none of its occurrences
+    /// is above the activation floor and it falls outside the retention
+    /// window. Archived episodes keep their rows but are skipped by
+    /// `load_system`; `restore_episode` brings them back.
+    pub fn gc_archive_pass(
+        &self,
+        activation_floor: u32,
+        retention: &crate::config::RetentionPolicy,
+    ) -> Result<GcResult> {
+        self.retry_busy("gc_archive_pass", || {
+            let before_occs = self.occurrence_count()?;
+            let before_size = self.db_size();
 
-        if to_evict == 0 {
-            return Ok(GcResult {
+            let ids = if self.neighborhood_count()? < retention.min_neighborhoods {
+                Vec::new()
+            } else {
+                self.cold_episode_ids(activation_floor, retention)?
+            };
+
+            let tx = self.transaction()?;
+            {
+                let mut update =
+                    tx.prepare("UPDATE episodes SET archived = 1 WHERE id = ?1 AND brain_id = ?2")?;
+                for id in &ids {
+                    update.execute(rusqlite::params![id, self.brain])?;
+                }
+            }
+            if !ids.is_empty() {
+                let event = AuditEvent::new(AuditKind::Gc, ids.len() as u64)
+                    .with_detail(format!("archived {} episodes", ids.len()));
+                self.audit_on(&tx, &event)?;
+            }
+            tx.commit()?;
+
+            Ok(GcResult {
                 evicted_occurrences: 0,
                 removed_neighborhoods: 0,
                 removed_episodes: 0,
+                archived_episodes: ids.len() as u64,
                 before_occurrences: before_occs,
                 before_size,
-                after_size: before_size,
-            });
+                after_size: self.db_size(),
+                detail: GcDetail {
+                    episodes: ids.iter().map(|id| parse_uuid(id)).collect::<Result<_>>()?,
+                    ..GcDetail::default()
+                },
+            })
+        })
+    }
+
+    /// Remember evicted occurrences for the resurrection check in
+    /// `save_system`.
+    /// Audit an eviction pass on its transaction, unless it removed nothing.
+    fn audit_eviction(
+        &self,
+        tx: &Connection,
+        occurrences: usize,
+        episodes: usize,
+        undo: Option<&UndoRows>,
+    ) -> Result<()> {
+        if occurrences == 0 {
+            return Ok(());
         }
+        let event = AuditEvent::new(AuditKind::Gc, occurrences as u64).with_detail(format!(
+            "evicted {occurrences} occurrences, {episodes} episodes"
+        ));
+        match undo {
+            Some(rows) => self.audit_with_undo(tx, &event, rows).map(drop),
+            None => self.audit_on(tx, &event),
+        }
+    }
 
-        // Delete the coldest occurrences + clean up empty structures atomically
-        let tx = self.conn.unchecked_transaction()?;
-        {
-            let mut del_stmt = tx.prepare("DELETE FROM occurrences WHERE id = ?1")?;
-            for (id, _) in rows.iter().take(to_evict as usize) {
-                del_stmt.execute([id])?;
-            }
+    fn record_evicted(&self, detail: &GcDetail) {
+        let mut evicted = self.gc_evicted.lock().expect("poisoned mutex");
+        evicted.extend(detail.occurrences.iter().copied());
+    }
+
+    /// How many occurrences in `system` GC already evicted through this
+    /// store. Nonzero means the system was loaded before the GC pass and
+    /// never reconciled with `DAESystem::apply_gc`.
+    pub fn resurrected_count(&self, system: &am_core::system::DAESystem) -> u64 {
+        let evicted = self.gc_evicted.lock().expect("poisoned mutex");
+        if evicted.is_empty() {
+            return 0;
         }
+        system
+            .episodes
+            .iter()
+            .flat_map(|e| &e.neighborhoods)
+            .flat_map(|n| &n.occurrences)
+            .filter(|o| evicted.contains(&o.id))
+            .count() as u64
+    }
 
-        let removed_neighborhoods: u64 = tx.execute(
-            "DELETE FROM neighborhoods WHERE id NOT IN (
-                 SELECT DISTINCT neighborhood_id FROM occurrences
-             ) AND episode_id IN (
-                 SELECT id FROM episodes WHERE is_conscious = 0
-             )",
-            [],
-        )? as u64;
-
-        let removed_episodes: u64 = tx.execute(
-            "DELETE FROM episodes WHERE is_conscious = 0
-             AND id NOT IN (
-                 SELECT DISTINCT episode_id FROM neighborhoods
-             )",
-            [],
-        )? as u64;
-
-        tx.commit()?;
-
-        // VACUUM to reclaim disk space (must run outside transaction)
-        let _ = self.conn.execute_batch("VACUUM;");
-        let after_size = self.db_size();
-
-        Ok(GcResult {
-            evicted_occurrences: to_evict,
-            removed_neighborhoods,
-            removed_episodes,
-            before_occurrences: before_occs,
-            before_size,
-            after_size,
-        })
+    /// Highest neighborhood epoch in this brain, or 0 when it is empty.
+    fn max_epoch(&self) -> u64 {
+        self.conn
+            .query_row(
+                "SELECT COALESCE(MAX(epoch), 0) FROM neighborhoods WHERE brain_id = ?1",
+                [&self.brain],
+                |row| row.get(0),
+            )
+            .unwrap_or(0)
+    }
+
+    /// IDs of unarchived subconscious episodes with at least one neighborhood,
+    /// no occurrence above `activation_floor`, and nothing inside the
+    /// retention window.
+    pub(super) fn cold_episode_ids(
+        &self,
+        activation_floor: u32,
+        retention: &crate::config::RetentionPolicy,
+    ) -> Result<Vec<String>> {
+        // Same sentinel scheme as gc_pass: -1 disables a retention clause.
+        let epoch_floor: i64 = if retention.grace_epochs > 0 {
+            self.max_epoch().saturating_sub(retention.grace_epochs) as i64
+        } else {
+            -1
+        };
+        let retention_secs: i64 = if retention.retention_days > 0 {
+            (retention.retention_days as i64) * 86400
+        } else {
+            -1
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT e.id FROM episodes e
+             WHERE e.brain_id = ?4 AND e.is_conscious = 0 AND e.archived = 0
+               AND EXISTS (SELECT 1 FROM neighborhoods n
+                           WHERE n.episode_id = e.id AND n.brain_id = e.brain_id)
+               AND NOT EXISTS (
+                   SELECT 1 FROM neighborhoods n
+                   JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+                   WHERE n.episode_id = e.id AND n.brain_id = e.brain_id AND o.activation_count > ?1
+               )
+               AND (?2 = -1 OR NOT EXISTS (
+                   SELECT 1 FROM neighborhoods n
+                   WHERE n.episode_id = e.id AND n.brain_id = e.brain_id AND n.epoch >= ?2
+               ))
+               AND (?3 = -1 OR e.timestamp = ''
+                    OR REPLACE(REPLACE(e.timestamp, 'T', ' '), 'Z', '')
+                       < datetime('now', '-' || ?3 || ' seconds'))
+             ORDER BY e.rowid",
+        )?;
+        let ids = stmt
+            .query_map(
+                rusqlite::params![activation_floor, epoch_floor, retention_secs, self.brain],
+                |row| row.get(0),
+            )?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(ids)
     }
 }
@@ -1,17 +1,17 @@
+use std::collections::HashMap;
+
 use uuid::Uuid;
 
 use am_core::{
     episode::Episode,
     neighborhood::{Neighborhood, NeighborhoodType},
     occurrence::Occurrence,
-    phasor::DaemonPhasor,
-    quaternion::Quaternion,
     system::DAESystem,
 };
 
 use crate::error::Result;
 
-use super::{Store, parse_uuid};
+use super::{Store, parse_uuid, span_from, text, validate::RowChecker};
 
 impl Store {
     pub fn load_system(&self) -> Result<DAESystem> {
@@ -20,123 +20,179 @@ impl Store {
             .unwrap_or_else(|| "unknown".to_string());
 
         let mut system = DAESystem::new(&agent_name);
+        for ep in self.load_episodes(false)? {
+            if ep.is_conscious {
+                system.conscious_episode = ep;
+            } else {
+                system.episodes.push(ep);
+            }
+        }
 
-        // Single three-way JOIN replaces the previous 1 + N + N*M query pattern.
-        // LEFT JOINs handle episodes with no neighborhoods and neighborhoods with no occurrences.
-        let mut stmt = self.conn.prepare(
-            "SELECT e.id, e.name, e.is_conscious, e.timestamp,
-                    n.id, n.seed_w, n.seed_x, n.seed_y, n.seed_z,
-                    n.source_text, COALESCE(n.neighborhood_type, 'memory'),
-                    n.epoch, n.superseded_by,
-                    o.id, o.word, o.pos_w, o.pos_x, o.pos_y, o.pos_z,
-                    o.phasor_theta, o.activation_count
-             FROM episodes e
-             LEFT JOIN neighborhoods n ON n.episode_id = e.id
-             LEFT JOIN occurrences o ON o.neighborhood_id = n.id
-             ORDER BY e.rowid, n.rowid, o.rowid",
+        system.set_word_weight_policy(self.word_weight_policy()?);
+        system.set_compose_config(self.compose_config()?);
+        system.set_drift_frozen(self.frozen_words()?);
+        for (word, multiplier) in self.word_biases()? {
+            system.set_word_bias(&word, multiplier);
+        }
+        system.mark_dirty();
+        system.sync_next_epoch();
+        // Rowids are shared across brains, so new neighborhoods number
+        // above the whole table, not just this brain's rows.
+        let max_rowid: u64 = self.conn.query_row(
+            "SELECT COALESCE(MAX(rowid), 0) FROM neighborhoods",
+            [],
+            |r| r.get(0),
         )?;
+        system.sync_seq(max_rowid);
+        Ok(system)
+    }
+
+    /// Episodes parked by `gc_archive_pass`. `load_system` leaves these out;
+    /// callers that opt into deep recall append them for a single query.
+    pub fn load_archived_episodes(&self) -> Result<Vec<Episode>> {
+        self.load_episodes(true)
+    }
 
-        // Track current episode and neighborhood being assembled.
-        let mut current_ep_id: Option<String> = None;
-        let mut current_nbhd_id: Option<String> = None;
-        let mut current_episode: Option<Episode> = None;
-        let mut current_nbhd: Option<Neighborhood> = None;
+    /// Load every episode with the given archived flag, in insertion order.
+    ///
+    /// One query per table, each in rowid order, assembled in memory by
+    /// parent id. A single three-way JOIN repeats every episode and
+    /// neighborhood column (source text included) on each occurrence row,
+    /// which dominates cold starts on large brains.
+    pub(super) fn load_episodes(&self, archived: bool) -> Result<Vec<Episode>> {
+        let params = rusqlite::params![archived, self.brain];
 
-        let mut rows = stmt.query([])?;
+        let mut episodes = Vec::new();
+        let mut episode_index: HashMap<String, usize> = HashMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, is_conscious, timestamp, actor,
+                    original_timestamp, reused_neighborhoods
+             FROM episodes
+             WHERE archived = ?1 AND brain_id = ?2
+             ORDER BY rowid",
+        )?;
+        let mut rows = stmt.query(params)?;
         while let Some(row) = rows.next()? {
-            let ep_id_str: String = row.get(0)?;
-            let nbhd_id_str: Option<String> = row.get(4)?;
-            let occ_id_str: Option<String> = row.get(13)?;
-
-            // Episode boundary: flush previous neighborhood and episode
-            if current_ep_id.as_ref() != Some(&ep_id_str) {
-                if let Some(nbhd) = current_nbhd.take()
-                    && let Some(ep) = current_episode.as_mut()
-                {
-                    ep.neighborhoods.push(nbhd);
-                }
-                current_nbhd_id = None;
-                if let Some(ep) = current_episode.take() {
-                    if ep.is_conscious {
-                        system.conscious_episode = ep;
-                    } else {
-                        system.episodes.push(ep);
-                    }
-                }
-
-                let ep_id = parse_uuid(&ep_id_str)?;
-                current_episode = Some(Episode {
-                    id: ep_id,
-                    name: row.get(1)?,
-                    is_conscious: row.get::<_, i32>(2)? != 0,
-                    timestamp: row.get(3)?,
-                    neighborhoods: Vec::new(),
-                });
-                current_ep_id = Some(ep_id_str);
-            }
+            let id_str: String = row.get(0)?;
+            episodes.push(Episode {
+                id: parse_uuid(&id_str)?,
+                name: row.get(1)?,
+                is_conscious: row.get::<_, i32>(2)? != 0,
+                timestamp: row.get(3)?,
+                neighborhoods: Vec::new(),
+                actor: row.get(4)?,
+                original_timestamp: row.get(5)?,
+                reused_neighborhoods: parse_reused_neighborhoods(
+                    row.get::<_, Option<String>>(6)?.as_deref(),
+                ),
+            });
+            episode_index.insert(id_str, episodes.len() - 1);
+        }
+
+        let mut checker = RowChecker::new(self.strict_load);
 
-            // Neighborhood boundary
-            if let Some(ref nid) = nbhd_id_str
-                && current_nbhd_id.as_ref() != Some(nid)
-            {
-                if let Some(nbhd) = current_nbhd.take()
-                    && let Some(ep) = current_episode.as_mut()
-                {
-                    ep.neighborhoods.push(nbhd);
-                }
-
-                let id = parse_uuid(nid)?;
-                let superseded_by: Option<String> = row.get(12)?;
-                current_nbhd = Some(Neighborhood {
-                    id,
-                    seed: Quaternion::new(row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?),
+        // Neighborhoods with the index of their episode, kept apart until
+        // their occurrences are attached.
+        let mut neighborhoods: Vec<(usize, Neighborhood)> = Vec::new();
+        let mut neighborhood_index: HashMap<String, usize> = HashMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT n.episode_id, n.id, n.seed_w, n.seed_x, n.seed_y, n.seed_z,
+                    n.source_text, COALESCE(n.neighborhood_type, 'memory'),
+                    n.epoch, n.superseded_by, n.actor, n.created_at, n.rowid, n.project,
+                    n.source_zip, n.feedback_score, n.group_id
+             FROM neighborhoods n
+             JOIN episodes e ON e.id = n.episode_id AND e.brain_id = n.brain_id
+             WHERE e.archived = ?1 AND e.brain_id = ?2
+             ORDER BY n.rowid",
+        )?;
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let episode_id: String = row.get(0)?;
+            let Some(&ep) = episode_index.get(&episode_id) else {
+                continue;
+            };
+            let nid: String = row.get(1)?;
+            let superseded_by: Option<String> = row.get(9)?;
+            neighborhoods.push((
+                ep,
+                Neighborhood {
+                    id: parse_uuid(&nid)?,
+                    seed: checker.quaternion(
+                        "neighborhood",
+                        &nid,
+                        (row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?),
+                    )?,
                     occurrences: Vec::new(),
-                    source_text: row.get(9)?,
-                    neighborhood_type: NeighborhoodType::from_str_lossy(&row.get::<_, String>(10)?),
-                    epoch: row.get(11)?,
+                    source_text: text::decode(&nid, row.get(6)?, row.get(14)?)?,
+                    neighborhood_type: NeighborhoodType::from_str_lossy(&row.get::<_, String>(7)?),
+                    epoch: row.get(8)?,
                     superseded_by: superseded_by.and_then(|s| Uuid::parse_str(&s).ok()),
-                });
-                current_nbhd_id = Some(nid.clone());
-            }
-
-            // Occurrence row
-            if let (Some(oid), Some(nid)) = (&occ_id_str, &nbhd_id_str) {
-                let id = parse_uuid(oid)?;
-                let nbhd_uuid = parse_uuid(nid)?;
-                if let Some(nbhd) = current_nbhd.as_mut() {
-                    nbhd.occurrences.push(Occurrence {
-                        id,
-                        neighborhood_id: nbhd_uuid,
-                        word: row.get(14)?,
-                        position: Quaternion::new(
-                            row.get(15)?,
-                            row.get(16)?,
-                            row.get(17)?,
-                            row.get(18)?,
-                        ),
-                        phasor: DaemonPhasor::new(row.get(19)?),
-                        activation_count: row.get(20)?,
-                    });
-                }
-            }
+                    actor: row.get(10)?,
+                    project: row.get(13)?,
+                    created_at: row.get(11)?,
+                    feedback_score: row.get(15)?,
+                    group_id: row
+                        .get::<_, Option<String>>(16)?
+                        .and_then(|s| Uuid::parse_str(&s).ok()),
+                    seq: row.get(12)?,
+                },
+            ));
+            neighborhood_index.insert(nid, neighborhoods.len() - 1);
         }
 
-        // Flush remaining neighborhood and episode
-        if let Some(nbhd) = current_nbhd.take()
-            && let Some(ep) = current_episode.as_mut()
-        {
-            ep.neighborhoods.push(nbhd);
+        let mut stmt = self.conn.prepare(
+            "SELECT o.neighborhood_id, o.id, o.word, o.pos_w, o.pos_x, o.pos_y, o.pos_z,
+                    o.phasor_theta, o.activation_count, o.drift, o.spillover,
+                    o.span_start, o.span_end
+             FROM occurrences o
+             JOIN neighborhoods n ON n.id = o.neighborhood_id AND n.brain_id = o.brain_id
+             JOIN episodes e ON e.id = n.episode_id AND e.brain_id = n.brain_id
+             WHERE e.archived = ?1 AND e.brain_id = ?2
+             ORDER BY o.rowid",
+        )?;
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let neighborhood_id: String = row.get(0)?;
+            let Some(&idx) = neighborhood_index.get(&neighborhood_id) else {
+                continue;
+            };
+            let nbhd = &mut neighborhoods[idx].1;
+            let oid: String = row.get(1)?;
+            nbhd.occurrences.push(Occurrence {
+                id: parse_uuid(&oid)?,
+                neighborhood_id: nbhd.id,
+                word: row.get(2)?,
+                position: checker.quaternion(
+                    "occurrence",
+                    &oid,
+                    (row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?),
+                )?,
+                phasor: checker.phasor(&oid, row.get(7)?)?,
+                activation_count: row.get(8)?,
+                drift: row.get(9)?,
+                spillover: row.get(10)?,
+                span: span_from(row.get(11)?, row.get(12)?),
+            });
         }
-        if let Some(ep) = current_episode.take() {
-            if ep.is_conscious {
-                system.conscious_episode = ep;
-            } else {
-                system.episodes.push(ep);
-            }
+
+        for (ep, nbhd) in neighborhoods {
+            episodes[ep].neighborhoods.push(nbhd);
         }
 
-        system.mark_dirty();
-        system.sync_next_epoch();
-        Ok(system)
+        self.record_repairs("load_system", checker.repaired);
+        Ok(episodes)
     }
 }
+
+/// Decode `episodes.reused_neighborhoods`. Malformed ids are dropped: the
+/// list is provenance, and a bad entry must not fail the load.
+fn parse_reused_neighborhoods(json: Option<&str>) -> Vec<Uuid> {
+    let Some(json) = json else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<String>>(json)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect()
+}
@@ -1,33 +1,72 @@
 mod activation;
+mod audit;
 mod core;
+mod epoch;
 mod forget;
 pub mod gc;
 mod load;
+mod options;
 mod persist;
+mod quarantine;
 mod query;
+mod retry;
+mod text;
+mod transaction;
+mod undo;
+mod validate;
 
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+
+use am_core::neighborhood::NeighborhoodType;
+use am_core::quaternion::Quaternion;
 use rusqlite::Connection;
 use uuid::Uuid;
 
 use crate::error::{Result, StoreError};
 
+pub use audit::{AuditEntry, AuditFilter};
+pub use epoch::{EpochInfo, EpochMove, EpochReport};
+pub use forget::ForgetReport;
+pub use options::{DEFAULT_BUSY_TIMEOUT_MS, StoreOptions, Synchronous};
+pub use quarantine::{DEFAULT_QUARANTINE_DAYS, QuarantineEntry, RestoreReport};
+pub use retry::{BusyRetry, DEFAULT_RETRY_ATTEMPTS, DEFAULT_RETRY_MAX_WAIT, RetryPolicy};
+pub use text::{DEFAULT_COMPRESS_TEXT_MIN_BYTES, TextCompaction, TextStorage};
+pub use undo::{DEFAULT_UNDO_KEEP, DEFAULT_UNDO_MAX_BYTES, UndoEntry, UndoPolicy, UndoReport};
+pub use validate::STRICT_LOAD_ENV;
+
 #[derive(Debug)]
 pub struct EpisodeInfo {
     pub id: String,
     pub name: String,
     pub is_conscious: bool,
+    pub archived: bool,
     pub timestamp: String,
     pub neighborhood_count: u64,
     pub occurrence_count: u64,
     pub total_activation: u64,
+    pub actor: Option<String>,
+    /// Estimated LLM tokens across the episode's neighborhoods.
+    pub llm_tokens: u64,
 }
 
 #[derive(Debug)]
 pub struct NeighborhoodInfo {
     pub id: String,
     pub source_text: String,
+    pub neighborhood_type: NeighborhoodType,
     pub occurrence_count: u64,
     pub total_activation: u64,
+    pub actor: Option<String>,
+    /// Project the memory is tagged for; `None` applies to every project.
+    pub project: Option<String>,
+    /// ISO-8601 creation time; `None` for rows stored before it was kept.
+    pub created_at: Option<String>,
+    /// Estimated LLM tokens of the source text.
+    pub llm_tokens: u64,
+    /// Shared by the chunks of one long salient text.
+    pub group_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -39,22 +78,289 @@ pub struct NeighborhoodDetail {
     pub occurrence_count: u64,
     pub total_activation: u64,
     pub max_activation: u32,
+    /// The neighborhood's own actor, else its episode's.
+    pub actor: Option<String>,
+    /// Mean and largest angle (radians) its occurrences have drifted
+    /// since they were placed.
+    pub mean_drift: f64,
+    pub max_drift: f64,
+    /// Estimated LLM tokens of the source text.
+    pub llm_tokens: u64,
+    /// Decayed sum of feedback signals; 0 is neutral.
+    pub feedback_score: f64,
+    /// ISO-8601 creation time; `None` for rows stored before it was kept.
+    pub created_at: Option<String>,
+}
+
+/// One neighborhood of an episode with its occurrences, as
+/// `am inspect episode` shows it.
+#[derive(Debug)]
+pub struct EpisodeNeighborhood {
+    pub id: String,
+    pub source_text: String,
+    pub neighborhood_type: NeighborhoodType,
+    pub actor: Option<String>,
+    /// Project the memory is tagged for; `None` applies to every project.
+    pub project: Option<String>,
+    /// ISO-8601 creation time; `None` for rows stored before it was kept.
+    pub created_at: Option<String>,
+    /// Estimated LLM tokens of the source text.
+    pub llm_tokens: u64,
+    /// Decayed sum of feedback signals; 0 is neutral.
+    pub feedback_score: f64,
+    /// Each occurrence's word and activation count, in stored order.
+    pub words: Vec<(String, u32)>,
+}
+
+/// One pending exchange in the conversation buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferEntry {
+    pub id: i64,
+    pub user_text: String,
+    pub assistant_text: String,
+    pub created_at: String,
+}
+
+/// Frequency and IDF weight of one word, aggregated in SQL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordWeight {
+    pub word: String,
+    pub neighborhood_count: u64,
+    pub occurrence_count: u64,
+    /// Clamped IDF weight times `bias`.
+    pub weight: f64,
+    /// Multiplier from `word_biases`, 1.0 when the word has none.
+    pub bias: f64,
+}
+
+/// Where one occurrence sits, as [`Store::for_each_position`] reads it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OccurrencePosition {
+    pub id: String,
+    pub word: String,
+    /// Name of the episode holding it (`conscious` for conscious memory).
+    pub episode: String,
+    pub activation_count: u32,
+    /// Radians moved since placement.
+    pub drift: f64,
+    pub position: Quaternion,
+}
+
+/// How widely words are spread across live neighborhoods, for spotting
+/// vocabulary that floods the IDF distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VocabStats {
+    /// Neighborhoods in live (unarchived) episodes, the denominator for
+    /// each word's share.
+    pub total_neighborhoods: u64,
+    /// Most widespread words first.
+    pub words: Vec<WordStat>,
+}
+
+/// One word's neighborhood frequency, broken down by episode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordStat {
+    pub word: String,
+    pub neighborhood_count: u64,
+    /// Episodes holding those neighborhoods, largest contributor first.
+    pub sources: Vec<WordSource>,
+}
+
+impl WordStat {
+    /// Fraction of all live neighborhoods that contain the word.
+    pub fn share(&self, total_neighborhoods: u64) -> f64 {
+        if total_neighborhoods == 0 {
+            0.0
+        } else {
+            self.neighborhood_count as f64 / total_neighborhoods as f64
+        }
+    }
+}
+
+/// Neighborhoods of one episode that contain a given word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordSource {
+    pub episode_id: String,
+    pub episode_name: String,
+    pub neighborhood_count: u64,
+}
+
+/// Order of an episode listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EpisodeSort {
+    /// Newest episode timestamp first, later insertion breaking ties.
+    #[default]
+    Recent,
+    /// Alphabetical by name.
+    Name,
+    /// Most occurrences first.
+    Size,
+    /// Highest total activation first.
+    Activation,
+}
+
+impl EpisodeSort {
+    /// SQL `ORDER BY` terms over the episode listing query.
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Recent => "e.timestamp DESC, e.rowid DESC",
+            Self::Name => "e.name COLLATE NOCASE, e.timestamp DESC, e.rowid DESC",
+            Self::Size => "occ_count DESC, e.timestamp DESC, e.rowid DESC",
+            Self::Activation => "total_activation DESC, e.timestamp DESC, e.rowid DESC",
+        }
+    }
+}
+
+/// Order of a neighborhood listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NeighborhoodSort {
+    /// Highest total activation first, insertion order breaking ties.
+    #[default]
+    Activation,
+    /// Newest first by creation time; rows without one sort last.
+    Recency,
+    /// Most occurrences first.
+    OccurrenceCount,
+}
+
+impl NeighborhoodSort {
+    /// SQL `ORDER BY` terms over the neighborhood listing query.
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Activation => "total_activation DESC, n.rowid",
+            Self::Recency => "n.created_at IS NULL, n.created_at DESC, n.rowid DESC",
+            Self::OccurrenceCount => "occ_count DESC, n.rowid",
+        }
+    }
+}
+
+/// Order of a conscious memory listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsciousSort {
+    /// The order they were stored in.
+    #[default]
+    Stored,
+    /// Newest first by creation time; rows without one sort last.
+    Recency,
+    /// Highest total activation first.
+    Activation,
+    /// Grouped by neighborhood type, newest first within a type.
+    Type,
+}
+
+impl ConsciousSort {
+    /// SQL `ORDER BY` terms over the conscious listing query.
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Stored => "n.rowid",
+            Self::Recency => "n.created_at IS NULL, n.created_at DESC, n.rowid DESC",
+            Self::Activation => "total_activation DESC, n.rowid",
+            Self::Type => {
+                "n.neighborhood_type, n.created_at IS NULL, n.created_at DESC, n.rowid DESC"
+            }
+        }
+    }
+}
+
+/// A window into an inspection listing, applied as SQL `LIMIT`/`OFFSET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl PageRequest {
+    /// Every row, no offset.
+    pub const ALL: Self = Self {
+        limit: usize::MAX,
+        offset: 0,
+    };
+
+    pub fn new(limit: usize, offset: usize) -> Self {
+        Self { limit, offset }
+    }
+
+    /// `(limit, offset)` as SQLite integers. A limit too large for `i64`
+    /// becomes `-1`, which SQLite treats as unbounded.
+    pub(crate) fn sql_params(self) -> (i64, i64) {
+        (
+            i64::try_from(self.limit).unwrap_or(-1),
+            i64::try_from(self.offset).unwrap_or(i64::MAX),
+        )
+    }
+}
+
+/// One page of an inspection listing plus the total number of matching rows.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
 }
 
 pub struct Store {
     pub(crate) conn: Connection,
+    /// Rows repaired by load invariant checks since the store was opened.
+    pub(crate) repaired_records: AtomicU64,
+    /// Fail loads on invariant violations instead of repairing them.
+    pub(crate) strict_load: bool,
+    /// Every query is scoped to rows with this `brain_id`.
+    pub(crate) brain: String,
+    /// Occurrences GC evicted through this store, so `save_system` can
+    /// notice a stale in-memory system writing them back.
+    pub(crate) gc_evicted: Mutex<HashSet<Uuid>>,
+    /// Warn when a save would resurrect more than this many of them.
+    pub(crate) resurrect_warn: u64,
+    /// How write operations retry on `SQLITE_BUSY`.
+    pub(crate) retry: RetryPolicy,
+    /// Compress subconscious text at least this many bytes long.
+    pub(crate) compress_min: Option<usize>,
+    /// How much destructive operations keep for `undo_last`.
+    pub(crate) undo: UndoPolicy,
+    /// Days forgotten rows stay in quarantine; 0 deletes them outright.
+    pub(crate) quarantine_days: u32,
 }
 
+/// Default for [`Store::with_resurrect_warn`].
+pub const DEFAULT_RESURRECT_WARN: u64 = 100;
+
 impl Drop for Store {
     fn drop(&mut self) {
         // Clean shutdown: flush WAL to main DB
+        if self.is_read_only() {
+            return;
+        }
         let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
     }
 }
 
+/// Brain that single-user stores and pre-multi-tenant rows belong to.
+pub const DEFAULT_BRAIN: &str = "default";
+
+/// Brain names are 1-64 ASCII letters, digits, `-`, or `_`, so they are
+/// safe in file names (each brain has its own conscious journal).
+pub fn validate_brain_name(brain: &str) -> Result<()> {
+    let valid = !brain.is_empty()
+        && brain.len() <= 64
+        && brain
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(StoreError::InvalidData(format!(
+            "invalid brain name {brain:?}: use 1-64 letters, digits, '-' or '_'"
+        )))
+    }
+}
+
 pub(crate) fn parse_uuid(s: &str) -> Result<Uuid> {
     Uuid::parse_str(s).map_err(|e| StoreError::InvalidData(format!("invalid UUID '{s}': {e}")))
 }
 
+/// An occurrence span from its nullable `span_start`/`span_end` columns.
+pub(crate) fn span_from(start: Option<usize>, end: Option<usize>) -> Option<(usize, usize)> {
+    start.zip(end).filter(|(start, end)| start <= end)
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,179 @@
+//! Connection settings applied every time a store opens its database.
+//!
+//! Two `am serve` processes, a CLI command, and a hook can all hold
+//! `brain.db` at once, so how a connection journals, syncs, and waits for
+//! locks is set explicitly rather than left to SQLite's compiled defaults.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::error::{Result, StoreError};
+use crate::schema::{self, SCHEMA_VERSION};
+
+use super::Store;
+
+/// Default for [`StoreOptions::busy_timeout_ms`].
+pub const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+
+/// SQLite's `synchronous` setting: how often it waits for data to reach
+/// the disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Synchronous {
+    /// Never sync; a power loss can corrupt the file.
+    Off,
+    /// Sync at checkpoints. Under WAL a power loss can roll back the
+    /// latest commits but never corrupts the file.
+    #[default]
+    Normal,
+    /// Sync on every commit.
+    Full,
+    /// `Full`, plus syncing the directory after a rollback journal is
+    /// deleted.
+    Extra,
+}
+
+impl Synchronous {
+    /// The value `PRAGMA synchronous` takes and reports.
+    fn level(self) -> i64 {
+        match self {
+            Self::Off => 0,
+            Self::Normal => 1,
+            Self::Full => 2,
+            Self::Extra => 3,
+        }
+    }
+}
+
+impl FromStr for Synchronous {
+    type Err = StoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "0" => Ok(Self::Off),
+            "normal" | "1" => Ok(Self::Normal),
+            "full" | "2" => Ok(Self::Full),
+            "extra" | "3" => Ok(Self::Extra),
+            _ => Err(StoreError::InvalidData(format!(
+                "invalid synchronous mode {s:?}: use off, normal, full, or extra"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Synchronous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Normal => "normal",
+            Self::Full => "full",
+            Self::Extra => "extra",
+        })
+    }
+}
+
+/// How [`Store::open_with`] configures its connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreOptions {
+    /// How long a statement waits on another connection's lock before
+    /// failing with `SQLITE_BUSY`.
+    pub busy_timeout_ms: u64,
+    pub synchronous: Synchronous,
+    /// Journal in write-ahead mode, so readers never block the writer.
+    /// Off uses a rollback journal.
+    pub wal: bool,
+    /// Open without write access, so the store never takes a write lock.
+    /// The schema is upgraded first if an older am wrote the file.
+    pub read_only: bool,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: DEFAULT_BUSY_TIMEOUT_MS,
+            synchronous: Synchronous::Normal,
+            wal: true,
+            read_only: false,
+        }
+    }
+}
+
+impl StoreOptions {
+    /// The same options without write access.
+    #[must_use]
+    pub fn read_only(self) -> Self {
+        Self {
+            read_only: true,
+            ..self
+        }
+    }
+
+    /// Set the per-connection pragmas on `conn`. Foreign keys are always
+    /// enforced.
+    fn apply(&self, conn: &Connection) -> Result<()> {
+        conn.busy_timeout(Duration::from_millis(self.busy_timeout_ms))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        if self.read_only {
+            return Ok(());
+        }
+        if !self.wal {
+            let mode: String =
+                conn.pragma_update_and_check(None, "journal_mode", "DELETE", |row| row.get(0))?;
+            if mode.eq_ignore_ascii_case("wal") {
+                tracing::warn!("journal_mode stayed WAL: another connection has the file open");
+            }
+        }
+        conn.pragma_update(None, "synchronous", self.synchronous.level())?;
+        Ok(())
+    }
+}
+
+impl Store {
+    /// Open the database at `path`, creating or upgrading it, and
+    /// configure the connection with `options`.
+    pub fn open_with(path: &Path, options: &StoreOptions) -> Result<Self> {
+        let conn = if options.read_only {
+            open_read_only(path)?
+        } else {
+            let conn = Connection::open(path)?;
+            schema::initialize(&conn)?;
+            conn
+        };
+        options.apply(&conn)?;
+        Ok(Self::from_conn(conn))
+    }
+
+    /// Open the database at `path` without write access, for inspection
+    /// that must never block a running server. Creates or upgrades the
+    /// file first when it is missing or older than this build.
+    pub fn open_readonly(path: &Path) -> Result<Self> {
+        Self::open_with(path, &StoreOptions::default().read_only())
+    }
+
+    /// Whether the connection was opened without write access.
+    pub fn is_read_only(&self) -> bool {
+        self.conn
+            .is_readonly(rusqlite::DatabaseName::Main)
+            .unwrap_or(false)
+    }
+}
+
+/// Open `path` read-only, initializing it through a writable connection
+/// first when it is missing or its schema is out of date.
+fn open_read_only(path: &Path) -> Result<Connection> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+        | OpenFlags::SQLITE_OPEN_URI
+        | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    if path.exists() {
+        let conn = Connection::open_with_flags(path, flags)?;
+        let version = schema::get_schema_version(&conn).ok().flatten();
+        if version.unwrap_or(0) >= SCHEMA_VERSION {
+            return Ok(conn);
+        }
+    }
+    schema::initialize(&Connection::open(path)?)?;
+    Ok(Connection::open_with_flags(path, flags)?)
+}
@@ -8,52 +8,78 @@ use am_core::{
 
 use crate::error::{Result, StoreError};
 
-use super::Store;
+use super::{Store, parse_uuid, text};
 
 impl Store {
     pub fn save_system(&self, system: &DAESystem) -> Result<()> {
-        // Guard: refuse to overwrite existing data with an empty system.
-        // This prevents data destruction when the server fails to load state
-        // and then saves its empty in-memory system over the real data.
-        if system.n() == 0 && system.episodes.is_empty() {
-            let existing: i64 =
-                self.conn
-                    .query_row("SELECT COUNT(*) FROM occurrences", [], |r| r.get(0))?;
-            if existing > 0 {
-                return Err(StoreError::InvalidData(format!(
-                    "refusing to overwrite {existing} existing occurrences with empty system \
-                     (possible failed load)"
-                )));
+        let _span = tracing::debug_span!(
+            "save_system",
+            episodes = system.episodes.len(),
+            occurrences = system.n(),
+        )
+        .entered();
+        self.retry_busy("save_system", || {
+            // Guard: refuse to overwrite existing data with an empty system.
+            // This prevents data destruction when the server fails to load state
+            // and then saves its empty in-memory system over the real data.
+            if system.n() == 0 && system.episodes.is_empty() {
+                let existing: i64 = self.conn.query_row(
+                    "SELECT COUNT(*) FROM occurrences o
+                     JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+                     JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                     WHERE e.archived = 0 AND e.brain_id = ?1",
+                    [&self.brain],
+                    |r| r.get(0),
+                )?;
+                if existing > 0 {
+                    return Err(StoreError::InvalidData(format!(
+                        "refusing to overwrite {existing} existing occurrences with empty system \
+                         (possible failed load)"
+                    )));
+                }
             }
-        }
 
-        let tx = self.conn.unchecked_transaction()?;
+            let resurrected = self.resurrected_count(system);
+            if resurrected > self.resurrect_warn {
+                tracing::warn!(
+                    "save writes back {resurrected} occurrences that GC evicted; \
+                     the in-memory system was not reconciled with apply_gc"
+                );
+            }
 
-        // Clear existing data
-        tx.execute_batch(
-            "DELETE FROM occurrences; DELETE FROM neighborhoods; DELETE FROM episodes;",
-        )?;
+            let tx = self.transaction()?;
 
-        self.set_metadata_on(&tx, "agent_name", &system.agent_name)?;
+            // Clear this brain's existing data; neighborhoods and occurrences
+            // cascade. Archived episodes are not part of a loaded system, so
+            // they survive the rewrite.
+            tx.execute(
+                "DELETE FROM episodes WHERE archived = 0 AND brain_id = ?1",
+                [&self.brain],
+            )?;
 
-        // Save subconscious episodes
-        for episode in &system.episodes {
-            self.save_episode_on(&tx, episode)?;
-        }
+            self.set_metadata_on(&tx, "agent_name", &system.agent_name)?;
 
-        // Save conscious episode
-        self.save_episode_on(&tx, &system.conscious_episode)?;
+            // Save subconscious episodes
+            for episode in &system.episodes {
+                self.save_episode_on(&tx, episode)?;
+            }
 
-        tx.commit()?;
-        // PASSIVE checkpoint after bulk write - flushes WAL without blocking readers
-        let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
-        Ok(())
+            // Save conscious episode
+            self.save_episode_on(&tx, &system.conscious_episode)?;
+
+            tx.commit()?;
+            // Whatever was written back is live again
+            self.gc_evicted.lock().expect("poisoned mutex").clear();
+            // PASSIVE checkpoint after bulk write - flushes WAL without blocking readers
+            let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
+            Ok(())
+        })
     }
 
-    fn set_metadata_on(&self, conn: &Connection, key: &str, value: &str) -> Result<()> {
+    pub(crate) fn set_metadata_on(&self, conn: &Connection, key: &str, value: &str) -> Result<()> {
         conn.execute(
-            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
-            params![key, value],
+            "INSERT OR REPLACE INTO metadata (brain_id, key, value) VALUES (?1, ?2, ?3)",
+            params![self.brain, key, value],
         )?;
         Ok(())
     }
@@ -62,10 +88,38 @@ impl Store {
     /// rewriting the entire system. Use after `DAESystem::add_episode` to
     /// avoid the full DELETE/rewrite cycle of `save_system`.
     pub fn save_episode(&self, episode: &Episode) -> Result<()> {
-        let tx = self.conn.unchecked_transaction()?;
-        self.save_episode_on(&tx, episode)?;
-        tx.commit()?;
-        Ok(())
+        self.retry_busy("save_episode", || {
+            let tx = self.transaction()?;
+            self.save_episode_on(&tx, episode)?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Return an archived episode to normal recall. Returns false if no
+    /// archived episode has that ID.
+    pub fn restore_episode(&self, episode_id: &str) -> Result<bool> {
+        self.retry_busy("restore_episode", || {
+            let uuid = parse_uuid(episode_id)?;
+            let changed = self.conn.execute(
+                "UPDATE episodes SET archived = 0 WHERE id = ?1 AND archived = 1 AND brain_id = ?2",
+                params![uuid.to_string(), self.brain],
+            )?;
+            Ok(changed > 0)
+        })
+    }
+
+    /// Rename a subconscious episode in place. Returns false if no
+    /// subconscious episode has that ID.
+    pub fn rename_episode(&self, episode_id: &str, name: &str) -> Result<bool> {
+        self.retry_busy("rename_episode", || {
+            let uuid = parse_uuid(episode_id)?;
+            let changed = self.conn.execute(
+                "UPDATE episodes SET name = ?2 WHERE id = ?1 AND is_conscious = 0 AND brain_id = ?3",
+                params![uuid.to_string(), name, self.brain],
+            )?;
+            Ok(changed > 0)
+        })
     }
 
     /// Persist a single neighborhood under an episode without rewriting the
@@ -74,49 +128,77 @@ impl Store {
     /// occurrences. Use after adding a neighborhood to the conscious episode
     /// via `add_to_conscious` or `extract_salient`.
     pub fn save_neighborhood(&self, episode: &Episode, neighborhood: &Neighborhood) -> Result<()> {
-        let tx = self.conn.unchecked_transaction()?;
-        // Ensure the parent episode row exists (no-op if already present)
-        tx.execute(
-            "INSERT OR IGNORE INTO episodes (id, name, is_conscious, timestamp) VALUES (?1, ?2, ?3, ?4)",
-            params![
-                episode.id.to_string(),
-                episode.name,
-                episode.is_conscious as i32,
-                episode.timestamp,
-            ],
-        )?;
-        self.save_neighborhood_on(&tx, neighborhood, episode.id)?;
-        tx.commit()?;
-        Ok(())
+        self.retry_busy("save_neighborhood", || {
+            let tx = self.transaction()?;
+            // Ensure the parent episode row exists (no-op if already present)
+            tx.execute(
+                "INSERT OR IGNORE INTO episodes (id, name, is_conscious, timestamp, actor, brain_id, original_timestamp, reused_neighborhoods) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    episode.id.to_string(),
+                    episode.name,
+                    episode.is_conscious as i32,
+                    episode.timestamp,
+                    episode.actor,
+                    self.brain,
+                    episode.original_timestamp,
+                    reused_neighborhoods_json(episode),
+                ],
+            )?;
+            self.save_neighborhood_on(&tx, neighborhood, episode.id, episode.is_conscious)?;
+            tx.commit()?;
+            Ok(())
+        })
     }
 
     pub(crate) fn save_episode_on(&self, conn: &Connection, episode: &Episode) -> Result<()> {
         conn.execute(
-            "INSERT INTO episodes (id, name, is_conscious, timestamp) VALUES (?1, ?2, ?3, ?4)",
+            "INSERT INTO episodes (id, name, is_conscious, timestamp, actor, brain_id, original_timestamp, reused_neighborhoods) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 episode.id.to_string(),
                 episode.name,
                 episode.is_conscious as i32,
                 episode.timestamp,
+                episode.actor,
+                self.brain,
+                episode.original_timestamp,
+                reused_neighborhoods_json(episode),
             ],
         )?;
 
         for neighborhood in &episode.neighborhoods {
-            self.save_neighborhood_on(conn, neighborhood, episode.id)?;
+            self.save_neighborhood_on(conn, neighborhood, episode.id, episode.is_conscious)?;
         }
 
         Ok(())
     }
 
-    fn save_neighborhood_on(
+    pub(crate) fn save_neighborhood_on(
         &self,
         conn: &Connection,
         neighborhood: &Neighborhood,
         episode_id: Uuid,
+        conscious: bool,
     ) -> Result<()> {
+        // Conscious text stays plain so listings can search it in SQL
+        let zip = if conscious {
+            None
+        } else {
+            text::compress(&neighborhood.source_text, self.compress_min)
+        };
+        let source_text = if zip.is_some() {
+            ""
+        } else {
+            neighborhood.source_text.as_str()
+        };
+        // Keep the neighborhood's seq as its rowid so insertion order (the
+        // ranking tiebreaker) survives the save/load round trip. An unset
+        // seq, or one another brain's row already holds, takes a fresh rowid.
         conn.execute(
-            "INSERT INTO neighborhoods (id, episode_id, seed_w, seed_x, seed_y, seed_z, source_text, neighborhood_type, epoch, superseded_by)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO neighborhoods (rowid, id, episode_id, seed_w, seed_x, seed_y, seed_z, source_text, neighborhood_type, epoch, superseded_by, actor, created_at, brain_id, llm_tokens, project, source_zip, feedback_score, group_id)
+             VALUES (
+                 (SELECT CASE WHEN ?14 = 0 OR EXISTS (SELECT 1 FROM neighborhoods WHERE rowid = ?14)
+                         THEN NULL ELSE ?14 END),
+                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?15, ?16, ?17, ?18, ?19)",
             params![
                 neighborhood.id.to_string(),
                 episode_id.to_string(),
@@ -124,10 +206,19 @@ impl Store {
                 neighborhood.seed.x,
                 neighborhood.seed.y,
                 neighborhood.seed.z,
-                neighborhood.source_text,
+                source_text,
                 neighborhood.neighborhood_type.as_str(),
                 neighborhood.epoch,
                 neighborhood.superseded_by.map(|id| id.to_string()),
+                neighborhood.actor,
+                neighborhood.created_at,
+                self.brain,
+                neighborhood.seq,
+                neighborhood.llm_tokens() as i64,
+                neighborhood.project,
+                zip,
+                neighborhood.feedback_score,
+                neighborhood.group_id.map(|id| id.to_string()),
             ],
         )?;
 
@@ -140,8 +231,8 @@ impl Store {
 
     fn save_occurrence_on(&self, conn: &Connection, occ: &Occurrence) -> Result<()> {
         conn.execute(
-            "INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, activation_count)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO occurrences (id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, activation_count, drift, spillover, brain_id, span_start, span_end)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 occ.id.to_string(),
                 occ.neighborhood_id.to_string(),
@@ -152,6 +243,11 @@ impl Store {
                 occ.position.z,
                 occ.phasor.theta,
                 occ.activation_count,
+                occ.drift,
+                occ.spillover,
+                self.brain,
+                occ.span.map(|(start, _)| start),
+                occ.span.map(|(_, end)| end),
             ],
         )?;
         Ok(())
@@ -159,26 +255,44 @@ impl Store {
 
     pub fn save_occurrence_positions(
         &self,
-        batch: &[(Uuid, Quaternion, DaemonPhasor)],
+        batch: &[(Uuid, Quaternion, DaemonPhasor, f64)],
     ) -> Result<()> {
-        let tx = self.conn.unchecked_transaction()?;
-        {
-            let mut stmt = tx.prepare(
-                "UPDATE occurrences SET pos_w = ?1, pos_x = ?2, pos_y = ?3, pos_z = ?4, phasor_theta = ?5 WHERE id = ?6",
-            )?;
-            for (id, pos, phasor) in batch {
-                stmt.execute(params![
-                    pos.w,
-                    pos.x,
-                    pos.y,
-                    pos.z,
-                    phasor.theta,
-                    id.to_string()
-                ])?;
+        self.retry_busy("save_occurrence_positions", || {
+            let tx = self.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE occurrences SET pos_w = ?1, pos_x = ?2, pos_y = ?3, pos_z = ?4, phasor_theta = ?5, drift = ?6
+                     WHERE id = ?7 AND brain_id = ?8",
+                )?;
+                for (id, pos, phasor, drift) in batch {
+                    stmt.execute(params![
+                        pos.w,
+                        pos.x,
+                        pos.y,
+                        pos.z,
+                        phasor.theta,
+                        drift,
+                        id.to_string(),
+                        self.brain
+                    ])?;
+                }
             }
-        }
-        tx.commit()?;
-        let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
-        Ok(())
+            tx.commit()?;
+            let _ = self.conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);");
+            Ok(())
+        })
+    }
+}
+
+/// `episodes.reused_neighborhoods`: a JSON array of ids, NULL when empty.
+fn reused_neighborhoods_json(episode: &Episode) -> Option<String> {
+    if episode.reused_neighborhoods.is_empty() {
+        return None;
     }
+    let ids: Vec<String> = episode
+        .reused_neighborhoods
+        .iter()
+        .map(Uuid::to_string)
+        .collect();
+    serde_json::to_string(&ids).ok()
 }
@@ -0,0 +1,188 @@
+//! Quarantine for forgotten memories.
+//!
+//! `forget_term`, `forget_episode`, `forget_conscious`, and
+//! `forget_neighborhoods` copy every row they delete into a `quarantine`
+//! entry, in the same transaction and the same form as their undo entry.
+//! The rows are gone from the memory tables, so loads and recall never see
+//! them, but unlike the undo stack the entry is not bounded by count or
+//! size: it stays until [`Store::purge_quarantine`] (run by `am gc`) drops
+//! it after [`Store::with_quarantine_days`] days, or
+//! [`Store::restore_forgotten`] puts it back. An `undo_last` of the same
+//! forget drops its quarantine entry, and a restore drops the undo entry,
+//! so the rows cannot come back twice.
+
+use am_core::store_trait::{AuditEvent, AuditKind};
+use am_core::time::{now_iso8601, now_unix_secs, unix_to_iso8601};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::error::Result;
+
+use super::Store;
+use super::undo::UndoRows;
+
+/// Default for [`Store::with_quarantine_days`].
+pub const DEFAULT_QUARANTINE_DAYS: u32 = 30;
+
+/// One forget held in quarantine, as `am restore-forgotten --list` shows
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantineEntry {
+    pub id: i64,
+    /// ISO-8601 UTC time of the forget.
+    pub forgotten_at: String,
+    /// The audit log detail of the forget, e.g. `term rust`.
+    pub detail: String,
+    /// Rows restoring it would put back.
+    pub episodes: u64,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+}
+
+/// What [`Store::restore_forgotten`] put back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// The entries restored, newest first.
+    pub entries: Vec<QuarantineEntry>,
+    pub episodes: u64,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+    /// Rows left out because their ID is in use again or their parent is
+    /// gone.
+    pub skipped: u64,
+}
+
+impl Store {
+    /// Keep forgotten rows in quarantine for `days` days. 0 deletes them
+    /// outright. Defaults to [`DEFAULT_QUARANTINE_DAYS`].
+    #[must_use]
+    pub fn with_quarantine_days(mut self, days: u32) -> Self {
+        self.quarantine_days = days;
+        self
+    }
+
+    /// Days forgotten rows stay in quarantine; 0 when forgets delete
+    /// outright.
+    pub fn quarantine_days(&self) -> u32 {
+        self.quarantine_days
+    }
+
+    /// Audit a forget, push its undo entry, and quarantine `rows`, inside
+    /// the caller's transaction.
+    pub(crate) fn audit_forget(
+        &self,
+        conn: &Connection,
+        event: &AuditEvent,
+        rows: &UndoRows,
+    ) -> Result<()> {
+        let undo_id = self.audit_with_undo(conn, event, rows)?;
+        if self.quarantine_days == 0 || rows.is_empty() {
+            return Ok(());
+        }
+        let (episodes, neighborhoods, occurrences) = rows.counts();
+        conn.execute(
+            "INSERT INTO quarantine (brain_id, forgotten_at, detail, episodes, neighborhoods,
+                                     occurrences, undo_id, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                self.brain,
+                now_iso8601(),
+                event.detail,
+                episodes,
+                neighborhoods,
+                occurrences,
+                undo_id,
+                rows.to_payload()?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Forgets held in quarantine for this brain, newest first.
+    pub fn quarantine_entries(&self) -> Result<Vec<QuarantineEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, forgotten_at, detail, episodes, neighborhoods, occurrences
+             FROM quarantine WHERE brain_id = ?1 ORDER BY id DESC",
+        )?;
+        let entries = stmt
+            .query_map([&self.brain], |row| {
+                Ok(QuarantineEntry {
+                    id: row.get(0)?,
+                    forgotten_at: row.get(1)?,
+                    detail: row.get(2)?,
+                    episodes: row.get(3)?,
+                    neighborhoods: row.get(4)?,
+                    occurrences: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Put back the quarantine entries `ids`, newest first so a later
+    /// forget's rows land before an earlier one's children, and drop them
+    /// with their undo entries. Unknown IDs are skipped. Audited as one
+    /// `undo`.
+    pub fn restore_forgotten(&self, ids: &[i64]) -> Result<RestoreReport> {
+        self.retry_busy("restore_forgotten", || {
+            let tx = self.immediate_transaction()?;
+            let mut report = RestoreReport::default();
+            let wanted = self
+                .quarantine_entries()?
+                .into_iter()
+                .filter(|entry| ids.contains(&entry.id));
+
+            for entry in wanted {
+                let Some((payload, undo_id)) = tx
+                    .query_row(
+                        "SELECT payload, undo_id FROM quarantine WHERE id = ?1",
+                        [entry.id],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?)),
+                    )
+                    .optional()?
+                else {
+                    continue;
+                };
+                let rows =
+                    UndoRows::from_payload(&payload, &format!("quarantine entry {}", entry.id))?;
+                let (episodes, neighborhoods, occurrences) = rows.restore(&tx)?;
+                report.skipped += entry.episodes + entry.neighborhoods + entry.occurrences
+                    - episodes
+                    - neighborhoods
+                    - occurrences;
+                report.episodes += episodes;
+                report.neighborhoods += neighborhoods;
+                report.occurrences += occurrences;
+
+                tx.execute("DELETE FROM quarantine WHERE id = ?1", [entry.id])?;
+                if let Some(undo_id) = undo_id {
+                    tx.execute("DELETE FROM undo_stack WHERE id = ?1", [undo_id])?;
+                }
+                report.entries.push(entry);
+            }
+
+            if report.entries.is_empty() {
+                return Ok(report);
+            }
+            let restored: Vec<String> = report.entries.iter().map(|e| e.id.to_string()).collect();
+            let event = AuditEvent::new(AuditKind::Undo, report.occurrences)
+                .with_detail(format!("quarantine {}", restored.join(", ")));
+            self.audit_on(&tx, &event)?;
+            tx.commit()?;
+            Ok(report)
+        })
+    }
+
+    /// Delete quarantine entries older than the retention window for good.
+    /// With quarantine off, every entry goes. Returns how many went.
+    pub fn purge_quarantine(&self) -> Result<u64> {
+        let window = u64::from(self.quarantine_days) * 86_400;
+        let cutoff = unix_to_iso8601(now_unix_secs().saturating_sub(window));
+        self.retry_busy("purge_quarantine", || {
+            let deleted = self.conn.execute(
+                "DELETE FROM quarantine WHERE brain_id = ?1 AND forgotten_at <= ?2",
+                params![self.brain, cutoff],
+            )?;
+            Ok(deleted as u64)
+        })
+    }
+}
@@ -1,10 +1,16 @@
+use std::collections::HashMap;
+
 use uuid::Uuid;
 
-use am_core::{occurrence::Occurrence, phasor::DaemonPhasor, quaternion::Quaternion};
+use am_core::{neighborhood::NeighborhoodType, occurrence::Occurrence};
 
 use crate::error::Result;
 
-use super::{EpisodeInfo, NeighborhoodDetail, NeighborhoodInfo, Store, parse_uuid};
+use super::{
+    ConsciousSort, EpisodeInfo, EpisodeNeighborhood, EpisodeSort, NeighborhoodDetail,
+    NeighborhoodInfo, NeighborhoodSort, OccurrencePosition, Page, PageRequest, Store, VocabStats,
+    WordSource, WordStat, WordWeight, parse_uuid, span_from, text, validate::RowChecker,
+};
 
 impl Store {
     pub fn get_occurrences_by_word(&self, word: &str) -> Result<Vec<Occurrence>> {
@@ -13,52 +19,79 @@ impl Store {
 
     fn load_occurrences_by_word(&self, word: &str) -> Result<Vec<Occurrence>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, activation_count
-             FROM occurrences WHERE word = ?1",
+            "SELECT id, neighborhood_id, word, pos_w, pos_x, pos_y, pos_z, phasor_theta, activation_count,
+                    drift, spillover, span_start, span_end
+             FROM occurrences WHERE word = ?1 AND brain_id = ?2",
         )?;
 
-        stmt.query_map([word], |row| {
-            let id_str: String = row.get(0)?;
-            let nbhd_id_str: String = row.get(1)?;
-            let word: String = row.get(2)?;
-            let w: f64 = row.get(3)?;
-            let x: f64 = row.get(4)?;
-            let y: f64 = row.get(5)?;
-            let z: f64 = row.get(6)?;
-            let theta: f64 = row.get(7)?;
-            let activation_count: u32 = row.get(8)?;
-            Ok((
-                id_str,
-                nbhd_id_str,
-                word,
-                w,
-                x,
-                y,
-                z,
-                theta,
-                activation_count,
-            ))
-        })?
-        .map(|r| {
-            let (id_str, nbhd_id_str, word, w, x, y, z, theta, activation_count) = r?;
-            Ok(Occurrence {
-                id: parse_uuid(&id_str)?,
-                neighborhood_id: parse_uuid(&nbhd_id_str)?,
-                word,
-                position: Quaternion::new(w, x, y, z),
-                phasor: DaemonPhasor::new(theta),
-                activation_count,
+        let mut checker = RowChecker::new(self.strict_load);
+        let occurrences = stmt
+            .query_map(rusqlite::params![word, self.brain], |row| {
+                let id_str: String = row.get(0)?;
+                let nbhd_id_str: String = row.get(1)?;
+                let word: String = row.get(2)?;
+                let w: f64 = row.get(3)?;
+                let x: f64 = row.get(4)?;
+                let y: f64 = row.get(5)?;
+                let z: f64 = row.get(6)?;
+                let theta: f64 = row.get(7)?;
+                let activation_count: u32 = row.get(8)?;
+                let drift: f64 = row.get(9)?;
+                let spillover: f64 = row.get(10)?;
+                let span = span_from(row.get(11)?, row.get(12)?);
+                Ok((
+                    id_str,
+                    nbhd_id_str,
+                    word,
+                    w,
+                    x,
+                    y,
+                    z,
+                    theta,
+                    activation_count,
+                    drift,
+                    spillover,
+                    span,
+                ))
+            })?
+            .map(|r| {
+                let (
+                    id_str,
+                    nbhd_id_str,
+                    word,
+                    w,
+                    x,
+                    y,
+                    z,
+                    theta,
+                    activation_count,
+                    drift,
+                    spillover,
+                    span,
+                ) = r?;
+                Ok(Occurrence {
+                    position: checker.quaternion("occurrence", &id_str, (w, x, y, z))?,
+                    phasor: checker.phasor(&id_str, theta)?,
+                    id: parse_uuid(&id_str)?,
+                    neighborhood_id: parse_uuid(&nbhd_id_str)?,
+                    word,
+                    activation_count,
+                    drift,
+                    spillover,
+                    span,
+                })
             })
-        })
-        .collect()
+            .collect::<Result<Vec<_>>>();
+        self.record_repairs("load_occurrences_by_word", checker.repaired);
+        occurrences
     }
 
     pub fn get_neighborhood_ids_by_word(&self, word: &str) -> Result<Vec<Uuid>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT DISTINCT neighborhood_id FROM occurrences WHERE word = ?1")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT neighborhood_id FROM occurrences WHERE word = ?1 AND brain_id = ?2",
+        )?;
 
-        stmt.query_map([word], |row| {
+        stmt.query_map(rusqlite::params![word, self.brain], |row| {
             let id_str: String = row.get(0)?;
             Ok(id_str)
         })?
@@ -71,81 +104,294 @@ impl Store {
 
     // --- Inspection queries (SQL-level, no full system load) ---
 
-    /// List all episodes with summary stats.
+    /// List all episodes with summary stats, archived ones included: the
+    /// conscious episode, then newest first by timestamp.
     pub fn list_episodes(&self) -> Result<Vec<EpisodeInfo>> {
         let mut stmt = self.conn.prepare(
-            "SELECT e.id, e.name, e.is_conscious, e.timestamp,
+            "SELECT e.id, e.name, e.is_conscious, e.archived, e.timestamp,
                     COUNT(DISTINCT n.id) as nbhd_count,
                     COUNT(o.id) as occ_count,
-                    COALESCE(SUM(o.activation_count), 0) as total_activation
+                    COALESCE(SUM(o.activation_count), 0) as total_activation,
+                    e.actor,
+                    (SELECT COALESCE(SUM(llm_tokens), 0) FROM neighborhoods
+                     WHERE episode_id = e.id AND brain_id = e.brain_id) as llm_tokens
              FROM episodes e
-             LEFT JOIN neighborhoods n ON n.episode_id = e.id
-             LEFT JOIN occurrences o ON o.neighborhood_id = n.id
+             LEFT JOIN neighborhoods n ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             LEFT JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE e.brain_id = ?1
              GROUP BY e.id
-             ORDER BY e.is_conscious DESC, e.rowid",
+             ORDER BY e.is_conscious DESC, e.timestamp DESC, e.rowid DESC",
         )?;
 
         let rows = stmt
-            .query_map([], |row| {
-                Ok(EpisodeInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    is_conscious: row.get::<_, i32>(2)? != 0,
-                    timestamp: row.get(3)?,
-                    neighborhood_count: row.get(4)?,
-                    occurrence_count: row.get(5)?,
-                    total_activation: row.get(6)?,
-                })
-            })?
+            .query_map([&self.brain], episode_info_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(rows)
     }
 
-    /// List conscious neighborhoods with their source text.
-    pub fn list_conscious_neighborhoods(&self) -> Result<Vec<NeighborhoodInfo>> {
+    /// Episodes `key` names, archived ones included: the conscious
+    /// episode for `conscious`, else the episode with exactly that id, else
+    /// every episode whose id starts with `key` (so the 8-character ids in
+    /// listings work), else every episode whose name starts with it,
+    /// ignoring case. More than one result means `key` is ambiguous.
+    pub fn find_episodes(&self, key: &str) -> Result<Vec<EpisodeInfo>> {
+        let key = key.trim().to_lowercase();
+        if key.is_empty() {
+            return Ok(Vec::new());
+        }
+        let episodes = self.list_episodes()?;
+        if key == "conscious" {
+            return Ok(episodes.into_iter().filter(|e| e.is_conscious).collect());
+        }
+        let (by_id, rest): (Vec<_>, Vec<_>) =
+            episodes.into_iter().partition(|e| e.id.starts_with(&key));
+        if !by_id.is_empty() {
+            let exact = by_id.iter().position(|e| e.id == key);
+            return Ok(match exact {
+                Some(i) => by_id.into_iter().skip(i).take(1).collect(),
+                None => by_id,
+            });
+        }
+        Ok(rest
+            .into_iter()
+            .filter(|e| e.name.to_lowercase().starts_with(&key))
+            .collect())
+    }
+
+    /// Every neighborhood of episode `episode_id` in insertion order, each
+    /// with its occurrences' words and activation counts.
+    pub fn episode_neighborhoods(&self, episode_id: &str) -> Result<Vec<EpisodeNeighborhood>> {
         let mut stmt = self.conn.prepare(
-            "SELECT n.id, n.source_text, COUNT(o.id) as occ_count,
-                    COALESCE(SUM(o.activation_count), 0) as total_activation
+            "SELECT n.id, n.source_text, n.source_zip,
+                    COALESCE(n.neighborhood_type, 'memory'), n.actor, n.project,
+                    n.created_at, n.llm_tokens, n.feedback_score
              FROM neighborhoods n
-             JOIN episodes e ON n.episode_id = e.id
-             LEFT JOIN occurrences o ON o.neighborhood_id = n.id
-             WHERE e.is_conscious = 1
-             GROUP BY n.id
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             WHERE n.episode_id = ?1 AND e.brain_id = ?2
              ORDER BY n.rowid",
         )?;
-
         let rows = stmt
-            .query_map([], |row| {
-                Ok(NeighborhoodInfo {
+            .query_map(rusqlite::params![episode_id, self.brain], |row| {
+                let neighborhood = EpisodeNeighborhood {
                     id: row.get(0)?,
                     source_text: row.get(1)?,
-                    occurrence_count: row.get(2)?,
-                    total_activation: row.get(3)?,
-                })
+                    neighborhood_type: NeighborhoodType::from_str_lossy(&row.get::<_, String>(3)?),
+                    actor: row.get(4)?,
+                    project: row.get(5)?,
+                    created_at: row.get(6)?,
+                    llm_tokens: row.get(7)?,
+                    feedback_score: row.get(8)?,
+                    words: Vec::new(),
+                };
+                Ok((neighborhood, row.get::<_, Option<Vec<u8>>>(2)?))
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut neighborhoods = rows
+            .into_iter()
+            .map(|(mut n, zip)| {
+                n.source_text = text::decode(&n.id, n.source_text, zip)?;
+                Ok(n)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let index: HashMap<String, usize> = neighborhoods
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.clone(), i))
+            .collect();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT o.neighborhood_id, o.word, o.activation_count
+             FROM occurrences o
+             JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE n.episode_id = ?1 AND n.brain_id = ?2
+             ORDER BY o.rowid",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![episode_id, self.brain])?;
+        while let Some(row) = rows.next()? {
+            let nid: String = row.get(0)?;
+            if let Some(&i) = index.get(&nid) {
+                neighborhoods[i].words.push((row.get(1)?, row.get(2)?));
+            }
+        }
+        Ok(neighborhoods)
+    }
+
+    /// One page of live (unarchived) subconscious episodes in `sort`
+    /// order, with the total live subconscious episode count.
+    pub fn list_subconscious_episodes_page(
+        &self,
+        page: PageRequest,
+        sort: EpisodeSort,
+    ) -> Result<Page<EpisodeInfo>> {
+        self.subconscious_episodes_page(page, sort, false)
+    }
+
+    /// One page of archived episodes in `sort` order, with the total
+    /// archived episode count.
+    pub fn list_archived_episodes_page(
+        &self,
+        page: PageRequest,
+        sort: EpisodeSort,
+    ) -> Result<Page<EpisodeInfo>> {
+        self.subconscious_episodes_page(page, sort, true)
+    }
+
+    fn subconscious_episodes_page(
+        &self,
+        page: PageRequest,
+        sort: EpisodeSort,
+        archived: bool,
+    ) -> Result<Page<EpisodeInfo>> {
+        let total: u64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM episodes WHERE is_conscious = 0 AND archived = ?1 AND brain_id = ?2",
+            rusqlite::params![archived, self.brain],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT e.id, e.name, e.is_conscious, e.archived, e.timestamp,
+                    COUNT(DISTINCT n.id) as nbhd_count,
+                    COUNT(o.id) as occ_count,
+                    COALESCE(SUM(o.activation_count), 0) as total_activation,
+                    e.actor,
+                    (SELECT COALESCE(SUM(llm_tokens), 0) FROM neighborhoods
+                     WHERE episode_id = e.id AND brain_id = e.brain_id) as llm_tokens
+             FROM episodes e
+             LEFT JOIN neighborhoods n ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             LEFT JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE e.is_conscious = 0 AND e.archived = ?3 AND e.brain_id = ?4
+             GROUP BY e.id
+             ORDER BY {}
+             LIMIT ?1 OFFSET ?2",
+            sort.order_by()
+        ))?;
+
+        let (limit, offset) = page.sql_params();
+        let items = stmt
+            .query_map(
+                rusqlite::params![limit, offset, archived, self.brain],
+                episode_info_from_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(rows)
+        Ok(Page { items, total })
+    }
+
+    /// List conscious neighborhoods with their source text.
+    pub fn list_conscious_neighborhoods(&self) -> Result<Vec<NeighborhoodInfo>> {
+        Ok(self
+            .list_conscious_neighborhoods_page(PageRequest::ALL, None, ConsciousSort::Stored)?
+            .items)
     }
 
-    /// List all neighborhoods (across all episodes).
+    /// One page of conscious neighborhoods in `sort` order, with the total
+    /// that match. `search` keeps neighborhoods whose source text contains
+    /// it (ASCII case-insensitive) or that hold it as a word, which also
+    /// finds rows stored without source text.
+    pub fn list_conscious_neighborhoods_page(
+        &self,
+        page: PageRequest,
+        search: Option<&str>,
+        sort: ConsciousSort,
+    ) -> Result<Page<NeighborhoodInfo>> {
+        let search = search.map(str::trim).filter(|s| !s.is_empty());
+        let pattern = search.map(|s| format!("%{}%", escape_like(s)));
+        let word = search.map(str::to_lowercase);
+        let filter = "(?2 IS NULL
+                      OR n.source_text LIKE ?2 ESCAPE '\\'
+                      OR EXISTS (SELECT 1 FROM occurrences w
+                                 WHERE w.neighborhood_id = n.id AND w.brain_id = n.brain_id AND w.word = ?3))";
+
+        let total: u64 = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM neighborhoods n
+                 JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                 WHERE e.is_conscious = 1 AND e.brain_id = ?1 AND {filter}"
+            ),
+            rusqlite::params![self.brain, pattern, word],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT n.id, n.source_text, n.neighborhood_type, COUNT(o.id) as occ_count,
+                    COALESCE(SUM(o.activation_count), 0) as total_activation,
+                    n.actor, n.created_at, n.llm_tokens, n.project, n.group_id
+             FROM neighborhoods n
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             LEFT JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE e.is_conscious = 1 AND e.brain_id = ?1 AND {filter}
+             GROUP BY n.id
+             ORDER BY {}
+             LIMIT ?4 OFFSET ?5",
+            sort.order_by()
+        ))?;
+
+        let (limit, offset) = page.sql_params();
+        let items = stmt
+            .query_map(
+                rusqlite::params![self.brain, pattern, word, limit, offset],
+                |row| {
+                    Ok(NeighborhoodInfo {
+                        id: row.get(0)?,
+                        source_text: row.get(1)?,
+                        neighborhood_type: NeighborhoodType::from_str_lossy(
+                            &row.get::<_, String>(2)?,
+                        ),
+                        occurrence_count: row.get(3)?,
+                        total_activation: row.get(4)?,
+                        actor: row.get(5)?,
+                        project: row.get(8)?,
+                        created_at: row.get(6)?,
+                        llm_tokens: row.get(7)?,
+                        group_id: row.get(9)?,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Page { items, total })
+    }
+
+    /// List all neighborhoods (across all episodes), by total activation.
     pub fn list_neighborhoods(&self) -> Result<Vec<NeighborhoodDetail>> {
-        let mut stmt = self.conn.prepare(
+        Ok(self
+            .list_neighborhoods_page(PageRequest::ALL, NeighborhoodSort::Activation)?
+            .items)
+    }
+
+    /// One page of neighborhoods in `sort` order (ties broken by insertion
+    /// order, so pages are stable), with the total count. Sorting and
+    /// paging run in SQL, so only the page's rows are read back.
+    pub fn list_neighborhoods_page(
+        &self,
+        page: PageRequest,
+        sort: NeighborhoodSort,
+    ) -> Result<Page<NeighborhoodDetail>> {
+        let total = self.neighborhood_count()?;
+
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT n.id, n.source_text, e.name, e.is_conscious,
                     COUNT(o.id) as occ_count,
                     COALESCE(SUM(o.activation_count), 0) as total_activation,
-                    COALESCE(MAX(o.activation_count), 0) as max_activation
+                    COALESCE(MAX(o.activation_count), 0) as max_activation,
+                    COALESCE(n.actor, e.actor),
+                    COALESCE(AVG(o.drift), 0.0), COALESCE(MAX(o.drift), 0.0),
+                    n.llm_tokens, n.source_zip, n.feedback_score, n.created_at
              FROM neighborhoods n
-             JOIN episodes e ON n.episode_id = e.id
-             LEFT JOIN occurrences o ON o.neighborhood_id = n.id
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             LEFT JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE e.brain_id = ?3
              GROUP BY n.id
-             ORDER BY total_activation DESC",
-        )?;
+             ORDER BY {}
+             LIMIT ?1 OFFSET ?2",
+            sort.order_by()
+        ))?;
 
+        let (limit, offset) = page.sql_params();
         let rows = stmt
-            .query_map([], |row| {
-                Ok(NeighborhoodDetail {
+            .query_map(rusqlite::params![limit, offset, self.brain], |row| {
+                let detail = NeighborhoodDetail {
                     id: row.get(0)?,
                     source_text: row.get(1)?,
                     episode_name: row.get(2)?,
@@ -153,11 +399,25 @@ impl Store {
                     occurrence_count: row.get(4)?,
                     total_activation: row.get(5)?,
                     max_activation: row.get(6)?,
-                })
+                    actor: row.get(7)?,
+                    mean_drift: row.get(8)?,
+                    max_drift: row.get(9)?,
+                    llm_tokens: row.get(10)?,
+                    feedback_score: row.get(12)?,
+                    created_at: row.get(13)?,
+                };
+                Ok((detail, row.get::<_, Option<Vec<u8>>>(11)?))
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
+        let items = rows
+            .into_iter()
+            .map(|(mut detail, zip)| {
+                detail.source_text = text::decode(&detail.id, detail.source_text, zip)?;
+                Ok(detail)
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        Ok(rows)
+        Ok(Page { items, total })
     }
 
     /// Get top words by activation count.
@@ -165,13 +425,14 @@ impl Store {
         let mut stmt = self.conn.prepare(
             "SELECT word, SUM(activation_count) as total_act, COUNT(*) as occ_count
              FROM occurrences
+             WHERE brain_id = ?2
              GROUP BY word
              ORDER BY total_act DESC
              LIMIT ?1",
         )?;
 
         let rows = stmt
-            .query_map([limit as i64], |row| {
+            .query_map(rusqlite::params![limit as i64, self.brain], |row| {
                 Ok((
                     row.get::<_, String>(0)?,
                     row.get::<_, u32>(1)?,
@@ -183,26 +444,194 @@ impl Store {
         Ok(rows)
     }
 
-    /// Count unique words in the database.
+    /// Neighborhood frequency, occurrence count, and IDF weight per word.
+    ///
+    /// Counts come from the occurrences table and the weight uses the
+    /// metadata word weight policy and the word's bias, so results match
+    /// `DAESystem::get_word_weight` without loading the system. Words are
+    /// lowercased; results keep the input order.
+    pub fn word_weights(&self, words: &[String]) -> Result<Vec<WordWeight>> {
+        let policy = self.word_weight_policy()?;
+        let biases: HashMap<String, f64> = self.word_biases()?.into_iter().collect();
+        let mut stmt = self.conn.prepare(
+            "SELECT COUNT(DISTINCT neighborhood_id), COUNT(*)
+             FROM occurrences WHERE word = ?1 AND brain_id = ?2",
+        )?;
+
+        words
+            .iter()
+            .map(|word| {
+                let word = word.to_lowercase();
+                let (neighborhood_count, occurrence_count): (u64, u64) = stmt
+                    .query_row(rusqlite::params![word, self.brain], |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    })?;
+                let bias = biases.get(&word).copied().unwrap_or(1.0);
+                Ok(WordWeight {
+                    weight: policy.weight_for(neighborhood_count as usize) * bias,
+                    bias,
+                    word,
+                    neighborhood_count,
+                    occurrence_count,
+                })
+            })
+            .collect()
+    }
+
+    /// The `limit` words found in the most live neighborhoods, each with
+    /// the episodes those neighborhoods come from. Archived episodes are
+    /// left out, as they are from the IDF weights recall uses.
+    pub fn word_stats(&self, limit: usize) -> Result<VocabStats> {
+        let total_neighborhoods: u64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM neighborhoods n
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             WHERE e.archived = 0 AND e.brain_id = ?1",
+            [&self.brain],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "WITH live AS (
+                 SELECT o.word, o.neighborhood_id, e.id AS episode_id, e.name
+                 FROM occurrences o
+                 JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+                 JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+                 WHERE e.archived = 0 AND e.brain_id = ?2
+             ),
+             top AS (
+                 SELECT word, COUNT(DISTINCT neighborhood_id) AS nbhds
+                 FROM live
+                 GROUP BY word
+                 ORDER BY nbhds DESC, word
+                 LIMIT ?1
+             )
+             SELECT t.word, t.nbhds, l.episode_id, l.name,
+                    COUNT(DISTINCT l.neighborhood_id) AS ep_nbhds
+             FROM top t
+             JOIN live l ON l.word = t.word
+             GROUP BY t.word, l.episode_id
+             ORDER BY t.nbhds DESC, t.word, ep_nbhds DESC, l.episode_id",
+        )?;
+
+        let (limit, _) = PageRequest::new(limit, 0).sql_params();
+        let mut words: Vec<WordStat> = Vec::new();
+        let mut rows = stmt.query(rusqlite::params![limit, self.brain])?;
+        while let Some(row) = rows.next()? {
+            let word: String = row.get(0)?;
+            let source = WordSource {
+                episode_id: row.get(2)?,
+                episode_name: row.get(3)?,
+                neighborhood_count: row.get(4)?,
+            };
+            match words.last_mut() {
+                Some(stat) if stat.word == word => stat.sources.push(source),
+                _ => words.push(WordStat {
+                    word,
+                    neighborhood_count: row.get(1)?,
+                    sources: vec![source],
+                }),
+            }
+        }
+
+        Ok(VocabStats {
+            total_neighborhoods,
+            words,
+        })
+    }
+
+    /// Call `f` with where every occurrence of this brain sits, ordered by
+    /// occurrence ID so walks taken at different times line up. Rows are
+    /// read one at a time, never collected. Returns how many `f` saw; an
+    /// error from `f` stops the walk.
+    pub fn for_each_position(
+        &self,
+        mut f: impl FnMut(&OccurrencePosition) -> Result<()>,
+    ) -> Result<u64> {
+        let mut stmt = self.conn.prepare(
+            "SELECT o.id, o.word, e.name, o.activation_count, o.drift,
+                    o.pos_w, o.pos_x, o.pos_y, o.pos_z
+             FROM occurrences o
+             JOIN neighborhoods n ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             JOIN episodes e ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             WHERE o.brain_id = ?1
+             ORDER BY o.id",
+        )?;
+        let mut checker = RowChecker::new(self.strict_load);
+        let mut rows = stmt.query([&self.brain])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            let position = checker.quaternion(
+                "occurrence",
+                &id,
+                (row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?),
+            )?;
+            f(&OccurrencePosition {
+                id,
+                word: row.get(1)?,
+                episode: row.get(2)?,
+                activation_count: row.get(3)?,
+                drift: row.get(4)?,
+                position,
+            })?;
+            count += 1;
+        }
+        self.record_repairs("for_each_position", checker.repaired);
+        Ok(count)
+    }
+
+    /// Count unique words in this brain.
     pub fn unique_word_count(&self) -> Result<u64> {
-        Ok(self
-            .conn
-            .query_row("SELECT COUNT(DISTINCT word) FROM occurrences", [], |row| {
-                row.get(0)
-            })?)
+        Ok(self.conn.query_row(
+            "SELECT COUNT(DISTINCT word) FROM occurrences WHERE brain_id = ?1",
+            [&self.brain],
+            |row| row.get(0),
+        )?)
     }
 
-    /// Total occurrence count in the database.
+    /// Total occurrence count in this brain.
     pub fn occurrence_count(&self) -> Result<u64> {
-        Ok(self
-            .conn
-            .query_row("SELECT COUNT(*) FROM occurrences", [], |row| row.get(0))?)
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM occurrences WHERE brain_id = ?1",
+            [&self.brain],
+            |row| row.get(0),
+        )?)
     }
 
-    /// Total neighborhood count in the database.
+    /// Total neighborhood count in this brain.
     pub fn neighborhood_count(&self) -> Result<u64> {
-        Ok(self
-            .conn
-            .query_row("SELECT COUNT(*) FROM neighborhoods", [], |row| row.get(0))?)
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM neighborhoods WHERE brain_id = ?1",
+            [&self.brain],
+            |row| row.get(0),
+        )?)
+    }
+}
+
+fn episode_info_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<EpisodeInfo> {
+    Ok(EpisodeInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        is_conscious: row.get::<_, i32>(2)? != 0,
+        archived: row.get::<_, i32>(3)? != 0,
+        timestamp: row.get(4)?,
+        neighborhood_count: row.get(5)?,
+        occurrence_count: row.get(6)?,
+        total_activation: row.get(7)?,
+        actor: row.get(8)?,
+        llm_tokens: row.get(9)?,
+    })
+}
+
+/// Escape `%`, `_` and the escape character itself for a `LIKE ... ESCAPE '\'`
+/// pattern, so a search term matches literally.
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
     }
+    escaped
 }
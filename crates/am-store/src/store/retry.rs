@@ -0,0 +1,126 @@
+//! Retrying writes that another process keeps locked.
+//!
+//! `busy_timeout` makes a statement wait for a lock, but a VACUUM or a long
+//! save in another `am` process can hold it longer, and a transaction that
+//! read before another connection wrote fails with `SQLITE_BUSY` without
+//! waiting at all. Write entry points run through [`Store::retry_busy`],
+//! which reruns the whole operation after an exponential backoff. Every
+//! wrapped operation is a single transaction (or a single statement), so a
+//! failed attempt leaves nothing behind.
+
+use std::time::{Duration, Instant};
+
+use am_core::store_trait::StoreErrorKind;
+use rand::Rng;
+
+use crate::error::Result;
+
+use super::Store;
+
+/// Default for [`RetryPolicy::max_attempts`].
+pub const DEFAULT_RETRY_ATTEMPTS: u32 = 5;
+
+/// Default for [`RetryPolicy::max_wait`].
+pub const DEFAULT_RETRY_MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Delay before the first retry; each later one doubles it.
+const BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// How store writes retry after `SQLITE_BUSY` outlasts the busy timeout.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included. 1 never retries.
+    pub max_attempts: u32,
+    /// No retry starts once this long has passed since the first attempt.
+    pub max_wait: Duration,
+    /// Delay before the first retry, doubled for each one after it, plus
+    /// up to 50% random jitter so competing processes fall out of step.
+    pub base_delay: Duration,
+    /// Called before each backoff sleep, e.g. to tell a waiting user.
+    pub on_retry: Option<fn(&BusyRetry)>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RETRY_ATTEMPTS,
+            max_wait: DEFAULT_RETRY_MAX_WAIT,
+            base_delay: BASE_DELAY,
+            on_retry: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Fail on the first `SQLITE_BUSY`.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based), without jitter.
+    fn backoff(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+/// A busy operation about to be retried.
+#[derive(Debug, Clone)]
+pub struct BusyRetry {
+    /// Store operation that hit the lock, e.g. `save_system`.
+    pub operation: &'static str,
+    /// Attempt that failed, 1-based.
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// Sleep before the next attempt.
+    pub delay: Duration,
+}
+
+impl Store {
+    /// Replace how write operations retry on `SQLITE_BUSY`. Defaults to
+    /// [`RetryPolicy::default`].
+    #[must_use]
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Run `op`, rerunning it while it fails with a busy error and the
+    /// policy allows another attempt. Other errors return at once.
+    pub(crate) fn retry_busy<T>(
+        &self,
+        operation: &'static str,
+        mut op: impl FnMut() -> Result<T>,
+    ) -> Result<T> {
+        let policy = self.retry;
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let err = match op() {
+                Err(e) if e.kind() == StoreErrorKind::Busy => e,
+                result => return result,
+            };
+            let backoff = policy.backoff(attempt);
+            let jitter = backoff.mul_f64(rand::rng().random_range(0.0..0.5));
+            let delay = backoff + jitter;
+            if attempt >= policy.max_attempts || started.elapsed() + delay > policy.max_wait {
+                return Err(err);
+            }
+            let retry = BusyRetry {
+                operation,
+                attempt,
+                max_attempts: policy.max_attempts,
+                delay,
+            };
+            tracing::debug!("{operation}: database busy, retrying in {delay:?} ({err})");
+            if let Some(notify) = policy.on_retry {
+                notify(&retry);
+            }
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use super::*;
 use am_core::{
-    episode::Episode, neighborhood::Neighborhood, phasor::DaemonPhasor, quaternion::Quaternion,
+    episode::Episode,
+    neighborhood::Neighborhood,
+    phasor::DaemonPhasor,
+    quaternion::Quaternion,
+    store_trait::{AuditEvent, AuditKind, StoreErrorKind},
     system::DAESystem,
 };
 use rand::SeedableRng;
@@ -144,6 +148,58 @@ fn test_batch_increment_activation_skips_unknown() {
     assert_eq!(c0, 1, "known occurrence should be incremented");
 }
 
+#[test]
+fn test_increment_activations_sums_deltas() {
+    let store = Store::open_in_memory().unwrap();
+    let system = make_system();
+    store.save_system(&system).unwrap();
+
+    let occ0 = system.episodes[0].neighborhoods[0].occurrences[0].id;
+    let occ1 = system.episodes[0].neighborhoods[0].occurrences[1].id;
+
+    store
+        .increment_activations(&[(occ0, 3), (occ1, 1), (Uuid::new_v4(), 9)])
+        .unwrap();
+    store.increment_activations(&[(occ0, 4)]).unwrap();
+    // Repeated IDs fold into the same total
+    store
+        .batch_increment_activation(&[occ1, occ1, occ0])
+        .unwrap();
+
+    let loaded = store.load_system().unwrap();
+    let occs = &loaded.episodes[0].neighborhoods[0].occurrences;
+    assert_eq!(occs[0].activation_count, 8);
+    assert_eq!(occs[1].activation_count, 3);
+}
+
+#[test]
+fn test_increment_activations_rolls_back_failed_batch() {
+    let store = Store::open_in_memory().unwrap();
+    let system = make_system();
+    store.save_system(&system).unwrap();
+
+    let occ0 = system.episodes[0].neighborhoods[0].occurrences[0].id;
+    let occ1 = system.episodes[0].neighborhoods[0].occurrences[1].id;
+    store
+        .conn
+        .execute_batch(&format!(
+            "CREATE TEMP TRIGGER fail_second BEFORE UPDATE ON occurrences \
+             WHEN NEW.id = '{occ1}' BEGIN SELECT RAISE(ABORT, 'injected failure'); END;"
+        ))
+        .unwrap();
+
+    assert!(
+        store
+            .increment_activations(&[(occ0, 5), (occ1, 5)])
+            .is_err()
+    );
+
+    let loaded = store.load_system().unwrap();
+    let occs = &loaded.episodes[0].neighborhoods[0].occurrences;
+    assert_eq!(occs[0].activation_count, 0, "first write must roll back");
+    assert_eq!(occs[1].activation_count, 0);
+}
+
 #[test]
 fn test_batch_set_activation_counts() {
     let store = Store::open_in_memory().unwrap();
@@ -214,7 +270,7 @@ fn test_save_occurrence_positions() {
     let new_phasor = DaemonPhasor::new(1.23);
 
     store
-        .save_occurrence_positions(&[(occ.id, new_pos, new_phasor)])
+        .save_occurrence_positions(&[(occ.id, new_pos, new_phasor, 0.75)])
         .unwrap();
 
     let loaded = store.load_system().unwrap();
@@ -225,6 +281,7 @@ fn test_save_occurrence_positions() {
         (loaded_occ.phasor.theta - 1.23).abs() < 1e-10,
         "phasor not updated"
     );
+    assert_eq!(loaded_occ.drift, 0.75);
 }
 
 #[test]
@@ -240,6 +297,231 @@ fn test_metadata() {
     assert_eq!(store.get_metadata("foo").unwrap(), Some("baz".to_string()));
 }
 
+#[test]
+fn test_word_weight_policy_metadata_overrides() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+
+    let defaults = store.load_system().unwrap().word_weight_policy();
+    assert_eq!(defaults, am_core::system::WordWeightPolicy::default());
+
+    store.set_metadata("word_weight_floor", "0.05").unwrap();
+    store.set_metadata("word_weight_ceiling", "0.5").unwrap();
+    store.set_metadata("skip_hapax_drift", "true").unwrap();
+    store.set_metadata("response_drift_scale", "0.1").unwrap();
+
+    let mut loaded = store.load_system().unwrap();
+    let policy = loaded.word_weight_policy();
+    assert!((policy.floor - 0.05).abs() < 1e-10);
+    assert!((policy.ceiling - 0.5).abs() < 1e-10);
+    assert!(policy.skip_hapax_drift);
+    assert!((policy.response_drift_scale - 0.1).abs() < 1e-10);
+    assert!(loaded.get_word_weight("hello") <= 0.5);
+}
+
+#[test]
+fn test_frozen_words_round_trip_through_load() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+
+    let words = to_tokens(&["World", "test", "world"]);
+    assert_eq!(store.freeze_words(&words).unwrap(), 2);
+    assert_eq!(store.frozen_words().unwrap(), ["test", "world"]);
+
+    let loaded = store.load_system().unwrap();
+    assert!(loaded.is_drift_frozen("world"));
+    assert!(!loaded.is_drift_frozen("hello"));
+
+    assert_eq!(
+        store.unfreeze_words(&to_tokens(&["test", "nope"])).unwrap(),
+        1
+    );
+    assert_eq!(store.frozen_words().unwrap(), ["world"]);
+}
+
+#[test]
+fn test_word_biases_round_trip_through_load() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+
+    assert!((store.set_word_bias("Hello", 3.0).unwrap() - 3.0).abs() < 1e-10);
+    assert!((store.set_word_bias("world", 99.0).unwrap() - 10.0).abs() < 1e-10);
+    assert!(store.set_word_bias("world", 0.0).unwrap().abs() < 1e-10);
+    assert_eq!(
+        store.word_biases().unwrap(),
+        [("hello".to_string(), 3.0), ("world".to_string(), 0.0)]
+    );
+
+    let mut loaded = store.load_system().unwrap();
+    let plain = loaded.idf_weight("hello");
+    assert!((loaded.get_word_weight("hello") - 3.0 * plain).abs() < 1e-10);
+    assert!(loaded.get_word_weight("world").abs() < 1e-10);
+
+    assert_eq!(
+        store
+            .clear_word_biases(&to_tokens(&["WORLD", "nope"]))
+            .unwrap(),
+        1
+    );
+    assert_eq!(store.word_biases().unwrap().len(), 1);
+}
+
+#[test]
+fn test_conscious_project_round_trip() {
+    let store = Store::open_in_memory().unwrap();
+    let mut sys = make_system();
+    let kind = am_core::neighborhood::NeighborhoodType::Decision;
+    sys.add_to_conscious_for("ship nightly", kind, Some("repo-y"), &mut rng());
+    store.save_system(&sys).unwrap();
+
+    let loaded = store.load_system().unwrap();
+    let projects: Vec<Option<&str>> = loaded
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.project.as_deref())
+        .collect();
+    assert_eq!(projects, [None, Some("repo-y")]);
+
+    let listed = store.list_conscious_neighborhoods().unwrap();
+    let tagged = listed.iter().find(|n| n.source_text == "ship nightly");
+    assert_eq!(tagged.unwrap().project.as_deref(), Some("repo-y"));
+}
+
+#[test]
+fn test_compose_config_metadata_overrides() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    assert_eq!(
+        store.load_system().unwrap().compose_config(),
+        &am_core::compose::ComposeConfig::default()
+    );
+
+    store.set_metadata("min_activated_words", "2").unwrap();
+    store.set_metadata("min_candidate_score", "0.5").unwrap();
+    let config = store.load_system().unwrap().compose_config().clone();
+    assert_eq!(config.min_activated_words, 2);
+    assert!((config.min_score - 0.5).abs() < 1e-10);
+
+    store.set_metadata("min_activated_words", "0").unwrap();
+    store.set_metadata("min_candidate_score", "-1").unwrap();
+    assert_eq!(
+        store.compose_config().unwrap(),
+        am_core::compose::ComposeConfig::default()
+    );
+}
+
+#[test]
+fn test_compose_novel_metadata_overrides() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .set_metadata("compose.novel.max_activated_words", "4")
+        .unwrap();
+    store
+        .set_metadata("compose.novel.require_no_conscious_overlap", "false")
+        .unwrap();
+    store
+        .set_metadata("compose.novel.min_novelty_score", "0.25")
+        .unwrap();
+    let novel = store.compose_config().unwrap().novel;
+    assert_eq!(novel.max_activated_words, 4);
+    assert!(!novel.require_no_conscious_overlap);
+    assert!((novel.min_novelty_score - 0.25).abs() < 1e-10);
+
+    store
+        .set_metadata("compose.novel.require_no_conscious_overlap", "maybe")
+        .unwrap();
+    store
+        .set_metadata("compose.novel.min_novelty_score", "NaN")
+        .unwrap();
+    let novel = store.compose_config().unwrap().novel;
+    assert!(novel.require_no_conscious_overlap);
+    assert!(novel.min_novelty_score.abs() < 1e-10);
+}
+
+#[test]
+fn test_compose_label_metadata_overrides() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .set_metadata("compose.labels.subconscious", "ERINNERUNG {index}:")
+        .unwrap();
+    store
+        .set_metadata("compose.labels.source", "[Quelle: {source}]")
+        .unwrap();
+    let labels = store.compose_config().unwrap().labels;
+    assert_eq!(labels.subconscious, "ERINNERUNG {index}:");
+    assert_eq!(labels.source, "[Quelle: {source}]");
+    assert_eq!(
+        labels.conscious,
+        am_core::compose::LabelSet::default().conscious
+    );
+}
+
+#[test]
+fn test_compose_highlight_metadata() {
+    let store = Store::open_in_memory().unwrap();
+    assert_eq!(store.compose_config().unwrap().highlight, None);
+    store.set_metadata("compose.highlight.open", "**").unwrap();
+    let highlight = store.compose_config().unwrap().highlight.unwrap();
+    assert_eq!(
+        (highlight.open.as_str(), highlight.close.as_str()),
+        ("**", "**")
+    );
+    store.set_metadata("compose.highlight.close", "]]").unwrap();
+    assert_eq!(
+        store.compose_config().unwrap().highlight.unwrap().close,
+        "]]"
+    );
+}
+
+#[test]
+fn test_occurrence_spans_round_trip() {
+    let mut sys = DAESystem::new("test-agent");
+    let episode = am_core::tokenizer::ingest_text("Café crème, s'il vous plaît.", None, &mut rng());
+    let spans: Vec<_> = episode.neighborhoods[0]
+        .occurrences
+        .iter()
+        .map(|o| o.span)
+        .collect();
+    assert!(spans.iter().all(Option::is_some));
+    sys.add_episode(episode);
+    sys.add_to_conscious("no spans here", &mut rng());
+
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&sys).unwrap();
+    let loaded = store.load_system().unwrap();
+    let nbhd = &loaded.episodes[0].neighborhoods[0];
+    assert_eq!(
+        nbhd.occurrences.iter().map(|o| o.span).collect::<Vec<_>>(),
+        spans
+    );
+    for occ in &nbhd.occurrences {
+        let (start, end) = occ.span.unwrap();
+        assert_eq!(nbhd.source_text[start..end].to_lowercase(), occ.word);
+    }
+    assert!(
+        loaded.conscious_episode.neighborhoods[0]
+            .occurrences
+            .iter()
+            .all(|o| o.span.is_none())
+    );
+    let by_word = store.get_occurrences_by_word("crème").unwrap();
+    assert_eq!(by_word[0].span, spans[1]);
+}
+
+#[test]
+fn test_word_weight_policy_ignores_bad_metadata() {
+    let store = Store::open_in_memory().unwrap();
+    store.set_metadata("word_weight_floor", "lots").unwrap();
+    store.set_metadata("word_weight_ceiling", "-1").unwrap();
+    store.set_metadata("skip_hapax_drift", "maybe").unwrap();
+    store.set_metadata("response_drift_scale", "1.5").unwrap();
+    assert_eq!(
+        store.word_weight_policy().unwrap(),
+        am_core::system::WordWeightPolicy::default()
+    );
+}
+
 #[test]
 fn test_save_overwrites_previous() {
     let store = Store::open_in_memory().unwrap();
@@ -369,6 +651,167 @@ fn test_load_system_roundtrip_500_occurrences() {
     );
 }
 
+/// Seeded brain whose neighborhood rowids interleave across episodes, with
+/// an empty episode and an archived one.
+fn seeded_store(episodes: usize) -> Store {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("seeded");
+    let words: Vec<String> = (0..40).map(|i| format!("word{i}")).collect();
+    for e in 0..episodes {
+        let mut ep = Episode::new(&format!("ep-{e}"));
+        for n in 0..(e % 4) {
+            let tokens = &words[(e + n) % 20..(e + n) % 20 + 3 + n];
+            let text = tokens.join(" ");
+            ep.add_neighborhood(Neighborhood::from_tokens(tokens, None, &text, &mut rng));
+        }
+        sys.add_episode(ep);
+    }
+    sys.add_to_conscious("seeded conscious memory", &mut rng);
+    store.save_system(&sys).unwrap();
+
+    // A second save appends to the first episode, so its neighborhoods no
+    // longer sit together in rowid order.
+    let tokens = to_tokens(&["appended", "later"]);
+    sys.episodes[0].add_neighborhood(Neighborhood::from_tokens(
+        &tokens,
+        None,
+        "appended later",
+        &mut rng,
+    ));
+    store.save_system(&sys).unwrap();
+    let archived = sys.episodes[episodes - 1].id.to_string();
+    store
+        .conn
+        .execute(
+            "UPDATE episodes SET archived = 1 WHERE id = ?1",
+            [&archived],
+        )
+        .unwrap();
+    store
+}
+
+type FlatRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<u32>,
+    Option<f64>,
+);
+
+/// Rows of the single-JOIN loader `load_episodes` replaced.
+fn joined_rows(store: &Store, archived: bool) -> Vec<FlatRow> {
+    let mut stmt = store
+        .conn
+        .prepare(
+            "SELECT e.id, e.name, n.id, n.source_text, o.id, o.word,
+                    o.activation_count, o.phasor_theta
+             FROM episodes e
+             LEFT JOIN neighborhoods n ON n.episode_id = e.id AND n.brain_id = e.brain_id
+             LEFT JOIN occurrences o ON o.neighborhood_id = n.id AND o.brain_id = n.brain_id
+             WHERE e.archived = ?1 AND e.brain_id = ?2
+             ORDER BY e.rowid, n.rowid, o.rowid",
+        )
+        .unwrap();
+    stmt.query_map(rusqlite::params![archived, store.brain], |r| {
+        Ok((
+            r.get(0)?,
+            r.get(1)?,
+            r.get(2)?,
+            r.get(3)?,
+            r.get(4)?,
+            r.get(5)?,
+            r.get(6)?,
+            r.get(7)?,
+        ))
+    })
+    .unwrap()
+    .collect::<rusqlite::Result<_>>()
+    .unwrap()
+}
+
+/// `episodes` flattened the way the JOIN returns them.
+fn flat_rows<'a>(episodes: impl IntoIterator<Item = &'a Episode>) -> Vec<FlatRow> {
+    let mut rows = Vec::new();
+    for ep in episodes {
+        let row = |n: Option<&Neighborhood>, o: Option<&am_core::occurrence::Occurrence>| {
+            (
+                ep.id.to_string(),
+                ep.name.clone(),
+                n.map(|n| n.id.to_string()),
+                n.map(|n| n.source_text.clone()),
+                o.map(|o| o.id.to_string()),
+                o.map(|o| o.word.clone()),
+                o.map(|o| o.activation_count),
+                o.map(|o| o.phasor.theta),
+            )
+        };
+        if ep.neighborhoods.is_empty() {
+            rows.push(row(None, None));
+        }
+        for n in &ep.neighborhoods {
+            if n.occurrences.is_empty() {
+                rows.push(row(Some(n), None));
+            }
+            for o in &n.occurrences {
+                rows.push(row(Some(n), Some(o)));
+            }
+        }
+    }
+    rows
+}
+
+#[test]
+fn test_load_matches_single_join_order() {
+    let store = seeded_store(12);
+    let sys = store.load_system().unwrap();
+
+    let mut live = sys.episodes.clone();
+    live.insert(0, sys.conscious_episode.clone());
+    let mut expected = joined_rows(&store, false);
+    // The JOIN keeps rowid order; load_system moves the conscious episode
+    // out of the list, so compare it first.
+    let conscious_id = sys.conscious_episode.id.to_string();
+    expected.sort_by_key(|r| r.0 != conscious_id);
+    assert_eq!(flat_rows(&live), expected);
+    assert_eq!(sys.episodes[0].neighborhoods.len(), 1);
+    assert_eq!(
+        sys.episodes[0].neighborhoods[0].source_text,
+        "appended later"
+    );
+
+    let archived = store.load_archived_episodes().unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(flat_rows(&archived), joined_rows(&store, true));
+}
+
+static LOAD_STATEMENTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn count_statement(_sql: &str) {
+    LOAD_STATEMENTS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Statements `load_system` runs against `store`.
+fn load_statement_count(mut store: Store) -> usize {
+    store.conn.trace(Some(count_statement));
+    let before = LOAD_STATEMENTS.load(std::sync::atomic::Ordering::Relaxed);
+    store.load_system().unwrap();
+    let after = LOAD_STATEMENTS.load(std::sync::atomic::Ordering::Relaxed);
+    store.conn.trace(None);
+    after - before
+}
+
+#[test]
+fn test_load_statement_count_is_independent_of_size() {
+    let small = load_statement_count(seeded_store(3));
+    let large = load_statement_count(seeded_store(40));
+    assert!(small > 0, "trace saw no statements");
+    assert_eq!(small, large, "load_system statements grew with the brain");
+}
+
 #[test]
 fn test_health_check() {
     let store = Store::open_in_memory().unwrap();
@@ -463,6 +906,83 @@ fn test_gc_preserves_conscious() {
     );
 }
 
+/// Query "migration" `times` times against a stored "migration alembic
+/// downgrade" chunk, persisting each query's manifest the way the server
+/// does.
+fn query_with_spillover(spillover: f64, times: usize) -> Store {
+    use am_core::query::QueryEngine;
+
+    let store = Store::open_in_memory().unwrap();
+    let mut sys = DAESystem::new("test-agent");
+    let mut ep = Episode::new("episode-migrations");
+    let tokens = to_tokens(&["migration", "alembic", "downgrade"]);
+    ep.add_neighborhood(Neighborhood::from_tokens(
+        &tokens,
+        None,
+        "migration alembic downgrade",
+        &mut rng(),
+    ));
+    sys.add_episode(ep);
+    sys.set_activation_spillover(spillover);
+    store.save_system(&sys).unwrap();
+
+    for _ in 0..times {
+        let manifest = QueryEngine::process_query(&mut sys, "migration").manifest;
+        store
+            .increment_activations(&manifest.activation_deltas())
+            .unwrap();
+        let spilled: Vec<_> = sys.episodes[0].neighborhoods[0]
+            .occurrences
+            .iter()
+            .filter(|o| manifest.spilled.contains(&o.id))
+            .map(|o| (o.id, o.activation_count, o.spillover))
+            .collect();
+        store.save_activation_spillover(&spilled).unwrap();
+    }
+    store
+}
+
+#[test]
+fn test_spillover_protects_siblings_from_gc() {
+    // Without spillover only "migration" is warm; its context is evicted
+    let store = query_with_spillover(0.0, 8);
+    let result = store.gc_pass(1, &no_retention()).unwrap();
+    assert_eq!(result.evicted_occurrences, 2);
+    let words: Vec<_> = store.load_system().unwrap().episodes[0].neighborhoods[0]
+        .occurrences
+        .iter()
+        .map(|o| o.word.clone())
+        .collect();
+    assert_eq!(words, ["migration"]);
+
+    // 8 activations at 0.25 credit each sibling twice, above the floor
+    let store = query_with_spillover(0.25, 8);
+    let result = store.gc_pass(1, &no_retention()).unwrap();
+    assert_eq!(result.evicted_occurrences, 0);
+    let sys = store.load_system().unwrap();
+    let occs = &sys.episodes[0].neighborhoods[0].occurrences;
+    assert_eq!(occs[0].activation_count, 8);
+    assert!(occs[1..].iter().all(|o| o.activation_count == 2));
+}
+
+#[test]
+fn test_spillover_residue_persists() {
+    let store = query_with_spillover(0.25, 3);
+    let sys = store.load_system().unwrap();
+    let siblings = &sys.episodes[0].neighborhoods[0].occurrences[1..];
+    for occ in siblings {
+        assert_eq!(occ.activation_count, 0);
+        assert!((occ.spillover - 0.75).abs() < 1e-12);
+    }
+    assert_eq!(
+        store
+            .gc_pass(0, &no_retention())
+            .unwrap()
+            .evicted_occurrences,
+        2
+    );
+}
+
 #[test]
 fn test_gc_removes_empty_episodes() {
     let store = Store::open_in_memory().unwrap();
@@ -474,6 +994,34 @@ fn test_gc_removes_empty_episodes() {
     assert_eq!(result.removed_neighborhoods, 1);
 }
 
+#[test]
+fn test_gc_detail_reconciles_a_stale_system() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system_with_activations();
+    store.save_system(&sys).unwrap();
+    let cold = sys
+        .episodes
+        .iter()
+        .find(|e| e.name == "episode-cold")
+        .unwrap();
+
+    let result = store.gc_pass(0, &no_retention()).unwrap();
+    assert_eq!(result.detail.occurrences.len(), 3);
+    assert_eq!(result.detail.neighborhoods, vec![cold.neighborhoods[0].id]);
+    assert_eq!(result.detail.episodes, vec![cold.id]);
+
+    // A copy loaded before the pass would write the cold rows back
+    assert_eq!(store.resurrected_count(&sys), 3);
+    let mut reconciled = sys;
+    assert_eq!(reconciled.apply_gc(&result.detail), 3);
+    assert_eq!(store.resurrected_count(&reconciled), 0);
+    store.save_system(&reconciled).unwrap();
+
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.n(), reconciled.n());
+}
+
 #[test]
 fn test_gc_grace_epochs_protects_fresh_data() {
     let store = Store::open_in_memory().unwrap();
@@ -536,56 +1084,645 @@ fn test_gc_noop_when_empty() {
     assert_eq!(result.removed_episodes, 0);
 }
 
-// --- Inspection query tests ---
-
 #[test]
-fn test_list_episodes() {
+fn test_gc_archive_flags_cold_episodes_without_deleting() {
     let store = Store::open_in_memory().unwrap();
-    let sys = make_system();
+    let sys = make_system_with_activations();
     store.save_system(&sys).unwrap();
+    let before_occs = store.occurrence_count().unwrap();
 
-    let episodes = store.list_episodes().unwrap();
-    // 1 subconscious + 1 conscious
-    assert_eq!(episodes.len(), 2);
+    assert_eq!(
+        store.gc_archive_eligible_count(0, &no_retention()).unwrap(),
+        1
+    );
+    let result = store.gc_archive_pass(0, &no_retention()).unwrap();
+    assert_eq!(result.archived_episodes, 1);
+    assert_eq!(result.evicted_occurrences, 0);
+    assert_eq!(store.occurrence_count().unwrap(), before_occs);
 
-    let conscious: Vec<_> = episodes.iter().filter(|e| e.is_conscious).collect();
-    assert_eq!(conscious.len(), 1);
-    assert!(conscious[0].occurrence_count > 0);
+    // Archived rows are invisible to a normal load...
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.episodes[0].name, "episode-warm");
+    assert!(!loaded.conscious_episode.neighborhoods.is_empty());
+
+    // ...but reachable on demand, and listed separately
+    let archived = store.load_archived_episodes().unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].name, "episode-cold");
+    assert_eq!(archived[0].neighborhoods[0].occurrences.len(), 3);
+    let page = store
+        .list_archived_episodes_page(PageRequest::new(20, 0), EpisodeSort::Recent)
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert!(page.items[0].archived);
+    let live = store
+        .list_subconscious_episodes_page(PageRequest::new(20, 0), EpisodeSort::Recent)
+        .unwrap();
+    assert_eq!(live.total, 1);
 
-    let sub: Vec<_> = episodes.iter().filter(|e| !e.is_conscious).collect();
-    assert_eq!(sub.len(), 1);
-    assert_eq!(sub[0].name, "episode-1");
-    assert_eq!(sub[0].neighborhood_count, 1);
-    assert_eq!(sub[0].occurrence_count, 3);
+    // A second pass finds nothing new to archive
+    let again = store.gc_archive_pass(0, &no_retention()).unwrap();
+    assert_eq!(again.archived_episodes, 0);
 }
 
 #[test]
-fn test_list_conscious_neighborhoods() {
+fn test_archived_episodes_survive_save_and_gc() {
     let store = Store::open_in_memory().unwrap();
-    let sys = make_system();
-    store.save_system(&sys).unwrap();
+    store.save_system(&make_system_with_activations()).unwrap();
+    store.gc_archive_pass(0, &no_retention()).unwrap();
 
-    let conscious = store.list_conscious_neighborhoods().unwrap();
-    assert_eq!(conscious.len(), 1);
-    assert_eq!(conscious[0].source_text, "conscious thought");
-    assert!(conscious[0].occurrence_count > 0);
+    // A full rewrite of the loaded system leaves archived rows alone
+    let loaded = store.load_system().unwrap();
+    store.save_system(&loaded).unwrap();
+    assert_eq!(store.load_archived_episodes().unwrap().len(), 1);
+
+    // Deleting GC never touches archived episodes
+    let result = store.gc_pass(0, &no_retention()).unwrap();
+    assert_eq!(result.evicted_occurrences, 0);
+    assert_eq!(store.load_archived_episodes().unwrap().len(), 1);
 }
 
 #[test]
-fn test_list_neighborhoods() {
-    let store = Store::open_in_memory().unwrap();
-    let sys = make_system();
+fn test_epoch_pass_moves_cold_episodes_out_of_brain_db() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::open(&dir.path().join("brain.db")).unwrap();
+    let mut sys = make_system_with_activations();
+    sys.episodes[0].timestamp = "2023-04-01T10:00:00Z".to_string();
     store.save_system(&sys).unwrap();
 
-    let all = store.list_neighborhoods().unwrap();
-    // 1 subconscious + 1 conscious neighborhood
-    assert_eq!(all.len(), 2);
-}
+    // A dry run reports the move without making it
+    let plan = store.epoch_pass(0, &no_retention(), false).unwrap();
+    assert_eq!(plan.moved.len(), 1);
+    assert_eq!(plan.moved[0].file, "brain-2023.db");
+    assert_eq!(plan.moved[0].episodes, 1);
+    assert_eq!(plan.moved[0].neighborhoods, 1);
+    assert!(store.epoch_files().unwrap().is_empty());
+    assert_eq!(store.load_system().unwrap().episodes.len(), 2);
 
-#[test]
-fn test_top_words() {
-    let store = Store::open_in_memory().unwrap();
-    let sys = make_system_with_activations();
+    let report = store.epoch_pass(0, &no_retention(), true).unwrap();
+    assert_eq!(
+        report,
+        EpochReport {
+            before_size: report.before_size,
+            after_size: report.after_size,
+            ..plan
+        }
+    );
+    assert_eq!(
+        store.epoch_files().unwrap(),
+        vec![dir.path().join("brain-2023.db")]
+    );
+
+    // The active database no longer holds the cold episode...
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.episodes[0].name, "episode-warm");
+    assert!(!loaded.conscious_episode.neighborhoods.is_empty());
+    assert!(store.load_archived_episodes().unwrap().is_empty());
+
+    // ...the epoch file does, whole
+    let moved = store.load_epoch_episodes().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0].name, "episode-cold");
+    assert_eq!(moved[0].neighborhoods[0].occurrences.len(), 3);
+    let info = store.epoch_info().unwrap();
+    assert_eq!(info[0].episodes, 1);
+    assert!(info[0].size > 0);
+
+    // Nothing left to move
+    assert!(
+        store
+            .epoch_pass(0, &no_retention(), true)
+            .unwrap()
+            .moved
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_epoch_pass_moves_archived_but_never_conscious() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::open(&dir.path().join("brain.db")).unwrap();
+    let mut sys = make_system_with_activations();
+    sys.episodes[0].timestamp = "not a date".to_string();
+    store.save_system(&sys).unwrap();
+    store.gc_archive_pass(0, &no_retention()).unwrap();
+    let conscious_before = store
+        .load_system()
+        .unwrap()
+        .conscious_episode
+        .neighborhoods
+        .len();
+
+    // Everything is cold at a high floor, yet conscious memories stay put
+    let report = store.epoch_pass(u32::MAX, &no_retention(), true).unwrap();
+    let files: Vec<&str> = report.moved.iter().map(|m| m.file.as_str()).collect();
+    assert_eq!(report.episodes(), 2);
+    assert!(files.contains(&"brain-undated.db"), "{files:?}");
+
+    let loaded = store.load_system().unwrap();
+    assert!(loaded.episodes.is_empty());
+    assert_eq!(
+        loaded.conscious_episode.neighborhoods.len(),
+        conscious_before
+    );
+    let moved = store.load_epoch_episodes().unwrap();
+    assert_eq!(moved.len(), 2);
+    assert!(moved.iter().all(|ep| !ep.is_conscious));
+}
+
+#[test]
+fn test_epoch_pass_needs_a_file_backed_store() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system_with_activations()).unwrap();
+    let err = store.epoch_pass(0, &no_retention(), false).unwrap_err();
+    assert!(err.to_string().contains("file-backed"), "{err}");
+    assert!(store.load_epoch_episodes().unwrap().is_empty());
+}
+
+#[test]
+fn test_restore_episode_returns_it_to_load_system() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system_with_activations()).unwrap();
+    store.gc_archive_pass(0, &no_retention()).unwrap();
+    let id = store.load_archived_episodes().unwrap()[0].id.to_string();
+
+    assert!(store.restore_episode(&id).unwrap());
+    assert!(
+        !store.restore_episode(&id).unwrap(),
+        "already restored episodes are not archived"
+    );
+
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes.len(), 2);
+    assert!(loaded.episodes.iter().any(|e| e.name == "episode-cold"));
+    assert!(store.load_archived_episodes().unwrap().is_empty());
+}
+
+// --- Inspection query tests ---
+
+#[test]
+fn test_list_episodes() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let episodes = store.list_episodes().unwrap();
+    // 1 subconscious + 1 conscious
+    assert_eq!(episodes.len(), 2);
+
+    let conscious: Vec<_> = episodes.iter().filter(|e| e.is_conscious).collect();
+    assert_eq!(conscious.len(), 1);
+    assert!(conscious[0].occurrence_count > 0);
+
+    let sub: Vec<_> = episodes.iter().filter(|e| !e.is_conscious).collect();
+    assert_eq!(sub.len(), 1);
+    assert_eq!(sub[0].name, "episode-1");
+    assert_eq!(sub[0].neighborhood_count, 1);
+    assert_eq!(sub[0].occurrence_count, 3);
+}
+
+#[test]
+fn test_find_episodes_by_id_prefix_name_and_conscious() {
+    let store = Store::open_in_memory().unwrap();
+    let mut sys = make_system();
+    sys.add_episode(Episode::new("episode-2"));
+    store.save_system(&sys).unwrap();
+    let episodes = store.list_episodes().unwrap();
+    let first = episodes.iter().find(|e| e.name == "episode-1").unwrap();
+
+    let conscious = store.find_episodes("conscious").unwrap();
+    assert_eq!(conscious.len(), 1);
+    assert!(conscious[0].is_conscious);
+
+    let by_id = store.find_episodes(&first.id.to_uppercase()).unwrap();
+    assert_eq!(by_id.len(), 1);
+    assert_eq!(by_id[0].id, first.id);
+    let by_prefix = store.find_episodes(&first.id[..8]).unwrap();
+    assert_eq!(by_prefix.len(), 1);
+    assert_eq!(by_prefix[0].id, first.id);
+
+    // A name prefix shared by two episodes is ambiguous.
+    assert_eq!(store.find_episodes("Episode-").unwrap().len(), 2);
+    assert_eq!(store.find_episodes("episode-2").unwrap().len(), 1);
+    assert!(store.find_episodes("nothing").unwrap().is_empty());
+    assert!(store.find_episodes("  ").unwrap().is_empty());
+}
+
+#[test]
+fn test_episode_neighborhoods_lists_words_with_activation() {
+    let store = Store::open_in_memory().unwrap();
+    let mut sys = make_system();
+    sys.episodes[0].neighborhoods[0].occurrences[1].activation_count = 4;
+    store.save_system(&sys).unwrap();
+    let id = sys.episodes[0].id.to_string();
+
+    let neighborhoods = store.episode_neighborhoods(&id).unwrap();
+    assert_eq!(neighborhoods.len(), 1);
+    assert_eq!(neighborhoods[0].source_text, "hello world test");
+    assert_eq!(
+        neighborhoods[0].words,
+        vec![
+            ("hello".to_string(), 0),
+            ("world".to_string(), 4),
+            ("test".to_string(), 0),
+        ]
+    );
+    assert!(store.episode_neighborhoods("missing").unwrap().is_empty());
+}
+
+#[test]
+fn test_list_conscious_neighborhoods() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let conscious = store.list_conscious_neighborhoods().unwrap();
+    assert_eq!(conscious.len(), 1);
+    assert_eq!(conscious[0].source_text, "conscious thought");
+    assert!(conscious[0].occurrence_count > 0);
+}
+
+/// Three conscious memories with distinct types, ages, and activations,
+/// added oldest first.
+fn make_conscious_system() -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    let entries = [
+        (
+            "Use 100% of the retry_budget",
+            NeighborhoodType::Decision,
+            "2026-01-01T00:00:00Z",
+            9,
+        ),
+        (
+            "Prefers tabs over spaces",
+            NeighborhoodType::Preference,
+            "2026-03-01T00:00:00Z",
+            1,
+        ),
+        (
+            "Retry storms follow deploys",
+            NeighborhoodType::Insight,
+            "2026-02-01T00:00:00Z",
+            4,
+        ),
+    ];
+    for (text, kind, created_at, activation) in entries {
+        sys.add_to_conscious_typed(text, kind, &mut rng);
+        let nbhd = sys.conscious_episode.neighborhoods.last_mut().unwrap();
+        nbhd.created_at = Some(created_at.to_string());
+        for occ in &mut nbhd.occurrences {
+            occ.activation_count = activation;
+        }
+    }
+    sys
+}
+
+fn conscious_texts(store: &Store, search: Option<&str>, sort: ConsciousSort) -> Vec<String> {
+    store
+        .list_conscious_neighborhoods_page(PageRequest::ALL, search, sort)
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|n| n.source_text)
+        .collect()
+}
+
+#[test]
+fn test_conscious_search_matches_source_text() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_conscious_system()).unwrap();
+
+    // Case-insensitive substring of the text, not just a whole word
+    let hits = conscious_texts(&store, Some("RETRY"), ConsciousSort::Stored);
+    assert_eq!(
+        hits,
+        [
+            "Use 100% of the retry_budget",
+            "Retry storms follow deploys"
+        ]
+    );
+    assert_eq!(
+        conscious_texts(&store, Some("storms follow"), ConsciousSort::Stored),
+        ["Retry storms follow deploys"]
+    );
+
+    // LIKE wildcards in the term match literally
+    assert_eq!(
+        conscious_texts(&store, Some("100%"), ConsciousSort::Stored),
+        ["Use 100% of the retry_budget"]
+    );
+    assert!(conscious_texts(&store, Some("retry_b%t"), ConsciousSort::Stored).is_empty());
+
+    let page = store
+        .list_conscious_neighborhoods_page(
+            PageRequest::new(1, 0),
+            Some("retry"),
+            ConsciousSort::Stored,
+        )
+        .unwrap();
+    assert_eq!(page.total, 2, "total counts every match, not just the page");
+    assert!(conscious_texts(&store, Some("kubernetes"), ConsciousSort::Stored).is_empty());
+
+    // A blank term does not filter
+    assert_eq!(
+        conscious_texts(&store, Some("  "), ConsciousSort::Stored).len(),
+        3
+    );
+}
+
+#[test]
+fn test_conscious_sort_orders() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_conscious_system()).unwrap();
+
+    let recency = conscious_texts(&store, None, ConsciousSort::Recency);
+    assert_eq!(
+        recency,
+        [
+            "Prefers tabs over spaces",
+            "Retry storms follow deploys",
+            "Use 100% of the retry_budget",
+        ]
+    );
+
+    assert_eq!(
+        conscious_texts(&store, None, ConsciousSort::Activation),
+        [
+            "Use 100% of the retry_budget",
+            "Retry storms follow deploys",
+            "Prefers tabs over spaces",
+        ]
+    );
+
+    let by_type: Vec<_> = store
+        .list_conscious_neighborhoods_page(PageRequest::ALL, None, ConsciousSort::Type)
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|n| (n.neighborhood_type, n.created_at.unwrap()))
+        .collect();
+    assert_eq!(
+        by_type,
+        [
+            (
+                NeighborhoodType::Decision,
+                "2026-01-01T00:00:00Z".to_string()
+            ),
+            (
+                NeighborhoodType::Insight,
+                "2026-02-01T00:00:00Z".to_string()
+            ),
+            (
+                NeighborhoodType::Preference,
+                "2026-03-01T00:00:00Z".to_string()
+            ),
+        ]
+    );
+
+    // Stored order is insertion order
+    assert_eq!(
+        conscious_texts(&store, None, ConsciousSort::Stored)[0],
+        "Use 100% of the retry_budget"
+    );
+}
+
+#[test]
+fn test_list_neighborhoods() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let all = store.list_neighborhoods().unwrap();
+    // 1 subconscious + 1 conscious neighborhood
+    assert_eq!(all.len(), 2);
+}
+
+#[test]
+fn test_feedback_scores_round_trip() {
+    let store = Store::open_in_memory().unwrap();
+    let mut sys = make_system();
+    let id = sys.episodes[0].neighborhoods[0].id;
+    store.save_system(&sys).unwrap();
+
+    // Unknown IDs are skipped
+    store
+        .save_feedback_scores(&[(id, -1.8), (Uuid::new_v4(), 1.0)])
+        .unwrap();
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes[0].neighborhoods[0].feedback_score, -1.8);
+    let listed = store.list_neighborhoods().unwrap();
+    let detail = listed.iter().find(|n| n.id == id.to_string()).unwrap();
+    assert_eq!(detail.feedback_score, -1.8);
+
+    // A full save keeps the score too
+    sys.episodes[0].neighborhoods[0].feedback_score = 2.5;
+    store.save_system(&sys).unwrap();
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes[0].neighborhoods[0].feedback_score, 2.5);
+}
+
+#[test]
+fn test_salient_group_round_trips_and_lists() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    let chunks = ["first part".to_string(), "second part".to_string()];
+    let ids = sys.add_conscious_group(&chunks, NeighborhoodType::Insight, None, &mut rng);
+    sys.add_to_conscious("stored whole", &mut rng);
+    store.save_system(&sys).unwrap();
+
+    let loaded = store.load_system().unwrap();
+    let group = loaded.conscious_episode.neighborhoods[0].group_id.unwrap();
+    assert_eq!(
+        loaded
+            .conscious_group(group)
+            .iter()
+            .map(|n| n.id)
+            .collect::<Vec<_>>(),
+        ids
+    );
+    assert_eq!(loaded.conscious_episode.neighborhoods[2].group_id, None);
+
+    let page = store
+        .list_conscious_neighborhoods_page(PageRequest::ALL, None, ConsciousSort::Stored)
+        .unwrap();
+    let listed: Vec<_> = page.items.iter().map(|n| n.group_id.clone()).collect();
+    assert_eq!(
+        listed,
+        [Some(group.to_string()), Some(group.to_string()), None]
+    );
+}
+
+#[test]
+fn test_list_pages_are_bounded_in_sql() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    for i in 0..60 {
+        let mut ep = Episode::new(&format!("episode-{i}"));
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["alpha", &format!("word{i}")]),
+            None,
+            &format!("text {i}"),
+            &mut rng,
+        ));
+        sys.add_episode(ep);
+    }
+    store.save_system(&sys).unwrap();
+
+    // Timestamps never decrease across the loop, so newest first is
+    // reverse insertion
+    let page = store
+        .list_subconscious_episodes_page(PageRequest::new(20, 0), EpisodeSort::Recent)
+        .unwrap();
+    assert_eq!(page.items.len(), 20);
+    assert_eq!(page.total, 60);
+    assert_eq!(page.items[0].name, "episode-59");
+
+    let page = store
+        .list_subconscious_episodes_page(PageRequest::new(20, 50), EpisodeSort::Recent)
+        .unwrap();
+    assert_eq!(page.items.len(), 10);
+    assert_eq!(page.items[0].name, "episode-9");
+
+    let page = store
+        .list_neighborhoods_page(PageRequest::new(20, 100), NeighborhoodSort::Activation)
+        .unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.total, 60);
+
+    // Consecutive pages tile the full listing in the same order
+    let all = store.list_neighborhoods().unwrap();
+    let paged: Vec<_> = (0..3)
+        .flat_map(|i| {
+            store
+                .list_neighborhoods_page(PageRequest::new(20, i * 20), NeighborhoodSort::Activation)
+                .unwrap()
+                .items
+        })
+        .map(|n| n.id)
+        .collect();
+    let full: Vec<_> = all.into_iter().map(|n| n.id).collect();
+    assert_eq!(paged, full);
+
+    let conscious = store
+        .list_conscious_neighborhoods_page(PageRequest::new(20, 0), None, ConsciousSort::Stored)
+        .unwrap();
+    assert!(conscious.items.is_empty());
+    assert_eq!(conscious.total, 0);
+}
+
+#[test]
+fn test_list_neighborhoods_page_sorts_thousands_of_rows_in_sql() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    let words = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta"];
+    for e in 0..30 {
+        let mut ep = Episode::new(&format!("episode-{e}"));
+        for j in 0..100 {
+            let i = e * 100 + j;
+            let mut n = Neighborhood::from_tokens(
+                &to_tokens(&words[..i % 7 + 1]),
+                None,
+                &format!("text {i}"),
+                &mut rng,
+            );
+            n.created_at = Some(format!("2026-01-01T00:00:00.{i:04}Z"));
+            n.occurrences[0].activation_count = (i % 13) as u32;
+            ep.add_neighborhood(n);
+        }
+        sys.add_episode(ep);
+    }
+    store.save_system(&sys).unwrap();
+
+    let page = |sort, offset| {
+        store
+            .list_neighborhoods_page(PageRequest::new(25, offset), sort)
+            .unwrap()
+    };
+
+    let by_activation = page(NeighborhoodSort::Activation, 0);
+    assert_eq!(by_activation.total, 3000);
+    assert_eq!(by_activation.items.len(), 25);
+    assert!(by_activation.items.iter().all(|n| n.total_activation == 12));
+    assert_eq!(by_activation.items[0].source_text, "text 12");
+
+    let by_recency = page(NeighborhoodSort::Recency, 0);
+    assert_eq!(by_recency.items.len(), 25);
+    assert_eq!(by_recency.items[0].source_text, "text 2999");
+    assert_eq!(
+        by_recency.items[0].created_at.as_deref(),
+        Some("2026-01-01T00:00:00.2999Z")
+    );
+    let last_page = page(NeighborhoodSort::Recency, 2990);
+    assert_eq!(last_page.items.len(), 10);
+    assert_eq!(last_page.items[9].source_text, "text 0");
+
+    let by_size = page(NeighborhoodSort::OccurrenceCount, 0);
+    assert!(by_size.items.iter().all(|n| n.occurrence_count == 7));
+    assert_eq!(by_size.items[0].source_text, "text 6");
+    assert_eq!(by_size.items[1].source_text, "text 13");
+}
+
+thread_local! {
+    static TRACED: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn record_statement(sql: &str) {
+    TRACED.with(|traced| traced.borrow_mut().push(sql.to_string()));
+}
+
+#[test]
+fn test_inspection_joins_search_by_brain_and_parent() {
+    let mut store = seeded_store(3);
+    store.conn.trace(Some(record_statement));
+    store.list_episodes().unwrap();
+    store
+        .list_subconscious_episodes_page(PageRequest::new(20, 0), EpisodeSort::Recent)
+        .unwrap();
+    store
+        .list_conscious_neighborhoods_page(PageRequest::ALL, None, ConsciousSort::Stored)
+        .unwrap();
+    store
+        .list_neighborhoods_page(PageRequest::new(25, 0), NeighborhoodSort::Activation)
+        .unwrap();
+    store.conn.trace(None);
+
+    let joins: Vec<String> = TRACED
+        .take()
+        .into_iter()
+        .filter(|sql| sql.contains("LEFT JOIN occurrences o"))
+        .collect();
+    assert_eq!(joins.len(), 4, "{joins:#?}");
+    for sql in &joins {
+        let mut stmt = store
+            .conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {sql}"))
+            .unwrap();
+        let plan: Vec<String> = stmt
+            .query_map([], |row| row.get(3))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        let occurrences = plan
+            .iter()
+            .find(|step| step.starts_with("SEARCH o "))
+            .unwrap_or_else(|| panic!("no search of occurrences in {plan:#?}"));
+        assert!(
+            occurrences.contains("idx_occ_brain_nbhd (brain_id=? AND neighborhood_id=?)"),
+            "{occurrences}\n{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_top_words() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system_with_activations();
     store.save_system(&sys).unwrap();
 
     let top = store.top_words(3).unwrap();
@@ -640,6 +1777,9 @@ fn test_forget_episode() {
     let after = store.list_episodes().unwrap();
     assert_eq!(after.len(), 1);
     assert!(after[0].is_conscious);
+    // ...and its neighborhoods and occurrences cascaded with it
+    assert!(store.integrity_problems().unwrap().is_empty());
+    assert_eq!(store.list_neighborhoods().unwrap().len(), 1);
 }
 
 #[test]
@@ -654,6 +1794,34 @@ fn test_forget_episode_not_found() {
     assert_eq!(removed, 0);
 }
 
+#[test]
+fn test_rename_episode_persists() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let episodes = store.list_episodes().unwrap();
+    let sub_ep = episodes.iter().find(|e| !e.is_conscious).unwrap();
+    let conscious_ep = episodes.iter().find(|e| e.is_conscious).unwrap();
+
+    assert!(
+        store
+            .rename_episode(&sub_ep.id, "notes: sqlite, wal")
+            .unwrap()
+    );
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes[0].name, "notes: sqlite, wal");
+
+    // Conscious episode and unknown IDs are left alone
+    assert!(!store.rename_episode(&conscious_ep.id, "renamed").unwrap());
+    assert!(
+        !store
+            .rename_episode("00000000-0000-0000-0000-000000000000", "x")
+            .unwrap()
+    );
+    assert!(store.rename_episode("not-a-uuid", "x").is_err());
+}
+
 #[test]
 fn test_forget_conscious() {
     let store = Store::open_in_memory().unwrap();
@@ -663,11 +1831,14 @@ fn test_forget_conscious() {
     let conscious = store.list_conscious_neighborhoods().unwrap();
     assert!(!conscious.is_empty());
 
+    let before = store.occurrence_count().unwrap();
     let removed = store.forget_conscious(&conscious[0].id).unwrap();
     assert!(removed > 0);
+    assert_eq!(store.occurrence_count().unwrap(), before - removed);
 
     let after = store.list_conscious_neighborhoods().unwrap();
     assert!(after.is_empty());
+    assert!(store.integrity_problems().unwrap().is_empty());
 }
 
 #[test]
@@ -689,20 +1860,154 @@ fn test_forget_conscious_rejects_subconscious() {
 }
 
 #[test]
-fn test_forget_term() {
+fn test_forget_neighborhoods_drops_emptied_episodes_and_undoes() {
     let store = Store::open_in_memory().unwrap();
     let sys = make_system();
     store.save_system(&sys).unwrap();
 
+    let ids: Vec<Uuid> = store
+        .list_neighborhoods()
+        .unwrap()
+        .iter()
+        .map(|n| Uuid::parse_str(&n.id).unwrap())
+        .collect();
+    let conscious = sys.conscious_episode.neighborhoods[0].id;
     let before = store.occurrence_count().unwrap();
-    let (removed_occs, _, _) = store.forget_term("hello").unwrap();
-    assert!(removed_occs > 0);
-    assert!(store.occurrence_count().unwrap() < before);
-}
 
-#[test]
-fn test_forget_term_not_found() {
-    let store = Store::open_in_memory().unwrap();
+    let report = store.forget_neighborhoods(&ids, "query \"x\"").unwrap();
+    assert_eq!(report.neighborhoods, 2);
+    assert_eq!(report.occurrences, before);
+    // The emptied subconscious episode goes; the conscious one stays
+    assert_eq!(report.episodes, 1);
+    assert_eq!(report.conscious, vec![conscious]);
+    let episodes = store.list_episodes().unwrap();
+    assert_eq!(episodes.len(), 1);
+    assert!(episodes[0].is_conscious);
+    assert!(store.integrity_problems().unwrap().is_empty());
+
+    // Unknown IDs remove nothing and leave no undo point behind
+    let none = store
+        .forget_neighborhoods(&[Uuid::new_v4()], "none")
+        .unwrap();
+    assert_eq!(none.neighborhoods, 0);
+
+    store.undo_last().unwrap().expect("an undo point");
+    assert_eq!(store.occurrence_count().unwrap(), before);
+    assert_eq!(store.list_episodes().unwrap().len(), 2);
+}
+
+#[test]
+fn test_forget_quarantines_until_restored() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    let before = store.occurrence_count().unwrap();
+
+    let sub_ep = store
+        .list_episodes()
+        .unwrap()
+        .into_iter()
+        .find(|e| !e.is_conscious)
+        .unwrap();
+    let removed = store.forget_episode(&sub_ep.id).unwrap();
+
+    // Out of the memory tables, so loads never see it
+    assert_eq!(store.load_system().unwrap().episodes.len(), 0);
+    let entries = store.quarantine_entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].detail, format!("episode {}", sub_ep.id));
+    assert_eq!((entries[0].episodes, entries[0].occurrences), (1, removed));
+
+    let report = store.restore_forgotten(&[entries[0].id, 999]).unwrap();
+    assert_eq!(report.entries, entries);
+    assert_eq!((report.occurrences, report.skipped), (removed, 0));
+    assert_eq!(store.occurrence_count().unwrap(), before);
+    assert_eq!(store.load_system().unwrap().episodes.len(), 1);
+    // Restored once: neither quarantine nor undo can bring it back again
+    assert!(store.quarantine_entries().unwrap().is_empty());
+    assert!(store.undo_entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_undo_takes_the_forget_out_of_quarantine() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+
+    store.forget_term("hello").unwrap();
+    assert_eq!(store.quarantine_entries().unwrap().len(), 1);
+    store.undo_last().unwrap().expect("an undo point");
+    assert!(store.quarantine_entries().unwrap().is_empty());
+
+    // Without a window forgets delete outright
+    let store = Store::open_in_memory().unwrap().with_quarantine_days(0);
+    store.save_system(&make_system()).unwrap();
+    store.forget_term("hello").unwrap();
+    assert!(store.quarantine_entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_purge_quarantine_after_window() {
+    let start = 1_700_000_000;
+    let store = Store::open_in_memory().unwrap().with_quarantine_days(30);
+    store.save_system(&make_system()).unwrap();
+    {
+        let _clock = am_core::time::freeze_clock(start);
+        store.forget_term("hello").unwrap();
+    }
+
+    {
+        let _clock = am_core::time::freeze_clock(start + 29 * 86_400);
+        assert_eq!(store.purge_quarantine().unwrap(), 0);
+    }
+    let _clock = am_core::time::freeze_clock(start + 31 * 86_400);
+    assert_eq!(store.purge_quarantine().unwrap(), 1);
+    assert!(store.quarantine_entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_for_each_position_walks_every_occurrence_in_id_order() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let mut seen = Vec::new();
+    let count = store
+        .for_each_position(|row| {
+            seen.push(row.clone());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(count, store.occurrence_count().unwrap());
+    assert!(seen.windows(2).all(|w| w[0].id < w[1].id));
+    let hello = seen.iter().find(|r| r.word == "hello").unwrap();
+    assert_eq!(hello.episode, "episode-1");
+    let stored = &sys.episodes[0].neighborhoods[0].occurrences[0];
+    assert_eq!(hello.position, stored.position);
+
+    // An error from the callback stops the walk
+    let mut calls = 0;
+    let err = store.for_each_position(|_| {
+        calls += 1;
+        Err(StoreError::InvalidData("stop".into()))
+    });
+    assert!(err.is_err());
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_forget_term() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let before = store.occurrence_count().unwrap();
+    let (removed_occs, _, _) = store.forget_term("hello").unwrap();
+    assert!(removed_occs > 0);
+    assert!(store.occurrence_count().unwrap() < before);
+}
+
+#[test]
+fn test_forget_term_not_found() {
+    let store = Store::open_in_memory().unwrap();
     let sys = make_system();
     store.save_system(&sys).unwrap();
 
@@ -710,6 +2015,68 @@ fn test_forget_term_not_found() {
     assert_eq!(removed, 0);
 }
 
+#[test]
+fn test_token_totals_match_source_text() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = make_system();
+    let mut ep = Episode::new("episode-2");
+    for text in ["lonely orchid blooms", "orchid greenhouse humidity notes"] {
+        let tokens: Vec<String> = text.split(' ').map(str::to_string).collect();
+        ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, text, &mut rng));
+    }
+    sys.add_episode(ep);
+    store.save_system(&sys).unwrap();
+
+    let recomputed = |store: &Store| -> Vec<(String, u64)> {
+        let loaded = store.load_system().unwrap();
+        std::iter::once(&loaded.conscious_episode)
+            .chain(&loaded.episodes)
+            .map(|ep| (ep.id.to_string(), ep.llm_tokens() as u64))
+            .collect()
+    };
+    let stored = |store: &Store| -> Vec<(String, u64)> {
+        let mut infos: Vec<(String, u64)> = store
+            .list_episodes()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.id, e.llm_tokens))
+            .collect();
+        infos.sort();
+        infos
+    };
+    let mut expected = recomputed(&store);
+    expected.sort();
+    assert_eq!(stored(&store), expected);
+    let detail_total: u64 = store
+        .list_neighborhoods()
+        .unwrap()
+        .iter()
+        .map(|n| n.llm_tokens)
+        .sum();
+    assert_eq!(detail_total, expected.iter().map(|(_, t)| t).sum::<u64>());
+
+    // Forgetting every word of the first neighborhood drops it, and
+    // episode-2's total with it
+    let (_, removed_nbhds, _) = store.forget_term("lonely").unwrap();
+    let (_, removed_more, _) = store.forget_term("blooms").unwrap();
+    let (_, removed_last, _) = store.forget_term("orchid").unwrap();
+    assert_eq!(removed_nbhds + removed_more + removed_last, 1);
+    let mut expected = recomputed(&store);
+    expected.sort();
+    assert_eq!(stored(&store), expected);
+    let episode_2 = store
+        .list_episodes()
+        .unwrap()
+        .into_iter()
+        .find(|e| e.name == "episode-2")
+        .unwrap();
+    assert_eq!(
+        episode_2.llm_tokens,
+        am_core::compose::estimate_llm_tokens("orchid greenhouse humidity notes") as u64
+    );
+}
+
 #[test]
 fn test_drain_buffer_idempotent() {
     let store = Store::open_in_memory().unwrap();
@@ -718,14 +2085,114 @@ fn test_drain_buffer_idempotent() {
 
     let first = store.drain_buffer().unwrap();
     assert_eq!(first.len(), 2);
-    assert_eq!(first[0], ("hello".to_string(), "world".to_string()));
-    assert_eq!(first[1], ("foo".to_string(), "bar".to_string()));
+    assert_eq!(
+        (first[0].user.as_str(), first[0].assistant.as_str()),
+        ("hello", "world")
+    );
+    assert_eq!(
+        (first[1].user.as_str(), first[1].assistant.as_str()),
+        ("foo", "bar")
+    );
 
     // Second drain returns empty: rows were deleted atomically
     let second = store.drain_buffer().unwrap();
     assert!(second.is_empty(), "second drain should return empty");
 }
 
+#[test]
+fn test_episode_listing_sorts() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    // (name, timestamp, words): imported out of date order
+    for (name, timestamp, words) in [
+        ("beta", "2026-03-02T00:00:00Z", &["one", "two", "three"][..]),
+        ("Alpha", "2026-03-01T00:00:00Z", &["one"][..]),
+        ("gamma", "2026-03-03T00:00:00Z", &["one", "two"][..]),
+    ] {
+        let mut ep = Episode::new(name);
+        ep.timestamp = timestamp.to_string();
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(words),
+            None,
+            &words.join(" "),
+            &mut rng,
+        ));
+        sys.add_episode(ep);
+    }
+    store.save_system(&sys).unwrap();
+    let alpha_occ = sys.episodes[1].neighborhoods[0].occurrences[0].id;
+    store.increment_activations(&[(alpha_occ, 9)]).unwrap();
+
+    let names = |sort| -> Vec<String> {
+        store
+            .list_subconscious_episodes_page(PageRequest::ALL, sort)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|e| e.name)
+            .collect()
+    };
+    assert_eq!(names(EpisodeSort::Recent), ["gamma", "beta", "Alpha"]);
+    assert_eq!(names(EpisodeSort::Name), ["Alpha", "beta", "gamma"]);
+    assert_eq!(names(EpisodeSort::Size), ["beta", "gamma", "Alpha"]);
+    assert_eq!(names(EpisodeSort::Activation), ["Alpha", "gamma", "beta"]);
+
+    let all: Vec<String> = store
+        .list_episodes()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    assert_eq!(all[1..], ["gamma", "beta", "Alpha"]);
+}
+
+#[test]
+fn test_drain_buffer_carries_buffered_at() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .append_buffer_at("yesterday", "reply", "2026-05-01T09:30:00Z")
+        .unwrap();
+    // A row written before append_buffer stamped created_at itself
+    store
+        .conn
+        .execute(
+            "INSERT INTO conversation_buffer (user_text, assistant_text, created_at)
+             VALUES ('legacy', 'reply', '2026-05-01 10:00:00')",
+            [],
+        )
+        .unwrap();
+    store.append_buffer("now", "reply").unwrap();
+
+    let listed = store.list_buffer().unwrap();
+    assert_eq!(listed[1].created_at, "2026-05-01T10:00:00Z");
+
+    let drained = store.drain_buffer().unwrap();
+    assert_eq!(drained[0].buffered_at, "2026-05-01T09:30:00Z");
+    assert_eq!(drained[1].buffered_at, "2026-05-01T10:00:00Z");
+    assert!(drained[2].buffered_at.ends_with('Z'));
+    assert!(drained[2].buffered_at > drained[1].buffered_at);
+}
+
+#[test]
+fn test_list_buffer_does_not_drain() {
+    let store = Store::open_in_memory().unwrap();
+    store.append_buffer("hello", "world").unwrap();
+    store.append_buffer("foo", "bar").unwrap();
+
+    let listed = store.list_buffer().unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].user_text, "hello");
+    assert_eq!(listed[1].assistant_text, "bar");
+    assert!(listed[0].id < listed[1].id);
+    assert!(!listed[0].created_at.is_empty());
+    assert_eq!(store.buffer_count().unwrap(), 2);
+
+    assert_eq!(store.clear_buffer().unwrap(), 2);
+    assert!(store.list_buffer().unwrap().is_empty());
+    assert_eq!(store.clear_buffer().unwrap(), 0);
+}
+
 // --- Tests for ALP-1645: 7 untested store methods ---
 
 #[test]
@@ -752,6 +2219,87 @@ fn test_open_file_based() {
     }
 }
 
+fn pragma_i64(store: &Store, name: &str) -> i64 {
+    store
+        .conn
+        .query_row(&format!("PRAGMA {name}"), [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn test_open_sets_default_pragmas() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::open(&dir.path().join("brain.db")).unwrap();
+
+    let mode: String = store
+        .conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(mode, "wal");
+    assert_eq!(pragma_i64(&store, "synchronous"), 1, "NORMAL");
+    assert_eq!(pragma_i64(&store, "busy_timeout"), 5000);
+    assert_eq!(pragma_i64(&store, "foreign_keys"), 1);
+    assert!(!store.is_read_only());
+}
+
+#[test]
+fn test_open_with_applies_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = StoreOptions {
+        busy_timeout_ms: 250,
+        synchronous: Synchronous::Full,
+        wal: false,
+        read_only: false,
+    };
+    let store = Store::open_with(&dir.path().join("brain.db"), &options).unwrap();
+
+    let mode: String = store
+        .conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(mode, "delete");
+    assert_eq!(pragma_i64(&store, "synchronous"), 2, "FULL");
+    assert_eq!(pragma_i64(&store, "busy_timeout"), 250);
+}
+
+#[test]
+fn test_open_readonly_reads_but_rejects_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("brain.db");
+    let writer = Store::open(&db_path).unwrap();
+    writer.save_system(&make_system()).unwrap();
+
+    let reader = Store::open_readonly(&db_path).unwrap();
+    assert!(reader.is_read_only());
+    assert_eq!(pragma_i64(&reader, "busy_timeout"), 5000);
+    assert_eq!(reader.load_system().unwrap().episodes.len(), 1);
+    let err = reader.set_metadata("k", "v").unwrap_err();
+    assert!(err.to_string().contains("readonly"), "{err}");
+
+    // The writer is never blocked by the reader.
+    writer.set_metadata("k", "v").unwrap();
+}
+
+#[test]
+fn test_open_readonly_initializes_missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("brain.db");
+
+    let store = Store::open_readonly(&db_path).unwrap();
+    assert!(db_path.exists());
+    assert!(store.load_system().unwrap().episodes.is_empty());
+}
+
+#[test]
+fn test_synchronous_parses_names_and_levels() {
+    assert_eq!(
+        "NORMAL".parse::<Synchronous>().unwrap(),
+        Synchronous::Normal
+    );
+    assert_eq!("2".parse::<Synchronous>().unwrap(), Synchronous::Full);
+    assert!("sometimes".parse::<Synchronous>().is_err());
+}
+
 #[test]
 fn test_checkpoint_truncate() {
     let store = Store::open_in_memory().unwrap();
@@ -862,6 +2410,28 @@ fn test_mark_superseded_not_found() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_restamp_neighborhood() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+
+    let id = sys.episodes[0].neighborhoods[0].id;
+    store
+        .restamp_neighborhood(id, "2030-01-02T03:04:05Z")
+        .unwrap();
+    let reloaded = store.load_system().unwrap();
+    assert_eq!(
+        reloaded.episodes[0].neighborhoods[0].created_at.as_deref(),
+        Some("2030-01-02T03:04:05Z")
+    );
+    assert!(
+        store
+            .restamp_neighborhood(Uuid::new_v4(), "2030-01-02T03:04:05Z")
+            .is_err()
+    );
+}
+
 #[test]
 fn test_gc_eligible_count() {
     let store = Store::open_in_memory().unwrap();
@@ -955,9 +2525,9 @@ fn test_drain_buffer_atomicity_no_lost_rows() {
     );
 
     // Verify exact content and ordering
-    for (i, (user, asst)) in drained.iter().enumerate() {
-        assert_eq!(user, &format!("user_{i}"));
-        assert_eq!(asst, &format!("asst_{i}"));
+    for (i, exchange) in drained.iter().enumerate() {
+        assert_eq!(exchange.user, format!("user_{i}"));
+        assert_eq!(exchange.assistant, format!("asst_{i}"));
     }
 
     // Phase 2: interleave appends and drains
@@ -980,6 +2550,1014 @@ fn test_drain_buffer_atomicity_no_lost_rows() {
 
     let batch2 = store.drain_buffer().unwrap();
     assert_eq!(batch2.len(), 1, "only the newly appended row should appear");
-    assert_eq!(batch2[0], ("c".to_string(), "3".to_string()));
+    assert_eq!(
+        (batch2[0].user.as_str(), batch2[0].assistant.as_str()),
+        ("c", "3")
+    );
     assert_eq!(store.buffer_count().unwrap(), 0);
 }
+
+#[test]
+fn test_word_weights_match_system() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    let mut ep = Episode::new("weights");
+    for i in 0..4 {
+        let unique = format!("unique{i}");
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &to_tokens(&["cache", "cache", &unique]),
+            None,
+            "cache cache",
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    sys.add_to_conscious("cache invalidation", &mut rng);
+    store.save_system(&sys).unwrap();
+    store.set_metadata("word_weight_floor", "0.25").unwrap();
+    store.set_word_bias("invalidation", 4.0).unwrap();
+
+    let words = to_tokens(&["Cache", "unique0", "invalidation", "missing"]);
+    let weights = store.word_weights(&words).unwrap();
+    assert_eq!(weights.len(), 4);
+    assert_eq!(weights[0].word, "cache");
+    assert_eq!(weights[0].neighborhood_count, 5);
+    assert_eq!(weights[0].occurrence_count, 9);
+    assert_eq!(weights[3].neighborhood_count, 0);
+    assert_eq!(weights[3].occurrence_count, 0);
+    assert!((weights[2].bias - 4.0).abs() < 1e-12);
+
+    let mut loaded = store.load_system().unwrap();
+    for w in &weights {
+        let expected = loaded.get_word_weight(&w.word);
+        assert!(
+            (w.weight - expected).abs() < 1e-12,
+            "{}: store {} vs system {expected}",
+            w.word,
+            w.weight
+        );
+    }
+    // The floor applies to common words
+    assert!((weights[0].weight - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn test_list_neighborhoods_reports_drift_growing_with_recall() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    sys.add_episode(am_core::tokenizer::ingest_text(
+        "Cache invalidation breaks builds. Stale cache entries hide bugs. \
+         Builds rerun when the cache misses. Bugs hide in stale builds.",
+        Some("notes"),
+        &mut rng,
+    ));
+    store.save_system(&sys).unwrap();
+    for nbhd in store.list_neighborhoods().unwrap() {
+        assert_eq!((nbhd.mean_drift, nbhd.max_drift), (0.0, 0.0));
+    }
+
+    for _ in 0..5 {
+        am_core::query::QueryEngine::process_query(&mut sys, "stale cache builds bugs");
+    }
+    store.save_system(&sys).unwrap();
+    let listed = store.list_neighborhoods().unwrap();
+    let max = listed.iter().map(|n| n.max_drift).fold(0.0, f64::max);
+    assert!(max > 0.0, "recall should drift occurrences");
+    for nbhd in &listed {
+        let id: Uuid = nbhd.id.parse().unwrap();
+        let in_memory = sys.episodes[0]
+            .neighborhoods
+            .iter()
+            .find(|n| n.id == id)
+            .unwrap()
+            .drift();
+        assert!((nbhd.mean_drift - in_memory.0).abs() < 1e-9);
+        assert!((nbhd.max_drift - in_memory.1).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_word_stats_attributes_flooding_words_to_their_episode() {
+    let store = Store::open_in_memory().unwrap();
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    let mut notes = Episode::new("notes");
+    for text in [
+        "cache invalidation error",
+        "sqlite wal mode",
+        "retry budget",
+    ] {
+        let tokens: Vec<String> = text.split(' ').map(str::to_string).collect();
+        notes.add_neighborhood(Neighborhood::from_tokens(&tokens, None, text, &mut rng));
+    }
+    sys.add_episode(notes);
+    let mut log = Episode::new("build-log");
+    for i in 0..6 {
+        let tokens = to_tokens(&["error", "timeout", &format!("line{i}")]);
+        log.add_neighborhood(Neighborhood::from_tokens(&tokens, None, "log", &mut rng));
+    }
+    sys.add_episode(log);
+    let mut archived = Episode::new("archived");
+    for _ in 0..8 {
+        let tokens = to_tokens(&["archived"]);
+        archived.add_neighborhood(Neighborhood::from_tokens(&tokens, None, "old", &mut rng));
+    }
+    let archived_id = archived.id.to_string();
+    sys.add_episode(archived);
+    store.save_system(&sys).unwrap();
+    store
+        .conn
+        .execute(
+            "UPDATE episodes SET archived = 1 WHERE id = ?1",
+            [&archived_id],
+        )
+        .unwrap();
+
+    let stats = store.word_stats(2).unwrap();
+    assert_eq!(stats.total_neighborhoods, 9);
+    let words: Vec<&str> = stats.words.iter().map(|w| w.word.as_str()).collect();
+    assert_eq!(words, ["error", "timeout"]);
+    let error = &stats.words[0];
+    assert_eq!(error.neighborhood_count, 7);
+    assert!((error.share(stats.total_neighborhoods) - 7.0 / 9.0).abs() < 1e-12);
+    assert_eq!(error.sources.len(), 2);
+    assert_eq!(error.sources[0].episode_name, "build-log");
+    assert_eq!(error.sources[0].neighborhood_count, 6);
+    assert_eq!(error.sources[1].episode_name, "notes");
+}
+
+#[test]
+fn test_integrity_problems_reports_orphaned_rows() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    assert_eq!(store.integrity_problems().unwrap(), Vec::<String>::new());
+
+    store
+        .conn
+        .execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             UPDATE occurrences SET neighborhood_id = 'gone' WHERE word = 'hello';
+             PRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+    let problems = store.integrity_problems().unwrap();
+    assert_eq!(problems.len(), 1, "{problems:?}");
+    assert!(problems[0].starts_with("occurrences row "));
+    assert!(problems[0].ends_with("references a missing neighborhoods row"));
+}
+
+#[test]
+fn test_load_repairs_invalid_geometry() {
+    let store = Store::open_in_memory().unwrap().with_strict_load(false);
+    store.save_system(&make_system()).unwrap();
+
+    // Simulate rows written by a buggy build
+    store
+        .conn
+        .execute(
+            "UPDATE occurrences SET pos_w = pos_w * 3.0, pos_x = pos_x * 3.0,
+                 pos_y = pos_y * 3.0, pos_z = pos_z * 3.0
+             WHERE word = 'hello'",
+            [],
+        )
+        .unwrap();
+    store
+        .conn
+        .execute(
+            "UPDATE occurrences SET phasor_theta = -1.0 WHERE word = 'world'",
+            [],
+        )
+        .unwrap();
+    store
+        .conn
+        .execute(
+            "UPDATE occurrences SET phasor_theta = 20.0 WHERE word = 'test'",
+            [],
+        )
+        .unwrap();
+    assert_eq!(store.repaired_records(), 0);
+
+    let loaded = store.load_system().unwrap();
+    assert_eq!(store.repaired_records(), 3);
+
+    let all = loaded
+        .episodes
+        .iter()
+        .chain(std::iter::once(&loaded.conscious_episode))
+        .flat_map(|e| &e.neighborhoods);
+    for nbhd in all {
+        let s = nbhd.seed;
+        assert!(((s.w * s.w + s.x * s.x + s.y * s.y + s.z * s.z).sqrt() - 1.0).abs() < 1e-10);
+        for occ in &nbhd.occurrences {
+            let q = occ.position;
+            let norm = (q.w * q.w + q.x * q.x + q.y * q.y + q.z * q.z).sqrt();
+            assert!((norm - 1.0).abs() < 1e-10, "{} norm {norm}", occ.word);
+            assert!(
+                (0.0..std::f64::consts::TAU).contains(&occ.phasor.theta),
+                "{} theta {}",
+                occ.word,
+                occ.phasor.theta
+            );
+        }
+    }
+
+    // The by-word path repairs the same way and adds to the running total
+    let hello = store.get_occurrences_by_word("hello").unwrap();
+    assert_eq!(hello.len(), 1);
+    assert_eq!(store.repaired_records(), 4);
+
+    // A valid database loads without repairs
+    let clean = Store::open_in_memory().unwrap().with_strict_load(false);
+    clean.save_system(&make_system()).unwrap();
+    clean.load_system().unwrap();
+    assert_eq!(clean.repaired_records(), 0);
+}
+
+#[test]
+fn test_strict_load_rejects_invalid_geometry() {
+    let store = Store::open_in_memory().unwrap().with_strict_load(true);
+    store.save_system(&make_system()).unwrap();
+    store.load_system().unwrap();
+
+    store
+        .conn
+        .execute(
+            "UPDATE neighborhoods SET seed_w = 0, seed_x = 0, seed_y = 0, seed_z = 0",
+            [],
+        )
+        .unwrap();
+    let Err(err) = store.load_system() else {
+        panic!("strict load should reject a zero seed");
+    };
+    assert!(matches!(err, StoreError::InvalidData(_)), "{err}");
+    assert!(err.to_string().contains("quaternion norm"), "{err}");
+    assert_eq!(store.repaired_records(), 0);
+}
+
+fn brain_system(agent: &str, word: &str, salient: &str) -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new(agent);
+    let mut ep = Episode::new(&format!("{word}-episode"));
+    let tokens = to_tokens(&[word, "shared", "memory"]);
+    let text = tokens.join(" ");
+    ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, &text, &mut rng));
+    sys.add_episode(ep);
+    sys.add_to_conscious(salient, &mut rng);
+    sys
+}
+
+#[test]
+fn test_brains_sharing_a_file_never_see_each_other() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("brain.db");
+    let alice = Store::open(&db_path).unwrap().with_brain("alice").unwrap();
+    let bob = Store::open(&db_path).unwrap().with_brain("bob").unwrap();
+    assert_eq!(alice.brain(), "alice");
+
+    alice
+        .save_system(&brain_system("alice-agent", "alpha", "alice prefers tabs"))
+        .unwrap();
+    let bob_sys = brain_system("bob-agent", "bravo", "bob prefers spaces");
+    bob.save_system(&bob_sys).unwrap();
+    let bob_episode = bob_sys.episodes[0].id.to_string();
+
+    // Load: each brain gets its own episodes, conscious memory, and metadata
+    let loaded = alice.load_system().unwrap();
+    assert_eq!(loaded.agent_name, "alice-agent");
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.episodes[0].name, "alpha-episode");
+    let conscious: Vec<&str> = loaded
+        .conscious_episode
+        .neighborhoods
+        .iter()
+        .map(|n| n.source_text.as_str())
+        .collect();
+    assert_eq!(conscious, vec!["alice prefers tabs"]);
+
+    // Query paths and stats only count the brain's own rows
+    assert!(alice.get_occurrences_by_word("bravo").unwrap().is_empty());
+    assert_eq!(alice.get_occurrences_by_word("shared").unwrap().len(), 1);
+    assert_eq!(
+        alice.word_weights(&to_tokens(&["shared"])).unwrap()[0].neighborhood_count,
+        1
+    );
+    assert_eq!(alice.occurrence_count().unwrap(), 3 + 3);
+    assert_eq!(alice.neighborhood_count().unwrap(), 2);
+    assert_eq!(alice.activation_distribution().unwrap().total, 6);
+    assert_eq!(alice.list_episodes().unwrap().len(), 2);
+    assert_eq!(alice.list_conscious_neighborhoods().unwrap().len(), 1);
+    assert!(
+        alice
+            .word_stats(10)
+            .unwrap()
+            .words
+            .iter()
+            .all(|w| w.word != "bravo")
+    );
+
+    // Buffer
+    alice.append_buffer("hi", "hello").unwrap();
+    assert_eq!(bob.buffer_count().unwrap(), 0);
+    assert!(bob.drain_buffer().unwrap().is_empty());
+    assert_eq!(alice.buffer_count().unwrap(), 1);
+
+    // Forget: another brain's IDs and words are out of reach
+    assert_eq!(alice.forget_episode(&bob_episode).unwrap(), 0);
+    assert_eq!(alice.forget_term("bravo").unwrap(), (0, 0, 0));
+
+    // GC evicts only the brain it runs in
+    let result = alice.gc_pass(u32::MAX, &no_retention()).unwrap();
+    assert_eq!(result.evicted_occurrences, 3);
+    assert_eq!(result.removed_episodes, 1);
+    let after_gc = alice.load_system().unwrap();
+    assert!(after_gc.episodes.is_empty());
+
+    // A full rewrite of one brain leaves the other intact
+    alice.save_system(&after_gc).unwrap();
+    let loaded = bob.load_system().unwrap();
+    assert_eq!(loaded.agent_name, "bob-agent");
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.episodes[0].neighborhoods[0].occurrences.len(), 3);
+    assert_eq!(loaded.conscious_episode.neighborhoods.len(), 1);
+    assert_eq!(bob.get_occurrences_by_word("bravo").unwrap().len(), 1);
+
+    // The default brain sees none of it
+    let default = Store::open(&db_path).unwrap();
+    assert_eq!(default.brain(), DEFAULT_BRAIN);
+    assert_eq!(default.occurrence_count().unwrap(), 0);
+}
+
+#[test]
+fn test_one_export_imports_into_two_brains_of_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("brain.db");
+    let alice = Store::open(&db_path).unwrap().with_brain("alice").unwrap();
+    let bob = Store::open(&db_path).unwrap().with_brain("bob").unwrap();
+    let sys = brain_system("agent", "alpha", "prefers tabs");
+    alice.save_system(&sys).unwrap();
+    let json = alice.export_json_string().unwrap();
+
+    // The same episode, neighborhood, and occurrence IDs, in another brain
+    bob.import_json_str(&json).unwrap();
+    let counts = |store: &Store| -> Vec<(String, u64, u64, u64)> {
+        store
+            .list_episodes()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.id, e.neighborhood_count, e.occurrence_count, e.llm_tokens))
+            .collect()
+    };
+    let before = counts(&alice);
+    assert_eq!(before.len(), 2);
+    assert_eq!(counts(&bob), before);
+    assert_eq!(bob.occurrence_count().unwrap(), 5);
+
+    // An incremental save lands under bob's copy of the episode
+    let mut rng = rng();
+    let episode = &sys.episodes[0];
+    let tokens = to_tokens(&["bravo", "later"]);
+    let extra = Neighborhood::from_tokens(&tokens, None, &tokens.join(" "), &mut rng);
+    bob.save_neighborhood(episode, &extra).unwrap();
+    let id = episode.id.to_string();
+    assert_eq!(bob.episode_neighborhoods(&id).unwrap().len(), 2);
+    assert_eq!(alice.episode_neighborhoods(&id).unwrap().len(), 1);
+
+    // Forgetting and restoring in one brain leaves the other's rows alone
+    assert_eq!(alice.forget_episode(&id).unwrap(), 3);
+    assert_eq!(alice.occurrence_count().unwrap(), 2);
+    assert_eq!(bob.occurrence_count().unwrap(), 7);
+    let entry = alice.quarantine_entries().unwrap()[0].id;
+    let report = alice.restore_forgotten(&[entry]).unwrap();
+    assert_eq!((report.occurrences, report.skipped), (3, 0));
+    assert_eq!(counts(&alice), before);
+
+    // Deleting bob's copy cascades through bob's rows only
+    assert_eq!(bob.forget_episode(&id).unwrap(), 5);
+    assert_eq!(bob.occurrence_count().unwrap(), 2);
+    assert_eq!(alice.occurrence_count().unwrap(), 5);
+    assert!(alice.integrity_problems().unwrap().is_empty());
+}
+
+#[test]
+fn test_tied_recall_order_survives_save_and_load() {
+    use am_core::compose::{BudgetConfig, compose_context_budgeted};
+    use am_core::query::QueryEngine;
+    use am_core::surface::compute_surface;
+
+    const TAGS: [&str; 4] = ["amber", "basalt", "cobalt", "dolomite"];
+    fn recall(sys: &mut DAESystem) -> Vec<String> {
+        let result = QueryEngine::process_query(sys, &TAGS.join(" "));
+        let surface = compute_surface(sys, &result);
+        let budget = BudgetConfig {
+            min_conscious: 0,
+            min_novel: 0,
+            ..BudgetConfig::default()
+        };
+        compose_context_budgeted(sys, &surface, &result, &budget, None)
+            .included
+            .into_iter()
+            .map(|f| f.text)
+            .collect()
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("brain.db");
+    let alice = Store::open(&db_path).unwrap().with_brain("alice").unwrap();
+    let bob = Store::open(&db_path).unwrap().with_brain("bob").unwrap();
+    // Bob's rows already hold the rowids alice's fresh seqs start at
+    bob.save_system(&brain_system("bob-agent", "bravo", "bob prefers spaces"))
+        .unwrap();
+
+    // Each neighborhood matches one equally rare query word, so all tie
+    let mut rng = rng();
+    let mut sys = DAESystem::new("alice-agent");
+    let mut ep = Episode::new("minerals");
+    for tag in TAGS {
+        let tokens = to_tokens(&[tag, "deposit", "survey"]);
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &tokens,
+            None,
+            &tokens.join(" "),
+            &mut rng,
+        ));
+    }
+    for filler in [
+        ["tokio", "runtime"],
+        ["sqlite", "btree"],
+        ["tensor", "kernel"],
+    ] {
+        let tokens = to_tokens(&filler);
+        ep.add_neighborhood(Neighborhood::from_tokens(
+            &tokens,
+            None,
+            &tokens.join(" "),
+            &mut rng,
+        ));
+    }
+    sys.add_episode(ep);
+    alice.save_system(&sys).unwrap();
+
+    let mut loaded = alice.load_system().unwrap();
+    let seqs: Vec<u64> = loaded.episodes[0]
+        .neighborhoods
+        .iter()
+        .map(|n| n.seq)
+        .collect();
+    assert!(seqs.windows(2).all(|w| w[0] < w[1]), "{seqs:?}");
+
+    // A memory added after load numbers above every row, and keeps its
+    // seq as its rowid through the next save
+    let id = loaded.add_to_conscious("alice prefers tabs", &mut rng);
+    let seq_of = |sys: &DAESystem| {
+        sys.conscious_episode
+            .neighborhoods
+            .iter()
+            .find(|n| n.id == id)
+            .unwrap()
+            .seq
+    };
+    let seq = seq_of(&loaded);
+    assert!(seq > *seqs.last().unwrap());
+    alice.save_system(&loaded).unwrap();
+    assert_eq!(seq_of(&alice.load_system().unwrap()), seq);
+
+    let expected = recall(&mut sys);
+    assert_eq!(expected.len(), TAGS.len(), "{expected:?}");
+    for (text, tag) in expected.iter().zip(TAGS) {
+        assert!(text.starts_with(tag), "{text} out of order");
+    }
+    for _ in 0..5 {
+        assert_eq!(recall(&mut alice.load_system().unwrap()), expected);
+    }
+}
+
+#[test]
+fn test_with_brain_rejects_unsafe_names() {
+    for name in ["", "../x", "a b", &"x".repeat(65)] {
+        assert!(
+            Store::open_in_memory().unwrap().with_brain(name).is_err(),
+            "{name:?}"
+        );
+    }
+    assert!(
+        Store::open_in_memory()
+            .unwrap()
+            .with_brain("team-a_2")
+            .is_ok()
+    );
+}
+
+/// Hold an exclusive lock on `path` from another connection, released
+/// after `hold`. Returns once the lock is taken.
+fn hold_lock(path: &std::path::Path, hold: std::time::Duration) -> std::thread::JoinHandle<()> {
+    let path = path.to_path_buf();
+    let (locked, wait) = std::sync::mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        let other = rusqlite::Connection::open(path).unwrap();
+        other.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        locked.send(()).unwrap();
+        std::thread::sleep(hold);
+        other.execute_batch("ROLLBACK").unwrap();
+    });
+    wait.recv().unwrap();
+    handle
+}
+
+static BUSY_RETRIES: AtomicU64 = AtomicU64::new(0);
+
+fn count_busy_retry(_: &BusyRetry) {
+    BUSY_RETRIES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[test]
+fn test_busy_write_retries_until_lock_released() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.db");
+    let store = Store::open(&path).unwrap().with_retry(RetryPolicy {
+        max_attempts: 20,
+        max_wait: std::time::Duration::from_secs(10),
+        base_delay: std::time::Duration::from_millis(20),
+        on_retry: Some(count_busy_retry),
+    });
+    store
+        .set_busy_timeout(std::time::Duration::from_millis(10))
+        .unwrap();
+
+    let lock = hold_lock(&path, std::time::Duration::from_millis(300));
+    let started = std::time::Instant::now();
+    store.save_system(&make_system()).unwrap();
+    let elapsed = started.elapsed();
+    lock.join().unwrap();
+
+    assert!(BUSY_RETRIES.load(std::sync::atomic::Ordering::Relaxed) > 0);
+    assert!(
+        elapsed >= std::time::Duration::from_millis(250),
+        "{elapsed:?}"
+    );
+    assert!(elapsed < std::time::Duration::from_secs(10), "{elapsed:?}");
+    assert_eq!(store.load_system().unwrap().n(), make_system().n());
+}
+
+#[test]
+fn test_busy_write_gives_up_within_bounds() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.db");
+    let store = Store::open(&path).unwrap().with_retry(RetryPolicy {
+        max_attempts: 3,
+        base_delay: std::time::Duration::from_millis(10),
+        ..RetryPolicy::default()
+    });
+    store
+        .set_busy_timeout(std::time::Duration::from_millis(10))
+        .unwrap();
+
+    let lock = hold_lock(&path, std::time::Duration::from_secs(2));
+    let started = std::time::Instant::now();
+    let err = store.set_metadata("agent_name", "blocked").unwrap_err();
+    // Three 10 ms busy waits plus 10-15 ms and 20-30 ms of backoff
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    assert_eq!(err.kind(), StoreErrorKind::Busy);
+
+    let store = store.with_retry(RetryPolicy::none());
+    let started = std::time::Instant::now();
+    assert!(store.set_metadata("agent_name", "blocked").is_err());
+    assert!(started.elapsed() < std::time::Duration::from_millis(500));
+    lock.join().unwrap();
+}
+
+/// Chunk text long enough to compress under a 64-byte threshold.
+fn long_text(topic: &str) -> String {
+    format!("{topic} — the café's naïve résumé repeats itself. ").repeat(20)
+}
+
+/// One subconscious episode holding `texts`, activated `activation` times,
+/// plus a long conscious memory.
+fn make_text_system(texts: &[(&str, String)], activation: u32) -> DAESystem {
+    let mut rng = rng();
+    let mut sys = DAESystem::new("test-agent");
+    for (name, text) in texts {
+        let mut ep = Episode::new(name);
+        let tokens = am_core::tokenizer::tokenize(text);
+        let mut n = Neighborhood::from_tokens(&tokens, None, text, &mut rng);
+        for occ in &mut n.occurrences {
+            occ.activation_count = activation;
+        }
+        ep.add_neighborhood(n);
+        sys.add_episode(ep);
+    }
+    sys.add_to_conscious(&long_text("conscious"), &mut rng);
+    sys
+}
+
+/// (compressed, plain) neighborhood row counts.
+fn compressed_rows(store: &Store) -> (i64, i64) {
+    store
+        .conn
+        .query_row(
+            "SELECT COUNT(source_zip), COUNT(*) - COUNT(source_zip) FROM neighborhoods",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_text_stays_plain_with_compression_off() {
+    let store = Store::open_in_memory().unwrap().with_text_compression(None);
+    let sys = make_text_system(&[("long", long_text("alpha"))], 0);
+    store.save_system(&sys).unwrap();
+
+    assert_eq!(compressed_rows(&store), (0, 2));
+    assert!(store.compress_text(true).is_err());
+    let loaded = store.load_system().unwrap();
+    assert_eq!(
+        loaded.episodes[0].neighborhoods[0].source_text,
+        long_text("alpha")
+    );
+}
+
+#[cfg(feature = "compress-text")]
+#[test]
+fn test_compressed_text_round_trips() {
+    let store = Store::open_in_memory()
+        .unwrap()
+        .with_text_compression(Some(64));
+    let texts = [
+        ("long", long_text("alpha")),
+        ("short", "short plain chunk".to_string()),
+    ];
+    let sys = make_text_system(&texts, 0);
+    store.save_system(&sys).unwrap();
+
+    // The long subconscious chunk is compressed; the short one and the
+    // conscious memory stay plain
+    assert_eq!(compressed_rows(&store), (1, 2));
+    let stored: String = store
+        .conn
+        .query_row(
+            "SELECT n.source_text FROM neighborhoods n
+             JOIN episodes e ON e.id = n.episode_id AND e.brain_id = n.brain_id WHERE e.name = 'long'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(stored, "");
+
+    let loaded = store.load_system().unwrap();
+    for (ep, (_, text)) in loaded.episodes.iter().zip(&texts) {
+        assert_eq!(&ep.neighborhoods[0].source_text, text);
+    }
+    assert_eq!(
+        loaded.conscious_episode.neighborhoods[0].source_text,
+        long_text("conscious")
+    );
+
+    let listed = store.list_neighborhoods().unwrap();
+    assert!(listed.iter().any(|n| n.source_text == texts[0].1));
+
+    let storage = store.text_storage().unwrap();
+    assert_eq!((storage.neighborhoods, storage.compressed), (3, 1));
+    assert!(storage.stored_bytes < storage.text_bytes);
+}
+
+#[cfg(feature = "compress-text")]
+#[test]
+fn test_compress_text_migrates_plain_rows() {
+    let store = Store::open_in_memory().unwrap().with_text_compression(None);
+    let sys = make_text_system(&[("a", long_text("alpha")), ("b", long_text("beta"))], 0);
+    store.save_system(&sys).unwrap();
+    assert_eq!(compressed_rows(&store), (0, 3));
+
+    let store = store.with_text_compression(Some(64));
+    let dry = store.compress_text(false).unwrap();
+    assert_eq!(dry.neighborhoods, 2);
+    assert!(dry.bytes_after < dry.bytes_before);
+    assert_eq!(compressed_rows(&store), (0, 3), "dry run writes nothing");
+
+    assert_eq!(store.compress_text(true).unwrap(), dry);
+    assert_eq!(compressed_rows(&store), (2, 1));
+    assert_eq!(store.compress_text(true).unwrap().neighborhoods, 0);
+
+    // A short chunk saved later stays plain beside the compressed ones
+    let loaded = store.load_system().unwrap();
+    assert_eq!(
+        loaded.episodes[0].neighborhoods[0].source_text,
+        long_text("alpha")
+    );
+    assert_eq!(
+        loaded.episodes[1].neighborhoods[0].source_text,
+        long_text("beta")
+    );
+    let mut ep = Episode::new("c");
+    let text = "a plain chunk saved later";
+    let tokens = am_core::tokenizer::tokenize(text);
+    ep.add_neighborhood(Neighborhood::from_tokens(&tokens, None, text, &mut rng()));
+    store.save_episode(&ep).unwrap();
+    let reloaded = store.load_system().unwrap();
+    let reloaded_texts: Vec<&str> = reloaded
+        .episodes
+        .iter()
+        .map(|e| e.neighborhoods[0].source_text.as_str())
+        .collect();
+    assert_eq!(
+        reloaded_texts,
+        [
+            long_text("alpha").as_str(),
+            long_text("beta").as_str(),
+            text
+        ]
+    );
+}
+
+#[cfg(feature = "compress-text")]
+#[test]
+fn test_gc_works_over_compressed_text() {
+    let store = Store::open_in_memory()
+        .unwrap()
+        .with_text_compression(Some(64));
+    let mut sys = make_text_system(&[("cold", long_text("cold"))], 0);
+    let warm = make_text_system(&[("warm", long_text("warm"))], 5);
+    sys.add_episode(warm.episodes[0].clone());
+    store.save_system(&sys).unwrap();
+    assert_eq!(compressed_rows(&store).0, 2);
+
+    store.gc_pass(0, &no_retention()).unwrap();
+    let loaded = store.load_system().unwrap();
+    assert_eq!(loaded.episodes.len(), 1);
+    assert_eq!(loaded.episodes[0].name, "warm");
+    assert_eq!(
+        loaded.episodes[0].neighborhoods[0].source_text,
+        long_text("warm")
+    );
+    assert_eq!(compressed_rows(&store).0, 1);
+}
+
+fn audit_kinds(store: &Store) -> Vec<AuditKind> {
+    store
+        .audit_log(&AuditFilter::default())
+        .unwrap()
+        .into_iter()
+        .map(|e| e.kind)
+        .collect()
+}
+
+#[test]
+fn test_audit_round_trip_and_filters() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .audit(&AuditEvent::new(AuditKind::Ingest, 3).with_detail("notes"))
+        .unwrap();
+    store
+        .audit(&AuditEvent::new(AuditKind::Salient, 1))
+        .unwrap();
+
+    let all = store.audit_log(&AuditFilter::default()).unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].kind, AuditKind::Salient, "newest first");
+    assert_eq!((all[1].count, all[1].detail.as_str()), (3, "notes"));
+
+    let ingests = store
+        .audit_log(&AuditFilter {
+            kind: Some(AuditKind::Ingest),
+            ..AuditFilter::default()
+        })
+        .unwrap();
+    assert_eq!(ingests.len(), 1);
+    let future = store
+        .audit_log(&AuditFilter {
+            since: Some("2999-01-01T00:00:00Z".into()),
+            ..AuditFilter::default()
+        })
+        .unwrap();
+    assert!(future.is_empty());
+    let newest = store
+        .audit_log(&AuditFilter {
+            limit: Some(1),
+            ..AuditFilter::default()
+        })
+        .unwrap();
+    assert_eq!(newest[0].id, all[0].id);
+}
+
+#[test]
+fn test_audited_failed_write_leaves_no_entry() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+
+    // save_system refuses to overwrite stored data with an empty system
+    let empty = DAESystem::new("test-agent");
+    let event = AuditEvent::new(AuditKind::Import, 0);
+    assert!(store.audited(&event, || store.save_system(&empty)).is_err());
+    assert!(audit_kinds(&store).is_empty());
+
+    // A write that fails after an inner write succeeded rolls both back
+    let before = store.occurrence_count().unwrap();
+    let result: Result<()> = store.audited(&event, || {
+        store.save_system(&make_system())?;
+        Err(StoreError::InvalidData("boom".into()))
+    });
+    assert!(result.is_err());
+    assert!(audit_kinds(&store).is_empty());
+    assert_eq!(store.occurrence_count().unwrap(), before);
+
+    store
+        .audited(&event, || store.save_system(&make_system()))
+        .unwrap();
+    assert_eq!(audit_kinds(&store), [AuditKind::Import]);
+}
+
+#[test]
+fn test_forget_and_gc_are_audited() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    store.forget_term("hello").unwrap();
+    let conscious = store.list_conscious_neighborhoods().unwrap();
+    store.forget_conscious(&conscious[0].id).unwrap();
+
+    let log = store.audit_log(&AuditFilter::default()).unwrap();
+    assert_eq!(log.len(), 2);
+    assert!(log.iter().all(|e| e.kind == AuditKind::Forget));
+    assert_eq!(log[1].detail, "term hello");
+
+    // GC that evicts nothing records nothing
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system_with_activations()).unwrap();
+    store
+        .gc_pass(0, &crate::config::RetentionPolicy::default())
+        .unwrap();
+    assert!(audit_kinds(&store).is_empty());
+    let result = store.gc_pass(0, &no_retention()).unwrap();
+    assert!(result.evicted_occurrences > 0);
+    let log = store.audit_log(&AuditFilter::default()).unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].kind, AuditKind::Gc);
+    assert_eq!(log[0].count, result.evicted_occurrences);
+}
+
+#[test]
+fn test_import_is_audited() {
+    let source = Store::open_in_memory().unwrap();
+    source.save_system(&make_system()).unwrap();
+    let json = source.export_json_string().unwrap();
+
+    let store = Store::open_in_memory().unwrap();
+    store.import_json_str(&json).unwrap();
+    let log = store.audit_log(&AuditFilter::default()).unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].kind, AuditKind::Import);
+    assert!(
+        log[0].detail.starts_with("replace: 1 episodes"),
+        "{}",
+        log[0].detail
+    );
+}
+
+#[test]
+fn test_prune_audit_keeps_newest() {
+    let store = Store::open_in_memory().unwrap();
+    for count in 0..5 {
+        store
+            .audit(&AuditEvent::new(AuditKind::Feedback, count))
+            .unwrap();
+    }
+    assert_eq!(store.prune_audit(2).unwrap(), 3);
+    let counts: Vec<u64> = store
+        .audit_log(&AuditFilter::default())
+        .unwrap()
+        .iter()
+        .map(|e| e.count)
+        .collect();
+    assert_eq!(counts, [4, 3]);
+    assert_eq!(store.prune_audit(2).unwrap(), 0);
+}
+
+fn subconscious_episode(store: &Store) -> String {
+    let episodes = store.list_episodes().unwrap();
+    episodes.into_iter().find(|e| !e.is_conscious).unwrap().id
+}
+
+#[test]
+fn test_undo_forget_term_restores_export() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    let before = store.export_json_string().unwrap();
+
+    assert_eq!(store.forget_term("hello").unwrap(), (1, 0, 0));
+    assert_eq!(store.forget_term("world").unwrap(), (1, 0, 0));
+    assert_eq!(store.forget_term("test").unwrap(), (1, 1, 1));
+    assert_eq!(store.undo_entries().unwrap().len(), 3);
+
+    for _ in 0..3 {
+        let report = store.undo_last().unwrap().expect("an undo point");
+        assert_eq!(report.skipped, 0);
+    }
+    assert!(store.undo_last().unwrap().is_none());
+    assert_eq!(store.export_json_string().unwrap(), before);
+    assert_eq!(audit_kinds(&store)[..3], [AuditKind::Undo; 3]);
+}
+
+#[test]
+fn test_undo_forget_episode_and_conscious() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system()).unwrap();
+    let before = store.export_json_string().unwrap();
+
+    let episode = subconscious_episode(&store);
+    let conscious = store.list_conscious_neighborhoods().unwrap()[0].id.clone();
+    store.forget_episode(&episode).unwrap();
+    store.forget_conscious(&conscious).unwrap();
+
+    let entries = store.undo_entries().unwrap();
+    assert_eq!(entries[0].detail, format!("conscious {conscious}"));
+    assert_eq!(
+        (
+            entries[1].episodes,
+            entries[1].neighborhoods,
+            entries[1].occurrences
+        ),
+        (1, 1, 3)
+    );
+
+    let report = store.undo_last().unwrap().unwrap();
+    assert_eq!((report.neighborhoods, report.occurrences), (1, 2));
+    store.undo_last().unwrap().unwrap();
+    assert_eq!(store.export_json_string().unwrap(), before);
+}
+
+#[test]
+fn test_undo_skips_rows_whose_id_came_back() {
+    let store = Store::open_in_memory().unwrap();
+    let sys = make_system();
+    store.save_system(&sys).unwrap();
+    let episode = subconscious_episode(&store);
+    store.forget_episode(&episode).unwrap();
+
+    // The same episode is saved again before the undo
+    store.save_system(&sys).unwrap();
+    let report = store.undo_last().unwrap().unwrap();
+    assert_eq!(
+        (report.episodes, report.neighborhoods, report.occurrences),
+        (0, 0, 0)
+    );
+    assert_eq!(report.skipped, 5);
+    assert_eq!(store.occurrence_count().unwrap(), 5);
+}
+
+#[test]
+fn test_undo_stack_bounds() {
+    let store = Store::open_in_memory().unwrap().with_undo(UndoPolicy {
+        keep: 2,
+        ..UndoPolicy::default()
+    });
+    store.save_system(&make_system()).unwrap();
+    for word in ["hello", "world", "test"] {
+        store.forget_term(word).unwrap();
+    }
+    let details: Vec<String> = store
+        .undo_entries()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.detail)
+        .collect();
+    assert_eq!(details, ["term test", "term world"]);
+
+    // An entry over the byte budget is not kept, and undo stays audited
+    let store = Store::open_in_memory().unwrap().with_undo(UndoPolicy {
+        keep: 10,
+        max_bytes: 16,
+    });
+    store.save_system(&make_system()).unwrap();
+    store.forget_term("hello").unwrap();
+    assert!(store.undo_entries().unwrap().is_empty());
+    assert_eq!(audit_kinds(&store), [AuditKind::Forget]);
+
+    let store = Store::open_in_memory().unwrap().with_undo(UndoPolicy {
+        keep: 0,
+        ..UndoPolicy::default()
+    });
+    store.save_system(&make_system()).unwrap();
+    store.forget_term("hello").unwrap();
+    assert!(store.undo_entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_undo_gc_to_target_size() {
+    let store = Store::open_in_memory().unwrap();
+    store.save_system(&make_system_with_activations()).unwrap();
+    let before = store.export_json_string().unwrap();
+
+    let result = store.gc_to_target_size(0, &no_retention()).unwrap();
+    assert!(result.evicted_occurrences > 0);
+    let entry = &store.undo_entries().unwrap()[0];
+    assert_eq!(entry.kind, AuditKind::Gc);
+    assert_eq!(entry.occurrences, result.evicted_occurrences);
+
+    store.undo_last().unwrap().unwrap();
+    assert_eq!(store.export_json_string().unwrap(), before);
+
+    // The cheap floor pass is not undoable
+    store.gc_pass(0, &no_retention()).unwrap();
+    assert!(store.undo_entries().unwrap().is_empty());
+}
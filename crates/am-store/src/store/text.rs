@@ -0,0 +1,192 @@
+//! Compressed chunk text at rest.
+//!
+//! `source_text` dominates the database: every neighborhood keeps its chunk
+//! verbatim. With the `compress-text` feature, the text of subconscious
+//! neighborhoods at least [`Store::with_text_compression`] bytes long is
+//! deflated into `neighborhoods.source_zip` and `source_text` is left empty;
+//! a non-NULL `source_zip` is the flag. Reads inflate it transparently, so
+//! nothing above the store sees the difference. Conscious text always stays
+//! plain so `list_conscious_neighborhoods_page` can search it in SQL.
+//!
+//! New rows are compressed as they are saved; [`Store::compress_text`]
+//! compresses rows written before compression was on.
+
+use rusqlite::params;
+
+use crate::error::{Result, StoreError};
+
+use super::Store;
+
+/// Default for [`Store::with_text_compression`]: text shorter than this
+/// rarely shrinks enough to be worth inflating on every load.
+pub const DEFAULT_COMPRESS_TEXT_MIN_BYTES: usize = 512;
+
+/// What [`Store::compress_text`] compressed, or would compress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCompaction {
+    pub neighborhoods: u64,
+    /// Text bytes of those neighborhoods before and after compression.
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+/// How chunk text is stored, for `am stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStorage {
+    pub neighborhoods: u64,
+    pub compressed: u64,
+    /// Text bytes as the neighborhoods hold them.
+    pub text_bytes: u64,
+    /// Bytes the text takes in the database, compressed blobs included.
+    pub stored_bytes: u64,
+}
+
+/// Deflated `text`, when compression is on, `text` is at least
+/// `min_bytes` long, and the result is actually smaller.
+pub(crate) fn compress(text: &str, min_bytes: Option<usize>) -> Option<Vec<u8>> {
+    let min_bytes = min_bytes?;
+    if text.len() < min_bytes {
+        return None;
+    }
+    deflate(text.as_bytes()).filter(|zip| zip.len() < text.len())
+}
+
+/// The text of a neighborhood row: `source_zip` inflated when set,
+/// otherwise `source_text`.
+pub(crate) fn decode(id: &str, text: String, zip: Option<Vec<u8>>) -> Result<String> {
+    match zip {
+        None => Ok(text),
+        Some(zip) => inflate(id, &zip),
+    }
+}
+
+#[cfg(feature = "compress-text")]
+fn deflate(bytes: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(bytes).ok()?;
+    encoder.finish().ok()
+}
+
+#[cfg(not(feature = "compress-text"))]
+fn deflate(_bytes: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(feature = "compress-text")]
+fn inflate(id: &str, zip: &[u8]) -> Result<String> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    flate2::read::DeflateDecoder::new(zip)
+        .read_to_string(&mut text)
+        .map_err(|e| {
+            StoreError::InvalidData(format!("neighborhood {id}: corrupt compressed text: {e}"))
+        })?;
+    Ok(text)
+}
+
+#[cfg(not(feature = "compress-text"))]
+fn inflate(id: &str, _zip: &[u8]) -> Result<String> {
+    Err(StoreError::InvalidData(format!(
+        "neighborhood {id}: text is compressed, but am-store was built without \
+         the compress-text feature"
+    )))
+}
+
+impl Store {
+    /// Compress the text of subconscious neighborhoods at least `min_bytes`
+    /// long as they are saved; `None` stores all text plain. Defaults to
+    /// [`DEFAULT_COMPRESS_TEXT_MIN_BYTES`], and does nothing without the
+    /// `compress-text` feature.
+    #[must_use]
+    pub fn with_text_compression(mut self, min_bytes: Option<usize>) -> Self {
+        self.compress_min = min_bytes;
+        self
+    }
+
+    /// Compress the text of stored subconscious neighborhoods that are
+    /// plain but long enough to compress, then VACUUM so the database
+    /// shrinks. With `apply` false, only reports what would change.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` when compression is off or the store was built
+    /// without the `compress-text` feature.
+    pub fn compress_text(&self, apply: bool) -> Result<TextCompaction> {
+        if !cfg!(feature = "compress-text") {
+            return Err(StoreError::InvalidData(
+                "am-store was built without the compress-text feature".into(),
+            ));
+        }
+        let Some(min_bytes) = self.compress_min else {
+            return Err(StoreError::InvalidData(
+                "text compression is off (compress_text_min_bytes = 0)".into(),
+            ));
+        };
+
+        let compaction = self.retry_busy("compress_text", || {
+            let tx = self.transaction()?;
+            let mut compaction = TextCompaction::default();
+            {
+                let mut stmt = tx.prepare(
+                    "SELECT n.id, n.source_text FROM neighborhoods n
+                     JOIN episodes e ON e.id = n.episode_id AND e.brain_id = n.brain_id
+                     WHERE e.is_conscious = 0 AND n.brain_id = ?1
+                       AND n.source_zip IS NULL
+                       AND length(CAST(n.source_text AS BLOB)) >= ?2",
+                )?;
+                let rows = stmt
+                    .query_map(params![self.brain, min_bytes as i64], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                let mut update = tx.prepare(
+                    "UPDATE neighborhoods SET source_text = '', source_zip = ?2 WHERE id = ?1 AND brain_id = ?3",
+                )?;
+                for (id, text) in rows {
+                    let Some(zip) = compress(&text, Some(min_bytes)) else {
+                        continue;
+                    };
+                    compaction.neighborhoods += 1;
+                    compaction.bytes_before += text.len() as u64;
+                    compaction.bytes_after += zip.len() as u64;
+                    if apply {
+                        update.execute(params![id, zip, self.brain])?;
+                    }
+                }
+            }
+            if apply {
+                tx.commit()?;
+            }
+            Ok(compaction)
+        })?;
+
+        if apply && compaction.neighborhoods > 0 {
+            // VACUUM to reclaim disk space (must run outside transaction)
+            let _ = self.conn.execute_batch("VACUUM;");
+        }
+        Ok(compaction)
+    }
+
+    /// How much text this brain stores and how much of it is compressed.
+    pub fn text_storage(&self) -> Result<TextStorage> {
+        let mut storage = TextStorage::default();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, source_text, source_zip FROM neighborhoods WHERE brain_id = ?1")?;
+        let mut rows = stmt.query([&self.brain])?;
+        while let Some(row) = rows.next()? {
+            let text: String = row.get(1)?;
+            let zip: Option<Vec<u8>> = row.get(2)?;
+            storage.neighborhoods += 1;
+            storage.stored_bytes += (text.len() + zip.as_ref().map_or(0, Vec::len)) as u64;
+            if zip.is_some() {
+                storage.compressed += 1;
+            }
+            storage.text_bytes += decode(&row.get::<_, String>(0)?, text, zip)?.len() as u64;
+        }
+        Ok(storage)
+    }
+}
@@ -0,0 +1,95 @@
+//! Write transactions that nest.
+//!
+//! Every write entry point runs in its own transaction, so each one is
+//! atomic on its own. [`Store::audited`] groups several of them with their
+//! audit row into one, and SQLite cannot `BEGIN` inside a transaction: a
+//! [`Tx`] opened while one is already open becomes a savepoint instead.
+//! Committing a nested `Tx` releases its savepoint into the outer
+//! transaction; dropping it uncommitted rolls back only its own writes.
+
+use std::ops::Deref;
+
+use rusqlite::Connection;
+
+use crate::error::Result;
+
+use super::Store;
+
+const SAVEPOINT: &str = "am_nested";
+
+/// A transaction, or a savepoint inside one, rolled back on drop unless
+/// committed. Derefs to the connection for statements.
+pub(crate) struct Tx<'a> {
+    conn: &'a Connection,
+    nested: bool,
+    open: bool,
+}
+
+impl<'a> Tx<'a> {
+    fn begin(conn: &'a Connection, immediate: bool) -> Result<Self> {
+        let nested = !conn.is_autocommit();
+        let sql = if nested {
+            format!("SAVEPOINT {SAVEPOINT}")
+        } else if immediate {
+            "BEGIN IMMEDIATE".to_string()
+        } else {
+            "BEGIN DEFERRED".to_string()
+        };
+        conn.execute_batch(&sql)?;
+        Ok(Self {
+            conn,
+            nested,
+            open: true,
+        })
+    }
+
+    pub(crate) fn commit(mut self) -> Result<()> {
+        let sql = if self.nested {
+            format!("RELEASE {SAVEPOINT}")
+        } else {
+            "COMMIT".to_string()
+        };
+        self.conn.execute_batch(&sql)?;
+        self.open = false;
+        Ok(())
+    }
+}
+
+impl Deref for Tx<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for Tx<'_> {
+    fn drop(&mut self) {
+        // A failed statement may already have rolled everything back
+        if !self.open || self.conn.is_autocommit() {
+            return;
+        }
+        let sql = if self.nested {
+            format!("ROLLBACK TO {SAVEPOINT}; RELEASE {SAVEPOINT}")
+        } else {
+            "ROLLBACK".to_string()
+        };
+        if let Err(e) = self.conn.execute_batch(&sql) {
+            tracing::warn!("rollback failed: {e}");
+        }
+    }
+}
+
+impl Store {
+    /// Begin a deferred transaction, or a savepoint if one is open.
+    pub(crate) fn transaction(&self) -> Result<Tx<'_>> {
+        Tx::begin(&self.conn, false)
+    }
+
+    /// Begin a transaction that takes the write lock at once, so the
+    /// writes inside it never meet `SQLITE_BUSY` halfway through. A
+    /// savepoint if one is already open.
+    pub(crate) fn immediate_transaction(&self) -> Result<Tx<'_>> {
+        Tx::begin(&self.conn, true)
+    }
+}
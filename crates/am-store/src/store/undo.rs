@@ -0,0 +1,433 @@
+//! Undo for destructive operations.
+//!
+//! `forget_term`, `forget_episode`, `forget_conscious`, and the aggressive
+//! GC pass copy every row they delete into one `undo_stack` entry, in the
+//! same transaction as the delete and its audit row. [`Store::undo_last`]
+//! puts the newest entry's rows back and drops the entry. Rows are kept
+//! column by column as the table had them, so an entry taken before a
+//! schema upgrade still restores into the columns it has.
+//!
+//! The stack keeps the newest [`UndoPolicy::keep`] entries within
+//! [`UndoPolicy::max_bytes`]; older ones are dropped as new ones arrive.
+//! Forgets also keep their rows in quarantine (`super::quarantine`), in
+//! the same serialized form.
+
+use am_core::store_trait::{AuditEvent, AuditKind};
+use am_core::time::now_iso8601;
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::{Connection, OptionalExtension, Params, params, params_from_iter};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, StoreError};
+
+use super::Store;
+
+/// Default for [`UndoPolicy::keep`].
+pub const DEFAULT_UNDO_KEEP: usize = 10;
+
+/// Default for [`UndoPolicy::max_bytes`] (16 MiB).
+pub const DEFAULT_UNDO_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How much the undo stack holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoPolicy {
+    /// Newest entries kept; 0 turns undo off and skips the copying.
+    pub keep: usize,
+    /// Serialized size all entries may take together. An operation that
+    /// deletes more than this on its own cannot be undone.
+    pub max_bytes: u64,
+}
+
+impl Default for UndoPolicy {
+    fn default() -> Self {
+        Self {
+            keep: DEFAULT_UNDO_KEEP,
+            max_bytes: DEFAULT_UNDO_MAX_BYTES,
+        }
+    }
+}
+
+/// One column value, as SQLite stored it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Cell {
+    Null,
+    Int(i64),
+    /// Kept as its bits: a JSON number does not give back every `f64`
+    /// exactly.
+    Real {
+        bits: u64,
+    },
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl From<ValueRef<'_>> for Cell {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => Self::Null,
+            ValueRef::Integer(i) => Self::Int(i),
+            ValueRef::Real(f) => Self::Real { bits: f.to_bits() },
+            ValueRef::Text(t) => Self::Text(String::from_utf8_lossy(t).into_owned()),
+            ValueRef::Blob(b) => Self::Blob(b.to_vec()),
+        }
+    }
+}
+
+impl rusqlite::ToSql for Cell {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            Self::Null => ValueRef::Null,
+            Self::Int(i) => ValueRef::Integer(*i),
+            Self::Real { bits } => ValueRef::Real(f64::from_bits(*bits)),
+            Self::Text(t) => ValueRef::Text(t.as_bytes()),
+            Self::Blob(b) => ValueRef::Blob(b),
+        }))
+    }
+}
+
+/// Deleted rows of one table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RowSet {
+    columns: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl RowSet {
+    /// Run `sql`, a `SELECT rowid, *` or a `DELETE ... RETURNING rowid, *`,
+    /// and keep every row it returns. Returns how many it returned.
+    pub(crate) fn take(
+        &mut self,
+        conn: &Connection,
+        sql: &str,
+        params: impl Params,
+    ) -> Result<u64> {
+        let mut stmt = conn.prepare(sql)?;
+        if self.columns.is_empty() {
+            self.columns = stmt.column_names().into_iter().map(String::from).collect();
+        }
+        let width = self.columns.len();
+        let mut rows = stmt.query(params)?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let cells = (0..width)
+                .map(|i| row.get_ref(i).map(Cell::from))
+                .collect::<rusqlite::Result<_>>()?;
+            self.rows.push(cells);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// [`Self::take`], returning the `id` of each row taken.
+    pub(crate) fn take_ids(
+        &mut self,
+        conn: &Connection,
+        sql: &str,
+        params: impl Params,
+    ) -> Result<Vec<String>> {
+        let start = self.rows.len();
+        self.take(conn, sql, params)?;
+        let id = self.column("id")?;
+        Ok(self.rows[start..]
+            .iter()
+            .filter_map(|row| match &row[id] {
+                Cell::Text(id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn len(&self) -> u64 {
+        self.rows.len() as u64
+    }
+
+    /// Insert the rows into `table`, skipping any whose `id` is taken again
+    /// in their brain or whose `parent_column` names a row no longer in
+    /// `parent_table`.
+    /// A row goes back under its old rowid, so loads list it where it was,
+    /// unless a newer row took that rowid. Returns how many went in.
+    fn restore(&self, conn: &Connection, table: &str, parent: Option<(&str, &str)>) -> Result<u64> {
+        if self.rows.is_empty() {
+            return Ok(0);
+        }
+        let brain = self.column("brain_id")? + 1;
+        let exists = match parent {
+            Some((column, parent_table)) => {
+                let index = self.column(column)? + 1;
+                format!(
+                    " AND EXISTS (SELECT 1 FROM {parent_table} WHERE brain_id = ?{brain} AND id = ?{index})"
+                )
+            }
+            None => String::new(),
+        };
+        let id = self.column("id")? + 1;
+        let insert = |keep_rowid: bool| {
+            let (columns, values): (Vec<_>, Vec<_>) = self
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| keep_rowid || *c != "rowid")
+                .map(|(i, c)| (c.as_str(), format!("?{}", i + 1)))
+                .unzip();
+            format!(
+                "INSERT OR IGNORE INTO {table} ({}) SELECT {}
+                 WHERE NOT EXISTS (SELECT 1 FROM {table} WHERE brain_id = ?{brain} AND id = ?{id}){exists}",
+                columns.join(", "),
+                values.join(", ")
+            )
+        };
+        let mut in_place = conn.prepare(&insert(true))?;
+        let mut appended = conn.prepare(&insert(false))?;
+        let mut restored = 0;
+        for row in &self.rows {
+            let mut changed = in_place.execute(params_from_iter(row))?;
+            if changed == 0 {
+                changed = appended.execute(params_from_iter(row))?;
+            }
+            restored += changed as u64;
+        }
+        Ok(restored)
+    }
+
+    fn column(&self, name: &str) -> Result<usize> {
+        self.columns
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| StoreError::InvalidData(format!("undo entry lacks column {name}")))
+    }
+}
+
+/// Everything one destructive operation deleted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct UndoRows {
+    pub episodes: RowSet,
+    pub neighborhoods: RowSet,
+    pub occurrences: RowSet,
+}
+
+impl UndoRows {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.episodes.rows.is_empty()
+            && self.neighborhoods.rows.is_empty()
+            && self.occurrences.rows.is_empty()
+    }
+
+    /// Rows held per table: episodes, neighborhoods, occurrences.
+    pub(crate) fn counts(&self) -> (u64, u64, u64) {
+        (
+            self.episodes.len(),
+            self.neighborhoods.len(),
+            self.occurrences.len(),
+        )
+    }
+
+    pub(crate) fn to_payload(&self) -> Result<String> {
+        serde_json::to_string(self)
+            .map_err(|e| StoreError::InvalidData(format!("undo entry failed: {e}")))
+    }
+
+    pub(crate) fn from_payload(payload: &str, what: &str) -> Result<Self> {
+        serde_json::from_str(payload)
+            .map_err(|e| StoreError::InvalidData(format!("{what} is unreadable: {e}")))
+    }
+
+    /// Insert the rows back, parents first, skipping any whose ID is in
+    /// use again or whose parent is gone. Returns the episodes,
+    /// neighborhoods, and occurrences that went in.
+    pub(crate) fn restore(&self, conn: &Connection) -> Result<(u64, u64, u64)> {
+        let episodes = self.episodes.restore(conn, "episodes", None)?;
+        let neighborhoods =
+            self.neighborhoods
+                .restore(conn, "neighborhoods", Some(("episode_id", "episodes")))?;
+        let occurrences = self.occurrences.restore(
+            conn,
+            "occurrences",
+            Some(("neighborhood_id", "neighborhoods")),
+        )?;
+        Ok((episodes, neighborhoods, occurrences))
+    }
+}
+
+/// One undo point, as `am undo --list` shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoEntry {
+    pub id: i64,
+    /// ISO-8601 UTC time of the operation.
+    pub at: String,
+    /// The operation: `forget` or `gc`.
+    pub kind: AuditKind,
+    /// The audit log detail of the operation, e.g. `term rust`.
+    pub detail: String,
+    /// Rows restoring it would put back.
+    pub episodes: u64,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+}
+
+/// What [`Store::undo_last`] put back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoReport {
+    pub entry: UndoEntry,
+    pub episodes: u64,
+    pub neighborhoods: u64,
+    pub occurrences: u64,
+    /// Rows left out because their ID is in use again or their parent is
+    /// gone.
+    pub skipped: u64,
+}
+
+impl Store {
+    /// Keep at most `policy` on the undo stack. Defaults to
+    /// [`UndoPolicy::default`].
+    #[must_use]
+    pub fn with_undo(mut self, policy: UndoPolicy) -> Self {
+        self.undo = policy;
+        self
+    }
+
+    /// Whether destructive operations should copy what they delete.
+    pub(crate) fn undo_enabled(&self) -> bool {
+        self.undo.keep > 0
+    }
+
+    /// Audit `event` and push `rows` as its undo entry, inside the
+    /// caller's transaction, then trim the stack to the policy. Returns
+    /// the entry's ID, `None` when nothing was pushed.
+    pub(crate) fn audit_with_undo(
+        &self,
+        conn: &Connection,
+        event: &AuditEvent,
+        rows: &UndoRows,
+    ) -> Result<Option<i64>> {
+        self.audit_on(conn, event)?;
+        if !self.undo_enabled() || rows.is_empty() {
+            return Ok(None);
+        }
+        let payload = rows.to_payload()?;
+        let bytes = payload.len() as u64;
+        if bytes > self.undo.max_bytes {
+            tracing::warn!(
+                "{}: {bytes} bytes deleted exceeds the undo budget; it cannot be undone",
+                event.detail
+            );
+            return Ok(None);
+        }
+        conn.execute(
+            "INSERT INTO undo_stack (brain_id, at, kind, detail, episodes, neighborhoods,
+                                     occurrences, bytes, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                self.brain,
+                now_iso8601(),
+                event.kind.as_str(),
+                event.detail,
+                rows.episodes.len(),
+                rows.neighborhoods.len(),
+                rows.occurrences.len(),
+                bytes,
+                payload,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        let keep = i64::try_from(self.undo.keep).unwrap_or(i64::MAX);
+        conn.execute(
+            "DELETE FROM undo_stack WHERE brain_id = ?1 AND id NOT IN (
+                 SELECT id FROM undo_stack WHERE brain_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![self.brain, keep],
+        )?;
+        conn.execute(
+            "DELETE FROM undo_stack WHERE id IN (
+                 SELECT id FROM (
+                     SELECT id, SUM(bytes) OVER (ORDER BY id DESC) AS running
+                     FROM undo_stack WHERE brain_id = ?1
+                 ) WHERE running > ?2
+             )",
+            params![self.brain, self.undo.max_bytes],
+        )?;
+        Ok(Some(id))
+    }
+
+    /// Undo points of this brain, newest first.
+    pub fn undo_entries(&self) -> Result<Vec<UndoEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, at, kind, detail, episodes, neighborhoods, occurrences
+             FROM undo_stack WHERE brain_id = ?1 ORDER BY id DESC",
+        )?;
+        let rows = stmt
+            .query_map([&self.brain], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, u64>(4)?,
+                    row.get::<_, u64>(5)?,
+                    row.get::<_, u64>(6)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows
+            .into_iter()
+            .filter_map(
+                |(id, at, kind, detail, episodes, neighborhoods, occurrences)| {
+                    Some(UndoEntry {
+                        id,
+                        at,
+                        kind: AuditKind::parse(&kind)?,
+                        detail,
+                        episodes,
+                        neighborhoods,
+                        occurrences,
+                    })
+                },
+            )
+            .collect())
+    }
+
+    /// Put back what the newest undo entry holds and drop the entry.
+    /// Returns `None` when the stack is empty. Audited as `undo`.
+    pub fn undo_last(&self) -> Result<Option<UndoReport>> {
+        self.retry_busy("undo_last", || {
+            let tx = self.immediate_transaction()?;
+            let Some(entry) = self.undo_entries()?.into_iter().next() else {
+                return Ok(None);
+            };
+            let payload: String = tx
+                .query_row(
+                    "SELECT payload FROM undo_stack WHERE id = ?1",
+                    [entry.id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .unwrap_or_default();
+            let rows = UndoRows::from_payload(&payload, &format!("undo entry {}", entry.id))?;
+
+            let (episodes, neighborhoods, occurrences) = rows.restore(&tx)?;
+            let skipped = entry.episodes + entry.neighborhoods + entry.occurrences
+                - episodes
+                - neighborhoods
+                - occurrences;
+
+            tx.execute("DELETE FROM undo_stack WHERE id = ?1", [entry.id])?;
+            // The forget is taken back, so its quarantine copy goes too
+            tx.execute("DELETE FROM quarantine WHERE undo_id = ?1", [entry.id])?;
+            let event = AuditEvent::new(AuditKind::Undo, occurrences).with_detail(format!(
+                "{} {}",
+                entry.kind.as_str(),
+                entry.detail
+            ));
+            self.audit_on(&tx, &event)?;
+            tx.commit()?;
+
+            Ok(Some(UndoReport {
+                entry,
+                episodes,
+                neighborhoods,
+                occurrences,
+                skipped,
+            }))
+        })
+    }
+}
@@ -0,0 +1,83 @@
+//! Invariant checks for geometry read back from SQLite.
+//!
+//! Positions and seeds must be unit quaternions and phasor angles must lie
+//! in `[0, τ)`. Older builds occasionally wrote rows that break this, and
+//! drift misbehaves near them. Loads repair such rows in memory and count
+//! them; strict mode (`AM_STRICT_LOAD=1`) turns them into errors instead.
+
+use std::f64::consts::TAU;
+
+use am_core::{phasor::DaemonPhasor, quaternion::Quaternion};
+
+use crate::error::{Result, StoreError};
+
+/// Allowed deviation of a stored quaternion's norm from 1.
+const NORM_EPSILON: f64 = 1e-10;
+
+/// Environment variable that enables strict loading.
+pub const STRICT_LOAD_ENV: &str = "AM_STRICT_LOAD";
+
+/// Whether `AM_STRICT_LOAD` asks for errors instead of repairs.
+pub(crate) fn strict_load_from_env() -> bool {
+    std::env::var(STRICT_LOAD_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+/// Checks rows as they are loaded, counting repairs.
+pub(crate) struct RowChecker {
+    strict: bool,
+    pub(crate) repaired: u64,
+}
+
+impl RowChecker {
+    pub(crate) fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            repaired: 0,
+        }
+    }
+
+    /// Validate a stored quaternion. Off-norm values are renormalized;
+    /// non-finite or zero ones become the identity.
+    pub(crate) fn quaternion(
+        &mut self,
+        what: &str,
+        id: &str,
+        (w, x, y, z): (f64, f64, f64, f64),
+    ) -> Result<Quaternion> {
+        let norm = (w * w + x * x + y * y + z * z).sqrt();
+        if norm.is_finite() && (norm - 1.0).abs() <= NORM_EPSILON {
+            return Ok(Quaternion::new(w, x, y, z));
+        }
+        self.repair(format!("{what} {id}: quaternion norm {norm}"))?;
+        if norm.is_finite() {
+            Ok(Quaternion::new(w, x, y, z))
+        } else {
+            Ok(Quaternion::identity())
+        }
+    }
+
+    /// Validate a stored phase angle. Out-of-range values are wrapped into
+    /// `[0, τ)`; non-finite ones become 0.
+    pub(crate) fn phasor(&mut self, id: &str, theta: f64) -> Result<DaemonPhasor> {
+        if (0.0..TAU).contains(&theta) {
+            return Ok(DaemonPhasor::new(theta));
+        }
+        self.repair(format!(
+            "occurrence {id}: phasor theta {theta} outside [0, τ)"
+        ))?;
+        if theta.is_finite() {
+            Ok(DaemonPhasor::new(theta))
+        } else {
+            Ok(DaemonPhasor::new(0.0))
+        }
+    }
+
+    fn repair(&mut self, problem: String) -> Result<()> {
+        if self.strict {
+            return Err(StoreError::InvalidData(problem));
+        }
+        tracing::debug!("repairing {problem}");
+        self.repaired += 1;
+        Ok(())
+    }
+}